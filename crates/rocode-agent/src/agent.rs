@@ -12,6 +12,7 @@ use rocode_permission::{
     build_agent_ruleset, evaluate as evaluate_permission, PermissionAction, PermissionRule,
     PermissionRuleset,
 };
+use rocode_provider::ToolChoice;
 
 const PROMPT_GENERATE: &str = r#"You are an AI agent configuration generator. Given a description of what an agent should do, generate a JSON configuration with:
 - identifier: A unique, lowercase, single-word identifier for the agent (use underscores if needed)
@@ -70,10 +71,19 @@ pub struct AgentInfo {
     pub model: Option<ModelRef>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub model_preference: Option<ModelRef>,
+    /// Models tried in order when `model` fails with a failover-class error.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallback: Vec<ModelRef>,
     pub system_prompt: Option<String>,
     pub temperature: Option<f32>,
     pub top_p: Option<f32>,
     pub max_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
     pub max_steps: Option<u32>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub allowed_tools: Vec<String>,
@@ -198,10 +208,14 @@ impl AgentInfo {
             mode: AgentMode::Primary,
             model: None,
             model_preference: None,
+            fallback: Vec::new(),
             system_prompt: None,
             temperature: None,
             top_p: None,
             max_tokens: Some(8192),
+            tool_choice: None,
+            stop: None,
+            parallel_tool_calls: None,
             max_steps: Some(100),
             allowed_tools: Vec::new(),
             options: HashMap::new(),
@@ -220,10 +234,14 @@ impl AgentInfo {
             mode: AgentMode::Primary,
             model: None,
             model_preference: None,
+            fallback: Vec::new(),
             system_prompt: Some("You are a planning assistant. Analyze the task and create a detailed plan before execution.".to_string()),
             temperature: Some(0.3),
             top_p: None,
             max_tokens: Some(8192),
+            tool_choice: None,
+            stop: None,
+            parallel_tool_calls: None,
             max_steps: Some(50),
             allowed_tools: Vec::new(),
             options: HashMap::new(),
@@ -242,12 +260,16 @@ impl AgentInfo {
             mode: AgentMode::Primary,
             model: None,
             model_preference: None,
+            fallback: Vec::new(),
             system_prompt: Some(
                 "You are a helpful assistant. Complete the task given to you.".to_string(),
             ),
             temperature: Some(0.7),
             top_p: None,
             max_tokens: Some(8192),
+            tool_choice: None,
+            stop: None,
+            parallel_tool_calls: None,
             max_steps: Some(20),
             allowed_tools: Vec::new(),
             options: HashMap::new(),
@@ -266,10 +288,14 @@ impl AgentInfo {
             mode: AgentMode::Subagent,
             model: None,
             model_preference: None,
+            fallback: Vec::new(),
             system_prompt: Some("You are an exploration assistant. Search and read code to answer questions. Focus on read-only operations.".to_string()),
             temperature: Some(0.5),
             top_p: None,
             max_tokens: Some(8192),
+            tool_choice: None,
+            stop: None,
+            parallel_tool_calls: None,
             max_steps: Some(30),
             allowed_tools: vec![
                 "grep".to_string(),
@@ -293,10 +319,14 @@ impl AgentInfo {
             mode: AgentMode::Subagent,
             model: None,
             model_preference: None,
+            fallback: Vec::new(),
             system_prompt: Some("You are a title generator. Generate a concise 3-5 word title that summarizes the conversation. Return only the title, nothing else.".to_string()),
             temperature: Some(0.3),
             top_p: None,
             max_tokens: Some(1024),
+            tool_choice: None,
+            stop: None,
+            parallel_tool_calls: None,
             max_steps: Some(1),
             allowed_tools: Vec::new(),
             options: HashMap::new(),
@@ -319,10 +349,14 @@ impl AgentInfo {
             mode: AgentMode::Subagent,
             model: None,
             model_preference: None,
+            fallback: Vec::new(),
             system_prompt: Some("You are a summary generator. Create a concise summary of the conversation. Focus on key decisions and outcomes.".to_string()),
             temperature: Some(0.3),
             top_p: None,
             max_tokens: Some(1024),
+            tool_choice: None,
+            stop: None,
+            parallel_tool_calls: None,
             max_steps: Some(1),
             allowed_tools: Vec::new(),
             options: HashMap::new(),
@@ -345,10 +379,14 @@ impl AgentInfo {
             mode: AgentMode::Subagent,
             model: None,
             model_preference: None,
+            fallback: Vec::new(),
             system_prompt: Some("You are a context compaction assistant. Summarize the conversation while preserving all important context for future interactions.".to_string()),
             temperature: Some(0.3),
             top_p: None,
             max_tokens: Some(1024),
+            tool_choice: None,
+            stop: None,
+            parallel_tool_calls: None,
             max_steps: Some(1),
             allowed_tools: Vec::new(),
             options: HashMap::new(),
@@ -372,10 +410,14 @@ impl AgentInfo {
             mode: AgentMode::All,
             model: None,
             model_preference: None,
+            fallback: Vec::new(),
             system_prompt: None,
             temperature: None,
             top_p: None,
             max_tokens: None,
+            tool_choice: None,
+            stop: None,
+            parallel_tool_calls: None,
             max_steps: Some(100),
             allowed_tools: Vec::new(),
             options: HashMap::new(),
@@ -560,10 +602,25 @@ impl AgentRegistry {
         if let Some(max_tokens) = cfg.max_tokens {
             agent.max_tokens = Some(max_tokens);
         }
+        if let Some(tool_choice) = cfg.tool_choice.as_deref().and_then(ToolChoice::parse) {
+            agent.tool_choice = Some(tool_choice);
+        }
+        if let Some(stop) = &cfg.stop {
+            agent.stop = Some(stop.clone());
+        }
+        if let Some(parallel) = cfg.parallel_tool_calls {
+            agent.parallel_tool_calls = Some(parallel);
+        }
         if let Some(model) = cfg.model.as_deref().and_then(parse_model_ref) {
             agent.model_preference = Some(model.clone());
             agent.model = Some(model);
         }
+        if let Some(fallback) = &cfg.fallback {
+            agent.fallback = fallback
+                .iter()
+                .filter_map(|model| parse_model_ref(model))
+                .collect();
+        }
         if let Some(options) = &cfg.options {
             for (key, value) in options {
                 if let Some(existing) = agent.options.get_mut(key) {
@@ -944,6 +1001,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn config_sets_agent_fallback_chain() {
+        let config = LoadedConfig {
+            agent: Some(LoadedAgentConfigs {
+                entries: HashMap::from([(
+                    "build".to_string(),
+                    LoadedAgentConfig {
+                        model: Some("anthropic/claude-sonnet-4".to_string()),
+                        fallback: Some(vec![
+                            "amazon-bedrock/anthropic.claude-sonnet-4".to_string(),
+                            "not-a-model-ref".to_string(),
+                            "openrouter/anthropic/claude-sonnet-4".to_string(),
+                        ]),
+                        ..Default::default()
+                    },
+                )]),
+            }),
+            ..Default::default()
+        };
+
+        let registry = AgentRegistry::from_config(&config);
+        let build = registry.get("build").expect("build should exist");
+        let chain: Vec<(&str, &str)> = build
+            .fallback
+            .iter()
+            .map(|m| (m.provider_id.as_str(), m.model_id.as_str()))
+            .collect();
+        assert_eq!(
+            chain,
+            vec![
+                ("amazon-bedrock", "anthropic.claude-sonnet-4"),
+                ("openrouter", "anthropic/claude-sonnet-4"),
+            ]
+        );
+    }
+
+    #[test]
+    fn config_sets_agent_tool_controls() {
+        let config = LoadedConfig {
+            agent: Some(LoadedAgentConfigs {
+                entries: HashMap::from([(
+                    "build".to_string(),
+                    LoadedAgentConfig {
+                        tool_choice: Some("required".to_string()),
+                        stop: Some(vec!["<done>".to_string()]),
+                        parallel_tool_calls: Some(false),
+                        ..Default::default()
+                    },
+                )]),
+            }),
+            ..Default::default()
+        };
+
+        let registry = AgentRegistry::from_config(&config);
+        let build = registry.get("build").expect("build should exist");
+        assert_eq!(build.tool_choice, Some(ToolChoice::Required));
+        assert_eq!(build.stop, Some(vec!["<done>".to_string()]));
+        assert_eq!(build.parallel_tool_calls, Some(false));
+    }
+
     #[test]
     fn registry_supports_dynamic_custom_agents_from_config() {
         let mut config = LoadedConfig::default();
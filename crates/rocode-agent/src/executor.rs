@@ -6,7 +6,10 @@ use tokio::sync::Mutex;
 
 use crate::{AgentInfo, Conversation, ToolCall};
 use rocode_plugin::{HookContext, HookEvent};
-use rocode_provider::{ChatRequest, Provider, ProviderRegistry, StreamEvent};
+use rocode_provider::{
+    generate_structured, parse_json_output, ChatRequest, Provider, ProviderRegistry,
+    ResponseFormat, StreamEvent, StreamUsage, ToolChoice,
+};
 use rocode_tool::{ToolContext, ToolError, ToolRegistry};
 
 #[derive(Debug, thiserror::Error)]
@@ -25,8 +28,23 @@ pub enum AgentError {
 
     #[error("Invalid response")]
     InvalidResponse,
+
+    #[error("Structured output error: {0}")]
+    StructuredOutput(String),
+}
+
+/// Token usage of one model step, as reported to a [`UsageObserver`].
+#[derive(Debug, Clone)]
+pub struct StepUsage {
+    pub provider_id: String,
+    pub model_id: String,
+    pub agent: String,
+    pub usage: StreamUsage,
 }
 
+/// Called once per model step, including steps run by subagents.
+pub type UsageObserver = Arc<dyn Fn(StepUsage) + Send + Sync>;
+
 pub struct AgentExecutor {
     agent: AgentInfo,
     conversation: Conversation,
@@ -35,6 +53,7 @@ pub struct AgentExecutor {
     disabled_tools: HashSet<String>,
     subsessions: Arc<Mutex<HashMap<String, SubsessionState>>>,
     max_steps: u32,
+    usage_observer: Option<UsageObserver>,
 }
 
 #[derive(Debug, Clone)]
@@ -69,9 +88,15 @@ impl AgentExecutor {
             disabled_tools: HashSet::new(),
             subsessions: Arc::new(Mutex::new(HashMap::new())),
             max_steps,
+            usage_observer: None,
         }
     }
 
+    pub fn with_usage_observer(mut self, observer: UsageObserver) -> Self {
+        self.usage_observer = Some(observer);
+        self
+    }
+
     pub fn with_system_prompt(mut self, prompt: impl Into<String>) -> Self {
         self.conversation = Conversation::with_system_prompt(prompt);
         self
@@ -162,15 +187,18 @@ impl AgentExecutor {
             .await;
 
             let tool_defs = self.resolve_tool_definitions().await;
-            let request = ChatRequest::new(model_id, self.conversation.to_provider_messages())
-                .with_tools(tool_defs);
+            let mut request =
+                ChatRequest::new(model_id.clone(), self.conversation.to_provider_messages())
+                    .with_tools(tool_defs);
+            self.apply_tool_controls(&mut request, &provider, steps);
 
             let stream = provider
                 .chat_stream(request)
                 .await
                 .map_err(|e| AgentError::ProviderError(e.to_string()))?;
 
-            let (response, tool_calls) = self.process_stream(stream).await?;
+            let (response, tool_calls, usage) = self.process_stream(stream).await?;
+            self.report_usage(provider.id(), &model_id, usage);
 
             if tool_calls.is_empty() {
                 final_response = response;
@@ -233,6 +261,42 @@ impl AgentExecutor {
         Ok(final_response)
     }
 
+    /// Runs the agent loop and returns the final answer as JSON matching `format`.
+    ///
+    /// Tools stay available during the loop. If the final answer does not
+    /// validate, the model is asked to repair it up to `retries` more times.
+    pub async fn execute_structured(
+        &mut self,
+        user_message: impl Into<String>,
+        format: &ResponseFormat,
+        retries: u32,
+    ) -> Result<serde_json::Value, AgentError> {
+        let user_message = format!("{}\n\n{}", user_message.into(), format.instructions());
+        let answer = self.execute(user_message).await?;
+        self.conversation.add_assistant_message(&answer);
+        if let Ok(value) = parse_json_output(&answer) {
+            if format.validate(&value).is_empty() {
+                return Ok(value);
+            }
+        }
+        if retries == 0 {
+            return Err(AgentError::StructuredOutput(
+                "final answer does not match the schema".to_string(),
+            ));
+        }
+
+        let provider = self.get_provider()?;
+        let model_id = self.get_model_id(&provider);
+        let format_label = format.name.clone();
+        let request = ChatRequest::new(model_id, self.conversation.to_provider_messages());
+        let output = generate_structured(provider.as_ref(), request, format, retries - 1)
+            .await
+            .map_err(|e| AgentError::StructuredOutput(format!("{}: {}", format_label, e)))?;
+        self.conversation
+            .add_assistant_message(output.value.to_string());
+        Ok(output.value)
+    }
+
     async fn execute_subsession(
         &mut self,
         user_message: impl Into<String>,
@@ -248,15 +312,18 @@ impl AgentExecutor {
             let provider = self.get_provider()?;
             let model_id = self.get_model_id(&provider);
             let tool_defs = self.resolve_tool_definitions().await;
-            let request = ChatRequest::new(model_id, self.conversation.to_provider_messages())
-                .with_tools(tool_defs);
+            let mut request =
+                ChatRequest::new(model_id.clone(), self.conversation.to_provider_messages())
+                    .with_tools(tool_defs);
+            self.apply_tool_controls(&mut request, &provider, steps);
 
             let stream = provider
                 .chat_stream(request)
                 .await
                 .map_err(|e| AgentError::ProviderError(e.to_string()))?;
 
-            let (response, tool_calls) = self.process_stream(stream).await?;
+            let (response, tool_calls, usage) = self.process_stream(stream).await?;
+            self.report_usage(provider.id(), &model_id, usage);
 
             if tool_calls.is_empty() {
                 final_response = response;
@@ -326,8 +393,10 @@ impl AgentExecutor {
             let provider = self.get_provider()?;
             let model_id = self.get_model_id(&provider);
             let tool_defs = self.resolve_tool_definitions().await;
-            let request = ChatRequest::new(model_id, self.conversation.to_provider_messages())
-                .with_tools(tool_defs);
+            let mut request =
+                ChatRequest::new(model_id.clone(), self.conversation.to_provider_messages())
+                    .with_tools(tool_defs);
+            self.apply_tool_controls(&mut request, &provider, steps);
 
             let mut stream = provider
                 .chat_stream(request)
@@ -336,8 +405,12 @@ impl AgentExecutor {
 
             let mut response = String::new();
             let mut tool_calls: Vec<ToolCall> = Vec::new();
+            let mut usage = StreamUsage::default();
 
             while let Some(event) = stream.next().await {
+                if let Ok(event) = &event {
+                    track_step_usage(event, &mut usage);
+                }
                 match event {
                     Ok(StreamEvent::TextDelta(text)) => {
                         response.push_str(&text);
@@ -388,6 +461,8 @@ impl AgentExecutor {
                 }
             }
 
+            self.report_usage(provider.id(), &model_id, usage);
+
             if tool_calls.is_empty() {
                 self.conversation.add_assistant_message(&response);
                 emitted.push(Ok(StreamEvent::Done));
@@ -476,6 +551,25 @@ impl AgentExecutor {
         }
     }
 
+    /// Applies the agent's tool controls. A forced choice only holds for the
+    /// first step, otherwise the loop could never reach a final answer.
+    fn apply_tool_controls(
+        &self,
+        request: &mut ChatRequest,
+        provider: &Arc<dyn Provider>,
+        step: u32,
+    ) {
+        request.tool_choice = match &self.agent.tool_choice {
+            Some(ToolChoice::Required | ToolChoice::Tool(_)) if step > 1 => None,
+            choice => choice.clone(),
+        };
+        request.stop = self.agent.stop.clone();
+        request.parallel_tool_calls = self.agent.parallel_tool_calls;
+        if let Some(model) = provider.get_model(&request.model) {
+            request.apply_model_capabilities(model);
+        }
+    }
+
     fn get_model_id(&self, provider: &Arc<dyn Provider>) -> String {
         if let Some(ref model_ref) = self.agent.model {
             model_ref.model_id.clone()
@@ -488,11 +582,15 @@ impl AgentExecutor {
     async fn process_stream(
         &mut self,
         mut stream: rocode_provider::StreamResult,
-    ) -> Result<(String, Vec<ToolCall>), AgentError> {
+    ) -> Result<(String, Vec<ToolCall>, StreamUsage), AgentError> {
         let mut content = String::new();
         let mut tool_calls: Vec<ToolCall> = Vec::new();
+        let mut usage = StreamUsage::default();
 
         while let Some(event) = stream.next().await {
+            if let Ok(event) = &event {
+                track_step_usage(event, &mut usage);
+            }
             match event {
                 Ok(StreamEvent::TextDelta(text)) => {
                     content.push_str(&text);
@@ -519,7 +617,19 @@ impl AgentExecutor {
             }
         }
 
-        Ok((content, tool_calls))
+        Ok((content, tool_calls, usage))
+    }
+
+    fn report_usage(&self, provider_id: &str, model_id: &str, usage: StreamUsage) {
+        let Some(observer) = &self.usage_observer else {
+            return;
+        };
+        observer(StepUsage {
+            provider_id: provider_id.to_string(),
+            model_id: model_id.to_string(),
+            agent: self.agent.name.clone(),
+            usage,
+        });
     }
 
     async fn execute_tool(
@@ -595,6 +705,7 @@ impl AgentExecutor {
         let subsessions = self.subsessions.clone();
         let providers = self.providers.clone();
         let tools = self.tools.clone();
+        let usage_observer = self.usage_observer.clone();
 
         let ctx = ctx.with_get_agent_info(|name| async move {
             let cwd = std::env::current_dir().unwrap_or_default();
@@ -657,6 +768,7 @@ impl AgentExecutor {
                 let subsessions = subsessions.clone();
                 let providers = providers.clone();
                 let tools = tools.clone();
+                let usage_observer = usage_observer.clone();
                 async move {
                     let state = {
                         let store = subsessions.lock().await;
@@ -673,6 +785,7 @@ impl AgentExecutor {
                         AgentExecutor::new(state.agent, providers.clone(), tools.clone())
                             .with_disabled_tools(state.disabled_tools.iter().cloned());
                     executor.conversation = state.conversation;
+                    executor.usage_observer = usage_observer;
 
                     let output = executor.execute_subsession(prompt).await.map_err(|e| {
                         ToolError::ExecutionError(format!("Subagent execution failed: {}", e))
@@ -756,6 +869,26 @@ impl AgentExecutor {
     }
 }
 
+/// Keeps the latest usage a step reports; adapters send it either on
+/// `FinishStep` or as a separate `Usage` event.
+fn track_step_usage(event: &StreamEvent, usage: &mut StreamUsage) {
+    match event {
+        StreamEvent::FinishStep { usage: step, .. } => *usage = step.clone(),
+        StreamEvent::Usage {
+            prompt_tokens,
+            completion_tokens,
+            cache_read_tokens,
+            cache_write_tokens,
+        } => {
+            usage.prompt_tokens = *prompt_tokens;
+            usage.completion_tokens = *completion_tokens;
+            usage.cache_read_tokens = *cache_read_tokens;
+            usage.cache_write_tokens = *cache_write_tokens;
+        }
+        _ => {}
+    }
+}
+
 fn repair_tool_call_name(name: &str, available_tools: &[String]) -> Option<String> {
     if available_tools.iter().any(|tool| tool == name) {
         return None;
@@ -911,7 +1044,7 @@ mod tests {
             StreamEvent::Done,
         ]);
 
-        let (_, tool_calls) = executor.process_stream(stream).await.unwrap();
+        let (_, tool_calls, _) = executor.process_stream(stream).await.unwrap();
         assert_eq!(tool_calls.len(), 1);
         assert_eq!(tool_calls[0].name, "read");
         assert_eq!(
@@ -934,7 +1067,7 @@ mod tests {
             },
         ]);
 
-        let (_, tool_calls) = executor.process_stream(stream).await.unwrap();
+        let (_, tool_calls, _) = executor.process_stream(stream).await.unwrap();
         assert!(tool_calls.is_empty());
     }
 
@@ -955,7 +1088,7 @@ mod tests {
             StreamEvent::Done,
         ]);
 
-        let (_, tool_calls) = executor.process_stream(stream).await.unwrap();
+        let (_, tool_calls, _) = executor.process_stream(stream).await.unwrap();
         assert_eq!(tool_calls.len(), 2);
 
         let read_tc = tool_calls.iter().find(|t| t.name == "read").unwrap();
@@ -968,6 +1101,38 @@ mod tests {
         assert_eq!(bash_tc.arguments, serde_json::json!({"command": "ls"}));
     }
 
+    #[tokio::test]
+    async fn process_stream_reports_step_usage() {
+        let mut executor = build_executor(AgentInfo::general());
+        let stream = mock_stream(vec![
+            StreamEvent::TextDelta("done".into()),
+            StreamEvent::Usage {
+                prompt_tokens: 1,
+                completion_tokens: 1,
+                cache_read_tokens: 0,
+                cache_write_tokens: 0,
+            },
+            StreamEvent::FinishStep {
+                finish_reason: Some("stop".into()),
+                usage: StreamUsage {
+                    prompt_tokens: 120,
+                    completion_tokens: 40,
+                    reasoning_tokens: 10,
+                    cache_read_tokens: 300,
+                    cache_write_tokens: 0,
+                },
+                provider_metadata: None,
+            },
+            StreamEvent::Done,
+        ]);
+
+        let (_, _, usage) = executor.process_stream(stream).await.unwrap();
+        assert_eq!(usage.prompt_tokens, 120);
+        assert_eq!(usage.completion_tokens, 40);
+        assert_eq!(usage.reasoning_tokens, 10);
+        assert_eq!(usage.cache_read_tokens, 300);
+    }
+
     #[tokio::test]
     async fn process_stream_ignores_tool_call_end_with_empty_name() {
         let mut executor = build_executor(AgentInfo::general());
@@ -985,7 +1150,7 @@ mod tests {
             StreamEvent::Done,
         ]);
 
-        let (_, tool_calls) = executor.process_stream(stream).await.unwrap();
+        let (_, tool_calls, _) = executor.process_stream(stream).await.unwrap();
         assert_eq!(tool_calls.len(), 1);
         assert_eq!(tool_calls[0].id, "tool-call-1");
         assert_eq!(tool_calls[0].name, "ls");
@@ -1,4 +1,8 @@
 use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use rocode_core::credentials::{self, EncryptedFileBackend, MigrationOutcome};
 
 use crate::cli::AuthCommands;
 
@@ -120,7 +124,48 @@ pub(crate) async fn handle_auth_command(action: AuthCommands) -> anyhow::Result<
                 env_var
             );
         }
+        AuthCommands::Migrate => migrate_credential_stores()?,
     }
 
     Ok(())
 }
+
+fn credential_store_paths() -> Vec<PathBuf> {
+    let mut paths = vec![
+        rocode_provider::auth_data_dir().join("auth.json"),
+        rocode_config::wellknown::auth_json_path(),
+        rocode_mcp::auth::auth_file_path(),
+    ];
+    paths.dedup();
+    paths
+}
+
+fn migrate_credential_stores() -> anyhow::Result<()> {
+    let mut backend = credentials::backend_from_env()?;
+    if !backend.encrypts() {
+        print!(
+            "No {} or {} set. Enter a passphrase to encrypt with: ",
+            credentials::PASSPHRASE_ENV,
+            credentials::KEY_FILE_ENV
+        );
+        io::stdout().flush()?;
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let passphrase = input.trim_end_matches(['\r', '\n']).to_string();
+        backend = Arc::new(EncryptedFileBackend::from_passphrase(passphrase)?);
+        println!(
+            "Export {} with this passphrase so rocode can unlock the stores.",
+            credentials::PASSPHRASE_ENV
+        );
+    }
+
+    for path in credential_store_paths() {
+        let status = match credentials::migrate_file(&path, backend.as_ref())? {
+            MigrationOutcome::Missing => "not found",
+            MigrationOutcome::AlreadyEncrypted => "already encrypted",
+            MigrationOutcome::Migrated => "encrypted",
+        };
+        println!("  {:<18} {}", status, path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,772 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+use rocode_config::loader::load_config;
+use rocode_provider::{
+    governor, response_text, BatchHandle, BatchItem, BatchItemResult, BatchPoll, ChatRequest,
+    ChatResponse, Message, Provider, ProviderRegistry, Usage,
+};
+
+use crate::providers::setup_providers;
+
+pub(crate) struct BatchOptions {
+    pub input: PathBuf,
+    pub output: Option<PathBuf>,
+    pub model: Option<String>,
+    pub concurrency: usize,
+    pub rpm: Option<u32>,
+    pub native: bool,
+    pub retry_failed: bool,
+    pub poll_interval: u64,
+}
+
+/// One line of the input file. A bare JSON string is shorthand for `prompt`.
+#[derive(Debug, Clone, Deserialize)]
+struct BatchPrompt {
+    #[serde(default)]
+    id: Option<String>,
+    prompt: String,
+    #[serde(default)]
+    system: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    max_tokens: Option<u64>,
+    #[serde(default)]
+    temperature: Option<f32>,
+}
+
+/// One line of the results file, which doubles as the resume checkpoint.
+#[derive(Debug, Serialize, Deserialize)]
+struct BatchResultLine {
+    id: String,
+    model: String,
+    status: ResultStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    output: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    finish_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    usage: Option<Usage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    completed_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ResultStatus {
+    Ok,
+    Error,
+}
+
+impl BatchResultLine {
+    fn new(id: String, model: String, outcome: Result<ChatResponse, String>) -> Self {
+        let completed_at = chrono::Utc::now();
+        match outcome {
+            Ok(response) => Self {
+                output: Some(response_text(&response)),
+                finish_reason: response
+                    .choices
+                    .first()
+                    .and_then(|choice| choice.finish_reason.clone()),
+                usage: response.usage,
+                id,
+                model,
+                status: ResultStatus::Ok,
+                error: None,
+                completed_at,
+            },
+            Err(error) => Self {
+                id,
+                model,
+                status: ResultStatus::Error,
+                output: None,
+                finish_reason: None,
+                usage: None,
+                error: Some(error),
+                completed_at,
+            },
+        }
+    }
+}
+
+struct PendingItem {
+    id: String,
+    provider_id: String,
+    model_id: String,
+    request: ChatRequest,
+}
+
+impl PendingItem {
+    fn model_key(&self) -> String {
+        format!("{}/{}", self.provider_id, self.model_id)
+    }
+}
+
+/// Appends result lines, flushing each so an interrupted run loses nothing.
+struct ResultWriter {
+    file: Mutex<File>,
+}
+
+impl ResultWriter {
+    fn open(path: &Path) -> anyhow::Result<Self> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)?;
+        // Terminate a torn last line so the next result starts cleanly.
+        let len = file.metadata()?.len();
+        if len > 0 {
+            let mut last = [0u8; 1];
+            file.seek(SeekFrom::Start(len - 1))?;
+            file.read_exact(&mut last)?;
+            if last[0] != b'\n' {
+                writeln!(file)?;
+            }
+        }
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    fn append(&self, line: &BatchResultLine) -> anyhow::Result<()> {
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|_| anyhow::anyhow!("result writer poisoned"))?;
+        writeln!(file, "{}", serde_json::to_string(line)?)?;
+        file.flush()?;
+        Ok(())
+    }
+}
+
+pub(crate) async fn handle_batch_command(options: BatchOptions) -> anyhow::Result<()> {
+    let output = options
+        .output
+        .clone()
+        .unwrap_or_else(|| options.input.with_extension("results.jsonl"));
+    let prompts = read_prompts(&options.input)?;
+    let finished = read_finished(&output, options.retry_failed)?;
+
+    let config = load_config(&std::env::current_dir()?)?;
+    let registry = setup_providers(&config).await?;
+    let default_model = options.model.clone().or(config.model.clone());
+
+    let mut pending = Vec::new();
+    for (id, prompt) in prompts {
+        if finished.contains(&id) {
+            continue;
+        }
+        let model = prompt
+            .model
+            .clone()
+            .or_else(|| default_model.clone())
+            .ok_or_else(|| anyhow::anyhow!("item {} has no model; pass --model", id))?;
+        let (provider_id, model_id) = registry
+            .parse_model_string(&model)
+            .ok_or_else(|| anyhow::anyhow!("item {}: unknown model {}", id, model))?;
+        pending.push(PendingItem {
+            request: build_request(&model_id, prompt),
+            id,
+            provider_id,
+            model_id,
+        });
+    }
+    eprintln!(
+        "{} done, {} to run, results in {}",
+        finished.len(),
+        pending.len(),
+        output.display()
+    );
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let writer = Arc::new(ResultWriter::open(&output)?);
+    let (native, streamed): (Vec<_>, Vec<_>) = pending
+        .into_iter()
+        .partition(|item| options.native && supports_batch(&registry, item));
+    let native_run = async {
+        if native.is_empty() {
+            return Ok(());
+        }
+        run_native(
+            &registry,
+            native,
+            &output,
+            writer.clone(),
+            Duration::from_secs(options.poll_interval.max(1)),
+        )
+        .await
+    };
+    let streamed_run = async {
+        if streamed.is_empty() {
+            return Ok(());
+        }
+        run_concurrent(
+            &registry,
+            streamed,
+            writer.clone(),
+            options.concurrency.max(1),
+            options.rpm,
+        )
+        .await
+    };
+    tokio::try_join!(native_run, streamed_run)?;
+    Ok(())
+}
+
+/// Reads the input file, pairing each prompt with its id. Prompts without
+/// one are numbered by position; an id may only appear once.
+fn read_prompts(path: &Path) -> anyhow::Result<Vec<(String, BatchPrompt)>> {
+    let file = File::open(path)
+        .map_err(|e| anyhow::anyhow!("Failed to open {}: {}", path.display(), e))?;
+    let mut prompts = Vec::new();
+    let mut lines_by_id = HashMap::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let value: serde_json::Value = serde_json::from_str(&line)
+            .map_err(|e| anyhow::anyhow!("{}:{}: {}", path.display(), index + 1, e))?;
+        let prompt = match value {
+            serde_json::Value::String(prompt) => BatchPrompt {
+                id: None,
+                prompt,
+                system: None,
+                model: None,
+                max_tokens: None,
+                temperature: None,
+            },
+            value => serde_json::from_value(value)
+                .map_err(|e| anyhow::anyhow!("{}:{}: {}", path.display(), index + 1, e))?,
+        };
+        let id = prompt
+            .id
+            .clone()
+            .unwrap_or_else(|| (prompts.len() + 1).to_string());
+        if let Some(first) = lines_by_id.insert(id.clone(), index + 1) {
+            anyhow::bail!(
+                "{}:{}: duplicate id {} (first used on line {})",
+                path.display(),
+                index + 1,
+                id,
+                first
+            );
+        }
+        prompts.push((id, prompt));
+    }
+    Ok(prompts)
+}
+
+/// Ids already in the results file. Failed items count as finished unless
+/// `retry_failed` is set.
+fn read_finished(path: &Path, retry_failed: bool) -> anyhow::Result<HashSet<String>> {
+    let Ok(file) = File::open(path) else {
+        return Ok(HashSet::new());
+    };
+    let mut finished = HashSet::new();
+    for line in BufReader::new(file).lines() {
+        // A torn last line from an interrupted run is simply redone.
+        let Ok(result) = serde_json::from_str::<BatchResultLine>(&line?) else {
+            continue;
+        };
+        if result.status == ResultStatus::Ok || !retry_failed {
+            finished.insert(result.id);
+        } else {
+            finished.remove(&result.id);
+        }
+    }
+    Ok(finished)
+}
+
+fn build_request(model_id: &str, prompt: BatchPrompt) -> ChatRequest {
+    let mut request =
+        ChatRequest::new(model_id, vec![Message::user(prompt.prompt)]).with_stream(false);
+    if let Some(system) = prompt.system {
+        request = request.with_system(system);
+    }
+    if let Some(max_tokens) = prompt.max_tokens {
+        request = request.with_max_tokens(max_tokens);
+    }
+    if let Some(temperature) = prompt.temperature {
+        request = request.with_temperature(temperature);
+    }
+    request
+}
+
+fn supports_batch(registry: &ProviderRegistry, item: &PendingItem) -> bool {
+    registry
+        .get(&item.provider_id)
+        .and_then(|provider| provider.get_model(&item.model_id).map(|m| m.supports_batch))
+        .unwrap_or(false)
+}
+
+/// Runs items one request each, at most `concurrency` in flight per provider.
+/// Pacing is left to the provider rate governor, which `rpm` tightens for the
+/// providers this run touches.
+async fn run_concurrent(
+    registry: &ProviderRegistry,
+    items: Vec<PendingItem>,
+    writer: Arc<ResultWriter>,
+    concurrency: usize,
+    rpm: Option<u32>,
+) -> anyhow::Result<()> {
+    let mut slots: HashMap<String, Arc<Semaphore>> = HashMap::new();
+    let total = items.len();
+    let mut tasks = JoinSet::new();
+    for item in items {
+        let provider = registry.get_provider(&item.provider_id)?;
+        let semaphore = slots
+            .entry(item.provider_id.clone())
+            .or_insert_with(|| {
+                if let Some(rpm) = rpm {
+                    let mut limits = governor::limits(&item.provider_id);
+                    limits.requests_per_minute = Some(
+                        limits
+                            .requests_per_minute
+                            .map_or(rpm, |configured| configured.min(rpm)),
+                    );
+                    governor::set_limits(&item.provider_id, limits);
+                }
+                Arc::new(Semaphore::new(concurrency))
+            })
+            .clone();
+        let writer = writer.clone();
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await?;
+            let outcome = provider
+                .chat(item.request.clone())
+                .await
+                .map_err(|e| e.to_string());
+            let line = BatchResultLine::new(item.id.clone(), item.model_key(), outcome);
+            writer.append(&line)?;
+            anyhow::Ok(line.status)
+        });
+    }
+
+    let (mut ok, mut failed) = (0, 0);
+    while let Some(joined) = tasks.join_next().await {
+        match joined?? {
+            ResultStatus::Ok => ok += 1,
+            ResultStatus::Error => failed += 1,
+        }
+        eprint!("\r{}/{} done ({} failed)", ok + failed, total, failed);
+    }
+    eprintln!();
+    Ok(())
+}
+
+/// Native batches submitted but not yet collected, kept next to the results
+/// so a resumed run polls them instead of submitting again.
+fn handles_path(output: &Path) -> PathBuf {
+    output.with_extension("batches.json")
+}
+
+fn load_handles(output: &Path) -> anyhow::Result<Vec<BatchHandle>> {
+    match std::fs::read_to_string(handles_path(output)) {
+        Ok(raw) => Ok(serde_json::from_str(&raw)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn save_handles(output: &Path, handles: &[BatchHandle]) -> anyhow::Result<()> {
+    let path = handles_path(output);
+    if handles.is_empty() {
+        let _ = std::fs::remove_file(path);
+        return Ok(());
+    }
+    // Write then rename so a crash never leaves a truncated handles file.
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string_pretty(handles)?)?;
+    std::fs::rename(tmp, path)?;
+    Ok(())
+}
+
+/// Submits items through provider batch APIs, one batch per model, and
+/// polls until every batch finishes.
+///
+/// Only results for `items` are written. A batch resumed from the handles file
+/// may also carry ids that an earlier run already recorded before it stopped.
+async fn run_native(
+    registry: &ProviderRegistry,
+    items: Vec<PendingItem>,
+    output: &Path,
+    writer: Arc<ResultWriter>,
+    poll_interval: Duration,
+) -> anyhow::Result<()> {
+    let mut handles = load_handles(output)?;
+    let submitted: HashSet<&str> = handles
+        .iter()
+        .flat_map(|handle| handle.custom_ids.iter().map(String::as_str))
+        .collect();
+
+    let mut models: HashMap<String, String> = HashMap::new();
+    let mut groups: HashMap<(String, String), Vec<BatchItem>> = HashMap::new();
+    for item in items {
+        models.insert(item.id.clone(), item.model_key());
+        if submitted.contains(item.id.as_str()) {
+            continue;
+        }
+        groups
+            .entry((item.provider_id.clone(), item.model_id.clone()))
+            .or_default()
+            .push(BatchItem {
+                custom_id: item.id,
+                request: item.request,
+            });
+    }
+    for ((provider_id, model_id), batch) in groups {
+        let provider = registry.get_provider(&provider_id)?;
+        let handle = provider.submit_batch(batch).await?;
+        eprintln!(
+            "submitted {} items for {}/{} as batch {}",
+            handle.custom_ids.len(),
+            provider_id,
+            model_id,
+            handle.batch_id
+        );
+        handles.push(handle);
+        save_handles(output, &handles)?;
+    }
+
+    while !handles.is_empty() {
+        let mut remaining = Vec::new();
+        for handle in handles {
+            let provider = registry.get_provider(&handle.provider_id)?;
+            match poll_handle(provider.as_ref(), &handle).await? {
+                Some(results) => {
+                    for result in results {
+                        let Some(model) = models.remove(&result.custom_id) else {
+                            continue;
+                        };
+                        writer.append(&BatchResultLine::new(
+                            result.custom_id,
+                            model,
+                            result.outcome,
+                        ))?;
+                    }
+                }
+                None => remaining.push(handle),
+            }
+        }
+        handles = remaining;
+        save_handles(output, &handles)?;
+        if !handles.is_empty() {
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+    Ok(())
+}
+
+async fn poll_handle(
+    provider: &dyn Provider,
+    handle: &BatchHandle,
+) -> anyhow::Result<Option<Vec<BatchItemResult>>> {
+    match provider.poll_batch(handle).await? {
+        BatchPoll::Pending { completed, total } => {
+            eprintln!(
+                "batch {}: {}/{} complete",
+                handle.batch_id, completed, total
+            );
+            Ok(None)
+        }
+        BatchPoll::Complete(results) => Ok(Some(results)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+    use rocode_provider::{Choice, ModelInfo, ProviderError, StreamResult};
+
+    fn response(text: &str) -> ChatResponse {
+        ChatResponse {
+            id: "resp".to_string(),
+            model: "mock-model".to_string(),
+            choices: vec![Choice {
+                index: 0,
+                message: Message::assistant(text),
+                finish_reason: Some("stop".to_string()),
+            }],
+            usage: None,
+        }
+    }
+
+    /// Answers chat requests after a short delay while tracking how many run
+    /// at once, and serves one completed native batch for every id it is
+    /// polled with.
+    struct MockProvider {
+        id: String,
+        model: ModelInfo,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+        submitted: AtomicUsize,
+    }
+
+    impl MockProvider {
+        fn new(id: &str) -> Arc<Self> {
+            Arc::new(Self {
+                id: id.to_string(),
+                model: ModelInfo {
+                    id: "mock-model".to_string(),
+                    name: "Mock".to_string(),
+                    provider: id.to_string(),
+                    context_window: 8192,
+                    max_input_tokens: None,
+                    max_output_tokens: 1024,
+                    supports_vision: false,
+                    supports_tools: false,
+                    cost_per_million_input: 0.0,
+                    cost_per_million_output: 0.0,
+                    supports_batch: true,
+                    supports_pdf: false,
+                },
+                in_flight: AtomicUsize::new(0),
+                max_in_flight: AtomicUsize::new(0),
+                submitted: AtomicUsize::new(0),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Provider for MockProvider {
+        fn id(&self) -> &str {
+            &self.id
+        }
+
+        fn name(&self) -> &str {
+            "Mock"
+        }
+
+        fn models(&self) -> Vec<ModelInfo> {
+            vec![self.model.clone()]
+        }
+
+        fn get_model(&self, id: &str) -> Option<&ModelInfo> {
+            (self.model.id == id).then_some(&self.model)
+        }
+
+        async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse, ProviderError> {
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(30)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(response("answer"))
+        }
+
+        async fn chat_stream(&self, _request: ChatRequest) -> Result<StreamResult, ProviderError> {
+            Err(ProviderError::InvalidRequest("not streamed".to_string()))
+        }
+
+        async fn submit_batch(&self, items: Vec<BatchItem>) -> Result<BatchHandle, ProviderError> {
+            self.submitted.fetch_add(items.len(), Ordering::SeqCst);
+            Ok(BatchHandle {
+                provider_id: self.id.clone(),
+                batch_id: "fresh".to_string(),
+                custom_ids: items.into_iter().map(|item| item.custom_id).collect(),
+            })
+        }
+
+        async fn poll_batch(&self, handle: &BatchHandle) -> Result<BatchPoll, ProviderError> {
+            Ok(BatchPoll::Complete(
+                handle
+                    .custom_ids
+                    .iter()
+                    .map(|id| BatchItemResult {
+                        custom_id: id.clone(),
+                        outcome: Ok(response(id)),
+                    })
+                    .collect(),
+            ))
+        }
+    }
+
+    fn pending(id: &str, provider_id: &str) -> PendingItem {
+        PendingItem {
+            id: id.to_string(),
+            provider_id: provider_id.to_string(),
+            model_id: "mock-model".to_string(),
+            request: ChatRequest::new("mock-model", vec![Message::user(id)]),
+        }
+    }
+
+    fn result_ids(path: &Path) -> Vec<String> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .filter_map(|line| serde_json::from_str::<BatchResultLine>(line).ok())
+            .map(|line| line.id)
+            .collect()
+    }
+
+    #[test]
+    fn finished_ids_honour_retry_failed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.results.jsonl");
+        let writer = ResultWriter::open(&path).unwrap();
+        let line = |id: &str, outcome: Result<ChatResponse, String>| {
+            BatchResultLine::new(id.to_string(), "mock/mock-model".to_string(), outcome)
+        };
+        writer.append(&line("a", Ok(response("a")))).unwrap();
+        writer
+            .append(&line("b", Err("overloaded".to_string())))
+            .unwrap();
+        writer
+            .append(&line("c", Err("overloaded".to_string())))
+            .unwrap();
+        writer.append(&line("c", Ok(response("c")))).unwrap();
+
+        let keep_failed = read_finished(&path, false).unwrap();
+        assert_eq!(
+            keep_failed,
+            HashSet::from(["a", "b", "c"].map(String::from))
+        );
+        let retry = read_finished(&path, true).unwrap();
+        assert_eq!(retry, HashSet::from(["a", "c"].map(String::from)));
+        assert!(read_finished(&dir.path().join("missing.jsonl"), true)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn duplicate_ids_are_rejected_with_their_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("in.jsonl");
+
+        std::fs::write(&input, "\"first\"\n{\"id\":\"x\",\"prompt\":\"p\"}\n").unwrap();
+        let ids: Vec<_> = read_prompts(&input)
+            .unwrap()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(ids, ["1", "x"]);
+
+        std::fs::write(&input, "{\"id\":\"2\",\"prompt\":\"a\"}\n\n\"b\"\n").unwrap();
+        let error = read_prompts(&input).unwrap_err().to_string();
+        assert!(
+            error.ends_with(":3: duplicate id 2 (first used on line 1)"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn open_terminates_a_torn_last_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("out.results.jsonl");
+        let done = BatchResultLine::new("a".to_string(), "m".to_string(), Ok(response("a")));
+        std::fs::write(
+            &path,
+            format!(
+                "{}\n{{\"id\":\"b\",\"mod",
+                serde_json::to_string(&done).unwrap()
+            ),
+        )
+        .unwrap();
+
+        let writer = ResultWriter::open(&path).unwrap();
+        writer
+            .append(&BatchResultLine::new(
+                "c".to_string(),
+                "m".to_string(),
+                Ok(response("c")),
+            ))
+            .unwrap();
+
+        let raw = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(raw.lines().count(), 3);
+        assert_eq!(result_ids(&path), vec!["a", "c"]);
+        assert_eq!(
+            read_finished(&path, false).unwrap(),
+            HashSet::from(["a", "c"].map(String::from))
+        );
+    }
+
+    #[tokio::test]
+    async fn resumed_native_batches_are_polled_not_resubmitted() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("out.results.jsonl");
+        let provider = MockProvider::new("mock");
+        let mut registry = ProviderRegistry::new();
+        registry.register_arc(provider.clone());
+
+        // An earlier run recorded "a" from its batch, then stopped before the
+        // handle was cleared.
+        let writer = Arc::new(ResultWriter::open(&output).unwrap());
+        writer
+            .append(&BatchResultLine::new(
+                "a".to_string(),
+                "mock/mock-model".to_string(),
+                Ok(response("a")),
+            ))
+            .unwrap();
+        save_handles(
+            &output,
+            &[BatchHandle {
+                provider_id: "mock".to_string(),
+                batch_id: "earlier".to_string(),
+                custom_ids: vec!["a".to_string(), "b".to_string()],
+            }],
+        )
+        .unwrap();
+
+        let finished = read_finished(&output, false).unwrap();
+        let items: Vec<_> = ["a", "b", "c"]
+            .into_iter()
+            .filter(|id| !finished.contains(*id))
+            .map(|id| pending(id, "mock"))
+            .collect();
+        run_native(&registry, items, &output, writer, Duration::from_millis(1))
+            .await
+            .unwrap();
+
+        // Only "c" was new; "b" came from the resumed batch and "a" was not
+        // written a second time.
+        assert_eq!(provider.submitted.load(Ordering::SeqCst), 1);
+        let mut ids = result_ids(&output);
+        ids.sort();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        assert!(!handles_path(&output).exists());
+    }
+
+    #[tokio::test]
+    async fn concurrency_is_bounded_per_provider() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("out.results.jsonl");
+        let first = MockProvider::new("first");
+        let second = MockProvider::new("second");
+        let mut registry = ProviderRegistry::new();
+        registry.register_arc(first.clone());
+        registry.register_arc(second.clone());
+
+        let items = (0..12)
+            .map(|i| {
+                let provider = if i % 2 == 0 { "first" } else { "second" };
+                pending(&i.to_string(), provider)
+            })
+            .collect();
+        let writer = Arc::new(ResultWriter::open(&output).unwrap());
+        run_concurrent(&registry, items, writer, 2, None)
+            .await
+            .unwrap();
+
+        assert_eq!(first.max_in_flight.load(Ordering::SeqCst), 2);
+        assert_eq!(second.max_in_flight.load(Ordering::SeqCst), 2);
+        assert_eq!(result_ids(&output).len(), 12);
+    }
+}
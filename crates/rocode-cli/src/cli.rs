@@ -83,6 +83,38 @@ pub(crate) enum Commands {
         variant: Option<String>,
         #[arg(long, default_value_t = false)]
         thinking: bool,
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "JSON Schema the answer must match; prints only the validated JSON"
+        )]
+        schema: Option<PathBuf>,
+    },
+    #[command(about = "Run a JSONL file of prompts with bounded concurrency")]
+    Batch {
+        #[arg(value_name = "INPUT")]
+        input: PathBuf,
+        #[arg(
+            short = 'o',
+            long,
+            help = "Results JSONL, also used to resume [default: <INPUT>.results.jsonl]"
+        )]
+        output: Option<PathBuf>,
+        #[arg(short = 'm', long)]
+        model: Option<String>,
+        #[arg(long, default_value_t = 4, help = "Requests in flight per provider")]
+        concurrency: usize,
+        #[arg(long, help = "Request starts per minute per provider")]
+        rpm: Option<u32>,
+        #[arg(
+            long,
+            help = "Use the provider's native batch API where the model supports it"
+        )]
+        native: bool,
+        #[arg(long, help = "Run items that failed in a previous run again")]
+        retry_failed: bool,
+        #[arg(long, default_value_t = 30, value_name = "SECS")]
+        poll_interval: u64,
     },
     #[command(about = "Start HTTP server")]
     Serve {
@@ -149,6 +181,22 @@ pub(crate) enum Commands {
         models: Option<usize>,
         #[arg(long)]
         project: Option<String>,
+        #[arg(long, value_enum, help = "Group ledger totals by this dimension")]
+        group_by: Option<StatsGroupBy>,
+        #[arg(
+            long,
+            value_name = "YYYY-MM-DD",
+            help = "Only count usage on or after this date (UTC)"
+        )]
+        since: Option<String>,
+        #[arg(
+            long,
+            value_name = "YYYY-MM-DD",
+            help = "Only count usage on or before this date (UTC)"
+        )]
+        until: Option<String>,
+        #[arg(long, value_enum, default_value = "table")]
+        format: StatsFormat,
     },
     #[command(about = "Database tools")]
     Db {
@@ -160,7 +208,10 @@ pub(crate) enum Commands {
         format: DbOutputFormat,
     },
     #[command(about = "Show configuration")]
-    Config,
+    Config {
+        #[command(subcommand)]
+        action: Option<ConfigCommands>,
+    },
     #[command(about = "Manage credentials")]
     Auth {
         #[command(subcommand)]
@@ -224,7 +275,13 @@ pub(crate) enum Commands {
         force: bool,
     },
     #[command(about = "Generate OpenAPI specification JSON")]
-    Generate,
+    Generate {
+        #[arg(
+            long = "config-schema",
+            help = "Emit the JSON Schema for opencode.json instead"
+        )]
+        config_schema: bool,
+    },
     #[command(about = "Show version")]
     Version,
 }
@@ -241,6 +298,23 @@ pub(crate) enum SessionListFormat {
     Json,
 }
 
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub(crate) enum StatsGroupBy {
+    Provider,
+    Model,
+    Agent,
+    Session,
+    Project,
+    Day,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub(crate) enum StatsFormat {
+    Table,
+    Json,
+    Csv,
+}
+
 #[derive(Clone, Debug, ValueEnum)]
 pub(crate) enum DbOutputFormat {
     Json,
@@ -253,6 +327,21 @@ pub(crate) enum DbCommands {
     Path,
 }
 
+#[derive(Subcommand)]
+pub(crate) enum ConfigCommands {
+    #[command(about = "Check config files for unknown keys, type errors and bad references")]
+    Validate {
+        #[arg(long, default_value = "text")]
+        format: ConfigValidateFormat,
+    },
+}
+
+#[derive(Clone, Debug, ValueEnum)]
+pub(crate) enum ConfigValidateFormat {
+    Text,
+    Json,
+}
+
 #[derive(Subcommand)]
 pub(crate) enum SessionCommands {
     #[command(about = "List sessions")]
@@ -295,6 +384,10 @@ pub(crate) enum AuthCommands {
         #[arg(value_name = "PROVIDER")]
         provider: Option<String>,
     },
+    #[command(
+        about = "Encrypt the plaintext auth.json and mcp-auth.json stores with the credential passphrase or key file"
+    )]
+    Migrate,
 }
 
 #[derive(Subcommand)]
@@ -376,6 +469,22 @@ pub(crate) enum DebugCommands {
         #[arg(long)]
         params: Option<String>,
     },
+    #[command(about = "Provider wire debugging utilities")]
+    Provider {
+        #[command(subcommand)]
+        action: DebugProviderCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum DebugProviderCommands {
+    #[command(about = "Re-parse a recorded provider stream and print its events")]
+    Replay {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+        #[arg(long, help = "Print the recorded request body before the events")]
+        request: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -0,0 +1,43 @@
+use rocode_config::{validate_config_sources, Severity};
+
+use crate::cli::{ConfigCommands, ConfigValidateFormat};
+use crate::session_cmd::show_config;
+
+pub(crate) async fn handle_config_command(action: Option<ConfigCommands>) -> anyhow::Result<()> {
+    match action {
+        None => show_config().await,
+        Some(ConfigCommands::Validate { format }) => {
+            let cwd = std::env::current_dir()?;
+            let diagnostics = validate_config_sources(&cwd);
+            let errors = diagnostics
+                .iter()
+                .filter(|d| d.severity == Severity::Error)
+                .count();
+
+            match format {
+                ConfigValidateFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&diagnostics)?);
+                }
+                ConfigValidateFormat::Text => {
+                    for diagnostic in &diagnostics {
+                        println!("{}", diagnostic);
+                    }
+                    if diagnostics.is_empty() {
+                        println!("Config is valid.");
+                    } else {
+                        println!(
+                            "\n{} error(s), {} warning(s)",
+                            errors,
+                            diagnostics.len() - errors
+                        );
+                    }
+                }
+            }
+
+            if errors > 0 {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+    }
+}
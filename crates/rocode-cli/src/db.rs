@@ -1,10 +1,13 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::path::PathBuf;
 use std::process::Command as ProcessCommand;
 
-use rocode_storage::{Database, MessageRepository, SessionRepository};
+use rocode_storage::{
+    usage_summaries_to_csv, Database, MessageRepository, SessionRepository, UsageFilter,
+    UsageGroupBy, UsageLedgerRepository, UsageSummary,
+};
 
-use crate::cli::{DbCommands, DbOutputFormat};
+use crate::cli::{DbCommands, DbOutputFormat, StatsFormat, StatsGroupBy};
 
 fn local_database_path() -> PathBuf {
     dirs::data_local_dir()
@@ -53,39 +56,126 @@ pub(crate) async fn handle_db_command(
     Ok(())
 }
 
-pub(crate) async fn handle_stats_command(
-    days: Option<i64>,
-    tools_limit: Option<usize>,
-    models_limit: Option<usize>,
-    project: Option<String>,
-) -> anyhow::Result<()> {
+pub(crate) struct StatsOptions {
+    pub days: Option<i64>,
+    pub tools_limit: Option<usize>,
+    pub models_limit: Option<usize>,
+    pub project: Option<String>,
+    pub group_by: Option<StatsGroupBy>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub format: StatsFormat,
+}
+
+fn ledger_group_by(group_by: StatsGroupBy) -> UsageGroupBy {
+    match group_by {
+        StatsGroupBy::Provider => UsageGroupBy::Provider,
+        StatsGroupBy::Model => UsageGroupBy::Model,
+        StatsGroupBy::Agent => UsageGroupBy::Agent,
+        StatsGroupBy::Session => UsageGroupBy::Session,
+        StatsGroupBy::Project => UsageGroupBy::Project,
+        StatsGroupBy::Day => UsageGroupBy::Day,
+    }
+}
+
+/// Milliseconds at UTC midnight of `date` (`YYYY-MM-DD`), shifted by `days`.
+fn date_to_millis(date: &str, days: i64) -> anyhow::Result<i64> {
+    let date = chrono::NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d")
+        .map_err(|e| anyhow::anyhow!("Invalid date `{}` (expected YYYY-MM-DD): {}", date, e))?;
+    let midnight = (date + chrono::Duration::days(days))
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    Ok(
+        chrono::DateTime::<chrono::Utc>::from_naive_utc_and_offset(midnight, chrono::Utc)
+            .timestamp_millis(),
+    )
+}
+
+fn days_cutoff(days: i64) -> i64 {
+    if days == 0 {
+        let dt = chrono::Utc::now()
+            .date_naive()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        chrono::DateTime::<chrono::Utc>::from_naive_utc_and_offset(dt, chrono::Utc)
+            .timestamp_millis()
+    } else {
+        chrono::Utc::now().timestamp_millis() - (days * 24 * 60 * 60 * 1000)
+    }
+}
+
+fn print_ledger_table(rows: &[UsageSummary], key_label: &str) {
+    println!(
+        "  {:<40} {:>6} {:>12} {:>12} {:>12} {:>12}",
+        key_label, "steps", "input", "output", "cache_read", "cost"
+    );
+    for row in rows {
+        let cost = format!("${:.4}", row.cost);
+        println!(
+            "  {:<40} {:>6} {:>12} {:>12} {:>12} {:>12}",
+            row.key, row.steps, row.input_tokens, row.output_tokens, row.cache_read_tokens, cost
+        );
+    }
+}
+
+pub(crate) async fn handle_stats_command(options: StatsOptions) -> anyhow::Result<()> {
+    let StatsOptions {
+        days,
+        tools_limit,
+        models_limit,
+        project,
+        group_by,
+        since,
+        until,
+        format,
+    } = options;
     let db = Database::new().await?;
     let session_repo = SessionRepository::new(db.pool().clone());
     let message_repo = MessageRepository::new(db.pool().clone());
+    let ledger = UsageLedgerRepository::new(db.pool().clone());
 
-    let mut sessions = session_repo.list(None, 50_000).await?;
-    if let Some(project) = project {
+    let mut filter = UsageFilter::default();
+    if let Some(project) = project.as_deref() {
         if project.is_empty() {
-            let cwd = std::env::current_dir()?.display().to_string();
-            sessions.retain(|s| s.directory == cwd);
+            filter.directory = Some(std::env::current_dir()?.display().to_string());
         } else {
-            sessions.retain(|s| s.project_id == project);
+            filter.project_id = Some(project.to_string());
         }
     }
+    filter.since = match since.as_deref() {
+        Some(date) => Some(date_to_millis(date, 0)?),
+        None => days.map(days_cutoff),
+    };
+    filter.until = until
+        .as_deref()
+        .map(|date| date_to_millis(date, 1))
+        .transpose()?;
 
-    if let Some(days) = days {
-        let now = chrono::Utc::now().timestamp_millis();
-        let cutoff = if days == 0 {
-            let dt = chrono::Utc::now()
-                .date_naive()
-                .and_hms_opt(0, 0, 0)
-                .unwrap();
-            chrono::DateTime::<chrono::Utc>::from_naive_utc_and_offset(dt, chrono::Utc)
-                .timestamp_millis()
-        } else {
-            now - (days * 24 * 60 * 60 * 1000)
-        };
-        sessions.retain(|s| s.time.updated >= cutoff);
+    let ledger_group = group_by.map(ledger_group_by);
+    match format {
+        StatsFormat::Json | StatsFormat::Csv => {
+            let rows = ledger.summarize(&filter, ledger_group).await?;
+            if format == StatsFormat::Csv {
+                print!("{}", usage_summaries_to_csv(&rows));
+            } else {
+                println!("{}", serde_json::to_string_pretty(&rows)?);
+            }
+            return Ok(());
+        }
+        StatsFormat::Table => {}
+    }
+
+    let mut sessions = session_repo.list(None, 50_000).await?;
+    if let Some(directory) = &filter.directory {
+        sessions.retain(|s| &s.directory == directory);
+    } else if let Some(project) = &filter.project_id {
+        sessions.retain(|s| &s.project_id == project);
+    }
+    if let Some(since) = filter.since {
+        sessions.retain(|s| s.time.updated >= since);
+    }
+    if let Some(until) = filter.until {
+        sessions.retain(|s| s.time.created < until);
     }
 
     let mut total_messages = 0usize;
@@ -98,8 +188,27 @@ pub(crate) async fn handle_stats_command(
     let mut tool_usage: BTreeMap<String, usize> = BTreeMap::new();
     let mut model_usage: BTreeMap<String, usize> = BTreeMap::new();
 
+    // The ledger also covers deleted sessions; sessions recorded before it
+    // existed only carry their own totals, so those are added on top.
+    let ledger_sessions = ledger
+        .summarize(&filter, Some(UsageGroupBy::Session))
+        .await?;
+    let ledgered: HashSet<&str> = ledger_sessions.iter().map(|row| row.key.as_str()).collect();
+    for row in &ledger_sessions {
+        total_cost += row.cost;
+        total_input += row.input_tokens.max(0) as u64;
+        total_output += row.output_tokens.max(0) as u64;
+        total_reasoning += row.reasoning_tokens.max(0) as u64;
+        total_cache_read += row.cache_read_tokens.max(0) as u64;
+        total_cache_write += row.cache_write_tokens.max(0) as u64;
+    }
+
     for session in &sessions {
-        if let Some(usage) = &session.usage {
+        if let Some(usage) = session
+            .usage
+            .as_ref()
+            .filter(|_| !ledgered.contains(session.id.as_str()))
+        {
             total_cost += usage.total_cost;
             total_input += usage.input_tokens;
             total_output += usage.output_tokens;
@@ -138,6 +247,16 @@ pub(crate) async fn handle_stats_command(
         total_input, total_output, total_reasoning, total_cache_read, total_cache_write
     );
 
+    if let Some(group) = ledger_group.filter(|_| !ledger_sessions.is_empty()) {
+        let rows = ledger.summarize(&filter, Some(group)).await?;
+        let label = serde_json::to_value(group)?
+            .as_str()
+            .unwrap_or("key")
+            .to_string();
+        println!("\nUsage by {}:", label);
+        print_ledger_table(&rows, &label);
+    }
+
     if !model_usage.is_empty() {
         println!("\nModel usage:");
         let mut rows: Vec<_> = model_usage.into_iter().collect();
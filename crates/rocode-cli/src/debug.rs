@@ -5,11 +5,13 @@ use std::process::Command as ProcessCommand;
 use std::sync::Arc;
 use std::time::Duration;
 
+use futures::StreamExt;
 use rocode_agent::AgentRegistry;
 use rocode_config::loader::load_config;
 use rocode_config::{LspConfig, LspServerConfig as ConfigLspServerConfig};
 use rocode_grep::{FileSearchOptions, Ripgrep};
 use rocode_lsp::{LspClient, LspServerConfig};
+use rocode_provider::RecordedExchange;
 use rocode_session::snapshot::Snapshot;
 use rocode_storage::{Database, SessionRepository};
 use rocode_tool::skill::list_available_skills;
@@ -399,6 +401,45 @@ pub(crate) async fn handle_debug_command(action: DebugCommands) -> anyhow::Resul
                 );
             }
         }
+
+        DebugCommands::Provider { action } => match action {
+            DebugProviderCommands::Replay { file, request } => {
+                replay_provider_capture(&file, request).await?;
+            }
+        },
+    }
+    Ok(())
+}
+
+async fn replay_provider_capture(path: &Path, show_request: bool) -> anyhow::Result<()> {
+    let exchange = RecordedExchange::load(path)?;
+    println!(
+        "{} {} ({:?}, provider {})",
+        exchange.method, exchange.url, exchange.wire, exchange.provider
+    );
+    match exchange.status {
+        Some(status) => println!("status {}, {} chunks", status, exchange.chunks.len()),
+        None => println!("no response recorded"),
+    }
+    if show_request {
+        println!("{}", serde_json::to_string_pretty(&exchange.request_body)?);
+    }
+    for error in &exchange.errors {
+        println!("recorded error: {}", error);
+    }
+    if exchange.chunks.is_empty() {
+        if let Some(body) = &exchange.body {
+            println!("{}", body);
+        }
+        return Ok(());
+    }
+
+    let mut events = exchange.replay()?;
+    while let Some(event) = events.next().await {
+        match event {
+            Ok(event) => println!("{}", serde_json::to_string(&event)?),
+            Err(e) => println!("error: {}", e),
+        }
     }
     Ok(())
 }
@@ -5,7 +5,13 @@ use rocode_config::loader::load_config;
 use crate::providers::setup_providers;
 use crate::util::format_tokens;
 
-pub(crate) async fn handle_generate_command() -> anyhow::Result<()> {
+pub(crate) async fn handle_generate_command(config_schema: bool) -> anyhow::Result<()> {
+    if config_schema {
+        let schema = rocode_config::config_json_schema();
+        println!("{}", serde_json::to_string_pretty(&schema)?);
+        return Ok(());
+    }
+
     let mut paths: HashMap<String, serde_json::Map<String, serde_json::Value>> = HashMap::new();
     let operations: &[(&str, &str, &str)] = &[
         ("/health", "get", "health"),
@@ -87,6 +93,10 @@ pub(crate) async fn handle_generate_command() -> anyhow::Result<()> {
         ("/config/", "get", "configGet"),
         ("/config/", "patch", "configPatch"),
         ("/config/providers", "get", "configProviderGet"),
+        ("/config/validate", "get", "configValidate"),
+        ("/config/schema", "get", "configSchema"),
+        ("/config/schema/agent", "get", "configSchemaAgent"),
+        ("/config/schema/command", "get", "configSchemaCommand"),
         ("/mcp", "get", "mcpList"),
         ("/mcp", "post", "mcpAdd"),
         ("/mcp/{name}/connect", "post", "mcpConnect"),
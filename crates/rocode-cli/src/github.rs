@@ -10,6 +10,7 @@ use rocode_agent::{AgentExecutor, AgentInfo, AgentRegistry};
 use rocode_config::loader::load_config;
 use rocode_provider::StreamEvent;
 use rocode_session::system::{EnvironmentContext, SystemPrompt};
+use rocode_session::UtilityPipeline;
 use rocode_tool::registry::create_default_registry;
 
 use crate::cli::GithubCommands;
@@ -1050,6 +1051,31 @@ pub(crate) fn github_summary_title(response: &str, fallback: &str) -> String {
     truncate_text(first, 72)
 }
 
+/// Commit subject for an automated run. The utility model summarizes the
+/// agent's reply and the changed files; without one, or when it fails, the
+/// first line of the reply is used.
+pub(crate) async fn github_commit_subject(response: &str, fallback: &str) -> String {
+    let heuristic = github_summary_title(response, fallback);
+    let Some(config) = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| load_config(&cwd).ok())
+    else {
+        return heuristic;
+    };
+    if config.utility.as_ref().and_then(|utility| utility.disabled) == Some(true) {
+        return heuristic;
+    }
+    let Ok(registry) = setup_providers(&config).await else {
+        return heuristic;
+    };
+    let (target, settings) = rocode_server::resolve_utility(&config, &registry);
+    let changes = git_output(&["status", "--short"]).unwrap_or_default();
+    let description = format!("{}\n\nChanged files:\n{}", response, changes);
+    UtilityPipeline::new(target, settings)
+        .commit_message(&description, &heuristic, None)
+        .await
+}
+
 pub(crate) fn github_create_pr(
     owner: &str,
     repo: &str,
@@ -1446,7 +1472,8 @@ pub(crate) async fn handle_github_command(action: GithubCommands) -> anyhow::Res
                         })?;
 
                         let summary =
-                            github_summary_title(&response_text, "Scheduled automation update");
+                            github_commit_subject(&response_text, "Scheduled automation update")
+                                .await;
                         if has_uncommitted_changes {
                             github_commit_all(
                                 &summary,
@@ -1505,10 +1532,11 @@ pub(crate) async fn handle_github_command(action: GithubCommands) -> anyhow::Res
 
                     if is_pr_context_event {
                         if dirty {
-                            let summary = github_summary_title(
+                            let summary = github_commit_subject(
                                 &response_text,
                                 &format!("Update PR #{}", issue_number),
-                            );
+                            )
+                            .await;
                             if has_uncommitted_changes {
                                 github_commit_all(&summary, actor.as_deref(), true)?;
                             }
@@ -1539,10 +1567,11 @@ pub(crate) async fn handle_github_command(action: GithubCommands) -> anyhow::Res
 
                         // PLACEHOLDER_CHUNK_25
 
-                        let summary = github_summary_title(
+                        let summary = github_commit_subject(
                             &response_text,
                             &format!("Fix issue #{}", issue_number),
-                        );
+                        )
+                        .await;
                         if has_uncommitted_changes {
                             github_commit_all(&summary, actor.as_deref(), true)?;
                         }
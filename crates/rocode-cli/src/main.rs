@@ -2,7 +2,9 @@ use clap::Parser;
 
 mod agent_cmd;
 mod auth;
+mod batch;
 mod cli;
+mod config_cmd;
 mod db;
 mod debug;
 mod generate;
@@ -20,8 +22,10 @@ mod util;
 
 use agent_cmd::handle_agent_command;
 use auth::handle_auth_command;
+use batch::{handle_batch_command, BatchOptions};
 use cli::*;
-use db::{handle_db_command, handle_stats_command};
+use config_cmd::handle_config_command;
+use db::{handle_db_command, handle_stats_command, StatsOptions};
 use debug::handle_debug_command;
 use generate::{handle_generate_command, list_models};
 use github::{handle_github_command, handle_pr_command};
@@ -29,7 +33,7 @@ use import_export::{export_session_data, import_session_data};
 use mcp_cmd::handle_mcp_command;
 use run::run_non_interactive;
 use server::{run_acp_command, run_server_command, run_web_command};
-use session_cmd::{handle_session_command, show_config};
+use session_cmd::handle_session_command;
 use tui::run_tui;
 use upgrade::{handle_uninstall_command, handle_upgrade_command};
 
@@ -135,6 +139,7 @@ async fn main() -> anyhow::Result<()> {
             port,
             variant,
             thinking,
+            schema,
         }) => {
             run_non_interactive(
                 message,
@@ -153,6 +158,7 @@ async fn main() -> anyhow::Result<()> {
                 port,
                 variant,
                 thinking,
+                schema,
             )
             .await?;
         }
@@ -194,13 +200,49 @@ async fn main() -> anyhow::Result<()> {
         Some(Commands::Session { action }) => {
             handle_session_command(action).await?;
         }
+        Some(Commands::Batch {
+            input,
+            output,
+            model,
+            concurrency,
+            rpm,
+            native,
+            retry_failed,
+            poll_interval,
+        }) => {
+            handle_batch_command(BatchOptions {
+                input,
+                output,
+                model,
+                concurrency,
+                rpm,
+                native,
+                retry_failed,
+                poll_interval,
+            })
+            .await?;
+        }
         Some(Commands::Stats {
             days,
             tools,
             models,
             project,
+            group_by,
+            since,
+            until,
+            format,
         }) => {
-            handle_stats_command(days, tools, models, project).await?;
+            handle_stats_command(StatsOptions {
+                days,
+                tools_limit: tools,
+                models_limit: models,
+                project,
+                group_by,
+                since,
+                until,
+                format,
+            })
+            .await?;
         }
         Some(Commands::Db {
             action,
@@ -209,8 +251,8 @@ async fn main() -> anyhow::Result<()> {
         }) => {
             handle_db_command(action, query, format).await?;
         }
-        Some(Commands::Config) => {
-            show_config().await?;
+        Some(Commands::Config { action }) => {
+            handle_config_command(action).await?;
         }
         Some(Commands::Auth { action }) => {
             handle_auth_command(action).await?;
@@ -247,8 +289,8 @@ async fn main() -> anyhow::Result<()> {
         }) => {
             handle_uninstall_command(keep_config, keep_data, dry_run, force).await?;
         }
-        Some(Commands::Generate) => {
-            handle_generate_command().await?;
+        Some(Commands::Generate { config_schema }) => {
+            handle_generate_command(config_schema).await?;
         }
         Some(Commands::Version) => {
             println!("OpenCode {}", env!("CARGO_PKG_VERSION"));
@@ -4,8 +4,8 @@ use rocode_agent::AgentExecutor;
 use rocode_plugin::init_global;
 use rocode_plugin::subprocess::{PluginContext, PluginLoader};
 use rocode_provider::{
-    bootstrap_config_from_raw, create_registry_from_bootstrap_config, AuthInfo,
-    ConfigModel as BootstrapConfigModel, ConfigProvider as BootstrapConfigProvider,
+    auth_data_dir, bootstrap_config_from_raw, create_registry_from_bootstrap_config, AuthInfo,
+    AuthManager, ConfigModel as BootstrapConfigModel, ConfigProvider as BootstrapConfigProvider,
     ProviderRegistry,
 };
 
@@ -112,6 +112,14 @@ fn provider_to_bootstrap(provider: &rocode_config::ProviderConfig) -> BootstrapC
         models,
         blacklist: (!provider.blacklist.is_empty()).then_some(provider.blacklist.clone()),
         whitelist: (!provider.whitelist.is_empty()).then_some(provider.whitelist.clone()),
+        rate_limit: provider
+            .rate_limit
+            .as_ref()
+            .map(|limits| rocode_provider::RateLimits {
+                requests_per_minute: limits.requests_per_minute,
+                tokens_per_minute: limits.tokens_per_minute,
+                max_in_flight: limits.max_in_flight,
+            }),
         ..Default::default()
     }
 }
@@ -193,22 +201,24 @@ async fn load_plugin_auth_store(config: &rocode_config::Config) -> HashMap<Strin
         }
     }
 
-    let mut auth_store = HashMap::new();
+    // Bridge credentials land in the same persisted store the server uses.
+    let auth_manager = AuthManager::load_from_file(&auth_data_dir()).await;
     for (provider_id, bridge) in loader.auth_bridges().await {
         match bridge.load().await {
             Ok(result) => {
                 if let Some(api_key) = result.api_key {
-                    auth_store.insert(
-                        provider_id.clone(),
-                        AuthInfo::Api {
-                            key: api_key.clone(),
-                        },
-                    );
+                    auth_manager
+                        .set(
+                            &provider_id,
+                            AuthInfo::Api {
+                                key: api_key.clone(),
+                            },
+                        )
+                        .await;
                     if provider_id == "github-copilot" {
-                        auth_store.insert(
-                            "github-copilot-enterprise".to_string(),
-                            AuthInfo::Api { key: api_key },
-                        );
+                        auth_manager
+                            .set("github-copilot-enterprise", AuthInfo::Api { key: api_key })
+                            .await;
                     }
                 }
             }
@@ -218,7 +228,7 @@ async fn load_plugin_auth_store(config: &rocode_config::Config) -> HashMap<Strin
         }
     }
 
-    auth_store
+    auth_manager.list().await
 }
 
 pub(crate) fn show_help() {
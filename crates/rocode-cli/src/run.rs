@@ -1,14 +1,16 @@
 use futures::StreamExt;
 use std::io::{self, BufRead, Write};
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
-use rocode_agent::{AgentExecutor, AgentInfo, AgentRegistry};
+use rocode_agent::{AgentExecutor, AgentInfo, AgentRegistry, StepUsage, UsageObserver};
 use rocode_command::{CommandContext, CommandRegistry};
 use rocode_config::loader::load_config;
-use rocode_provider::StreamEvent;
+use rocode_provider::{ProviderRegistry, ResponseFormat, StreamEvent, DEFAULT_STRUCTURED_RETRIES};
 use rocode_session::system::{EnvironmentContext, SystemPrompt};
+use rocode_storage::{Database, UsageLedgerRepository, UsageRecord};
 use rocode_tool::registry::create_default_registry;
+use tokio::task::JoinHandle;
 
 use crate::cli::RunOutputFormat;
 use crate::providers::{
@@ -34,6 +36,7 @@ pub(crate) async fn run_non_interactive(
     _port: Option<u16>,
     variant: Option<String>,
     _thinking: bool,
+    schema: Option<PathBuf>,
 ) -> anyhow::Result<()> {
     if let Some(dir) = dir {
         std::env::set_current_dir(&dir).map_err(|e| {
@@ -45,10 +48,15 @@ pub(crate) async fn run_non_interactive(
         anyhow::bail!("--fork requires --continue or --session");
     }
 
+    let response_format = schema.as_deref().map(load_response_format).transpose()?;
+
     let mut input = collect_run_input(message)?;
     append_cli_file_attachments(&mut input, &files)?;
 
     if let Some(base_url) = attach {
+        if response_format.is_some() {
+            anyhow::bail!("--schema is not supported together with --attach");
+        }
         return run_non_interactive_attach(
             base_url,
             input,
@@ -88,6 +96,14 @@ pub(crate) async fn run_non_interactive(
         input = rendered;
     }
 
+    if let Some(format) = response_format {
+        if input.trim().is_empty() {
+            anyhow::bail!("--schema requires a message");
+        }
+        let (provider, model_id) = parse_model_and_provider(model);
+        return run_structured(model_id, provider, agent_name, input, format).await;
+    }
+
     if input.trim().is_empty() {
         let (provider, model_id) = parse_model_and_provider(model);
         return run_chat_session(model_id, provider, agent_name, None, false).await;
@@ -110,15 +126,45 @@ pub(crate) async fn run_non_interactive(
     Ok(())
 }
 
-async fn run_chat_session(
+fn load_response_format(path: &Path) -> anyhow::Result<ResponseFormat> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read schema {}: {}", path.display(), e))?;
+    let schema: serde_json::Value = serde_json::from_str(&raw)
+        .map_err(|e| anyhow::anyhow!("Invalid JSON in schema {}: {}", path.display(), e))?;
+    if !schema.is_object() {
+        anyhow::bail!("Schema {} must be a JSON object", path.display());
+    }
+    Ok(ResponseFormat::json_schema(schema))
+}
+
+/// Runs a single prompt and prints only the JSON answer validated against `format`.
+async fn run_structured(
     model: Option<String>,
     provider: Option<String>,
     agent_name: String,
-    initial_prompt: Option<String>,
-    single_shot: bool,
+    input: String,
+    format: ResponseFormat,
 ) -> anyhow::Result<()> {
+    let (mut executor, _, ledger) = prepare_executor(model, provider, &agent_name).await?;
+    let value = executor
+        .execute_structured(input, &format, DEFAULT_STRUCTURED_RETRIES)
+        .await;
+    if let Some(ledger) = &ledger {
+        ledger.flush().await;
+    }
+    let value = value?;
+    println!("{}", serde_json::to_string_pretty(&value)?);
+    Ok(())
+}
+
+async fn prepare_executor(
+    model: Option<String>,
+    provider: Option<String>,
+    agent_name: &str,
+) -> anyhow::Result<(AgentExecutor, Arc<ProviderRegistry>, Option<RunLedger>)> {
     let current_dir = std::env::current_dir()?;
     let config = load_config(&current_dir)?;
+    rocode_tool::task::configure_background_tasks(&config);
 
     let provider_registry = Arc::new(setup_providers(&config).await?);
 
@@ -149,7 +195,7 @@ async fn run_chat_session(
 
     let agent_registry = AgentRegistry::from_config(&config);
     let mut agent_info = agent_registry
-        .get(&agent_name)
+        .get(agent_name)
         .cloned()
         .unwrap_or_else(|| AgentInfo::build());
 
@@ -164,16 +210,6 @@ async fn run_chat_session(
         agent_info = agent_info.with_model(model_id.clone(), provider_id);
     }
 
-    println!("\n╔══════════════════════════════════════════╗");
-    println!("║        OpenCode Interactive Mode         ║");
-    println!("╚══════════════════════════════════════════╝");
-    println!();
-    println!("  Model: {}", model.as_deref().unwrap_or("auto"));
-    println!("  Agent: {}", agent_name);
-    println!("  Directory: {}", current_dir.display());
-    println!();
-    println!("  Commands: exit, quit, help, clear");
-    println!();
     let mut executor =
         AgentExecutor::new(agent_info.clone(), provider_registry.clone(), tool_registry);
 
@@ -197,9 +233,125 @@ async fn run_chat_session(
         executor = executor.with_system_prompt(full_prompt);
     }
 
+    let ledger = RunLedger::open(&current_dir, provider_registry.clone()).await;
+    if let Some(ledger) = &ledger {
+        executor = executor.with_usage_observer(ledger.observer());
+    }
+
+    Ok((executor, provider_registry, ledger))
+}
+
+/// Records every model step of a CLI run in the usage ledger, priced the same
+/// way as server sessions, under a session id of its own.
+struct RunLedger {
+    ledger: UsageLedgerRepository,
+    session_id: String,
+    directory: String,
+    providers: Arc<ProviderRegistry>,
+    pending: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl RunLedger {
+    async fn open(directory: &Path, providers: Arc<ProviderRegistry>) -> Option<Self> {
+        let db = match Database::new().await {
+            Ok(db) => db,
+            Err(error) => {
+                tracing::warn!(%error, "usage ledger unavailable; this run will not be recorded");
+                return None;
+            }
+        };
+        Some(Self {
+            ledger: UsageLedgerRepository::new(db.pool().clone()),
+            session_id: rocode_core::id::create(rocode_core::id::Prefix::Session, true, None),
+            directory: directory.display().to_string(),
+            providers,
+            pending: Arc::new(Mutex::new(Vec::new())),
+        })
+    }
+
+    fn observer(&self) -> UsageObserver {
+        let ledger = self.ledger.clone();
+        let session_id = self.session_id.clone();
+        let directory = self.directory.clone();
+        let providers = self.providers.clone();
+        let pending = self.pending.clone();
+        Arc::new(move |step: StepUsage| {
+            let model = providers
+                .get(&step.provider_id)
+                .and_then(|provider| provider.get_model(&step.model_id).cloned());
+            let cost = rocode_provider::resolve_model_cost(
+                &step.provider_id,
+                &step.model_id,
+                model.as_ref(),
+            )
+            .map(|cost| cost.cost_for(&step.usage))
+            .unwrap_or(0.0);
+            let record = UsageRecord {
+                created_at: chrono::Utc::now().timestamp_millis(),
+                session_id: session_id.clone(),
+                message_id: None,
+                project_id: "default".to_string(),
+                directory: directory.clone(),
+                provider_id: step.provider_id,
+                model_id: step.model_id,
+                agent: Some(step.agent),
+                input_tokens: step.usage.prompt_tokens,
+                output_tokens: step.usage.completion_tokens,
+                reasoning_tokens: step.usage.reasoning_tokens,
+                cache_read_tokens: step.usage.cache_read_tokens,
+                cache_write_tokens: step.usage.cache_write_tokens,
+                cost,
+            };
+            let ledger = ledger.clone();
+            let handle = tokio::spawn(async move {
+                if let Err(error) = ledger.append(&record).await {
+                    tracing::warn!(%error, "failed to append usage ledger row");
+                }
+            });
+            pending
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(handle);
+        })
+    }
+
+    /// Waits for queued ledger rows so a run that exits right away keeps them.
+    async fn flush(&self) {
+        let handles = std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()));
+        for handle in handles {
+            let _ = handle.await;
+        }
+    }
+}
+
+async fn run_chat_session(
+    model: Option<String>,
+    provider: Option<String>,
+    agent_name: String,
+    initial_prompt: Option<String>,
+    single_shot: bool,
+) -> anyhow::Result<()> {
+    let current_dir = std::env::current_dir()?;
+    let (mut executor, provider_registry, ledger) =
+        prepare_executor(model.clone(), provider, &agent_name).await?;
+
+    println!("\n╔══════════════════════════════════════════╗");
+    println!("║        OpenCode Interactive Mode         ║");
+    println!("╚══════════════════════════════════════════╝");
+    println!();
+    println!("  Model: {}", model.as_deref().unwrap_or("auto"));
+    println!("  Agent: {}", agent_name);
+    println!("  Directory: {}", current_dir.display());
+    println!();
+    println!("  Commands: exit, quit, help, clear");
+    println!();
     if let Some(prompt_text) = initial_prompt {
         println!("User: {}", prompt_text);
-        process_message(&mut executor, &prompt_text).await?;
+        let result = process_message(&mut executor, &prompt_text).await;
+        if let Some(ledger) = &ledger {
+            ledger.flush().await;
+        }
+        result?;
         if single_shot {
             return Ok(());
         }
@@ -258,11 +410,12 @@ async fn run_chat_session(
             continue;
         }
 
-        match process_message(&mut executor, input).await {
-            Ok(_) => {}
-            Err(e) => {
-                eprintln!("\nError: {}", e);
-            }
+        let result = process_message(&mut executor, input).await;
+        if let Some(ledger) = &ledger {
+            ledger.flush().await;
+        }
+        if let Err(e) = result {
+            eprintln!("\nError: {}", e);
         }
     }
 
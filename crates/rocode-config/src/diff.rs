@@ -0,0 +1,121 @@
+use crate::Config;
+use serde_json::Value;
+
+/// Sections keyed by name whose entries are diffed individually, so a change
+/// to one MCP server or agent is reported as `mcp.<name>` rather than `mcp`.
+const KEYED_SECTIONS: &[&str] = &["mcp", "agent", "command", "provider", "lsp", "formatter"];
+
+/// Keys that feed the provider registry bootstrap.
+const PROVIDER_KEYS: &[&str] = &[
+    "provider",
+    "disabled_providers",
+    "enabled_providers",
+    "model",
+    "small_model",
+];
+
+/// Semantic difference between two effective configs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigDiff {
+    /// Changed top-level keys, or `section.name` for keyed sections. Sorted.
+    pub changed: Vec<String>,
+}
+
+impl ConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty()
+    }
+
+    /// Whether `section` or any entry below it changed.
+    pub fn touches(&self, section: &str) -> bool {
+        self.changed.iter().any(|key| {
+            key == section
+                || key
+                    .strip_prefix(section)
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+    }
+
+    /// Names of the changed entries of a keyed section such as `mcp`.
+    pub fn changed_entries<'a>(&'a self, section: &str) -> Vec<&'a str> {
+        self.changed
+            .iter()
+            .filter_map(|key| key.strip_prefix(section)?.strip_prefix('.'))
+            .collect()
+    }
+
+    pub fn touches_providers(&self) -> bool {
+        PROVIDER_KEYS.iter().any(|key| self.touches(key))
+    }
+}
+
+/// Compares two configs by their serialized form.
+pub fn diff_configs(old: &Config, new: &Config) -> ConfigDiff {
+    let old = serde_json::to_value(old).unwrap_or(Value::Null);
+    let new = serde_json::to_value(new).unwrap_or(Value::Null);
+    let empty = serde_json::Map::new();
+    let old = old.as_object().unwrap_or(&empty);
+    let new = new.as_object().unwrap_or(&empty);
+
+    let mut changed = Vec::new();
+    for key in old
+        .keys()
+        .chain(new.keys().filter(|k| !old.contains_key(*k)))
+    {
+        let (before, after) = (old.get(key), new.get(key));
+        if before == after {
+            continue;
+        }
+        match (before, after) {
+            (Some(Value::Object(a)), Some(Value::Object(b)))
+                if KEYED_SECTIONS.contains(&key.as_str()) =>
+            {
+                for name in a.keys().chain(b.keys().filter(|n| !a.contains_key(*n))) {
+                    if a.get(name) != b.get(name) {
+                        changed.push(format!("{}.{}", key, name));
+                    }
+                }
+            }
+            _ => changed.push(key.clone()),
+        }
+    }
+    changed.sort();
+    ConfigDiff { changed }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(json: &str) -> Config {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn reports_keyed_entries_individually() {
+        let old = config(
+            r#"{"model":"a/b","mcp":{"one":{"type":"local","command":["x"]},"two":{"type":"local","command":["y"]}}}"#,
+        );
+        let new = config(
+            r#"{"model":"a/c","mcp":{"one":{"type":"local","command":["x"]},"three":{"type":"local","command":["z"]}}}"#,
+        );
+
+        let diff = diff_configs(&old, &new);
+        assert_eq!(diff.changed, vec!["mcp.three", "mcp.two", "model"]);
+        assert!(diff.touches("mcp"));
+        assert!(!diff.touches("mc"));
+        assert_eq!(diff.changed_entries("mcp"), vec!["three", "two"]);
+        assert!(diff.touches_providers());
+    }
+
+    #[test]
+    fn identical_configs_have_no_diff() {
+        let old = config(r#"{"theme":"dark","compaction":{"auto":true}}"#);
+        assert!(diff_configs(&old, &old.clone()).is_empty());
+
+        let new = config(r#"{"theme":"dark","compaction":{"auto":false}}"#);
+        let diff = diff_configs(&old, &new);
+        assert_eq!(diff.changed, vec!["compaction"]);
+        assert!(!diff.touches_providers());
+    }
+}
@@ -0,0 +1,97 @@
+//! JSON Schemas derived from the config types, for editor completion and
+//! validation of `opencode.json` and markdown frontmatter.
+
+use schemars::gen::SchemaSettings;
+use schemars::JsonSchema;
+use serde_json::Value;
+
+use crate::schema::{AgentConfig, CommandConfig, Config};
+
+/// Schema for `opencode.json{,c}`.
+pub fn config_json_schema() -> Value {
+    let mut schema = root_schema::<Config>("rocode config");
+    // Unknown top-level keys are almost always typos; let editors flag them.
+    schema["additionalProperties"] = Value::Bool(false);
+    schema
+}
+
+/// Schema for the YAML frontmatter of agent and mode markdown files.
+pub fn agent_frontmatter_json_schema() -> Value {
+    root_schema::<AgentConfig>("rocode agent frontmatter")
+}
+
+/// Schema for the YAML frontmatter of command markdown files.
+pub fn command_frontmatter_json_schema() -> Value {
+    root_schema::<CommandConfig>("rocode command frontmatter")
+}
+
+fn root_schema<T: JsonSchema>(title: &str) -> Value {
+    let generator = SchemaSettings::draft07()
+        .with(|settings| settings.option_add_null_type = false)
+        .into_generator();
+    let mut root = generator.into_root_schema_for::<T>();
+    root.schema.metadata().title = Some(title.to_string());
+    serde_json::to_value(root).unwrap_or(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// The published schemas under `schema/` must match the Rust types.
+    /// Regenerate with `ROCODE_UPDATE_SCHEMA=1 cargo test -p rocode-config`.
+    #[test]
+    fn committed_schemas_are_up_to_date() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("schema");
+        let schemas = [
+            ("config.schema.json", config_json_schema()),
+            ("agent.schema.json", agent_frontmatter_json_schema()),
+            ("command.schema.json", command_frontmatter_json_schema()),
+        ];
+        for (file, schema) in schemas {
+            let path = dir.join(file);
+            let generated = serde_json::to_string_pretty(&schema).unwrap() + "\n";
+            if std::env::var_os("ROCODE_UPDATE_SCHEMA").is_some() {
+                std::fs::create_dir_all(&dir).unwrap();
+                std::fs::write(&path, &generated).unwrap();
+                continue;
+            }
+            let committed = std::fs::read_to_string(&path).unwrap_or_default();
+            assert!(
+                committed == generated,
+                "{} is out of date; run `ROCODE_UPDATE_SCHEMA=1 cargo test -p rocode-config`",
+                path.display()
+            );
+        }
+    }
+
+    #[test]
+    fn config_schema_describes_sections() {
+        let schema = config_json_schema();
+        let properties = schema["properties"].as_object().unwrap();
+        for key in [
+            "$schema",
+            "model",
+            "agent",
+            "permission",
+            "mcp",
+            "compaction",
+        ] {
+            assert!(properties.contains_key(key), "missing {}", key);
+        }
+        assert_eq!(schema["additionalProperties"], Value::Bool(false));
+        assert!(schema["definitions"]["AgentConfig"].is_object());
+        assert_eq!(
+            schema["definitions"]["AgentConfigs"]["additionalProperties"]["$ref"],
+            "#/definitions/AgentConfig"
+        );
+        assert_eq!(properties["mode"]["$ref"], "#/definitions/AgentConfigs");
+        assert_eq!(
+            schema["definitions"]["PermissionConfig"]["additionalProperties"]["$ref"],
+            "#/definitions/PermissionRule"
+        );
+        assert!(schema["definitions"]["PermissionRule"].is_object());
+        assert!(agent_frontmatter_json_schema()["properties"]["model"].is_object());
+    }
+}
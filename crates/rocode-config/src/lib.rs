@@ -1,6 +1,12 @@
+pub mod diff;
+pub mod json_schema;
 pub mod loader;
 pub mod schema;
+pub mod validate;
 pub mod wellknown;
 
+pub use diff::*;
+pub use json_schema::*;
 pub use loader::*;
 pub use schema::*;
+pub use validate::*;
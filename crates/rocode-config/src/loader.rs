@@ -253,7 +253,7 @@ impl Default for ConfigLoader {
     }
 }
 
-fn get_global_config_path() -> PathBuf {
+pub(crate) fn get_global_config_path() -> PathBuf {
     let config_dir = if cfg!(target_os = "macos") {
         dirs::config_dir().unwrap_or_else(|| PathBuf::from("~/.config"))
     } else if cfg!(target_os = "windows") {
@@ -477,11 +477,11 @@ fn parse_jsonc(content: &str) -> Result<Config> {
     serde_json::from_value(parsed).with_context(|| "Failed to parse config JSON")
 }
 
-fn normalize_existing_path(path: &Path) -> PathBuf {
+pub(crate) fn normalize_existing_path(path: &Path) -> PathBuf {
     fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
 }
 
-fn detect_worktree_stop(start: &Path) -> PathBuf {
+pub(crate) fn detect_worktree_stop(start: &Path) -> PathBuf {
     let mut current = normalize_existing_path(start);
     let mut topmost = current.clone();
     loop {
@@ -499,7 +499,7 @@ fn detect_worktree_stop(start: &Path) -> PathBuf {
     }
 }
 
-fn find_up(target: &str, start: &Path, stop: &Path) -> Vec<PathBuf> {
+pub(crate) fn find_up(target: &str, start: &Path, stop: &Path) -> Vec<PathBuf> {
     let mut current = normalize_existing_path(start);
     let stop = normalize_existing_path(stop);
     let mut result = Vec::new();
@@ -525,7 +525,7 @@ fn find_up(target: &str, start: &Path, stop: &Path) -> Vec<PathBuf> {
 }
 
 /// Get the managed config directory for enterprise deployments.
-fn get_managed_config_dir() -> PathBuf {
+pub(crate) fn get_managed_config_dir() -> PathBuf {
     if let Ok(test_dir) = env::var("OPENCODE_TEST_MANAGED_CONFIG_DIR") {
         return PathBuf::from(test_dir);
     }
@@ -541,7 +541,7 @@ fn get_managed_config_dir() -> PathBuf {
 }
 
 /// Collect .opencode directories from project hierarchy and global config.
-fn collect_opencode_directories(project_dir: &Path) -> Vec<PathBuf> {
+pub(crate) fn collect_opencode_directories(project_dir: &Path) -> Vec<PathBuf> {
     let mut directories = Vec::new();
 
     // Global config directory
@@ -677,7 +677,7 @@ fn load_plugins_from_dir(dir: &Path) -> Vec<String> {
 }
 
 /// Recursively find all .md files in a directory.
-fn glob_md_files(dir: &Path) -> Result<Vec<PathBuf>> {
+pub(crate) fn glob_md_files(dir: &Path) -> Result<Vec<PathBuf>> {
     let mut results = Vec::new();
     glob_md_files_recursive(dir, &mut results)?;
     Ok(results)
@@ -740,7 +740,7 @@ fn parse_markdown_agent(path: &Path, base_dir: &Path) -> Option<(String, AgentCo
 }
 
 /// Split markdown content into optional YAML frontmatter and body.
-fn split_frontmatter(content: &str) -> (Option<String>, String) {
+pub(crate) fn split_frontmatter(content: &str) -> (Option<String>, String) {
     let trimmed = content.trim_start();
     if !trimmed.starts_with("---") {
         return (None, content.to_string());
@@ -890,7 +890,7 @@ fn indent_level(line: &str) -> usize {
 /// Handles: flat key-value, inline lists/maps, multi-line dash lists,
 /// nested objects (indentation-based), and block scalars (| and >).
 /// Falls back to sanitized re-parse on failure.
-fn serde_yaml_frontmatter_to_json(yaml: &str) -> serde_json::Value {
+pub(crate) fn serde_yaml_frontmatter_to_json(yaml: &str) -> serde_json::Value {
     match parse_yaml_mapping(yaml) {
         Some(value) => value,
         None => {
@@ -903,7 +903,7 @@ fn serde_yaml_frontmatter_to_json(yaml: &str) -> serde_json::Value {
 }
 
 /// Parse a YAML mapping (object) from a string. Returns None on structural failure.
-fn parse_yaml_mapping(yaml: &str) -> Option<serde_json::Value> {
+pub(crate) fn parse_yaml_mapping(yaml: &str) -> Option<serde_json::Value> {
     let lines: Vec<&str> = yaml.lines().collect();
     let (map, _) = parse_yaml_mapping_lines(&lines, 0, 0)?;
     Some(serde_json::Value::Object(map))
@@ -1181,6 +1181,18 @@ fn merge_agent_config(target: &mut AgentConfig, source: AgentConfig) {
     if source.max_tokens.is_some() {
         target.max_tokens = source.max_tokens;
     }
+    if source.fallback.is_some() {
+        target.fallback = source.fallback;
+    }
+    if source.tool_choice.is_some() {
+        target.tool_choice = source.tool_choice;
+    }
+    if source.stop.is_some() {
+        target.stop = source.stop;
+    }
+    if source.parallel_tool_calls.is_some() {
+        target.parallel_tool_calls = source.parallel_tool_calls;
+    }
     if let Some(source_opts) = source.options {
         let target_opts = target.options.get_or_insert_with(HashMap::new);
         for (k, v) in source_opts {
@@ -1331,30 +1343,74 @@ pub async fn load_config_with_remote<P: AsRef<Path>>(project_dir: P) -> Result<C
     loader.load_all_with_remote(project_dir).await
 }
 
-/// Update project-level config by merging a patch.
-pub fn update_config(project_dir: &Path, patch: &Config) -> Result<()> {
-    let config_path = project_dir.join("opencode.json");
+/// Directories whose contents feed the config for `project_dir`: every
+/// `.opencode`-style directory, its agent/command/mode subdirectories, and
+/// the directories that may hold `opencode.json{,c}` files. Used to watch
+/// for changes; entries that do not exist yet are skipped.
+pub fn config_watch_dirs<P: AsRef<Path>>(project_dir: P) -> Vec<PathBuf> {
+    let project_dir = project_dir.as_ref();
+    let start_dir = normalize_existing_path(project_dir);
+    let stop_dir = detect_worktree_stop(&start_dir);
 
-    let existing = if config_path.exists() {
-        let content = fs::read_to_string(&config_path)?;
-        parse_jsonc(&content).unwrap_or_default()
-    } else {
-        Config::default()
-    };
+    let mut dirs = Vec::new();
+    let mut current = start_dir.as_path();
+    loop {
+        dirs.push(current.to_path_buf());
+        if current == stop_dir {
+            break;
+        }
+        match current.parent() {
+            Some(parent) if parent != current => current = parent,
+            _ => break,
+        }
+    }
+    if let Some(global_dir) = get_global_config_path().parent() {
+        dirs.push(global_dir.to_path_buf());
+    }
+    if let Ok(path) = env::var("OPENCODE_CONFIG") {
+        if let Some(parent) = Path::new(&path).parent() {
+            dirs.push(parent.to_path_buf());
+        }
+    }
+    dirs.push(get_managed_config_dir());
 
-    let mut merged = existing;
-    merged.merge(patch.clone());
+    for dir in collect_opencode_directories(project_dir) {
+        for subdir in ["agent", "agents", "command", "commands", "mode", "modes"] {
+            dirs.push(dir.join(subdir));
+        }
+        dirs.push(dir);
+    }
 
-    let json =
-        serde_json::to_string_pretty(&merged).with_context(|| "Failed to serialize config")?;
-    fs::write(&config_path, json)
-        .with_context(|| format!("Failed to write config to {:?}", config_path))?;
+    let mut seen = std::collections::HashSet::new();
+    dirs.retain(|d| d.is_dir() && seen.insert(d.clone()));
+    dirs
+}
 
-    Ok(())
+/// Update project-level config by merging a patch.
+pub fn update_config(project_dir: &Path, patch: &Config) -> Result<()> {
+    update_config_replacing(project_dir, patch, &[])
+}
+
+/// Like [`update_config`], but the top-level `replace` keys are taken from
+/// `patch` as a whole instead of being merged into the stored value, so
+/// entries removed from e.g. `agent` are removed from the file too.
+pub fn update_config_replacing(
+    project_dir: &Path,
+    patch: &Config,
+    replace: &[String],
+) -> Result<()> {
+    let config_path = project_dir.join("opencode.json");
+    merge_into_file(&config_path, patch, replace)
+        .with_context(|| format!("Failed to write config to {:?}", config_path))
 }
 
 /// Update global config by merging a patch.
 pub fn update_global_config(patch: &Config) -> Result<()> {
+    update_global_config_replacing(patch, &[])
+}
+
+/// Global counterpart of [`update_config_replacing`].
+pub fn update_global_config_replacing(patch: &Config, replace: &[String]) -> Result<()> {
     let global_path = get_global_config_path();
 
     // Try to find existing global config file
@@ -1369,21 +1425,33 @@ pub fn update_global_config(patch: &Config) -> Result<()> {
         fs::create_dir_all(parent)?;
     }
 
-    let existing = if config_path.exists() {
-        let content = fs::read_to_string(&config_path)?;
-        parse_jsonc(&content).unwrap_or_default()
+    merge_into_file(&config_path, patch, replace)
+        .with_context(|| format!("Failed to write global config to {:?}", config_path))
+}
+
+fn merge_into_file(config_path: &Path, patch: &Config, replace: &[String]) -> Result<()> {
+    let mut existing = if config_path.exists() {
+        let content = fs::read_to_string(config_path)?;
+        parse_jsonc(&content)
+            .with_context(|| format!("Refusing to overwrite unparsable config {:?}", config_path))?
     } else {
         Config::default()
     };
 
+    if !replace.is_empty() {
+        let mut value = serde_json::to_value(&existing)?;
+        if let Some(object) = value.as_object_mut() {
+            object.retain(|key, _| !replace.iter().any(|r| r == key));
+        }
+        existing = serde_json::from_value(value)?;
+    }
+
     let mut merged = existing;
     merged.merge(patch.clone());
 
     let json =
         serde_json::to_string_pretty(&merged).with_context(|| "Failed to serialize config")?;
-    fs::write(&config_path, json)
-        .with_context(|| format!("Failed to write global config to {:?}", config_path))?;
-
+    fs::write(config_path, json)?;
     Ok(())
 }
 
@@ -1426,6 +1494,45 @@ mod tests {
         assert_eq!(config.model, Some("claude-3-opus".to_string()));
     }
 
+    #[test]
+    fn test_update_config_replacing_drops_removed_entries() {
+        let temp = TestDir::new("opencode_update_config_replace");
+        let path = temp.path.join("opencode.json");
+        fs::write(
+            &path,
+            r#"{ "theme": "nord", "agent": { "build": { "steps": 3 }, "old": { "steps": 1 } } }"#,
+        )
+        .unwrap();
+
+        let patch: Config =
+            serde_json::from_str(r#"{ "agent": { "build": { "steps": 5 } } }"#).unwrap();
+        update_config(&temp.path, &patch).unwrap();
+        let merged: Config = parse_jsonc(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(merged.agent.as_ref().unwrap().entries.contains_key("old"));
+
+        update_config_replacing(&temp.path, &patch, &["agent".to_string()]).unwrap();
+        let replaced: Config = parse_jsonc(&fs::read_to_string(&path).unwrap()).unwrap();
+        let agents = &replaced.agent.as_ref().unwrap().entries;
+        assert_eq!(agents.keys().collect::<Vec<_>>(), vec!["build"]);
+        assert_eq!(agents["build"].steps, Some(5));
+        assert_eq!(replaced.theme.as_deref(), Some("nord"));
+    }
+
+    #[test]
+    fn test_update_config_keeps_unparsable_file() {
+        let temp = TestDir::new("opencode_update_config_invalid");
+        let path = temp.path.join("opencode.json");
+        fs::write(&path, "{ not json").unwrap();
+
+        let patch = Config {
+            theme: Some("nord".to_string()),
+            ..Default::default()
+        };
+
+        assert!(update_config(&temp.path, &patch).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "{ not json");
+    }
+
     #[test]
     fn test_parse_jsonc_with_comments() {
         let content = r#"{
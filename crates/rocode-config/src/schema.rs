@@ -1,7 +1,10 @@
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct Config {
     #[serde(rename = "$schema", skip_serializing_if = "Option::is_none")]
     pub schema: Option<String>,
@@ -61,6 +64,9 @@ pub struct Config {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub small_model: Option<String>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub utility: Option<UtilityConfig>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub default_agent: Option<String>,
 
@@ -103,6 +109,12 @@ pub struct Config {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub compaction: Option<CompactionConfig>,
 
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<EmbeddingConfig>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub websearch: Option<WebSearchConfig>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub experimental: Option<ExperimentalConfig>,
 
@@ -110,7 +122,7 @@ pub struct Config {
     pub env: Option<HashMap<String, String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ShareMode {
     Manual,
@@ -118,14 +130,14 @@ pub enum ShareMode {
     Disabled,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum AutoUpdateMode {
     Boolean(bool),
     Notify(String),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct KeybindsConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub leader: Option<String>,
@@ -315,6 +327,9 @@ pub struct KeybindsConfig {
     pub tips_toggle: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub display_thinking: Option<String>,
+    /// Leaves insert mode when vim editing is enabled (default `ctrl+g`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vim_normal_mode: Option<String>,
 
     // Legacy fields kept for backward compatibility
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -325,7 +340,7 @@ pub struct KeybindsConfig {
     pub interrupt: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct TuiConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mode: Option<String>,
@@ -337,14 +352,17 @@ pub struct TuiConfig {
     pub scroll_acceleration: Option<ScrollAccelerationConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub diff_style: Option<String>,
+    /// Modal vim-style editing in the prompt input.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vim: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ScrollAccelerationConfig {
     pub enabled: bool,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ServerConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub port: Option<u16>,
@@ -362,7 +380,7 @@ pub struct ServerConfig {
     pub cors: Option<Vec<String>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct CommandConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -378,7 +396,7 @@ pub struct CommandConfig {
     pub subtask: Option<bool>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct SkillsConfig {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub paths: Vec<String>,
@@ -386,7 +404,7 @@ pub struct SkillsConfig {
     pub urls: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct WatcherConfig {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub ignore: Vec<String>,
@@ -398,7 +416,19 @@ pub struct AgentConfigs {
     pub entries: HashMap<String, AgentConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+// `#[serde(flatten)]` on a map derives an empty object schema; describe the
+// entries so editors can complete agent and mode definitions.
+impl JsonSchema for AgentConfigs {
+    fn schema_name() -> String {
+        "AgentConfigs".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        <HashMap<String, AgentConfig>>::json_schema(gen)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct AgentConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -430,13 +460,25 @@ pub struct AgentConfig {
     pub max_steps: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_tokens: Option<u64>,
+    /// Models tried in order, as `provider/model`, when the agent's model
+    /// keeps failing with rate limits, overloads or server errors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fallback: Option<Vec<String>>,
+    /// `auto`, `none`, `required`, or the name of a tool the model must call.
+    /// Forcing choices only apply to the first step of each prompt.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub permission: Option<PermissionConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tools: Option<HashMap<String, bool>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum AgentMode {
     Primary,
@@ -444,7 +486,7 @@ pub enum AgentMode {
     All,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ProviderConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -462,9 +504,24 @@ pub struct ProviderConfig {
     pub whitelist: Vec<String>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub blacklist: Vec<String>,
+    #[serde(alias = "rateLimit", skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitConfig>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Client-side limits applied to each model of a provider and shared by every
+/// session, subagent and batch run in the process. Unset fields fall back to
+/// whatever the provider advertises in its rate-limit response headers.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct RateLimitConfig {
+    #[serde(alias = "requestsPerMinute", skip_serializing_if = "Option::is_none")]
+    pub requests_per_minute: Option<u32>,
+    #[serde(alias = "tokensPerMinute", skip_serializing_if = "Option::is_none")]
+    pub tokens_per_minute: Option<u32>,
+    #[serde(alias = "maxInFlight", skip_serializing_if = "Option::is_none")]
+    pub max_in_flight: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ModelConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
@@ -478,7 +535,7 @@ pub struct ModelConfig {
     pub variants: Option<HashMap<String, ModelVariantConfig>>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ModelVariantConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub disabled: Option<bool>,
@@ -486,14 +543,14 @@ pub struct ModelVariantConfig {
     pub extra: HashMap<String, serde_json::Value>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum McpServerConfig {
     Enabled { enabled: bool },
     Full(McpServer),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct McpServer {
     #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
     pub server_type: Option<String>,
@@ -537,14 +594,14 @@ pub struct McpServer {
 
 /// OAuth configuration for remote MCP servers.
 /// Can be a full config object or `false` to disable OAuth auto-detection.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum McpOAuthConfig {
     Disabled(bool),
     Config(McpOAuth),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct McpOAuth {
     #[serde(
         rename = "clientId",
@@ -562,14 +619,14 @@ pub struct McpOAuth {
     pub scope: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum FormatterConfig {
     Disabled(bool),
     Enabled(HashMap<String, FormatterEntry>),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct FormatterEntry {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub disabled: Option<bool>,
@@ -581,14 +638,14 @@ pub struct FormatterEntry {
     pub extensions: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum LspConfig {
     Disabled(bool),
     Enabled(HashMap<String, LspServerConfig>),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct LspServerConfig {
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub command: Vec<String>,
@@ -603,7 +660,7 @@ pub struct LspServerConfig {
 }
 
 /// Layout mode: "auto" or "stretch" (TS: z.enum(["auto", "stretch"]))
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum LayoutMode {
     Auto,
@@ -619,8 +676,22 @@ pub struct PermissionConfig {
     pub rules: HashMap<String, PermissionRule>,
 }
 
+impl JsonSchema for PermissionConfig {
+    fn schema_name() -> String {
+        "PermissionConfig".to_string()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let mut schema = <HashMap<String, PermissionRule>>::json_schema(gen).into_object();
+        schema.metadata().description = Some(
+            "Permission config: a record of tool name -> permission rule. Each rule can be a simple action string (\"ask\"/\"allow\"/\"deny\") or a record of sub-keys to actions.".to_string(),
+        );
+        schema.into()
+    }
+}
+
 /// A permission rule: either a simple action or a map of sub-keys to actions.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum PermissionRule {
     Action(PermissionAction),
@@ -628,7 +699,7 @@ pub enum PermissionRule {
 }
 
 /// Permission action: "ask", "allow", or "deny".
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum PermissionAction {
     Ask,
@@ -636,7 +707,7 @@ pub enum PermissionAction {
     Deny,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct EnterpriseConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,
@@ -644,7 +715,7 @@ pub struct EnterpriseConfig {
     pub managed_config_dir: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct CompactionConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub auto: Option<bool>,
@@ -654,7 +725,107 @@ pub struct CompactionConfig {
     pub reserved: Option<u64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Embedding model used by the local semantic code index.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct EmbeddingConfig {
+    /// `provider/model`, e.g. `openai/text-embedding-3-small` or
+    /// `ollama/nomic-embed-text`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// OpenAI-compatible endpoint; defaults to the provider's known URL.
+    #[serde(alias = "baseURL", skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    #[serde(alias = "apiKey", skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dimensions: Option<u32>,
+    #[serde(alias = "batchSize", skip_serializing_if = "Option::is_none")]
+    pub batch_size: Option<usize>,
+    /// Extra glob patterns excluded from indexing, on top of `.gitignore`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignore: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled: Option<bool>,
+}
+
+/// Model pipeline for background work: session and message titles, commit
+/// messages and, when enabled, compaction.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct UtilityConfig {
+    /// `provider/model`, e.g. `ollama/qwen2.5:3b`. Defaults to `small_model`,
+    /// then to the session's own model.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Keep conversation text away from any extra model call: titles and
+    /// commit messages use local heuristics and compaction stays on the
+    /// session model.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled: Option<bool>,
+    /// Per-call timeout in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// Maximum utility calls in flight at once.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub concurrency: Option<usize>,
+    /// Reuse results for identical inputs. Defaults to `true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache: Option<bool>,
+    /// Per-task switches for `title`, `summary`, `commit` and `compaction`.
+    /// Compaction is off unless enabled here.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tasks: HashMap<String, bool>,
+}
+
+/// Backend used by the `websearch` tool. Defaults to Exa when unset.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+pub struct WebSearchConfig {
+    /// `exa`, `searxng`, `brave`, `tavily`, `http` or `mcp`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+    /// Instance URL for `searxng`, URL template for `http`, or an override of
+    /// the hosted endpoint for the other backends.
+    #[serde(alias = "baseURL", skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    #[serde(alias = "apiKey", skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+    /// Maximum requests per minute sent to the backend.
+    #[serde(alias = "rateLimit", skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<u32>,
+    /// Request timeout in milliseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// `http` backend: `GET` (default) or `POST`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub method: Option<String>,
+    /// `http` backend: extra headers; values may use `{query}`, `{limit}`
+    /// and `{apiKey}` placeholders.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// `http` backend: JSON body template for `POST` requests.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<serde_json::Value>,
+    /// `http` backend: dotted path to the result array, e.g. `data.items`.
+    #[serde(alias = "resultsPath", skip_serializing_if = "Option::is_none")]
+    pub results_path: Option<String>,
+    /// `http` backend: maps `title`, `url`, `snippet` and `date` to field
+    /// names in each result object.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub fields: HashMap<String, String>,
+    /// `mcp` backend: configured MCP server name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server: Option<String>,
+    /// `mcp` backend: tool name on that server.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    /// `mcp` backend: argument that receives the query (default `query`).
+    #[serde(alias = "queryParam", skip_serializing_if = "Option::is_none")]
+    pub query_param: Option<String>,
+    /// `mcp` backend: argument that receives the result count, if any.
+    #[serde(alias = "limitParam", skip_serializing_if = "Option::is_none")]
+    pub limit_param: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct ExperimentalConfig {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub disable_paste_summary: Option<bool>,
@@ -668,6 +839,9 @@ pub struct ExperimentalConfig {
     pub continue_loop_on_deny: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mcp_timeout: Option<u64>,
+    /// Maximum number of background `task` subagents running at once.
+    #[serde(alias = "maxBackgroundTasks", skip_serializing_if = "Option::is_none")]
+    pub max_background_tasks: Option<usize>,
 }
 
 trait DeepMerge {
@@ -920,6 +1094,7 @@ impl DeepMerge for KeybindsConfig {
         merge_option_replace(&mut self.terminal_title_toggle, other.terminal_title_toggle);
         merge_option_replace(&mut self.tips_toggle, other.tips_toggle);
         merge_option_replace(&mut self.display_thinking, other.display_thinking);
+        merge_option_replace(&mut self.vim_normal_mode, other.vim_normal_mode);
         // Legacy fields
         merge_option_replace(&mut self.submit, other.submit);
         merge_option_replace(&mut self.cancel, other.cancel);
@@ -934,6 +1109,7 @@ impl DeepMerge for TuiConfig {
         merge_option_replace(&mut self.scroll_speed, other.scroll_speed);
         merge_option_replace(&mut self.scroll_acceleration, other.scroll_acceleration);
         merge_option_replace(&mut self.diff_style, other.diff_style);
+        merge_option_replace(&mut self.vim, other.vim);
     }
 }
 
@@ -994,6 +1170,10 @@ impl DeepMerge for AgentConfig {
         merge_option_replace(&mut self.steps, other.steps);
         merge_option_replace(&mut self.max_tokens, other.max_tokens);
         merge_option_replace(&mut self.max_steps, other.max_steps);
+        merge_option_replace(&mut self.fallback, other.fallback);
+        merge_option_replace(&mut self.tool_choice, other.tool_choice);
+        merge_option_replace(&mut self.stop, other.stop);
+        merge_option_replace(&mut self.parallel_tool_calls, other.parallel_tool_calls);
         merge_option_deep(&mut self.permission, other.permission);
         merge_option_map_overwrite_values(&mut self.tools, other.tools);
     }
@@ -1038,6 +1218,7 @@ impl DeepMerge for ProviderConfig {
         if !other.blacklist.is_empty() {
             self.blacklist = other.blacklist;
         }
+        merge_option_replace(&mut self.rate_limit, other.rate_limit);
     }
 }
 
@@ -1163,6 +1344,48 @@ impl DeepMerge for CompactionConfig {
     }
 }
 
+impl DeepMerge for EmbeddingConfig {
+    fn deep_merge(&mut self, other: Self) {
+        merge_option_replace(&mut self.model, other.model);
+        merge_option_replace(&mut self.base_url, other.base_url);
+        merge_option_replace(&mut self.api_key, other.api_key);
+        merge_option_replace(&mut self.dimensions, other.dimensions);
+        merge_option_replace(&mut self.batch_size, other.batch_size);
+        append_unique_keep_order(&mut self.ignore, other.ignore);
+        merge_option_replace(&mut self.disabled, other.disabled);
+    }
+}
+
+impl DeepMerge for UtilityConfig {
+    fn deep_merge(&mut self, other: Self) {
+        merge_option_replace(&mut self.model, other.model);
+        merge_option_replace(&mut self.disabled, other.disabled);
+        merge_option_replace(&mut self.timeout, other.timeout);
+        merge_option_replace(&mut self.concurrency, other.concurrency);
+        merge_option_replace(&mut self.cache, other.cache);
+        self.tasks.extend(other.tasks);
+    }
+}
+
+impl DeepMerge for WebSearchConfig {
+    fn deep_merge(&mut self, other: Self) {
+        merge_option_replace(&mut self.backend, other.backend);
+        merge_option_replace(&mut self.base_url, other.base_url);
+        merge_option_replace(&mut self.api_key, other.api_key);
+        merge_option_replace(&mut self.rate_limit, other.rate_limit);
+        merge_option_replace(&mut self.timeout, other.timeout);
+        merge_option_replace(&mut self.method, other.method);
+        self.headers.extend(other.headers);
+        merge_option_replace(&mut self.body, other.body);
+        merge_option_replace(&mut self.results_path, other.results_path);
+        self.fields.extend(other.fields);
+        merge_option_replace(&mut self.server, other.server);
+        merge_option_replace(&mut self.tool, other.tool);
+        merge_option_replace(&mut self.query_param, other.query_param);
+        merge_option_replace(&mut self.limit_param, other.limit_param);
+    }
+}
+
 impl DeepMerge for ExperimentalConfig {
     fn deep_merge(&mut self, other: Self) {
         merge_option_replace(&mut self.disable_paste_summary, other.disable_paste_summary);
@@ -1173,6 +1396,7 @@ impl DeepMerge for ExperimentalConfig {
         }
         merge_option_replace(&mut self.continue_loop_on_deny, other.continue_loop_on_deny);
         merge_option_replace(&mut self.mcp_timeout, other.mcp_timeout);
+        merge_option_replace(&mut self.max_background_tasks, other.max_background_tasks);
     }
 }
 
@@ -1193,6 +1417,7 @@ impl Config {
         merge_option_replace(&mut self.autoupdate, other.autoupdate);
         merge_option_replace(&mut self.model, other.model);
         merge_option_replace(&mut self.small_model, other.small_model);
+        merge_option_deep(&mut self.utility, other.utility);
         merge_option_replace(&mut self.default_agent, other.default_agent);
         merge_option_replace(&mut self.username, other.username);
         merge_option_deep(&mut self.mode, other.mode);
@@ -1206,6 +1431,8 @@ impl Config {
         merge_option_map_overwrite_values(&mut self.tools, other.tools);
         merge_option_deep(&mut self.enterprise, other.enterprise);
         merge_option_deep(&mut self.compaction, other.compaction);
+        merge_option_deep(&mut self.embedding, other.embedding);
+        merge_option_deep(&mut self.websearch, other.websearch);
         merge_option_deep(&mut self.experimental, other.experimental);
         merge_option_map_overwrite_values(&mut self.env, other.env);
 
@@ -1431,4 +1658,29 @@ mod tests {
         assert_eq!(options.get("b"), Some(&serde_json::json!(2)));
         assert!(agents.contains_key("research"));
     }
+
+    #[test]
+    fn project_utility_overlay_keeps_global_model_and_merges_tasks() {
+        let mut global = Config {
+            utility: Some(UtilityConfig {
+                model: Some("ollama/qwen2.5:3b".to_string()),
+                tasks: HashMap::from([("compaction".to_string(), true)]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let project: Config = serde_json::from_value(serde_json::json!({
+            "utility": { "disabled": true, "tasks": { "commit": false } }
+        }))
+        .unwrap();
+
+        global.merge(project);
+
+        let utility = global.utility.unwrap();
+        assert_eq!(utility.model.as_deref(), Some("ollama/qwen2.5:3b"));
+        assert_eq!(utility.disabled, Some(true));
+        assert_eq!(utility.tasks.get("compaction"), Some(&true));
+        assert_eq!(utility.tasks.get("commit"), Some(&false));
+    }
 }
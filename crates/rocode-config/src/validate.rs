@@ -0,0 +1,669 @@
+//! Validation of config sources with file/line/column diagnostics.
+//!
+//! Loading is deliberately lenient: unknown keys are ignored and mistyped
+//! markdown frontmatter falls back to defaults. This pass reports what the
+//! loader would silently drop.
+
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use jsonc_parser::ast::{ObjectPropName, Value as AstValue};
+use jsonc_parser::common::Ranged;
+use jsonc_parser::{parse_to_ast, CollectOptions, ParseOptions};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::loader::{
+    collect_opencode_directories, detect_worktree_stop, find_up, get_global_config_path,
+    get_managed_config_dir, glob_md_files, normalize_existing_path, parse_yaml_mapping,
+    serde_yaml_frontmatter_to_json, split_frontmatter,
+};
+use crate::schema::{AgentConfig, CommandConfig, Config, McpServer, PermissionAction};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticKind {
+    Syntax,
+    UnknownKey,
+    TypeMismatch,
+    InvalidValue,
+    UnresolvedReference,
+    InvalidModel,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub kind: DiagnosticKind,
+    pub file: PathBuf,
+    /// 1-based.
+    pub line: usize,
+    /// 1-based, in characters.
+    pub column: usize,
+    /// Dotted config path the diagnostic refers to, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(
+            f,
+            "{}:{}:{}: {}: {}",
+            self.file.display(),
+            self.line,
+            self.column,
+            severity,
+            self.message
+        )
+    }
+}
+
+/// A problem found in a deserialized value, before it is mapped to a
+/// position in the source text.
+struct Issue {
+    severity: Severity,
+    kind: DiagnosticKind,
+    path: Vec<String>,
+    message: String,
+}
+
+/// Which schema a markdown file's frontmatter is checked against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkdownKind {
+    Agent,
+    Command,
+}
+
+/// Validates every config source that `load_config(project_dir)` reads.
+pub fn validate_config_sources<P: AsRef<Path>>(project_dir: P) -> Vec<Diagnostic> {
+    let project_dir = project_dir.as_ref();
+    let mut diagnostics = Vec::new();
+
+    for path in config_json_files(project_dir) {
+        match fs::read_to_string(&path) {
+            Ok(text) => diagnostics.extend(validate_config_text(&text, &path)),
+            Err(e) => diagnostics.push(Diagnostic {
+                severity: Severity::Error,
+                kind: DiagnosticKind::Syntax,
+                file: path.clone(),
+                line: 1,
+                column: 1,
+                key: None,
+                message: format!("cannot read file: {}", e),
+            }),
+        }
+    }
+
+    if let Ok(content) = env::var("OPENCODE_CONFIG_CONTENT") {
+        diagnostics.extend(validate_config_text(
+            &content,
+            Path::new("<OPENCODE_CONFIG_CONTENT>"),
+        ));
+    }
+
+    for dir in collect_opencode_directories(project_dir) {
+        for (subdir, kind) in [
+            ("agent", MarkdownKind::Agent),
+            ("agents", MarkdownKind::Agent),
+            ("mode", MarkdownKind::Agent),
+            ("modes", MarkdownKind::Agent),
+            ("command", MarkdownKind::Command),
+            ("commands", MarkdownKind::Command),
+        ] {
+            for path in glob_md_files(&dir.join(subdir)).unwrap_or_default() {
+                if let Ok(text) = fs::read_to_string(&path) {
+                    diagnostics.extend(validate_markdown_text(&text, &path, kind));
+                }
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// JSON config files in load order; mirrors `ConfigLoader::load_all`.
+fn config_json_files(project_dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+
+    let global = get_global_config_path();
+    if let Some(path) = ["jsonc", "json"]
+        .iter()
+        .map(|ext| global.with_extension(ext))
+        .find(|p| p.exists())
+    {
+        files.push(path);
+    }
+    if let Ok(path) = env::var("OPENCODE_CONFIG") {
+        files.push(PathBuf::from(path));
+    }
+
+    let start_dir = normalize_existing_path(project_dir);
+    let stop_dir = detect_worktree_stop(&start_dir);
+    for target in [
+        "opencode.jsonc",
+        "opencode.json",
+        ".opencode/opencode.jsonc",
+        ".opencode/opencode.json",
+    ] {
+        files.extend(find_up(target, &start_dir, &stop_dir).into_iter().rev());
+    }
+    for dir in collect_opencode_directories(project_dir) {
+        files.push(dir.join("opencode.jsonc"));
+        files.push(dir.join("opencode.json"));
+    }
+    let managed = get_managed_config_dir();
+    files.push(managed.join("opencode.jsonc"));
+    files.push(managed.join("opencode.json"));
+
+    let mut seen = std::collections::HashSet::new();
+    files.retain(|p| p.is_file() && seen.insert(normalize_existing_path(p)));
+    files
+}
+
+/// Validates the text of one `opencode.json{,c}` file.
+pub fn validate_config_text(text: &str, file: &Path) -> Vec<Diagnostic> {
+    let parse_options = ParseOptions {
+        allow_trailing_commas: true,
+        ..Default::default()
+    };
+    let ast = match parse_to_ast(text, &CollectOptions::default(), &parse_options) {
+        Ok(result) => result.value,
+        Err(e) => {
+            let (line, column) = line_col(text, e.range().start);
+            return vec![Diagnostic {
+                severity: Severity::Error,
+                kind: DiagnosticKind::Syntax,
+                file: file.to_path_buf(),
+                line,
+                column,
+                key: None,
+                message: e.kind().to_string(),
+            }];
+        }
+    };
+    let Some(ast) = ast else {
+        return Vec::new();
+    };
+    let value = ast_to_json(&ast);
+
+    let mut issues = Vec::new();
+    match &value {
+        Value::Object(root) => check_config_object(root, &mut issues),
+        _ => issues.push(Issue {
+            severity: Severity::Error,
+            kind: DiagnosticKind::TypeMismatch,
+            path: Vec::new(),
+            message: "config must be a JSON object".to_string(),
+        }),
+    }
+
+    let mut diagnostics: Vec<Diagnostic> = issues
+        .into_iter()
+        .map(|issue| {
+            let offset = locate(&ast, &issue.path);
+            let (line, column) = line_col(text, offset);
+            Diagnostic {
+                severity: issue.severity,
+                kind: issue.kind,
+                file: file.to_path_buf(),
+                line,
+                column,
+                key: (!issue.path.is_empty()).then(|| issue.path.join(".")),
+                message: issue.message,
+            }
+        })
+        .collect();
+    diagnostics.extend(check_references(text, file));
+    diagnostics.sort_by_key(|d| (d.line, d.column));
+    diagnostics
+}
+
+/// Validates the YAML frontmatter of an agent, mode or command markdown file.
+pub fn validate_markdown_text(text: &str, file: &Path, kind: MarkdownKind) -> Vec<Diagnostic> {
+    let (frontmatter, _) = split_frontmatter(text);
+    let Some(frontmatter) = frontmatter else {
+        return Vec::new();
+    };
+    let first_line = text
+        .lines()
+        .position(|line| line.trim_start().starts_with("---"))
+        .map(|i| i + 2)
+        .unwrap_or(1);
+
+    let mut issues = Vec::new();
+    if parse_yaml_mapping(&frontmatter).is_none() {
+        issues.push(Issue {
+            severity: Severity::Warning,
+            kind: DiagnosticKind::Syntax,
+            path: Vec::new(),
+            message: "frontmatter is not valid YAML; values containing ':' were read as plain text"
+                .to_string(),
+        });
+    }
+    let value = serde_yaml_frontmatter_to_json(&frontmatter);
+    match kind {
+        MarkdownKind::Agent => {
+            check_typed::<AgentConfig>(&value, &[], &mut issues);
+            check_model_field(&value, &[], &mut issues);
+        }
+        MarkdownKind::Command => {
+            check_typed::<CommandConfig>(&value, &[], &mut issues);
+            check_model_field(&value, &[], &mut issues);
+        }
+    }
+
+    let lines: Vec<&str> = frontmatter.lines().collect();
+    issues
+        .into_iter()
+        .map(|issue| {
+            let (line, column) = issue
+                .path
+                .first()
+                .and_then(|key| {
+                    lines.iter().enumerate().find_map(|(i, line)| {
+                        let rest = line.strip_prefix(key.as_str())?;
+                        rest.trim_start()
+                            .starts_with(':')
+                            .then_some((first_line + i, 1))
+                    })
+                })
+                .unwrap_or((first_line, 1));
+            Diagnostic {
+                severity: issue.severity,
+                kind: issue.kind,
+                file: file.to_path_buf(),
+                line,
+                column,
+                key: (!issue.path.is_empty()).then(|| issue.path.join(".")),
+                message: issue.message,
+            }
+        })
+        .collect()
+}
+
+fn check_config_object(root: &serde_json::Map<String, Value>, issues: &mut Vec<Issue>) {
+    for (key, value) in root {
+        let path = [key.clone()];
+        match key.as_str() {
+            // Keyed by user-chosen names; flattened maps hide unknown keys
+            // from serde, so each entry is checked on its own.
+            "agent" | "mode" => for_each_entry(value, &path, issues, |entry, path, issues| {
+                check_typed::<AgentConfig>(entry, path, issues);
+                check_model_field(entry, path, issues);
+            }),
+            "command" => for_each_entry(value, &path, issues, |entry, path, issues| {
+                check_typed::<CommandConfig>(entry, path, issues);
+                check_model_field(entry, path, issues);
+            }),
+            "mcp" => for_each_entry(value, &path, issues, |entry, path, issues| {
+                let only_enabled = entry
+                    .as_object()
+                    .is_some_and(|o| o.len() == 1 && o.contains_key("enabled"));
+                if !only_enabled {
+                    check_typed::<McpServer>(entry, path, issues);
+                }
+            }),
+            "permission" => check_permission(value, &path, issues),
+            _ => {
+                let mut single = serde_json::Map::new();
+                single.insert(key.clone(), value.clone());
+                check_typed::<Config>(&Value::Object(single), &[], issues);
+                if matches!(key.as_str(), "model" | "small_model") {
+                    check_model(value, &path, issues);
+                }
+            }
+        }
+    }
+}
+
+fn for_each_entry(
+    value: &Value,
+    path: &[String],
+    issues: &mut Vec<Issue>,
+    check: impl Fn(&Value, &[String], &mut Vec<Issue>),
+) {
+    let Some(entries) = value.as_object() else {
+        issues.push(type_issue(path, "expected an object"));
+        return;
+    };
+    for (name, entry) in entries {
+        let mut entry_path = path.to_vec();
+        entry_path.push(name.clone());
+        check(entry, &entry_path, issues);
+    }
+}
+
+fn check_permission(value: &Value, path: &[String], issues: &mut Vec<Issue>) {
+    let check_action = |action: &Value, path: &[String], issues: &mut Vec<Issue>| match action {
+        Value::String(s) => {
+            if serde_json::from_value::<PermissionAction>(action.clone()).is_err() {
+                issues.push(Issue {
+                    severity: Severity::Error,
+                    kind: DiagnosticKind::InvalidValue,
+                    path: path.to_vec(),
+                    message: format!(
+                        "invalid permission `{}`, expected one of `ask`, `allow`, `deny`",
+                        s
+                    ),
+                });
+            }
+        }
+        _ => issues.push(type_issue(path, "expected `ask`, `allow` or `deny`")),
+    };
+    for_each_entry(value, path, issues, |rule, path, issues| match rule {
+        Value::Object(patterns) => {
+            for (pattern, action) in patterns {
+                let mut action_path = path.to_vec();
+                action_path.push(pattern.clone());
+                check_action(action, &action_path, issues);
+            }
+        }
+        other => check_action(other, path, issues),
+    });
+}
+
+fn check_model_field(value: &Value, path: &[String], issues: &mut Vec<Issue>) {
+    if let Some(model) = value.get("model") {
+        let mut model_path = path.to_vec();
+        model_path.push("model".to_string());
+        check_model(model, &model_path, issues);
+    }
+}
+
+fn check_model(value: &Value, path: &[String], issues: &mut Vec<Issue>) {
+    let Some(model) = value.as_str() else {
+        return;
+    };
+    if model.contains("{env:") || model.contains("{file:") {
+        return;
+    }
+    let valid = model
+        .split_once('/')
+        .is_some_and(|(provider, id)| !provider.is_empty() && !id.is_empty());
+    if !valid {
+        issues.push(Issue {
+            severity: Severity::Error,
+            kind: DiagnosticKind::InvalidModel,
+            path: path.to_vec(),
+            message: format!(
+                "invalid model id `{}`, expected `provider/model` (e.g. `anthropic/claude-sonnet-4`)",
+                model
+            ),
+        });
+    }
+}
+
+/// Deserializes `value` as `T`, recording ignored keys and the first type
+/// error with their paths below `prefix`.
+fn check_typed<T: DeserializeOwned>(value: &Value, prefix: &[String], issues: &mut Vec<Issue>) {
+    let mut unknown = Vec::new();
+    let mut track = serde_path_to_error::Track::new();
+    let deserializer = serde_path_to_error::Deserializer::new(value, &mut track);
+    let result: Result<T, _> = serde_ignored::deserialize(deserializer, |path| {
+        unknown.push(path.to_string());
+    });
+
+    for path in unknown {
+        let path = join_path(prefix, &path);
+        issues.push(Issue {
+            severity: Severity::Warning,
+            kind: DiagnosticKind::UnknownKey,
+            message: format!("unknown key `{}`", path.last().cloned().unwrap_or_default()),
+            path,
+        });
+    }
+    if let Err(error) = result {
+        let path = join_path(prefix, &track.path().to_string());
+        let message = error.to_string();
+        let kind = if message.starts_with("unknown variant") {
+            DiagnosticKind::InvalidValue
+        } else {
+            DiagnosticKind::TypeMismatch
+        };
+        issues.push(Issue {
+            severity: Severity::Error,
+            kind,
+            path,
+            message,
+        });
+    }
+}
+
+fn join_path(prefix: &[String], path: &str) -> Vec<String> {
+    let mut joined = prefix.to_vec();
+    joined.extend(
+        path.split('.')
+            .filter(|segment| !segment.is_empty() && *segment != "?")
+            .map(|segment| segment.trim_matches(|c| c == '[' || c == ']').to_string()),
+    );
+    joined
+}
+
+fn type_issue(path: &[String], message: &str) -> Issue {
+    Issue {
+        severity: Severity::Error,
+        kind: DiagnosticKind::TypeMismatch,
+        path: path.to_vec(),
+        message: message.to_string(),
+    }
+}
+
+/// Reports `{env:VAR}` references to unset variables and `{file:path}`
+/// references to missing files.
+fn check_references(text: &str, file: &Path) -> Vec<Diagnostic> {
+    let base_dir = file.parent().unwrap_or(Path::new("."));
+    let re = regex::Regex::new(r"\{(env|file):([^}]+)\}").unwrap();
+    let mut diagnostics = Vec::new();
+
+    for caps in re.captures_iter(text) {
+        let whole = caps.get(0).unwrap();
+        let (line, column) = line_col(text, whole.start());
+        let line_text = text.lines().nth(line - 1).unwrap_or_default();
+        if line_text.trim_start().starts_with("//") {
+            continue;
+        }
+        let target = &caps[2];
+        let (severity, message) = if &caps[1] == "env" {
+            if env::var_os(target).is_some() {
+                continue;
+            }
+            (
+                Severity::Warning,
+                format!("environment variable `{}` is not set", target),
+            )
+        } else {
+            let resolved = if let Some(rest) = target.strip_prefix("~/") {
+                dirs::home_dir().unwrap_or_default().join(rest)
+            } else {
+                base_dir.join(target)
+            };
+            if resolved.is_file() {
+                continue;
+            }
+            (
+                Severity::Error,
+                format!("referenced file {} does not exist", resolved.display()),
+            )
+        };
+        diagnostics.push(Diagnostic {
+            severity,
+            kind: DiagnosticKind::UnresolvedReference,
+            file: file.to_path_buf(),
+            line,
+            column,
+            key: None,
+            message,
+        });
+    }
+    diagnostics
+}
+
+fn ast_to_json(value: &AstValue) -> Value {
+    match value {
+        AstValue::StringLit(s) => Value::String(s.value.to_string()),
+        AstValue::NumberLit(n) => serde_json::from_str(n.value).unwrap_or(Value::Null),
+        AstValue::BooleanLit(b) => Value::Bool(b.value),
+        AstValue::NullKeyword(_) => Value::Null,
+        AstValue::Array(array) => Value::Array(array.elements.iter().map(ast_to_json).collect()),
+        AstValue::Object(object) => Value::Object(
+            object
+                .properties
+                .iter()
+                .map(|prop| (prop_name(&prop.name).to_string(), ast_to_json(&prop.value)))
+                .collect(),
+        ),
+    }
+}
+
+fn prop_name<'a>(name: &'a ObjectPropName<'a>) -> &'a str {
+    match name {
+        ObjectPropName::String(s) => &s.value,
+        ObjectPropName::Word(w) => w.value,
+    }
+}
+
+/// Byte offset of the deepest property along `path`.
+fn locate(ast: &AstValue, path: &[String]) -> usize {
+    let mut current = ast;
+    let mut offset = ast.start();
+    for segment in path {
+        let next = match current {
+            AstValue::Object(object) => object
+                .properties
+                .iter()
+                .rev()
+                .find(|prop| prop_name(&prop.name) == segment)
+                .map(|prop| (prop.name.start(), &prop.value)),
+            AstValue::Array(array) => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| array.elements.get(i))
+                .map(|element| (element.start(), element)),
+            _ => None,
+        };
+        let Some((start, value)) = next else {
+            break;
+        };
+        offset = start;
+        current = value;
+    }
+    offset
+}
+
+fn line_col(text: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(text.len());
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(text: &str) -> Vec<Diagnostic> {
+        validate_config_text(text, Path::new("opencode.json"))
+    }
+
+    #[test]
+    fn reports_unknown_keys_with_position() {
+        let diagnostics = check(
+            "{\n  // comment\n  \"permision\": {\"bash\": \"ask\"},\n  \"theme\": \"dark\",\n}",
+        );
+        assert_eq!(diagnostics.len(), 1);
+        let d = &diagnostics[0];
+        assert_eq!(d.kind, DiagnosticKind::UnknownKey);
+        assert_eq!(d.severity, Severity::Warning);
+        assert_eq!((d.line, d.column), (3, 3));
+        assert_eq!(d.key.as_deref(), Some("permision"));
+        assert_eq!(
+            d.to_string(),
+            "opencode.json:3:3: warning: unknown key `permision`"
+        );
+    }
+
+    #[test]
+    fn reports_nested_type_errors_enums_and_models() {
+        let diagnostics = check(
+            r#"{
+  "model": "claude-sonnet",
+  "compaction": { "auto": "yes" },
+  "share": "sometimes",
+  "agent": { "build": { "temprature": 0.2, "model": "openai/gpt-4o" } },
+  "permission": { "bash": { "git *": "allow", "rm *": "never" } }
+}"#,
+        );
+        let summary: Vec<(DiagnosticKind, Option<&str>, usize)> = diagnostics
+            .iter()
+            .map(|d| (d.kind, d.key.as_deref(), d.line))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (DiagnosticKind::InvalidModel, Some("model"), 2),
+                (DiagnosticKind::TypeMismatch, Some("compaction.auto"), 3),
+                (DiagnosticKind::InvalidValue, Some("share"), 4),
+                (
+                    DiagnosticKind::UnknownKey,
+                    Some("agent.build.temprature"),
+                    5
+                ),
+                (
+                    DiagnosticKind::InvalidValue,
+                    Some("permission.bash.rm *"),
+                    6
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_syntax_errors_and_unresolved_references() {
+        let diagnostics = check("{\n  \"theme\": \"dark\",\n  \"model\": ]\n}");
+        assert_eq!(diagnostics[0].kind, DiagnosticKind::Syntax);
+        assert_eq!(diagnostics[0].line, 3);
+
+        let diagnostics = check(
+            "{\n  \"username\": \"{env:ROCODE_TEST_SURELY_UNSET_VAR}\",\n  \"theme\": \"{file:missing-theme.txt}\"\n}",
+        );
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics
+            .iter()
+            .all(|d| d.kind == DiagnosticKind::UnresolvedReference));
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[1].severity, Severity::Error);
+        assert_eq!((diagnostics[1].line, diagnostics[1].column), (3, 13));
+    }
+
+    #[test]
+    fn validates_markdown_frontmatter() {
+        let text = "---\ndescription: Reviews code\nmodle: openai/gpt-4o\nsteps: many\n---\nYou review code.\n";
+        let diagnostics = validate_markdown_text(text, Path::new("review.md"), MarkdownKind::Agent);
+        let summary: Vec<(DiagnosticKind, usize)> =
+            diagnostics.iter().map(|d| (d.kind, d.line)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (DiagnosticKind::UnknownKey, 3),
+                (DiagnosticKind::TypeMismatch, 4)
+            ]
+        );
+    }
+}
@@ -43,7 +43,7 @@ struct CacheEntry {
 
 static CACHE: Mutex<Option<HashMap<String, CacheEntry>>> = Mutex::new(None);
 /// Returns the path to `auth.json` inside the opencode data directory.
-fn auth_json_path() -> PathBuf {
+pub fn auth_json_path() -> PathBuf {
     let data_dir = dirs::data_dir()
         .unwrap_or_else(|| PathBuf::from("~/.local/share"))
         .join("opencode");
@@ -53,12 +53,17 @@ fn auth_json_path() -> PathBuf {
 /// Reads `auth.json` and returns only the wellknown entries (url -> WellKnownAuth).
 fn read_wellknown_entries() -> HashMap<String, WellKnownAuth> {
     let path = auth_json_path();
-    let content = match std::fs::read_to_string(&path) {
-        Ok(c) => c,
-        Err(_) => return HashMap::new(),
+    let content = match rocode_core::credentials::backend().and_then(|backend| backend.read(&path))
+    {
+        Ok(Some(c)) => c,
+        Ok(None) => return HashMap::new(),
+        Err(error) => {
+            tracing::warn!(%error, "failed to read wellknown auth entries");
+            return HashMap::new();
+        }
     };
 
-    let raw: HashMap<String, serde_json::Value> = match serde_json::from_str(&content) {
+    let raw: HashMap<String, serde_json::Value> = match serde_json::from_slice(&content) {
         Ok(v) => v,
         Err(_) => return HashMap::new(),
     };
@@ -0,0 +1,621 @@
+//! Pluggable storage for credential files (`auth.json`, `mcp-auth.json`).
+//!
+//! Every component that persists secrets — provider auth, MCP OAuth tokens and
+//! the plugin auth bridges feeding the provider store — reads and writes
+//! through the process-wide [`backend`]. By default that is a plaintext file
+//! backend; setting `OPENCODE_CREDENTIAL_PASSPHRASE` or
+//! `OPENCODE_CREDENTIAL_KEY_FILE` switches to [`EncryptedFileBackend`], which
+//! keeps the files sealed with AES-256-GCM.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use once_cell::sync::OnceCell;
+use parking_lot::Mutex;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+
+pub const PASSPHRASE_ENV: &str = "OPENCODE_CREDENTIAL_PASSPHRASE";
+pub const KEY_FILE_ENV: &str = "OPENCODE_CREDENTIAL_KEY_FILE";
+
+/// PBKDF2 rounds for passphrase-derived keys.
+pub const PASSPHRASE_ITERATIONS: u32 = 600_000;
+/// Upper bound on the round count accepted from an envelope, so a tampered
+/// store cannot stall the process in key derivation.
+const MAX_ITERATIONS: u32 = 10 * PASSPHRASE_ITERATIONS;
+/// Key files already carry full entropy, so a single round is enough.
+const KEY_FILE_ITERATIONS: u32 = 1;
+const ENVELOPE_VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CredentialError {
+    #[error("credential store I/O error at {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error(
+        "credential store {0} is encrypted; set {PASSPHRASE_ENV} or {KEY_FILE_ENV} to unlock it"
+    )]
+    Locked(PathBuf),
+
+    #[error("failed to decrypt credential store {0}: wrong passphrase or key file, or the file is corrupt")]
+    Decrypt(PathBuf),
+
+    #[error("invalid credential store {path}: {message}")]
+    Format { path: PathBuf, message: String },
+
+    #[error("invalid credential key: {0}")]
+    Key(String),
+}
+
+/// Storage backend for credential documents.
+///
+/// Callers hand over and receive the plaintext document; how it is kept on
+/// disk is up to the backend.
+pub trait CredentialBackend: Send + Sync {
+    /// Short name shown in diagnostics, e.g. `plaintext` or `encrypted`.
+    fn kind(&self) -> &'static str;
+
+    /// Whether documents written by this backend are encrypted at rest.
+    fn encrypts(&self) -> bool;
+
+    /// Reads the document at `path`, or `None` when it does not exist.
+    fn read(&self, path: &Path) -> Result<Option<Vec<u8>>, CredentialError>;
+
+    /// Replaces the document at `path`, creating parent directories as needed.
+    fn write(&self, path: &Path, contents: &[u8]) -> Result<(), CredentialError>;
+}
+
+/// Stores documents as-is, the historical `auth.json` layout.
+#[derive(Debug, Default)]
+pub struct PlaintextFileBackend;
+
+impl CredentialBackend for PlaintextFileBackend {
+    fn kind(&self) -> &'static str {
+        "plaintext"
+    }
+
+    fn encrypts(&self) -> bool {
+        false
+    }
+
+    fn read(&self, path: &Path) -> Result<Option<Vec<u8>>, CredentialError> {
+        let Some(raw) = read_raw(path)? else {
+            return Ok(None);
+        };
+        if is_encrypted(&raw) {
+            return Err(CredentialError::Locked(path.to_path_buf()));
+        }
+        Ok(Some(raw))
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> Result<(), CredentialError> {
+        write_private(path, contents)
+    }
+}
+
+/// On-disk wrapper around an encrypted document.
+#[derive(Debug, Serialize, Deserialize)]
+struct Envelope {
+    rocode_credentials: u32,
+    kdf: String,
+    iterations: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+enum Secret {
+    Passphrase(String),
+    KeyFile(Vec<u8>),
+}
+
+/// Seals documents with AES-256-GCM under a key derived (PBKDF2-HMAC-SHA256)
+/// from a passphrase or the contents of a key file.
+///
+/// Derived keys are cached per salt and round count, so the expensive derivation runs once per
+/// process no matter how often the stores are read or rewritten.
+pub struct EncryptedFileBackend {
+    secret: Secret,
+    iterations: u32,
+    rng: SystemRandom,
+    /// Salt used for new writes, shared with the first store that was unlocked.
+    write_salt: Mutex<Option<Vec<u8>>>,
+    keys: Mutex<HashMap<(Vec<u8>, u32), [u8; KEY_LEN]>>,
+}
+
+impl EncryptedFileBackend {
+    pub fn from_passphrase(passphrase: impl Into<String>) -> Result<Self, CredentialError> {
+        let passphrase = passphrase.into();
+        if passphrase.is_empty() {
+            return Err(CredentialError::Key("passphrase is empty".to_string()));
+        }
+        Ok(Self::new(
+            Secret::Passphrase(passphrase),
+            PASSPHRASE_ITERATIONS,
+        ))
+    }
+
+    pub fn from_key_file(path: &Path) -> Result<Self, CredentialError> {
+        let bytes = std::fs::read(path).map_err(|source| CredentialError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let trimmed = bytes.trim_ascii();
+        if trimmed.len() < KEY_LEN {
+            return Err(CredentialError::Key(format!(
+                "key file {} must contain at least {KEY_LEN} bytes",
+                path.display()
+            )));
+        }
+        Ok(Self::new(
+            Secret::KeyFile(trimmed.to_vec()),
+            KEY_FILE_ITERATIONS,
+        ))
+    }
+
+    /// Overrides the PBKDF2 round count for passphrase keys.
+    pub fn with_iterations(mut self, iterations: u32) -> Self {
+        if matches!(self.secret, Secret::Passphrase(_)) {
+            self.iterations = iterations.max(1);
+        }
+        self
+    }
+
+    fn new(secret: Secret, iterations: u32) -> Self {
+        Self {
+            secret,
+            iterations,
+            rng: SystemRandom::new(),
+            write_salt: Mutex::new(None),
+            keys: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn kdf_name(&self) -> &'static str {
+        match self.secret {
+            Secret::Passphrase(_) => "pbkdf2-sha256",
+            Secret::KeyFile(_) => "key-file",
+        }
+    }
+
+    fn key_for(&self, salt: &[u8], iterations: u32) -> [u8; KEY_LEN] {
+        let mut keys = self.keys.lock();
+        *keys.entry((salt.to_vec(), iterations)).or_insert_with(|| {
+            let secret = match &self.secret {
+                Secret::Passphrase(passphrase) => passphrase.as_bytes(),
+                Secret::KeyFile(bytes) => bytes.as_slice(),
+            };
+            let mut key = [0u8; KEY_LEN];
+            pbkdf2::derive(
+                pbkdf2::PBKDF2_HMAC_SHA256,
+                std::num::NonZeroU32::new(iterations.max(1)).expect("non-zero iterations"),
+                salt,
+                secret,
+                &mut key,
+            );
+            key
+        })
+    }
+
+    fn sealing_key(key: &[u8; KEY_LEN]) -> LessSafeKey {
+        LessSafeKey::new(UnboundKey::new(&AES_256_GCM, key).expect("AES-256 key length"))
+    }
+
+    fn decrypt(&self, path: &Path, raw: &[u8]) -> Result<Vec<u8>, CredentialError> {
+        let format_error = |message: &str| CredentialError::Format {
+            path: path.to_path_buf(),
+            message: message.to_string(),
+        };
+        let envelope: Envelope = serde_json::from_slice(raw)
+            .map_err(|error| format_error(&format!("malformed envelope: {error}")))?;
+        if envelope.rocode_credentials != ENVELOPE_VERSION {
+            return Err(format_error(&format!(
+                "unsupported envelope version {}",
+                envelope.rocode_credentials
+            )));
+        }
+        if envelope.kdf != self.kdf_name() {
+            return Err(format_error(&format!(
+                "store was sealed with `{}`, but a {} was provided",
+                envelope.kdf,
+                match self.secret {
+                    Secret::Passphrase(_) => "passphrase",
+                    Secret::KeyFile(_) => "key file",
+                }
+            )));
+        }
+        if envelope.iterations > MAX_ITERATIONS {
+            return Err(format_error(&format!(
+                "iteration count {} exceeds the limit of {MAX_ITERATIONS}",
+                envelope.iterations
+            )));
+        }
+        let salt = hex::decode(&envelope.salt).map_err(|_| format_error("invalid salt"))?;
+        let nonce = hex::decode(&envelope.nonce).map_err(|_| format_error("invalid nonce"))?;
+        let nonce = Nonce::try_assume_unique_for_key(&nonce)
+            .map_err(|_| format_error("invalid nonce length"))?;
+        let mut buffer =
+            hex::decode(&envelope.ciphertext).map_err(|_| format_error("invalid ciphertext"))?;
+
+        let key = self.key_for(&salt, envelope.iterations);
+        let plaintext = Self::sealing_key(&key)
+            .open_in_place(nonce, Aad::from(path_aad(path)), &mut buffer)
+            .map_err(|_| CredentialError::Decrypt(path.to_path_buf()))?;
+        let plaintext = plaintext.to_vec();
+
+        self.write_salt.lock().get_or_insert(salt);
+        Ok(plaintext)
+    }
+
+    fn encrypt(&self, path: &Path, contents: &[u8]) -> Result<Vec<u8>, CredentialError> {
+        let salt = {
+            let mut write_salt = self.write_salt.lock();
+            match write_salt.as_ref() {
+                Some(salt) => salt.clone(),
+                None => {
+                    let mut salt = vec![0u8; SALT_LEN];
+                    self.fill_random(&mut salt)?;
+                    *write_salt = Some(salt.clone());
+                    salt
+                }
+            }
+        };
+        let mut nonce = [0u8; NONCE_LEN];
+        self.fill_random(&mut nonce)?;
+
+        let key = self.key_for(&salt, self.iterations);
+        let mut buffer = contents.to_vec();
+        Self::sealing_key(&key)
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(path_aad(path)),
+                &mut buffer,
+            )
+            .map_err(|_| CredentialError::Key("encryption failed".to_string()))?;
+
+        let envelope = Envelope {
+            rocode_credentials: ENVELOPE_VERSION,
+            kdf: self.kdf_name().to_string(),
+            iterations: self.iterations,
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(buffer),
+        };
+        serde_json::to_vec_pretty(&envelope).map_err(|error| CredentialError::Format {
+            path: path.to_path_buf(),
+            message: error.to_string(),
+        })
+    }
+
+    fn fill_random(&self, buffer: &mut [u8]) -> Result<(), CredentialError> {
+        self.rng
+            .fill(buffer)
+            .map_err(|_| CredentialError::Key("system random source unavailable".to_string()))
+    }
+}
+
+impl CredentialBackend for EncryptedFileBackend {
+    fn kind(&self) -> &'static str {
+        "encrypted"
+    }
+
+    fn encrypts(&self) -> bool {
+        true
+    }
+
+    /// Plaintext stores are still readable so existing installs keep working;
+    /// they are sealed on the next write or by an explicit migration.
+    fn read(&self, path: &Path) -> Result<Option<Vec<u8>>, CredentialError> {
+        let Some(raw) = read_raw(path)? else {
+            return Ok(None);
+        };
+        if !is_encrypted(&raw) {
+            return Ok(Some(raw));
+        }
+        self.decrypt(path, &raw).map(Some)
+    }
+
+    fn write(&self, path: &Path, contents: &[u8]) -> Result<(), CredentialError> {
+        let sealed = self.encrypt(path, contents)?;
+        write_private(path, &sealed)
+    }
+}
+
+/// Binds the ciphertext to the store's file name so sealed stores cannot be
+/// swapped for one another.
+fn path_aad(path: &Path) -> Vec<u8> {
+    path.file_name()
+        .map(|name| name.to_string_lossy().as_bytes().to_vec())
+        .unwrap_or_default()
+}
+
+/// Returns true when `raw` is an envelope written by [`EncryptedFileBackend`].
+pub fn is_encrypted(raw: &[u8]) -> bool {
+    serde_json::from_slice::<serde_json::Value>(raw)
+        .ok()
+        .and_then(|value| value.get("rocode_credentials").cloned())
+        .is_some()
+}
+
+fn read_raw(path: &Path) -> Result<Option<Vec<u8>>, CredentialError> {
+    match std::fs::read(path) {
+        Ok(raw) => Ok(Some(raw)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(source) => Err(CredentialError::Io {
+            path: path.to_path_buf(),
+            source,
+        }),
+    }
+}
+
+/// Writes through a private temporary file renamed over `path`, so a crash or
+/// full disk never leaves a truncated store behind.
+fn write_private(path: &Path, contents: &[u8]) -> Result<(), CredentialError> {
+    use std::io::Write;
+
+    let io_error = |source| CredentialError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let temp = path.with_file_name(format!(".{file_name}.{}.tmp", uuid::Uuid::new_v4()));
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let written = options.open(&temp).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    });
+    if let Err(source) = written.and_then(|()| std::fs::rename(&temp, path)) {
+        let _ = std::fs::remove_file(&temp);
+        return Err(io_error(source));
+    }
+    Ok(())
+}
+
+static BACKEND: OnceCell<Arc<dyn CredentialBackend>> = OnceCell::new();
+
+/// Returns the process-wide credential backend, choosing it from the
+/// environment on first use. A key file takes precedence over a passphrase.
+///
+/// A configured key that cannot be loaded is an error rather than a reason to
+/// fall back to plaintext, which would write secrets unsealed.
+pub fn backend() -> Result<Arc<dyn CredentialBackend>, CredentialError> {
+    if let Some(backend) = BACKEND.get() {
+        return Ok(backend.clone());
+    }
+    let backend = backend_from_env()?;
+    Ok(BACKEND.get_or_init(|| backend).clone())
+}
+
+/// Installs `backend` as the process-wide backend. Returns false when a
+/// backend was already selected.
+pub fn install_backend(backend: Arc<dyn CredentialBackend>) -> bool {
+    BACKEND.set(backend).is_ok()
+}
+
+/// Builds the backend described by the credential environment variables.
+pub fn backend_from_env() -> Result<Arc<dyn CredentialBackend>, CredentialError> {
+    if let Some(path) = non_empty_env(KEY_FILE_ENV) {
+        return Ok(Arc::new(EncryptedFileBackend::from_key_file(Path::new(
+            &path,
+        ))?));
+    }
+    if let Some(passphrase) = non_empty_env(PASSPHRASE_ENV) {
+        return Ok(Arc::new(EncryptedFileBackend::from_passphrase(passphrase)?));
+    }
+    Ok(Arc::new(PlaintextFileBackend))
+}
+
+fn non_empty_env(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .filter(|value| !value.trim().is_empty())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationOutcome {
+    Missing,
+    AlreadyEncrypted,
+    Migrated,
+}
+
+/// Re-seals a plaintext store at `path` with `backend`.
+pub fn migrate_file(
+    path: &Path,
+    backend: &dyn CredentialBackend,
+) -> Result<MigrationOutcome, CredentialError> {
+    if !backend.encrypts() {
+        return Err(CredentialError::Key(format!(
+            "the {} backend does not encrypt; set {PASSPHRASE_ENV} or {KEY_FILE_ENV}",
+            backend.kind()
+        )));
+    }
+    let Some(raw) = read_raw(path)? else {
+        return Ok(MigrationOutcome::Missing);
+    };
+    if is_encrypted(&raw) {
+        return Ok(MigrationOutcome::AlreadyEncrypted);
+    }
+    serde_json::from_slice::<serde_json::Value>(&raw).map_err(|error| CredentialError::Format {
+        path: path.to_path_buf(),
+        message: error.to_string(),
+    })?;
+    backend.write(path, &raw)?;
+    Ok(MigrationOutcome::Migrated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rocode-credentials-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        dir
+    }
+
+    fn passphrase_backend(passphrase: &str) -> EncryptedFileBackend {
+        EncryptedFileBackend::from_passphrase(passphrase)
+            .expect("backend")
+            .with_iterations(10)
+    }
+
+    #[test]
+    fn encrypted_backend_round_trips_and_hides_plaintext() {
+        let dir = temp_dir();
+        let path = dir.join("auth.json");
+        let backend = passphrase_backend("correct horse");
+
+        backend
+            .write(&path, br#"{"openai":{"type":"api","key":"sk-secret"}}"#)
+            .expect("write");
+        let raw = std::fs::read(&path).expect("raw");
+        assert!(is_encrypted(&raw));
+        assert!(!String::from_utf8_lossy(&raw).contains("sk-secret"));
+
+        let fresh = passphrase_backend("correct horse");
+        let read = fresh.read(&path).expect("read").expect("present");
+        assert_eq!(read, br#"{"openai":{"type":"api","key":"sk-secret"}}"#);
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn wrong_passphrase_and_plaintext_backend_cannot_read_sealed_store() {
+        let dir = temp_dir();
+        let path = dir.join("auth.json");
+        passphrase_backend("right")
+            .write(&path, b"{}")
+            .expect("write");
+
+        assert!(matches!(
+            passphrase_backend("wrong").read(&path),
+            Err(CredentialError::Decrypt(_))
+        ));
+        assert!(matches!(
+            PlaintextFileBackend.read(&path),
+            Err(CredentialError::Locked(_))
+        ));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn envelopes_with_excessive_iterations_are_rejected() {
+        let dir = temp_dir();
+        let path = dir.join("auth.json");
+        let backend = passphrase_backend("right");
+        backend.write(&path, b"{}").expect("write");
+
+        let mut envelope: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).expect("raw")).expect("envelope");
+        envelope["iterations"] = serde_json::json!(u32::MAX);
+        std::fs::write(&path, serde_json::to_vec(&envelope).unwrap()).expect("tamper");
+
+        assert!(matches!(
+            passphrase_backend("right").read(&path),
+            Err(CredentialError::Format { .. })
+        ));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn rewrites_after_an_iteration_change_use_the_new_round_count() {
+        let dir = temp_dir();
+        let path = dir.join("auth.json");
+        passphrase_backend("right")
+            .write(&path, b"{}")
+            .expect("write");
+
+        let backend = EncryptedFileBackend::from_passphrase("right")
+            .expect("backend")
+            .with_iterations(20);
+        backend.read(&path).expect("read").expect("present");
+        backend.write(&path, b"{\"a\":1}").expect("rewrite");
+
+        let fresh = EncryptedFileBackend::from_passphrase("right")
+            .expect("backend")
+            .with_iterations(20);
+        let read = fresh.read(&path).expect("read").expect("present");
+        assert_eq!(read, b"{\"a\":1}");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn writes_replace_the_store_without_leaving_temp_files() {
+        let dir = temp_dir();
+        let path = dir.join("auth.json");
+        PlaintextFileBackend
+            .write(&path, b"{\"a\":1}")
+            .expect("first");
+        PlaintextFileBackend.write(&path, b"{}").expect("second");
+
+        assert_eq!(std::fs::read(&path).expect("read"), b"{}");
+        let entries: Vec<_> = std::fs::read_dir(&dir)
+            .expect("dir")
+            .map(|entry| entry.expect("entry").file_name())
+            .collect();
+        assert_eq!(entries, vec![std::ffi::OsString::from("auth.json")]);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).expect("meta").permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn migration_seals_plaintext_store_once() {
+        let dir = temp_dir();
+        let path = dir.join("mcp-auth.json");
+        let key_file = dir.join("credential.key");
+        std::fs::write(&key_file, hex::encode([7u8; 32])).expect("key file");
+        std::fs::write(&path, br#"{"server":{"tokens":null}}"#).expect("plaintext");
+        let backend = EncryptedFileBackend::from_key_file(&key_file).expect("backend");
+
+        assert_eq!(
+            migrate_file(&path, &backend).expect("migrate"),
+            MigrationOutcome::Migrated
+        );
+        assert_eq!(
+            migrate_file(&path, &backend).expect("migrate again"),
+            MigrationOutcome::AlreadyEncrypted
+        );
+        assert_eq!(
+            migrate_file(&dir.join("missing.json"), &backend).expect("missing"),
+            MigrationOutcome::Missing
+        );
+        assert_eq!(
+            backend.read(&path).expect("read").expect("present"),
+            br#"{"server":{"tokens":null}}"#
+        );
+        assert!(migrate_file(&path, &PlaintextFileBackend).is_err());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
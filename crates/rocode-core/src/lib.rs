@@ -1,4 +1,5 @@
 pub mod bus;
+pub mod credentials;
 pub mod id;
 pub mod process_registry;
 
@@ -1,3 +1,4 @@
+use ignore::WalkBuilder;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::fs::File;
@@ -35,6 +36,10 @@ pub struct FileSearchOptions {
     pub hidden: bool,
     pub follow: bool,
     pub max_depth: Option<usize>,
+    /// Skip files excluded by `.gitignore`, `.ignore` and global git
+    /// excludes, like `rg --files`.
+    #[serde(default)]
+    pub respect_ignore: bool,
 }
 
 impl Default for FileSearchOptions {
@@ -44,6 +49,7 @@ impl Default for FileSearchOptions {
             hidden: true,
             follow: false,
             max_depth: None,
+            respect_ignore: false,
         }
     }
 }
@@ -95,41 +101,20 @@ impl Ripgrep {
         }
 
         let mut result = Vec::new();
-        let mut walk = WalkDir::new(path);
-
-        if let Some(depth) = options.max_depth {
-            walk = walk.max_depth(depth);
-        }
-
-        for entry in walk.into_iter().filter_map(|e| e.ok()) {
-            let entry_path = entry.path();
-
-            if !entry.file_type().is_file() {
-                continue;
-            }
-
-            let file_name = entry.file_name().to_string_lossy();
-            if !options.hidden && file_name.starts_with('.') {
-                continue;
-            }
-
-            if file_name == ".git" || entry_path.to_string_lossy().contains(".git/") {
-                continue;
-            }
-
+        for entry_path in walk_files(path, &options) {
             if !options.glob.is_empty() {
                 let matches_glob = options.glob.iter().any(|g| {
-                    if g.starts_with("!") {
-                        return !glob_match::glob_match(&g[1..], entry_path);
+                    if let Some(negated) = g.strip_prefix('!') {
+                        return !glob_match::glob_match(negated, &entry_path);
                     }
-                    glob_match::glob_match(g, entry_path)
+                    glob_match::glob_match(g, &entry_path)
                 });
                 if !matches_glob {
                     continue;
                 }
             }
 
-            result.push(entry_path.to_path_buf());
+            result.push(entry_path);
         }
 
         Ok(result)
@@ -209,6 +194,41 @@ impl Ripgrep {
     }
 }
 
+fn walk_files(path: &Path, options: &FileSearchOptions) -> Vec<PathBuf> {
+    if options.respect_ignore {
+        let mut walk = WalkBuilder::new(path);
+        walk.hidden(!options.hidden)
+            .follow_links(options.follow)
+            .max_depth(options.max_depth)
+            .filter_entry(|entry| entry.file_name() != ".git");
+        return walk
+            .build()
+            .filter_map(|e| e.ok())
+            .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+            .map(|entry| entry.into_path())
+            .collect();
+    }
+
+    let mut walk = WalkDir::new(path);
+    if let Some(depth) = options.max_depth {
+        walk = walk.max_depth(depth);
+    }
+    walk.into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|entry| {
+            if !entry.file_type().is_file() {
+                return false;
+            }
+            let file_name = entry.file_name().to_string_lossy();
+            if !options.hidden && file_name.starts_with('.') {
+                return false;
+            }
+            file_name != ".git" && !entry.path().to_string_lossy().contains(".git/")
+        })
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
 fn search_file(
     path: &Path,
     regex: &regex::Regex,
@@ -319,6 +339,29 @@ mod tests {
         assert!(!result.is_empty());
     }
 
+    #[test]
+    fn files_respects_ignore_files() {
+        let root = std::env::temp_dir().join(format!("rocode-grep-files-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("generated")).unwrap();
+        std::fs::write(root.join(".ignore"), "generated/\n").unwrap();
+        std::fs::write(root.join("src/lib.rs"), "").unwrap();
+        std::fs::write(root.join("generated/out.rs"), "").unwrap();
+
+        let files = Ripgrep::files(&root, FileSearchOptions::default()).unwrap();
+        assert!(files.contains(&root.join("generated/out.rs")));
+
+        let options = FileSearchOptions {
+            respect_ignore: true,
+            ..Default::default()
+        };
+        let files = Ripgrep::files(&root, options).unwrap();
+        assert!(files.contains(&root.join("src/lib.rs")));
+        assert!(!files.contains(&root.join("generated/out.rs")));
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
     #[test]
     fn test_tree() {
         let result = Ripgrep::tree(".", Some(10)).unwrap();
@@ -1,12 +1,13 @@
 //! Persistent storage for MCP OAuth credentials.
 //!
 //! Mirrors the TypeScript `McpAuth` namespace – stores tokens, client info,
-//! code verifiers and OAuth state in a JSON file inside the user data directory.
+//! code verifiers and OAuth state in a JSON file inside the user data directory,
+//! read and written through the shared [`rocode_core::credentials`] backend.
 
+use rocode_core::credentials;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
-use tokio::fs;
 
 /// OAuth tokens obtained from the authorization server.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,31 +50,41 @@ pub struct AuthEntry {
     pub server_url: Option<String>,
 }
 /// Resolve the path to the auth JSON file.
-fn auth_file_path() -> PathBuf {
+pub fn auth_file_path() -> PathBuf {
     let data_dir = dirs::data_dir()
         .unwrap_or_else(|| PathBuf::from("."))
         .join("opencode");
     data_dir.join("mcp-auth.json")
 }
 
-/// Read the entire auth store from disk.
-async fn read_all() -> HashMap<String, AuthEntry> {
+/// Read the entire auth store through the process-wide credential backend.
+///
+/// A store that exists but cannot be read (locked, wrong key, corrupt) is an
+/// error, so callers never write back a map missing its entries.
+async fn read_all() -> Result<HashMap<String, AuthEntry>, std::io::Error> {
     let path = auth_file_path();
-    match fs::read_to_string(&path).await {
-        Ok(contents) => serde_json::from_str(&contents).unwrap_or_default(),
-        Err(_) => HashMap::new(),
+    let backend = credentials::backend().map_err(std::io::Error::other)?;
+    let contents = tokio::task::spawn_blocking(move || backend.read(&path))
+        .await
+        .map_err(std::io::Error::other)?
+        .map_err(std::io::Error::other)?;
+    match contents {
+        Some(contents) => serde_json::from_slice(&contents)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+        None => Ok(HashMap::new()),
     }
 }
 
-/// Write the entire auth store to disk (creates parent dirs as needed).
+/// Write the entire auth store through the credential backend (creates parent dirs as needed).
 async fn write_all(data: &HashMap<String, AuthEntry>) -> Result<(), std::io::Error> {
     let path = auth_file_path();
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent).await?;
-    }
-    let json = serde_json::to_string_pretty(data)
+    let json = serde_json::to_vec_pretty(data)
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-    fs::write(&path, json).await
+    let backend = credentials::backend().map_err(std::io::Error::other)?;
+    tokio::task::spawn_blocking(move || backend.write(&path, &json))
+        .await
+        .map_err(std::io::Error::other)?
+        .map_err(std::io::Error::other)
 }
 
 // ---------------------------------------------------------------------------
@@ -82,8 +93,13 @@ async fn write_all(data: &HashMap<String, AuthEntry>) -> Result<(), std::io::Err
 
 /// Get the auth entry for a given MCP server name.
 pub async fn get(mcp_name: &str) -> Option<AuthEntry> {
-    let data = read_all().await;
-    data.get(mcp_name).cloned()
+    match read_all().await {
+        Ok(data) => data.get(mcp_name).cloned(),
+        Err(error) => {
+            tracing::warn!(%error, "failed to read MCP auth store");
+            None
+        }
+    }
 }
 
 /// Get the auth entry only if it was stored for the same `server_url`.
@@ -100,7 +116,7 @@ pub async fn set(
     entry: AuthEntry,
     server_url: Option<&str>,
 ) -> Result<(), std::io::Error> {
-    let mut data = read_all().await;
+    let mut data = read_all().await?;
     let mut entry = entry;
     if let Some(url) = server_url {
         entry.server_url = Some(url.to_string());
@@ -111,7 +127,7 @@ pub async fn set(
 
 /// Remove all stored auth data for a server.
 pub async fn remove(mcp_name: &str) -> Result<(), std::io::Error> {
-    let mut data = read_all().await;
+    let mut data = read_all().await?;
     data.remove(mcp_name);
     write_all(&data).await
 }
@@ -290,6 +290,11 @@ pub fn build_agent_ruleset(agent_name: &str, user_ruleset: &[PermissionRule]) ->
                     pattern: "*".to_string(),
                     action: PermissionAction::Allow,
                 },
+                PermissionRule {
+                    permission: "semantic_search".to_string(),
+                    pattern: "*".to_string(),
+                    action: PermissionAction::Allow,
+                },
                 PermissionRule {
                     permission: "read".to_string(),
                     pattern: "*".to_string(),
@@ -595,6 +595,14 @@ fn hook_io_from_context(context: &HookContext) -> (Value, Value) {
             copy_first(&source, &mut output, "topK", &["topK"]);
             copy_first(&source, &mut output, "options", &["options"]);
             copy_first(&source, &mut output, "maxTokens", &["maxTokens"]);
+            copy_first(&source, &mut output, "toolChoice", &["toolChoice"]);
+            copy_first(&source, &mut output, "stop", &["stop"]);
+            copy_first(
+                &source,
+                &mut output,
+                "parallelToolCalls",
+                &["parallelToolCalls"],
+            );
         }
         HookEvent::ChatHeaders => {
             copy_first(&source, &mut input, "sessionID", &["sessionID"]);
@@ -782,6 +790,9 @@ fn seed_hook_output(event: HookEvent, output: &mut Map<String, Value>) {
             ensure_default(output, "temperature", Value::Null);
             ensure_default(output, "topP", Value::Null);
             ensure_default(output, "topK", Value::Null);
+            ensure_default(output, "toolChoice", Value::Null);
+            ensure_default(output, "stop", Value::Null);
+            ensure_default(output, "parallelToolCalls", Value::Null);
             ensure_object(output, "options");
         }
         HookEvent::ChatMessage => {
@@ -2,10 +2,13 @@ use async_trait::async_trait;
 use futures::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
+use crate::batch::{BatchHandle, BatchItem, BatchItemResult, BatchPoll};
+use crate::recorder::{ExchangeRecorder, WireFormat};
 use crate::{
     ChatRequest, ChatResponse, Choice, Message, ModelInfo, Provider, ProviderError, StreamEvent,
-    StreamResult, Usage,
+    StreamResult, ToolChoice, Usage,
 };
 
 const ANTHROPIC_API_URL: &str = "https://api.anthropic.com/v1/messages";
@@ -44,6 +47,8 @@ impl AnthropicProvider {
                 supports_tools: true,
                 cost_per_million_input: 3.0,
                 cost_per_million_output: 15.0,
+                supports_batch: true,
+                supports_pdf: true,
             },
             ModelInfo {
                 id: "claude-3-5-sonnet-20241022".to_string(),
@@ -56,6 +61,8 @@ impl AnthropicProvider {
                 supports_tools: true,
                 cost_per_million_input: 3.0,
                 cost_per_million_output: 15.0,
+                supports_batch: true,
+                supports_pdf: true,
             },
             ModelInfo {
                 id: "claude-3-5-haiku-20241022".to_string(),
@@ -68,6 +75,8 @@ impl AnthropicProvider {
                 supports_tools: true,
                 cost_per_million_input: 1.0,
                 cost_per_million_output: 5.0,
+                supports_batch: true,
+                supports_pdf: true,
             },
             ModelInfo {
                 id: "claude-3-opus-20240229".to_string(),
@@ -80,6 +89,8 @@ impl AnthropicProvider {
                 supports_tools: true,
                 cost_per_million_input: 15.0,
                 cost_per_million_output: 75.0,
+                supports_batch: true,
+                supports_pdf: true,
             },
         ];
 
@@ -99,21 +110,29 @@ impl AnthropicProvider {
             .unwrap_or(16000);
         let max_tokens = request.max_tokens.unwrap_or_else(|| model_max.min(32_000));
         let mut messages = Vec::new();
-        let mut system = request.system;
+        let mut system = request.system.map(AnthropicSystem::Text);
 
         for msg in request.messages {
+            let cached = has_cache_breakpoint(&msg);
             match msg.role {
                 crate::Role::System => {
                     if let crate::Content::Text(text) = msg.content {
-                        system = Some(text);
+                        system = Some(if cached {
+                            AnthropicSystem::Blocks(vec![AnthropicBlock {
+                                content: AnthropicContent::Text { text },
+                                cache_control: Some(ephemeral_cache_control()),
+                            }])
+                        } else {
+                            AnthropicSystem::Text(text)
+                        });
                     }
                 }
                 _ => {
-                    let mut content = Vec::new();
+                    let mut content: Vec<AnthropicBlock> = Vec::new();
                     match msg.content {
                         crate::Content::Text(text) => {
                             if !text.is_empty() {
-                                content.push(AnthropicContent::Text { text });
+                                content.push(AnthropicContent::Text { text }.into());
                             }
                         }
                         crate::Content::Parts(parts) => {
@@ -121,29 +140,36 @@ impl AnthropicProvider {
                                 if part.content_type == "reasoning" {
                                     if let Some(text) = part.text {
                                         if !text.is_empty() {
-                                            content.push(AnthropicContent::Thinking {
-                                                thinking: text,
-                                            });
+                                            content.push(
+                                                AnthropicContent::Thinking { thinking: text }
+                                                    .into(),
+                                            );
                                         }
                                     }
                                 } else if let Some(text) = part.text {
                                     if !text.is_empty() {
-                                        content.push(AnthropicContent::Text { text });
+                                        content.push(AnthropicContent::Text { text }.into());
                                     }
                                 }
                                 if let Some(tool_use) = part.tool_use {
-                                    content.push(AnthropicContent::ToolUse {
-                                        id: tool_use.id,
-                                        name: tool_use.name,
-                                        input: tool_use.input,
-                                    });
+                                    content.push(
+                                        AnthropicContent::ToolUse {
+                                            id: tool_use.id,
+                                            name: tool_use.name,
+                                            input: tool_use.input,
+                                        }
+                                        .into(),
+                                    );
                                 }
                                 if let Some(tool_result) = part.tool_result {
-                                    content.push(AnthropicContent::ToolResult {
-                                        tool_use_id: tool_result.tool_use_id,
-                                        content: tool_result.content,
-                                        is_error: tool_result.is_error,
-                                    });
+                                    content.push(
+                                        AnthropicContent::ToolResult {
+                                            tool_use_id: tool_result.tool_use_id,
+                                            content: tool_result.content,
+                                            is_error: tool_result.is_error,
+                                        }
+                                        .into(),
+                                    );
                                 }
                             }
                         }
@@ -152,6 +178,11 @@ impl AnthropicProvider {
                     if content.is_empty() {
                         continue;
                     }
+                    if cached {
+                        if let Some(last) = content.last_mut() {
+                            last.cache_control = Some(ephemeral_cache_control());
+                        }
+                    }
 
                     messages.push(AnthropicMessage {
                         role: match msg.role {
@@ -166,7 +197,8 @@ impl AnthropicProvider {
             }
         }
 
-        let tools = request.tools.and_then(|tools| {
+        let stop_sequences = request.stop.filter(|stop| !stop.is_empty());
+        let mut tools = request.tools.and_then(|tools| {
             if tools.is_empty() {
                 None
             } else {
@@ -183,14 +215,41 @@ impl AnthropicProvider {
             }
         });
 
+        // Anthropic has no response format: force a tool whose input schema
+        // is the requested one. Thinking cannot be combined with forced tools.
+        let mut tool_choice = tools.as_ref().and_then(|_| {
+            anthropic_tool_choice(request.tool_choice.as_ref(), request.parallel_tool_calls)
+        });
+        let mut thinking = anthropic_thinking_config(request.variant.as_deref(), max_tokens);
+        // Forced tool use (`any`/`tool`) cannot be combined with extended thinking.
+        if matches!(
+            request.tool_choice,
+            Some(ToolChoice::Required | ToolChoice::Tool(_))
+        ) && tool_choice.is_some()
+        {
+            thinking = None;
+        }
+        if let Some(format) = &request.response_format {
+            let tool = format.anthropic_tool();
+            tools.get_or_insert_with(Vec::new).push(AnthropicTool {
+                name: tool.name.clone(),
+                description: tool.description,
+                input_schema: tool.parameters,
+            });
+            tool_choice = Some(serde_json::json!({ "type": "tool", "name": tool.name }));
+            thinking = None;
+        }
+
         AnthropicRequest {
             model: request.model,
             max_tokens,
             messages,
             system,
             tools,
+            tool_choice,
+            stop_sequences,
             stream: request.stream,
-            thinking: anthropic_thinking_config(request.variant.as_deref(), max_tokens),
+            thinking,
         }
     }
 }
@@ -215,81 +274,268 @@ impl Provider for AnthropicProvider {
 
     async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, ProviderError> {
         let anthropic_request = self.convert_request(request);
+        let (response, recorder) = self.send(&anthropic_request, false).await?;
 
-        let url = self.config.base_url.as_deref().unwrap_or(ANTHROPIC_API_URL);
-
-        let response = self
-            .client
-            .post(url)
-            .header("x-api-key", &self.config.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("anthropic-beta", "claude-code-20250219,interleaved-thinking-2025-05-14,fine-grained-tool-streaming-2025-05-14")
-            .header("content-type", "application/json")
-            .json(&anthropic_request)
-            .send()
+        let status = response.status();
+        let body = response
+            .text()
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+        if let Some(recorder) = &recorder {
+            recorder.body(&body);
+        }
+        if !status.is_success() {
+            return Err(ProviderError::ApiError(format!("{}: {}", status, body)));
+        }
+
+        let anthropic_response: AnthropicResponse =
+            serde_json::from_str(&body).map_err(|e| ProviderError::ApiError(e.to_string()))?;
+
+        Ok(convert_response(anthropic_response))
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<StreamResult, ProviderError> {
+        let mut anthropic_request = self.convert_request(request);
+        anthropic_request.stream = Some(true);
+        let (response, recorder) = self.send(&anthropic_request, true).await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
+            if let Some(recorder) = &recorder {
+                recorder.body(&body);
+            }
             return Err(ProviderError::ApiError(format!("{}: {}", status, body)));
         }
 
-        let anthropic_response: AnthropicResponse = response
-            .json()
+        let chunks = response.bytes_stream().map(|chunk| match chunk {
+            Ok(bytes) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+            Err(e) => Err(ProviderError::StreamError(e.to_string())),
+        });
+        Ok(anthropic_event_stream(crate::recorder::tap(
+            chunks, recorder,
+        )))
+    }
+
+    async fn submit_batch(&self, items: Vec<BatchItem>) -> Result<BatchHandle, ProviderError> {
+        let custom_ids = items.iter().map(|item| item.custom_id.clone()).collect();
+        let requests: Vec<_> = items
+            .into_iter()
+            .map(|item| {
+                let mut params = self.convert_request(item.request);
+                params.stream = None;
+                serde_json::json!({ "custom_id": item.custom_id, "params": params })
+            })
+            .collect();
+
+        let mut builder = self.client.post(self.batches_url());
+        for (name, value) in self.headers(false) {
+            builder = builder.header(name, value);
+        }
+        let response = builder
+            .json(&serde_json::json!({ "requests": requests }))
+            .send()
             .await
-            .map_err(|e| ProviderError::ApiError(e.to_string()))?;
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+        let batch: AnthropicBatch = read_json(response).await?;
 
-        Ok(convert_response(anthropic_response))
+        Ok(BatchHandle {
+            provider_id: self.id().to_string(),
+            batch_id: batch.id,
+            custom_ids,
+        })
     }
 
-    async fn chat_stream(&self, request: ChatRequest) -> Result<StreamResult, ProviderError> {
-        let mut anthropic_request = self.convert_request(request);
-        anthropic_request.stream = Some(true);
+    async fn poll_batch(&self, handle: &BatchHandle) -> Result<BatchPoll, ProviderError> {
+        let batch: AnthropicBatch = read_json(
+            self.get(&format!("{}/{}", self.batches_url(), handle.batch_id))
+                .await?,
+        )
+        .await?;
+        let counts = &batch.request_counts;
+        let completed = counts.succeeded + counts.errored + counts.canceled + counts.expired;
+        let (Some(results_url), "ended") = (batch.results_url, batch.processing_status.as_str())
+        else {
+            return Ok(BatchPoll::Pending {
+                completed,
+                total: completed + counts.processing,
+            });
+        };
+
+        let body = self
+            .get(&results_url)
+            .await?
+            .text()
+            .await
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+        let results = body
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(
+                |line| match serde_json::from_str::<AnthropicBatchLine>(line) {
+                    Ok(AnthropicBatchLine { custom_id, result }) => match result {
+                        AnthropicBatchResult::Succeeded { message } => BatchItemResult {
+                            custom_id,
+                            outcome: Ok(convert_response(message)),
+                        },
+                        AnthropicBatchResult::Errored { error } => {
+                            BatchItemResult::failed(custom_id, error.to_string())
+                        }
+                        AnthropicBatchResult::Canceled => {
+                            BatchItemResult::failed(custom_id, "canceled")
+                        }
+                        AnthropicBatchResult::Expired => {
+                            BatchItemResult::failed(custom_id, "expired")
+                        }
+                    },
+                    Err(e) => BatchItemResult::failed("", format!("invalid batch result: {}", e)),
+                },
+            )
+            .collect();
+        Ok(BatchPoll::Complete(results))
+    }
+}
 
+impl AnthropicProvider {
+    fn headers(&self, stream: bool) -> HashMap<String, String> {
+        let mut headers = HashMap::from([
+            ("x-api-key".to_string(), self.config.api_key.clone()),
+            ("anthropic-version".to_string(), "2023-06-01".to_string()),
+            ("anthropic-beta".to_string(), "claude-code-20250219,interleaved-thinking-2025-05-14,fine-grained-tool-streaming-2025-05-14".to_string()),
+            ("content-type".to_string(), "application/json".to_string()),
+        ]);
+        if stream {
+            headers.insert("accept".to_string(), "text/event-stream".to_string());
+        }
+        headers
+    }
+
+    /// The Message Batches endpoint, next to the configured messages URL.
+    fn batches_url(&self) -> String {
         let url = self.config.base_url.as_deref().unwrap_or(ANTHROPIC_API_URL);
+        format!("{}/batches", url.trim_end_matches('/'))
+    }
 
-        let response = self
-            .client
-            .post(url)
-            .header("x-api-key", &self.config.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("anthropic-beta", "claude-code-20250219,interleaved-thinking-2025-05-14,fine-grained-tool-streaming-2025-05-14")
-            .header("content-type", "application/json")
-            .header("accept", "text/event-stream")
-            .json(&anthropic_request)
+    async fn get(&self, url: &str) -> Result<reqwest::Response, ProviderError> {
+        let mut builder = self.client.get(url);
+        for (name, value) in self.headers(false) {
+            builder = builder.header(name, value);
+        }
+        builder
             .send()
             .await
-            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))
+    }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(ProviderError::ApiError(format!("{}: {}", status, body)));
+    /// Sends `request`, opening a capture when recording is enabled.
+    async fn send(
+        &self,
+        request: &AnthropicRequest,
+        stream: bool,
+    ) -> Result<(reqwest::Response, Option<ExchangeRecorder>), ProviderError> {
+        let url = self.config.base_url.as_deref().unwrap_or(ANTHROPIC_API_URL);
+        let headers = self.headers(stream);
+        let body = serde_json::to_string(request)
+            .map_err(|e| ProviderError::InvalidRequest(format!("failed to encode body: {}", e)))?;
+        let recorder = ExchangeRecorder::begin(
+            self.id(),
+            WireFormat::Anthropic,
+            "POST",
+            url,
+            &headers,
+            &body,
+        );
+
+        let mut builder = self.client.post(url);
+        for (name, value) in &headers {
+            builder = builder.header(name, value);
         }
+        let response = builder.body(body).send().await.map_err(|e| {
+            if let Some(recorder) = &recorder {
+                recorder.error(&e.to_string());
+            }
+            ProviderError::NetworkError(e.to_string())
+        })?;
+        crate::governor::observe(&response);
+        if let Some(recorder) = &recorder {
+            recorder.response(response.status().as_u16(), response.headers());
+        }
+        Ok((response, recorder))
+    }
+}
 
-        let stream = response.bytes_stream().flat_map(move |chunk_result| {
-            let events: Vec<Result<StreamEvent, ProviderError>> = match chunk_result {
-                Ok(bytes) => {
-                    let text = String::from_utf8_lossy(&bytes);
-                    let mut events = Vec::new();
-                    for line in text.lines() {
-                        if let Some(data) = line.strip_prefix("data: ") {
-                            if let Some(event) = crate::stream::parse_anthropic_sse(data) {
-                                events.push(Ok(event));
-                            }
+async fn read_json<T: serde::de::DeserializeOwned>(
+    response: reqwest::Response,
+) -> Result<T, ProviderError> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(ProviderError::ApiError(format!("{}: {}", status, body)));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| ProviderError::ApiError(e.to_string()))
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicBatch {
+    id: String,
+    processing_status: String,
+    request_counts: AnthropicBatchCounts,
+    #[serde(default)]
+    results_url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct AnthropicBatchCounts {
+    processing: usize,
+    succeeded: usize,
+    errored: usize,
+    canceled: usize,
+    expired: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicBatchLine {
+    custom_id: String,
+    result: AnthropicBatchResult,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum AnthropicBatchResult {
+    Succeeded { message: AnthropicResponse },
+    Errored { error: serde_json::Value },
+    Canceled,
+    Expired,
+}
+
+/// Parses raw Anthropic SSE chunks into stream events.
+pub(crate) fn anthropic_event_stream<S>(chunks: S) -> StreamResult
+where
+    S: futures::Stream<Item = Result<String, ProviderError>> + Send + 'static,
+{
+    let stream = chunks.flat_map(|chunk_result| {
+        let events: Vec<Result<StreamEvent, ProviderError>> = match chunk_result {
+            Ok(text) => {
+                let mut events = Vec::new();
+                for line in text.lines() {
+                    if let Some(data) = line.strip_prefix("data: ") {
+                        if let Some(event) = crate::stream::parse_anthropic_sse(data) {
+                            events.push(Ok(event));
                         }
                     }
-                    events
                 }
-                Err(e) => vec![Err(ProviderError::StreamError(e.to_string()))],
-            };
-            futures::stream::iter(events)
-        });
+                events
+            }
+            Err(e) => vec![Err(e)],
+        };
+        futures::stream::iter(events)
+    });
 
-        Ok(crate::stream::assemble_tool_calls(Box::pin(stream)))
-    }
+    crate::stream::assemble_tool_calls(Box::pin(stream))
 }
 
 #[derive(Debug, Serialize)]
@@ -298,10 +544,14 @@ struct AnthropicRequest {
     max_tokens: u64,
     messages: Vec<AnthropicMessage>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    system: Option<String>,
+    system: Option<AnthropicSystem>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<AnthropicTool>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     stream: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     thinking: Option<AnthropicThinking>,
@@ -310,7 +560,52 @@ struct AnthropicRequest {
 #[derive(Debug, Serialize)]
 struct AnthropicMessage {
     role: String,
-    content: Vec<AnthropicContent>,
+    content: Vec<AnthropicBlock>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum AnthropicSystem {
+    Text(String),
+    Blocks(Vec<AnthropicBlock>),
+}
+
+/// A content block plus its optional prompt-cache breakpoint.
+#[derive(Debug, Serialize)]
+struct AnthropicBlock {
+    #[serde(flatten)]
+    content: AnthropicContent,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_control: Option<serde_json::Value>,
+}
+
+impl From<AnthropicContent> for AnthropicBlock {
+    fn from(content: AnthropicContent) -> Self {
+        Self {
+            content,
+            cache_control: None,
+        }
+    }
+}
+
+fn ephemeral_cache_control() -> serde_json::Value {
+    serde_json::json!({ "type": "ephemeral" })
+}
+
+/// Whether `transform::apply_caching` (message-level provider options) or
+/// `apply_caching_per_part` marked this message as a cache breakpoint.
+fn has_cache_breakpoint(message: &Message) -> bool {
+    let marked = |options: Option<&std::collections::HashMap<String, serde_json::Value>>| {
+        options
+            .and_then(|options| options.get("anthropic"))
+            .and_then(|anthropic| anthropic.get("cacheControl"))
+            .is_some()
+    };
+    message.cache_control.is_some()
+        || marked(message.provider_options.as_ref())
+        || matches!(&message.content, crate::Content::Parts(parts) if parts
+            .last()
+            .is_some_and(|part| part.cache_control.is_some() || marked(part.provider_options.as_ref())))
 }
 
 #[derive(Debug, Serialize)]
@@ -354,6 +649,25 @@ enum AnthropicThinking {
     },
 }
 
+/// Anthropic spells parallel tool use as a flag on `tool_choice`.
+fn anthropic_tool_choice(
+    choice: Option<&ToolChoice>,
+    parallel_tool_calls: Option<bool>,
+) -> Option<serde_json::Value> {
+    let mut value = match choice {
+        None if parallel_tool_calls == Some(false) => serde_json::json!({ "type": "auto" }),
+        None => return None,
+        Some(ToolChoice::Auto) => serde_json::json!({ "type": "auto" }),
+        Some(ToolChoice::None) => return Some(serde_json::json!({ "type": "none" })),
+        Some(ToolChoice::Required) => serde_json::json!({ "type": "any" }),
+        Some(ToolChoice::Tool(name)) => serde_json::json!({ "type": "tool", "name": name }),
+    };
+    if parallel_tool_calls == Some(false) {
+        value["disable_parallel_tool_use"] = serde_json::json!(true);
+    }
+    Some(value)
+}
+
 fn anthropic_thinking_config(variant: Option<&str>, max_tokens: u64) -> Option<AnthropicThinking> {
     let variant = variant?.trim().to_ascii_lowercase();
     let target = match variant.as_str() {
@@ -385,21 +699,38 @@ struct AnthropicResponseContent {
     #[serde(rename = "type")]
     _content_type: String,
     text: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    input: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
 struct AnthropicResponseUsage {
     input_tokens: u64,
     output_tokens: u64,
+    #[serde(default)]
+    cache_read_input_tokens: u64,
+    #[serde(default)]
+    cache_creation_input_tokens: u64,
 }
 
 fn convert_response(response: AnthropicResponse) -> ChatResponse {
-    let content = response
-        .content
-        .iter()
-        .filter_map(|c| c.text.clone())
-        .collect::<Vec<_>>()
-        .join("");
+    // A forced structured output tool call is the answer itself.
+    let structured = response.content.iter().find_map(|c| {
+        c.input
+            .as_ref()
+            .filter(|_| c.name.as_deref() == Some(crate::STRUCTURED_OUTPUT_TOOL))
+            .map(|input| input.to_string())
+    });
+    let content = structured.unwrap_or_else(|| {
+        response
+            .content
+            .iter()
+            .filter_map(|c| c.text.clone())
+            .collect::<Vec<_>>()
+            .join("")
+    });
 
     ChatResponse {
         id: response.id,
@@ -413,8 +744,90 @@ fn convert_response(response: AnthropicResponse) -> ChatResponse {
             prompt_tokens: response.usage.input_tokens,
             completion_tokens: response.usage.output_tokens,
             total_tokens: response.usage.input_tokens + response.usage.output_tokens,
-            cache_read_input_tokens: None,
-            cache_creation_input_tokens: None,
+            cache_read_input_tokens: Some(response.usage.cache_read_input_tokens),
+            cache_creation_input_tokens: Some(response.usage.cache_creation_input_tokens),
         }),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_result_lines_parse_every_outcome() {
+        let succeeded: AnthropicBatchLine = serde_json::from_str(
+            r#"{"custom_id":"a","result":{"type":"succeeded","message":{"id":"msg_1","model":"claude","content":[{"type":"text","text":"hi"}],"usage":{"input_tokens":5,"output_tokens":2}}}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            succeeded.result,
+            AnthropicBatchResult::Succeeded { .. }
+        ));
+
+        let expired: AnthropicBatchLine =
+            serde_json::from_str(r#"{"custom_id":"b","result":{"type":"expired"}}"#).unwrap();
+        assert_eq!(expired.custom_id, "b");
+        assert!(matches!(expired.result, AnthropicBatchResult::Expired));
+    }
+
+    #[test]
+    fn tool_choice_maps_to_anthropic_shapes() {
+        assert_eq!(anthropic_tool_choice(None, None), None);
+        assert_eq!(
+            anthropic_tool_choice(Some(&ToolChoice::Required), None),
+            Some(serde_json::json!({ "type": "any" }))
+        );
+        assert_eq!(
+            anthropic_tool_choice(Some(&ToolChoice::Tool("read".to_string())), Some(false)),
+            Some(serde_json::json!({
+                "type": "tool",
+                "name": "read",
+                "disable_parallel_tool_use": true
+            }))
+        );
+        assert_eq!(
+            anthropic_tool_choice(Some(&ToolChoice::None), Some(false)),
+            Some(serde_json::json!({ "type": "none" }))
+        );
+        assert_eq!(
+            anthropic_tool_choice(None, Some(false)),
+            Some(serde_json::json!({ "type": "auto", "disable_parallel_tool_use": true }))
+        );
+    }
+
+    #[test]
+    fn cache_breakpoints_serialize_as_cache_control() {
+        let mut messages = vec![
+            Message::system("You are helpful."),
+            Message::user("first"),
+            Message::assistant("answer"),
+            Message::user("second"),
+        ];
+        crate::transform::apply_caching(&mut messages, crate::ProviderType::Anthropic);
+
+        let provider = AnthropicProvider::new("test-key");
+        let request = provider.convert_request(ChatRequest::new("claude-test", messages));
+        let body = serde_json::to_value(&request).expect("serialize request");
+
+        assert_eq!(
+            body["system"],
+            serde_json::json!([{
+                "type": "text",
+                "text": "You are helpful.",
+                "cache_control": { "type": "ephemeral" }
+            }])
+        );
+        assert!(body["messages"][0]["content"][0]
+            .get("cache_control")
+            .is_none());
+        assert_eq!(
+            body["messages"][1]["content"][0]["cache_control"],
+            serde_json::json!({ "type": "ephemeral" })
+        );
+        assert_eq!(
+            body["messages"][2]["content"][0]["cache_control"],
+            serde_json::json!({ "type": "ephemeral" })
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use rocode_core::credentials::{self, CredentialBackend};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -57,6 +58,14 @@ pub enum AuthMethodType {
 pub struct AuthManager {
     credentials: Arc<RwLock<HashMap<String, AuthInfo>>>,
     filepath: Option<PathBuf>,
+    store: Store,
+}
+
+enum Store {
+    Ready(Arc<dyn CredentialBackend>),
+    /// The store could not be read (locked, wrong key, corrupt). Its entries
+    /// are unknown, so writing back would clobber them; writes are refused.
+    ReadFailed(String),
 }
 
 impl AuthManager {
@@ -64,28 +73,65 @@ impl AuthManager {
         Self {
             credentials: Arc::new(RwLock::new(HashMap::new())),
             filepath: None,
+            store: match credentials::backend() {
+                Ok(backend) => Store::Ready(backend),
+                Err(error) => Store::ReadFailed(error.to_string()),
+            },
         }
     }
 
     pub fn with_filepath(filepath: PathBuf) -> Self {
         Self {
-            credentials: Arc::new(RwLock::new(HashMap::new())),
             filepath: Some(filepath),
+            ..Self::new()
         }
     }
 
+    /// Loads `auth.json` from `data_dir` through the process-wide credential backend.
     pub async fn load_from_file(data_dir: &Path) -> Self {
+        match credentials::backend() {
+            Ok(backend) => Self::load_with_backend(data_dir, backend).await,
+            Err(error) => {
+                tracing::warn!(%error, "credential backend unavailable; auth store is read-only");
+                Self {
+                    store: Store::ReadFailed(error.to_string()),
+                    ..Self::with_filepath(data_dir.join("auth.json"))
+                }
+            }
+        }
+    }
+
+    pub async fn load_with_backend(data_dir: &Path, backend: Arc<dyn CredentialBackend>) -> Self {
         let filepath = data_dir.join("auth.json");
-        let manager = Self::with_filepath(filepath.clone());
-        if let Ok(content) = tokio::fs::read_to_string(&filepath).await {
-            if let Ok(data) = serde_json::from_str::<HashMap<String, AuthInfo>>(&content) {
-                let mut creds = manager.credentials.write().await;
-                *creds = data;
+        let mut manager = Self {
+            store: Store::Ready(backend.clone()),
+            ..Self::with_filepath(filepath.clone())
+        };
+        let content = tokio::task::spawn_blocking(move || backend.read(&filepath)).await;
+        let loaded = match content {
+            Ok(Ok(Some(content))) => serde_json::from_slice(&content).map_err(|e| e.to_string()),
+            Ok(Ok(None)) => Ok(HashMap::new()),
+            Ok(Err(error)) => Err(error.to_string()),
+            Err(error) => Err(error.to_string()),
+        };
+        match loaded {
+            Ok(data) => *manager.credentials.write().await = data,
+            Err(error) => {
+                tracing::warn!(%error, "failed to read auth store; it will not be overwritten");
+                manager.store = Store::ReadFailed(error);
             }
         }
         manager
     }
 
+    /// Why the store on disk is not being written, when it could not be read.
+    pub fn read_error(&self) -> Option<&str> {
+        match &self.store {
+            Store::Ready(_) => None,
+            Store::ReadFailed(error) => Some(error),
+        }
+    }
+
     pub async fn get(&self, provider_id: &str) -> Option<AuthInfo> {
         let creds = self.credentials.read().await;
         creds.get(provider_id).cloned()
@@ -142,19 +188,19 @@ impl AuthManager {
             return Ok(());
         };
 
-        if let Some(parent) = path.parent() {
-            tokio::fs::create_dir_all(parent).await?;
-        }
-
-        let creds = self.credentials.read().await;
-        let json = serde_json::to_vec_pretty(&*creds)?;
-        tokio::fs::write(path, json).await?;
-
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::PermissionsExt;
-            tokio::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600)).await?;
-        }
+        let json = {
+            let creds = self.credentials.read().await;
+            serde_json::to_vec_pretty(&*creds)?
+        };
+        let backend = match &self.store {
+            Store::Ready(backend) => backend.clone(),
+            Store::ReadFailed(error) => anyhow::bail!(
+                "refusing to overwrite {} because it could not be read: {error}",
+                path.display()
+            ),
+        };
+        let path = path.clone();
+        tokio::task::spawn_blocking(move || backend.write(&path, &json)).await??;
 
         Ok(())
     }
@@ -166,6 +212,22 @@ impl Default for AuthManager {
     }
 }
 
+/// Directory holding the provider `auth.json`; `OPENCODE_DATA_DIR` overrides it.
+pub fn auth_data_dir() -> PathBuf {
+    if let Ok(path) = std::env::var("OPENCODE_DATA_DIR") {
+        let trimmed = path.trim();
+        if !trimmed.is_empty() {
+            return PathBuf::from(trimmed);
+        }
+    }
+
+    dirs::data_local_dir()
+        .or_else(dirs::data_dir)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("opencode")
+        .join("data")
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum AuthError {
     #[error("OAuth pending request not found for provider: {0}")]
@@ -276,4 +338,116 @@ mod tests {
 
         let _ = std::fs::remove_dir_all(&dir);
     }
+
+    fn sealed_backend(passphrase: &str) -> Arc<dyn CredentialBackend> {
+        Arc::new(
+            credentials::EncryptedFileBackend::from_passphrase(passphrase)
+                .expect("backend")
+                .with_iterations(10),
+        )
+    }
+
+    #[tokio::test]
+    async fn wrong_passphrase_does_not_clobber_the_store() {
+        let dir = temp_auth_dir();
+        let right = sealed_backend("right");
+        AuthManager::load_with_backend(&dir, right.clone())
+            .await
+            .set(
+                "openai",
+                AuthInfo::Api {
+                    key: "sk-keep".to_string(),
+                },
+            )
+            .await;
+        let sealed = std::fs::read(dir.join("auth.json")).expect("auth.json");
+
+        let wrong = AuthManager::load_with_backend(&dir, sealed_backend("wrong")).await;
+        assert!(wrong.read_error().is_some());
+        wrong
+            .set(
+                "anthropic",
+                AuthInfo::Api {
+                    key: "sk-new".to_string(),
+                },
+            )
+            .await;
+        wrong.remove("openai").await;
+
+        assert_eq!(
+            std::fs::read(dir.join("auth.json")).expect("auth.json"),
+            sealed
+        );
+        let reloaded = AuthManager::load_with_backend(&dir, right).await;
+        assert_eq!(
+            reloaded.get_api_key("openai").await.as_deref(),
+            Some("sk-keep")
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn locked_store_refuses_writes() {
+        let dir = temp_auth_dir();
+        AuthManager::load_with_backend(&dir, sealed_backend("secret"))
+            .await
+            .set(
+                "openai",
+                AuthInfo::Api {
+                    key: "sk-keep".to_string(),
+                },
+            )
+            .await;
+        let sealed = std::fs::read(dir.join("auth.json")).expect("auth.json");
+
+        let locked =
+            AuthManager::load_with_backend(&dir, Arc::new(credentials::PlaintextFileBackend)).await;
+        assert!(locked.read_error().is_some());
+        assert!(locked.persist().await.is_err());
+        locked
+            .set(
+                "openai",
+                AuthInfo::Api {
+                    key: "sk-plain".to_string(),
+                },
+            )
+            .await;
+
+        let raw = std::fs::read(dir.join("auth.json")).expect("auth.json");
+        assert_eq!(raw, sealed);
+        assert!(!String::from_utf8_lossy(&raw).contains("sk-plain"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn auth_manager_round_trips_through_encrypted_backend() {
+        let dir = temp_auth_dir();
+        let backend: Arc<dyn CredentialBackend> = Arc::new(
+            credentials::EncryptedFileBackend::from_passphrase("auth-test")
+                .expect("backend")
+                .with_iterations(10),
+        );
+        let manager = AuthManager::load_with_backend(&dir, backend.clone()).await;
+        manager
+            .set(
+                "anthropic",
+                AuthInfo::Api {
+                    key: "sk-ant-secret".to_string(),
+                },
+            )
+            .await;
+
+        let raw = std::fs::read_to_string(dir.join("auth.json")).expect("auth.json");
+        assert!(!raw.contains("sk-ant-secret"));
+
+        let reloaded = AuthManager::load_with_backend(&dir, backend).await;
+        assert_eq!(
+            reloaded.get_api_key("anthropic").await.as_deref(),
+            Some("sk-ant-secret")
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -0,0 +1,75 @@
+//! Native batch inference.
+//!
+//! Providers whose [`ModelInfo::supports_batch`](crate::ModelInfo) is set
+//! accept a whole set of requests at once and finish them asynchronously,
+//! usually at a discount. A batch is submitted once and then polled until the
+//! provider reports every item done.
+
+use serde::{Deserialize, Serialize};
+
+use crate::message::{ChatRequest, ChatResponse};
+
+/// One request inside a batch, tagged so results can be matched back.
+#[derive(Debug, Clone)]
+pub struct BatchItem {
+    pub custom_id: String,
+    pub request: ChatRequest,
+}
+
+/// Provider-assigned handle for a submitted batch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchHandle {
+    pub provider_id: String,
+    pub batch_id: String,
+    pub custom_ids: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum BatchPoll {
+    Pending { completed: usize, total: usize },
+    Complete(Vec<BatchItemResult>),
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchItemResult {
+    pub custom_id: String,
+    pub outcome: Result<ChatResponse, String>,
+}
+
+impl BatchItemResult {
+    pub fn failed(custom_id: impl Into<String>, error: impl Into<String>) -> Self {
+        Self {
+            custom_id: custom_id.into(),
+            outcome: Err(error.into()),
+        }
+    }
+}
+
+/// Error message for providers without a batch endpoint.
+pub(crate) fn unsupported(provider_id: &str) -> crate::ProviderError {
+    crate::ProviderError::InvalidRequest(format!(
+        "provider {} does not offer a batch API",
+        provider_id
+    ))
+}
+
+/// Writes a `multipart/form-data` body with text `fields` and one file part.
+/// Hand-rolled because the workspace builds reqwest without `multipart`.
+pub(crate) fn multipart_body(
+    boundary: &str,
+    fields: &[(&str, &str)],
+    file_field: &str,
+    file_name: &str,
+    file_content: &str,
+) -> String {
+    let mut body = String::new();
+    for (name, value) in fields {
+        body.push_str(&format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+        ));
+    }
+    body.push_str(&format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"{file_field}\"; filename=\"{file_name}\"\r\nContent-Type: application/jsonl\r\n\r\n{file_content}\r\n--{boundary}--\r\n"
+    ));
+    body
+}
@@ -1,11 +1,14 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use futures::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
+use crate::recorder::{ExchangeRecorder, WireFormat};
 use crate::{
     ChatRequest, ChatResponse, Choice, Content, Message, ModelInfo, Provider, ProviderError, Role,
-    StreamEvent, StreamResult, Usage,
+    StreamEvent, StreamResult, ToolChoice, ToolDefinition, Usage,
 };
 
 const BEDROCK_RUNTIME_URL: &str = "https://bedrock-runtime.{region}.amazonaws.com";
@@ -54,6 +57,8 @@ impl BedrockProvider {
                 supports_tools: true,
                 cost_per_million_input: 3.0,
                 cost_per_million_output: 15.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "anthropic.claude-3-5-sonnet-20241022-v2:0".to_string(),
@@ -66,6 +71,8 @@ impl BedrockProvider {
                 supports_tools: true,
                 cost_per_million_input: 3.0,
                 cost_per_million_output: 15.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "anthropic.claude-3-5-haiku-20241022-v1:0".to_string(),
@@ -78,6 +85,8 @@ impl BedrockProvider {
                 supports_tools: true,
                 cost_per_million_input: 1.0,
                 cost_per_million_output: 5.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "anthropic.claude-3-opus-20240229-v1:0".to_string(),
@@ -90,6 +99,8 @@ impl BedrockProvider {
                 supports_tools: true,
                 cost_per_million_input: 15.0,
                 cost_per_million_output: 75.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "amazon.nova-pro-v1:0".to_string(),
@@ -102,6 +113,8 @@ impl BedrockProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.8,
                 cost_per_million_output: 3.2,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "amazon.nova-lite-v1:0".to_string(),
@@ -114,6 +127,8 @@ impl BedrockProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.06,
                 cost_per_million_output: 0.24,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "amazon.nova-micro-v1:0".to_string(),
@@ -126,6 +141,8 @@ impl BedrockProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.035,
                 cost_per_million_output: 0.14,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "meta.llama3-3-70b-instruct-v1:0".to_string(),
@@ -138,6 +155,8 @@ impl BedrockProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.72,
                 cost_per_million_output: 0.72,
+                supports_batch: false,
+                supports_pdf: false,
             },
         ];
 
@@ -196,8 +215,12 @@ impl BedrockProvider {
             }
         }
 
+        let tool_config =
+            bedrock_tool_config(request.tools.as_deref(), request.tool_choice.as_ref());
+
         BedrockConverseRequest {
             messages,
+            tool_config,
             system: if system.is_empty() {
                 None
             } else {
@@ -206,6 +229,7 @@ impl BedrockProvider {
             inference_config: Some(BedrockInferenceConfig {
                 max_tokens: request.max_tokens,
                 temperature: request.temperature,
+                stop_sequences: request.stop,
             }),
         }
     }
@@ -342,6 +366,7 @@ impl Provider for BedrockProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -374,6 +399,18 @@ impl Provider for BedrockProvider {
             .await?;
 
         let url = format!("{}/model/{}/converse-stream", endpoint, model_id_encoded);
+        let recorded_headers: HashMap<String, String> = headers
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let recorder = ExchangeRecorder::begin(
+            self.id(),
+            WireFormat::Bedrock,
+            "POST",
+            &url,
+            &recorded_headers,
+            &String::from_utf8_lossy(&body),
+        );
 
         let response = self
             .client
@@ -383,28 +420,42 @@ impl Provider for BedrockProvider {
             .body(body)
             .send()
             .await
-            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+            .map_err(|e| {
+                if let Some(recorder) = &recorder {
+                    recorder.error(&e.to_string());
+                }
+                ProviderError::NetworkError(e.to_string())
+            })?;
 
+        crate::governor::observe(&response);
+        if let Some(recorder) = &recorder {
+            recorder.response(response.status().as_u16(), response.headers());
+        }
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
+            if let Some(recorder) = &recorder {
+                recorder.body(&body);
+            }
             return Err(ProviderError::ApiError(format!("{}: {}", status, body)));
         }
 
-        let stream = response
-            .bytes_stream()
-            .map(move |chunk_result| match chunk_result {
-                Ok(bytes) => {
-                    let text = String::from_utf8_lossy(&bytes);
-                    parse_bedrock_stream(&text)
-                }
-                Err(e) => Err(ProviderError::StreamError(e.to_string())),
-            });
-
-        Ok(Box::pin(stream))
+        let chunks = response.bytes_stream().map(|chunk| match chunk {
+            Ok(bytes) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+            Err(e) => Err(ProviderError::StreamError(e.to_string())),
+        });
+        Ok(bedrock_event_stream(crate::recorder::tap(chunks, recorder)))
     }
 }
 
+/// Maps each event-stream chunk (decoded lossily as text) to one event.
+pub(crate) fn bedrock_event_stream<S>(chunks: S) -> StreamResult
+where
+    S: futures::Stream<Item = Result<String, ProviderError>> + Send + 'static,
+{
+    Box::pin(chunks.map(|chunk_result| chunk_result.and_then(|text| parse_bedrock_stream(&text))))
+}
+
 #[derive(Debug, Serialize)]
 struct BedrockConverseRequest {
     messages: Vec<BedrockMessage>,
@@ -412,6 +463,61 @@ struct BedrockConverseRequest {
     system: Option<Vec<BedrockSystemContent>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     inference_config: Option<BedrockInferenceConfig>,
+    #[serde(rename = "toolConfig", skip_serializing_if = "Option::is_none")]
+    tool_config: Option<BedrockToolConfig>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BedrockToolConfig {
+    tools: Vec<BedrockTool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BedrockTool {
+    tool_spec: BedrockToolSpec,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BedrockToolSpec {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    input_schema: serde_json::Value,
+}
+
+/// Converse has no `none` tool choice, so that case leaves the tools off the
+/// request entirely. It also has no parallel tool call switch, so
+/// `parallel_tool_calls` is not sent.
+fn bedrock_tool_config(
+    tools: Option<&[ToolDefinition]>,
+    choice: Option<&ToolChoice>,
+) -> Option<BedrockToolConfig> {
+    let tools = tools.filter(|tools| !tools.is_empty())?;
+    let tool_choice = match choice {
+        None => None,
+        Some(ToolChoice::None) => return None,
+        Some(ToolChoice::Auto) => Some(serde_json::json!({ "auto": {} })),
+        Some(ToolChoice::Required) => Some(serde_json::json!({ "any": {} })),
+        Some(ToolChoice::Tool(name)) => Some(serde_json::json!({ "tool": { "name": name } })),
+    };
+    Some(BedrockToolConfig {
+        tools: tools
+            .iter()
+            .map(|tool| BedrockTool {
+                tool_spec: BedrockToolSpec {
+                    name: tool.name.clone(),
+                    description: tool.description.clone(),
+                    input_schema: serde_json::json!({ "json": tool.parameters }),
+                },
+            })
+            .collect(),
+        tool_choice,
+    })
 }
 
 #[derive(Debug, Serialize)]
@@ -444,6 +550,8 @@ struct BedrockInferenceConfig {
     max_tokens: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f32>,
+    #[serde(rename = "stopSequences", skip_serializing_if = "Option::is_none")]
+    stop_sequences: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -522,3 +630,60 @@ fn parse_bedrock_stream(text: &str) -> Result<StreamEvent, ProviderError> {
 
     Ok(StreamEvent::TextDelta(String::new()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_tool() -> ToolDefinition {
+        ToolDefinition {
+            name: "read".to_string(),
+            description: Some("Read a file".to_string()),
+            parameters: serde_json::json!({ "type": "object" }),
+        }
+    }
+
+    #[test]
+    fn tool_choice_maps_to_converse_tool_config() {
+        let provider = BedrockProvider::new("us-east-1", "key", "secret");
+        let body = |request: ChatRequest| {
+            serde_json::to_value(provider.convert_request(request)).expect("serialize request")
+        };
+
+        let forced = body(
+            ChatRequest::new("anthropic.claude-test", vec![Message::user("hi")])
+                .with_tools(vec![read_tool()])
+                .with_tool_choice(ToolChoice::Tool("read".to_string())),
+        );
+        assert_eq!(
+            forced["toolConfig"],
+            serde_json::json!({
+                "tools": [{
+                    "toolSpec": {
+                        "name": "read",
+                        "description": "Read a file",
+                        "inputSchema": { "json": { "type": "object" } }
+                    }
+                }],
+                "toolChoice": { "tool": { "name": "read" } }
+            })
+        );
+
+        let required = body(
+            ChatRequest::new("anthropic.claude-test", vec![Message::user("hi")])
+                .with_tools(vec![read_tool()])
+                .with_tool_choice(ToolChoice::Required),
+        );
+        assert_eq!(
+            required["toolConfig"]["toolChoice"],
+            serde_json::json!({ "any": {} })
+        );
+
+        let none = body(
+            ChatRequest::new("anthropic.claude-test", vec![Message::user("hi")])
+                .with_tools(vec![read_tool()])
+                .with_tool_choice(ToolChoice::None),
+        );
+        assert!(none.get("toolConfig").is_none());
+    }
+}
@@ -1196,6 +1196,8 @@ pub struct ConfigProvider {
     pub blacklist: Option<Vec<String>>,
     #[serde(default)]
     pub whitelist: Option<Vec<String>>,
+    #[serde(default)]
+    pub rate_limit: Option<crate::governor::RateLimits>,
 }
 
 /// Top-level bootstrap configuration.
@@ -2408,6 +2410,20 @@ impl RuntimeProvider for AliasedProvider {
     ) -> Result<crate::StreamResult, crate::ProviderError> {
         self.inner.chat_stream(request).await
     }
+
+    async fn submit_batch(
+        &self,
+        items: Vec<crate::BatchItem>,
+    ) -> Result<crate::BatchHandle, crate::ProviderError> {
+        self.inner.submit_batch(items).await
+    }
+
+    async fn poll_batch(
+        &self,
+        handle: &crate::BatchHandle,
+    ) -> Result<crate::BatchPoll, crate::ProviderError> {
+        self.inner.poll_batch(handle).await
+    }
 }
 
 fn state_model_to_runtime(provider_id: &str, model: &ProviderModel) -> RuntimeModelInfo {
@@ -2425,6 +2441,11 @@ fn state_model_to_runtime(provider_id: &str, model: &ProviderModel) -> RuntimeMo
         supports_tools: model.capabilities.toolcall,
         cost_per_million_input: model.cost.input,
         cost_per_million_output: model.cost.output,
+        supports_batch: matches!(
+            (provider_id, model.api.npm.as_str()),
+            ("anthropic", "@ai-sdk/anthropic") | ("openai", "@ai-sdk/openai")
+        ),
+        supports_pdf: model.capabilities.input.pdf,
     }
 }
 
@@ -2550,7 +2571,7 @@ fn register_fallback_env_providers(registry: &mut ProviderRegistry) {
             models: HashMap::new(),
         };
         if let Some(provider) = create_concrete_provider(provider_id, &state) {
-            registry.register_arc(provider);
+            registry.register_arc(crate::governor::GovernedProvider::wrap(provider));
         }
     }
 }
@@ -2586,13 +2607,25 @@ fn bootstrap_registry(
 ) -> ProviderRegistry {
     let mut registry = ProviderRegistry::new();
 
+    crate::governor::configure(
+        config
+            .providers
+            .iter()
+            .filter_map(|(id, provider)| Some((id.clone(), provider.rate_limit?)))
+            .collect(),
+    );
+
     let models_dev = load_models_dev_cache();
     let state = ProviderBootstrapState::init(&models_dev, config, auth_store);
 
     for (provider_id, provider_state) in &state.providers {
         if let Some(provider) = create_concrete_provider(provider_id, provider_state) {
             let provider = wrap_provider_for_state(provider_state, provider);
+            let provider = crate::governor::GovernedProvider::wrap(provider);
             let registered_id = provider.id().to_string();
+            for (model_id, model) in &provider_state.models {
+                crate::pricing::register_model_cost(&registered_id, model_id, model.cost.clone());
+            }
             registry.register_arc(provider);
             if !provider_state.options.is_empty() {
                 registry.merge_config(&registered_id, provider_state.options.clone());
@@ -2823,6 +2856,15 @@ mod tests {
         }
     }
 
+    #[test]
+    fn runtime_model_carries_pdf_input_capability() {
+        let mut model = provider_model("doc-reader");
+        assert!(!state_model_to_runtime("test", &model).supports_pdf);
+
+        model.capabilities.input.pdf = true;
+        assert!(state_model_to_runtime("test", &model).supports_pdf);
+    }
+
     #[test]
     fn creates_openai_provider_from_state_key() {
         let mut state = provider_state("openai");
@@ -32,6 +32,8 @@ impl CerebrasProvider {
                     supports_tools: true,
                     cost_per_million_input: 0.6,
                     cost_per_million_output: 0.6,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "llama-3.1-8b".to_string(),
@@ -44,6 +46,8 @@ impl CerebrasProvider {
                     supports_tools: true,
                     cost_per_million_input: 0.1,
                     cost_per_million_output: 0.1,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
             ],
         }
@@ -85,8 +89,17 @@ impl Provider for CerebrasProvider {
         if let Some(tools) = &request.tools {
             if !tools.is_empty() {
                 body["tools"] = serde_json::json!(tools);
+                if let Some(choice) = &request.tool_choice {
+                    body["tool_choice"] = serde_json::json!(choice);
+                }
+                if let Some(parallel) = request.parallel_tool_calls {
+                    body["parallel_tool_calls"] = serde_json::json!(parallel);
+                }
             }
         }
+        if let Some(stop) = &request.stop {
+            body["stop"] = serde_json::json!(stop);
+        }
 
         let response = self
             .client
@@ -98,6 +111,7 @@ impl Provider for CerebrasProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_default();
             return Err(ProviderError::ApiError(error_text));
@@ -129,8 +143,17 @@ impl Provider for CerebrasProvider {
         if let Some(tools) = &request.tools {
             if !tools.is_empty() {
                 body["tools"] = serde_json::json!(tools);
+                if let Some(choice) = &request.tool_choice {
+                    body["tool_choice"] = serde_json::json!(choice);
+                }
+                if let Some(parallel) = request.parallel_tool_calls {
+                    body["parallel_tool_calls"] = serde_json::json!(parallel);
+                }
             }
         }
+        if let Some(stop) = &request.stop {
+            body["stop"] = serde_json::json!(stop);
+        }
 
         let response = self
             .client
@@ -142,6 +165,7 @@ impl Provider for CerebrasProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_default();
             return Err(ProviderError::ApiError(error_text));
@@ -32,6 +32,8 @@ impl DeepInfraProvider {
                     supports_tools: true,
                     cost_per_million_input: 0.59,
                     cost_per_million_output: 0.79,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "meta-llama/Llama-3.3-70B-Instruct".to_string(),
@@ -44,6 +46,8 @@ impl DeepInfraProvider {
                     supports_tools: true,
                     cost_per_million_input: 0.35,
                     cost_per_million_output: 0.40,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "mistralai/Mistral-Small-24B-Instruct-2501".to_string(),
@@ -56,6 +60,8 @@ impl DeepInfraProvider {
                     supports_tools: true,
                     cost_per_million_input: 0.10,
                     cost_per_million_output: 0.10,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "Qwen/Qwen2.5-72B-Instruct".to_string(),
@@ -68,6 +74,8 @@ impl DeepInfraProvider {
                     supports_tools: true,
                     cost_per_million_input: 0.35,
                     cost_per_million_output: 0.40,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
             ],
         }
@@ -107,8 +115,17 @@ impl Provider for DeepInfraProvider {
         if let Some(tools) = &request.tools {
             if !tools.is_empty() {
                 body["tools"] = serde_json::json!(tools);
+                if let Some(choice) = &request.tool_choice {
+                    body["tool_choice"] = serde_json::json!(choice);
+                }
+                if let Some(parallel) = request.parallel_tool_calls {
+                    body["parallel_tool_calls"] = serde_json::json!(parallel);
+                }
             }
         }
+        if let Some(stop) = &request.stop {
+            body["stop"] = serde_json::json!(stop);
+        }
 
         let response = self
             .client
@@ -120,6 +137,7 @@ impl Provider for DeepInfraProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_default();
             return Err(ProviderError::ApiError(error_text));
@@ -149,8 +167,17 @@ impl Provider for DeepInfraProvider {
         if let Some(tools) = &request.tools {
             if !tools.is_empty() {
                 body["tools"] = serde_json::json!(tools);
+                if let Some(choice) = &request.tool_choice {
+                    body["tool_choice"] = serde_json::json!(choice);
+                }
+                if let Some(parallel) = request.parallel_tool_calls {
+                    body["parallel_tool_calls"] = serde_json::json!(parallel);
+                }
             }
         }
+        if let Some(stop) = &request.stop {
+            body["stop"] = serde_json::json!(stop);
+        }
 
         let response = self
             .client
@@ -162,6 +189,7 @@ impl Provider for DeepInfraProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_default();
             return Err(ProviderError::ApiError(error_text));
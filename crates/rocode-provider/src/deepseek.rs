@@ -32,6 +32,8 @@ impl DeepSeekProvider {
                     supports_tools: true,
                     cost_per_million_input: 0.27,
                     cost_per_million_output: 1.1,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "deepseek-reasoner".to_string(),
@@ -44,6 +46,8 @@ impl DeepSeekProvider {
                     supports_tools: false,
                     cost_per_million_input: 0.55,
                     cost_per_million_output: 2.19,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
             ],
         }
@@ -76,6 +80,7 @@ impl Provider for DeepSeekProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -103,6 +108,7 @@ impl Provider for DeepSeekProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
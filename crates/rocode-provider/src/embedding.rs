@@ -0,0 +1,162 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::ProviderError;
+
+/// A model that turns text into dense vectors.
+#[async_trait]
+pub trait EmbeddingModel: Send + Sync {
+    /// Stable identifier (`provider/model`) stored alongside vectors so an
+    /// index built with one model is never queried with another.
+    fn id(&self) -> &str;
+
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, ProviderError>;
+}
+
+/// Base URLs of providers that expose an OpenAI-compatible `/embeddings`
+/// endpoint. Local runtimes need no API key.
+pub fn default_embedding_base_url(provider_id: &str) -> Option<&'static str> {
+    match provider_id {
+        "openai" => Some("https://api.openai.com/v1"),
+        "mistral" => Some("https://api.mistral.ai/v1"),
+        "together" | "togetherai" => Some("https://api.together.xyz/v1"),
+        "deepinfra" => Some("https://api.deepinfra.com/v1/openai"),
+        "voyage" | "voyageai" => Some("https://api.voyageai.com/v1"),
+        "ollama" => Some("http://localhost:11434/v1"),
+        "lmstudio" => Some("http://localhost:1234/v1"),
+        "llamacpp" | "llama.cpp" => Some("http://localhost:8080/v1"),
+        _ => None,
+    }
+}
+
+pub fn is_local_embedding_provider(provider_id: &str) -> bool {
+    matches!(
+        provider_id,
+        "ollama" | "lmstudio" | "llamacpp" | "llama.cpp"
+    )
+}
+
+/// Whether `model_id` names an embedding model rather than a chat model.
+/// Model catalogs do not flag embedding models, so this follows the same
+/// naming convention the bootstrap uses to keep them out of chat listings.
+pub fn is_embedding_model(model_id: &str) -> bool {
+    let lower = model_id.to_lowercase();
+    let name = lower.rsplit('/').next().unwrap_or(&lower);
+    name.contains("embed")
+        || name.starts_with("voyage-")
+        || name.starts_with("bge-")
+        || name.starts_with("gte-")
+        || name.starts_with("e5-")
+        || name.contains("minilm")
+}
+
+#[derive(Debug, Clone)]
+pub struct OpenAICompatibleEmbeddings {
+    client: Client,
+    id: String,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    dimensions: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dimensions: Option<u32>,
+    encoding_format: &'static str,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    #[serde(default)]
+    index: Option<usize>,
+    embedding: Vec<f32>,
+}
+
+impl OpenAICompatibleEmbeddings {
+    pub fn new(
+        provider_id: &str,
+        model: impl Into<String>,
+        base_url: impl Into<String>,
+        api_key: Option<String>,
+    ) -> Self {
+        let model = model.into();
+        Self {
+            client: Client::new(),
+            id: format!("{}/{}", provider_id, model),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: api_key.filter(|k| !k.trim().is_empty()),
+            model,
+            dimensions: None,
+        }
+    }
+
+    pub fn with_dimensions(mut self, dimensions: Option<u32>) -> Self {
+        self.dimensions = dimensions;
+        self
+    }
+}
+
+#[async_trait]
+impl EmbeddingModel for OpenAICompatibleEmbeddings {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
+        if inputs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let body = EmbeddingRequest {
+            model: &self.model,
+            input: inputs,
+            dimensions: self.dimensions,
+            encoding_format: "float",
+        };
+        let mut request = self
+            .client
+            .post(format!("{}/embeddings", self.base_url))
+            .header("Content-Type", "application/json")
+            .json(&body);
+        if let Some(key) = &self.api_key {
+            request = request.header("Authorization", format!("Bearer {}", key));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+        let status = response.status();
+        if status.as_u16() == 429 {
+            return Err(ProviderError::RateLimit);
+        }
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(ProviderError::api_error_with_status(body, status.as_u16()));
+        }
+
+        let mut parsed: EmbeddingResponse = response
+            .json()
+            .await
+            .map_err(|e| ProviderError::ApiError(e.to_string()))?;
+        if parsed.data.len() != inputs.len() {
+            return Err(ProviderError::ApiError(format!(
+                "embedding endpoint returned {} vectors for {} inputs",
+                parsed.data.len(),
+                inputs.len()
+            )));
+        }
+        parsed.data.sort_by_key(|d| d.index.unwrap_or(usize::MAX));
+        Ok(parsed.data.into_iter().map(|d| d.embedding).collect())
+    }
+}
@@ -84,6 +84,8 @@ impl GitHubCopilotProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.0,
                 cost_per_million_output: 0.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "gpt-4o-mini".to_string(),
@@ -96,6 +98,8 @@ impl GitHubCopilotProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.0,
                 cost_per_million_output: 0.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "claude-3.5-sonnet".to_string(),
@@ -108,6 +112,8 @@ impl GitHubCopilotProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.0,
                 cost_per_million_output: 0.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "claude-3.5-haiku".to_string(),
@@ -120,6 +126,8 @@ impl GitHubCopilotProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.0,
                 cost_per_million_output: 0.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "o1".to_string(),
@@ -132,6 +140,8 @@ impl GitHubCopilotProvider {
                 supports_tools: false,
                 cost_per_million_input: 0.0,
                 cost_per_million_output: 0.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "o1-mini".to_string(),
@@ -144,6 +154,8 @@ impl GitHubCopilotProvider {
                 supports_tools: false,
                 cost_per_million_input: 0.0,
                 cost_per_million_output: 0.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
         ];
 
@@ -204,6 +216,7 @@ impl GitHubCopilotProvider {
             max_tokens: request.max_tokens,
             temperature: request.temperature,
             top_p: request.top_p,
+            stop: request.stop,
             stream: false,
         }
     }
@@ -279,11 +292,14 @@ impl GitHubCopilotProvider {
                 copilot_reasoning_effort(&request.model, request.variant.as_deref())
                     .map(ToString::to_string);
         }
+        if provider_options.parallel_tool_calls.is_none() {
+            provider_options.parallel_tool_calls = request.parallel_tool_calls;
+        }
 
         GenerateOptions {
             prompt,
             tools: Self::tools_to_input_tools(request.tools.as_ref()),
-            tool_choice: None,
+            tool_choice: request.tool_choice.as_ref().map(Into::into),
             max_output_tokens: request.max_tokens,
             temperature: request.temperature,
             top_p: request.top_p,
@@ -291,7 +307,7 @@ impl GitHubCopilotProvider {
             seed: None,
             presence_penalty: None,
             frequency_penalty: None,
-            stop_sequences: None,
+            stop_sequences: request.stop.clone(),
             provider_options: Some(provider_options),
             response_format: None,
         }
@@ -387,6 +403,7 @@ impl GitHubCopilotProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -427,6 +444,7 @@ impl GitHubCopilotProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -580,6 +598,8 @@ struct CopilotRequest {
     temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<Vec<String>>,
     stream: bool,
 }
 
@@ -44,6 +44,8 @@ impl GitLabProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.0,
                 cost_per_million_output: 0.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "claude-3-5-haiku-20241022".to_string(),
@@ -56,6 +58,8 @@ impl GitLabProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.0,
                 cost_per_million_output: 0.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "code-suggestions".to_string(),
@@ -68,6 +72,8 @@ impl GitLabProvider {
                 supports_tools: false,
                 cost_per_million_input: 0.0,
                 cost_per_million_output: 0.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
         ];
 
@@ -117,6 +123,7 @@ impl GitLabProvider {
             messages,
             max_tokens: request.max_tokens,
             temperature: request.temperature,
+            stop: request.stop,
             stream: false,
         }
     }
@@ -154,6 +161,7 @@ impl Provider for GitLabProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -187,6 +195,7 @@ impl Provider for GitLabProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -229,6 +238,8 @@ struct GitLabRequest {
     max_tokens: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<Vec<String>>,
     stream: bool,
 }
 
@@ -1,11 +1,14 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use futures::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
+use crate::recorder::{ExchangeRecorder, WireFormat};
 use crate::{
     ChatRequest, ChatResponse, Choice, Content, Message, ModelInfo, Provider, ProviderError, Role,
-    StreamEvent, StreamResult, Usage,
+    StreamEvent, StreamResult, ToolChoice, ToolDefinition, Usage,
 };
 
 const GOOGLE_API_URL: &str = "https://generativelanguage.googleapis.com/v1beta/models";
@@ -44,6 +47,8 @@ impl GoogleProvider {
                 supports_tools: true,
                 cost_per_million_input: 1.25,
                 cost_per_million_output: 10.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "gemini-2.0-flash".to_string(),
@@ -56,6 +61,8 @@ impl GoogleProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.1,
                 cost_per_million_output: 0.4,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "gemini-2.0-flash-lite".to_string(),
@@ -68,6 +75,8 @@ impl GoogleProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.075,
                 cost_per_million_output: 0.3,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "gemini-1.5-pro".to_string(),
@@ -80,6 +89,8 @@ impl GoogleProvider {
                 supports_tools: true,
                 cost_per_million_input: 1.25,
                 cost_per_million_output: 5.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "gemini-1.5-flash".to_string(),
@@ -92,6 +103,8 @@ impl GoogleProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.075,
                 cost_per_million_output: 0.3,
+                supports_batch: false,
+                supports_pdf: false,
             },
         ];
 
@@ -148,12 +161,40 @@ impl GoogleProvider {
             }
         }
 
+        let tools = request
+            .tools
+            .as_deref()
+            .filter(|tools| !tools.is_empty())
+            .map(|tools| {
+                vec![GoogleTool {
+                    function_declarations: tools
+                        .iter()
+                        .map(GoogleFunctionDeclaration::from)
+                        .collect(),
+                }]
+            });
+        let tool_config = tools
+            .as_ref()
+            .and(request.tool_choice.as_ref())
+            .map(GoogleToolConfig::from);
+
         GoogleRequest {
             contents,
             system_instruction,
+            tools,
+            tool_config,
             generation_config: Some(GenerationConfig {
                 max_output_tokens: request.max_tokens,
                 temperature: request.temperature,
+                response_mime_type: request
+                    .response_format
+                    .as_ref()
+                    .map(|_| "application/json".to_string()),
+                response_schema: request
+                    .response_format
+                    .as_ref()
+                    .map(|format| format.gemini_schema()),
+                stop_sequences: request.stop.clone(),
             }),
         }
     }
@@ -195,6 +236,7 @@ impl Provider for GoogleProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -217,43 +259,65 @@ impl Provider for GoogleProvider {
         );
 
         let google_request = self.convert_request(request);
+        let body = serde_json::to_string(&google_request)
+            .map_err(|e| ProviderError::InvalidRequest(format!("failed to encode body: {}", e)))?;
+        let headers = HashMap::from([
+            ("Content-Type".to_string(), "application/json".to_string()),
+            ("Accept".to_string(), "text/event-stream".to_string()),
+        ]);
+        let recorder =
+            ExchangeRecorder::begin(self.id(), WireFormat::Google, "POST", &url, &headers, &body);
+
+        let mut builder = self.client.post(&url);
+        for (name, value) in &headers {
+            builder = builder.header(name, value);
+        }
+        let response = builder.body(body).send().await.map_err(|e| {
+            if let Some(recorder) = &recorder {
+                recorder.error(&e.to_string());
+            }
+            ProviderError::NetworkError(e.to_string())
+        })?;
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .header("Accept", "text/event-stream")
-            .json(&google_request)
-            .send()
-            .await
-            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
-
+        crate::governor::observe(&response);
+        if let Some(recorder) = &recorder {
+            recorder.response(response.status().as_u16(), response.headers());
+        }
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
+            if let Some(recorder) = &recorder {
+                recorder.body(&body);
+            }
             return Err(ProviderError::ApiError(format!("{}: {}", status, body)));
         }
 
-        let stream = response
-            .bytes_stream()
-            .map(move |chunk_result| match chunk_result {
-                Ok(bytes) => {
-                    let text = String::from_utf8_lossy(&bytes);
-                    for line in text.lines() {
-                        if line.starts_with("data: ") {
-                            let data = &line[6..];
-                            if let Some(event) = parse_google_sse(data) {
-                                return Ok(event);
-                            }
-                        }
+        let chunks = response.bytes_stream().map(|chunk| match chunk {
+            Ok(bytes) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+            Err(e) => Err(ProviderError::StreamError(e.to_string())),
+        });
+        Ok(google_event_stream(crate::recorder::tap(chunks, recorder)))
+    }
+}
+
+/// Maps each SSE chunk to the first event it carries.
+pub(crate) fn google_event_stream<S>(chunks: S) -> StreamResult
+where
+    S: futures::Stream<Item = Result<String, ProviderError>> + Send + 'static,
+{
+    Box::pin(chunks.map(|chunk_result| match chunk_result {
+        Ok(text) => {
+            for line in text.lines() {
+                if let Some(data) = line.strip_prefix("data: ") {
+                    if let Some(event) = parse_google_sse(data) {
+                        return Ok(event);
                     }
-                    Ok(StreamEvent::TextDelta(String::new()))
                 }
-                Err(e) => Err(ProviderError::StreamError(e.to_string())),
-            });
-
-        Ok(Box::pin(stream))
-    }
+            }
+            Ok(StreamEvent::TextDelta(String::new()))
+        }
+        Err(e) => Err(e),
+    }))
 }
 
 #[derive(Debug, Serialize)]
@@ -262,9 +326,69 @@ struct GoogleRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     system_instruction: Option<GoogleContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<GoogleTool>>,
+    #[serde(rename = "toolConfig", skip_serializing_if = "Option::is_none")]
+    tool_config: Option<GoogleToolConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     generation_config: Option<GenerationConfig>,
 }
 
+#[derive(Debug, Serialize)]
+struct GoogleTool {
+    function_declarations: Vec<GoogleFunctionDeclaration>,
+}
+
+#[derive(Debug, Serialize)]
+struct GoogleFunctionDeclaration {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    parameters: serde_json::Value,
+}
+
+impl From<&ToolDefinition> for GoogleFunctionDeclaration {
+    fn from(tool: &ToolDefinition) -> Self {
+        Self {
+            name: tool.name.clone(),
+            description: tool.description.clone(),
+            parameters: tool.parameters.clone(),
+        }
+    }
+}
+
+/// Gemini has no switch for parallel function calls, so only `tool_choice`
+/// reaches the request; `parallel_tool_calls` is not sent.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleToolConfig {
+    function_calling_config: GoogleFunctionCallingConfig,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleFunctionCallingConfig {
+    mode: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allowed_function_names: Option<Vec<String>>,
+}
+
+impl From<&ToolChoice> for GoogleToolConfig {
+    fn from(choice: &ToolChoice) -> Self {
+        let (mode, allowed_function_names) = match choice {
+            ToolChoice::Auto => ("AUTO", None),
+            ToolChoice::None => ("NONE", None),
+            ToolChoice::Required => ("ANY", None),
+            ToolChoice::Tool(name) => ("ANY", Some(vec![name.clone()])),
+        };
+        Self {
+            function_calling_config: GoogleFunctionCallingConfig {
+                mode,
+                allowed_function_names,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct GoogleContent {
     parts: Vec<GooglePart>,
@@ -291,6 +415,12 @@ struct GenerationConfig {
     max_output_tokens: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_schema: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_sequences: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -361,3 +491,57 @@ fn parse_google_sse(data: &str) -> Option<StreamEvent> {
 
     Some(StreamEvent::TextDelta(text))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_tool() -> ToolDefinition {
+        ToolDefinition {
+            name: "read".to_string(),
+            description: Some("Read a file".to_string()),
+            parameters: serde_json::json!({ "type": "object" }),
+        }
+    }
+
+    #[test]
+    fn tool_choice_maps_to_function_calling_config() {
+        let provider = GoogleProvider::new("test-key");
+        let body = |request: ChatRequest| {
+            serde_json::to_value(provider.convert_request(request)).expect("serialize request")
+        };
+
+        let forced = body(
+            ChatRequest::new("gemini-test", vec![Message::user("hi")])
+                .with_tools(vec![read_tool()])
+                .with_tool_choice(ToolChoice::Tool("read".to_string())),
+        );
+        assert_eq!(
+            forced["tools"][0]["function_declarations"][0]["name"],
+            serde_json::json!("read")
+        );
+        assert_eq!(
+            forced["toolConfig"],
+            serde_json::json!({
+                "functionCallingConfig": { "mode": "ANY", "allowedFunctionNames": ["read"] }
+            })
+        );
+
+        let none = body(
+            ChatRequest::new("gemini-test", vec![Message::user("hi")])
+                .with_tools(vec![read_tool()])
+                .with_tool_choice(ToolChoice::None),
+        );
+        assert_eq!(
+            none["toolConfig"],
+            serde_json::json!({ "functionCallingConfig": { "mode": "NONE" } })
+        );
+
+        let untooled = body(
+            ChatRequest::new("gemini-test", vec![Message::user("hi")])
+                .with_tool_choice(ToolChoice::Required),
+        );
+        assert!(untooled.get("tools").is_none());
+        assert!(untooled.get("toolConfig").is_none());
+    }
+}
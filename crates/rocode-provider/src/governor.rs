@@ -0,0 +1,919 @@
+//! Client-side rate limiting shared by every caller of a provider.
+//!
+//! Sessions, subagents and the batch command all reach providers through the
+//! registry, so limits are enforced once here instead of by each caller. Every
+//! provider/model pair gets a [`Governor`] combining a request bucket, a token
+//! bucket and an in-flight cap. Governors tighten themselves from the
+//! rate-limit headers providers send back, and hand out turns round-robin by
+//! session so one busy session cannot starve the others.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use tokio::sync::oneshot;
+
+use crate::message::{ChatRequest, ChatResponse, Content};
+use crate::provider::{ModelInfo, Provider, ProviderError};
+use crate::stream::{StreamEvent, StreamResult};
+
+/// Configured limits for one provider, applied to each of its models.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimits {
+    #[serde(default, alias = "requestsPerMinute")]
+    pub requests_per_minute: Option<u32>,
+    #[serde(default, alias = "tokensPerMinute")]
+    pub tokens_per_minute: Option<u32>,
+    #[serde(default, alias = "maxInFlight")]
+    pub max_in_flight: Option<u32>,
+}
+
+/// Point-in-time view of one governor, for status displays.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GovernorSnapshot {
+    /// `provider/model`.
+    pub key: String,
+    /// Effective limits: configured values tightened by learned ones.
+    pub limits: RateLimits,
+    pub in_flight: u32,
+    pub queued: usize,
+    pub sessions_waiting: usize,
+    /// Age of the oldest request still waiting for a turn.
+    pub oldest_wait_ms: u64,
+    pub last_wait_ms: u64,
+    pub avg_wait_ms: u64,
+    pub max_wait_ms: u64,
+    pub granted: u64,
+    /// Time left on a provider-imposed pause (429 or exhausted quota).
+    pub cooldown_ms: u64,
+}
+
+static LIMITS: Lazy<RwLock<HashMap<String, RateLimits>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+static GOVERNORS: Lazy<RwLock<HashMap<String, Arc<Governor>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+tokio::task_local! {
+    static ACTIVE: Arc<Governor>;
+}
+
+/// Replaces the configured limits for all providers. Existing governors pick
+/// up the new values immediately; providers missing from `limits` become
+/// unlimited apart from what their headers advertise.
+pub fn configure(limits: HashMap<String, RateLimits>) {
+    let governors: Vec<Arc<Governor>> = GOVERNORS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+        .cloned()
+        .collect();
+    for governor in governors {
+        let configured = limits
+            .get(&governor.provider_id)
+            .copied()
+            .unwrap_or_default();
+        governor.set_configured(configured);
+    }
+    *LIMITS.write().unwrap_or_else(|e| e.into_inner()) = limits;
+}
+
+/// Configured limits for `provider_id`.
+pub fn limits(provider_id: &str) -> RateLimits {
+    LIMITS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(provider_id)
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Replaces the configured limits of a single provider.
+pub fn set_limits(provider_id: &str, limits: RateLimits) {
+    LIMITS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(provider_id.to_string(), limits);
+    let governors: Vec<Arc<Governor>> = GOVERNORS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+        .filter(|governor| governor.provider_id == provider_id)
+        .cloned()
+        .collect();
+    for governor in governors {
+        governor.set_configured(limits);
+    }
+}
+
+/// Returns the governor for `provider_id`/`model`, creating it on first use.
+pub fn governor_for(provider_id: &str, model: &str) -> Arc<Governor> {
+    let key = format!("{}/{}", provider_id, model);
+    if let Some(governor) = GOVERNORS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&key)
+    {
+        return governor.clone();
+    }
+    let configured = limits(provider_id);
+    GOVERNORS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(key.clone())
+        .or_insert_with(|| Arc::new(Governor::new(key, provider_id, configured)))
+        .clone()
+}
+
+/// Snapshots of every governor that has seen traffic, sorted by key.
+pub fn snapshot() -> Vec<GovernorSnapshot> {
+    let mut snapshots: Vec<GovernorSnapshot> = GOVERNORS
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .values()
+        .map(|governor| governor.snapshot())
+        .collect();
+    snapshots.sort_by(|a, b| a.key.cmp(&b.key));
+    snapshots
+}
+
+/// Feeds a provider response into the governor of the call in progress, if
+/// the call was made through a [`GovernedProvider`].
+pub(crate) fn observe(response: &reqwest::Response) {
+    let _ = ACTIVE.try_with(|governor| {
+        governor.observe(response.status().as_u16(), response.headers());
+    });
+}
+
+/// Rough input size of a request, in the chars/4 units used for compaction.
+pub fn estimate_tokens(request: &ChatRequest) -> u64 {
+    let mut chars = request.system.as_deref().map(str::len).unwrap_or(0);
+    for message in &request.messages {
+        chars += match &message.content {
+            Content::Text(text) => text.len(),
+            Content::Parts(parts) => serde_json::to_string(parts).map(|s| s.len()).unwrap_or(0),
+        };
+    }
+    (chars / 4) as u64
+}
+
+struct Bucket {
+    per_minute: f64,
+    level: f64,
+    refilled: Instant,
+}
+
+impl Bucket {
+    fn new(per_minute: u32, now: Instant) -> Self {
+        Self {
+            per_minute: per_minute as f64,
+            level: per_minute as f64,
+            refilled: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled).as_secs_f64();
+        self.level = (self.level + elapsed * self.per_minute / 60.0).min(self.per_minute);
+        self.refilled = now;
+    }
+
+    /// Time until `amount` is available. Requests larger than the whole
+    /// bucket only wait for it to be full, otherwise they could never run.
+    fn wait_for(&self, amount: f64) -> Duration {
+        let needed = amount.min(self.per_minute) - self.level;
+        if needed <= 0.0 || self.per_minute <= 0.0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(needed * 60.0 / self.per_minute)
+    }
+
+    fn resize(&mut self, per_minute: u32) {
+        self.per_minute = per_minute as f64;
+        self.level = self.level.min(self.per_minute);
+    }
+}
+
+struct Waiter {
+    tokens: u64,
+    enqueued: Instant,
+    grant: oneshot::Sender<Permit>,
+}
+
+struct Lane {
+    session: String,
+    waiters: VecDeque<Waiter>,
+}
+
+enum Readiness {
+    Now,
+    After(Duration),
+    /// At the in-flight cap; a finishing request will pump the queue.
+    Saturated,
+}
+
+#[derive(Default)]
+struct WaitStats {
+    granted: u64,
+    total: Duration,
+    last: Duration,
+    max: Duration,
+}
+
+struct State {
+    configured: RateLimits,
+    learned: RateLimits,
+    requests: Option<Bucket>,
+    tokens: Option<Bucket>,
+    in_flight: u32,
+    cooldown_until: Option<Instant>,
+    lanes: VecDeque<Lane>,
+    wake_at: Option<Instant>,
+    stats: WaitStats,
+}
+
+impl State {
+    fn effective(&self) -> RateLimits {
+        fn tighter(a: Option<u32>, b: Option<u32>) -> Option<u32> {
+            match (a, b) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            }
+        }
+        RateLimits {
+            requests_per_minute: tighter(
+                self.configured.requests_per_minute,
+                self.learned.requests_per_minute,
+            ),
+            tokens_per_minute: tighter(
+                self.configured.tokens_per_minute,
+                self.learned.tokens_per_minute,
+            ),
+            max_in_flight: self.configured.max_in_flight,
+        }
+    }
+
+    fn apply_limits(&mut self, now: Instant) {
+        let limits = self.effective();
+        sync_bucket(&mut self.requests, limits.requests_per_minute, now);
+        sync_bucket(&mut self.tokens, limits.tokens_per_minute, now);
+    }
+
+    fn readiness(&mut self, tokens: u64, now: Instant) -> Readiness {
+        if let Some(max) = self.configured.max_in_flight {
+            if self.in_flight >= max.max(1) {
+                return Readiness::Saturated;
+            }
+        }
+        let mut wait = self
+            .cooldown_until
+            .map(|until| until.saturating_duration_since(now))
+            .unwrap_or_default();
+        if let Some(bucket) = self.requests.as_mut() {
+            bucket.refill(now);
+            wait = wait.max(bucket.wait_for(1.0));
+        }
+        if let Some(bucket) = self.tokens.as_mut() {
+            bucket.refill(now);
+            wait = wait.max(bucket.wait_for(tokens as f64));
+        }
+        if wait.is_zero() {
+            Readiness::Now
+        } else {
+            Readiness::After(wait)
+        }
+    }
+
+    fn extend_cooldown(&mut self, until: Instant) {
+        if self.cooldown_until.is_none_or(|current| current < until) {
+            self.cooldown_until = Some(until);
+        }
+    }
+}
+
+fn sync_bucket(bucket: &mut Option<Bucket>, per_minute: Option<u32>, now: Instant) {
+    match (bucket.as_mut(), per_minute) {
+        (Some(existing), Some(limit)) => existing.resize(limit),
+        (None, Some(limit)) => *bucket = Some(Bucket::new(limit, now)),
+        (_, None) => *bucket = None,
+    }
+}
+
+/// Shared limiter for one provider/model pair.
+pub struct Governor {
+    key: String,
+    provider_id: String,
+    state: Mutex<State>,
+}
+
+impl Governor {
+    fn new(key: String, provider_id: &str, configured: RateLimits) -> Self {
+        let mut state = State {
+            configured,
+            learned: RateLimits::default(),
+            requests: None,
+            tokens: None,
+            in_flight: 0,
+            cooldown_until: None,
+            lanes: VecDeque::new(),
+            wake_at: None,
+            stats: WaitStats::default(),
+        };
+        state.apply_limits(Instant::now());
+        Self {
+            key,
+            provider_id: provider_id.to_string(),
+            state: Mutex::new(state),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Waits for a turn. Callers from the same `session` queue behind each
+    /// other; different sessions take turns. `tokens` is the estimated input
+    /// size, corrected later through [`Permit::settle`].
+    pub async fn acquire(self: &Arc<Self>, session: &str, tokens: u64) -> Permit {
+        let (grant, granted) = oneshot::channel();
+        {
+            let mut state = self.lock();
+            let waiter = Waiter {
+                tokens,
+                enqueued: Instant::now(),
+                grant,
+            };
+            match state.lanes.iter_mut().find(|lane| lane.session == session) {
+                Some(lane) => lane.waiters.push_back(waiter),
+                None => state.lanes.push_back(Lane {
+                    session: session.to_string(),
+                    waiters: VecDeque::from([waiter]),
+                }),
+            }
+            self.pump(&mut state);
+        }
+        // The sender lives in the queue until it is granted, so this only
+        // fails if the process is tearing down; run unthrottled then.
+        granted.await.unwrap_or_else(|_| Permit {
+            governor: None,
+            charged: tokens,
+            waited: Duration::ZERO,
+        })
+    }
+
+    /// Grants turns in round-robin session order until the head of the queue
+    /// has to wait, then arranges to be woken when it can proceed.
+    fn pump(self: &Arc<Self>, state: &mut State) {
+        let now = Instant::now();
+        loop {
+            let Some(lane) = state.lanes.front_mut() else {
+                return;
+            };
+            while lane
+                .waiters
+                .front()
+                .is_some_and(|waiter| waiter.grant.is_closed())
+            {
+                lane.waiters.pop_front();
+            }
+            let Some(tokens) = lane.waiters.front().map(|waiter| waiter.tokens) else {
+                state.lanes.pop_front();
+                continue;
+            };
+
+            match state.readiness(tokens, now) {
+                Readiness::Saturated => return,
+                Readiness::After(delay) => {
+                    self.schedule_wake(state, now + delay);
+                    return;
+                }
+                Readiness::Now => {}
+            }
+
+            let mut lane = state.lanes.pop_front().expect("front lane checked above");
+            let waiter = lane
+                .waiters
+                .pop_front()
+                .expect("front waiter checked above");
+            if !lane.waiters.is_empty() {
+                state.lanes.push_back(lane);
+            }
+
+            if let Some(bucket) = state.requests.as_mut() {
+                bucket.level -= 1.0;
+            }
+            if let Some(bucket) = state.tokens.as_mut() {
+                bucket.level -= waiter.tokens as f64;
+            }
+            state.in_flight += 1;
+            let waited = now.saturating_duration_since(waiter.enqueued);
+            state.stats.granted += 1;
+            state.stats.total += waited;
+            state.stats.last = waited;
+            state.stats.max = state.stats.max.max(waited);
+
+            let permit = Permit {
+                governor: Some(self.clone()),
+                charged: waiter.tokens,
+                waited,
+            };
+            if let Err(mut permit) = waiter.grant.send(permit) {
+                // The caller gave up between the check above and now; hand
+                // the turn back without re-entering the lock from `Drop`.
+                permit.governor = None;
+                state.in_flight -= 1;
+                if let Some(bucket) = state.requests.as_mut() {
+                    bucket.level += 1.0;
+                }
+                if let Some(bucket) = state.tokens.as_mut() {
+                    bucket.level += waiter.tokens as f64;
+                }
+            }
+        }
+    }
+
+    fn schedule_wake(self: &Arc<Self>, state: &mut State, at: Instant) {
+        if state.wake_at.is_some_and(|pending| pending <= at) {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        state.wake_at = Some(at);
+        let governor = self.clone();
+        runtime.spawn(async move {
+            tokio::time::sleep_until(at.into()).await;
+            let mut state = governor.lock();
+            if state.wake_at == Some(at) {
+                state.wake_at = None;
+            }
+            governor.pump(&mut state);
+        });
+    }
+
+    fn set_configured(self: &Arc<Self>, configured: RateLimits) {
+        let mut state = self.lock();
+        state.configured = configured;
+        state.apply_limits(Instant::now());
+        self.pump(&mut state);
+    }
+
+    /// Learns limits and pauses from OpenAI-style `x-ratelimit-*` and
+    /// Anthropic `anthropic-ratelimit-*` headers, and from 429 responses.
+    fn observe(self: &Arc<Self>, status: u16, headers: &HeaderMap) {
+        let now = Instant::now();
+        let mut state = self.lock();
+
+        let requests = header_number(
+            headers,
+            &[
+                "x-ratelimit-limit-requests",
+                "anthropic-ratelimit-requests-limit",
+            ],
+        );
+        let tokens = header_number(
+            headers,
+            &[
+                "x-ratelimit-limit-tokens",
+                "anthropic-ratelimit-input-tokens-limit",
+                "anthropic-ratelimit-tokens-limit",
+            ],
+        );
+        let learned = RateLimits {
+            requests_per_minute: requests.map(|n| n.min(u32::MAX as u64) as u32),
+            tokens_per_minute: tokens.map(|n| n.min(u32::MAX as u64) as u32),
+            max_in_flight: None,
+        };
+        if learned.requests_per_minute.is_some() || learned.tokens_per_minute.is_some() {
+            state.learned = RateLimits {
+                requests_per_minute: learned
+                    .requests_per_minute
+                    .or(state.learned.requests_per_minute),
+                tokens_per_minute: learned
+                    .tokens_per_minute
+                    .or(state.learned.tokens_per_minute),
+                max_in_flight: None,
+            };
+            state.apply_limits(now);
+        }
+
+        let exhausted = [
+            (
+                [
+                    "x-ratelimit-remaining-requests",
+                    "anthropic-ratelimit-requests-remaining",
+                ],
+                [
+                    "x-ratelimit-reset-requests",
+                    "anthropic-ratelimit-requests-reset",
+                ],
+            ),
+            (
+                [
+                    "x-ratelimit-remaining-tokens",
+                    "anthropic-ratelimit-tokens-remaining",
+                ],
+                [
+                    "x-ratelimit-reset-tokens",
+                    "anthropic-ratelimit-tokens-reset",
+                ],
+            ),
+        ];
+        for (remaining, reset) in exhausted {
+            if header_number(headers, &remaining) == Some(0) {
+                if let Some(delay) = reset
+                    .iter()
+                    .find_map(|name| headers.get(*name)?.to_str().ok())
+                    .and_then(parse_reset)
+                {
+                    state.extend_cooldown(now + delay);
+                }
+            }
+        }
+
+        if status == 429 {
+            let retry_headers: HashMap<String, String> = headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.as_str().to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect();
+            let delay = crate::retry::delay(1, Some(&retry_headers));
+            state.extend_cooldown(now + Duration::from_millis(delay));
+        }
+
+        self.pump(&mut state);
+    }
+
+    /// Pauses the governor after a rate-limit error from a provider whose
+    /// adapter does not report headers, unless a pause is already running.
+    fn penalize(self: &Arc<Self>) {
+        let now = Instant::now();
+        let mut state = self.lock();
+        if state.cooldown_until.is_some_and(|until| until > now) {
+            return;
+        }
+        state.extend_cooldown(now + Duration::from_millis(crate::retry::delay(1, None)));
+    }
+
+    pub fn snapshot(&self) -> GovernorSnapshot {
+        let now = Instant::now();
+        let state = self.lock();
+        let oldest = state
+            .lanes
+            .iter()
+            .filter_map(|lane| lane.waiters.front())
+            .map(|waiter| now.saturating_duration_since(waiter.enqueued))
+            .max()
+            .unwrap_or_default();
+        let avg = if state.stats.granted == 0 {
+            Duration::ZERO
+        } else {
+            state.stats.total / state.stats.granted.min(u32::MAX as u64) as u32
+        };
+        GovernorSnapshot {
+            key: self.key.clone(),
+            limits: state.effective(),
+            in_flight: state.in_flight,
+            queued: state.lanes.iter().map(|lane| lane.waiters.len()).sum(),
+            sessions_waiting: state.lanes.len(),
+            oldest_wait_ms: oldest.as_millis() as u64,
+            last_wait_ms: state.stats.last.as_millis() as u64,
+            avg_wait_ms: avg.as_millis() as u64,
+            max_wait_ms: state.stats.max.as_millis() as u64,
+            granted: state.stats.granted,
+            cooldown_ms: state
+                .cooldown_until
+                .map(|until| until.saturating_duration_since(now).as_millis() as u64)
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// A granted turn. Dropping it frees the in-flight slot.
+pub struct Permit {
+    governor: Option<Arc<Governor>>,
+    charged: u64,
+    waited: Duration,
+}
+
+impl Permit {
+    /// How long the request queued before being granted.
+    pub fn waited(&self) -> Duration {
+        self.waited
+    }
+
+    /// Corrects the token bucket once the provider reports real usage.
+    pub fn settle(&mut self, actual_tokens: u64) {
+        let Some(governor) = &self.governor else {
+            return;
+        };
+        let mut state = governor.lock();
+        if let Some(bucket) = state.tokens.as_mut() {
+            bucket.level -= actual_tokens as f64 - self.charged as f64;
+            bucket.level = bucket.level.min(bucket.per_minute);
+        }
+        self.charged = actual_tokens;
+    }
+}
+
+impl Drop for Permit {
+    fn drop(&mut self) {
+        let Some(governor) = self.governor.take() else {
+            return;
+        };
+        let mut state = governor.lock();
+        state.in_flight = state.in_flight.saturating_sub(1);
+        governor.pump(&mut state);
+    }
+}
+
+fn header_number(headers: &HeaderMap, names: &[&str]) -> Option<u64> {
+    names
+        .iter()
+        .find_map(|name| headers.get(*name)?.to_str().ok()?.trim().parse().ok())
+}
+
+/// Parses a reset header: an RFC 3339 timestamp (Anthropic), a Go-style
+/// duration such as `6m0s` or `20ms` (OpenAI), or plain seconds.
+fn parse_reset(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(at) = chrono::DateTime::parse_from_rfc3339(value) {
+        let ms = at
+            .signed_duration_since(chrono::Utc::now())
+            .num_milliseconds();
+        return Some(Duration::from_millis(ms.max(0) as u64));
+    }
+    if let Ok(secs) = value.parse::<f64>() {
+        return (secs.is_finite() && secs >= 0.0).then(|| Duration::from_secs_f64(secs));
+    }
+
+    let mut total = 0.0;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..digits].parse().ok()?;
+        rest = &rest[digits..];
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len());
+        let scale = match &rest[..unit_len] {
+            "ms" => 0.001,
+            "s" => 1.0,
+            "m" => 60.0,
+            "h" => 3600.0,
+            _ => return None,
+        };
+        total += number * scale;
+        rest = &rest[unit_len..];
+    }
+    Some(Duration::from_secs_f64(total))
+}
+
+/// Registry wrapper that routes every chat call through the governor of its
+/// provider/model. Batch submission is passed through untouched since those
+/// APIs have separate quotas.
+pub struct GovernedProvider {
+    inner: Arc<dyn Provider>,
+}
+
+impl GovernedProvider {
+    pub fn wrap(inner: Arc<dyn Provider>) -> Arc<dyn Provider> {
+        Arc::new(Self { inner })
+    }
+
+    async fn admit(&self, request: &ChatRequest) -> (Arc<Governor>, Permit) {
+        let governor = governor_for(self.inner.id(), &request.model);
+        let session = crate::recorder::current_session().unwrap_or_default();
+        let permit = governor.acquire(&session, estimate_tokens(request)).await;
+        if !permit.waited().is_zero() {
+            tracing::debug!(
+                governor = %governor.key,
+                session = %session,
+                waited_ms = permit.waited().as_millis() as u64,
+                "Provider request queued by rate governor"
+            );
+        }
+        (governor, permit)
+    }
+}
+
+fn is_rate_limited(error: &ProviderError) -> bool {
+    matches!(
+        error,
+        ProviderError::RateLimit
+            | ProviderError::ApiErrorWithStatus {
+                status_code: 429,
+                ..
+            }
+    )
+}
+
+#[async_trait]
+impl Provider for GovernedProvider {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn models(&self) -> Vec<ModelInfo> {
+        self.inner.models()
+    }
+
+    fn get_model(&self, id: &str) -> Option<&ModelInfo> {
+        self.inner.get_model(id)
+    }
+
+    async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, ProviderError> {
+        let (governor, mut permit) = self.admit(&request).await;
+        let result = ACTIVE
+            .scope(governor.clone(), self.inner.chat(request))
+            .await;
+        match &result {
+            Ok(response) => {
+                if let Some(usage) = &response.usage {
+                    permit.settle(usage.prompt_tokens + usage.completion_tokens);
+                }
+            }
+            Err(error) if is_rate_limited(error) => governor.penalize(),
+            Err(_) => {}
+        }
+        result
+    }
+
+    async fn chat_stream(&self, request: ChatRequest) -> Result<StreamResult, ProviderError> {
+        let (governor, mut permit) = self.admit(&request).await;
+        let stream = match ACTIVE
+            .scope(governor.clone(), self.inner.chat_stream(request))
+            .await
+        {
+            Ok(stream) => stream,
+            Err(error) => {
+                if is_rate_limited(&error) {
+                    governor.penalize();
+                }
+                return Err(error);
+            }
+        };
+        // The permit rides along with the stream so the in-flight slot is
+        // held until the response has been fully read or dropped.
+        Ok(Box::pin(stream.map(move |event| {
+            match &event {
+                Ok(StreamEvent::FinishStep { usage, .. }) => {
+                    permit.settle(usage.prompt_tokens + usage.completion_tokens)
+                }
+                Ok(StreamEvent::Usage {
+                    prompt_tokens,
+                    completion_tokens,
+                    ..
+                }) => permit.settle(prompt_tokens + completion_tokens),
+                _ => {}
+            }
+            event
+        })))
+    }
+
+    async fn submit_batch(
+        &self,
+        items: Vec<crate::BatchItem>,
+    ) -> Result<crate::BatchHandle, ProviderError> {
+        self.inner.submit_batch(items).await
+    }
+
+    async fn poll_batch(
+        &self,
+        handle: &crate::BatchHandle,
+    ) -> Result<crate::BatchPoll, ProviderError> {
+        self.inner.poll_batch(handle).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn governor(limits: RateLimits) -> Arc<Governor> {
+        Arc::new(Governor::new("test/model".to_string(), "test", limits))
+    }
+
+    /// Yields until `queued` requests are waiting, so tests order their
+    /// callers without relying on wall-clock sleeps.
+    async fn wait_until_queued(governor: &Governor, queued: usize) {
+        while governor.snapshot().queued < queued {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[test]
+    fn reset_headers_parse_all_formats() {
+        assert_eq!(parse_reset("6m0s"), Some(Duration::from_secs(360)));
+        assert_eq!(parse_reset("20ms"), Some(Duration::from_millis(20)));
+        assert_eq!(parse_reset("1.5s"), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_reset("2"), Some(Duration::from_secs(2)));
+        assert_eq!(parse_reset("soon"), None);
+        let future = (chrono::Utc::now() + chrono::Duration::seconds(30)).to_rfc3339();
+        let parsed = parse_reset(&future).unwrap();
+        assert!(parsed > Duration::from_secs(28) && parsed <= Duration::from_secs(30));
+    }
+
+    #[tokio::test]
+    async fn in_flight_cap_is_shared_and_released_on_drop() {
+        let governor = governor(RateLimits {
+            max_in_flight: Some(1),
+            ..Default::default()
+        });
+        let first = governor.acquire("a", 0).await;
+        let waiting = {
+            let governor = governor.clone();
+            tokio::spawn(async move { governor.acquire("b", 0).await.waited() })
+        };
+        wait_until_queued(&governor, 1).await;
+        assert!(!waiting.is_finished());
+        drop(first);
+        waiting.await.unwrap();
+        assert_eq!(governor.snapshot().in_flight, 0);
+    }
+
+    #[tokio::test]
+    async fn sessions_take_turns() {
+        let governor = governor(RateLimits {
+            max_in_flight: Some(1),
+            ..Default::default()
+        });
+        let holder = governor.acquire("busy", 0).await;
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let mut tasks = Vec::new();
+        for (queued, session) in ["busy", "busy", "quiet"].into_iter().enumerate() {
+            let governor_for_task = governor.clone();
+            let order = order.clone();
+            tasks.push(tokio::spawn(async move {
+                let _permit = governor_for_task.acquire(session, 0).await;
+                order.lock().unwrap().push(session);
+            }));
+            wait_until_queued(&governor, queued + 1).await;
+        }
+        drop(holder);
+        for task in tasks {
+            task.await.unwrap();
+        }
+        assert_eq!(*order.lock().unwrap(), vec!["busy", "quiet", "busy"]);
+    }
+
+    #[tokio::test]
+    async fn token_bucket_delays_until_refilled() {
+        let governor = governor(RateLimits {
+            tokens_per_minute: Some(60_000),
+            ..Default::default()
+        });
+        let mut first = governor.acquire("a", 1_000).await;
+        // The response turned out far larger than the estimate.
+        first.settle(60_000);
+        drop(first);
+        let second = governor.acquire("a", 100).await;
+        assert!(second.waited() >= Duration::from_millis(80));
+    }
+
+    #[tokio::test]
+    async fn headers_teach_limits_and_pause_on_exhaustion() {
+        let governor = governor(RateLimits::default());
+        let mut headers = HeaderMap::new();
+        headers.insert("anthropic-ratelimit-requests-limit", "50".parse().unwrap());
+        headers.insert("x-ratelimit-remaining-requests", "0".parse().unwrap());
+        headers.insert("x-ratelimit-reset-requests", "2s".parse().unwrap());
+        governor.observe(200, &headers);
+
+        let snapshot = governor.snapshot();
+        assert_eq!(snapshot.limits.requests_per_minute, Some(50));
+        assert!(snapshot.cooldown_ms > 1500 && snapshot.cooldown_ms <= 2000);
+
+        let mut throttled = HeaderMap::new();
+        throttled.insert("retry-after", "5".parse().unwrap());
+        governor.observe(429, &throttled);
+        assert!(governor.snapshot().cooldown_ms > 4500);
+    }
+
+    #[test]
+    fn configured_limits_win_when_tighter() {
+        let governor = governor(RateLimits {
+            requests_per_minute: Some(10),
+            ..Default::default()
+        });
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-limit-requests", "500".parse().unwrap());
+        headers.insert("x-ratelimit-limit-tokens", "30000".parse().unwrap());
+        governor.observe(200, &headers);
+        let limits = governor.snapshot().limits;
+        assert_eq!(limits.requests_per_minute, Some(10));
+        assert_eq!(limits.tokens_per_minute, Some(30000));
+    }
+}
@@ -32,6 +32,8 @@ impl GroqProvider {
                     supports_tools: true,
                     cost_per_million_input: 0.59,
                     cost_per_million_output: 0.79,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "llama-3.1-8b-instant".to_string(),
@@ -44,6 +46,8 @@ impl GroqProvider {
                     supports_tools: true,
                     cost_per_million_input: 0.05,
                     cost_per_million_output: 0.08,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "mixtral-8x7b-32768".to_string(),
@@ -56,6 +60,8 @@ impl GroqProvider {
                     supports_tools: true,
                     cost_per_million_input: 0.24,
                     cost_per_million_output: 0.24,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "gemma2-9b-it".to_string(),
@@ -68,6 +74,8 @@ impl GroqProvider {
                     supports_tools: false,
                     cost_per_million_input: 0.2,
                     cost_per_million_output: 0.2,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "deepseek-r1-distill-llama-70b".to_string(),
@@ -80,6 +88,8 @@ impl GroqProvider {
                     supports_tools: false,
                     cost_per_million_input: 0.75,
                     cost_per_million_output: 0.99,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
             ],
         }
@@ -112,6 +122,7 @@ impl Provider for GroqProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -139,6 +150,7 @@ impl Provider for GroqProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -1,6 +1,7 @@
 pub mod anthropic;
 pub mod auth;
 pub mod azure;
+pub mod batch;
 pub mod bedrock;
 pub mod bootstrap;
 pub mod cerebras;
@@ -8,9 +9,11 @@ pub mod cohere;
 pub mod custom_fetch;
 pub mod deepinfra;
 pub mod deepseek;
+pub mod embedding;
 pub mod github_copilot;
 pub mod gitlab;
 pub mod google;
+pub mod governor;
 pub mod groq;
 pub mod message;
 pub mod mistral;
@@ -18,11 +21,14 @@ pub mod models;
 pub mod openai;
 pub mod openrouter;
 pub mod perplexity;
+pub mod pricing;
 pub mod provider;
+pub mod recorder;
 pub mod responses;
 pub mod responses_convert;
 pub mod retry;
 pub mod stream;
+pub mod structured;
 pub mod together;
 pub mod tools;
 pub mod transform;
@@ -31,25 +37,39 @@ pub mod vertex;
 pub mod xai;
 
 pub use auth::*;
+pub use batch::{BatchHandle, BatchItem, BatchItemResult, BatchPoll};
 pub use bootstrap::create_registry_from_env;
 pub use bootstrap::create_registry_from_env_with_auth_store;
 pub use bootstrap::{
     apply_custom_loaders, bootstrap_config_from_raw, create_registry_from_bootstrap_config,
     filter_models_by_status, BootstrapConfig, ConfigModel, ConfigProvider, CustomLoaderResult,
+    ModelCostCache, ModelCostOver200K, ProviderModelCost,
 };
 pub use custom_fetch::*;
+pub use embedding::{
+    default_embedding_base_url, is_embedding_model, is_local_embedding_provider, EmbeddingModel,
+    OpenAICompatibleEmbeddings,
+};
+pub use governor::{GovernedProvider, GovernorSnapshot, RateLimits};
 pub use message::*;
+pub use pricing::{model_cost, register_model_cost, resolve_model_cost};
 pub use provider::*;
-pub use retry::{with_retry, with_retry_and_hook, IsRetryable, RetryConfig};
+pub use recorder::{ExchangeRecorder, RecordedExchange, RecordedFrame, WireFormat};
+pub use retry::{with_retry, with_retry_and_hook, FailoverReason, IsRetryable, RetryConfig};
 pub use stream::*;
+pub use structured::{
+    generate_structured, parse_json_output, response_text, ResponseFormat, StructuredOutput,
+    StructuredOutputError, DEFAULT_STRUCTURED_RETRIES, STRUCTURED_OUTPUT_TOOL,
+};
 pub use tools::*;
 pub use transform::{
     apply_caching, apply_caching_per_part, dedup_messages, ensure_noop_tool_if_needed,
     extract_reasoning_from_response, max_output_tokens, mime_to_modality,
     normalize_interleaved_thinking, normalize_messages, normalize_messages_for_caching,
-    normalize_messages_with_interleaved_field, options, provider_options_map, schema, sdk_key,
-    small_options, temperature_for_model, top_k_for_model, top_p_for_model, transform_messages,
-    unsupported_parts, variants, Modality, ProviderType, OUTPUT_TOKEN_MAX,
+    normalize_messages_with_interleaved_field, options, plan_cache_breakpoints,
+    provider_options_map, schema, sdk_key, small_options, temperature_for_model, top_k_for_model,
+    top_p_for_model, transform_messages, unsupported_parts, variants, CacheBust, CacheFingerprint,
+    Modality, ProviderType, MAX_CACHE_BREAKPOINTS, OUTPUT_TOKEN_MAX,
 };
 
 pub use models::{
@@ -22,6 +22,15 @@ pub struct ChatRequest {
     pub provider_options: Option<HashMap<String, serde_json::Value>>,
     #[serde(skip)]
     pub variant: Option<String>,
+    /// Mapped by each provider onto its native structured output mechanism.
+    #[serde(skip)]
+    pub response_format: Option<crate::ResponseFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallel_tool_calls: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -169,6 +178,105 @@ impl Serialize for ToolDefinition {
     }
 }
 
+/// How the model may use tools on a single turn.
+///
+/// Serializes to the OpenAI chat completions shape; other adapters translate
+/// it themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToolChoice {
+    Auto,
+    None,
+    Required,
+    Tool(String),
+}
+
+impl ToolChoice {
+    /// Parses `auto`, `none`, `required` (or `any`), treating anything else
+    /// as the name of the tool to force.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        match value {
+            "" => None,
+            "auto" => Some(Self::Auto),
+            "none" => Some(Self::None),
+            "required" | "any" => Some(Self::Required),
+            name => Some(Self::Tool(name.to_string())),
+        }
+    }
+
+    pub fn tool_name(&self) -> Option<&str> {
+        match self {
+            Self::Tool(name) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+impl From<&ToolChoice> for crate::InputToolChoice {
+    fn from(choice: &ToolChoice) -> Self {
+        match choice {
+            ToolChoice::Auto => Self::Auto,
+            ToolChoice::None => Self::None,
+            ToolChoice::Required => Self::Required,
+            ToolChoice::Tool(name) => Self::Tool {
+                tool_name: name.clone(),
+            },
+        }
+    }
+}
+
+impl Serialize for ToolChoice {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Self::Auto => serializer.serialize_str("auto"),
+            Self::None => serializer.serialize_str("none"),
+            Self::Required => serializer.serialize_str("required"),
+            Self::Tool(name) => serde_json::json!({
+                "type": "function",
+                "function": { "name": name },
+            })
+            .serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ToolChoice {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct Named {
+            name: String,
+        }
+
+        // Accepts the OpenAI, Anthropic and AI SDK spellings.
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Mode(String),
+            Function { function: Named },
+            Named(Named),
+            Input(crate::InputToolChoice),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Mode(value) => {
+                Self::parse(&value).ok_or_else(|| serde::de::Error::custom("empty tool choice"))
+            }
+            Repr::Function { function } => Ok(Self::Tool(function.name)),
+            Repr::Named(named) => Ok(Self::Tool(named.name)),
+            Repr::Input(crate::InputToolChoice::Auto) => Ok(Self::Auto),
+            Repr::Input(crate::InputToolChoice::None) => Ok(Self::None),
+            Repr::Input(crate::InputToolChoice::Required) => Ok(Self::Required),
+            Repr::Input(crate::InputToolChoice::Tool { tool_name }) => Ok(Self::Tool(tool_name)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatResponse {
     #[serde(default)]
@@ -249,6 +357,10 @@ impl ChatRequest {
             stream: Some(true),
             provider_options: None,
             variant: None,
+            response_format: None,
+            tool_choice: None,
+            stop: None,
+            parallel_tool_calls: None,
         }
     }
 
@@ -281,6 +393,51 @@ impl ChatRequest {
         self.tools = Some(tools);
         self
     }
+
+    pub fn with_response_format(mut self, format: crate::ResponseFormat) -> Self {
+        self.response_format = Some(format);
+        self
+    }
+
+    pub fn with_tool_choice(mut self, tool_choice: ToolChoice) -> Self {
+        self.tool_choice = Some(tool_choice);
+        self
+    }
+
+    pub fn with_stop(mut self, stop: Vec<String>) -> Self {
+        self.stop = Some(stop);
+        self
+    }
+
+    pub fn with_parallel_tool_calls(mut self, parallel: bool) -> Self {
+        self.parallel_tool_calls = Some(parallel);
+        self
+    }
+
+    /// Drops tool controls `model` cannot honour, or that no longer match the
+    /// tools on this request, so adapters never send a body the backend rejects.
+    pub fn apply_model_capabilities(&mut self, model: &crate::ModelInfo) {
+        let has_tools = self.tools.as_ref().is_some_and(|tools| !tools.is_empty());
+        if !has_tools || !model.supports_tools {
+            if self.tool_choice.is_some() || self.parallel_tool_calls.is_some() {
+                tracing::debug!(
+                    model = %model.id,
+                    "dropping tool controls for a request without tool support"
+                );
+            }
+            self.tool_choice = None;
+            self.parallel_tool_calls = None;
+            return;
+        }
+        let Some(name) = self.tool_choice.as_ref().and_then(ToolChoice::tool_name) else {
+            return;
+        };
+        let known = self.tools.iter().flatten().any(|tool| tool.name == name);
+        if !known {
+            tracing::warn!(tool = %name, "tool_choice names a tool that is not offered; ignoring");
+            self.tool_choice = None;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -307,4 +464,79 @@ mod tests {
         assert_eq!(value["function"]["description"], "Execute shell commands");
         assert_eq!(value["function"]["parameters"]["type"], "object");
     }
+
+    #[test]
+    fn tool_choice_serializes_openai_and_accepts_other_spellings() {
+        assert_eq!(
+            serde_json::to_value(ToolChoice::Required).unwrap(),
+            serde_json::json!("required")
+        );
+        assert_eq!(
+            serde_json::to_value(ToolChoice::Tool("bash".to_string())).unwrap(),
+            serde_json::json!({"type": "function", "function": {"name": "bash"}})
+        );
+
+        let parse = |value: serde_json::Value| serde_json::from_value::<ToolChoice>(value).unwrap();
+        assert_eq!(parse(serde_json::json!("any")), ToolChoice::Required);
+        assert_eq!(
+            parse(serde_json::json!({"type": "function", "function": {"name": "bash"}})),
+            ToolChoice::Tool("bash".to_string())
+        );
+        assert_eq!(
+            parse(serde_json::json!({"type": "tool", "name": "bash"})),
+            ToolChoice::Tool("bash".to_string())
+        );
+        assert_eq!(
+            parse(serde_json::json!({"type": "tool", "toolName": "bash"})),
+            ToolChoice::Tool("bash".to_string())
+        );
+        assert_eq!(parse(serde_json::json!({"type": "none"})), ToolChoice::None);
+    }
+
+    #[test]
+    fn model_capabilities_drop_unusable_tool_controls() {
+        let mut model = crate::ModelInfo {
+            id: "m".to_string(),
+            name: "M".to_string(),
+            provider: "mock".to_string(),
+            context_window: 8192,
+            max_input_tokens: None,
+            max_output_tokens: 1024,
+            supports_vision: false,
+            supports_tools: true,
+            cost_per_million_input: 0.0,
+            cost_per_million_output: 0.0,
+            supports_batch: false,
+            supports_pdf: false,
+        };
+        let bash = ToolDefinition {
+            name: "bash".to_string(),
+            description: None,
+            parameters: serde_json::json!({"type": "object"}),
+        };
+
+        let mut request = ChatRequest::new("m", Vec::new())
+            .with_tools(vec![bash.clone()])
+            .with_tool_choice(ToolChoice::Tool("missing".to_string()))
+            .with_parallel_tool_calls(false);
+        request.apply_model_capabilities(&model);
+        assert_eq!(request.tool_choice, None);
+        assert_eq!(request.parallel_tool_calls, Some(false));
+
+        let mut request = ChatRequest::new("m", Vec::new())
+            .with_tool_choice(ToolChoice::Required)
+            .with_parallel_tool_calls(false);
+        request.apply_model_capabilities(&model);
+        assert_eq!(request.tool_choice, None);
+        assert_eq!(request.parallel_tool_calls, None);
+
+        model.supports_tools = false;
+        let mut request = ChatRequest::new("m", Vec::new())
+            .with_tools(vec![bash])
+            .with_tool_choice(ToolChoice::Auto)
+            .with_stop(vec!["END".to_string()]);
+        request.apply_model_capabilities(&model);
+        assert_eq!(request.tool_choice, None);
+        assert_eq!(request.stop, Some(vec!["END".to_string()]));
+    }
 }
@@ -32,6 +32,8 @@ impl MistralProvider {
                     supports_tools: true,
                     cost_per_million_input: 2.0,
                     cost_per_million_output: 6.0,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "mistral-medium-latest".to_string(),
@@ -44,6 +46,8 @@ impl MistralProvider {
                     supports_tools: true,
                     cost_per_million_input: 2.7,
                     cost_per_million_output: 8.1,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "mistral-small-latest".to_string(),
@@ -56,6 +60,8 @@ impl MistralProvider {
                     supports_tools: true,
                     cost_per_million_input: 0.2,
                     cost_per_million_output: 0.6,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "codestral-latest".to_string(),
@@ -68,6 +74,8 @@ impl MistralProvider {
                     supports_tools: true,
                     cost_per_million_input: 0.3,
                     cost_per_million_output: 0.9,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "pixtral-12b-2409".to_string(),
@@ -80,6 +88,8 @@ impl MistralProvider {
                     supports_tools: false,
                     cost_per_million_input: 0.15,
                     cost_per_million_output: 0.15,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
             ],
         }
@@ -112,6 +122,7 @@ impl Provider for MistralProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -139,6 +150,7 @@ impl Provider for MistralProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use futures::{stream, StreamExt};
+use futures::{stream, Stream, StreamExt};
 use reqwest::Client;
 use serde::Deserialize;
 use serde_json::{json, Map, Value};
@@ -282,7 +282,9 @@ fn normalize_tool_call_arguments_for_request(
     }
 }
 
+use crate::batch::{BatchHandle, BatchItem, BatchItemResult, BatchPoll};
 use crate::custom_fetch::get_custom_fetch_proxy;
+use crate::recorder::{ExchangeRecorder, WireFormat};
 use crate::responses::{
     FinishReason, GenerateOptions, OpenAIResponsesConfig, OpenAIResponsesLanguageModel,
     ResponsesProviderOptions, StreamOptions,
@@ -387,6 +389,8 @@ impl OpenAIProvider {
                 supports_tools: true,
                 cost_per_million_input: 2.5,
                 cost_per_million_output: 10.0,
+                supports_batch: !legacy_only,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "gpt-4o-mini".to_string(),
@@ -399,6 +403,8 @@ impl OpenAIProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.15,
                 cost_per_million_output: 0.6,
+                supports_batch: !legacy_only,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "gpt-4-turbo".to_string(),
@@ -411,6 +417,8 @@ impl OpenAIProvider {
                 supports_tools: true,
                 cost_per_million_input: 10.0,
                 cost_per_million_output: 30.0,
+                supports_batch: !legacy_only,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "o1-preview".to_string(),
@@ -423,6 +431,8 @@ impl OpenAIProvider {
                 supports_tools: false,
                 cost_per_million_input: 15.0,
                 cost_per_million_output: 60.0,
+                supports_batch: !legacy_only,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "o1-mini".to_string(),
@@ -435,6 +445,8 @@ impl OpenAIProvider {
                 supports_tools: false,
                 cost_per_million_input: 3.0,
                 cost_per_million_output: 12.0,
+                supports_batch: !legacy_only,
+                supports_pdf: false,
             },
         ];
 
@@ -485,10 +497,17 @@ impl OpenAIProvider {
         };
 
         let usage = chunk.get("usage");
+        let cache_read_tokens = usage
+            .and_then(|u| u.get("prompt_tokens_details"))
+            .and_then(|d| d.get("cached_tokens"))
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        // `prompt_tokens` includes cache hits; stream usage counts them apart.
         let prompt_tokens = usage
             .and_then(|u| u.get("prompt_tokens"))
             .and_then(Value::as_u64)
-            .unwrap_or(0);
+            .unwrap_or(0)
+            .saturating_sub(cache_read_tokens);
         let completion_tokens = usage
             .and_then(|u| u.get("completion_tokens"))
             .and_then(Value::as_u64)
@@ -498,6 +517,8 @@ impl OpenAIProvider {
             events.push(StreamEvent::Usage {
                 prompt_tokens,
                 completion_tokens,
+                cache_read_tokens,
+                cache_write_tokens: 0,
             });
         }
 
@@ -634,6 +655,7 @@ impl OpenAIProvider {
                         usage: crate::stream::StreamUsage {
                             prompt_tokens,
                             completion_tokens,
+                            cache_read_tokens,
                             ..Default::default()
                         },
                         provider_metadata: None,
@@ -924,6 +946,10 @@ impl OpenAIProvider {
                     Value::String(effort.to_string()),
                 );
             }
+
+            if let Some(format) = &request.response_format {
+                obj.insert("response_format".to_string(), format.openai_chat_format());
+            }
         }
 
         Ok(value)
@@ -1019,11 +1045,14 @@ impl OpenAIProvider {
                 openai_reasoning_effort(&request.model, request.variant.as_deref())
                     .map(ToString::to_string);
         }
+        if provider_options.parallel_tool_calls.is_none() {
+            provider_options.parallel_tool_calls = request.parallel_tool_calls;
+        }
 
         GenerateOptions {
             prompt,
             tools: Self::tools_to_input_tools(request.tools.as_ref()),
-            tool_choice: None,
+            tool_choice: request.tool_choice.as_ref().map(Into::into),
             max_output_tokens: request.max_tokens,
             temperature: request.temperature,
             top_p: request.top_p,
@@ -1031,9 +1060,12 @@ impl OpenAIProvider {
             seed: None,
             presence_penalty: None,
             frequency_penalty: None,
-            stop_sequences: None,
+            stop_sequences: request.stop.clone(),
             provider_options: Some(provider_options),
-            response_format: None,
+            response_format: request
+                .response_format
+                .as_ref()
+                .map(|format| format.openai_responses_format()),
         }
     }
 
@@ -1121,25 +1153,14 @@ impl OpenAIProvider {
             obj.remove("stream_options");
         }
 
-        let mut req_builder = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("Content-Type", "application/json");
-
-        if let Some(org) = &self.config.organization {
-            req_builder = req_builder.header("OpenAI-Organization", org);
-        }
-
-        let response = req_builder
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+        let (response, recorder) = self.send_legacy(&url, &request_body, false).await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
+            if let Some(recorder) = &recorder {
+                recorder.body(&body);
+            }
             return Err(ProviderError::ApiError(format!("{}: {}", status, body)));
         }
 
@@ -1153,6 +1174,9 @@ impl OpenAIProvider {
             }
             ProviderError::ApiError(msg)
         })?;
+        if let Some(recorder) = &recorder {
+            recorder.body(&body);
+        }
 
         // Some OpenAI-compatible providers (e.g. ZhipuAI) return SSE-formatted
         // streaming data even for non-streaming requests. Detect and reassemble.
@@ -1303,32 +1327,126 @@ impl OpenAIProvider {
             );
         }
 
-        let mut req_builder = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
-            .header("Content-Type", "application/json")
-            .header("Accept", "text/event-stream");
-
-        if let Some(org) = &self.config.organization {
-            req_builder = req_builder.header("OpenAI-Organization", org);
-        }
-
-        let response = req_builder
-            .json(&request_body)
-            .send()
-            .await
-            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+        let (response, recorder) = self.send_legacy(&url, &request_body, true).await?;
 
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
+            if let Some(recorder) = &recorder {
+                recorder.body(&body);
+            }
             return Err(ProviderError::ApiError(format!("{}: {}", status, body)));
         }
 
+        let chunks = response.bytes_stream().map(|chunk| match chunk {
+            Ok(bytes) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+            Err(err) => Err(ProviderError::StreamError(err.to_string())),
+        });
+        Ok(Self::legacy_event_stream(crate::recorder::tap(
+            chunks, recorder,
+        )))
+    }
+
+    /// A request against the API root, e.g. `files` or `batches/{id}`.
+    fn api_request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let completions = Self::chat_completions_url(self.config.base_url.as_deref());
+        let root = completions.trim_end_matches("/chat/completions");
+        let mut builder = self
+            .client
+            .request(method, format!("{}/{}", root, path))
+            .header("Authorization", format!("Bearer {}", self.config.api_key));
+        if let Some(org) = &self.config.organization {
+            builder = builder.header("OpenAI-Organization", org);
+        }
+        builder
+    }
+
+    fn batch_result_line(line: &str) -> BatchItemResult {
+        let parsed: OpenAIBatchLine = match serde_json::from_str(line) {
+            Ok(parsed) => parsed,
+            Err(e) => return BatchItemResult::failed("", format!("invalid batch result: {}", e)),
+        };
+        if let Some(error) = parsed.error.filter(|e| !e.is_null()) {
+            return BatchItemResult::failed(parsed.custom_id, error.to_string());
+        }
+        let Some(response) = parsed.response else {
+            return BatchItemResult::failed(parsed.custom_id, "missing response");
+        };
+        if response.status_code >= 400 {
+            return BatchItemResult::failed(
+                parsed.custom_id,
+                format!("{}: {}", response.status_code, response.body),
+            );
+        }
+        match serde_json::from_value::<RawChatResponse>(response.body) {
+            Ok(raw) => BatchItemResult {
+                custom_id: parsed.custom_id,
+                outcome: Ok(raw.into_chat_response()),
+            },
+            Err(e) => BatchItemResult::failed(
+                parsed.custom_id,
+                format!("failed to decode response: {}", e),
+            ),
+        }
+    }
+
+    /// Sends a chat completions request, opening a capture when recording
+    /// is enabled.
+    async fn send_legacy(
+        &self,
+        url: &str,
+        request_body: &Value,
+        stream: bool,
+    ) -> Result<(reqwest::Response, Option<ExchangeRecorder>), ProviderError> {
+        let mut headers = HashMap::from([
+            (
+                "Authorization".to_string(),
+                format!("Bearer {}", self.config.api_key),
+            ),
+            ("Content-Type".to_string(), "application/json".to_string()),
+        ]);
+        if stream {
+            headers.insert("Accept".to_string(), "text/event-stream".to_string());
+        }
+        if let Some(org) = &self.config.organization {
+            headers.insert("OpenAI-Organization".to_string(), org.clone());
+        }
+        let body = serde_json::to_string(request_body)
+            .map_err(|e| ProviderError::InvalidRequest(format!("failed to encode body: {}", e)))?;
+        let recorder = ExchangeRecorder::begin(
+            self.id(),
+            WireFormat::OpenaiChat,
+            "POST",
+            url,
+            &headers,
+            &body,
+        );
+
+        let mut req_builder = self.client.post(url);
+        for (name, value) in &headers {
+            req_builder = req_builder.header(name, value);
+        }
+        let response = req_builder.body(body).send().await.map_err(|e| {
+            if let Some(recorder) = &recorder {
+                recorder.error(&e.to_string());
+            }
+            ProviderError::NetworkError(e.to_string())
+        })?;
+        crate::governor::observe(&response);
+        if let Some(recorder) = &recorder {
+            recorder.response(response.status().as_u16(), response.headers());
+        }
+        Ok((response, recorder))
+    }
+
+    /// Parses raw chat completions SSE chunks into stream events.
+    pub(crate) fn legacy_event_stream<S>(chunks: S) -> StreamResult
+    where
+        S: Stream<Item = Result<String, ProviderError>> + Send + 'static,
+    {
         let stream = stream::try_unfold(
             (
-                response.bytes_stream(),
+                Box::pin(chunks),
                 String::new(),
                 LegacySseParserState::default(),
                 VecDeque::<StreamEvent>::new(),
@@ -1348,15 +1466,15 @@ impl OpenAIProvider {
                     }
 
                     match chunks.next().await {
-                        Some(Ok(bytes)) => {
-                            buffer.push_str(&String::from_utf8_lossy(&bytes));
+                        Some(Ok(text)) => {
+                            buffer.push_str(&text);
                             pending.extend(Self::drain_legacy_sse_events(
                                 &mut buffer,
                                 &mut parser_state,
                                 false,
                             ));
                         }
-                        Some(Err(err)) => return Err(ProviderError::StreamError(err.to_string())),
+                        Some(Err(err)) => return Err(err),
                         None => {
                             exhausted = true;
                             pending.extend(Self::drain_legacy_sse_events(
@@ -1370,7 +1488,7 @@ impl OpenAIProvider {
             },
         );
 
-        Ok(crate::stream::assemble_tool_calls(Box::pin(stream)))
+        crate::stream::assemble_tool_calls(Box::pin(stream))
     }
 }
 
@@ -1461,6 +1579,174 @@ impl Provider for OpenAIProvider {
         )
         .await
     }
+
+    async fn submit_batch(&self, items: Vec<BatchItem>) -> Result<BatchHandle, ProviderError> {
+        if self.legacy_only {
+            return Err(crate::batch::unsupported(self.id()));
+        }
+        let mut lines = Vec::with_capacity(items.len());
+        let mut custom_ids = Vec::with_capacity(items.len());
+        for item in items {
+            let mut body = Self::build_request_body(&item.request)?;
+            if let Value::Object(obj) = &mut body {
+                obj.remove("stream");
+                obj.remove("stream_options");
+            }
+            lines.push(
+                json!({
+                    "custom_id": item.custom_id,
+                    "method": "POST",
+                    "url": "/v1/chat/completions",
+                    "body": body,
+                })
+                .to_string(),
+            );
+            custom_ids.push(item.custom_id);
+        }
+
+        let boundary = format!("rocode-{}", uuid::Uuid::new_v4().simple());
+        let upload = crate::batch::multipart_body(
+            &boundary,
+            &[("purpose", "batch")],
+            "file",
+            "batch.jsonl",
+            &lines.join("\n"),
+        );
+        let file: OpenAIFile = read_json(
+            self.api_request(reqwest::Method::POST, "files")
+                .header(
+                    "Content-Type",
+                    format!("multipart/form-data; boundary={}", boundary),
+                )
+                .body(upload),
+        )
+        .await?;
+        let batch: OpenAIBatch = read_json(
+            self.api_request(reqwest::Method::POST, "batches")
+                .json(&json!({
+                    "input_file_id": file.id,
+                    "endpoint": "/v1/chat/completions",
+                    "completion_window": "24h",
+                })),
+        )
+        .await?;
+
+        Ok(BatchHandle {
+            provider_id: self.id().to_string(),
+            batch_id: batch.id,
+            custom_ids,
+        })
+    }
+
+    async fn poll_batch(&self, handle: &BatchHandle) -> Result<BatchPoll, ProviderError> {
+        let batch: OpenAIBatch = read_json(self.api_request(
+            reqwest::Method::GET,
+            &format!("batches/{}", handle.batch_id),
+        ))
+        .await?;
+        let counts = batch.request_counts.unwrap_or_default();
+        if !matches!(
+            batch.status.as_str(),
+            "completed" | "failed" | "expired" | "cancelled"
+        ) {
+            return Ok(BatchPoll::Pending {
+                completed: counts.completed + counts.failed,
+                total: counts.total.max(handle.custom_ids.len()),
+            });
+        }
+
+        let mut results = Vec::new();
+        for file_id in [batch.output_file_id, batch.error_file_id]
+            .into_iter()
+            .flatten()
+        {
+            let content = self
+                .api_request(reqwest::Method::GET, &format!("files/{}/content", file_id))
+                .send()
+                .await
+                .map_err(|e| ProviderError::NetworkError(e.to_string()))?
+                .text()
+                .await
+                .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+            results.extend(
+                content
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(Self::batch_result_line),
+            );
+        }
+        // Items missing from both files never ran (e.g. the batch expired).
+        for custom_id in &handle.custom_ids {
+            if !results
+                .iter()
+                .any(|r: &BatchItemResult| &r.custom_id == custom_id)
+            {
+                results.push(BatchItemResult::failed(
+                    custom_id.clone(),
+                    format!("batch {}", batch.status),
+                ));
+            }
+        }
+        Ok(BatchPoll::Complete(results))
+    }
+}
+
+async fn read_json<T: serde::de::DeserializeOwned>(
+    request: reqwest::RequestBuilder,
+) -> Result<T, ProviderError> {
+    let response = request
+        .send()
+        .await
+        .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(ProviderError::ApiError(format!("{}: {}", status, body)));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| ProviderError::ApiError(e.to_string()))
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIFile {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIBatch {
+    id: String,
+    status: String,
+    #[serde(default)]
+    request_counts: Option<OpenAIBatchCounts>,
+    #[serde(default)]
+    output_file_id: Option<String>,
+    #[serde(default)]
+    error_file_id: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct OpenAIBatchCounts {
+    total: usize,
+    completed: usize,
+    failed: usize,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIBatchLine {
+    custom_id: String,
+    #[serde(default)]
+    response: Option<OpenAIBatchResponse>,
+    #[serde(default)]
+    error: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIBatchResponse {
+    status_code: u16,
+    body: Value,
 }
 
 fn openai_reasoning_effort(model_id: &str, variant: Option<&str>) -> Option<&'static str> {
@@ -1597,6 +1883,22 @@ mod tests {
         assert!(provider.prefers_legacy_route());
     }
 
+    #[test]
+    fn batch_result_line_maps_success_and_failure() {
+        let ok = OpenAIProvider::batch_result_line(
+            r#"{"custom_id":"a","response":{"status_code":200,"body":{"choices":[{"message":{"role":"assistant","content":"done"},"finish_reason":"stop"}],"usage":{"prompt_tokens":3,"completion_tokens":1,"total_tokens":4}}},"error":null}"#,
+        );
+        assert_eq!(ok.custom_id, "a");
+        let response = ok.outcome.unwrap();
+        assert_eq!(response.usage.unwrap().prompt_tokens, 3);
+
+        let failed = OpenAIProvider::batch_result_line(
+            r#"{"custom_id":"b","response":{"status_code":400,"body":{"error":"bad"}}}"#,
+        );
+        assert_eq!(failed.custom_id, "b");
+        assert!(failed.outcome.unwrap_err().starts_with("400"));
+    }
+
     #[test]
     fn drain_legacy_sse_events_handles_partial_and_multiple_lines() {
         let mut state = LegacySseParserState::default();
@@ -1618,11 +1920,30 @@ mod tests {
             &events[1],
             StreamEvent::Usage {
                 prompt_tokens: 1,
-                completion_tokens: 2
+                completion_tokens: 2,
+                ..
             }
         ));
     }
 
+    #[test]
+    fn parse_legacy_sse_data_reports_cached_prompt_tokens() {
+        let mut state = LegacySseParserState::default();
+        let events = OpenAIProvider::parse_legacy_sse_data(
+            r#"{"usage":{"prompt_tokens":100,"completion_tokens":5,"prompt_tokens_details":{"cached_tokens":80}}}"#,
+            &mut state,
+        );
+        assert!(matches!(
+            events.as_slice(),
+            [StreamEvent::Usage {
+                prompt_tokens: 20,
+                completion_tokens: 5,
+                cache_read_tokens: 80,
+                ..
+            }]
+        ));
+    }
+
     #[test]
     fn parse_legacy_sse_data_uses_stable_tool_call_id_when_missing() {
         let mut state = LegacySseParserState::default();
@@ -1645,6 +1966,24 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn build_request_body_maps_response_format() {
+        let request = ChatRequest::new("gpt-4.1", vec![Message::user("hi")]).with_response_format(
+            crate::ResponseFormat::json_schema(serde_json::json!({
+                "type": "object",
+                "properties": {"ok": {"type": "boolean"}}
+            })),
+        );
+
+        let body = OpenAIProvider::build_request_body(&request).expect("request body");
+        assert_eq!(body["response_format"]["type"], "json_schema");
+        assert_eq!(body["response_format"]["json_schema"]["name"], "response");
+        assert_eq!(
+            body["response_format"]["json_schema"]["schema"]["properties"]["ok"]["type"],
+            "boolean"
+        );
+    }
+
     #[test]
     fn converts_tool_roundtrip_messages_to_openai_compatible_shape() {
         let assistant = Message {
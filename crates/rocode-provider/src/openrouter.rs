@@ -46,6 +46,8 @@ impl OpenRouterProvider {
                 supports_tools: true,
                 cost_per_million_input: 3.0,
                 cost_per_million_output: 15.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "anthropic/claude-3.5-sonnet".to_string(),
@@ -58,6 +60,8 @@ impl OpenRouterProvider {
                 supports_tools: true,
                 cost_per_million_input: 3.0,
                 cost_per_million_output: 15.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "openai/gpt-4o".to_string(),
@@ -70,6 +74,8 @@ impl OpenRouterProvider {
                 supports_tools: true,
                 cost_per_million_input: 2.5,
                 cost_per_million_output: 10.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "openai/gpt-4o-mini".to_string(),
@@ -82,6 +88,8 @@ impl OpenRouterProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.15,
                 cost_per_million_output: 0.6,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "google/gemini-2.5-pro-preview".to_string(),
@@ -94,6 +102,8 @@ impl OpenRouterProvider {
                 supports_tools: true,
                 cost_per_million_input: 1.25,
                 cost_per_million_output: 10.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "google/gemini-2.0-flash-001".to_string(),
@@ -106,6 +116,8 @@ impl OpenRouterProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.1,
                 cost_per_million_output: 0.4,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "deepseek/deepseek-chat".to_string(),
@@ -118,6 +130,8 @@ impl OpenRouterProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.14,
                 cost_per_million_output: 0.28,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "meta-llama/llama-3.3-70b-instruct".to_string(),
@@ -130,6 +144,8 @@ impl OpenRouterProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.35,
                 cost_per_million_output: 0.4,
+                supports_batch: false,
+                supports_pdf: false,
             },
         ];
 
@@ -196,6 +212,7 @@ impl Provider for OpenRouterProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -228,6 +245,7 @@ impl Provider for OpenRouterProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
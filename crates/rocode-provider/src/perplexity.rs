@@ -32,6 +32,8 @@ impl PerplexityProvider {
                     supports_tools: false,
                     cost_per_million_input: 3.0,
                     cost_per_million_output: 15.0,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "sonar".to_string(),
@@ -44,6 +46,8 @@ impl PerplexityProvider {
                     supports_tools: false,
                     cost_per_million_input: 1.0,
                     cost_per_million_output: 1.0,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "sonar-reasoning-pro".to_string(),
@@ -56,6 +60,8 @@ impl PerplexityProvider {
                     supports_tools: false,
                     cost_per_million_input: 2.0,
                     cost_per_million_output: 8.0,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "sonar-reasoning".to_string(),
@@ -68,6 +74,8 @@ impl PerplexityProvider {
                     supports_tools: false,
                     cost_per_million_input: 1.0,
                     cost_per_million_output: 5.0,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
             ],
         }
@@ -100,6 +108,7 @@ impl Provider for PerplexityProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -127,6 +136,7 @@ impl Provider for PerplexityProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -0,0 +1,161 @@
+//! Token prices for usage accounting.
+//!
+//! The bootstrap registry records each model's [`ProviderModelCost`] here so
+//! the session loop can price a step from just the provider and model ids.
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::bootstrap::{ModelCostCache, ProviderModelCost};
+use crate::provider::ModelInfo;
+use crate::stream::StreamUsage;
+
+/// Prompt size (input plus cache reads) above which the
+/// `experimental_over_200k` tier applies.
+pub const OVER_200K_THRESHOLD: u64 = 200_000;
+
+static MODEL_COSTS: Lazy<RwLock<HashMap<String, ProviderModelCost>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+fn cost_key(provider_id: &str, model_id: &str) -> String {
+    format!("{provider_id}/{model_id}")
+}
+
+pub fn register_model_cost(provider_id: &str, model_id: &str, cost: ProviderModelCost) {
+    if let Ok(mut guard) = MODEL_COSTS.write() {
+        guard.insert(cost_key(provider_id, model_id), cost);
+    }
+}
+
+pub fn model_cost(provider_id: &str, model_id: &str) -> Option<ProviderModelCost> {
+    MODEL_COSTS
+        .read()
+        .ok()
+        .and_then(|guard| guard.get(&cost_key(provider_id, model_id)).cloned())
+}
+
+/// Prices for `model_id`, falling back to the flat per-million rates on the
+/// runtime model when the bootstrap registry has no entry.
+pub fn resolve_model_cost(
+    provider_id: &str,
+    model_id: &str,
+    model: Option<&ModelInfo>,
+) -> Option<ProviderModelCost> {
+    model_cost(provider_id, model_id).or_else(|| {
+        model.map(|model| ProviderModelCost {
+            input: model.cost_per_million_input,
+            output: model.cost_per_million_output,
+            cache: ModelCostCache {
+                read: 0.0,
+                write: 0.0,
+            },
+            experimental_over_200k: None,
+        })
+    })
+}
+
+impl ProviderModelCost {
+    /// USD cost of one step. Prices are per million tokens. Reasoning tokens
+    /// are already part of `completion_tokens` and bill at the output rate
+    /// with them.
+    pub fn cost_for(&self, usage: &StreamUsage) -> f64 {
+        let (input, output, cache) = match &self.experimental_over_200k {
+            Some(tier) if usage.prompt_tokens + usage.cache_read_tokens > OVER_200K_THRESHOLD => {
+                (tier.input, tier.output, &tier.cache)
+            }
+            _ => (self.input, self.output, &self.cache),
+        };
+        (usage.prompt_tokens as f64 * input
+            + usage.completion_tokens as f64 * output
+            + usage.cache_read_tokens as f64 * cache.read
+            + usage.cache_write_tokens as f64 * cache.write)
+            / 1_000_000.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bootstrap::ModelCostOver200K;
+
+    fn cost() -> ProviderModelCost {
+        ProviderModelCost {
+            input: 3.0,
+            output: 15.0,
+            cache: ModelCostCache {
+                read: 0.3,
+                write: 3.75,
+            },
+            experimental_over_200k: Some(ModelCostOver200K {
+                input: 6.0,
+                output: 22.5,
+                cache: ModelCostCache {
+                    read: 0.6,
+                    write: 7.5,
+                },
+            }),
+        }
+    }
+
+    #[test]
+    fn cost_uses_base_prices_below_threshold() {
+        // 200k output tokens, half of them reasoning, bill once at $15.
+        let usage = StreamUsage {
+            prompt_tokens: 1_000_000,
+            completion_tokens: 200_000,
+            reasoning_tokens: 100_000,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+        };
+        let mut cost = cost();
+        cost.experimental_over_200k = None;
+        assert!((cost.cost_for(&usage) - 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cost_switches_to_over_200k_tier() {
+        let usage = StreamUsage {
+            prompt_tokens: 150_000,
+            completion_tokens: 0,
+            reasoning_tokens: 0,
+            cache_read_tokens: 100_000,
+            cache_write_tokens: 0,
+        };
+        // 150k * $6 + 100k * $0.6 per million.
+        assert!((cost().cost_for(&usage) - 0.96).abs() < 1e-9);
+
+        let below = StreamUsage {
+            cache_read_tokens: 50_000,
+            ..usage
+        };
+        assert!((cost().cost_for(&below) - 0.465).abs() < 1e-9);
+    }
+
+    #[test]
+    fn resolve_falls_back_to_runtime_model_rates() {
+        let model = ModelInfo {
+            id: "pricing-test-model".to_string(),
+            name: "Pricing Test".to_string(),
+            provider: "pricing-test".to_string(),
+            context_window: 1000,
+            max_input_tokens: None,
+            max_output_tokens: 100,
+            supports_vision: false,
+            supports_tools: false,
+            cost_per_million_input: 1.0,
+            cost_per_million_output: 2.0,
+            supports_batch: false,
+            supports_pdf: false,
+        };
+        let resolved = resolve_model_cost("pricing-test", "pricing-test-model", Some(&model))
+            .expect("fallback cost");
+        assert_eq!(resolved.input, 1.0);
+        assert!(resolved.experimental_over_200k.is_none());
+
+        register_model_cost("pricing-test", "pricing-test-model", cost());
+        let registered = resolve_model_cost("pricing-test", "pricing-test-model", Some(&model))
+            .expect("registered cost");
+        assert_eq!(registered.input, 3.0);
+    }
+}
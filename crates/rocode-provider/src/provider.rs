@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::batch::{BatchHandle, BatchItem, BatchPoll};
 use crate::{ChatRequest, ChatResponse, StreamResult};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,6 +23,12 @@ pub struct ModelInfo {
     pub supports_tools: bool,
     pub cost_per_million_input: f64,
     pub cost_per_million_output: f64,
+    /// Whether the provider accepts this model through its native batch API.
+    #[serde(default)]
+    pub supports_batch: bool,
+    /// Whether PDFs can be attached as documents rather than extracted text.
+    #[serde(default)]
+    pub supports_pdf: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,6 +50,18 @@ pub trait Provider: Send + Sync {
 
     async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, ProviderError>;
     async fn chat_stream(&self, request: ChatRequest) -> Result<StreamResult, ProviderError>;
+
+    /// Submits `items` to the native batch API. Only called for models whose
+    /// `supports_batch` is set.
+    async fn submit_batch(&self, items: Vec<BatchItem>) -> Result<BatchHandle, ProviderError> {
+        let _ = items;
+        Err(crate::batch::unsupported(self.id()))
+    }
+
+    async fn poll_batch(&self, handle: &BatchHandle) -> Result<BatchPoll, ProviderError> {
+        let _ = handle;
+        Err(crate::batch::unsupported(self.id()))
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
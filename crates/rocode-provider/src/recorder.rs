@@ -0,0 +1,557 @@
+//! Opt-in recorder for provider HTTP exchanges.
+//!
+//! When recording is enabled for a session (or process-wide through
+//! `OPENCODE_RECORD_PROVIDER`), every adapter request writes one JSONL file
+//! holding the serialized request body, the response status and headers and
+//! the raw stream chunks exactly as they arrived. Credentials are redacted
+//! before anything reaches disk. [`RecordedExchange::replay`] feeds a capture
+//! back through the same stream parsers the adapters use, so conversion bugs
+//! can be reproduced offline.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{File, OpenOptions};
+use std::future::Future;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+use futures::{Stream, StreamExt};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::provider::ProviderError;
+use crate::stream::StreamResult;
+
+/// Environment variable that records every exchange in the process. Set it to
+/// a directory, or to `1`/`true` for [`default_recording_dir`].
+pub const RECORD_ENV: &str = "OPENCODE_RECORD_PROVIDER";
+
+const REDACTED: &str = "[REDACTED]";
+
+tokio::task_local! {
+    static RECORDING_SESSION: String;
+}
+
+static SESSION_DIRS: Lazy<RwLock<HashMap<String, PathBuf>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Wire protocol of a recorded exchange; selects the parser used on replay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WireFormat {
+    Anthropic,
+    OpenaiChat,
+    OpenaiResponses,
+    Google,
+    Vertex,
+    Bedrock,
+}
+
+/// One line of a capture file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RecordedFrame {
+    Request {
+        provider: String,
+        wire: WireFormat,
+        method: String,
+        url: String,
+        headers: BTreeMap<String, String>,
+        body: serde_json::Value,
+        recorded_at: chrono::DateTime<chrono::Utc>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        session_id: Option<String>,
+    },
+    Response {
+        status: u16,
+        headers: BTreeMap<String, String>,
+    },
+    /// Raw stream chunk, split where the transport split it.
+    Chunk {
+        data: String,
+    },
+    /// Complete body of a non-streaming or failed response.
+    Body {
+        data: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// Records `session_id`'s exchanges under `dir/<session_id>/`.
+pub fn enable_recording(session_id: impl Into<String>, dir: impl Into<PathBuf>) {
+    if let Ok(mut guard) = SESSION_DIRS.write() {
+        guard.insert(session_id.into(), dir.into());
+    }
+}
+
+pub fn disable_recording(session_id: &str) {
+    if let Ok(mut guard) = SESSION_DIRS.write() {
+        guard.remove(session_id);
+    }
+}
+
+pub fn is_recording(session_id: &str) -> bool {
+    recording_dir_for(Some(session_id)).is_some()
+}
+
+/// `recordings/` next to the provider `auth.json`.
+pub fn default_recording_dir() -> PathBuf {
+    crate::auth::auth_data_dir().join("recordings")
+}
+
+/// Runs `fut` with provider requests attributed to `session_id`, so adapters
+/// called inside it pick up that session's recording settings.
+pub async fn scope<F: Future>(session_id: impl Into<String>, fut: F) -> F::Output {
+    RECORDING_SESSION.scope(session_id.into(), fut).await
+}
+
+pub(crate) fn current_session() -> Option<String> {
+    RECORDING_SESSION.try_with(Clone::clone).ok()
+}
+
+fn env_recording_dir() -> Option<PathBuf> {
+    let value = std::env::var(RECORD_ENV).ok()?;
+    match value.trim() {
+        "" | "0" | "false" => None,
+        "1" | "true" => Some(default_recording_dir()),
+        path => Some(PathBuf::from(path)),
+    }
+}
+
+fn recording_dir_for(session_id: Option<&str>) -> Option<PathBuf> {
+    session_id
+        .and_then(|id| SESSION_DIRS.read().ok()?.get(id).cloned())
+        .or_else(env_recording_dir)
+}
+
+fn is_secret_name(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    matches!(
+        name.as_str(),
+        "authorization" | "proxy-authorization" | "cookie" | "set-cookie"
+    ) || ["key", "token", "secret", "signature", "credential"]
+        .iter()
+        .any(|marker| name.contains(marker))
+}
+
+/// Copies `headers`, replacing credential-bearing values.
+pub fn redact_headers<'a>(
+    headers: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> BTreeMap<String, String> {
+    headers
+        .into_iter()
+        .map(|(name, value)| {
+            let value = if is_secret_name(name) {
+                REDACTED.to_string()
+            } else {
+                value.to_string()
+            };
+            (name.to_ascii_lowercase(), value)
+        })
+        .collect()
+}
+
+/// Redacts credential query parameters such as Google's `?key=`.
+pub fn redact_url(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let query = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if is_secret_name(name) => format!("{}={}", name, REDACTED),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("{}?{}", base, query)
+}
+
+fn redact_body(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (name, field) in map.iter_mut() {
+                if field.is_string() && is_secret_name(name) {
+                    *field = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    redact_body(field);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_body),
+        _ => {}
+    }
+}
+
+/// Handle for the capture file of one in-flight exchange. Cheap to clone;
+/// write failures are logged once and then ignored so recording never breaks
+/// a request.
+#[derive(Debug, Clone)]
+pub struct ExchangeRecorder {
+    path: PathBuf,
+    file: Arc<Mutex<Option<File>>>,
+}
+
+impl ExchangeRecorder {
+    /// Opens a capture file when recording is enabled for the current scope.
+    pub fn begin(
+        provider: &str,
+        wire: WireFormat,
+        method: &str,
+        url: &str,
+        headers: &HashMap<String, String>,
+        body: &str,
+    ) -> Option<Self> {
+        let session_id = current_session();
+        let dir = recording_dir_for(session_id.as_deref())?;
+        let now = chrono::Utc::now();
+        let file_name = format!(
+            "{}-{}-{}.jsonl",
+            now.format("%Y%m%dT%H%M%S%.3f"),
+            provider.replace(['/', '\\'], "_"),
+            &uuid::Uuid::new_v4().simple().to_string()[..8]
+        );
+        let path = dir
+            .join(session_id.as_deref().unwrap_or("unscoped"))
+            .join(file_name);
+        let file = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| OpenOptions::new().create(true).append(true).open(&path));
+        let file = match file {
+            Ok(file) => file,
+            Err(e) => {
+                tracing::warn!(path = %path.display(), error = %e, "cannot open provider recording");
+                return None;
+            }
+        };
+
+        let mut body =
+            serde_json::from_str(body).unwrap_or_else(|_| serde_json::Value::String(body.into()));
+        redact_body(&mut body);
+        let recorder = Self {
+            path,
+            file: Arc::new(Mutex::new(Some(file))),
+        };
+        recorder.write(&RecordedFrame::Request {
+            provider: provider.to_string(),
+            wire,
+            method: method.to_string(),
+            url: redact_url(url),
+            headers: redact_headers(headers.iter().map(|(k, v)| (k.as_str(), v.as_str()))),
+            body,
+            recorded_at: now,
+            session_id,
+        });
+        tracing::debug!(path = %recorder.path.display(), "recording provider exchange");
+        Some(recorder)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn response(&self, status: u16, headers: &reqwest::header::HeaderMap) {
+        self.write(&RecordedFrame::Response {
+            status,
+            headers: redact_headers(
+                headers
+                    .iter()
+                    .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?))),
+            ),
+        });
+    }
+
+    /// Same as [`Self::response`] for responses from a custom fetch proxy.
+    pub fn proxied_response(&self, status: u16, headers: &HashMap<String, String>) {
+        self.write(&RecordedFrame::Response {
+            status,
+            headers: redact_headers(headers.iter().map(|(k, v)| (k.as_str(), v.as_str()))),
+        });
+    }
+
+    pub fn chunk(&self, data: &str) {
+        self.write(&RecordedFrame::Chunk {
+            data: data.to_string(),
+        });
+    }
+
+    pub fn body(&self, data: &str) {
+        self.write(&RecordedFrame::Body {
+            data: data.to_string(),
+        });
+    }
+
+    pub fn error(&self, message: &str) {
+        self.write(&RecordedFrame::Error {
+            message: message.to_string(),
+        });
+    }
+
+    fn write(&self, frame: &RecordedFrame) {
+        let Ok(mut guard) = self.file.lock() else {
+            return;
+        };
+        let Some(file) = guard.as_mut() else {
+            return;
+        };
+        let written = serde_json::to_string(frame)
+            .map_err(std::io::Error::other)
+            .and_then(|line| writeln!(file, "{}", line));
+        if let Err(e) = written {
+            tracing::warn!(path = %self.path.display(), error = %e, "provider recording stopped");
+            *guard = None;
+        }
+    }
+}
+
+/// Passes `chunks` through unchanged, copying each one (and any transport
+/// error) into `recorder`.
+pub fn tap<S>(
+    chunks: S,
+    recorder: Option<ExchangeRecorder>,
+) -> impl Stream<Item = Result<String, ProviderError>> + Send
+where
+    S: Stream<Item = Result<String, ProviderError>> + Send,
+{
+    chunks.inspect(move |chunk| {
+        if let Some(recorder) = &recorder {
+            match chunk {
+                Ok(data) => recorder.chunk(data),
+                Err(e) => recorder.error(&e.to_string()),
+            }
+        }
+    })
+}
+
+/// A capture file loaded back into memory.
+#[derive(Debug, Clone)]
+pub struct RecordedExchange {
+    pub provider: String,
+    pub wire: WireFormat,
+    pub method: String,
+    pub url: String,
+    pub request_body: serde_json::Value,
+    pub status: Option<u16>,
+    pub response_headers: BTreeMap<String, String>,
+    pub chunks: Vec<String>,
+    pub body: Option<String>,
+    pub errors: Vec<String>,
+}
+
+impl RecordedExchange {
+    pub fn load(path: &Path) -> Result<Self, ProviderError> {
+        let file = File::open(path).map_err(|e| {
+            ProviderError::InvalidRequest(format!("cannot open {}: {}", path.display(), e))
+        })?;
+        Self::from_frames(
+            BufReader::new(file)
+                .lines()
+                .enumerate()
+                .filter_map(|(index, line)| match line {
+                    Ok(line) if line.trim().is_empty() => None,
+                    Ok(line) => Some(serde_json::from_str(&line).map_err(|e| {
+                        ProviderError::InvalidRequest(format!(
+                            "{}:{}: invalid frame: {}",
+                            path.display(),
+                            index + 1,
+                            e
+                        ))
+                    })),
+                    Err(e) => Some(Err(ProviderError::InvalidRequest(e.to_string()))),
+                }),
+        )
+    }
+
+    fn from_frames(
+        frames: impl IntoIterator<Item = Result<RecordedFrame, ProviderError>>,
+    ) -> Result<Self, ProviderError> {
+        let mut frames = frames.into_iter();
+        let Some(RecordedFrame::Request {
+            provider,
+            wire,
+            method,
+            url,
+            body,
+            ..
+        }) = frames.next().transpose()?
+        else {
+            return Err(ProviderError::InvalidRequest(
+                "capture does not start with a request frame".to_string(),
+            ));
+        };
+
+        let mut exchange = Self {
+            provider,
+            wire,
+            method,
+            url,
+            request_body: body,
+            status: None,
+            response_headers: BTreeMap::new(),
+            chunks: Vec::new(),
+            body: None,
+            errors: Vec::new(),
+        };
+        for frame in frames {
+            match frame? {
+                RecordedFrame::Request { .. } => {
+                    return Err(ProviderError::InvalidRequest(
+                        "capture holds more than one request".to_string(),
+                    ))
+                }
+                RecordedFrame::Response { status, headers } => {
+                    exchange.status = Some(status);
+                    exchange.response_headers = headers;
+                }
+                RecordedFrame::Chunk { data } => exchange.chunks.push(data),
+                RecordedFrame::Body { data } => exchange.body = Some(data),
+                RecordedFrame::Error { message } => exchange.errors.push(message),
+            }
+        }
+        Ok(exchange)
+    }
+
+    /// Re-parses the recorded chunks with the adapter's stream parser,
+    /// preserving the original chunk boundaries.
+    pub fn replay(&self) -> Result<StreamResult, ProviderError> {
+        if self.chunks.is_empty() {
+            return Err(ProviderError::InvalidRequest(match &self.body {
+                Some(_) => "capture has a response body but no stream chunks".to_string(),
+                None => "capture has no stream chunks".to_string(),
+            }));
+        }
+        let chunks: Vec<Result<String, ProviderError>> =
+            self.chunks.iter().cloned().map(Ok).collect();
+        let chunks = futures::stream::iter(chunks);
+        Ok(match self.wire {
+            WireFormat::Anthropic => crate::anthropic::anthropic_event_stream(chunks),
+            WireFormat::OpenaiChat => crate::openai::OpenAIProvider::legacy_event_stream(chunks),
+            WireFormat::OpenaiResponses => crate::responses::responses_event_stream(chunks, None),
+            WireFormat::Google => crate::google::google_event_stream(chunks),
+            WireFormat::Vertex => crate::vertex::vertex_event_stream(chunks),
+            WireFormat::Bedrock => crate::bedrock::bedrock_event_stream(chunks),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::StreamEvent;
+
+    #[test]
+    fn redacts_credentials_in_headers_urls_and_bodies() {
+        let headers = redact_headers([
+            ("Authorization", "Bearer sk-live"),
+            ("x-api-key", "sk-ant"),
+            ("content-type", "application/json"),
+        ]);
+        assert_eq!(headers["authorization"], REDACTED);
+        assert_eq!(headers["x-api-key"], REDACTED);
+        assert_eq!(headers["content-type"], "application/json");
+
+        assert_eq!(
+            redact_url("https://example.test/v1?alt=sse&key=AIza"),
+            "https://example.test/v1?alt=sse&key=[REDACTED]"
+        );
+
+        let mut body = serde_json::json!({
+            "model": "m",
+            "api_key": "secret",
+            "messages": [{ "content": "key facts", "max_tokens": 5 }]
+        });
+        redact_body(&mut body);
+        assert_eq!(body["api_key"], REDACTED);
+        assert_eq!(body["model"], "m");
+        assert_eq!(body["messages"][0]["content"], "key facts");
+        assert_eq!(body["messages"][0]["max_tokens"], 5);
+    }
+
+    #[tokio::test]
+    async fn records_scoped_session_and_replays_split_chunks() {
+        let dir = std::env::temp_dir().join(format!("rocode-recorder-{}", uuid::Uuid::new_v4()));
+        enable_recording("ses_record", &dir);
+
+        let mut headers = HashMap::new();
+        headers.insert("x-api-key".to_string(), "sk-ant".to_string());
+        let recorder = scope("ses_record", async {
+            ExchangeRecorder::begin(
+                "anthropic",
+                WireFormat::Anthropic,
+                "POST",
+                "https://api.anthropic.com/v1/messages",
+                &headers,
+                r#"{"model":"claude"}"#,
+            )
+        })
+        .await
+        .expect("recording enabled");
+        disable_recording("ses_record");
+
+        let frames = [
+            "data: {\"type\":\"message_start\"}\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"hi\"}}\n",
+            "data: {\"type\":\"message_stop\"}\n",
+        ];
+        let chunks = futures::stream::iter(frames.map(|f| Ok(f.to_string())));
+        let _: Vec<_> = tap(chunks, Some(recorder.clone())).collect().await;
+
+        let raw = std::fs::read_to_string(recorder.path()).unwrap();
+        assert!(!raw.contains("sk-ant"));
+        let exchange = RecordedExchange::load(recorder.path()).unwrap();
+        assert_eq!(exchange.wire, WireFormat::Anthropic);
+        assert_eq!(exchange.chunks.len(), 3);
+
+        let events: Vec<_> = exchange.replay().unwrap().collect().await;
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, Ok(StreamEvent::TextDelta(text)) if text == "hi")));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn replays_google_captures() {
+        let frames = [
+            RecordedFrame::Request {
+                provider: "google".to_string(),
+                wire: WireFormat::Google,
+                method: "POST".to_string(),
+                url: "https://example.test/m:streamGenerateContent?key=[REDACTED]".to_string(),
+                headers: BTreeMap::new(),
+                body: serde_json::json!({}),
+                recorded_at: chrono::Utc::now(),
+                session_id: None,
+            },
+            RecordedFrame::Chunk {
+                data: "data: {\"candidates\":[{\"content\":{\"role\":\"model\",\"parts\":[{\"text\":\"hi\"}]}}]}\n"
+                    .to_string(),
+            },
+        ];
+        let exchange = RecordedExchange::from_frames(frames.map(Ok)).unwrap();
+
+        let events: Vec<_> = exchange.replay().unwrap().collect().await;
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, Ok(StreamEvent::TextDelta(text)) if text == "hi")));
+    }
+
+    #[test]
+    fn begin_is_inert_without_recording() {
+        if env_recording_dir().is_some() {
+            return;
+        }
+        assert!(!is_recording("ses_never_enabled"));
+        assert!(ExchangeRecorder::begin(
+            "openai",
+            WireFormat::OpenaiChat,
+            "POST",
+            "https://api.openai.com/v1/chat/completions",
+            &HashMap::new(),
+            "{}",
+        )
+        .is_none());
+    }
+}
@@ -21,6 +21,7 @@ use tokio_stream::wrappers::ReceiverStream;
 use crate::custom_fetch::{get_custom_fetch_proxy, CustomFetchRequest};
 use crate::message::{Content, ContentPart, Message, Role, ToolResult, ToolUse};
 use crate::provider::ProviderError;
+use crate::recorder::{ExchangeRecorder, WireFormat};
 use crate::responses_convert::convert_to_openai_responses_input;
 use crate::stream::{StreamEvent, StreamResult, StreamUsage, ToolResultOutput};
 use crate::tools::{prepare_responses_tools, InputTool, InputToolChoice, ResponsesTool};
@@ -1280,6 +1281,14 @@ impl OpenAIResponsesLanguageModel {
         let headers = self.build_headers("application/json");
         let request_body = serde_json::to_string(&prepared.body)
             .map_err(|e| ProviderError::InvalidRequest(format!("failed to encode body: {}", e)))?;
+        let recorder = ExchangeRecorder::begin(
+            &self.config.provider,
+            WireFormat::OpenaiResponses,
+            "POST",
+            &url,
+            &headers,
+            &request_body,
+        );
 
         let (status_code, raw) = if let Some(proxy) = get_custom_fetch_proxy(&self.config.provider)
         {
@@ -1291,6 +1300,9 @@ impl OpenAIResponsesLanguageModel {
                     body: Some(request_body),
                 })
                 .await?;
+            if let Some(recorder) = &recorder {
+                recorder.proxied_response(response.status, &response.headers);
+            }
             (response.status, response.body)
         } else {
             let client = self.config.client.clone().unwrap_or_default();
@@ -1304,6 +1316,10 @@ impl OpenAIResponsesLanguageModel {
                 .await
                 .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
             let status = response.status().as_u16();
+            crate::governor::observe(&response);
+            if let Some(recorder) = &recorder {
+                recorder.response(status, response.headers());
+            }
             let body = response.text().await.map_err(|e| {
                 let mut msg = e.to_string();
                 let mut source = std::error::Error::source(&e);
@@ -1316,6 +1332,9 @@ impl OpenAIResponsesLanguageModel {
             })?;
             (status, body)
         };
+        if let Some(recorder) = &recorder {
+            recorder.body(&raw);
+        }
         if status_code >= 400 {
             return Err(ProviderError::ApiErrorWithStatus {
                 message: raw,
@@ -1398,6 +1417,14 @@ impl OpenAIResponsesLanguageModel {
         let headers = self.build_headers("text/event-stream");
         let request_body = serde_json::to_string(&prepared.body)
             .map_err(|e| ProviderError::InvalidRequest(format!("failed to encode body: {}", e)))?;
+        let recorder = ExchangeRecorder::begin(
+            &self.config.provider,
+            WireFormat::OpenaiResponses,
+            "POST",
+            &url,
+            &headers,
+            &request_body,
+        );
         let text_stream: Pin<Box<dyn Stream<Item = Result<String, ProviderError>> + Send>> =
             if let Some(proxy) = get_custom_fetch_proxy(&self.config.provider) {
                 let response = proxy
@@ -1408,6 +1435,9 @@ impl OpenAIResponsesLanguageModel {
                         body: Some(request_body),
                     })
                     .await?;
+                if let Some(recorder) = &recorder {
+                    recorder.proxied_response(response.status, &response.headers);
+                }
                 if response.status >= 400 {
                     return Err(ProviderError::ApiErrorWithStatus {
                         message: format!(
@@ -1430,8 +1460,15 @@ impl OpenAIResponsesLanguageModel {
                     .await
                     .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
                 let status = response.status();
+                crate::governor::observe(&response);
+                if let Some(recorder) = &recorder {
+                    recorder.response(status.as_u16(), response.headers());
+                }
                 if !status.is_success() {
                     let body = response.text().await.unwrap_or_default();
+                    if let Some(recorder) = &recorder {
+                        recorder.body(&body);
+                    }
                     return Err(ProviderError::ApiErrorWithStatus {
                         message: body,
                         status_code: status.as_u16(),
@@ -1452,148 +1489,158 @@ impl OpenAIResponsesLanguageModel {
             .as_ref()
             .map(|extractor| extractor.create_stream_extractor());
 
-        let (tx, rx) = mpsc::channel::<Result<StreamEvent, ProviderError>>(256);
-        tokio::spawn(async move {
-            let _ = tx.send(Ok(StreamEvent::Start)).await;
-            let _ = tx.send(Ok(StreamEvent::StartStep)).await;
-
-            let tx = tx;
-            let mut text_stream = text_stream;
-            let mut stream_metadata_extractor = metadata_extractor;
-
-            let mut buffer = String::new();
-            let mut finish_reason = FinishReason::Unknown;
-            let mut usage = ResponsesUsage::default();
-            let mut logprobs: Vec<Vec<LogprobEntry>> = Vec::new();
-            let mut response_id: Option<String> = None;
-            let mut ongoing_tool_calls: HashMap<usize, OngoingToolCall> = HashMap::new();
-            let mut has_function_call = false;
-            let mut active_reasoning: HashMap<usize, ActiveReasoning> = HashMap::new();
-            let mut current_reasoning_output_index: Option<usize> = None;
-            let mut reasoning_item_to_output_index: HashMap<String, usize> = HashMap::new();
-            let mut current_text_id: Option<String> = None;
-            let mut text_open = false;
-            let mut service_tier: Option<String> = None;
-
-            while let Some(chunk_result) = text_stream.next().await {
-                let chunk = match chunk_result {
-                    Ok(text) => text,
-                    Err(err) => {
-                        let _ = tx.send(Err(err)).await;
-                        return;
-                    }
-                };
-                buffer.push_str(&chunk);
-
-                while let Some(frame) = drain_next_sse_frame(&mut buffer) {
-                    let Some(data) = extract_sse_data(&frame) else {
-                        continue;
-                    };
-                    if data == "[DONE]" {
-                        break;
-                    }
-
-                    let parsed_value: Value = match serde_json::from_str(&data) {
-                        Ok(value) => value,
-                        Err(_) => continue,
-                    };
-                    if let Some(extractor) = stream_metadata_extractor.as_mut() {
-                        extractor.process_chunk(&parsed_value);
-                    }
+        Ok(responses_event_stream(
+            crate::recorder::tap(text_stream, recorder),
+            metadata_extractor,
+        ))
+    }
+}
 
-                    let parsed_chunk: ResponsesStreamChunk = serde_json::from_value(parsed_value)
-                        .unwrap_or(ResponsesStreamChunk::Unknown);
-
-                    for event in process_stream_chunk(
-                        parsed_chunk,
-                        &mut finish_reason,
-                        &mut usage,
-                        &mut logprobs,
-                        &mut response_id,
-                        &mut ongoing_tool_calls,
-                        &mut has_function_call,
-                        &mut active_reasoning,
-                        &mut current_reasoning_output_index,
-                        &mut reasoning_item_to_output_index,
-                        &mut current_text_id,
-                        &mut text_open,
-                        &mut service_tier,
-                    ) {
-                        if tx.send(Ok(event)).await.is_err() {
-                            return;
-                        }
-                    }
-                }
-            }
+/// Parses raw Responses API SSE chunks into stream events.
+pub(crate) fn responses_event_stream<S>(
+    text_stream: S,
+    metadata_extractor: Option<Box<dyn StreamMetadataExtractor>>,
+) -> StreamResult
+where
+    S: Stream<Item = Result<String, ProviderError>> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel::<Result<StreamEvent, ProviderError>>(256);
+    tokio::spawn(async move {
+        let _ = tx.send(Ok(StreamEvent::Start)).await;
+        let _ = tx.send(Ok(StreamEvent::StartStep)).await;
+
+        let tx = tx;
+        let mut text_stream = Box::pin(text_stream);
+        let mut stream_metadata_extractor = metadata_extractor;
+
+        let mut buffer = String::new();
+        let mut finish_reason = FinishReason::Unknown;
+        let mut usage = ResponsesUsage::default();
+        let mut logprobs: Vec<Vec<LogprobEntry>> = Vec::new();
+        let mut response_id: Option<String> = None;
+        let mut ongoing_tool_calls: HashMap<usize, OngoingToolCall> = HashMap::new();
+        let mut has_function_call = false;
+        let mut active_reasoning: HashMap<usize, ActiveReasoning> = HashMap::new();
+        let mut current_reasoning_output_index: Option<usize> = None;
+        let mut reasoning_item_to_output_index: HashMap<String, usize> = HashMap::new();
+        let mut current_text_id: Option<String> = None;
+        let mut text_open = false;
+        let mut service_tier: Option<String> = None;
 
-            if text_open {
-                if tx.send(Ok(StreamEvent::TextEnd)).await.is_err() {
+        while let Some(chunk_result) = text_stream.next().await {
+            let chunk = match chunk_result {
+                Ok(text) => text,
+                Err(err) => {
+                    let _ = tx.send(Err(err)).await;
                     return;
                 }
-            }
-            for ongoing in ongoing_tool_calls.into_values() {
-                if tx
-                    .send(Ok(StreamEvent::ToolInputEnd {
-                        id: ongoing.tool_call_id,
-                    }))
-                    .await
-                    .is_err()
-                {
-                    return;
+            };
+            buffer.push_str(&chunk);
+
+            while let Some(frame) = drain_next_sse_frame(&mut buffer) {
+                let Some(data) = extract_sse_data(&frame) else {
+                    continue;
+                };
+                if data == "[DONE]" {
+                    break;
                 }
-            }
-            for reasoning in active_reasoning.into_values() {
-                if tx
-                    .send(Ok(StreamEvent::ReasoningEnd {
-                        id: reasoning.canonical_id,
-                    }))
-                    .await
-                    .is_err()
-                {
-                    return;
+
+                let parsed_value: Value = match serde_json::from_str(&data) {
+                    Ok(value) => value,
+                    Err(_) => continue,
+                };
+                if let Some(extractor) = stream_metadata_extractor.as_mut() {
+                    extractor.process_chunk(&parsed_value);
                 }
-            }
 
-            let mut provider_metadata = json!({
-                "response_id": response_id,
-                "service_tier": service_tier,
-            });
-            if !logprobs.is_empty() {
-                provider_metadata["logprobs"] =
-                    serde_json::to_value(logprobs).unwrap_or(Value::Null);
-            }
-            if let Some(extractor) = stream_metadata_extractor.as_ref() {
-                if let Some(extra) = extractor.build_metadata() {
-                    provider_metadata["metadata"] =
-                        serde_json::to_value(extra).unwrap_or(Value::Null);
+                let parsed_chunk: ResponsesStreamChunk =
+                    serde_json::from_value(parsed_value).unwrap_or(ResponsesStreamChunk::Unknown);
+
+                for event in process_stream_chunk(
+                    parsed_chunk,
+                    &mut finish_reason,
+                    &mut usage,
+                    &mut logprobs,
+                    &mut response_id,
+                    &mut ongoing_tool_calls,
+                    &mut has_function_call,
+                    &mut active_reasoning,
+                    &mut current_reasoning_output_index,
+                    &mut reasoning_item_to_output_index,
+                    &mut current_text_id,
+                    &mut text_open,
+                    &mut service_tier,
+                ) {
+                    if tx.send(Ok(event)).await.is_err() {
+                        return;
+                    }
                 }
             }
+        }
 
-            let resolved_reason = if finish_reason == FinishReason::Unknown {
-                map_openai_response_finish_reason(None, has_function_call)
-            } else {
-                finish_reason
-            };
-
+        if text_open && tx.send(Ok(StreamEvent::TextEnd)).await.is_err() {
+            return;
+        }
+        for ongoing in ongoing_tool_calls.into_values() {
             if tx
-                .send(Ok(StreamEvent::FinishStep {
-                    finish_reason: Some(finish_reason_label(resolved_reason).to_string()),
-                    usage: usage_to_stream_usage(&usage),
-                    provider_metadata: Some(provider_metadata),
+                .send(Ok(StreamEvent::ToolInputEnd {
+                    id: ongoing.tool_call_id,
                 }))
                 .await
                 .is_err()
             {
                 return;
             }
-            if tx.send(Ok(StreamEvent::Finish)).await.is_err() {
+        }
+        for reasoning in active_reasoning.into_values() {
+            if tx
+                .send(Ok(StreamEvent::ReasoningEnd {
+                    id: reasoning.canonical_id,
+                }))
+                .await
+                .is_err()
+            {
                 return;
             }
-            let _ = tx.send(Ok(StreamEvent::Done)).await;
+        }
+
+        let mut provider_metadata = json!({
+            "response_id": response_id,
+            "service_tier": service_tier,
         });
+        if !logprobs.is_empty() {
+            provider_metadata["logprobs"] = serde_json::to_value(logprobs).unwrap_or(Value::Null);
+        }
+        if let Some(extractor) = stream_metadata_extractor.as_ref() {
+            if let Some(extra) = extractor.build_metadata() {
+                provider_metadata["metadata"] = serde_json::to_value(extra).unwrap_or(Value::Null);
+            }
+        }
 
-        Ok(Box::pin(ReceiverStream::new(rx)))
-    }
+        let resolved_reason = if finish_reason == FinishReason::Unknown {
+            map_openai_response_finish_reason(None, has_function_call)
+        } else {
+            finish_reason
+        };
+
+        if tx
+            .send(Ok(StreamEvent::FinishStep {
+                finish_reason: Some(finish_reason_label(resolved_reason).to_string()),
+                usage: usage_to_stream_usage(&usage),
+                provider_metadata: Some(provider_metadata),
+            }))
+            .await
+            .is_err()
+        {
+            return;
+        }
+        if tx.send(Ok(StreamEvent::Finish)).await.is_err() {
+            return;
+        }
+        let _ = tx.send(Ok(StreamEvent::Done)).await;
+    });
+
+    Box::pin(ReceiverStream::new(rx))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -2300,19 +2347,21 @@ fn provider_executed_tool_parts(
 }
 
 fn usage_to_stream_usage(usage: &ResponsesUsage) -> StreamUsage {
+    let cached = usage
+        .input_tokens_details
+        .as_ref()
+        .and_then(|d| d.cached_tokens)
+        .unwrap_or(0);
     StreamUsage {
-        prompt_tokens: usage.input_tokens,
+        // `input_tokens` includes cache hits; `StreamUsage` counts them apart.
+        prompt_tokens: usage.input_tokens.saturating_sub(cached),
         completion_tokens: usage.output_tokens,
         reasoning_tokens: usage
             .output_tokens_details
             .as_ref()
             .and_then(|d| d.reasoning_tokens)
             .unwrap_or(0),
-        cache_read_tokens: usage
-            .input_tokens_details
-            .as_ref()
-            .and_then(|d| d.cached_tokens)
-            .unwrap_or(0),
+        cache_read_tokens: cached,
         cache_write_tokens: 0,
     }
 }
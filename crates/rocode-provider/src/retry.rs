@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::future::Future;
 use tokio::sync::watch;
 use tracing::warn;
@@ -46,6 +47,69 @@ impl Default for RetryConfig {
 pub trait IsRetryable {
     fn is_retryable(&self) -> Option<String>;
 }
+
+// ---------------------------------------------------------------------------
+// FailoverReason – errors that move a request down a fallback chain
+// ---------------------------------------------------------------------------
+
+/// Why a request left its model for the next one in a fallback chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailoverReason {
+    RateLimited,
+    Overloaded,
+    ServerError(u16),
+    Unreachable,
+}
+
+impl FailoverReason {
+    /// Classify a provider error. Only capacity and availability failures
+    /// fail over: auth, request and context errors would fail on any model.
+    pub fn classify(error: &crate::ProviderError) -> Option<Self> {
+        use crate::ProviderError;
+
+        match error {
+            ProviderError::RateLimit => Some(Self::RateLimited),
+            ProviderError::ApiErrorWithStatus { status_code, .. } => match status_code {
+                429 => Some(Self::RateLimited),
+                503 | 529 => Some(Self::Overloaded),
+                500 | 502 | 504 => Some(Self::ServerError(*status_code)),
+                _ => None,
+            },
+            ProviderError::ApiError(message) | ProviderError::StreamError(message) => {
+                Self::classify_message(message)
+            }
+            ProviderError::Timeout | ProviderError::NetworkError(_) => Some(Self::Unreachable),
+            _ => None,
+        }
+    }
+
+    /// Classify an error reported as text, such as a `StreamEvent::Error`
+    /// sent after the response started, where no status code is available.
+    pub fn classify_message(message: &str) -> Option<Self> {
+        let message = message.to_lowercase();
+        if message.contains("overloaded") {
+            Some(Self::Overloaded)
+        } else if message.contains("rate limit")
+            || message.contains("rate_limit")
+            || message.contains("too many requests")
+        {
+            Some(Self::RateLimited)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for FailoverReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::RateLimited => write!(f, "rate limited"),
+            Self::Overloaded => write!(f, "overloaded"),
+            Self::ServerError(status) => write!(f, "server error {status}"),
+            Self::Unreachable => write!(f, "unreachable"),
+        }
+    }
+}
 // ---------------------------------------------------------------------------
 // sleep – cancellable async sleep
 // ---------------------------------------------------------------------------
@@ -228,6 +292,60 @@ where
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_failover_classification() {
+        use crate::ProviderError;
+
+        assert_eq!(
+            FailoverReason::classify(&ProviderError::RateLimit),
+            Some(FailoverReason::RateLimited)
+        );
+        assert_eq!(
+            FailoverReason::classify(&ProviderError::ApiErrorWithStatus {
+                message: "overloaded".to_string(),
+                status_code: 529,
+            }),
+            Some(FailoverReason::Overloaded)
+        );
+        assert_eq!(
+            FailoverReason::classify(&ProviderError::ApiErrorWithStatus {
+                message: "bad gateway".to_string(),
+                status_code: 502,
+            }),
+            Some(FailoverReason::ServerError(502))
+        );
+        assert_eq!(
+            FailoverReason::classify(&ProviderError::StreamError(
+                "{\"type\":\"overloaded_error\"}".to_string()
+            )),
+            Some(FailoverReason::Overloaded)
+        );
+        assert_eq!(
+            FailoverReason::classify_message("rate_limit_error: Number of requests exceeded"),
+            Some(FailoverReason::RateLimited)
+        );
+        assert_eq!(
+            FailoverReason::classify_message("invalid tool schema"),
+            None
+        );
+        // Errors another model would hit too stay on the current model.
+        assert_eq!(
+            FailoverReason::classify(&ProviderError::ApiErrorWithStatus {
+                message: "bad request".to_string(),
+                status_code: 400,
+            }),
+            None
+        );
+        assert_eq!(
+            FailoverReason::classify(&ProviderError::AuthError("expired".to_string())),
+            None
+        );
+        assert_eq!(
+            FailoverReason::classify(&ProviderError::ContextOverflow("too long".to_string())),
+            None
+        );
+    }
+
     #[test]
     fn test_exponential_backoff_no_headers() {
         // Without headers the delay is capped at 30 000 ms.
@@ -82,6 +82,10 @@ pub enum StreamEvent {
     Usage {
         prompt_tokens: u64,
         completion_tokens: u64,
+        #[serde(default)]
+        cache_read_tokens: u64,
+        #[serde(default)]
+        cache_write_tokens: u64,
     },
     /// Stream finished (maps to "finish" in TS).
     Finish,
@@ -110,7 +114,10 @@ pub struct ToolResultOutput {
     pub attachments: Option<Vec<serde_json::Value>>,
 }
 
-/// Usage information from a step completion.
+/// Usage information from a step completion. `prompt_tokens` excludes the
+/// cache reads and writes counted separately. `completion_tokens` includes
+/// `reasoning_tokens`, which only break it down; adapters for providers that
+/// report thinking apart from output add it to `completion_tokens`.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct StreamUsage {
     pub prompt_tokens: u64,
@@ -167,6 +174,23 @@ pub struct OpenAIUsage {
     pub prompt_tokens: u64,
     #[serde(default)]
     pub completion_tokens: u64,
+    #[serde(default)]
+    pub prompt_tokens_details: Option<OpenAIPromptTokensDetails>,
+}
+
+impl OpenAIUsage {
+    fn cached_tokens(&self) -> u64 {
+        self.prompt_tokens_details
+            .as_ref()
+            .and_then(|d| d.cached_tokens)
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenAIPromptTokensDetails {
+    #[serde(default)]
+    pub cached_tokens: Option<u64>,
 }
 
 fn openai_tool_call_id(tc: &OpenAIToolCall) -> String {
@@ -334,8 +358,10 @@ pub fn parse_openai_sse(data: &str) -> Vec<StreamEvent> {
 
     let mut events = Vec::new();
     let usage = event.usage.as_ref().map(|u| StreamUsage {
-        prompt_tokens: u.prompt_tokens,
+        // `prompt_tokens` includes cache hits; `StreamUsage` counts them apart.
+        prompt_tokens: u.prompt_tokens.saturating_sub(u.cached_tokens()),
         completion_tokens: u.completion_tokens,
+        cache_read_tokens: u.cached_tokens(),
         ..Default::default()
     });
 
@@ -395,10 +421,12 @@ pub fn parse_openai_sse(data: &str) -> Vec<StreamEvent> {
         }
     }
 
-    if let Some(usage) = event.usage {
+    if let Some(usage) = usage {
         events.push(StreamEvent::Usage {
             prompt_tokens: usage.prompt_tokens,
             completion_tokens: usage.completion_tokens,
+            cache_read_tokens: usage.cache_read_tokens,
+            cache_write_tokens: 0,
         });
     }
 
@@ -442,6 +470,10 @@ pub struct AnthropicMessage {
 pub struct AnthropicUsage {
     pub input_tokens: u64,
     pub output_tokens: u64,
+    #[serde(default)]
+    pub cache_read_input_tokens: u64,
+    #[serde(default)]
+    pub cache_creation_input_tokens: u64,
 }
 
 pub fn parse_anthropic_sse(data: &str) -> Option<StreamEvent> {
@@ -494,6 +526,8 @@ pub fn parse_anthropic_sse(data: &str) -> Option<StreamEvent> {
                     return Some(StreamEvent::Usage {
                         prompt_tokens: usage.input_tokens,
                         completion_tokens: usage.output_tokens,
+                        cache_read_tokens: usage.cache_read_input_tokens,
+                        cache_write_tokens: usage.cache_creation_input_tokens,
                     });
                 }
             }
@@ -673,6 +707,26 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_openai_sse_splits_cached_prompt_tokens() {
+        let data = r#"{"choices":[{"finish_reason":"stop"}],"usage":{"prompt_tokens":100,"completion_tokens":5,"prompt_tokens_details":{"cached_tokens":80}}}"#;
+        let events = parse_openai_sse(data);
+        assert!(matches!(
+            &events[0],
+            StreamEvent::FinishStep { usage, .. }
+                if usage.prompt_tokens == 20 && usage.cache_read_tokens == 80
+        ));
+        assert!(matches!(
+            events.last(),
+            Some(StreamEvent::Usage {
+                prompt_tokens: 20,
+                completion_tokens: 5,
+                cache_read_tokens: 80,
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn parse_anthropic_sse_uses_index_for_tool_start_id() {
         let data = r#"{"type":"content_block_start","index":3,"content_block":{"type":"tool_use","id":"toolu_abc","name":"bash","input":{}}}"#;
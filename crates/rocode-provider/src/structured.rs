@@ -0,0 +1,611 @@
+//! Provider-neutral structured output.
+//!
+//! A [`ResponseFormat`] carries the JSON Schema the final answer must satisfy.
+//! Each backend maps it onto its native mechanism (OpenAI `response_format`,
+//! Anthropic tool forcing, Gemini `responseSchema`); [`generate_structured`]
+//! adds the validate-and-repair loop on top so callers always get back a
+//! value that matches the schema, or an error saying why not.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::{ChatRequest, ChatResponse, Content, Message, Provider, ProviderError, ToolDefinition};
+
+/// Name of the tool Anthropic is forced to call when a response format is set.
+pub const STRUCTURED_OUTPUT_TOOL: &str = "StructuredOutput";
+
+/// Repair rounds after the first answer fails validation.
+pub const DEFAULT_STRUCTURED_RETRIES: u32 = 2;
+
+const DEFAULT_FORMAT_NAME: &str = "response";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseFormat {
+    #[serde(default = "default_format_name")]
+    pub name: String,
+    pub schema: Value,
+    /// Ask OpenAI for strict schema adherence. Strict mode only accepts
+    /// schemas where every object is closed and every property required.
+    #[serde(default)]
+    pub strict: bool,
+}
+
+fn default_format_name() -> String {
+    DEFAULT_FORMAT_NAME.to_string()
+}
+
+impl ResponseFormat {
+    pub fn json_schema(mut schema: Value) -> Self {
+        if let Some(obj) = schema.as_object_mut() {
+            obj.remove("$schema");
+        }
+        let name = schema
+            .get("title")
+            .and_then(Value::as_str)
+            .map(sanitize_format_name)
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(default_format_name);
+        Self {
+            name,
+            schema,
+            strict: false,
+        }
+    }
+
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// `response_format` for OpenAI-compatible chat completions.
+    pub fn openai_chat_format(&self) -> Value {
+        json!({
+            "type": "json_schema",
+            "json_schema": {
+                "name": self.name,
+                "schema": self.schema,
+                "strict": self.strict,
+            }
+        })
+    }
+
+    /// `text.format` for the OpenAI Responses API.
+    pub fn openai_responses_format(&self) -> Value {
+        json!({
+            "type": "json_schema",
+            "name": self.name,
+            "schema": self.schema,
+            "strict": self.strict,
+        })
+    }
+
+    /// Tool Anthropic is forced to call; its input is the structured answer.
+    pub fn anthropic_tool(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: STRUCTURED_OUTPUT_TOOL.to_string(),
+            description: Some(
+                "Return the final answer. The input must match the requested schema.".to_string(),
+            ),
+            parameters: self.schema.clone(),
+        }
+    }
+
+    /// Gemini `responseSchema`: the OpenAPI subset Gemini accepts.
+    pub fn gemini_schema(&self) -> Value {
+        crate::transform::sanitize_gemini(strip_gemini_unsupported(self.schema.clone()))
+    }
+
+    /// Prompt text that asks for the format. Sent to every backend so models
+    /// without a native mechanism still know what to produce.
+    pub fn instructions(&self) -> String {
+        format!(
+            "Respond with only a JSON value matching this JSON Schema. \
+             Do not add prose or code fences.\n\n{}",
+            serde_json::to_string_pretty(&self.schema).unwrap_or_default()
+        )
+    }
+
+    pub fn repair_prompt(&self, errors: &[String]) -> String {
+        format!(
+            "That response did not match the schema:\n- {}\n\nReply again with only the corrected JSON value.",
+            errors.join("\n- ")
+        )
+    }
+
+    /// Schema violations in `value`, one message per problem.
+    pub fn validate(&self, value: &Value) -> Vec<String> {
+        let mut errors = Vec::new();
+        validate_value(&self.schema, value, "$", &mut errors);
+        errors
+    }
+}
+
+fn sanitize_format_name(title: &str) -> String {
+    title
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .take(64)
+        .collect()
+}
+
+fn strip_gemini_unsupported(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(key, _)| {
+                    !matches!(key.as_str(), "$schema" | "additionalProperties" | "title")
+                })
+                .map(|(key, value)| (key, strip_gemini_unsupported(value)))
+                .collect(),
+        ),
+        Value::Array(items) => {
+            Value::Array(items.into_iter().map(strip_gemini_unsupported).collect())
+        }
+        other => other,
+    }
+}
+
+/// Parses a model's answer as JSON, tolerating code fences and stray prose
+/// around a single object or array.
+pub fn parse_json_output(text: &str) -> Result<Value, String> {
+    let trimmed = strip_code_fence(text.trim());
+    if trimmed.is_empty() {
+        return Err("response was empty".to_string());
+    }
+    let error = match serde_json::from_str::<Value>(trimmed) {
+        Ok(value) => return Ok(value),
+        Err(error) => error,
+    };
+    let start = trimmed.find(['{', '[']);
+    let end = trimmed.rfind(['}', ']']);
+    if let (Some(start), Some(end)) = (start, end) {
+        if start < end {
+            if let Ok(value) = serde_json::from_str::<Value>(&trimmed[start..=end]) {
+                return Ok(value);
+            }
+        }
+    }
+    Err(format!("response is not valid JSON: {}", error))
+}
+
+fn strip_code_fence(text: &str) -> &str {
+    let Some(rest) = text.strip_prefix("```") else {
+        return text;
+    };
+    let body = rest.split_once('\n').map_or("", |(_, body)| body);
+    body.trim_end().strip_suffix("```").unwrap_or(body).trim()
+}
+
+/// Text of the first choice, or the structured output tool input when forced.
+pub fn response_text(response: &ChatResponse) -> String {
+    let Some(choice) = response.choices.first() else {
+        return String::new();
+    };
+    match &choice.message.content {
+        Content::Text(text) => text.clone(),
+        Content::Parts(parts) => {
+            if let Some(tool_use) = parts.iter().find_map(|part| {
+                part.tool_use
+                    .as_ref()
+                    .filter(|tool_use| tool_use.name == STRUCTURED_OUTPUT_TOOL)
+            }) {
+                return tool_use.input.to_string();
+            }
+            parts
+                .iter()
+                .filter_map(|part| part.text.as_deref())
+                .collect::<Vec<_>>()
+                .join("")
+        }
+    }
+}
+
+fn validate_value(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    let schema = match schema {
+        Value::Bool(true) => return,
+        Value::Bool(false) => {
+            errors.push(format!("{path}: no value is allowed here"));
+            return;
+        }
+        Value::Object(schema) => schema,
+        _ => return,
+    };
+
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            errors.push(format!("{path}: expected {expected}"));
+        }
+    }
+    if let Some(Value::Array(options)) = schema.get("enum") {
+        if !options.contains(value) {
+            let options = options
+                .iter()
+                .map(Value::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            errors.push(format!("{path}: expected one of {options}"));
+        }
+    }
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|name| matches_type(name, value)) {
+            errors.push(format!(
+                "{path}: expected {}, got {}",
+                types.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+
+    match value {
+        Value::Object(object) => validate_object(schema, object, path, errors),
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    validate_value(item_schema, item, &format!("{path}[{index}]"), errors);
+                }
+            }
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if (items.len() as u64) < min {
+                    errors.push(format!("{path}: expected at least {min} items"));
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if items.len() as u64 > max {
+                    errors.push(format!("{path}: expected at most {max} items"));
+                }
+            }
+        }
+        Value::String(text) => {
+            let length = text.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if length < min {
+                    errors.push(format!("{path}: expected at least {min} characters"));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                if length > max {
+                    errors.push(format!("{path}: expected at most {max} characters"));
+                }
+            }
+            if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+                if let Ok(regex) = regex::Regex::new(pattern) {
+                    if !regex.is_match(text) {
+                        errors.push(format!("{path}: does not match pattern {pattern}"));
+                    }
+                }
+            }
+        }
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            let bound = |key: &str| schema.get(key).and_then(Value::as_f64);
+            if bound("minimum").is_some_and(|min| number < min)
+                || bound("exclusiveMinimum").is_some_and(|min| number <= min)
+            {
+                errors.push(format!("{path}: {number} is below the minimum"));
+            }
+            if bound("maximum").is_some_and(|max| number > max)
+                || bound("exclusiveMaximum").is_some_and(|max| number >= max)
+            {
+                errors.push(format!("{path}: {number} is above the maximum"));
+            }
+        }
+        _ => {}
+    }
+
+    let passes = |sub: &Value| {
+        let mut sub_errors = Vec::new();
+        validate_value(sub, value, path, &mut sub_errors);
+        sub_errors.is_empty()
+    };
+    if let Some(Value::Array(all)) = schema.get("allOf") {
+        for sub in all {
+            validate_value(sub, value, path, errors);
+        }
+    }
+    if let Some(Value::Array(any)) = schema.get("anyOf") {
+        if !any.iter().any(passes) {
+            errors.push(format!("{path}: does not match any allowed shape"));
+        }
+    }
+    if let Some(Value::Array(one)) = schema.get("oneOf") {
+        if one.iter().filter(|sub| passes(sub)).count() != 1 {
+            errors.push(format!("{path}: must match exactly one allowed shape"));
+        }
+    }
+}
+
+fn validate_object(
+    schema: &Map<String, Value>,
+    object: &Map<String, Value>,
+    path: &str,
+    errors: &mut Vec<String>,
+) {
+    if let Some(Value::Array(required)) = schema.get("required") {
+        for key in required.iter().filter_map(Value::as_str) {
+            if !object.contains_key(key) {
+                errors.push(format!("{path}: missing required property `{key}`"));
+            }
+        }
+    }
+    let properties = schema.get("properties").and_then(Value::as_object);
+    for (key, item) in object {
+        let item_path = format!("{path}.{key}");
+        match properties.and_then(|properties| properties.get(key)) {
+            Some(item_schema) => validate_value(item_schema, item, &item_path, errors),
+            None => match schema.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    errors.push(format!("{path}: unexpected property `{key}`"))
+                }
+                Some(extra) => validate_value(extra, item, &item_path, errors),
+                None => {}
+            },
+        }
+    }
+}
+
+fn matches_type(name: &str, value: &Value) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// A validated structured answer.
+#[derive(Debug, Clone)]
+pub struct StructuredOutput {
+    pub value: Value,
+    /// Requests made, including repairs.
+    pub attempts: u32,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StructuredOutputError {
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+
+    #[error("structured output failed validation after {attempts} attempts: {}", errors.join("; "))]
+    Invalid { attempts: u32, errors: Vec<String> },
+}
+
+/// Asks for `format` on top of `request` and repairs the answer until it
+/// validates, making at most `retries + 1` requests. Tools are dropped: this
+/// is for producing the final answer, not for doing more work.
+pub async fn generate_structured(
+    provider: &dyn Provider,
+    mut request: ChatRequest,
+    format: &ResponseFormat,
+    retries: u32,
+) -> Result<StructuredOutput, StructuredOutputError> {
+    request.tools = None;
+    request.stream = Some(false);
+    request.response_format = Some(format.clone());
+    request.messages.push(Message::user(format.instructions()));
+
+    let mut errors = Vec::new();
+    for attempt in 1..=retries + 1 {
+        let response = provider.chat(request.clone()).await?;
+        let text = response_text(&response);
+        errors = match parse_json_output(&text) {
+            Ok(value) => {
+                let violations = format.validate(&value);
+                if violations.is_empty() {
+                    return Ok(StructuredOutput {
+                        value,
+                        attempts: attempt,
+                    });
+                }
+                violations
+            }
+            Err(error) => vec![error],
+        };
+        tracing::debug!(attempt, ?errors, "structured output failed validation");
+        request.messages.push(Message::assistant(text));
+        request
+            .messages
+            .push(Message::user(format.repair_prompt(&errors)));
+    }
+
+    Err(StructuredOutputError::Invalid {
+        attempts: retries + 1,
+        errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Choice, ModelInfo, StreamResult};
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    fn person_format() -> ResponseFormat {
+        ResponseFormat::json_schema(json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "person record",
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "minLength": 1},
+                "age": {"type": "integer", "minimum": 0},
+                "tags": {"type": "array", "items": {"type": "string"}},
+                "role": {"enum": ["admin", "user"]}
+            },
+            "required": ["name", "age"],
+            "additionalProperties": false
+        }))
+    }
+
+    #[test]
+    fn json_schema_strips_meta_and_names_format() {
+        let format = person_format();
+        assert_eq!(format.name, "person_record");
+        assert!(format.schema.get("$schema").is_none());
+        assert_eq!(
+            format.openai_chat_format()["json_schema"]["name"],
+            "person_record"
+        );
+        let gemini = format.gemini_schema();
+        assert!(gemini.get("additionalProperties").is_none());
+        assert!(gemini.get("title").is_none());
+    }
+
+    #[test]
+    fn validates_nested_values() {
+        let format = person_format();
+        assert!(format
+            .validate(&json!({"name": "Ada", "age": 36, "tags": ["math"]}))
+            .is_empty());
+
+        let errors = format.validate(&json!({
+            "name": "",
+            "age": 1.5,
+            "tags": ["ok", 3],
+            "role": "owner",
+            "extra": true
+        }));
+        assert_eq!(
+            errors,
+            vec![
+                "$.age: expected integer, got number".to_string(),
+                "$: unexpected property `extra`".to_string(),
+                "$.name: expected at least 1 characters".to_string(),
+                "$.role: expected one of \"admin\", \"user\"".to_string(),
+                "$.tags[1]: expected string, got number".to_string(),
+            ]
+        );
+        assert_eq!(
+            format.validate(&json!({"name": "Ada"})),
+            vec!["$: missing required property `age`".to_string()]
+        );
+    }
+
+    #[test]
+    fn parses_fenced_and_wrapped_json() {
+        assert_eq!(
+            parse_json_output("```json\n{\"a\": 1}\n```").unwrap(),
+            json!({"a": 1})
+        );
+        assert_eq!(
+            parse_json_output("Here you go: {\"a\": [1, 2]} hope that helps").unwrap(),
+            json!({"a": [1, 2]})
+        );
+        assert!(parse_json_output("no json here").is_err());
+        assert!(parse_json_output("   ").is_err());
+    }
+
+    struct ScriptedProvider {
+        replies: Mutex<Vec<&'static str>>,
+        requests: Mutex<Vec<ChatRequest>>,
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        fn id(&self) -> &str {
+            "scripted"
+        }
+
+        fn name(&self) -> &str {
+            "Scripted"
+        }
+
+        fn models(&self) -> Vec<ModelInfo> {
+            Vec::new()
+        }
+
+        fn get_model(&self, _id: &str) -> Option<&ModelInfo> {
+            None
+        }
+
+        async fn chat(&self, request: ChatRequest) -> Result<ChatResponse, ProviderError> {
+            self.requests.lock().unwrap().push(request);
+            let reply = self.replies.lock().unwrap().remove(0);
+            Ok(ChatResponse {
+                id: "resp".to_string(),
+                model: "scripted".to_string(),
+                choices: vec![Choice {
+                    index: 0,
+                    message: Message::assistant(reply),
+                    finish_reason: Some("stop".to_string()),
+                }],
+                usage: None,
+            })
+        }
+
+        async fn chat_stream(&self, _request: ChatRequest) -> Result<StreamResult, ProviderError> {
+            Err(ProviderError::InvalidRequest("not streamed".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn repairs_until_output_validates() {
+        let provider = ScriptedProvider {
+            replies: Mutex::new(vec![
+                "{\"name\": \"Ada\"}",
+                "{\"name\": \"Ada\", \"age\": 36}",
+            ]),
+            requests: Mutex::new(Vec::new()),
+        };
+        let request = ChatRequest::new("scripted", vec![Message::user("who?")]);
+
+        let output = generate_structured(&provider, request, &person_format(), 2)
+            .await
+            .unwrap();
+        assert_eq!(output.value, json!({"name": "Ada", "age": 36}));
+        assert_eq!(output.attempts, 2);
+
+        let requests = provider.requests.lock().unwrap();
+        let repair = requests[1].messages.last().unwrap();
+        match &repair.content {
+            Content::Text(text) => assert!(text.contains("missing required property `age`")),
+            other => panic!("unexpected repair content: {other:?}"),
+        }
+        assert!(requests[1].response_format.is_some());
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_retries() {
+        let provider = ScriptedProvider {
+            replies: Mutex::new(vec!["nope", "still nope"]),
+            requests: Mutex::new(Vec::new()),
+        };
+        let request = ChatRequest::new("scripted", vec![Message::user("who?")]);
+
+        let error = generate_structured(&provider, request, &person_format(), 1)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            StructuredOutputError::Invalid { attempts: 2, .. }
+        ));
+    }
+}
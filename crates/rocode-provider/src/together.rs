@@ -32,6 +32,8 @@ impl TogetherProvider {
                     supports_tools: true,
                     cost_per_million_input: 0.88,
                     cost_per_million_output: 0.88,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "meta-llama/Llama-3.2-90B-Vision-Instruct-Turbo".to_string(),
@@ -44,6 +46,8 @@ impl TogetherProvider {
                     supports_tools: true,
                     cost_per_million_input: 0.88,
                     cost_per_million_output: 0.88,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "mistralai/Mixtral-8x7B-Instruct-v0.1".to_string(),
@@ -56,6 +60,8 @@ impl TogetherProvider {
                     supports_tools: false,
                     cost_per_million_input: 0.6,
                     cost_per_million_output: 0.6,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "Qwen/Qwen2.5-72B-Instruct-Turbo".to_string(),
@@ -68,6 +74,8 @@ impl TogetherProvider {
                     supports_tools: true,
                     cost_per_million_input: 0.88,
                     cost_per_million_output: 0.88,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "deepseek-ai/DeepSeek-V3".to_string(),
@@ -80,6 +88,8 @@ impl TogetherProvider {
                     supports_tools: true,
                     cost_per_million_input: 1.25,
                     cost_per_million_output: 1.25,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
             ],
         }
@@ -112,6 +122,7 @@ impl Provider for TogetherProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -139,6 +150,7 @@ impl Provider for TogetherProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
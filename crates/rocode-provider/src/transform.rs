@@ -1,5 +1,7 @@
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
 use crate::models;
 use crate::{CacheControl, Content, ContentPart, Message};
@@ -107,31 +109,146 @@ pub fn apply_caching(messages: &mut [Message], provider_type: ProviderType) {
         return;
     }
 
-    let system_indices: Vec<usize> = messages
+    for idx in plan_cache_breakpoints(messages) {
+        apply_cache_to_message(&mut messages[idx], provider_type);
+    }
+}
+
+/// Anthropic and Bedrock reject requests with more cache breakpoints than this.
+pub const MAX_CACHE_BREAKPOINTS: usize = 4;
+
+/// Chooses the messages that carry a cache breakpoint. Each breakpoint closes
+/// a prefix that stays byte-identical on the next request: the first system
+/// message (provider header), the last system message (the full system prompt,
+/// which also covers the tool definitions rendered before it), the end of the
+/// previous turn, and the final message, which the next step reads back.
+pub fn plan_cache_breakpoints(messages: &[Message]) -> Vec<usize> {
+    let is_system = |idx: usize| matches!(messages[idx].role, crate::Role::System);
+    let first_system = (0..messages.len()).find(|&idx| is_system(idx));
+    let last_system = (0..messages.len()).rev().find(|&idx| is_system(idx));
+    let previous_turn_end = messages
         .iter()
-        .enumerate()
-        .filter(|(_, m)| matches!(m.role, crate::Role::System))
-        .map(|(i, _)| i)
-        .take(2)
-        .collect();
-
-    let total = messages.len();
-    let final_indices: Vec<usize> = (total.saturating_sub(2)..total).collect();
-
-    let mut indices_to_cache: Vec<usize> = Vec::new();
-    for idx in system_indices.into_iter().chain(final_indices.into_iter()) {
-        if !indices_to_cache.contains(&idx) {
-            indices_to_cache.push(idx);
+        .rposition(|m| matches!(m.role, crate::Role::User))
+        .and_then(|idx| idx.checked_sub(1))
+        .filter(|&idx| !is_system(idx));
+    let final_message = messages.len().checked_sub(1);
+
+    let mut planned: Vec<usize> = Vec::new();
+    for idx in [first_system, last_system, previous_turn_end, final_message]
+        .into_iter()
+        .flatten()
+    {
+        if !planned.contains(&idx) {
+            planned.push(idx);
         }
     }
+    planned.sort_unstable();
+    // Later breakpoints cover longer prefixes, so they win when over budget.
+    let excess = planned.len().saturating_sub(MAX_CACHE_BREAKPOINTS);
+    planned.drain(..excess);
+    planned
+}
+
+/// Hashes of the parts of a request that must not change between steps for
+/// the provider prompt cache to hit. Taken before cache markers are applied.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheFingerprint {
+    pub system: u64,
+    pub tools: u64,
+    pub messages: Vec<u64>,
+}
 
-    for idx in indices_to_cache {
-        if let Some(msg) = messages.get_mut(idx) {
-            apply_cache_to_message(msg, provider_type);
+/// Why a request could not reuse the cached prefix of the previous one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheBust {
+    SystemChanged,
+    ToolsChanged,
+    /// A message the previous request already sent was edited or dropped.
+    PrefixRewritten {
+        index: usize,
+    },
+}
+
+impl fmt::Display for CacheBust {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CacheBust::SystemChanged => write!(f, "system prompt changed"),
+            CacheBust::ToolsChanged => write!(f, "tool definitions changed"),
+            CacheBust::PrefixRewritten { index } => {
+                write!(f, "conversation rewritten at message {}", index)
+            }
         }
     }
 }
 
+impl CacheFingerprint {
+    pub fn of(messages: &[Message], tools: &[crate::ToolDefinition]) -> Self {
+        let (system, conversation): (Vec<&Message>, Vec<&Message>) = messages
+            .iter()
+            .partition(|m| matches!(m.role, crate::Role::System));
+        Self {
+            system: stable_hash(
+                &system
+                    .iter()
+                    .map(|m| fingerprint_bytes(m))
+                    .collect::<Vec<_>>(),
+            ),
+            tools: stable_hash(
+                &tools
+                    .iter()
+                    .map(|t| serde_json::to_vec(t).unwrap_or_default())
+                    .collect::<Vec<_>>(),
+            ),
+            messages: conversation
+                .iter()
+                .map(|m| stable_hash(&fingerprint_bytes(m)))
+                .collect(),
+        }
+    }
+
+    /// Compares against the previous request in the same session. Appending
+    /// messages keeps the prefix; anything else busts the cache.
+    pub fn bust_since(&self, previous: &CacheFingerprint) -> Option<CacheBust> {
+        if self.system != previous.system {
+            return Some(CacheBust::SystemChanged);
+        }
+        if self.tools != previous.tools {
+            return Some(CacheBust::ToolsChanged);
+        }
+        previous
+            .messages
+            .iter()
+            .enumerate()
+            .find(|(idx, hash)| self.messages.get(*idx) != Some(*hash))
+            .map(|(index, _)| CacheBust::PrefixRewritten { index })
+    }
+}
+
+/// Role and content only: cache markers and provider options move between
+/// steps without changing what the provider caches.
+fn fingerprint_bytes(message: &Message) -> Vec<u8> {
+    let content = match &message.content {
+        Content::Text(text) => serde_json::json!(text),
+        Content::Parts(parts) => serde_json::json!(parts
+            .iter()
+            .map(|part| ContentPart {
+                cache_control: None,
+                provider_options: None,
+                ..part.clone()
+            })
+            .collect::<Vec<_>>()),
+    };
+    serde_json::to_vec(&(&message.role, content)).unwrap_or_default()
+}
+
+/// `DefaultHasher::new` uses fixed keys, so hashes are comparable across
+/// requests within a process.
+fn stable_hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
 fn apply_cache_to_message(message: &mut Message, provider_type: ProviderType) {
     // TS applyCaching uses providerOptions with multiple provider keys merged via mergeDeep.
     // We replicate that by setting provider_options on the message or its last content part.
@@ -750,28 +867,14 @@ pub fn normalize_interleaved_thinking(
 pub fn apply_caching_per_part(messages: &mut [Message], provider_type: &ProviderType) {
     match provider_type {
         ProviderType::Anthropic => {
-            if let Some(last_user) = messages
-                .iter_mut()
-                .rev()
-                .find(|m| matches!(m.role, crate::Role::User))
-            {
-                if let Content::Parts(ref mut parts) = last_user.content {
+            for idx in plan_cache_breakpoints(messages) {
+                let msg = &mut messages[idx];
+                msg.cache_control = Some(CacheControl::ephemeral());
+                if let Content::Parts(ref mut parts) = msg.content {
                     if let Some(last_part) = parts.last_mut() {
                         last_part.cache_control = Some(CacheControl::ephemeral());
                     }
                 }
-                last_user.cache_control = Some(CacheControl::ephemeral());
-            }
-
-            for msg in messages.iter_mut() {
-                if matches!(msg.role, crate::Role::System) {
-                    msg.cache_control = Some(CacheControl::ephemeral());
-                    if let Content::Parts(ref mut parts) = msg.content {
-                        if let Some(last_part) = parts.last_mut() {
-                            last_part.cache_control = Some(CacheControl::ephemeral());
-                        }
-                    }
-                }
             }
         }
         _ => {}
@@ -1456,7 +1559,7 @@ pub fn schema(model: &models::ModelInfo, input_schema: serde_json::Value) -> ser
     }
 }
 
-fn sanitize_gemini(obj: serde_json::Value) -> serde_json::Value {
+pub(crate) fn sanitize_gemini(obj: serde_json::Value) -> serde_json::Value {
     use serde_json::{json, Map, Value};
 
     match obj {
@@ -1877,6 +1980,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn cache_breakpoints_close_system_previous_turn_and_final_message() {
+        let messages = vec![
+            Message::system("header"),
+            Message::system("instructions"),
+            Message::user("first"),
+            Message::assistant("answer"),
+            Message::user("second"),
+            Message::assistant("calling tool"),
+        ];
+        assert_eq!(plan_cache_breakpoints(&messages), vec![0, 1, 3, 5]);
+
+        let single_turn = vec![Message::system("system"), Message::user("hello")];
+        assert_eq!(plan_cache_breakpoints(&single_turn), vec![0, 1]);
+        assert!(plan_cache_breakpoints(&[]).is_empty());
+    }
+
+    #[test]
+    fn cache_fingerprint_reports_what_busted_the_prefix() {
+        let tool = crate::ToolDefinition {
+            name: "read".to_string(),
+            description: None,
+            parameters: serde_json::json!({ "type": "object" }),
+        };
+        let first = vec![Message::system("system"), Message::user("hello")];
+        let previous = CacheFingerprint::of(&first, std::slice::from_ref(&tool));
+
+        let mut appended = first.clone();
+        appended.push(Message::assistant("hi"));
+        appended.push(Message::user("more"));
+        apply_caching(&mut appended, ProviderType::Anthropic);
+        let next = CacheFingerprint::of(&appended, std::slice::from_ref(&tool));
+        assert_eq!(next.bust_since(&previous), None);
+
+        let edited = vec![Message::system("system"), Message::user("edited")];
+        assert_eq!(
+            CacheFingerprint::of(&edited, std::slice::from_ref(&tool)).bust_since(&previous),
+            Some(CacheBust::PrefixRewritten { index: 0 })
+        );
+
+        let new_system = vec![Message::system("system v2"), Message::user("hello")];
+        assert_eq!(
+            CacheFingerprint::of(&new_system, std::slice::from_ref(&tool)).bust_since(&previous),
+            Some(CacheBust::SystemChanged)
+        );
+        assert_eq!(
+            CacheFingerprint::of(&first, &[]).bust_since(&previous),
+            Some(CacheBust::ToolsChanged)
+        );
+    }
+
     #[test]
     fn test_apply_caching_per_part_anthropic() {
         let mut messages = vec![
@@ -43,6 +43,8 @@ impl VercelProvider {
             supports_tools: false,
             cost_per_million_input: 0.0,
             cost_per_million_output: 0.0,
+            supports_batch: false,
+            supports_pdf: false,
         }];
 
         Self {
@@ -102,6 +104,7 @@ impl VercelProvider {
             max_tokens: request.max_tokens,
             temperature: request.temperature,
             top_p: request.top_p,
+            stop: request.stop,
             stream: true,
         }
     }
@@ -139,6 +142,7 @@ impl Provider for VercelProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -172,6 +176,7 @@ impl Provider for VercelProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -216,6 +221,8 @@ struct VercelRequest {
     temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<Vec<String>>,
     stream: bool,
 }
 
@@ -1,11 +1,14 @@
+use std::collections::HashMap;
+
 use async_trait::async_trait;
 use futures::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
+use crate::recorder::{ExchangeRecorder, WireFormat};
 use crate::{
     ChatRequest, ChatResponse, Choice, Content, Message, ModelInfo, Provider, ProviderError, Role,
-    StreamEvent, StreamResult, Usage,
+    StreamEvent, StreamResult, ToolChoice, Usage,
 };
 
 const VERTEX_API_BASE: &str = "https://aiplatform.googleapis.com/v1";
@@ -52,6 +55,8 @@ impl GoogleVertexProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.1,
                 cost_per_million_output: 0.4,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "gemini-2.0-flash-lite".to_string(),
@@ -64,6 +69,8 @@ impl GoogleVertexProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.075,
                 cost_per_million_output: 0.3,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "gemini-1.5-pro".to_string(),
@@ -76,6 +83,8 @@ impl GoogleVertexProvider {
                 supports_tools: true,
                 cost_per_million_input: 1.25,
                 cost_per_million_output: 5.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "gemini-1.5-flash".to_string(),
@@ -88,6 +97,8 @@ impl GoogleVertexProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.075,
                 cost_per_million_output: 0.3,
+                supports_batch: false,
+                supports_pdf: false,
             },
             ModelInfo {
                 id: "gemini-1.0-pro".to_string(),
@@ -100,6 +111,8 @@ impl GoogleVertexProvider {
                 supports_tools: true,
                 cost_per_million_input: 0.5,
                 cost_per_million_output: 1.5,
+                supports_batch: false,
+                supports_pdf: false,
             },
         ];
 
@@ -172,13 +185,44 @@ impl GoogleVertexProvider {
             max_output_tokens: request.max_tokens,
             temperature: request.temperature,
             top_p: request.top_p,
+            response_mime_type: request
+                .response_format
+                .as_ref()
+                .map(|_| "application/json".to_string()),
+            response_schema: request
+                .response_format
+                .as_ref()
+                .map(|format| format.gemini_schema()),
+            stop_sequences: request.stop.clone(),
         };
 
+        let tools = request
+            .tools
+            .as_deref()
+            .filter(|tools| !tools.is_empty())
+            .map(|tools| {
+                vec![VertexTool {
+                    function_declarations: tools
+                        .iter()
+                        .map(|tool| VertexFunctionDeclaration {
+                            name: tool.name.clone(),
+                            description: tool.description.clone().unwrap_or_default(),
+                            parameters: tool.parameters.clone(),
+                        })
+                        .collect(),
+                }]
+            });
+        let tool_config = tools
+            .as_ref()
+            .and(request.tool_choice.as_ref())
+            .map(VertexToolConfig::from);
+
         VertexRequest {
             contents,
             system_instruction,
             generation_config: Some(generation_config),
-            tools: None,
+            tools,
+            tool_config,
         }
     }
 
@@ -239,6 +283,7 @@ impl Provider for GoogleVertexProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -259,55 +304,77 @@ impl Provider for GoogleVertexProvider {
     async fn chat_stream(&self, request: ChatRequest) -> Result<StreamResult, ProviderError> {
         let url = self.build_url(&request.model, "streamGenerateContent");
         let vertex_request = self.convert_request(request);
-
-        let response = self
-            .client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .header(
-                "Authorization",
+        let body = serde_json::to_string(&vertex_request)
+            .map_err(|e| ProviderError::InvalidRequest(format!("failed to encode body: {}", e)))?;
+        let headers = HashMap::from([
+            ("Content-Type".to_string(), "application/json".to_string()),
+            (
+                "Authorization".to_string(),
                 format!("Bearer {}", self.config.access_token),
-            )
-            .header("Accept", "text/event-stream")
-            .json(&vertex_request)
-            .send()
-            .await
-            .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
+            ),
+            ("Accept".to_string(), "text/event-stream".to_string()),
+        ]);
+        let recorder =
+            ExchangeRecorder::begin(self.id(), WireFormat::Vertex, "POST", &url, &headers, &body);
+
+        let mut builder = self.client.post(&url);
+        for (name, value) in &headers {
+            builder = builder.header(name, value);
+        }
+        let response = builder.body(body).send().await.map_err(|e| {
+            if let Some(recorder) = &recorder {
+                recorder.error(&e.to_string());
+            }
+            ProviderError::NetworkError(e.to_string())
+        })?;
 
+        crate::governor::observe(&response);
+        if let Some(recorder) = &recorder {
+            recorder.response(response.status().as_u16(), response.headers());
+        }
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
+            if let Some(recorder) = &recorder {
+                recorder.body(&body);
+            }
             return Err(ProviderError::api_error_with_status(
                 format!("{}: {}", status, body),
                 status.as_u16(),
             ));
         }
 
-        let stream = response.bytes_stream().map(move |chunk_result| {
-            match chunk_result {
-                Ok(bytes) => {
-                    let text = String::from_utf8_lossy(&bytes);
-                    for line in text.lines() {
-                        if line.starts_with("data: ") {
-                            let data = &line[6..];
-                            if let Some(event) = parse_vertex_sse(data) {
-                                return Ok(event);
-                            }
-                        } else if !line.is_empty() && !line.starts_with(':') {
-                            // Vertex sometimes returns raw JSON without "data: " prefix
-                            if let Some(event) = parse_vertex_sse(line) {
-                                return Ok(event);
-                            }
-                        }
+        let chunks = response.bytes_stream().map(|chunk| match chunk {
+            Ok(bytes) => Ok(String::from_utf8_lossy(&bytes).into_owned()),
+            Err(e) => Err(ProviderError::StreamError(e.to_string())),
+        });
+        Ok(vertex_event_stream(crate::recorder::tap(chunks, recorder)))
+    }
+}
+
+/// Maps each SSE chunk to the first event it carries.
+pub(crate) fn vertex_event_stream<S>(chunks: S) -> StreamResult
+where
+    S: futures::Stream<Item = Result<String, ProviderError>> + Send + 'static,
+{
+    Box::pin(chunks.map(|chunk_result| match chunk_result {
+        Ok(text) => {
+            for line in text.lines() {
+                if let Some(data) = line.strip_prefix("data: ") {
+                    if let Some(event) = parse_vertex_sse(data) {
+                        return Ok(event);
+                    }
+                } else if !line.is_empty() && !line.starts_with(':') {
+                    // Vertex sometimes returns raw JSON without "data: " prefix
+                    if let Some(event) = parse_vertex_sse(line) {
+                        return Ok(event);
                     }
-                    Ok(StreamEvent::TextDelta(String::new()))
                 }
-                Err(e) => Err(ProviderError::StreamError(e.to_string())),
             }
-        });
-
-        Ok(Box::pin(stream))
-    }
+            Ok(StreamEvent::TextDelta(String::new()))
+        }
+        Err(e) => Err(e),
+    }))
 }
 
 #[derive(Debug, Serialize)]
@@ -319,6 +386,8 @@ struct VertexRequest {
     generation_config: Option<VertexGenerationConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<VertexTool>>,
+    #[serde(rename = "toolConfig", skip_serializing_if = "Option::is_none")]
+    tool_config: Option<VertexToolConfig>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -389,6 +458,12 @@ struct VertexGenerationConfig {
     temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_schema: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_sequences: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -403,6 +478,39 @@ struct VertexFunctionDeclaration {
     parameters: serde_json::Value,
 }
 
+/// Gemini has no switch for parallel function calls, so only `tool_choice`
+/// reaches the request; `parallel_tool_calls` is not sent.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VertexToolConfig {
+    function_calling_config: VertexFunctionCallingConfig,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct VertexFunctionCallingConfig {
+    mode: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allowed_function_names: Option<Vec<String>>,
+}
+
+impl From<&ToolChoice> for VertexToolConfig {
+    fn from(choice: &ToolChoice) -> Self {
+        let (mode, allowed_function_names) = match choice {
+            ToolChoice::Auto => ("AUTO", None),
+            ToolChoice::None => ("NONE", None),
+            ToolChoice::Required => ("ANY", None),
+            ToolChoice::Tool(name) => ("ANY", Some(vec![name.clone()])),
+        };
+        Self {
+            function_calling_config: VertexFunctionCallingConfig {
+                mode,
+                allowed_function_names,
+            },
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct VertexResponse {
     candidates: Vec<VertexCandidate>,
@@ -506,3 +614,56 @@ fn parse_vertex_sse(data: &str) -> Option<StreamEvent> {
 
     Some(StreamEvent::TextDelta(text))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ToolDefinition;
+
+    fn read_tool() -> ToolDefinition {
+        ToolDefinition {
+            name: "read".to_string(),
+            description: Some("Read a file".to_string()),
+            parameters: serde_json::json!({ "type": "object" }),
+        }
+    }
+
+    #[test]
+    fn tool_choice_maps_to_function_calling_config() {
+        let provider = GoogleVertexProvider::new("token", "project", "us-central1");
+        let body = |request: ChatRequest| {
+            serde_json::to_value(provider.convert_request(request)).expect("serialize request")
+        };
+
+        let required = body(
+            ChatRequest::new("gemini-test", vec![Message::user("hi")])
+                .with_tools(vec![read_tool()])
+                .with_tool_choice(ToolChoice::Required),
+        );
+        assert_eq!(
+            required["tools"][0]["function_declarations"][0]["name"],
+            serde_json::json!("read")
+        );
+        assert_eq!(
+            required["toolConfig"],
+            serde_json::json!({ "functionCallingConfig": { "mode": "ANY" } })
+        );
+
+        let forced = body(
+            ChatRequest::new("gemini-test", vec![Message::user("hi")])
+                .with_tools(vec![read_tool()])
+                .with_tool_choice(ToolChoice::Tool("read".to_string())),
+        );
+        assert_eq!(
+            forced["toolConfig"]["functionCallingConfig"]["allowedFunctionNames"],
+            serde_json::json!(["read"])
+        );
+
+        let auto = body(
+            ChatRequest::new("gemini-test", vec![Message::user("hi")])
+                .with_tools(vec![read_tool()])
+                .with_parallel_tool_calls(false),
+        );
+        assert!(auto.get("toolConfig").is_none());
+    }
+}
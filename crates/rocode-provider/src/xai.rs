@@ -32,6 +32,8 @@ impl XaiProvider {
                     supports_tools: true,
                     cost_per_million_input: 2.0,
                     cost_per_million_output: 10.0,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "grok-2-1212".to_string(),
@@ -44,6 +46,8 @@ impl XaiProvider {
                     supports_tools: true,
                     cost_per_million_input: 2.0,
                     cost_per_million_output: 10.0,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "grok-beta".to_string(),
@@ -56,6 +60,8 @@ impl XaiProvider {
                     supports_tools: true,
                     cost_per_million_input: 5.0,
                     cost_per_million_output: 15.0,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 ModelInfo {
                     id: "grok-vision-beta".to_string(),
@@ -68,6 +74,8 @@ impl XaiProvider {
                     supports_tools: false,
                     cost_per_million_input: 5.0,
                     cost_per_million_output: 15.0,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
             ],
         }
@@ -100,6 +108,7 @@ impl Provider for XaiProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
@@ -127,6 +136,7 @@ impl Provider for XaiProvider {
             .await
             .map_err(|e| ProviderError::NetworkError(e.to_string()))?;
 
+        crate::governor::observe(&response);
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
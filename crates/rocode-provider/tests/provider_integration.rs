@@ -126,6 +126,8 @@ fn test_model_info_clone() {
         supports_tools: true,
         cost_per_million_input: 1.0,
         cost_per_million_output: 2.0,
+        supports_batch: false,
+        supports_pdf: false,
     };
 
     let cloned = model.clone();
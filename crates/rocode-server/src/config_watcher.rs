@@ -0,0 +1,152 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use rocode_config::{config_watch_dirs, load_config, ConfigDiff};
+use tokio::sync::mpsc;
+
+use crate::routes::apply_config;
+use crate::ServerState;
+
+/// Quiet period after the last file event before reloading, so editors that
+/// write in several steps trigger a single reload.
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Subdirectories holding markdown agents, commands and modes.
+const MARKDOWN_DIRS: &[&str] = &["agent", "agents", "command", "commands", "mode", "modes"];
+
+/// Watches every config source for `project_dir` and applies changes to the
+/// running server without a restart.
+pub fn spawn_config_watcher(state: Arc<ServerState>, project_dir: PathBuf) {
+    tokio::spawn(async move {
+        let (tx, mut rx) = mpsc::unbounded_channel::<PathBuf>();
+        let mut watcher = match arm_watcher(&project_dir, tx.clone()) {
+            Ok(watcher) => watcher,
+            Err(error) => {
+                tracing::warn!(%error, "config hot reload disabled");
+                return;
+            }
+        };
+
+        while let Some(path) = rx.recv().await {
+            tracing::debug!(path = ?path, "config source changed");
+            // Drain the burst of events an editor save produces.
+            while let Ok(Some(_)) = tokio::time::timeout(RELOAD_DEBOUNCE, rx.recv()).await {}
+
+            if let Err(error) = reload_config(&state, &project_dir).await {
+                tracing::warn!(%error, "ignoring config change that failed to load");
+                state.broadcast(
+                    &serde_json::json!({
+                        "type": "config.error",
+                        "properties": { "message": format!("{:#}", error) },
+                    })
+                    .to_string(),
+                );
+                continue;
+            }
+
+            // New `.opencode` or agent directories may have appeared.
+            match arm_watcher(&project_dir, tx.clone()) {
+                Ok(rearmed) => watcher = rearmed,
+                Err(error) => tracing::warn!(%error, "failed to re-arm config watcher"),
+            }
+        }
+        drop(watcher);
+    });
+}
+
+/// Loads the config for `project_dir` and applies it. A config that fails
+/// to load leaves the running one untouched.
+pub(crate) async fn reload_config(
+    state: &ServerState,
+    project_dir: &Path,
+) -> anyhow::Result<ConfigDiff> {
+    let dir = project_dir.to_path_buf();
+    let config = tokio::task::spawn_blocking(move || load_config(&dir)).await??;
+    Ok(apply_config(state, config).await)
+}
+
+fn arm_watcher(
+    project_dir: &Path,
+    tx: mpsc::UnboundedSender<PathBuf>,
+) -> notify::Result<RecommendedWatcher> {
+    let custom_config = std::env::var_os("OPENCODE_CONFIG").map(PathBuf::from);
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+        let Ok(event) = res else {
+            return;
+        };
+        if !matches!(
+            event.kind,
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+        ) {
+            return;
+        }
+        for path in event.paths {
+            if is_config_source(&path, custom_config.as_deref()) {
+                let _ = tx.send(path);
+            }
+        }
+    })?;
+
+    for dir in config_watch_dirs(project_dir) {
+        let mode = if is_markdown_dir(&dir) {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        if let Err(error) = watcher.watch(&dir, mode) {
+            tracing::debug!(dir = ?dir, %error, "cannot watch config directory");
+        }
+    }
+    Ok(watcher)
+}
+
+fn is_markdown_dir(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| MARKDOWN_DIRS.contains(&name))
+}
+
+/// Whether a change to `path` can affect the loaded config.
+fn is_config_source(path: &Path, custom_config: Option<&Path>) -> bool {
+    if custom_config.is_some_and(|custom| custom == path) {
+        return true;
+    }
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    matches!(name, "opencode.json" | "opencode.jsonc" | ".opencode")
+        || MARKDOWN_DIRS.contains(&name)
+        || (name.ends_with(".md") && path.ancestors().skip(1).any(is_markdown_dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_config_sources() {
+        assert!(is_config_source(Path::new("/p/opencode.json"), None));
+        assert!(is_config_source(
+            Path::new("/p/.opencode/opencode.jsonc"),
+            None
+        ));
+        assert!(is_config_source(
+            Path::new("/p/.opencode/agents/nested/review.md"),
+            None
+        ));
+        assert!(is_config_source(Path::new("/p/.opencode/commands"), None));
+        assert!(is_config_source(
+            Path::new("/etc/custom.json"),
+            Some(Path::new("/etc/custom.json"))
+        ));
+
+        assert!(!is_config_source(Path::new("/p/README.md"), None));
+        assert!(!is_config_source(Path::new("/p/src/main.rs"), None));
+        assert!(!is_config_source(
+            Path::new("/p/.opencode/opencode.json.swp"),
+            None
+        ));
+    }
+}
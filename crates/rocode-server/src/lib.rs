@@ -1,5 +1,6 @@
 #![allow(ambiguous_glob_reexports)]
 
+pub mod config_watcher;
 pub mod error;
 pub mod mcp_oauth;
 pub mod oauth;
@@ -8,6 +9,7 @@ pub mod routes;
 pub mod server;
 pub mod worktree;
 
+pub use config_watcher::*;
 pub use error::*;
 pub use mcp_oauth::*;
 pub use oauth::*;
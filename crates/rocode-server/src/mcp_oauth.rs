@@ -149,6 +149,19 @@ impl McpOAuthManager {
         status
     }
 
+    /// Disconnects and forgets a server. Returns `false` if it was unknown.
+    pub async fn remove_server(&self, server_name: &str) -> bool {
+        let Some(managed) = self.servers.write().await.remove(server_name) else {
+            return false;
+        };
+        if let McpRuntimeConfig::Local(_) = managed.config {
+            let _ = self.clients.remove(server_name).await;
+        }
+        self.statuses.write().await.remove(server_name);
+        self.log_event(server_name, "info", "Server removed").await;
+        true
+    }
+
     pub async fn connect(&self, server_name: &str) -> Result<McpServerInfo, McpOAuthError> {
         self.log_event(server_name, "info", "Connect requested")
             .await;
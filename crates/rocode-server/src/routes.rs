@@ -32,7 +32,10 @@ use crate::pty::{PtyManager, PtySession as PtySessionStruct, PtySubscription};
 use crate::worktree::{self, WorktreeInfo as WorktreeInfoStruct};
 use crate::{ApiError, Result, ServerState};
 use rocode_agent::{AgentMode, AgentRegistry};
-use rocode_config::{load_config, Config as AppConfig, McpServerConfig as LoadedMcpServerConfig};
+use rocode_config::{
+    diff_configs, load_config, Config as AppConfig, ConfigDiff,
+    McpServerConfig as LoadedMcpServerConfig,
+};
 use rocode_plugin::subprocess::{PluginAuthBridge, PluginLoader, PluginSubprocessError};
 use rocode_provider::{
     temperature_for_model, top_p_for_model, AuthInfo, AuthMethodType, ModelsData, ModelsDevInfo,
@@ -53,6 +56,7 @@ pub fn router() -> Router<Arc<ServerState>> {
         .route("/auth/{id}", put(set_auth).delete(delete_auth))
         .route("/doc", get(get_doc))
         .route("/log", post(write_log))
+        .route("/usage", get(get_usage))
         .nest("/session", session_routes())
         .nest("/provider", provider_routes())
         .nest("/config", config_routes())
@@ -81,12 +85,15 @@ fn session_routes() -> Router<Arc<ServerState>> {
         )
         .route("/{id}/children", get(get_session_children))
         .route("/{id}/todo", get(get_session_todos))
+        .route("/{id}/task", get(list_background_tasks))
+        .route("/{id}/task/{taskID}/cancel", post(cancel_background_task))
         .route("/{id}/fork", post(fork_session))
         .route("/{id}/abort", post(abort_session))
         .route("/{id}/share", post(share_session).delete(unshare_session))
         .route("/{id}/archive", post(archive_session))
         .route("/{id}/title", patch(set_session_title))
         .route("/{id}/permission", patch(set_session_permission))
+        .route("/{id}/recording", post(set_session_recording))
         .route(
             "/{id}/summary",
             get(get_session_summary).patch(set_session_summary),
@@ -116,6 +123,8 @@ fn session_routes() -> Router<Arc<ServerState>> {
         .route("/{id}/prompt/abort", post(abort_prompt))
         .route("/{id}/prompt_async", post(prompt_async))
         .route("/{id}/diff", get(get_session_diff))
+        .route("/{id}/review", get(get_session_review))
+        .route("/{id}/review/reject", post(reject_session_review))
 }
 
 #[derive(Debug, Deserialize)]
@@ -141,6 +150,16 @@ pub struct SessionInfo {
     pub share: Option<SessionShareInfo>,
     pub revert: Option<SessionRevertInfo>,
     pub permission: Option<PermissionRulesetInfo>,
+    pub cache: Option<SessionCacheInfo>,
+}
+
+/// Prompt-cache effectiveness across the session's assistant turns.
+#[derive(Debug, Serialize)]
+pub struct SessionCacheInfo {
+    pub input: u64,
+    pub read: u64,
+    pub write: u64,
+    pub hit_ratio: f64,
 }
 
 #[derive(Debug, Serialize)]
@@ -213,6 +232,15 @@ fn session_to_info(session: &rocode_session::Session) -> SessionInfo {
             deny: p.deny.clone(),
             mode: p.mode.clone(),
         }),
+        cache: {
+            let usage = session.get_usage();
+            usage.cache_hit_ratio().map(|hit_ratio| SessionCacheInfo {
+                input: usage.input_tokens,
+                read: usage.cache_read_tokens,
+                write: usage.cache_write_tokens,
+                hit_ratio,
+            })
+        },
     }
 }
 
@@ -503,6 +531,30 @@ async fn get_session_todos(
     Ok(Json(items))
 }
 
+async fn list_background_tasks(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<rocode_tool::task::BackgroundTaskInfo>>> {
+    let sessions = state.sessions.lock().await;
+    if sessions.get(&id).is_none() {
+        return Err(ApiError::SessionNotFound(id));
+    }
+    drop(sessions);
+
+    Ok(Json(rocode_tool::task::background_tasks().list(&id)))
+}
+
+async fn cancel_background_task(Path((id, task_id)): Path<(String, String)>) -> Result<Json<bool>> {
+    let tasks = rocode_tool::task::background_tasks();
+    match tasks.get(&task_id) {
+        Some(info) if info.parent_session_id == id => Ok(Json(tasks.cancel(&task_id))),
+        _ => Err(ApiError::NotFound(format!(
+            "Background task not found: {}",
+            task_id
+        ))),
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ForkSessionRequest {
     pub message_id: Option<String>,
@@ -620,6 +672,63 @@ async fn set_session_permission(
     Ok(Json(info))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct SessionRecordingRequest {
+    pub enabled: bool,
+    /// Subdirectory of `recordings/` in the data directory to capture into.
+    pub dir: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionRecordingInfo {
+    pub enabled: bool,
+    pub dir: Option<String>,
+}
+
+/// Toggles provider exchange recording for one session.
+async fn set_session_recording(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<String>,
+    Json(req): Json<SessionRecordingRequest>,
+) -> Result<Json<SessionRecordingInfo>> {
+    if state.sessions.lock().await.get(&id).is_none() {
+        return Err(ApiError::SessionNotFound(id));
+    }
+    if !req.enabled {
+        rocode_provider::recorder::disable_recording(&id);
+        return Ok(Json(SessionRecordingInfo {
+            enabled: rocode_provider::recorder::is_recording(&id),
+            dir: None,
+        }));
+    }
+    let dir = recording_dir(req.dir.as_deref())?;
+    rocode_provider::recorder::enable_recording(&id, &dir);
+    Ok(Json(SessionRecordingInfo {
+        enabled: true,
+        dir: Some(dir.join(&id).display().to_string()),
+    }))
+}
+
+/// Resolves a client-supplied capture directory under the default recording
+/// directory, refusing anything that could escape it.
+fn recording_dir(subdir: Option<&str>) -> Result<PathBuf> {
+    let base = rocode_provider::recorder::default_recording_dir();
+    let Some(subdir) = subdir else {
+        return Ok(base);
+    };
+    let path = FsPath::new(subdir);
+    if path
+        .components()
+        .any(|component| !matches!(component, std::path::Component::Normal(_)))
+    {
+        return Err(ApiError::BadRequest(format!(
+            "recording dir must be a relative path without `..`: {}",
+            subdir
+        )));
+    }
+    Ok(base.join(path))
+}
+
 async fn get_session_summary(
     State(state): State<Arc<ServerState>>,
     Path(id): Path<String>,
@@ -1138,16 +1247,47 @@ async fn resolve_provider_and_model(
     Ok((provider, provider_id, model_id))
 }
 
+/// Resolves an agent's fallback chain against the configured providers,
+/// dropping models that are not available.
+async fn resolve_fallback_models(
+    state: &ServerState,
+    chain: &[rocode_agent::ModelRef],
+) -> Vec<rocode_session::FallbackModel> {
+    let providers = state.providers.read().await;
+    chain
+        .iter()
+        .filter_map(|model| {
+            match providers.get_language_model(&model.provider_id, &model.model_id) {
+                Ok((provider, _)) => Some(rocode_session::FallbackModel {
+                    provider_id: model.provider_id.clone(),
+                    model_id: model.model_id.clone(),
+                    provider,
+                }),
+                Err(error) => {
+                    tracing::warn!(
+                        provider_id = %model.provider_id,
+                        model_id = %model.model_id,
+                        %error,
+                        "skipping unavailable fallback model"
+                    );
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
 async fn send_message(
     State(state): State<Arc<ServerState>>,
     Path(session_id): Path<String>,
     Json(req): Json<SendMessageRequest>,
 ) -> Result<Json<MessageInfo>> {
+    let review_base = track_session_review_base(&state, &session_id).await?;
     let mut sessions = state.sessions.lock().await;
     let session = sessions
         .get_mut(&session_id)
         .ok_or_else(|| ApiError::SessionNotFound(session_id.clone()))?;
-    session.add_user_message(&req.content);
+    add_user_prompt(session, &req.content, review_base);
     if let Some(variant) = req.variant.as_deref() {
         session
             .metadata
@@ -1436,13 +1576,14 @@ async fn stream_message(
             .await?;
     drop(config);
 
+    let review_base = track_session_review_base(&state, &session_id).await?;
     let selected_variant = {
         let mut sessions = state.sessions.lock().await;
         let session = sessions
             .get_mut(&session_id)
             .ok_or_else(|| ApiError::SessionNotFound(session_id.clone()))?;
 
-        session.add_user_message(&req.content);
+        add_user_prompt(session, &req.content, review_base);
         let selected_variant = req.variant.clone().or_else(|| {
             session
                 .metadata
@@ -1540,6 +1681,10 @@ async fn stream_message(
                 stream: Some(true),
                 variant: stream_variant.clone(),
                 provider_options: None,
+                response_format: None,
+                tool_choice: None,
+                stop: None,
+                parallel_tool_calls: None,
             };
 
             let mut final_text = String::new();
@@ -1643,6 +1788,7 @@ async fn stream_message(
                             Ok(rocode_provider::StreamEvent::Usage {
                                 prompt_tokens,
                                 completion_tokens,
+                                ..
                             }) => {
                                 send_sse_event(
                                     &tx,
@@ -1972,6 +2118,12 @@ pub struct SessionPromptRequest {
     pub agent: Option<String>,
     pub command: Option<String>,
     pub arguments: Option<String>,
+    /// JSON Schema the final answer must match. The validated object is stored
+    /// on the last assistant message under `structured_output`.
+    #[serde(default)]
+    pub schema: Option<serde_json::Value>,
+    #[serde(default)]
+    pub schema_retries: Option<u32>,
 }
 
 async fn session_prompt(
@@ -1991,13 +2143,29 @@ async fn session_prompt(
             "Either `message` or `command` must be provided".to_string(),
         ));
     };
+    let response_format = match req.schema.clone() {
+        Some(schema) if schema.is_object() => {
+            Some(rocode_provider::ResponseFormat::json_schema(schema))
+        }
+        Some(_) => {
+            return Err(ApiError::BadRequest(
+                "`schema` must be a JSON Schema object".to_string(),
+            ))
+        }
+        None => None,
+    };
+    let prompt_text = match response_format.as_ref() {
+        Some(format) => format!("{}\n\n{}", prompt_text, format.instructions()),
+        None => prompt_text,
+    };
 
-    {
+    let session_directory = {
         let sessions = state.sessions.lock().await;
-        if sessions.get(&id).is_none() {
-            return Err(ApiError::SessionNotFound(id));
+        match sessions.get(&id) {
+            Some(session) => session.directory.clone(),
+            None => return Err(ApiError::SessionNotFound(id)),
         }
-    }
+    };
     let _ = ensure_plugin_loader_active(&state).await?;
 
     let mut config = CONFIG_STATE.read().await.clone();
@@ -2032,10 +2200,22 @@ async fn session_prompt(
     let agent_system_prompt = resolved_agent
         .as_ref()
         .and_then(|agent| agent.system_prompt.clone());
+    let fallback = match resolved_agent.as_ref() {
+        Some(agent) => resolve_fallback_models(&state, &agent.fallback).await,
+        None => Vec::new(),
+    };
     let agent_params = rocode_session::AgentParams {
         max_tokens: resolved_agent.as_ref().and_then(|agent| agent.max_tokens),
         temperature: resolved_agent.as_ref().and_then(|agent| agent.temperature),
         top_p: resolved_agent.as_ref().and_then(|agent| agent.top_p),
+        fallback,
+        tool_choice: resolved_agent
+            .as_ref()
+            .and_then(|agent| agent.tool_choice.clone()),
+        stop: resolved_agent.as_ref().and_then(|agent| agent.stop.clone()),
+        parallel_tool_calls: resolved_agent
+            .as_ref()
+            .and_then(|agent| agent.parallel_tool_calls),
     };
     tracing::info!(
         requested_agent = ?req.agent,
@@ -2053,6 +2233,14 @@ async fn session_prompt(
     let task_provider = provider_id.clone();
     let task_system_prompt = agent_system_prompt.clone();
     let task_agent_params = agent_params.clone();
+    let utility = utility_pipeline(
+        &state,
+        PathBuf::from(resolved_session_directory(&session_directory)),
+    )
+    .await;
+    let schema_retries = req
+        .schema_retries
+        .unwrap_or(rocode_provider::DEFAULT_STRUCTURED_RETRIES);
     tokio::spawn(async move {
         let mut session = {
             let sessions = task_state.sessions.lock().await;
@@ -2067,6 +2255,13 @@ async fn session_prompt(
         }
         set_session_run_status(&task_state, &session_id, SessionRunStatus::Busy).await;
 
+        let review_base = track_review_base(&session.directory).await;
+        let mut parts = Vec::new();
+        if let Some(note) = start_review_turn(&mut session, review_base) {
+            parts.push(rocode_session::PartInput::Text { text: note });
+        }
+        parts.push(rocode_session::PartInput::Text { text: prompt_text });
+
         // Safety guard: ensure status is always set to idle when this block
         // exits, mirroring the TS `defer(() => cancel(sessionID))` pattern.
         // This prevents the spinner from getting stuck if anything panics.
@@ -2170,13 +2365,26 @@ async fn session_prompt(
         });
         // Keep persist_worker handle at this scope so the outer timeout path can abort it.
         let persist_worker_handle = persist_worker;
+        let updates = Arc::new(std::sync::Mutex::new(PromptUpdates::Live(update_tx)));
+        let hook_updates = updates.clone();
+        let late_state = task_state.clone();
         let update_hook: rocode_session::SessionUpdateHook = Arc::new(move |snapshot| {
-            let _ = update_tx.send(snapshot.clone());
+            let mut updates = hook_updates.lock().unwrap_or_else(|e| e.into_inner());
+            match &mut *updates {
+                PromptUpdates::Live(update_tx) => {
+                    let _ = update_tx.send(snapshot.clone());
+                }
+                PromptUpdates::Closing(late) => late.push(snapshot.clone()),
+                PromptUpdates::Done => {
+                    tokio::spawn(apply_late_titles(late_state.clone(), snapshot.clone()));
+                }
+            }
         });
 
         let prompt_runner = rocode_session::SessionPrompt::new(Arc::new(RwLock::new(
             rocode_session::SessionStateManager::new(),
-        )));
+        )))
+        .with_utility(utility);
         let tool_defs = rocode_session::resolve_tools(task_state.tool_registry.as_ref()).await;
         let input = rocode_session::PromptInput {
             session_id: session_id.clone(),
@@ -2189,7 +2397,7 @@ async fn session_prompt(
             no_reply: false,
             system: None,
             variant: task_variant.clone(),
-            parts: vec![rocode_session::PartInput::Text { text: prompt_text }],
+            parts,
             tools: None,
         };
 
@@ -2221,6 +2429,7 @@ async fn session_prompt(
             }))
         };
 
+        let structured_provider = provider.clone();
         if let Err(error) = prompt_runner
             .prompt_with_update_hook(
                 input,
@@ -2263,7 +2472,23 @@ async fn session_prompt(
                     .insert("agent".to_string(), serde_json::json!(agent));
             }
             assistant.add_text(format!("Provider error: {}", error));
+        } else if let Some(format) = response_format.as_ref() {
+            if let Err(error) = rocode_session::record_structured_output(
+                &mut session,
+                structured_provider,
+                &task_provider,
+                &task_model,
+                task_system_prompt.as_deref(),
+                format,
+                schema_retries,
+            )
+            .await
+            {
+                tracing::warn!(session_id = %session_id, %error, "structured output failed");
+            }
         }
+        // Dropping the sender lets the update worker drain and exit.
+        *updates.lock().unwrap_or_else(|e| e.into_inner()) = PromptUpdates::Closing(Vec::new());
         match tokio::time::timeout(Duration::from_secs(1), &mut update_task).await {
             Ok(joined) => {
                 let _ = joined;
@@ -2285,6 +2510,15 @@ async fn session_prompt(
 
         {
             let mut sessions = task_state.sessions.lock().await;
+            let late = std::mem::replace(
+                &mut *updates.lock().unwrap_or_else(|e| e.into_inner()),
+                PromptUpdates::Done,
+            );
+            if let PromptUpdates::Closing(late) = late {
+                for snapshot in &late {
+                    rocode_session::merge_generated_titles(&mut session, snapshot);
+                }
+            }
             sessions.update(session);
         }
         task_state.broadcast(
@@ -2311,6 +2545,41 @@ async fn session_prompt(
     })))
 }
 
+/// Where a running prompt's session snapshots go. While the prompt runs they
+/// feed the update worker. Afterwards only late titles from the utility
+/// model arrive: they are held until the final session is stored, then
+/// merged into it.
+enum PromptUpdates {
+    Live(mpsc::UnboundedSender<rocode_session::Session>),
+    Closing(Vec<rocode_session::Session>),
+    Done,
+}
+
+/// Merges titles that landed after a prompt finished into the stored
+/// session, leaving anything written since untouched.
+async fn apply_late_titles(state: Arc<ServerState>, snapshot: rocode_session::Session) {
+    let changed = {
+        let mut sessions = state.sessions.lock().await;
+        sessions
+            .get_mut(&snapshot.id)
+            .is_some_and(|session| rocode_session::merge_generated_titles(session, &snapshot))
+    };
+    if !changed {
+        return;
+    }
+    state.broadcast(
+        &serde_json::json!({
+            "type": "session.updated",
+            "sessionID": snapshot.id,
+            "source": "prompt.title",
+        })
+        .to_string(),
+    );
+    if let Err(err) = state.flush_session_to_storage(&snapshot.id).await {
+        tracing::error!(session_id = %snapshot.id, %err, "failed to flush session to storage");
+    }
+}
+
 async fn abort_prompt(
     State(state): State<Arc<ServerState>>,
     Path(id): Path<String>,
@@ -2480,15 +2749,16 @@ async fn prompt_async(
     Path(id): Path<String>,
     Json(req): Json<PromptAsyncRequest>,
 ) -> Result<Json<serde_json::Value>> {
-    let mut sessions = state.sessions.lock().await;
-    let session = sessions
-        .get_mut(&id)
-        .ok_or_else(|| ApiError::SessionNotFound(id.clone()))?;
     let text = req
         .message
         .as_deref()
         .ok_or_else(|| ApiError::BadRequest("Field `message` is required".to_string()))?;
-    session.add_user_message(text);
+    let review_base = track_session_review_base(&state, &id).await?;
+    let mut sessions = state.sessions.lock().await;
+    let session = sessions
+        .get_mut(&id)
+        .ok_or_else(|| ApiError::SessionNotFound(id.clone()))?;
+    add_user_prompt(session, text, review_base);
     let assistant = session.add_assistant_message();
     let assistant_id = assistant.id.clone();
     drop(sessions);
@@ -2549,6 +2819,7 @@ async fn execute_command(
     Path(id): Path<String>,
     Json(req): Json<ExecuteCommandRequest>,
 ) -> Result<Json<serde_json::Value>> {
+    let review_base = track_session_review_base(&state, &id).await?;
     let mut sessions = state.sessions.lock().await;
     let session = sessions
         .get_mut(&id)
@@ -2558,7 +2829,7 @@ async fn execute_command(
         .as_deref()
         .map(|args| format!("/{cmd} {args}", cmd = req.command))
         .unwrap_or_else(|| format!("/{}", req.command));
-    session.add_user_message(text);
+    add_user_prompt(session, &text, review_base);
     let assistant = session.add_assistant_message();
     assistant.add_text(format!("Command queued: {}", req.command));
     let assistant_id = assistant.id.clone();
@@ -2612,6 +2883,161 @@ async fn get_session_diff(
     Ok(Json(diffs))
 }
 
+/// Snapshots the worktree as the `/session/{id}/review` baseline for the
+/// turn about to start, before the agent edits anything.
+async fn track_review_base(directory: &str) -> Option<String> {
+    let directory = PathBuf::from(resolved_session_directory(directory));
+    let tracked =
+        tokio::task::spawn_blocking(move || rocode_session::snapshot::Snapshot::track(&directory))
+            .await;
+    match tracked {
+        Ok(Ok(hash)) => Some(hash),
+        Ok(Err(error)) => {
+            tracing::warn!(%error, "failed to snapshot session worktree");
+            None
+        }
+        Err(error) => {
+            tracing::warn!(%error, "snapshot task failed");
+            None
+        }
+    }
+}
+
+/// [`track_review_base`] for a session looked up by id.
+async fn track_session_review_base(state: &ServerState, id: &str) -> Result<Option<String>> {
+    let directory = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(id)
+            .ok_or_else(|| ApiError::SessionNotFound(id.to_string()))?
+            .directory
+            .clone()
+    };
+    Ok(track_review_base(&directory).await)
+}
+
+/// Records the new review baseline and returns the note about hunks the
+/// user rejected since the previous prompt.
+fn start_review_turn(
+    session: &mut rocode_session::Session,
+    base: Option<String>,
+) -> Option<String> {
+    if let Some(hash) = base {
+        session.metadata.insert(
+            rocode_session::REVIEW_SNAPSHOT_KEY.to_string(),
+            serde_json::json!(hash),
+        );
+    }
+    rocode_session::take_rejection_note(session)
+}
+
+/// Adds a user prompt, preceded by any pending review note.
+fn add_user_prompt(session: &mut rocode_session::Session, text: &str, review_base: Option<String>) {
+    match start_review_turn(session, review_base) {
+        Some(note) => session.add_user_message(note).add_text(text),
+        None => {
+            session.add_user_message(text);
+        }
+    }
+}
+
+/// Snapshot hash and worktree of a session, if it has prompted yet.
+async fn session_review_base(state: &ServerState, id: &str) -> Result<Option<(PathBuf, String)>> {
+    let sessions = state.sessions.lock().await;
+    let session = sessions
+        .get(id)
+        .ok_or_else(|| ApiError::SessionNotFound(id.to_string()))?;
+    let snapshot = session
+        .metadata
+        .get(rocode_session::REVIEW_SNAPSHOT_KEY)
+        .and_then(|value| value.as_str())
+        .map(str::to_string);
+    Ok(snapshot.map(|snapshot| {
+        (
+            PathBuf::from(resolved_session_directory(&session.directory)),
+            snapshot,
+        )
+    }))
+}
+
+async fn get_session_review(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<rocode_session::ReviewFile>>> {
+    let Some((directory, snapshot)) = session_review_base(&state, &id).await? else {
+        return Ok(Json(Vec::new()));
+    };
+    let files =
+        tokio::task::spawn_blocking(move || rocode_session::review_files(&directory, &snapshot))
+            .await
+            .map_err(|e| ApiError::InternalError(e.to_string()))?
+            .map_err(|e| {
+                ApiError::InternalError(format!("Failed to diff session changes: {}", e))
+            })?;
+    Ok(Json(files))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewRejectFile {
+    pub path: String,
+    /// Indices into the file's hunks as returned by `GET /session/{id}/review`.
+    #[serde(default)]
+    pub hunks: Vec<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReviewRejectRequest {
+    pub files: Vec<ReviewRejectFile>,
+}
+
+/// Reverts the given hunks on disk and queues a note about them for the
+/// session's next prompt. Returns the remaining changes.
+async fn reject_session_review(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<String>,
+    Json(req): Json<ReviewRejectRequest>,
+) -> Result<Json<Vec<rocode_session::ReviewFile>>> {
+    let Some((directory, snapshot)) = session_review_base(&state, &id).await? else {
+        return Err(ApiError::BadRequest(
+            "Session has no changes to review".to_string(),
+        ));
+    };
+
+    let (rejected, remaining) = tokio::task::spawn_blocking(move || {
+        let mut rejected = Vec::new();
+        for file in &req.files {
+            rejected.push(rocode_session::reject_hunks(
+                &directory,
+                &snapshot,
+                &file.path,
+                &file.hunks,
+            )?);
+        }
+        let remaining = rocode_session::review_files(&directory, &snapshot)?;
+        anyhow::Ok((rejected, remaining))
+    })
+    .await
+    .map_err(|e| ApiError::InternalError(e.to_string()))?
+    .map_err(|e| ApiError::BadRequest(format!("{:#}", e)))?;
+
+    {
+        let mut sessions = state.sessions.lock().await;
+        if let Some(session) = sessions.get_mut(&id) {
+            rocode_session::record_rejections(session, rejected);
+        }
+    }
+    persist_sessions_if_enabled(&state).await;
+    state.broadcast(
+        &serde_json::json!({
+            "type": "session.updated",
+            "sessionID": id,
+            "source": "review.reject",
+        })
+        .to_string(),
+    );
+    Ok(Json(remaining))
+}
+
 #[derive(Debug, Serialize)]
 pub struct FileDiffInfo {
     pub path: String,
@@ -2624,6 +3050,7 @@ fn provider_routes() -> Router<Arc<ServerState>> {
         .route("/", get(list_providers))
         .route("/known", get(list_known_providers))
         .route("/auth", get(get_provider_auth))
+        .route("/queue", get(get_provider_queues))
         .route("/{id}/oauth/authorize", post(oauth_authorize))
         .route("/{id}/oauth/callback", post(oauth_callback))
 }
@@ -2840,6 +3267,12 @@ pub struct AuthMethodInfo {
     pub description: String,
 }
 
+/// Client-side rate governor state per provider/model, including how long
+/// requests have been queueing.
+async fn get_provider_queues() -> Json<Vec<rocode_provider::GovernorSnapshot>> {
+    Json(rocode_provider::governor::snapshot())
+}
+
 async fn get_provider_auth(
     State(state): State<Arc<ServerState>>,
 ) -> Json<HashMap<String, Vec<AuthMethodInfo>>> {
@@ -2949,29 +3382,295 @@ fn config_routes() -> Router<Arc<ServerState>> {
     Router::new()
         .route("/", get(get_config).patch(patch_config))
         .route("/providers", get(get_config_providers))
+        .route("/validate", get(validate_config))
+        .route("/schema", get(get_config_schema))
+        .route("/schema/agent", get(get_agent_schema))
+        .route("/schema/command", get(get_command_schema))
+}
+
+static CONFIG_STATE: Lazy<RwLock<AppConfig>> = Lazy::new(|| {
+    let config = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| load_config(&cwd).ok())
+        .unwrap_or_default();
+    RwLock::new(config)
+});
+
+static UTILITY_PIPELINES: Lazy<Mutex<HashMap<PathBuf, Arc<rocode_session::UtilityPipeline>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// The shared utility pipeline for the project at `directory`. Its settings
+/// come from that project's config rather than the server's, so a project
+/// that sets `utility.disabled` never sends text to a utility model. A
+/// pipeline is rebuilt only when the resolved model or settings change, so
+/// its queue and cache span requests.
+async fn utility_pipeline(
+    state: &ServerState,
+    directory: PathBuf,
+) -> Arc<rocode_session::UtilityPipeline> {
+    let project_dir = directory.clone();
+    let loaded = tokio::task::spawn_blocking(move || load_config(&project_dir))
+        .await
+        .map_err(anyhow::Error::from)
+        .and_then(|config| config);
+    let (target, settings) = match loaded {
+        Ok(config) => {
+            let providers = state.providers.read().await;
+            crate::server::resolve_utility(&config, &providers)
+        }
+        Err(error) => {
+            // The project's privacy switch is unknown, so assume it is on.
+            tracing::warn!(
+                directory = %directory.display(),
+                "utility model disabled: failed to load project config: {:#}",
+                error
+            );
+            let settings = rocode_session::UtilitySettings {
+                disabled: true,
+                ..Default::default()
+            };
+            (None, settings)
+        }
+    };
+    let mut pipelines = UTILITY_PIPELINES.lock().await;
+    if let Some(pipeline) = pipelines
+        .get(&directory)
+        .filter(|pipeline| pipeline.matches(target.as_ref(), &settings))
+    {
+        return pipeline.clone();
+    }
+    let pipeline = Arc::new(rocode_session::UtilityPipeline::new(target, settings));
+    pipelines.insert(directory, pipeline.clone());
+    pipeline
 }
 
-static CONFIG_STATE: Lazy<RwLock<AppConfig>> = Lazy::new(|| RwLock::new(AppConfig::default()));
+#[derive(Debug, Default, Deserialize)]
+pub struct ConfigQuery {
+    /// Project directory whose effective config is returned; defaults to
+    /// the server's working directory.
+    pub directory: Option<String>,
+}
 
-async fn get_config(State(_state): State<Arc<ServerState>>) -> Result<Json<AppConfig>> {
+async fn get_config(
+    State(_state): State<Arc<ServerState>>,
+    Query(query): Query<ConfigQuery>,
+) -> Result<Json<AppConfig>> {
+    if let Some(directory) = query.directory.filter(|d| !d.is_empty()) {
+        let config = tokio::task::spawn_blocking(move || load_config(&directory))
+            .await
+            .map_err(|e| ApiError::InternalError(e.to_string()))?
+            .map_err(|e| ApiError::BadRequest(format!("Failed to load config: {:#}", e)))?;
+        return Ok(Json(config));
+    }
     let config = CONFIG_STATE.read().await;
     Ok(Json(config.clone()))
 }
 
+async fn validate_config(
+    Query(query): Query<ConfigQuery>,
+) -> Result<Json<Vec<rocode_config::Diagnostic>>> {
+    let directory = match query.directory.filter(|d| !d.is_empty()) {
+        Some(directory) => PathBuf::from(directory),
+        None => std::env::current_dir().map_err(|e| {
+            ApiError::InternalError(format!("Failed to resolve current directory: {}", e))
+        })?,
+    };
+    let diagnostics =
+        tokio::task::spawn_blocking(move || rocode_config::validate_config_sources(&directory))
+            .await
+            .map_err(|e| ApiError::InternalError(e.to_string()))?;
+    Ok(Json(diagnostics))
+}
+
+/// JSON Schema for `opencode.json`, so editors can point `$schema` at the
+/// running server.
+async fn get_config_schema() -> Json<serde_json::Value> {
+    Json(rocode_config::config_json_schema())
+}
+
+async fn get_agent_schema() -> Json<serde_json::Value> {
+    Json(rocode_config::agent_frontmatter_json_schema())
+}
+
+async fn get_command_schema() -> Json<serde_json::Value> {
+    Json(rocode_config::command_frontmatter_json_schema())
+}
+
+/// Which config file a `PATCH /config` is written to.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigScope {
+    /// `opencode.json` in the request's project directory.
+    #[default]
+    Project,
+    /// The user's global `opencode.json(c)`.
+    Global,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PatchConfigQuery {
+    #[serde(default)]
+    pub scope: ConfigScope,
+    /// Project directory for `scope=project`; defaults to the server's
+    /// working directory.
+    pub directory: Option<String>,
+    /// Comma-separated top-level keys that the patch replaces instead of
+    /// merging into.
+    pub replace: Option<String>,
+}
+
+fn validate_config_patch(patch: &AppConfig) -> std::result::Result<(), String> {
+    for (field, value) in [("model", &patch.model), ("small_model", &patch.small_model)] {
+        if let Some(model) = value {
+            let valid = model
+                .split_once('/')
+                .is_some_and(|(provider, id)| !provider.is_empty() && !id.is_empty());
+            if !valid {
+                return Err(format!(
+                    "{} must be `provider/model`, got \"{}\"",
+                    field, model
+                ));
+            }
+        }
+    }
+    if let Some(agents) = &patch.agent {
+        for (name, agent) in &agents.entries {
+            if let Some(temperature) = agent.temperature {
+                if !(0.0..=2.0).contains(&temperature) {
+                    return Err(format!(
+                        "agent.{}.temperature must be between 0 and 2",
+                        name
+                    ));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Persists `patch` to the project or global config file and reloads the
+/// effective config. The response is the effective config of the project
+/// directory that was written to.
 async fn patch_config(
     State(state): State<Arc<ServerState>>,
+    Query(query): Query<PatchConfigQuery>,
     Json(patch): Json<AppConfig>,
 ) -> Result<Json<AppConfig>> {
-    let mut config = CONFIG_STATE.write().await;
-    config.merge(patch);
-    let updated = config.clone();
+    validate_config_patch(&patch).map_err(ApiError::BadRequest)?;
+    let cwd = std::env::current_dir().map_err(|e| {
+        ApiError::InternalError(format!("Failed to resolve current directory: {}", e))
+    })?;
+    let directory = query
+        .directory
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| cwd.clone());
+    let replace: Vec<String> = query
+        .replace
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    let written = match query.scope {
+        ConfigScope::Project => {
+            rocode_config::update_config_replacing(&directory, &patch, &replace)
+        }
+        ConfigScope::Global => rocode_config::update_global_config_replacing(&patch, &replace),
+    };
+    written.map_err(|e| ApiError::InternalError(format!("Failed to write config: {:#}", e)))?;
+
+    let reloaded = match load_config(&cwd) {
+        Ok(reloaded) => reloaded,
+        Err(error) => {
+            tracing::warn!(%error, "failed to reload config after update");
+            let mut config = CONFIG_STATE.read().await.clone();
+            config.merge(patch);
+            config
+        }
+    };
+    apply_config(&state, reloaded).await;
+    if directory != cwd {
+        let effective = load_config(&directory)
+            .map_err(|e| ApiError::InternalError(format!("Failed to reload config: {:#}", e)))?;
+        return Ok(Json(effective));
+    }
+    let updated = CONFIG_STATE.read().await.clone();
+    Ok(Json(updated))
+}
+
+/// Installs `config` as the effective config, restarts only the subsystems
+/// whose settings changed and broadcasts `config.updated` with the changed
+/// keys. Agents need no restart: `list_agents` and prompts build their
+/// registry from `CONFIG_STATE` on every request.
+pub(crate) async fn apply_config(state: &ServerState, config: AppConfig) -> ConfigDiff {
+    let diff = {
+        let mut current = CONFIG_STATE.write().await;
+        let diff = diff_configs(&current, &config);
+        *current = config.clone();
+        diff
+    };
+    if diff.is_empty() {
+        return diff;
+    }
+
+    if diff.touches_providers() {
+        state.reload_providers(&config).await;
+    }
+    rocode_tool::task::configure_background_tasks(&config);
+    for name in diff.changed_entries("mcp") {
+        restart_mcp_server(name, config.mcp.as_ref().and_then(|m| m.get(name))).await;
+    }
+
+    tracing::info!(changed = ?diff.changed, "config updated");
     state.broadcast(
         &serde_json::json!({
             "type": "config.updated",
+            "properties": { "changed": diff.changed },
         })
         .to_string(),
     );
-    Ok(Json(updated))
+    diff
+}
+
+/// Brings a registered MCP server in line with its new config entry. Servers
+/// that were connected are reconnected; removed entries are shut down.
+async fn restart_mcp_server(name: &str, entry: Option<&LoadedMcpServerConfig>) {
+    let manager = get_mcp_oauth_manager();
+    let was_connected = manager
+        .get_server(name)
+        .await
+        .is_some_and(|info| info.status == "connected");
+
+    let Some(entry) = entry else {
+        manager.remove_server(name).await;
+        return;
+    };
+    if let LoadedMcpServerConfig::Enabled { enabled: false } = entry {
+        if manager.has_server(name).await {
+            let _ = manager.disconnect(name).await;
+        }
+        return;
+    }
+    match parse_runtime_from_loaded_config(entry.clone()) {
+        Ok(Some((runtime, enabled))) => {
+            manager.add_server(name.to_string(), runtime, enabled).await;
+            if enabled && was_connected {
+                if let Err(error) = manager.restart(name).await {
+                    tracing::warn!(server = name, %error, "failed to restart MCP server");
+                }
+            } else if !enabled {
+                let _ = manager.disconnect(name).await;
+            }
+        }
+        Ok(None) => {}
+        Err(error) => {
+            tracing::warn!(server = name, %error, "invalid MCP server config");
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -4756,6 +5455,8 @@ async fn list_tool_ids() -> Json<Vec<String>> {
         "webfetch".to_string(),
         "websearch".to_string(),
         "task".to_string(),
+        "task_status".to_string(),
+        "task_result".to_string(),
         "lsp".to_string(),
         "batch".to_string(),
         "plan_enter".to_string(),
@@ -4763,6 +5464,7 @@ async fn list_tool_ids() -> Json<Vec<String>> {
         "todoread".to_string(),
         "todowrite".to_string(),
         "codesearch".to_string(),
+        "semantic_search".to_string(),
         "apply_patch".to_string(),
         "skill".to_string(),
         "multiedit".to_string(),
@@ -4905,6 +5607,82 @@ async fn get_doc() -> Json<DocResponse> {
     })
 }
 
+// --- /usage endpoint: aggregates the usage ledger for dashboards ---
+
+#[derive(Debug, Deserialize)]
+pub struct UsageQuery {
+    /// Inclusive start, milliseconds since epoch.
+    pub since: Option<i64>,
+    /// Exclusive end, milliseconds since epoch.
+    pub until: Option<i64>,
+    pub project: Option<String>,
+    pub directory: Option<String>,
+    pub group_by: Option<String>,
+    /// `json` (default) or `csv`.
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsageResponse {
+    pub group_by: Option<rocode_storage::UsageGroupBy>,
+    pub total: rocode_storage::UsageSummary,
+    pub rows: Vec<rocode_storage::UsageSummary>,
+}
+
+async fn get_usage(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<UsageQuery>,
+) -> Result<axum::response::Response> {
+    let ledger = state
+        .usage_ledger
+        .as_ref()
+        .ok_or_else(|| ApiError::InternalError("usage ledger is not available".to_string()))?;
+    let group_by = match query.group_by.as_deref() {
+        Some(value) => Some(rocode_storage::UsageGroupBy::parse(value).ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "unknown group_by `{value}`; expected provider, model, agent, session, project or day"
+            ))
+        })?),
+        None => None,
+    };
+    let filter = rocode_storage::UsageFilter {
+        since: query.since,
+        until: query.until,
+        project_id: query.project,
+        directory: query.directory,
+    };
+
+    let rows = ledger
+        .summarize(&filter, group_by)
+        .await
+        .map_err(|e| ApiError::InternalError(e.to_string()))?;
+    match query.format.as_deref().unwrap_or("json") {
+        "csv" => Ok((
+            [(axum::http::header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+            rocode_storage::usage_summaries_to_csv(&rows),
+        )
+            .into_response()),
+        "json" => {
+            let total = ledger
+                .summarize(&filter, None)
+                .await
+                .map_err(|e| ApiError::InternalError(e.to_string()))?
+                .into_iter()
+                .next()
+                .unwrap_or_default();
+            Ok(Json(UsageResponse {
+                group_by,
+                total,
+                rows,
+            })
+            .into_response())
+        }
+        other => Err(ApiError::BadRequest(format!(
+            "unknown format `{other}`; expected json or csv"
+        ))),
+    }
+}
+
 // --- /log endpoint: accepts a log entry and writes it via tracing ---
 
 #[derive(Debug, Deserialize)]
@@ -5028,15 +5806,12 @@ struct AgentInfo {
 }
 
 async fn list_agents() -> Result<Json<Vec<AgentInfo>>> {
-    let mut config = std::env::current_dir()
-        .ok()
-        .and_then(|cwd| load_config(&cwd).ok());
-
-    if let (Some(loader), Some(config)) = (get_plugin_loader(), config.as_mut()) {
-        apply_plugin_config_hooks(loader, config).await;
+    let mut config = CONFIG_STATE.read().await.clone();
+    if let Some(loader) = get_plugin_loader() {
+        apply_plugin_config_hooks(loader, &mut config).await;
     }
 
-    let registry = AgentRegistry::from_optional_config(config.as_ref());
+    let registry = AgentRegistry::from_config(&config);
     let agents = registry
         .list()
         .into_iter()
@@ -5387,3 +6162,67 @@ async fn ensure_plugin_loader_active(
     loader.touch_activity();
     Ok(Some(loader))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_watcher::reload_config;
+
+    #[test]
+    fn recording_dir_stays_under_the_default_directory() {
+        let base = rocode_provider::recorder::default_recording_dir();
+        assert_eq!(recording_dir(None).unwrap(), base);
+        assert_eq!(
+            recording_dir(Some("bug-123")).unwrap(),
+            base.join("bug-123")
+        );
+        assert!(recording_dir(Some("/tmp/elsewhere")).is_err());
+        assert!(recording_dir(Some("../outside")).is_err());
+        assert!(recording_dir(Some("nested/../../outside")).is_err());
+    }
+
+    #[tokio::test]
+    async fn config_reload_applies_changes_and_keeps_config_on_errors() {
+        let project = tempfile::tempdir().unwrap();
+        let config_path = project.path().join("opencode.json");
+        std::fs::write(
+            &config_path,
+            r#"{
+                "provider": {
+                    "reload-test": {
+                        "name": "Reload Test",
+                        "npm": "@ai-sdk/openai-compatible",
+                        "options": { "baseURL": "http://127.0.0.1:9/v1", "apiKey": "k" },
+                        "models": { "m1": { "name": "M1" } }
+                    }
+                },
+                "agent": { "reload-reviewer": { "description": "Reviews", "mode": "primary" } }
+            }"#,
+        )
+        .unwrap();
+
+        let state = ServerState::new();
+        let diff = reload_config(&state, project.path()).await.unwrap();
+        assert!(diff.touches_providers());
+        assert!(state
+            .providers
+            .read()
+            .await
+            .list_models()
+            .iter()
+            .any(|m| m.provider == "reload-test" && m.id == "m1"));
+        let Json(agents) = list_agents().await.unwrap();
+        assert!(agents.iter().any(|a| a.name == "reload-reviewer"));
+
+        std::fs::write(&config_path, "{ \"agent\": ").unwrap();
+        assert!(reload_config(&state, project.path()).await.is_err());
+        let Json(agents) = list_agents().await.unwrap();
+        assert!(agents.iter().any(|a| a.name == "reload-reviewer"));
+        assert!(CONFIG_STATE
+            .read()
+            .await
+            .provider
+            .as_ref()
+            .is_some_and(|p| p.contains_key("reload-test")));
+    }
+}
@@ -4,7 +4,6 @@ use futures::StreamExt;
 use once_cell::sync::Lazy;
 use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
-use std::path::PathBuf;
 use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
 use std::sync::RwLock;
@@ -20,15 +19,16 @@ use rocode_plugin::subprocess::{
     PluginAuthBridge, PluginContext, PluginFetchRequest, PluginLoader,
 };
 use rocode_provider::{
-    bootstrap_config_from_raw, create_registry_from_bootstrap_config, register_custom_fetch_proxy,
-    unregister_custom_fetch_proxy, AuthInfo, AuthManager, BootstrapConfig,
-    ConfigModel as BootstrapConfigModel, ConfigProvider as BootstrapConfigProvider,
-    CustomFetchProxy, CustomFetchRequest, CustomFetchResponse, CustomFetchStreamResponse,
-    ProviderError, ProviderRegistry,
+    auth_data_dir, bootstrap_config_from_raw, create_registry_from_bootstrap_config,
+    register_custom_fetch_proxy, unregister_custom_fetch_proxy, AuthInfo, AuthManager,
+    BootstrapConfig, ConfigModel as BootstrapConfigModel,
+    ConfigProvider as BootstrapConfigProvider, CustomFetchProxy, CustomFetchRequest,
+    CustomFetchResponse, CustomFetchStreamResponse, ProviderError, ProviderRegistry,
 };
-use rocode_session::SessionManager;
-use rocode_storage::{Database, MessageRepository, SessionRepository};
+use rocode_session::{SessionManager, UtilitySettings, UtilityTarget, UtilityTask};
+use rocode_storage::{Database, MessageRepository, SessionRepository, UsageLedgerRepository};
 
+use crate::config_watcher::spawn_config_watcher;
 use crate::routes;
 
 const DEFAULT_SERVER_URL: &str = "http://127.0.0.1:4096";
@@ -205,13 +205,14 @@ fn spawn_plugin_idle_monitor(loader: Arc<PluginLoader>) {
 pub struct ServerState {
     pub sessions: Mutex<SessionManager>,
     pub providers: tokio::sync::RwLock<ProviderRegistry>,
-    pub bootstrap_config: BootstrapConfig,
+    pub bootstrap_config: tokio::sync::RwLock<BootstrapConfig>,
     pub tool_registry: Arc<rocode_tool::ToolRegistry>,
     pub auth_manager: Arc<AuthManager>,
     pub event_bus: broadcast::Sender<String>,
     pub api_perf: Arc<ApiPerfCounters>,
     pub(crate) session_repo: Option<SessionRepository>,
     pub(crate) message_repo: Option<MessageRepository>,
+    pub(crate) usage_ledger: Option<UsageLedgerRepository>,
 }
 
 pub struct ApiPerfCounters {
@@ -236,13 +237,14 @@ impl ServerState {
         Self {
             sessions: Mutex::new(SessionManager::new()),
             providers: tokio::sync::RwLock::new(ProviderRegistry::new()),
-            bootstrap_config: BootstrapConfig::default(),
+            bootstrap_config: tokio::sync::RwLock::new(BootstrapConfig::default()),
             tool_registry: Arc::new(rocode_tool::ToolRegistry::new()),
             auth_manager: Arc::new(AuthManager::new()),
             event_bus: tx,
             api_perf: Arc::new(ApiPerfCounters::new()),
             session_repo: None,
             message_repo: None,
+            usage_ledger: None,
         }
     }
 
@@ -261,14 +263,8 @@ impl ServerState {
         let cwd = std::env::current_dir().unwrap_or_default();
         let bootstrap_config = match load_config(&cwd) {
             Ok(config) => {
-                let providers = convert_config_providers_for_bootstrap(&config);
-                bootstrap_config_from_raw(
-                    providers,
-                    config.disabled_providers.clone(),
-                    config.enabled_providers.clone(),
-                    config.model.clone(),
-                    config.small_model.clone(),
-                )
+                rocode_tool::task::configure_background_tasks(&config);
+                bootstrap_config_for(&config)
             }
             Err(error) => {
                 tracing::warn!(%error, "failed to load config for provider bootstrap, using defaults");
@@ -295,12 +291,15 @@ impl ServerState {
             &bootstrap_config,
             &auth_store,
         ));
-        state.bootstrap_config = bootstrap_config;
+        state.bootstrap_config = tokio::sync::RwLock::new(bootstrap_config);
         state.tool_registry = Arc::new(rocode_tool::create_default_registry().await);
         let db = Database::new().await?;
         let pool = db.pool().clone();
         state.session_repo = Some(SessionRepository::new(pool.clone()));
-        state.message_repo = Some(MessageRepository::new(pool));
+        state.message_repo = Some(MessageRepository::new(pool.clone()));
+        let usage_ledger = UsageLedgerRepository::new(pool);
+        rocode_session::install_usage_ledger(usage_ledger.clone());
+        state.usage_ledger = Some(usage_ledger);
         state.load_sessions_from_storage().await?;
         Ok(state)
     }
@@ -314,11 +313,20 @@ impl ServerState {
     /// connected providers become available immediately.
     pub async fn rebuild_providers(&self) {
         let auth_store = self.auth_manager.list().await;
-        let new_registry =
-            create_registry_from_bootstrap_config(&self.bootstrap_config, &auth_store);
+        let new_registry = create_registry_from_bootstrap_config(
+            &*self.bootstrap_config.read().await,
+            &auth_store,
+        );
         *self.providers.write().await = new_registry;
     }
 
+    /// Replace the bootstrap config with the provider settings of `config`
+    /// and rebuild the provider registry.
+    pub async fn reload_providers(&self, config: &rocode_config::Config) {
+        *self.bootstrap_config.write().await = bootstrap_config_for(config);
+        self.rebuild_providers().await;
+    }
+
     async fn load_sessions_from_storage(&self) -> anyhow::Result<()> {
         let (Some(session_repo), Some(message_repo)) = (&self.session_repo, &self.message_repo)
         else {
@@ -401,6 +409,17 @@ impl ServerState {
     }
 }
 
+fn bootstrap_config_for(config: &rocode_config::Config) -> BootstrapConfig {
+    let providers = convert_config_providers_for_bootstrap(config);
+    bootstrap_config_from_raw(
+        providers,
+        config.disabled_providers.clone(),
+        config.enabled_providers.clone(),
+        config.model.clone(),
+        config.small_model.clone(),
+    )
+}
+
 /// Convert rocode_config::ProviderConfig map to bootstrap ConfigProvider map.
 fn convert_config_providers_for_bootstrap(
     config: &rocode_config::Config,
@@ -443,10 +462,59 @@ fn provider_to_bootstrap(provider: &rocode_config::ProviderConfig) -> BootstrapC
         models,
         blacklist: (!provider.blacklist.is_empty()).then_some(provider.blacklist.clone()),
         whitelist: (!provider.whitelist.is_empty()).then_some(provider.whitelist.clone()),
+        rate_limit: provider
+            .rate_limit
+            .as_ref()
+            .map(|limits| rocode_provider::RateLimits {
+                requests_per_minute: limits.requests_per_minute,
+                tokens_per_minute: limits.tokens_per_minute,
+                max_in_flight: limits.max_in_flight,
+            }),
         ..Default::default()
     }
 }
 
+/// Resolves the `utility` block (falling back to `small_model`) against the
+/// registered providers. An unknown model leaves the pipeline on the session
+/// model rather than failing the request.
+pub fn resolve_utility(
+    config: &rocode_config::Config,
+    providers: &ProviderRegistry,
+) -> (Option<UtilityTarget>, UtilitySettings) {
+    let utility = config.utility.clone().unwrap_or_default();
+    let defaults = UtilitySettings::default();
+    let settings = UtilitySettings {
+        disabled: utility.disabled.unwrap_or(false),
+        timeout: utility
+            .timeout
+            .map(Duration::from_millis)
+            .unwrap_or(defaults.timeout),
+        concurrency: utility.concurrency.unwrap_or(defaults.concurrency),
+        cache: utility.cache.unwrap_or(defaults.cache),
+        tasks: utility
+            .tasks
+            .iter()
+            .filter_map(|(name, enabled)| UtilityTask::from_name(name).map(|task| (task, *enabled)))
+            .collect(),
+    };
+
+    let target = utility
+        .model
+        .as_deref()
+        .or(config.small_model.as_deref())
+        .and_then(|model| {
+            let (provider_id, model_id) = providers.parse_model_string(model)?;
+            let provider = providers.get_provider(&provider_id).ok()?;
+            if provider.get_model(&model_id).is_none() {
+                tracing::warn!(model, "utility model not found; using the session model");
+                return None;
+            }
+            Some(UtilityTarget::new(provider, provider_id, model_id))
+        });
+
+    (target, settings)
+}
+
 fn model_to_bootstrap(id: &str, model: &rocode_config::ModelConfig) -> BootstrapConfigModel {
     let mut options = HashMap::new();
     if let Some(api_key) = &model.api_key {
@@ -488,21 +556,6 @@ fn variant_to_bootstrap(
     values
 }
 
-fn auth_data_dir() -> PathBuf {
-    if let Ok(path) = std::env::var("OPENCODE_DATA_DIR") {
-        let trimmed = path.trim();
-        if !trimmed.is_empty() {
-            return PathBuf::from(trimmed);
-        }
-    }
-
-    dirs::data_local_dir()
-        .or_else(dirs::data_dir)
-        .unwrap_or_else(std::env::temp_dir)
-        .join("opencode")
-        .join("data")
-}
-
 async fn load_plugin_auth_store(server_url: &str, auth_manager: Arc<AuthManager>) {
     let cwd = match std::env::current_dir() {
         Ok(cwd) => cwd,
@@ -624,6 +677,9 @@ pub async fn run_server(addr: SocketAddr) -> anyhow::Result<()> {
     };
     let state = Arc::new(ServerState::new_with_storage_for_url(server_url).await?);
 
+    let cwd = std::env::current_dir().unwrap_or_default();
+    spawn_config_watcher(state.clone(), cwd);
+
     let app = routes::router()
         .layer(cors_layer())
         .layer(TraceLayer::new_for_http())
@@ -642,6 +698,9 @@ pub async fn run_server_with_state(
     addr: SocketAddr,
     state: Arc<ServerState>,
 ) -> anyhow::Result<()> {
+    let cwd = std::env::current_dir().unwrap_or_default();
+    spawn_config_watcher(state.clone(), cwd);
+
     let app = routes::router()
         .layer(cors_layer())
         .layer(TraceLayer::new_for_http())
@@ -785,4 +844,127 @@ mod tests {
             .expect("get should succeed")
             .is_none());
     }
+
+    struct LocalModels(Vec<rocode_provider::ModelInfo>);
+
+    impl LocalModels {
+        fn new() -> Self {
+            Self(
+                ["small", "tiny"]
+                    .into_iter()
+                    .map(|id| rocode_provider::ModelInfo {
+                        id: id.to_string(),
+                        name: id.to_string(),
+                        provider: "local".to_string(),
+                        context_window: 8192,
+                        max_input_tokens: None,
+                        max_output_tokens: 1024,
+                        supports_vision: false,
+                        supports_tools: false,
+                        cost_per_million_input: 0.0,
+                        cost_per_million_output: 0.0,
+                        supports_batch: false,
+                        supports_pdf: false,
+                    })
+                    .collect(),
+            )
+        }
+    }
+
+    #[async_trait]
+    impl rocode_provider::Provider for LocalModels {
+        fn id(&self) -> &str {
+            "local"
+        }
+
+        fn name(&self) -> &str {
+            "Local"
+        }
+
+        fn models(&self) -> Vec<rocode_provider::ModelInfo> {
+            self.0.clone()
+        }
+
+        fn get_model(&self, id: &str) -> Option<&rocode_provider::ModelInfo> {
+            self.0.iter().find(|model| model.id == id)
+        }
+
+        async fn chat(
+            &self,
+            _request: rocode_provider::ChatRequest,
+        ) -> Result<rocode_provider::ChatResponse, ProviderError> {
+            Err(ProviderError::InvalidRequest("not used".to_string()))
+        }
+
+        async fn chat_stream(
+            &self,
+            _request: rocode_provider::ChatRequest,
+        ) -> Result<rocode_provider::StreamResult, ProviderError> {
+            Err(ProviderError::InvalidRequest("not used".to_string()))
+        }
+    }
+
+    #[test]
+    fn resolve_utility_prefers_utility_model_then_small_model() {
+        let mut providers = ProviderRegistry::new();
+        providers.register(LocalModels::new());
+        let target_of = |config: &rocode_config::Config| {
+            resolve_utility(config, &providers)
+                .0
+                .map(|target| format!("{}/{}", target.provider_id, target.model_id))
+        };
+
+        let small_only = rocode_config::Config {
+            small_model: Some("local/tiny".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(target_of(&small_only).as_deref(), Some("local/tiny"));
+
+        let both = rocode_config::Config {
+            small_model: Some("local/tiny".to_string()),
+            utility: Some(rocode_config::UtilityConfig {
+                model: Some("local/small".to_string()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(target_of(&both).as_deref(), Some("local/small"));
+
+        assert_eq!(target_of(&rocode_config::Config::default()), None);
+    }
+
+    #[test]
+    fn resolve_utility_ignores_unknown_models_and_tasks() {
+        let mut providers = ProviderRegistry::new();
+        providers.register(LocalModels::new());
+
+        for model in ["local/missing", "remote/small"] {
+            let config = rocode_config::Config {
+                small_model: Some("local/tiny".to_string()),
+                utility: Some(rocode_config::UtilityConfig {
+                    model: Some(model.to_string()),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+            assert!(resolve_utility(&config, &providers).0.is_none(), "{model}");
+        }
+
+        let config = rocode_config::Config {
+            utility: Some(rocode_config::UtilityConfig {
+                disabled: Some(true),
+                timeout: Some(500),
+                tasks: HashMap::from([("commit".to_string(), false), ("bogus".to_string(), true)]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let (_, settings) = resolve_utility(&config, &providers);
+        assert!(settings.disabled);
+        assert_eq!(settings.timeout, Duration::from_millis(500));
+        assert_eq!(
+            settings.tasks,
+            HashMap::from([(UtilityTask::CommitMessage, false)])
+        );
+    }
 }
@@ -514,9 +514,18 @@ When constructing the summary, try to stick to this template:
             top_p: None,
             variant: input.variant.clone(),
             provider_options: None,
+            response_format: None,
+            tool_choice: None,
+            stop: None,
+            parallel_tool_calls: None,
         };
 
-        match provider.chat_stream(request).await {
+        let stream = rocode_provider::recorder::scope(
+            input.session_id.clone(),
+            provider.chat_stream(request),
+        )
+        .await;
+        match stream {
             Ok(stream) => {
                 let text = collect_compaction_text(stream, input.abort.clone()).await?;
 
@@ -1131,6 +1140,8 @@ mod tests {
                     supports_tools: false,
                     cost_per_million_input: 0.0,
                     cost_per_million_output: 0.0,
+                    supports_batch: false,
+                    supports_pdf: false,
                 },
                 stream_events: vec![
                     StreamEvent::Start,
@@ -8,12 +8,15 @@ pub mod message_v2;
 pub mod prompt;
 pub mod retry;
 pub mod revert;
+pub mod review;
 pub mod session;
 pub mod snapshot;
 pub mod status;
 pub mod summary;
 pub mod system;
 pub mod todo;
+pub mod usage_ledger;
+pub mod utility;
 
 pub use compaction::*;
 pub use instruction::*;
@@ -22,11 +25,14 @@ pub use message_v2::*;
 pub use prompt::*;
 pub use retry::*;
 pub use revert::*;
+pub use review::*;
 pub use session::*;
 pub use status::*;
 pub use summary::*;
 pub use system::*;
 pub use todo::*;
+pub use usage_ledger::install_usage_ledger;
+pub use utility::{UtilityPipeline, UtilitySettings, UtilityTarget, UtilityTask};
 
 pub use session::{
     BusyError, FileDiff, PermissionRuleset, RunStatus, Session, SessionError, SessionEvent,
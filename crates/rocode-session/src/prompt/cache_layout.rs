@@ -0,0 +1,69 @@
+//! Remembers the cacheable layout of each session's last request so a step
+//! that cannot reuse the provider prompt cache is reported rather than
+//! silently billed at the full input price.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use rocode_provider::{CacheBust, CacheFingerprint};
+
+fn layouts() -> &'static Mutex<HashMap<String, CacheFingerprint>> {
+    static LAYOUTS: OnceLock<Mutex<HashMap<String, CacheFingerprint>>> = OnceLock::new();
+    LAYOUTS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Stores `fingerprint` as the session's latest layout and returns why it
+/// breaks the previous request's cached prefix, if it does.
+pub(super) fn observe(session_id: &str, fingerprint: CacheFingerprint) -> Option<CacheBust> {
+    let mut layouts = layouts().lock().ok()?;
+    let bust = layouts
+        .get(session_id)
+        .and_then(|previous| fingerprint.bust_since(previous));
+    layouts.insert(session_id.to_string(), fingerprint);
+    bust
+}
+
+/// Drops the remembered layout once a session is deleted or archived.
+pub(crate) fn forget(session_id: &str) {
+    if let Ok(mut layouts) = layouts().lock() {
+        layouts.remove(session_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rocode_provider::Message;
+
+    #[test]
+    fn observe_compares_against_the_previous_request() {
+        let session_id = "ses_cache_layout_test";
+        let first = vec![Message::system("system"), Message::user("hello")];
+        assert_eq!(observe(session_id, CacheFingerprint::of(&first, &[])), None);
+
+        let mut next = first.clone();
+        next.push(Message::assistant("hi"));
+        assert_eq!(observe(session_id, CacheFingerprint::of(&next, &[])), None);
+
+        let changed = vec![Message::system("system, edited by a hook")];
+        assert_eq!(
+            observe(session_id, CacheFingerprint::of(&changed, &[])),
+            Some(CacheBust::SystemChanged)
+        );
+    }
+
+    #[test]
+    fn forgotten_sessions_start_a_fresh_layout() {
+        let session_id = "ses_cache_layout_forget_test";
+        let first = vec![Message::system("system")];
+        observe(session_id, CacheFingerprint::of(&first, &[]));
+
+        forget(session_id);
+        let changed = vec![Message::system("another system")];
+        assert_eq!(
+            observe(session_id, CacheFingerprint::of(&changed, &[])),
+            None
+        );
+        forget(session_id);
+    }
+}
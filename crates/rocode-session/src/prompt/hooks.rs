@@ -87,3 +87,51 @@ pub(crate) fn apply_chat_message_hook_outputs(
         }
     }
 }
+
+/// Applies `chat.params` hook outputs. `null` leaves a parameter unchanged.
+pub(crate) fn apply_chat_params_hook_outputs(
+    request: &mut rocode_provider::ChatRequest,
+    hook_outputs: Vec<rocode_plugin::HookOutput>,
+) {
+    fn present<'a>(
+        object: &'a serde_json::Map<String, serde_json::Value>,
+        key: &str,
+    ) -> Option<&'a serde_json::Value> {
+        object.get(key).filter(|value| !value.is_null())
+    }
+
+    for output in hook_outputs {
+        let Some(payload) = output.payload.as_ref() else {
+            continue;
+        };
+        let Some(object) = hook_payload_object(payload) else {
+            continue;
+        };
+        if let Some(value) = present(object, "temperature").and_then(|v| v.as_f64()) {
+            request.temperature = Some(value as f32);
+        }
+        if let Some(value) = present(object, "topP").and_then(|v| v.as_f64()) {
+            request.top_p = Some(value as f32);
+        }
+        if let Some(value) = present(object, "maxTokens").and_then(|v| v.as_u64()) {
+            request.max_tokens = Some(value);
+        }
+        if let Some(value) = present(object, "toolChoice") {
+            match serde_json::from_value(value.clone()) {
+                Ok(choice) => request.tool_choice = Some(choice),
+                Err(error) => {
+                    tracing::warn!(%error, "ignoring invalid toolChoice from chat.params")
+                }
+            }
+        }
+        if let Some(value) = present(object, "stop") {
+            match serde_json::from_value::<Vec<String>>(value.clone()) {
+                Ok(stop) => request.stop = Some(stop).filter(|stop| !stop.is_empty()),
+                Err(error) => tracing::warn!(%error, "ignoring invalid stop from chat.params"),
+            }
+        }
+        if let Some(value) = present(object, "parallelToolCalls").and_then(|v| v.as_bool()) {
+            request.parallel_tool_calls = Some(value);
+        }
+    }
+}
@@ -3,9 +3,11 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
+use rocode_provider::transform::{transform_messages, Modality, ProviderType};
 use rocode_provider::{
     get_model_context_limit, ChatResponse, Content, ContentPart, Message, Provider, Role,
 };
+use tokio::task::JoinHandle;
 
 use crate::compaction::{
     CompactionConfig, CompactionEngine, MessageForPrune, ModelLimits, PruneToolPart, TokenUsage,
@@ -16,11 +18,140 @@ use crate::message_v2::{
     MessagePath, MessageWithParts, ModelRef as V2ModelRef, Part as V2Part, StepFinishPart,
     StepStartPart, StepTokens, UserTime,
 };
-use crate::summary::{summarize_into_session, SummarizeInput};
+use crate::summary::{
+    first_user_text_for_message, set_message_summary_title, summarize_into_session, SummarizeInput,
+    MESSAGE_SUMMARY_TITLE_KEY,
+};
+use crate::utility::{UtilityPipeline, UtilityTarget};
 use crate::{MessageRole, PartType, Session, SessionMessage};
 
-use super::tools_and_output::generate_session_title_llm;
-use super::SessionPrompt;
+use super::{SessionPrompt, SessionUpdateHook};
+
+/// Title jobs started alongside the first model call. Results are applied as
+/// they land, so the prompt loop never waits on the utility model; the
+/// heuristic titles written in the meantime are replaced. Jobs still running
+/// when this is dropped are aborted.
+#[derive(Default)]
+pub(super) struct PendingTitles {
+    session_title: Option<JoinHandle<String>>,
+    message_title: Option<(String, JoinHandle<Option<String>>)>,
+}
+
+impl PendingTitles {
+    pub(super) fn start(
+        utility: &Arc<UtilityPipeline>,
+        session: &Session,
+        target: &UtilityTarget,
+    ) -> Self {
+        let mut pending = Self::default();
+
+        if session.is_default_title() {
+            let first_user_text = session
+                .messages
+                .iter()
+                .find(|m| matches!(m.role, MessageRole::User))
+                .map(|m| m.get_text())
+                .unwrap_or_default();
+            if !first_user_text.trim().is_empty() {
+                pending.session_title =
+                    utility.spawn_session_title(first_user_text, target.clone());
+            }
+        }
+
+        let last_user = session
+            .messages
+            .iter()
+            .rev()
+            .find(|m| matches!(m.role, MessageRole::User));
+        if let Some(message) = last_user {
+            let has_title = message
+                .metadata
+                .get(MESSAGE_SUMMARY_TITLE_KEY)
+                .and_then(|value| value.as_str())
+                .is_some_and(|value| !value.trim().is_empty());
+            if !has_title {
+                if let Some(text) = first_user_text_for_message(session, &message.id) {
+                    pending.message_title = utility
+                        .spawn_message_title(text, target.clone())
+                        .map(|handle| (message.id.clone(), handle));
+                }
+            }
+        }
+
+        pending
+    }
+
+    /// Applies jobs that have already finished. Returns whether the session
+    /// changed.
+    pub(super) async fn apply_finished(&mut self, session: &mut Session) -> bool {
+        self.apply(session, false).await
+    }
+
+    /// Hands jobs that are still running to a background task. When they
+    /// land, it applies them to a copy of `session` and reports that copy
+    /// through `update_hook`, so the prompt finishes without waiting on the
+    /// utility model. Without a hook there is nobody to tell, and the jobs
+    /// are aborted.
+    pub(super) fn detach(&mut self, session: &Session, update_hook: Option<SessionUpdateHook>) {
+        if self.session_title.is_none() && self.message_title.is_none() {
+            return;
+        }
+        let Some(hook) = update_hook else {
+            self.abort();
+            return;
+        };
+        let mut pending = std::mem::take(self);
+        let mut snapshot = session.clone();
+        tokio::spawn(async move {
+            if pending.apply(&mut snapshot, true).await {
+                hook(&snapshot);
+            }
+        });
+    }
+
+    pub(super) fn abort(&mut self) {
+        if let Some(handle) = self.session_title.take() {
+            handle.abort();
+        }
+        if let Some((_, handle)) = self.message_title.take() {
+            handle.abort();
+        }
+    }
+
+    async fn apply(&mut self, session: &mut Session, wait: bool) -> bool {
+        let mut changed = false;
+
+        if let Some(handle) = self
+            .session_title
+            .take_if(|handle| wait || handle.is_finished())
+        {
+            if let Ok(title) = handle.await {
+                if session.is_default_title() && !title.trim().is_empty() {
+                    session.set_title(title);
+                    changed = true;
+                }
+            }
+        }
+
+        if let Some((message_id, handle)) = self
+            .message_title
+            .take_if(|(_, handle)| wait || handle.is_finished())
+        {
+            if let Ok(Some(title)) = handle.await {
+                set_message_summary_title(session, &message_id, &title);
+                changed = true;
+            }
+        }
+
+        changed
+    }
+}
+
+impl Drop for PendingTitles {
+    fn drop(&mut self) {
+        self.abort();
+    }
+}
 
 impl SessionPrompt {
     pub(super) fn build_chat_messages(
@@ -97,6 +228,32 @@ impl SessionPrompt {
         Ok(messages)
     }
 
+    /// Converts the transcript for a model other than the one it was built
+    /// for, e.g. after failing over to a different provider.
+    pub(super) fn chat_messages_for_model(
+        session_messages: &[SessionMessage],
+        system_prompt: Option<&str>,
+        provider: &dyn Provider,
+        provider_id: &str,
+        model_id: &str,
+    ) -> anyhow::Result<Vec<Message>> {
+        let mut messages = Self::build_chat_messages(session_messages, system_prompt)?;
+        let modalities = match provider.get_model(model_id) {
+            Some(model) if model.supports_vision => vec![Modality::Image, Modality::Pdf],
+            Some(_) => Vec::new(),
+            None => vec![Modality::Image, Modality::Pdf],
+        };
+        transform_messages(
+            &mut messages,
+            ProviderType::from_provider_id(provider_id),
+            model_id,
+            &modalities,
+            "",
+            provider_id,
+        );
+        Ok(messages)
+    }
+
     pub(super) fn parts_to_content(parts: &[crate::MessagePart]) -> Content {
         let has_parts = parts
             .iter()
@@ -384,32 +541,6 @@ impl SessionPrompt {
         total_chars > MAX_CONTEXT_CHARS
     }
 
-    pub(super) async fn ensure_title(
-        session: &mut Session,
-        provider: Arc<dyn Provider>,
-        model_id: &str,
-    ) {
-        if !session.is_default_title() {
-            return;
-        }
-
-        let first_user_text = session
-            .messages
-            .iter()
-            .find(|m| matches!(m.role, MessageRole::User))
-            .map(|m| m.get_text())
-            .unwrap_or_default();
-
-        if first_user_text.trim().is_empty() {
-            return;
-        }
-
-        let title = generate_session_title_llm(&first_user_text, provider, model_id).await;
-        if !title.trim().is_empty() {
-            session.set_title(title);
-        }
-    }
-
     pub(super) fn to_message_with_parts(
         messages: &[SessionMessage],
         provider_id: &str,
@@ -795,7 +926,6 @@ impl SessionPrompt {
         session_id: &str,
         provider_id: &str,
         model_id: &str,
-        provider: &dyn Provider,
     ) -> anyhow::Result<()> {
         let directory = session.directory.clone();
         let worktree = std::path::Path::new(&directory);
@@ -816,8 +946,9 @@ impl SessionPrompt {
             session,
             &messages,
             worktree,
-            Some(provider),
-            Some(model_id),
+            None,
+            None,
+            None,
             None,
         )
         .await?;
@@ -976,6 +1107,8 @@ mod tests {
                     supports_tools: false,
                     cost_per_million_input: 0.0,
                     cost_per_million_output: 0.0,
+                    supports_batch: false,
+                    supports_pdf: false,
                 }),
             }
         }
@@ -1157,6 +1290,8 @@ mod tests {
                 supports_tools: false,
                 cost_per_million_input: 0.0,
                 cost_per_million_output: 0.0,
+                supports_batch: false,
+                supports_pdf: false,
             }),
         };
         let mut msg = SessionMessage::user("ses_test", "hello");
@@ -1,3 +1,4 @@
+pub(crate) mod cache_layout;
 pub mod compaction_helpers;
 mod file_parts;
 pub(crate) mod hooks;
@@ -10,20 +11,22 @@ pub mod tools_and_output;
 
 pub use compaction_helpers::{should_compact, trigger_compaction};
 pub(crate) use hooks::{
-    apply_chat_message_hook_outputs, apply_chat_messages_hook_outputs, session_message_hook_payload,
+    apply_chat_message_hook_outputs, apply_chat_messages_hook_outputs,
+    apply_chat_params_hook_outputs, session_message_hook_payload,
 };
 #[cfg(test)]
 pub(crate) use shell::resolve_shell_invocation;
 pub use shell::{resolve_command_template, shell_exec, CommandInput, ShellInput};
 pub use subtask::{tool_definitions_from_schemas, SubtaskExecutor, ToolSchema};
 pub use tools_and_output::{
-    create_structured_output_tool, extract_structured_output, generate_session_title,
-    generate_session_title_llm, insert_reminders, max_steps_for_agent, merge_tool_definitions,
-    resolve_tools, resolve_tools_with_mcp, resolve_tools_with_mcp_registry,
-    structured_output_system_prompt, was_plan_agent, ResolvedTool, StructuredOutputConfig,
+    generate_session_title, generate_session_title_llm, insert_reminders, max_steps_for_agent,
+    merge_tool_definitions, record_structured_output, resolve_tools, resolve_tools_with_mcp,
+    resolve_tools_with_mcp_registry, was_plan_agent, ResolvedTool, STRUCTURED_OUTPUT_ERROR_KEY,
+    STRUCTURED_OUTPUT_KEY,
 };
 
 use std::collections::HashMap;
+use std::fmt;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -34,11 +37,16 @@ use tokio_util::sync::CancellationToken;
 use futures::StreamExt;
 use rocode_plugin::{HookContext, HookEvent};
 use rocode_provider::transform::{apply_caching, ProviderType};
-use rocode_provider::{ChatRequest, Provider, StreamEvent, ToolDefinition};
+use rocode_provider::{
+    CacheFingerprint, ChatRequest, FailoverReason, Provider, ProviderError, StreamEvent,
+    StreamResult, StreamUsage, ToolChoice, ToolDefinition,
+};
 
 use crate::compaction::{run_compaction, CompactionResult};
 use crate::message_v2::ModelRef as V2ModelRef;
+use crate::utility::{UtilityPipeline, UtilityTarget};
 use crate::{MessageRole, PartType, Session, SessionMessage, SessionStateManager};
+use message_building::PendingTitles;
 
 const MAX_STEPS: u32 = 100;
 const STREAM_UPDATE_INTERVAL_MS: u64 = 120;
@@ -157,6 +165,30 @@ pub struct AgentParams {
     pub max_tokens: Option<u64>,
     pub temperature: Option<f32>,
     pub top_p: Option<f32>,
+    /// Models tried in order when the current one fails with a
+    /// [`FailoverReason`]. Once switched, the rest of the run stays there.
+    pub fallback: Vec<FallbackModel>,
+    /// Applied to the first step only when it forces a tool call.
+    pub tool_choice: Option<ToolChoice>,
+    pub stop: Option<Vec<String>>,
+    pub parallel_tool_calls: Option<bool>,
+}
+
+/// A fallback model together with the provider that serves it.
+#[derive(Clone)]
+pub struct FallbackModel {
+    pub provider_id: String,
+    pub model_id: String,
+    pub provider: Arc<dyn Provider>,
+}
+
+impl fmt::Debug for FallbackModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FallbackModel")
+            .field("provider_id", &self.provider_id)
+            .field("model_id", &self.model_id)
+            .finish_non_exhaustive()
+    }
 }
 
 pub type SessionUpdateHook = Arc<dyn Fn(&Session) + Send + Sync + 'static>;
@@ -176,6 +208,7 @@ pub struct SessionPrompt {
     session_state: Arc<RwLock<SessionStateManager>>,
     mcp_clients: Option<Arc<rocode_mcp::McpClientRegistry>>,
     lsp_registry: Option<Arc<rocode_lsp::LspClientRegistry>>,
+    utility: Option<Arc<UtilityPipeline>>,
 }
 
 impl SessionPrompt {
@@ -260,6 +293,7 @@ impl SessionPrompt {
             session_state,
             mcp_clients: None,
             lsp_registry: None,
+            utility: None,
         }
     }
 
@@ -273,6 +307,13 @@ impl SessionPrompt {
         self
     }
 
+    /// Pipeline for titles, summaries and compaction. Without one, no
+    /// utility model is called and titles come from the heuristics.
+    pub fn with_utility(mut self, utility: Arc<UtilityPipeline>) -> Self {
+        self.utility = Some(utility);
+        self
+    }
+
     pub async fn assert_not_busy(&self, session_id: &str) -> anyhow::Result<()> {
         let state = self.state.lock().await;
         if state.contains_key(session_id) {
@@ -508,6 +549,7 @@ impl SessionPrompt {
             update_hook,
             agent_lookup,
             ask_question_hook,
+            self.utility.clone(),
         )
         .await;
 
@@ -590,6 +632,7 @@ impl SessionPrompt {
             None,
             None,
             None,
+            self.utility.clone(),
         )
         .await;
 
@@ -606,9 +649,9 @@ impl SessionPrompt {
     async fn loop_inner(
         session_id: String,
         token: CancellationToken,
-        provider: Arc<dyn Provider>,
-        model_id: String,
-        provider_id: String,
+        mut provider: Arc<dyn Provider>,
+        mut model_id: String,
+        mut provider_id: String,
         session: &mut Session,
         agent_name: Option<&str>,
         system_prompt: Option<String>,
@@ -617,10 +660,16 @@ impl SessionPrompt {
         update_hook: Option<SessionUpdateHook>,
         agent_lookup: Option<Arc<dyn Fn(&str) -> Option<rocode_tool::TaskAgentInfo> + Send + Sync>>,
         ask_question_hook: Option<AskQuestionHook>,
+        utility: Option<Arc<UtilityPipeline>>,
     ) -> anyhow::Result<()> {
         let mut step = 0u32;
-        let provider_type = ProviderType::from_provider_id(&provider_id);
+        let mut provider_type = ProviderType::from_provider_id(&provider_id);
         let mut post_first_step_ran = false;
+        let utility = utility.unwrap_or_else(|| Arc::new(UtilityPipeline::disabled()));
+        let session_target = UtilityTarget::new(provider.clone(), &provider_id, &model_id);
+        let mut pending_titles = PendingTitles::start(&utility, session, &session_target);
+        let mut fallback_chain = agent_params.fallback.iter();
+        let mut failover: Option<(String, FailoverReason)> = None;
 
         loop {
             if token.is_cancelled() {
@@ -705,9 +754,13 @@ impl SessionPrompt {
                     .unwrap_or_default();
                 let compaction_messages =
                     Self::build_chat_messages(&filtered_messages, None).unwrap_or_default();
+                let current_target = UtilityTarget::new(provider.clone(), &provider_id, &model_id);
+                let compaction_target = utility
+                    .compaction_target(&current_target)
+                    .unwrap_or(&current_target);
                 let model_ref = V2ModelRef {
-                    provider_id: provider_id.clone(),
-                    model_id: model_id.clone(),
+                    provider_id: compaction_target.provider_id.clone(),
+                    model_id: compaction_target.model_id.clone(),
                 };
 
                 match run_compaction::<crate::compaction::NoopSessionOps>(
@@ -715,7 +768,7 @@ impl SessionPrompt {
                     &parent_id,
                     compaction_messages,
                     model_ref,
-                    provider.clone(),
+                    compaction_target.provider.clone(),
                     CancellationToken::new(),
                     true, // auto-triggered
                     None,
@@ -774,6 +827,7 @@ impl SessionPrompt {
                     .with_data("messages", hook_messages),
             )
             .await;
+            let messages_hooked = !message_hook_outputs.is_empty();
             apply_chat_messages_hook_outputs(&mut filtered_messages, message_hook_outputs);
 
             let mut prompt_messages = filtered_messages;
@@ -782,14 +836,35 @@ impl SessionPrompt {
                 prompt_messages = insert_reminders(&prompt_messages, agent, was_plan);
             }
 
-            let mut chat_messages =
-                Self::build_chat_messages(&prompt_messages, system_prompt.as_deref())?;
+            let mut chat_messages = Self::chat_messages_for_model(
+                &prompt_messages,
+                system_prompt.as_deref(),
+                provider.as_ref(),
+                &provider_id,
+                &model_id,
+            )?;
 
-            apply_caching(&mut chat_messages, provider_type);
             let resolved_tools =
                 merge_tool_definitions(tools.clone(), Self::mcp_tools_from_session(session));
 
-            let request = ChatRequest {
+            // Anything but appended messages invalidates the provider's cached
+            // prefix; say so instead of silently paying full input price.
+            let cache_bust = cache_layout::observe(
+                &session_id,
+                CacheFingerprint::of(&chat_messages, &resolved_tools),
+            );
+            if let Some(reason) = &cache_bust {
+                tracing::warn!(
+                    session_id = %session_id,
+                    step,
+                    %reason,
+                    messages_hooked,
+                    "prompt cache prefix changed since the previous request"
+                );
+            }
+            apply_caching(&mut chat_messages, provider_type);
+
+            let mut request = ChatRequest {
                 model: model_id.clone(),
                 messages: chat_messages,
                 max_tokens: Some(agent_params.max_tokens.unwrap_or(8192)),
@@ -804,15 +879,86 @@ impl SessionPrompt {
                 top_p: agent_params.top_p,
                 variant: None,
                 provider_options: None,
+                response_format: None,
+                tool_choice: match &agent_params.tool_choice {
+                    Some(ToolChoice::Required | ToolChoice::Tool(_)) if step > 1 => None,
+                    choice => choice.clone(),
+                },
+                stop: agent_params.stop.clone(),
+                parallel_tool_calls: agent_params.parallel_tool_calls,
             };
 
-            // Stream the response (matching TS streamText approach).
-            let mut stream = match provider.chat_stream(request).await {
-                Ok(s) => s,
-                Err(e) => {
-                    tracing::error!("Provider error for session {}: {}", session_id, e);
-                    return Err(anyhow::anyhow!("{}", e));
+            // Plugin hook: chat.params — let plugins adjust sampling and tool controls
+            let mut params_hook = HookContext::new(HookEvent::ChatParams)
+                .with_session(&session_id)
+                .with_data("sessionID", serde_json::json!(&session_id))
+                .with_data("model_id", serde_json::json!(&model_id))
+                .with_data("provider_id", serde_json::json!(&provider_id))
+                .with_data("temperature", serde_json::json!(request.temperature))
+                .with_data("topP", serde_json::json!(request.top_p))
+                .with_data("maxTokens", serde_json::json!(request.max_tokens))
+                .with_data("toolChoice", serde_json::json!(request.tool_choice))
+                .with_data("stop", serde_json::json!(request.stop))
+                .with_data(
+                    "parallelToolCalls",
+                    serde_json::json!(request.parallel_tool_calls),
+                );
+            if let Some(agent) = agent_name {
+                params_hook = params_hook.with_data("agent", serde_json::json!(agent));
+            }
+            let params_hook_outputs = rocode_plugin::trigger_collect(params_hook).await;
+            apply_chat_params_hook_outputs(&mut request, params_hook_outputs);
+
+            // Stream the response (matching TS streamText approach). Capacity
+            // failures move down the agent's fallback chain before giving up.
+            let mut stream = loop {
+                let mut attempt = request.clone();
+                if let Some(model) = provider.get_model(&model_id) {
+                    attempt.apply_model_capabilities(model);
                 }
+                let error = match rocode_provider::recorder::scope(
+                    session_id.clone(),
+                    provider.chat_stream(attempt),
+                )
+                .await
+                {
+                    Ok(s) if fallback_chain.as_slice().is_empty() => break s,
+                    Ok(s) => match await_first_output(s).await {
+                        Ok(s) => break s,
+                        Err(e) => e,
+                    },
+                    Err(e) => e,
+                };
+                let Some((reason, next)) =
+                    FailoverReason::classify(&error).zip(fallback_chain.next())
+                else {
+                    tracing::error!("Provider error for session {}: {}", session_id, error);
+                    return Err(anyhow::anyhow!("{}", error));
+                };
+                tracing::warn!(
+                    session_id = %session_id,
+                    from = %format!("{}/{}", provider_id, model_id),
+                    to = %format!("{}/{}", next.provider_id, next.model_id),
+                    %reason,
+                    %error,
+                    "failing over to fallback model"
+                );
+                let failed_model = format!("{}/{}", provider_id, model_id);
+                let origin = failover.take().map_or(failed_model, |(origin, _)| origin);
+                failover = Some((origin, reason));
+                provider = next.provider.clone();
+                provider_id = next.provider_id.clone();
+                model_id = next.model_id.clone();
+                provider_type = ProviderType::from_provider_id(&provider_id);
+                request.model = model_id.clone();
+                request.messages = Self::chat_messages_for_model(
+                    &prompt_messages,
+                    system_prompt.as_deref(),
+                    provider.as_ref(),
+                    &provider_id,
+                    &model_id,
+                )?;
+                apply_caching(&mut request.messages, provider_type);
             };
 
             // Create assistant message placeholder before consuming the stream so
@@ -826,10 +972,23 @@ impl SessionPrompt {
                 serde_json::json!(&provider_id),
             );
             assistant_metadata.insert("model_id".to_string(), serde_json::json!(&model_id));
+            if let Some((origin, reason)) = &failover {
+                assistant_metadata.insert("failover_from".to_string(), serde_json::json!(origin));
+                assistant_metadata.insert(
+                    "failover_reason".to_string(),
+                    serde_json::json!(reason.to_string()),
+                );
+            }
             if let Some(agent) = agent_name {
                 assistant_metadata.insert("agent".to_string(), serde_json::json!(agent));
                 assistant_metadata.insert("mode".to_string(), serde_json::json!(agent));
             }
+            if let Some(reason) = &cache_bust {
+                assistant_metadata.insert(
+                    "cache_bust".to_string(),
+                    serde_json::json!(reason.to_string()),
+                );
+            }
             session.messages.push(SessionMessage {
                 id: assistant_message_id,
                 session_id: session_id.clone(),
@@ -1266,9 +1425,13 @@ impl SessionPrompt {
                     Ok(StreamEvent::Usage {
                         prompt_tokens: pt,
                         completion_tokens: ct,
+                        cache_read_tokens: cr,
+                        cache_write_tokens: cw,
                     }) => {
                         prompt_tokens = pt;
                         completion_tokens = ct;
+                        cache_read_tokens = cr;
+                        cache_write_tokens = cw;
                     }
                     Ok(StreamEvent::Done | StreamEvent::Finish) => break,
                     Ok(StreamEvent::Start) => {}
@@ -1402,6 +1565,22 @@ impl SessionPrompt {
                 }
             }
 
+            // Price the step for the message and the usage ledger.
+            let step_usage = StreamUsage {
+                prompt_tokens,
+                completion_tokens,
+                reasoning_tokens,
+                cache_read_tokens,
+                cache_write_tokens,
+            };
+            let step_cost = rocode_provider::resolve_model_cost(
+                &provider_id,
+                &model_id,
+                provider.get_model(&model_id),
+            )
+            .map(|cost| cost.cost_for(&step_usage))
+            .unwrap_or(0.0);
+
             // Finalize the placeholder assistant message with usage metadata.
             if let Some(assistant_msg) = session.messages.get_mut(assistant_index) {
                 if let Some(reason) = finish_reason.clone() {
@@ -1448,9 +1627,25 @@ impl SessionPrompt {
                     reasoning_tokens,
                     cache_read_tokens,
                     cache_write_tokens,
-                    ..Default::default()
+                    total_cost: step_cost,
                 });
             }
+            let step_tokens =
+                prompt_tokens + completion_tokens + cache_read_tokens + cache_write_tokens;
+            if step_tokens > 0 {
+                let message_id = session.messages.get(assistant_index).map(|m| m.id.clone());
+                crate::usage_ledger::record_usage(
+                    session,
+                    crate::usage_ledger::UsageStep {
+                        message_id,
+                        provider_id: &provider_id,
+                        model_id: &model_id,
+                        agent: agent_name,
+                    },
+                    &step_usage,
+                    step_cost,
+                );
+            }
 
             if !stream_tool_results.is_empty() {
                 let mut tool_msg = SessionMessage::tool(session_id.clone());
@@ -1633,17 +1828,13 @@ impl SessionPrompt {
             }
 
             if !post_first_step_ran {
-                Self::ensure_title(session, provider.clone(), &model_id).await;
-                let _ = Self::summarize_session(
-                    session,
-                    &session_id,
-                    &provider_id,
-                    &model_id,
-                    provider.as_ref(),
-                )
-                .await;
+                let _ =
+                    Self::summarize_session(session, &session_id, &provider_id, &model_id).await;
                 post_first_step_ran = true;
             }
+            if pending_titles.apply_finished(session).await {
+                Self::emit_session_update(update_hook.as_ref(), session);
+            }
 
             if !matches!(
                 finish_reason.as_deref(),
@@ -1664,11 +1855,13 @@ impl SessionPrompt {
         // are set to error status with "Tool execution aborted".
         if token.is_cancelled() {
             Self::abort_pending_tool_calls(session);
+            pending_titles.abort();
         }
 
         Self::prune_after_loop(session);
         session.touch();
         Self::emit_session_update(update_hook.as_ref(), session);
+        pending_titles.detach(session, update_hook);
 
         Ok(())
     }
@@ -1977,6 +2170,35 @@ pub async fn resolve_prompt_parts(
     parts
 }
 
+/// Holds back a stream until it produces output. Providers that accept the
+/// request and then report overload or rate limiting as the first stream event
+/// surface that as an error here, while nothing has been committed to the
+/// session and another model can still take the turn.
+async fn await_first_output(mut stream: StreamResult) -> Result<StreamResult, ProviderError> {
+    let mut head = Vec::new();
+    while let Some(item) = stream.next().await {
+        match item {
+            Ok(StreamEvent::Error(message))
+                if FailoverReason::classify_message(&message).is_some() =>
+            {
+                return Err(ProviderError::StreamError(message));
+            }
+            Err(error) if FailoverReason::classify(&error).is_some() => return Err(error),
+            Ok(
+                event @ (StreamEvent::Start
+                | StreamEvent::StartStep
+                | StreamEvent::TextStart
+                | StreamEvent::ReasoningStart { .. }),
+            ) => head.push(Ok(event)),
+            other => {
+                head.push(other);
+                break;
+            }
+        }
+    }
+    Ok(Box::pin(futures::stream::iter(head).chain(stream)))
+}
+
 pub fn extract_file_references(template: &str) -> Vec<String> {
     let re = regex::Regex::new(FILE_REFERENCE_REGEX).unwrap();
     let mut seen = std::collections::HashSet::new();
@@ -2025,6 +2247,8 @@ mod tests {
                     supports_tools: false,
                     cost_per_million_input: 0.0,
                     cost_per_million_output: 0.0,
+                    supports_batch: false,
+                    supports_pdf: false,
                 }),
             }
         }
@@ -2102,6 +2326,78 @@ mod tests {
         }
     }
 
+    /// Utility model that answers title requests only after `delay`.
+    struct SlowTitleProvider {
+        model: ModelInfo,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl Provider for SlowTitleProvider {
+        fn id(&self) -> &str {
+            "utility"
+        }
+
+        fn name(&self) -> &str {
+            "Utility"
+        }
+
+        fn models(&self) -> Vec<ModelInfo> {
+            vec![self.model.clone()]
+        }
+
+        fn get_model(&self, id: &str) -> Option<&ModelInfo> {
+            (self.model.id == id).then_some(&self.model)
+        }
+
+        async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse, ProviderError> {
+            tokio::time::sleep(self.delay).await;
+            Ok(ChatResponse {
+                id: "chat_utility".to_string(),
+                model: self.model.id.clone(),
+                choices: vec![rocode_provider::Choice {
+                    index: 0,
+                    message: rocode_provider::Message::assistant("Greeting check"),
+                    finish_reason: Some("stop".to_string()),
+                }],
+                usage: None,
+            })
+        }
+
+        async fn chat_stream(&self, _request: ChatRequest) -> Result<StreamResult, ProviderError> {
+            Ok(Box::pin(stream::empty()))
+        }
+    }
+
+    struct RateLimitedProvider;
+
+    #[async_trait]
+    impl Provider for RateLimitedProvider {
+        fn id(&self) -> &str {
+            "limited"
+        }
+
+        fn name(&self) -> &str {
+            "Limited"
+        }
+
+        fn models(&self) -> Vec<ModelInfo> {
+            Vec::new()
+        }
+
+        fn get_model(&self, _id: &str) -> Option<&ModelInfo> {
+            None
+        }
+
+        async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse, ProviderError> {
+            Err(ProviderError::RateLimit)
+        }
+
+        async fn chat_stream(&self, _request: ChatRequest) -> Result<StreamResult, ProviderError> {
+            Err(ProviderError::RateLimit)
+        }
+    }
+
     struct MultiTurnScriptedProvider {
         model: ModelInfo,
         turns: Arc<StdMutex<std::collections::VecDeque<Vec<StreamEvent>>>>,
@@ -2292,6 +2588,8 @@ mod tests {
                 supports_tools: false,
                 cost_per_million_input: 0.0,
                 cost_per_million_output: 0.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             events: vec![
                 StreamEvent::Start,
@@ -2377,6 +2675,521 @@ mod tests {
         assert_eq!(final_text, "Hello");
     }
 
+    #[tokio::test]
+    async fn slow_utility_model_does_not_delay_prompt_result() {
+        let model = |id: &str, provider: &str| ModelInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            provider: provider.to_string(),
+            context_window: 8192,
+            max_input_tokens: None,
+            max_output_tokens: 1024,
+            supports_vision: false,
+            supports_tools: false,
+            cost_per_million_input: 0.0,
+            cost_per_million_output: 0.0,
+            supports_batch: false,
+            supports_pdf: false,
+        };
+        let provider = Arc::new(ScriptedStreamProvider {
+            model: model("test-model", "mock"),
+            events: vec![
+                StreamEvent::Start,
+                StreamEvent::TextDelta("Hello".to_string()),
+                StreamEvent::FinishStep {
+                    finish_reason: Some("stop".to_string()),
+                    usage: StreamUsage::default(),
+                    provider_metadata: None,
+                },
+                StreamEvent::Done,
+            ],
+        });
+        let utility_delay = Duration::from_millis(1500);
+        let utility = Arc::new(SlowTitleProvider {
+            model: model("small", "utility"),
+            delay: utility_delay,
+        });
+        let pipeline = UtilityPipeline::new(
+            Some(UtilityTarget::new(utility, "utility", "small")),
+            crate::utility::UtilitySettings {
+                timeout: Duration::from_secs(10),
+                ..Default::default()
+            },
+        );
+        let prompt = SessionPrompt::default().with_utility(Arc::new(pipeline));
+        let mut session = Session::new("proj", ".");
+
+        let snapshots = Arc::new(StdMutex::new(Vec::<Session>::new()));
+        let snapshot_sink = snapshots.clone();
+        let hook: SessionUpdateHook = Arc::new(move |snapshot| {
+            snapshot_sink
+                .lock()
+                .expect("snapshot lock should not poison")
+                .push(snapshot.clone());
+        });
+
+        let input = PromptInput {
+            session_id: session.id.clone(),
+            message_id: None,
+            model: Some(ModelRef {
+                provider_id: "mock".to_string(),
+                model_id: "test-model".to_string(),
+            }),
+            agent: None,
+            no_reply: false,
+            system: None,
+            variant: None,
+            parts: vec![PartInput::Text {
+                text: "Say hello".to_string(),
+            }],
+            tools: None,
+        };
+
+        let started = Instant::now();
+        prompt
+            .prompt_with_update_hook(
+                input,
+                &mut session,
+                provider,
+                None,
+                Vec::new(),
+                AgentParams::default(),
+                Some(hook),
+                None,
+                None,
+            )
+            .await
+            .expect("prompt should succeed");
+
+        assert!(started.elapsed() < utility_delay);
+        assert!(session.is_default_title());
+
+        // The title lands later and is reported through the update hook.
+        let late_title = async {
+            loop {
+                let titled = snapshots
+                    .lock()
+                    .expect("snapshot lock should not poison")
+                    .iter()
+                    .any(|snapshot| snapshot.title == "Greeting check");
+                if titled {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), late_title)
+            .await
+            .expect("late title should be reported");
+    }
+
+    #[tokio::test]
+    async fn prompt_prices_step_and_appends_usage_ledger_row() {
+        let db = rocode_storage::Database::in_memory()
+            .await
+            .expect("in-memory db should initialize");
+        let ledger = rocode_storage::UsageLedgerRepository::new(db.pool().clone());
+        crate::install_usage_ledger(ledger.clone());
+
+        let prompt = SessionPrompt::default();
+        let mut session = Session::new("ledger-proj", ".");
+        let provider = Arc::new(ScriptedStreamProvider {
+            model: ModelInfo {
+                id: "ledger-model".to_string(),
+                name: "Ledger Model".to_string(),
+                provider: "ledger-mock".to_string(),
+                context_window: 8192,
+                max_input_tokens: None,
+                max_output_tokens: 1024,
+                supports_vision: false,
+                supports_tools: false,
+                cost_per_million_input: 1_000.0,
+                cost_per_million_output: 2_000.0,
+                supports_batch: false,
+                supports_pdf: false,
+            },
+            events: vec![
+                StreamEvent::Start,
+                StreamEvent::TextDelta("ok".to_string()),
+                StreamEvent::FinishStep {
+                    finish_reason: Some("stop".to_string()),
+                    usage: StreamUsage {
+                        prompt_tokens: 3,
+                        completion_tokens: 2,
+                        ..Default::default()
+                    },
+                    provider_metadata: None,
+                },
+                StreamEvent::Done,
+            ],
+        });
+        let input = PromptInput {
+            session_id: session.id.clone(),
+            message_id: None,
+            model: Some(ModelRef {
+                provider_id: "ledger-mock".to_string(),
+                model_id: "ledger-model".to_string(),
+            }),
+            agent: Some("build".to_string()),
+            no_reply: false,
+            system: None,
+            variant: None,
+            parts: vec![PartInput::Text {
+                text: "hi".to_string(),
+            }],
+            tools: None,
+        };
+
+        prompt
+            .prompt_with_update_hook(
+                input,
+                &mut session,
+                provider,
+                None,
+                Vec::new(),
+                AgentParams::default(),
+                None,
+                None,
+                None,
+            )
+            .await
+            .expect("prompt should succeed");
+
+        let usage = session
+            .messages
+            .iter()
+            .rev()
+            .find(|m| matches!(m.role, MessageRole::Assistant))
+            .and_then(|m| m.usage.clone())
+            .expect("assistant usage");
+        assert!((usage.total_cost - 0.007).abs() < 1e-9);
+
+        let filter = rocode_storage::UsageFilter {
+            project_id: Some("ledger-proj".to_string()),
+            ..Default::default()
+        };
+        let mut rows = Vec::new();
+        for _ in 0..50 {
+            rows = ledger.list(&filter).await.expect("ledger list");
+            if !rows.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].session_id, session.id);
+        assert_eq!(rows[0].provider_id, "ledger-mock");
+        assert_eq!(rows[0].agent.as_deref(), Some("build"));
+        assert_eq!(rows[0].input_tokens, 3);
+        assert!((rows[0].cost - 0.007).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn prompt_fails_over_to_next_model_in_chain() {
+        let prompt = SessionPrompt::default();
+        let mut session = Session::new("proj", ".");
+        let fallback = Arc::new(ScriptedStreamProvider {
+            model: ModelInfo {
+                id: "backup-model".to_string(),
+                name: "Backup Model".to_string(),
+                provider: "mock".to_string(),
+                context_window: 8192,
+                max_input_tokens: None,
+                max_output_tokens: 1024,
+                supports_vision: false,
+                supports_tools: false,
+                cost_per_million_input: 0.0,
+                cost_per_million_output: 0.0,
+                supports_batch: false,
+                supports_pdf: false,
+            },
+            events: vec![
+                StreamEvent::TextDelta("served".to_string()),
+                StreamEvent::FinishStep {
+                    finish_reason: Some("stop".to_string()),
+                    usage: StreamUsage::default(),
+                    provider_metadata: None,
+                },
+                StreamEvent::Done,
+            ],
+        });
+        let agent_params = AgentParams {
+            fallback: vec![
+                FallbackModel {
+                    provider_id: "limited".to_string(),
+                    model_id: "second-choice".to_string(),
+                    provider: Arc::new(RateLimitedProvider),
+                },
+                FallbackModel {
+                    provider_id: "mock".to_string(),
+                    model_id: "backup-model".to_string(),
+                    provider: fallback,
+                },
+            ],
+            ..Default::default()
+        };
+        let input = PromptInput {
+            session_id: session.id.clone(),
+            message_id: None,
+            model: Some(ModelRef {
+                provider_id: "limited".to_string(),
+                model_id: "first-choice".to_string(),
+            }),
+            agent: None,
+            no_reply: false,
+            system: None,
+            variant: None,
+            parts: vec![PartInput::Text {
+                text: "hi".to_string(),
+            }],
+            tools: None,
+        };
+
+        prompt
+            .prompt(
+                input,
+                &mut session,
+                Arc::new(RateLimitedProvider),
+                None,
+                Vec::new(),
+                agent_params,
+            )
+            .await
+            .expect("fallback model should serve the prompt");
+
+        let assistant = session
+            .messages
+            .iter()
+            .rev()
+            .find(|m| matches!(m.role, MessageRole::Assistant))
+            .expect("assistant message");
+        assert_eq!(assistant.get_text(), "served");
+        assert_eq!(
+            assistant.metadata.get("model_provider"),
+            Some(&serde_json::json!("mock"))
+        );
+        assert_eq!(
+            assistant.metadata.get("model_id"),
+            Some(&serde_json::json!("backup-model"))
+        );
+        assert_eq!(
+            assistant.metadata.get("failover_from"),
+            Some(&serde_json::json!("limited/first-choice"))
+        );
+        assert_eq!(
+            assistant.metadata.get("failover_reason"),
+            Some(&serde_json::json!("rate limited"))
+        );
+    }
+
+    #[tokio::test]
+    async fn prompt_fails_over_on_overload_reported_mid_stream() {
+        let model = |id: &str| ModelInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            provider: "mock".to_string(),
+            context_window: 8192,
+            max_input_tokens: None,
+            max_output_tokens: 1024,
+            supports_vision: false,
+            supports_tools: false,
+            cost_per_million_input: 0.0,
+            cost_per_million_output: 0.0,
+            supports_batch: false,
+            supports_pdf: false,
+        };
+        let prompt = SessionPrompt::default();
+        let mut session = Session::new("proj", ".");
+        let primary = Arc::new(ScriptedStreamProvider {
+            model: model("primary-model"),
+            events: vec![
+                StreamEvent::Start,
+                StreamEvent::Error("overloaded_error: Overloaded".to_string()),
+            ],
+        });
+        let fallback = Arc::new(ScriptedStreamProvider {
+            model: model("backup-model"),
+            events: vec![
+                StreamEvent::Start,
+                StreamEvent::TextDelta("served".to_string()),
+                StreamEvent::FinishStep {
+                    finish_reason: Some("stop".to_string()),
+                    usage: StreamUsage::default(),
+                    provider_metadata: None,
+                },
+                StreamEvent::Done,
+            ],
+        });
+        let agent_params = AgentParams {
+            fallback: vec![FallbackModel {
+                provider_id: "mock".to_string(),
+                model_id: "backup-model".to_string(),
+                provider: fallback,
+            }],
+            ..Default::default()
+        };
+        let input = PromptInput {
+            session_id: session.id.clone(),
+            message_id: None,
+            model: Some(ModelRef {
+                provider_id: "mock".to_string(),
+                model_id: "primary-model".to_string(),
+            }),
+            agent: None,
+            no_reply: false,
+            system: None,
+            variant: None,
+            parts: vec![PartInput::Text {
+                text: "hi".to_string(),
+            }],
+            tools: None,
+        };
+
+        prompt
+            .prompt(input, &mut session, primary, None, Vec::new(), agent_params)
+            .await
+            .expect("fallback model should serve the prompt");
+
+        let assistants: Vec<_> = session
+            .messages
+            .iter()
+            .filter(|m| matches!(m.role, MessageRole::Assistant))
+            .collect();
+        assert_eq!(assistants.len(), 1);
+        assert_eq!(assistants[0].get_text(), "served");
+        assert_eq!(
+            assistants[0].metadata.get("failover_from"),
+            Some(&serde_json::json!("mock/primary-model"))
+        );
+        assert_eq!(
+            assistants[0].metadata.get("failover_reason"),
+            Some(&serde_json::json!("overloaded"))
+        );
+    }
+
+    #[tokio::test]
+    async fn prompt_without_fallback_surfaces_provider_error() {
+        let prompt = SessionPrompt::default();
+        let mut session = Session::new("proj", ".");
+        let input = PromptInput {
+            session_id: session.id.clone(),
+            message_id: None,
+            model: Some(ModelRef {
+                provider_id: "limited".to_string(),
+                model_id: "first-choice".to_string(),
+            }),
+            agent: None,
+            no_reply: false,
+            system: None,
+            variant: None,
+            parts: vec![PartInput::Text {
+                text: "hi".to_string(),
+            }],
+            tools: None,
+        };
+
+        let result = prompt
+            .prompt(
+                input,
+                &mut session,
+                Arc::new(RateLimitedProvider),
+                None,
+                Vec::new(),
+                AgentParams::default(),
+            )
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn chat_params_hook_overrides_tool_controls() {
+        let mut request = ChatRequest::new("m", Vec::new())
+            .with_temperature(0.7)
+            .with_tool_choice(ToolChoice::Auto);
+        let outputs = vec![
+            rocode_plugin::HookOutput::with_payload(serde_json::json!({
+                "temperature": null,
+                "toolChoice": "none",
+                "stop": ["</answer>"],
+                "parallelToolCalls": false
+            })),
+            rocode_plugin::HookOutput::with_payload(serde_json::json!({
+                "output": { "toolChoice": { "type": "tool", "name": "read" } }
+            })),
+        ];
+
+        apply_chat_params_hook_outputs(&mut request, outputs);
+
+        assert_eq!(request.temperature, Some(0.7));
+        assert_eq!(
+            request.tool_choice,
+            Some(ToolChoice::Tool("read".to_string()))
+        );
+        assert_eq!(request.stop, Some(vec!["</answer>".to_string()]));
+        assert_eq!(request.parallel_tool_calls, Some(false));
+    }
+
+    #[tokio::test]
+    async fn structured_output_accepts_valid_reply_without_another_call() {
+        let mut session = Session::new("proj", ".");
+        session.add_user_message("Who wrote it?");
+        session
+            .add_assistant_message()
+            .add_text("```json\n{\"author\": \"Ada\"}\n```");
+        let format = rocode_provider::ResponseFormat::json_schema(serde_json::json!({
+            "type": "object",
+            "properties": {"author": {"type": "string"}},
+            "required": ["author"]
+        }));
+        let provider: Arc<dyn Provider> =
+            Arc::new(StaticModelProvider::with_model("test-model", 8192, 1024));
+
+        let value = record_structured_output(
+            &mut session,
+            provider,
+            "mock",
+            "test-model",
+            None,
+            &format,
+            0,
+        )
+        .await
+        .expect("reply should validate");
+
+        assert_eq!(value, serde_json::json!({"author": "Ada"}));
+        let assistant = session.last_assistant_message().expect("assistant");
+        assert_eq!(assistant.metadata.get(STRUCTURED_OUTPUT_KEY), Some(&value));
+    }
+
+    #[tokio::test]
+    async fn structured_output_records_error_when_repair_fails() {
+        let mut session = Session::new("proj", ".");
+        session.add_user_message("Who wrote it?");
+        session.add_assistant_message().add_text("Ada wrote it.");
+        let format = rocode_provider::ResponseFormat::json_schema(serde_json::json!({
+            "type": "object",
+            "required": ["author"]
+        }));
+        let provider: Arc<dyn Provider> =
+            Arc::new(StaticModelProvider::with_model("test-model", 8192, 1024));
+
+        let result = record_structured_output(
+            &mut session,
+            provider,
+            "mock",
+            "test-model",
+            None,
+            &format,
+            1,
+        )
+        .await;
+
+        assert!(result.is_err());
+        let assistant = session.last_assistant_message().expect("assistant");
+        assert!(assistant.metadata.contains_key(STRUCTURED_OUTPUT_ERROR_KEY));
+        assert!(!assistant.metadata.contains_key(STRUCTURED_OUTPUT_KEY));
+    }
+
     #[tokio::test]
     async fn prompt_continues_after_tool_calls_without_finish_step_reason() {
         let prompt = SessionPrompt::default();
@@ -2400,6 +3213,8 @@ mod tests {
                 supports_tools: true,
                 cost_per_million_input: 0.0,
                 cost_per_million_output: 0.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             vec![
                 vec![
@@ -211,6 +211,10 @@ impl SubtaskExecutor {
                 top_p: self.agent_params.top_p,
                 variant: None,
                 provider_options: None,
+                response_format: None,
+                tool_choice: None,
+                stop: None,
+                parallel_tool_calls: None,
             };
 
             let response = provider.chat(request).await?;
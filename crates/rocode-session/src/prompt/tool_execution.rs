@@ -5,7 +5,7 @@ use std::sync::Arc;
 
 use tokio::sync::Mutex;
 
-use rocode_provider::{Provider, ToolDefinition};
+use rocode_provider::{ModelInfo, Provider, ToolDefinition};
 
 use crate::{MessageRole, PartType, Session, SessionMessage};
 
@@ -16,6 +16,18 @@ use super::{
 };
 
 impl SessionPrompt {
+    /// Input modalities advertised to tools, from the model's capabilities.
+    pub(super) fn input_modalities(model: &ModelInfo) -> Vec<&'static str> {
+        let mut modalities = vec!["text"];
+        if model.supports_vision {
+            modalities.push("image");
+        }
+        if model.supports_pdf {
+            modalities.push("pdf");
+        }
+        modalities
+    }
+
     pub async fn execute_tool_calls(
         session: &mut Session,
         tool_registry: Arc<rocode_tool::ToolRegistry>,
@@ -123,6 +135,16 @@ impl SessionPrompt {
         // Emit update so TUI shows tools in "Running" state immediately.
         Self::emit_session_update(update_hook, session);
 
+        // Lets tools such as `read` decide between attaching media and
+        // extracting text based on what the active model can ingest.
+        let mut ctx = ctx;
+        if let Some(model) = provider.get_model(model_id) {
+            ctx.extra.insert(
+                "inputModalities".to_string(),
+                serde_json::json!(Self::input_modalities(model)),
+            );
+        }
+
         let subsessions = Arc::new(Mutex::new(Self::load_persisted_subsessions(session)));
         let default_model = format!("{}:{}", provider_id, model_id);
         let ctx = Self::with_persistent_subsession_callbacks(
@@ -559,6 +581,10 @@ impl SessionPrompt {
             max_tokens: Some(2048),
             temperature: Some(0.2),
             top_p: None,
+            fallback: Vec::new(),
+            tool_choice: None,
+            stop: None,
+            parallel_tool_calls: None,
         };
 
         executor
@@ -670,6 +696,34 @@ mod tests {
         assert_eq!(loaded["task_explore_1"].history.len(), 1);
     }
 
+    #[test]
+    fn pdf_capable_models_advertise_pdf_input() {
+        let mut model = ModelInfo {
+            id: "claude".to_string(),
+            name: "Claude".to_string(),
+            provider: "anthropic".to_string(),
+            context_window: 200_000,
+            max_input_tokens: None,
+            max_output_tokens: 8192,
+            supports_vision: true,
+            supports_tools: true,
+            cost_per_million_input: 0.0,
+            cost_per_million_output: 0.0,
+            supports_batch: false,
+            supports_pdf: true,
+        };
+        assert_eq!(
+            SessionPrompt::input_modalities(&model),
+            vec!["text", "image", "pdf"]
+        );
+
+        model.supports_pdf = false;
+        assert_eq!(
+            SessionPrompt::input_modalities(&model),
+            vec!["text", "image"]
+        );
+    }
+
     #[test]
     fn parse_model_string_supports_provider_prefix() {
         let model = SessionPrompt::parse_model_string("openai:gpt-4o");
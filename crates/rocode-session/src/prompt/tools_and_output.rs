@@ -1,54 +1,82 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
-use rocode_provider::{ChatRequest, Content, Message, Provider, Role, ToolDefinition};
+use rocode_provider::{
+    generate_structured, parse_json_output, ChatRequest, Provider, ResponseFormat, ToolDefinition,
+};
 
-use crate::{MessageRole, PartType, SessionMessage};
+use crate::utility::UtilityPipeline;
+use crate::{MessageRole, PartType, Session, SessionMessage};
 
-use super::MAX_STEPS;
+use super::{SessionPrompt, MAX_STEPS};
 
 // --- Structured Output ---
 
-const STRUCTURED_OUTPUT_DESCRIPTION: &str = r#"Use this tool to return your final response in the requested structured format.
+/// Metadata key holding the validated JSON answer on the final assistant message.
+pub const STRUCTURED_OUTPUT_KEY: &str = "structured_output";
 
-IMPORTANT:
-- You MUST call this tool exactly once at the end of your response
-- The input must be valid JSON matching the required schema
-- Complete all necessary research and tool calls BEFORE calling this tool
-- This tool provides your final answer - no further actions are taken after calling it"#;
+/// Metadata key holding the reason structured output could not be produced.
+pub const STRUCTURED_OUTPUT_ERROR_KEY: &str = "structured_output_error";
 
-const STRUCTURED_OUTPUT_SYSTEM_PROMPT: &str = r#"IMPORTANT: The user has requested structured output. You MUST use the StructuredOutput tool to provide your final response. Do NOT respond with plain text - you MUST call the StructuredOutput tool with your answer formatted according to the schema."#;
-
-pub struct StructuredOutputConfig {
-    pub schema: serde_json::Value,
-}
-
-pub fn create_structured_output_tool(schema: serde_json::Value) -> ToolDefinition {
-    let mut tool_schema = schema;
-    if let Some(obj) = tool_schema.as_object_mut() {
-        obj.remove("$schema");
-    }
-
-    ToolDefinition {
-        name: "StructuredOutput".to_string(),
-        description: Some(STRUCTURED_OUTPUT_DESCRIPTION.to_string()),
-        parameters: tool_schema,
-    }
-}
+/// Turns the latest assistant reply into JSON matching `format` and records it
+/// on that message under [`STRUCTURED_OUTPUT_KEY`].
+///
+/// A reply that already validates is used as-is; otherwise the model is asked
+/// for the JSON (and to repair it) up to `retries` times. Failures are recorded
+/// under [`STRUCTURED_OUTPUT_ERROR_KEY`].
+pub async fn record_structured_output(
+    session: &mut Session,
+    provider: Arc<dyn Provider>,
+    provider_id: &str,
+    model_id: &str,
+    system_prompt: Option<&str>,
+    format: &ResponseFormat,
+    retries: u32,
+) -> anyhow::Result<serde_json::Value> {
+    let Some(index) = session
+        .messages
+        .iter()
+        .rposition(|m| matches!(m.role, MessageRole::Assistant))
+    else {
+        anyhow::bail!("session has no assistant reply to structure");
+    };
 
-pub fn structured_output_system_prompt() -> String {
-    STRUCTURED_OUTPUT_SYSTEM_PROMPT.to_string()
-}
+    let answer = session.messages[index].get_text();
+    let existing = parse_json_output(&answer)
+        .ok()
+        .filter(|value| format.validate(value).is_empty());
+    let result = match existing {
+        Some(value) => Ok(value),
+        None => {
+            let messages = SessionPrompt::chat_messages_for_model(
+                &session.messages,
+                system_prompt,
+                provider.as_ref(),
+                provider_id,
+                model_id,
+            )?;
+            let request = ChatRequest::new(model_id, messages);
+            generate_structured(provider.as_ref(), request, format, retries)
+                .await
+                .map(|output| output.value)
+                .map_err(anyhow::Error::from)
+        }
+    };
 
-pub fn extract_structured_output(parts: &[crate::MessagePart]) -> Option<serde_json::Value> {
-    for part in parts {
-        if let PartType::ToolCall { name, input, .. } = &part.part_type {
-            if name == "StructuredOutput" {
-                return Some(input.clone());
-            }
+    let metadata = &mut session.messages[index].metadata;
+    match &result {
+        Ok(value) => {
+            metadata.insert(STRUCTURED_OUTPUT_KEY.to_string(), value.clone());
+            metadata.remove(STRUCTURED_OUTPUT_ERROR_KEY);
+        }
+        Err(error) => {
+            metadata.insert(
+                STRUCTURED_OUTPUT_ERROR_KEY.to_string(),
+                serde_json::json!(error.to_string()),
+            );
         }
     }
-    None
+    result
 }
 
 // --- Plan Mode ---
@@ -221,74 +249,15 @@ pub fn generate_session_title(first_user_message: &str) -> String {
 }
 
 /// Generate a session title using an LLM (matching TS `ensureTitle`).
-/// Falls back to `generate_session_title` on any failure.
+/// `utility` decides which model runs it and whether one may run at all;
+/// falls back to `generate_session_title` on any failure.
 pub async fn generate_session_title_llm(
     first_user_message: &str,
     provider: Arc<dyn Provider>,
     model_id: &str,
+    utility: &UtilityPipeline,
 ) -> String {
-    let fallback = generate_session_title(first_user_message);
-
-    let request = ChatRequest {
-        model: model_id.to_string(),
-        messages: vec![Message {
-            role: Role::User,
-            content: Content::Text(format!(
-                "Generate a short title (under 80 chars) for this conversation. \
-                     Reply with ONLY the title, no quotes or explanation.\n\n{}",
-                first_user_message
-            )),
-            cache_control: None,
-            provider_options: None,
-        }],
-        tools: None,
-        system: Some(
-            "You generate concise conversation titles. Reply with only the title.".to_string(),
-        ),
-        max_tokens: Some(100),
-        temperature: Some(0.0),
-        top_p: None,
-        stream: None,
-        provider_options: None,
-        variant: None,
-    };
-
-    match provider.chat(request).await {
-        Ok(response) => {
-            // Extract text from the first choice
-            let text = response
-                .choices
-                .first()
-                .map(|c| match &c.message.content {
-                    Content::Text(t) => t.clone(),
-                    Content::Parts(parts) => parts
-                        .iter()
-                        .filter_map(|p| p.text.clone())
-                        .collect::<Vec<_>>()
-                        .join(""),
-                })
-                .unwrap_or_default();
-
-            // Clean up: remove thinking tags, take first non-empty line
-            let cleaned = text
-                .replace(|c: char| c == '"' || c == '\'', "")
-                .lines()
-                .map(|l| l.trim())
-                .find(|l| !l.is_empty() && !l.starts_with("<think>"))
-                .unwrap_or("")
-                .to_string();
-
-            if cleaned.is_empty() {
-                fallback
-            } else if cleaned.chars().count() > 100 {
-                format!("{}...", cleaned.chars().take(97).collect::<String>())
-            } else {
-                cleaned
-            }
-        }
-        Err(e) => {
-            tracing::warn!(error = %e, "Failed to generate title via LLM, using fallback");
-            fallback
-        }
-    }
+    utility
+        .session_title(first_user_message, Some((provider.as_ref(), model_id)))
+        .await
 }
@@ -0,0 +1,464 @@
+//! Hunk-level review of the edits made during a session's latest turn.
+//!
+//! The worktree is diffed against the snapshot taken before the most recent
+//! prompt. Rejected hunks are reverted on disk and remembered on the
+//! session so the next prompt can tell the agent what was undone.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::session::Session;
+use crate::snapshot::{Snapshot, SnapshotPatch};
+
+/// Session metadata key holding the snapshot hash edits are reviewed against.
+pub const REVIEW_SNAPSHOT_KEY: &str = "review_snapshot";
+/// Session metadata key holding rejected hunks not yet reported to the agent.
+pub const REVIEW_REJECTIONS_KEY: &str = "review_rejections";
+
+/// Maximum hunk body lines quoted back to the agent per hunk.
+const NOTE_MAX_HUNK_LINES: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewFileStatus {
+    Added,
+    Deleted,
+    Modified,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    /// The `@@ -a,b +c,d @@` line, including any trailing context.
+    pub header: String,
+    /// Body lines, each prefixed with ` `, `+`, `-` or `\`.
+    pub lines: Vec<String>,
+}
+
+impl DiffHunk {
+    /// Old- and new-side content of the hunk, with line endings.
+    fn sides(&self) -> (Vec<String>, Vec<String>) {
+        let mut old = Vec::new();
+        let mut new = Vec::new();
+        let mut last = ' ';
+        for line in &self.lines {
+            let (marker, text) = line.split_at(line.chars().next().map_or(0, char::len_utf8));
+            match marker {
+                " " => {
+                    old.push(format!("{}\n", text));
+                    new.push(format!("{}\n", text));
+                }
+                "-" => old.push(format!("{}\n", text)),
+                "+" => new.push(format!("{}\n", text)),
+                "\\" => {
+                    // "\ No newline at end of file" applies to the line above.
+                    let sides: &mut [&mut Vec<String>] = match last {
+                        '-' => &mut [&mut old],
+                        '+' => &mut [&mut new],
+                        _ => &mut [&mut old, &mut new],
+                    };
+                    for side in sides.iter_mut() {
+                        if let Some(prev) = side.last_mut() {
+                            prev.pop();
+                        }
+                    }
+                    continue;
+                }
+                _ => continue,
+            }
+            last = marker.chars().next().unwrap_or(' ');
+        }
+        (old, new)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewFile {
+    pub path: String,
+    pub status: ReviewFileStatus,
+    pub binary: bool,
+    pub additions: u64,
+    pub deletions: u64,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// Hunks of one file that the user rejected.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RejectedHunks {
+    pub path: String,
+    /// Empty for binary files, which are reverted as a whole.
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// Files changed in `directory` since `snapshot`, split into hunks.
+pub fn review_files(directory: &Path, snapshot: &str) -> Result<Vec<ReviewFile>> {
+    let patch = Snapshot::patch(directory, snapshot)?;
+    Ok(parse_unified_diff(&patch))
+}
+
+/// Reverts the hunks at `indices` of `path` to their snapshot content.
+///
+/// Hunks are resolved against a fresh diff, and the revert is refused if the
+/// file no longer matches what the hunks describe.
+pub fn reject_hunks(
+    directory: &Path,
+    snapshot: &str,
+    path: &str,
+    indices: &[usize],
+) -> Result<RejectedHunks> {
+    let file = review_files(directory, snapshot)?
+        .into_iter()
+        .find(|file| file.path == path)
+        .with_context(|| format!("{} has no pending changes", path))?;
+    let absolute = directory.join(path);
+
+    if file.binary {
+        Snapshot::revert(
+            directory,
+            vec![SnapshotPatch {
+                hash: snapshot.to_string(),
+                files: vec![path.to_string()],
+            }],
+        )?;
+        return Ok(RejectedHunks {
+            path: path.to_string(),
+            hunks: Vec::new(),
+        });
+    }
+
+    let mut selected = Vec::new();
+    for &index in indices {
+        let hunk = file
+            .hunks
+            .get(index)
+            .with_context(|| format!("{} has no hunk {}", path, index))?;
+        if !selected.contains(&hunk) {
+            selected.push(hunk);
+        }
+    }
+    if selected.is_empty() {
+        bail!("no hunks selected for {}", path);
+    }
+
+    if file.status == ReviewFileStatus::Added && selected.len() == file.hunks.len() {
+        fs::remove_file(&absolute)
+            .with_context(|| format!("failed to remove {}", absolute.display()))?;
+    } else {
+        let current = match file.status {
+            ReviewFileStatus::Deleted => String::new(),
+            _ => fs::read_to_string(&absolute)
+                .with_context(|| format!("failed to read {}", absolute.display()))?,
+        };
+        let reverted = revert_hunks(&current, &selected)
+            .with_context(|| format!("{} changed since the review was loaded", path))?;
+        if let Some(parent) = absolute.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&absolute, reverted)
+            .with_context(|| format!("failed to write {}", absolute.display()))?;
+    }
+
+    Ok(RejectedHunks {
+        path: path.to_string(),
+        hunks: selected.into_iter().cloned().collect(),
+    })
+}
+
+/// Replaces the new side of each hunk in `current` with its old side.
+pub fn revert_hunks(current: &str, hunks: &[&DiffHunk]) -> Result<String> {
+    let mut lines: Vec<String> = current.split_inclusive('\n').map(str::to_string).collect();
+    let mut ordered = hunks.to_vec();
+    ordered.sort_by_key(|hunk| std::cmp::Reverse(hunk.new_start));
+
+    for hunk in ordered {
+        let (old, new) = hunk.sides();
+        // A zero-length range starts *after* line `new_start`.
+        let start = if hunk.new_lines == 0 {
+            hunk.new_start
+        } else {
+            hunk.new_start.saturating_sub(1)
+        };
+        let end = start + new.len();
+        if end > lines.len() || lines[start..end] != new[..] {
+            bail!("hunk {} does not match the current content", hunk.header);
+        }
+        lines.splice(start..end, old);
+    }
+    Ok(lines.concat())
+}
+
+/// Parses `git diff` output into per-file hunks.
+pub fn parse_unified_diff(patch: &str) -> Vec<ReviewFile> {
+    let mut files: Vec<ReviewFile> = Vec::new();
+    let mut in_hunk = false;
+
+    // Split on '\n' only: a CRLF file's hunk bodies keep their '\r' so they
+    // compare equal to the file's lines in `revert_hunks`.
+    for raw in patch.split_inclusive('\n') {
+        let raw = raw.strip_suffix('\n').unwrap_or(raw);
+        let line = raw.strip_suffix('\r').unwrap_or(raw);
+        if let Some(rest) = line.strip_prefix("diff --git a/") {
+            // Renames are disabled, so both sides name the same path.
+            let path = rest.get(..rest.len().saturating_sub(3) / 2).unwrap_or(rest);
+            files.push(ReviewFile {
+                path: path.to_string(),
+                status: ReviewFileStatus::Modified,
+                binary: false,
+                additions: 0,
+                deletions: 0,
+                hunks: Vec::new(),
+            });
+            in_hunk = false;
+            continue;
+        }
+        let Some(file) = files.last_mut() else {
+            continue;
+        };
+
+        if let Some(header) = line.strip_prefix("@@ ") {
+            if let Some(hunk) = parse_hunk_header(line, header) {
+                file.hunks.push(hunk);
+                in_hunk = true;
+            }
+            continue;
+        }
+        if in_hunk {
+            if let Some(hunk) = file.hunks.last_mut() {
+                match line.chars().next() {
+                    Some('+') => file.additions += 1,
+                    Some('-') => file.deletions += 1,
+                    Some(' ') | Some('\\') => {}
+                    // git never emits empty context lines, but editors may strip them.
+                    None => {
+                        hunk.lines.push(format!(" {}", raw));
+                        continue;
+                    }
+                    Some(_) => {
+                        in_hunk = false;
+                        continue;
+                    }
+                }
+                hunk.lines.push(raw.to_string());
+            }
+            continue;
+        }
+
+        if line.starts_with("new file mode") {
+            file.status = ReviewFileStatus::Added;
+        } else if line.starts_with("deleted file mode") {
+            file.status = ReviewFileStatus::Deleted;
+        } else if line.starts_with("Binary files ") {
+            file.binary = true;
+        } else if let Some(path) = line.strip_prefix("+++ b/") {
+            file.path = path.to_string();
+        }
+    }
+    files
+}
+
+/// `@@ -a,b +c,d @@ context` -> hunk with an empty body.
+fn parse_hunk_header(line: &str, header: &str) -> Option<DiffHunk> {
+    let mut parts = header.split_whitespace();
+    let (old_start, old_lines) = parse_range(parts.next()?.strip_prefix('-')?)?;
+    let (new_start, new_lines) = parse_range(parts.next()?.strip_prefix('+')?)?;
+    Some(DiffHunk {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        header: line.to_string(),
+        lines: Vec::new(),
+    })
+}
+
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+/// Appends rejections to the session so the next prompt can report them.
+pub fn record_rejections(session: &mut Session, rejected: Vec<RejectedHunks>) {
+    let mut pending: Vec<RejectedHunks> = session
+        .metadata
+        .get(REVIEW_REJECTIONS_KEY)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or_default();
+    pending.extend(rejected);
+    if let Ok(value) = serde_json::to_value(pending) {
+        session
+            .metadata
+            .insert(REVIEW_REJECTIONS_KEY.to_string(), value);
+    }
+}
+
+/// Removes pending rejections from the session and renders them as a note
+/// for the agent.
+pub fn take_rejection_note(session: &mut Session) -> Option<String> {
+    let pending: Vec<RejectedHunks> =
+        serde_json::from_value(session.metadata.remove(REVIEW_REJECTIONS_KEY)?).ok()?;
+    rejection_note(&pending)
+}
+
+pub fn rejection_note(rejected: &[RejectedHunks]) -> Option<String> {
+    if rejected.is_empty() {
+        return None;
+    }
+    let mut note = String::from(
+        "The user reviewed your earlier edits and rejected the changes below. \
+         They have been reverted on disk; do not re-apply them unless asked.\n",
+    );
+    for file in rejected {
+        if file.hunks.is_empty() {
+            note.push_str(&format!("\n{}: all changes reverted\n", file.path));
+            continue;
+        }
+        for hunk in &file.hunks {
+            note.push_str(&format!("\n{} {}\n```diff\n", file.path, hunk.header));
+            for line in hunk.lines.iter().take(NOTE_MAX_HUNK_LINES) {
+                note.push_str(line);
+                note.push('\n');
+            }
+            if hunk.lines.len() > NOTE_MAX_HUNK_LINES {
+                note.push_str(&format!(
+                    "... {} more lines\n",
+                    hunk.lines.len() - NOTE_MAX_HUNK_LINES
+                ));
+            }
+            note.push_str("```\n");
+        }
+    }
+    Some(note)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const PATCH: &str = "diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@
+ one
+-two
++TWO
+ three
+@@ -8,2 +8,3 @@ fn tail() {
+ eight
+ nine
++ten
+diff --git a/new.txt b/new.txt
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1 @@
++hello
+\\ No newline at end of file
+diff --git a/logo.png b/logo.png
+index 4444444..5555555 100644
+Binary files a/logo.png and b/logo.png differ
+";
+
+    #[test]
+    fn parses_files_and_hunks() {
+        let files = parse_unified_diff(PATCH);
+        assert_eq!(files.len(), 3);
+
+        assert_eq!(files[0].path, "src/lib.rs");
+        assert_eq!(files[0].status, ReviewFileStatus::Modified);
+        assert_eq!((files[0].additions, files[0].deletions), (2, 1));
+        assert_eq!(files[0].hunks.len(), 2);
+        assert_eq!(files[0].hunks[1].new_start, 8);
+        assert_eq!(files[0].hunks[1].new_lines, 3);
+
+        assert_eq!(files[1].status, ReviewFileStatus::Added);
+        assert_eq!(files[1].hunks[0].new_lines, 1);
+        assert_eq!(files[2].path, "logo.png");
+        assert!(files[2].binary);
+    }
+
+    #[test]
+    fn reverts_only_selected_hunks() {
+        let files = parse_unified_diff(PATCH);
+        let current = "one\nTWO\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\n";
+
+        let second = revert_hunks(current, &[&files[0].hunks[1]]).unwrap();
+        assert_eq!(
+            second,
+            "one\nTWO\nthree\nfour\nfive\nsix\nseven\neight\nnine\n"
+        );
+        let both = revert_hunks(current, &[&files[0].hunks[0], &files[0].hunks[1]]).unwrap();
+        assert_eq!(
+            both,
+            "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\n"
+        );
+
+        assert!(revert_hunks("unrelated\n", &[&files[0].hunks[0]]).is_err());
+    }
+
+    #[test]
+    fn reverts_hunks_in_crlf_files() {
+        let patch = concat!(
+            "diff --git a/win.txt b/win.txt\n",
+            "index 1111111..2222222 100644\n",
+            "--- a/win.txt\n",
+            "+++ b/win.txt\n",
+            "@@ -1,3 +1,3 @@\n",
+            " one\r\n",
+            "-two\r\n",
+            "+TWO\r\n",
+            " three\r\n",
+        );
+        let files = parse_unified_diff(patch);
+        assert_eq!(files[0].path, "win.txt");
+        assert_eq!((files[0].additions, files[0].deletions), (1, 1));
+
+        let reverted = revert_hunks("one\r\nTWO\r\nthree\r\n", &[&files[0].hunks[0]]).unwrap();
+        assert_eq!(reverted, "one\r\ntwo\r\nthree\r\n");
+    }
+
+    #[test]
+    fn rejects_hunks_against_snapshot() {
+        let temp = tempdir().expect("temp dir");
+        let root = temp.path();
+        let original: String = (1..=12).map(|i| format!("line {}\n", i)).collect();
+        fs::write(root.join("file.txt"), &original).unwrap();
+        let snapshot = Snapshot::track(root).expect("track snapshot");
+
+        let edited = original
+            .replace("line 2\n", "line two\n")
+            .replace("line 11\n", "line eleven\n");
+        fs::write(root.join("file.txt"), &edited).unwrap();
+        fs::write(root.join("added.txt"), "new\n").unwrap();
+
+        let files = review_files(root, &snapshot).unwrap();
+        let file = files.iter().find(|f| f.path == "file.txt").unwrap();
+        assert_eq!(file.hunks.len(), 2);
+
+        let rejected = reject_hunks(root, &snapshot, "file.txt", &[1]).unwrap();
+        assert_eq!(rejected.hunks.len(), 1);
+        assert_eq!(
+            fs::read_to_string(root.join("file.txt")).unwrap(),
+            original.replace("line 2\n", "line two\n")
+        );
+
+        reject_hunks(root, &snapshot, "added.txt", &[0]).unwrap();
+        assert!(!root.join("added.txt").exists());
+
+        let mut session = Session::new("project", root.display().to_string());
+        record_rejections(&mut session, vec![rejected]);
+        let note = take_rejection_note(&mut session).unwrap();
+        assert!(note.contains("file.txt @@"));
+        assert!(note.contains("+line eleven"));
+        assert!(take_rejection_note(&mut session).is_none());
+    }
+}
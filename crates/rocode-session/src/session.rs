@@ -114,6 +114,15 @@ pub struct SessionUsage {
     pub total_cost: f64,
 }
 
+impl SessionUsage {
+    /// Share of prompt tokens served from the provider cache, `None` until
+    /// the session has sent a prompt.
+    pub fn cache_hit_ratio(&self) -> Option<f64> {
+        let prompt = self.input_tokens + self.cache_read_tokens + self.cache_write_tokens;
+        (prompt > 0).then(|| self.cache_read_tokens as f64 / prompt as f64)
+    }
+}
+
 // ============================================================================
 // Session Event Types
 // ============================================================================
@@ -935,6 +944,7 @@ impl SessionManager {
             session.set_archived(time);
             session.clone()
         };
+        crate::prompt::cache_layout::forget(session_id);
         self.events.push(SessionEvent::Updated {
             info: updated.clone(),
         });
@@ -1080,6 +1090,7 @@ impl SessionManager {
         }
 
         let session = self.sessions.remove(id)?;
+        crate::prompt::cache_layout::forget(id);
         self.events.push(SessionEvent::Deleted {
             info: session.clone(),
         });
@@ -1401,6 +1412,7 @@ mod tests {
         assert_eq!(usage.input_tokens, 0);
         assert_eq!(usage.output_tokens, 0);
         assert_eq!(usage.total_cost, 0.0);
+        assert_eq!(usage.cache_hit_ratio(), None);
     }
 
     #[test]
@@ -1447,6 +1459,8 @@ mod tests {
         assert_eq!(usage.cache_write_tokens, 60);
         assert_eq!(usage.cache_read_tokens, 90);
         assert!((usage.total_cost - 0.015).abs() < f64::EPSILON);
+        // 90 cache reads out of 300 + 90 + 60 prompt tokens.
+        assert!((usage.cache_hit_ratio().unwrap() - 0.2).abs() < 1e-9);
     }
 
     #[test]
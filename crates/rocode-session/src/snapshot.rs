@@ -126,6 +126,30 @@ impl Snapshot {
         Ok(diffs)
     }
 
+    /// Unified diff (3 lines of context) of the worktree against `from_hash`.
+    pub fn patch(directory: &Path, from_hash: &str) -> Result<String> {
+        let git_dir = ensure_snapshot_repo(directory)?;
+        git_add_all(directory, &git_dir)?;
+        let output = git_output(
+            directory,
+            &git_dir,
+            &[
+                "-c",
+                "core.autocrlf=false",
+                "-c",
+                "core.quotepath=false",
+                "diff",
+                "--no-ext-diff",
+                "--no-renames",
+                "--unified=3",
+                from_hash,
+                "--",
+                ".",
+            ],
+        )?;
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
     /// Compute diff between two git refs (matching TS `Snapshot.diffFull`).
     ///
     /// Runs `git diff --numstat --no-renames <from> <to> -- .` to get per-file
@@ -1,11 +1,12 @@
 use serde::{Deserialize, Serialize};
 
 use rocode_core::bus::Bus;
-use rocode_provider::{ChatRequest, Content, Message, Provider, Role};
+use rocode_provider::Provider;
 
 use crate::message_v2::{MessageInfo, MessageWithParts, Part, StepFinishPart, StepStartPart};
 use crate::session::{FileDiff as SessionFileDiff, Session, SessionSummary as SessionSummaryInfo};
 use crate::snapshot::Snapshot;
+use crate::utility::UtilityPipeline;
 use crate::{MessageRole, PartType};
 
 // ============================================================================
@@ -39,7 +40,7 @@ pub struct TitleGenerationResponse {
 }
 
 const SESSION_DIFF_STORAGE_KEY_PREFIX: &str = "session_diff:";
-const MESSAGE_SUMMARY_TITLE_KEY: &str = "summary_title";
+pub(crate) const MESSAGE_SUMMARY_TITLE_KEY: &str = "summary_title";
 const MESSAGE_SUMMARY_DIFFS_KEY: &str = "summary_diffs";
 
 // ============================================================================
@@ -283,7 +284,7 @@ pub fn set_session_summary(session: &mut Session, summary: &SessionSummaryData)
     });
 }
 
-fn first_user_text_for_message(session: &Session, message_id: &str) -> Option<String> {
+pub(crate) fn first_user_text_for_message(session: &Session, message_id: &str) -> Option<String> {
     let message = session.get_message(message_id)?;
     if !matches!(message.role, MessageRole::User) {
         return None;
@@ -295,74 +296,59 @@ fn first_user_text_for_message(session: &Session, message_id: &str) -> Option<St
     })
 }
 
-fn fallback_message_title(text: &str) -> String {
-    generate_title_from_messages(&[text.to_string()])
-}
-
-async fn generate_message_title_llm(
-    text: &str,
-    provider: &dyn Provider,
-    model_id: &str,
-) -> Option<String> {
-    if text.trim().is_empty() {
-        return None;
+/// Record a generated title on a user message, replacing the heuristic one
+/// written by `summarize_message_for_session`.
+pub(crate) fn set_message_summary_title(session: &mut Session, message_id: &str, title: &str) {
+    if title.trim().is_empty() {
+        return;
     }
+    if let Some(mut message) = session.get_message(message_id).cloned() {
+        message.metadata.insert(
+            MESSAGE_SUMMARY_TITLE_KEY.to_string(),
+            serde_json::json!(title),
+        );
+        let _ = session.update_message(message);
+    }
+}
 
-    let fallback = fallback_message_title(text);
-    let request = ChatRequest {
-        model: model_id.to_string(),
-        messages: vec![Message {
-            role: Role::User,
-            content: Content::Text(format!(
-                "Generate a concise title (under 80 chars) for this user request.\n\n{}",
-                text
-            )),
-            cache_control: None,
-            provider_options: None,
-        }],
-        max_tokens: Some(64),
-        temperature: Some(0.0),
-        top_p: None,
-        system: Some(
-            "You generate short request titles. Reply with only the title text.".to_string(),
-        ),
-        tools: None,
-        stream: Some(false),
-        provider_options: None,
-        variant: None,
-    };
-
-    let response = provider.chat(request).await.ok()?;
-    let raw = response
-        .choices
-        .first()
-        .map(|choice| match &choice.message.content {
-            Content::Text(text) => text.clone(),
-            Content::Parts(parts) => parts
-                .iter()
-                .filter_map(|part| part.text.clone())
-                .collect::<Vec<_>>()
-                .join(" "),
-        })?;
-
-    let cleaned = raw
-        .replace(|c: char| c == '"' || c == '\'', "")
-        .lines()
-        .map(str::trim)
-        .find(|line| !line.is_empty() && !line.starts_with("<think>"))
-        .unwrap_or("")
-        .to_string();
-
-    if cleaned.is_empty() {
-        Some(fallback)
-    } else if cleaned.len() > 100 {
-        Some(format!("{}...", &cleaned[..97]))
-    } else {
-        Some(cleaned)
+/// Copy titles generated after a prompt finished from `snapshot` into
+/// `session`, leaving everything else alone. A session title is only taken
+/// while `session` still has its default title. Returns whether anything
+/// changed.
+pub fn merge_generated_titles(session: &mut Session, snapshot: &Session) -> bool {
+    let mut changed = false;
+    if session.is_default_title() && !snapshot.is_default_title() {
+        session.set_title(snapshot.title.clone());
+        changed = true;
+    }
+    for message in &snapshot.messages {
+        let Some(title) = message
+            .metadata
+            .get(MESSAGE_SUMMARY_TITLE_KEY)
+            .and_then(|value| value.as_str())
+        else {
+            continue;
+        };
+        let Some(current) = session.get_message(&message.id) else {
+            continue;
+        };
+        let unchanged = current
+            .metadata
+            .get(MESSAGE_SUMMARY_TITLE_KEY)
+            .and_then(|value| value.as_str())
+            == Some(title);
+        if !unchanged {
+            set_message_summary_title(session, &message.id, title);
+            changed = true;
+        }
     }
+    changed
 }
 
 /// Summarize a specific user message and persist per-message summary metadata.
+///
+/// A missing title is generated through `utility`, on its own model or on
+/// `provider`/`model_id`. Without a pipeline the heuristic title is written.
 pub async fn summarize_message_for_session(
     session: &mut Session,
     message_id: &str,
@@ -370,6 +356,7 @@ pub async fn summarize_message_for_session(
     worktree: &std::path::Path,
     provider: Option<&dyn Provider>,
     model_id: Option<&str>,
+    utility: Option<&UtilityPipeline>,
 ) -> anyhow::Result<()> {
     if message_id.is_empty() {
         return Ok(());
@@ -395,12 +382,9 @@ pub async fn summarize_message_for_session(
 
             if !has_title {
                 if let Some(text) = first_user_text_for_message(session, message_id) {
-                    let generated = if let (Some(provider), Some(model_id)) = (provider, model_id) {
-                        generate_message_title_llm(&text, provider, model_id)
-                            .await
-                            .or_else(|| Some(fallback_message_title(&text)))
-                    } else {
-                        Some(fallback_message_title(&text))
+                    let generated = match utility {
+                        Some(utility) => utility.message_title(&text, provider.zip(model_id)).await,
+                        None => Some(generate_title_from_messages(&[text])),
                     };
 
                     if let Some(title) = generated.filter(|value| !value.trim().is_empty()) {
@@ -422,6 +406,7 @@ pub async fn summarize_message_for_session(
 }
 
 /// Run full session + message summarization and persist all outputs.
+#[allow(clippy::too_many_arguments)]
 pub async fn summarize_into_session(
     input: &SummarizeInput,
     session: &mut Session,
@@ -429,6 +414,7 @@ pub async fn summarize_into_session(
     worktree: &std::path::Path,
     provider: Option<&dyn Provider>,
     model_id: Option<&str>,
+    utility: Option<&UtilityPipeline>,
     bus: Option<&Bus>,
 ) -> anyhow::Result<SessionSummaryData> {
     let summary = summarize(input, messages, worktree, bus).await;
@@ -441,6 +427,7 @@ pub async fn summarize_into_session(
         worktree,
         provider,
         model_id,
+        utility,
     )
     .await?;
     Ok(summary)
@@ -560,9 +547,12 @@ impl SessionSummary {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utility::UtilitySettings;
     use async_trait::async_trait;
     use futures::stream;
-    use rocode_provider::{ChatResponse, Choice, ModelInfo, ProviderError, StreamResult, Usage};
+    use rocode_provider::{
+        ChatRequest, ChatResponse, Choice, Message, ModelInfo, ProviderError, StreamResult, Usage,
+    };
 
     struct MockProvider {
         model: ModelInfo,
@@ -784,6 +774,8 @@ mod tests {
                 supports_tools: false,
                 cost_per_million_input: 0.0,
                 cost_per_million_output: 0.0,
+                supports_batch: false,
+                supports_pdf: false,
             },
             title: "Summary Pipeline".to_string(),
         };
@@ -795,6 +787,7 @@ mod tests {
             std::path::Path::new("."),
             Some(&provider),
             Some("mock-model"),
+            Some(&UtilityPipeline::new(None, UtilitySettings::default())),
         )
         .await
         .expect("summarize_message_for_session should work");
@@ -811,4 +804,61 @@ mod tests {
         );
         assert!(updated.metadata.contains_key("summary_diffs"));
     }
+
+    #[test]
+    fn merge_generated_titles_keeps_later_changes() {
+        let mut snapshot = Session::new("proj", ".");
+        let message_id = snapshot.add_user_message("rename the loader").id.clone();
+        let mut live = snapshot.clone();
+        live.add_assistant_message().add_text("structured output");
+
+        snapshot.set_title("Rename loader");
+        set_message_summary_title(&mut snapshot, &message_id, "Rename the config loader");
+
+        assert!(merge_generated_titles(&mut live, &snapshot));
+        assert_eq!(live.title, "Rename loader");
+        assert_eq!(live.messages.len(), 2);
+        assert_eq!(
+            live.get_message(&message_id)
+                .and_then(|message| message.metadata.get(MESSAGE_SUMMARY_TITLE_KEY))
+                .and_then(|value| value.as_str()),
+            Some("Rename the config loader")
+        );
+        assert!(!merge_generated_titles(&mut live, &snapshot));
+
+        live.set_title("Renamed by hand");
+        snapshot.set_title("Model title");
+        assert!(!merge_generated_titles(&mut live, &snapshot));
+        assert_eq!(live.title, "Renamed by hand");
+    }
+
+    #[tokio::test]
+    async fn test_summarize_message_for_session_without_pipeline_uses_heuristic() {
+        let mut session = Session::new("proj", ".");
+        let message = session.add_user_message("Implement summary pipeline for session diffs");
+        let message_id = message.id.clone();
+
+        summarize_message_for_session(
+            &mut session,
+            &message_id,
+            &[],
+            std::path::Path::new("."),
+            None,
+            None,
+            None,
+        )
+        .await
+        .expect("summarize_message_for_session should work");
+
+        let updated = session
+            .get_message(&message_id)
+            .expect("message should still exist");
+        assert_eq!(
+            updated
+                .metadata
+                .get("summary_title")
+                .and_then(|value| value.as_str()),
+            Some("Implement summary pipeline for session diffs")
+        );
+    }
 }
@@ -0,0 +1,56 @@
+//! Feeds the append-only usage ledger from the prompt loop.
+//!
+//! The server and CLI install a ledger once their database is open; until then
+//! (and in tests) steps are priced but not recorded.
+
+use std::sync::RwLock;
+
+use rocode_provider::StreamUsage;
+use rocode_storage::{UsageLedgerRepository, UsageRecord};
+
+use crate::Session;
+
+static USAGE_LEDGER: RwLock<Option<UsageLedgerRepository>> = RwLock::new(None);
+
+pub fn install_usage_ledger(ledger: UsageLedgerRepository) {
+    if let Ok(mut guard) = USAGE_LEDGER.write() {
+        *guard = Some(ledger);
+    }
+}
+
+/// Identifies the model step a usage record belongs to.
+pub(crate) struct UsageStep<'a> {
+    pub message_id: Option<String>,
+    pub provider_id: &'a str,
+    pub model_id: &'a str,
+    pub agent: Option<&'a str>,
+}
+
+/// Appends one ledger row in the background; failures are logged, never
+/// surfaced to the prompt loop.
+pub(crate) fn record_usage(session: &Session, step: UsageStep<'_>, usage: &StreamUsage, cost: f64) {
+    let Some(ledger) = USAGE_LEDGER.read().ok().and_then(|guard| guard.clone()) else {
+        return;
+    };
+    let record = UsageRecord {
+        created_at: chrono::Utc::now().timestamp_millis(),
+        session_id: session.id.clone(),
+        message_id: step.message_id,
+        project_id: session.project_id.clone(),
+        directory: session.directory.clone(),
+        provider_id: step.provider_id.to_string(),
+        model_id: step.model_id.to_string(),
+        agent: step.agent.map(str::to_string),
+        input_tokens: usage.prompt_tokens,
+        output_tokens: usage.completion_tokens,
+        reasoning_tokens: usage.reasoning_tokens,
+        cache_read_tokens: usage.cache_read_tokens,
+        cache_write_tokens: usage.cache_write_tokens,
+        cost,
+    };
+    tokio::spawn(async move {
+        if let Err(error) = ledger.append(&record).await {
+            tracing::warn!(%error, session_id = %record.session_id, "failed to append usage ledger row");
+        }
+    });
+}
@@ -0,0 +1,644 @@
+//! Utility model pipeline.
+//!
+//! Titles, per-message summary titles, commit messages and (opt-in)
+//! compaction are cheap side jobs that should not compete with the session
+//! model or hold up the first response. They run through a
+//! `UtilityPipeline`, which does the following:
+//! - targets its own `provider/model` (typically a small or local model),
+//!   falling back to the session model;
+//! - bounds in-flight calls with its own queue;
+//! - applies a per-call timeout that includes time spent queued;
+//! - caches results by task, model and input.
+//!
+//! Every text job has a local heuristic fallback, so a slow, failing or
+//! disabled utility model only ever costs title quality.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use rocode_provider::{ChatRequest, Content, Message, Provider};
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+
+use crate::prompt::generate_session_title;
+use crate::summary::generate_title_from_messages;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(20);
+const DEFAULT_CONCURRENCY: usize = 2;
+const CACHE_CAPACITY: usize = 256;
+const TITLE_MAX_CHARS: usize = 100;
+const COMMIT_SUBJECT_MAX_CHARS: usize = 72;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UtilityTask {
+    /// Session title, generated from the first user message.
+    Title,
+    /// Per-message summary title shown in the session timeline.
+    Summary,
+    /// Commit subject for changes made by an automated run.
+    CommitMessage,
+    /// Context compaction summary.
+    Compaction,
+}
+
+impl UtilityTask {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "title" => Some(Self::Title),
+            "summary" => Some(Self::Summary),
+            "commit" | "commit_message" => Some(Self::CommitMessage),
+            "compaction" => Some(Self::Compaction),
+            _ => None,
+        }
+    }
+
+    fn default_enabled(self) -> bool {
+        !matches!(self, Self::Compaction)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UtilitySettings {
+    /// No conversation text is sent to any utility call; heuristics only.
+    pub disabled: bool,
+    pub timeout: Duration,
+    pub concurrency: usize,
+    pub cache: bool,
+    /// Explicit per-task switches; unlisted tasks use their default.
+    pub tasks: HashMap<UtilityTask, bool>,
+}
+
+impl Default for UtilitySettings {
+    fn default() -> Self {
+        Self {
+            disabled: false,
+            timeout: DEFAULT_TIMEOUT,
+            concurrency: DEFAULT_CONCURRENCY,
+            cache: true,
+            tasks: HashMap::new(),
+        }
+    }
+}
+
+impl UtilitySettings {
+    pub fn task_enabled(&self, task: UtilityTask) -> bool {
+        !self.disabled
+            && self
+                .tasks
+                .get(&task)
+                .copied()
+                .unwrap_or_else(|| task.default_enabled())
+    }
+}
+
+/// The session's own provider and model, used when no utility model is set.
+pub type SessionModel<'a> = (&'a dyn Provider, &'a str);
+
+/// A resolved `provider/model` pair the pipeline can call.
+#[derive(Clone)]
+pub struct UtilityTarget {
+    pub provider: Arc<dyn Provider>,
+    pub provider_id: String,
+    pub model_id: String,
+}
+
+impl UtilityTarget {
+    pub fn new(
+        provider: Arc<dyn Provider>,
+        provider_id: impl Into<String>,
+        model_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            provider,
+            provider_id: provider_id.into(),
+            model_id: model_id.into(),
+        }
+    }
+
+    pub fn as_model(&self) -> SessionModel<'_> {
+        (self.provider.as_ref(), self.model_id.as_str())
+    }
+
+    fn context_window(&self) -> Option<u64> {
+        self.provider
+            .get_model(&self.model_id)
+            .map(|model| model.context_window)
+    }
+}
+
+#[derive(Default)]
+struct ResultCache {
+    entries: HashMap<u64, String>,
+    order: VecDeque<u64>,
+}
+
+impl ResultCache {
+    fn get(&self, key: u64) -> Option<String> {
+        self.entries.get(&key).cloned()
+    }
+
+    fn insert(&mut self, key: u64, value: String) {
+        if self.entries.insert(key, value).is_some() {
+            return;
+        }
+        self.order.push_back(key);
+        while self.order.len() > CACHE_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+}
+
+pub struct UtilityPipeline {
+    target: Option<UtilityTarget>,
+    settings: UtilitySettings,
+    queue: Semaphore,
+    cache: Mutex<ResultCache>,
+}
+
+impl Default for UtilityPipeline {
+    fn default() -> Self {
+        Self::new(None, UtilitySettings::default())
+    }
+}
+
+impl UtilityPipeline {
+    /// Without a `target`, jobs run on the session model passed to each call.
+    pub fn new(target: Option<UtilityTarget>, settings: UtilitySettings) -> Self {
+        let permits = settings.concurrency.max(1);
+        Self {
+            target,
+            settings,
+            queue: Semaphore::new(permits),
+            cache: Mutex::new(ResultCache::default()),
+        }
+    }
+
+    /// A pipeline that never calls a model, for callers that have no
+    /// configured pipeline and so cannot know whether the project allows it.
+    pub fn disabled() -> Self {
+        Self::new(
+            None,
+            UtilitySettings {
+                disabled: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    pub fn settings(&self) -> &UtilitySettings {
+        &self.settings
+    }
+
+    /// Whether this pipeline was built from the same target and settings, so
+    /// callers can keep it (and its queue and cache) across requests.
+    pub fn matches(&self, target: Option<&UtilityTarget>, settings: &UtilitySettings) -> bool {
+        let same_target = match (&self.target, target) {
+            (None, None) => true,
+            (Some(a), Some(b)) => {
+                Arc::ptr_eq(&a.provider, &b.provider)
+                    && a.provider_id == b.provider_id
+                    && a.model_id == b.model_id
+            }
+            _ => false,
+        };
+        same_target && self.settings == *settings
+    }
+
+    /// The model a text job should run on, or `None` when the task is
+    /// switched off and the caller should use its heuristic.
+    pub fn target_for<'a>(
+        &'a self,
+        task: UtilityTask,
+        session: Option<SessionModel<'a>>,
+    ) -> Option<SessionModel<'a>> {
+        if !self.settings.task_enabled(task) {
+            return None;
+        }
+        match &self.target {
+            Some(target) => Some((target.provider.as_ref(), target.model_id.as_str())),
+            None => session,
+        }
+    }
+
+    /// The utility model, when compaction is enabled for it and its context
+    /// window is at least as large as the session model's.
+    pub fn compaction_target(&self, session: &UtilityTarget) -> Option<&UtilityTarget> {
+        if !self.settings.task_enabled(UtilityTask::Compaction) {
+            return None;
+        }
+        let target = self.target.as_ref()?;
+        let required = session.context_window()?;
+        (target.context_window()? >= required).then_some(target)
+    }
+
+    pub async fn session_title(
+        &self,
+        first_user_message: &str,
+        session: Option<SessionModel<'_>>,
+    ) -> String {
+        let fallback = generate_session_title(first_user_message);
+        let Some(target) = self.target_for(UtilityTask::Title, session) else {
+            return fallback;
+        };
+        let prompt = format!(
+            "Generate a short title (under 80 chars) for this conversation. \
+             Reply with ONLY the title, no quotes or explanation.\n\n{}",
+            first_user_message
+        );
+        self.complete(
+            UtilityTask::Title,
+            target,
+            "You generate concise conversation titles. Reply with only the title.",
+            &prompt,
+            100,
+        )
+        .await
+        .and_then(|raw| clean_line(&raw, TITLE_MAX_CHARS))
+        .unwrap_or(fallback)
+    }
+
+    pub async fn message_title(
+        &self,
+        text: &str,
+        session: Option<SessionModel<'_>>,
+    ) -> Option<String> {
+        if text.trim().is_empty() {
+            return None;
+        }
+        let fallback = generate_title_from_messages(&[text.to_string()]);
+        let Some(target) = self.target_for(UtilityTask::Summary, session) else {
+            return Some(fallback);
+        };
+        let prompt = format!(
+            "Generate a concise title (under 80 chars) for this user request.\n\n{}",
+            text
+        );
+        let title = self
+            .complete(
+                UtilityTask::Summary,
+                target,
+                "You generate short request titles. Reply with only the title text.",
+                &prompt,
+                64,
+            )
+            .await
+            .and_then(|raw| clean_line(&raw, TITLE_MAX_CHARS))
+            .unwrap_or(fallback);
+        Some(title)
+    }
+
+    /// A commit subject line for the described changes, or `fallback` when
+    /// no model is available or the call fails.
+    pub async fn commit_message(
+        &self,
+        description: &str,
+        fallback: &str,
+        session: Option<SessionModel<'_>>,
+    ) -> String {
+        let Some(target) = self.target_for(UtilityTask::CommitMessage, session) else {
+            return fallback.to_string();
+        };
+        if description.trim().is_empty() {
+            return fallback.to_string();
+        }
+        let prompt = format!(
+            "Write a git commit subject line (imperative mood, under 72 chars) \
+             for the changes described below.\n\n{}",
+            description
+        );
+        self.complete(
+            UtilityTask::CommitMessage,
+            target,
+            "You write concise git commit subject lines. Reply with only the subject line.",
+            &prompt,
+            64,
+        )
+        .await
+        .and_then(|raw| clean_line(&raw, COMMIT_SUBJECT_MAX_CHARS))
+        .unwrap_or_else(|| fallback.to_string())
+    }
+
+    /// Starts a session title job in the background; `None` when titles are
+    /// switched off.
+    pub fn spawn_session_title(
+        self: &Arc<Self>,
+        first_user_message: String,
+        session: UtilityTarget,
+    ) -> Option<JoinHandle<String>> {
+        self.target_for(UtilityTask::Title, Some(session.as_model()))?;
+        let pipeline = Arc::clone(self);
+        Some(tokio::spawn(async move {
+            pipeline
+                .session_title(&first_user_message, Some(session.as_model()))
+                .await
+        }))
+    }
+
+    /// Starts a message summary title job in the background; `None` when
+    /// summaries are switched off.
+    pub fn spawn_message_title(
+        self: &Arc<Self>,
+        text: String,
+        session: UtilityTarget,
+    ) -> Option<JoinHandle<Option<String>>> {
+        self.target_for(UtilityTask::Summary, Some(session.as_model()))?;
+        let pipeline = Arc::clone(self);
+        Some(tokio::spawn(async move {
+            pipeline
+                .message_title(&text, Some(session.as_model()))
+                .await
+        }))
+    }
+
+    async fn complete(
+        &self,
+        task: UtilityTask,
+        (provider, model_id): SessionModel<'_>,
+        system: &str,
+        prompt: &str,
+        max_tokens: u64,
+    ) -> Option<String> {
+        let key = cache_key(task, provider.id(), model_id, prompt);
+        if self.settings.cache {
+            if let Some(hit) = self.cache.lock().ok()?.get(key) {
+                return Some(hit);
+            }
+        }
+
+        let mut request = ChatRequest::new(model_id, vec![Message::user(prompt)]);
+        request.system = Some(system.to_string());
+        request.max_tokens = Some(max_tokens);
+        request.temperature = Some(0.0);
+        request.stream = Some(false);
+
+        // Waiting for a queue slot counts against the timeout, so a saturated
+        // queue falls back to the heuristic as quickly as a slow model does.
+        let call = async {
+            let _permit = self.queue.acquire().await.ok()?;
+            Some(provider.chat(request).await)
+        };
+        let response = match tokio::time::timeout(self.settings.timeout, call).await {
+            Ok(Some(Ok(response))) => response,
+            Ok(Some(Err(error))) => {
+                tracing::warn!(?task, model = %model_id, %error, "utility call failed");
+                return None;
+            }
+            Ok(None) => return None,
+            Err(_) => {
+                tracing::warn!(?task, model = %model_id, "utility call timed out");
+                return None;
+            }
+        };
+
+        let text = response
+            .choices
+            .first()
+            .map(|choice| match &choice.message.content {
+                Content::Text(text) => text.clone(),
+                Content::Parts(parts) => parts
+                    .iter()
+                    .filter_map(|part| part.text.clone())
+                    .collect::<Vec<_>>()
+                    .join(""),
+            })
+            .filter(|text| !text.trim().is_empty())?;
+
+        if self.settings.cache {
+            if let Ok(mut cache) = self.cache.lock() {
+                cache.insert(key, text.clone());
+            }
+        }
+        Some(text)
+    }
+}
+
+fn cache_key(task: UtilityTask, provider_id: &str, model_id: &str, prompt: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    task.hash(&mut hasher);
+    provider_id.hash(&mut hasher);
+    model_id.hash(&mut hasher);
+    prompt.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// First meaningful line of a model reply: reasoning blocks and quotes are
+/// dropped and the result is capped at `max_chars`.
+fn clean_line(raw: &str, max_chars: usize) -> Option<String> {
+    let answer = match raw.rfind("</think>") {
+        Some(end) => &raw[end + "</think>".len()..],
+        None => raw,
+    };
+    let line = answer
+        .replace(['"', '\''], "")
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with("<think>"))?
+        .to_string();
+    if line.chars().count() > max_chars {
+        let head: String = line.chars().take(max_chars.saturating_sub(3)).collect();
+        Some(format!("{}...", head))
+    } else {
+        Some(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use futures::stream;
+    use rocode_provider::{ChatResponse, Choice, ModelInfo, ProviderError, StreamResult};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingProvider {
+        model: ModelInfo,
+        reply: String,
+        delay: Duration,
+        calls: AtomicUsize,
+    }
+
+    impl CountingProvider {
+        fn new(context_window: u64, reply: &str) -> Arc<Self> {
+            Arc::new(Self {
+                model: ModelInfo {
+                    id: "small".to_string(),
+                    name: "Small".to_string(),
+                    provider: "mock".to_string(),
+                    context_window,
+                    max_input_tokens: None,
+                    max_output_tokens: 1024,
+                    supports_vision: false,
+                    supports_tools: false,
+                    cost_per_million_input: 0.0,
+                    cost_per_million_output: 0.0,
+                    supports_batch: false,
+                    supports_pdf: false,
+                },
+                reply: reply.to_string(),
+                delay: Duration::ZERO,
+                calls: AtomicUsize::new(0),
+            })
+        }
+
+        fn target(self: &Arc<Self>) -> UtilityTarget {
+            UtilityTarget::new(self.clone(), "mock", "small")
+        }
+    }
+
+    #[async_trait]
+    impl Provider for CountingProvider {
+        fn id(&self) -> &str {
+            "mock"
+        }
+
+        fn name(&self) -> &str {
+            "Mock"
+        }
+
+        fn models(&self) -> Vec<ModelInfo> {
+            vec![self.model.clone()]
+        }
+
+        fn get_model(&self, id: &str) -> Option<&ModelInfo> {
+            (id == self.model.id).then_some(&self.model)
+        }
+
+        async fn chat(&self, _request: ChatRequest) -> Result<ChatResponse, ProviderError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            Ok(ChatResponse {
+                id: "chat_mock".to_string(),
+                model: self.model.id.clone(),
+                choices: vec![Choice {
+                    index: 0,
+                    message: Message::assistant(self.reply.clone()),
+                    finish_reason: Some("stop".to_string()),
+                }],
+                usage: None,
+            })
+        }
+
+        async fn chat_stream(&self, _request: ChatRequest) -> Result<StreamResult, ProviderError> {
+            Ok(Box::pin(stream::empty()))
+        }
+    }
+
+    #[tokio::test]
+    async fn caches_results_for_identical_inputs() {
+        let provider = CountingProvider::new(8192, "\"Fix login redirect\"");
+        let pipeline = UtilityPipeline::default();
+        let target = provider.target();
+
+        let first = pipeline
+            .session_title("fix the login redirect", Some(target.as_model()))
+            .await;
+        let second = pipeline
+            .session_title("fix the login redirect", Some(target.as_model()))
+            .await;
+
+        assert_eq!(first, "Fix login redirect");
+        assert_eq!(second, first);
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn disabled_pipeline_never_calls_the_model() {
+        let provider = CountingProvider::new(8192, "Model title");
+        let pipeline = UtilityPipeline::new(
+            Some(provider.target()),
+            UtilitySettings {
+                disabled: true,
+                ..Default::default()
+            },
+        );
+
+        let title = pipeline
+            .session_title("explain the build\nmore detail", None)
+            .await;
+        let commit = pipeline
+            .commit_message("Updated the parser", "Fix issue #4", None)
+            .await;
+
+        assert_eq!(title, "explain the build");
+        assert_eq!(commit, "Fix issue #4");
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn timeout_falls_back_to_heuristic_title() {
+        let mut slow = CountingProvider::new(8192, "Too late");
+        Arc::get_mut(&mut slow).unwrap().delay = Duration::from_secs(5);
+        let pipeline = UtilityPipeline::new(
+            None,
+            UtilitySettings {
+                timeout: Duration::from_millis(20),
+                ..Default::default()
+            },
+        );
+
+        let title = pipeline
+            .message_title("rename the config loader", Some(slow.target().as_model()))
+            .await;
+
+        assert_eq!(title.as_deref(), Some("rename the config loader"));
+    }
+
+    #[tokio::test]
+    async fn time_spent_queued_counts_against_the_timeout() {
+        let provider = CountingProvider::new(8192, "Model title");
+        let pipeline = UtilityPipeline::new(
+            Some(provider.target()),
+            UtilitySettings {
+                timeout: Duration::from_millis(50),
+                concurrency: 1,
+                ..Default::default()
+            },
+        );
+        let _busy = pipeline.queue.acquire().await.unwrap();
+
+        let started = std::time::Instant::now();
+        let title = pipeline.session_title("queued behind it", None).await;
+
+        assert_eq!(title, "queued behind it");
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(provider.calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn compaction_requires_opt_in_and_a_large_enough_window() {
+        let session = CountingProvider::new(200_000, "").target();
+        let small = CountingProvider::new(32_000, "").target();
+        let large = CountingProvider::new(200_000, "").target();
+        let enabled = UtilitySettings {
+            tasks: HashMap::from([(UtilityTask::Compaction, true)]),
+            ..Default::default()
+        };
+
+        let default_settings =
+            UtilityPipeline::new(Some(large.clone()), UtilitySettings::default());
+        assert!(default_settings.compaction_target(&session).is_none());
+
+        let too_small = UtilityPipeline::new(Some(small), enabled.clone());
+        assert!(too_small.compaction_target(&session).is_none());
+
+        let fits = UtilityPipeline::new(Some(large), enabled);
+        assert!(fits.compaction_target(&session).is_some());
+    }
+
+    #[test]
+    fn clean_line_strips_reasoning_and_truncates() {
+        assert_eq!(
+            clean_line("<think>\nponder\n</think>\n\n'Add retry'", 100).as_deref(),
+            Some("Add retry")
+        );
+        let long = "é".repeat(120);
+        let cleaned = clean_line(&long, 72).unwrap();
+        assert_eq!(cleaned.chars().count(), 72);
+        assert!(cleaned.ends_with("..."));
+    }
+}
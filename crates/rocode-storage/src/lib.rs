@@ -3,4 +3,7 @@ pub mod repository;
 pub mod schema;
 
 pub use database::{Database, DatabaseError};
-pub use repository::{MessageRepository, SessionRepository, TodoItem, TodoRepository};
+pub use repository::{
+    usage_summaries_to_csv, MessageRepository, SessionRepository, TodoItem, TodoRepository,
+    UsageFilter, UsageGroupBy, UsageLedgerRepository, UsageRecord, UsageSummary,
+};
@@ -1159,6 +1159,263 @@ impl PartRepository {
     }
 }
 
+/// One model step recorded in the usage ledger.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageRecord {
+    pub created_at: i64,
+    pub session_id: String,
+    pub message_id: Option<String>,
+    pub project_id: String,
+    pub directory: String,
+    pub provider_id: String,
+    pub model_id: String,
+    pub agent: Option<String>,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub reasoning_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+    pub cost: f64,
+}
+
+/// Row filter for ledger queries; `None` fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct UsageFilter {
+    /// Inclusive lower bound, milliseconds since epoch.
+    pub since: Option<i64>,
+    /// Exclusive upper bound, milliseconds since epoch.
+    pub until: Option<i64>,
+    pub project_id: Option<String>,
+    pub directory: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageGroupBy {
+    Provider,
+    Model,
+    Agent,
+    Session,
+    Project,
+    Day,
+}
+
+impl UsageGroupBy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "provider" => Some(Self::Provider),
+            "model" => Some(Self::Model),
+            "agent" => Some(Self::Agent),
+            "session" => Some(Self::Session),
+            "project" => Some(Self::Project),
+            "day" | "date" => Some(Self::Day),
+            _ => None,
+        }
+    }
+
+    fn key_sql(self) -> &'static str {
+        match self {
+            Self::Provider => "provider_id",
+            Self::Model => "provider_id || '/' || model_id",
+            Self::Agent => "COALESCE(agent, '')",
+            Self::Session => "session_id",
+            Self::Project => "project_id",
+            Self::Day => "strftime('%Y-%m-%d', created_at / 1000, 'unixepoch')",
+        }
+    }
+}
+
+/// Aggregated ledger totals for one group (or the whole range when ungrouped).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct UsageSummary {
+    pub key: String,
+    pub steps: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub reasoning_tokens: i64,
+    pub cache_read_tokens: i64,
+    pub cache_write_tokens: i64,
+    pub cost: f64,
+}
+
+impl UsageSummary {
+    pub const CSV_HEADER: &'static str = "key,steps,input_tokens,output_tokens,reasoning_tokens,cache_read_tokens,cache_write_tokens,cost";
+
+    /// One CSV line matching [`Self::CSV_HEADER`]; the key is quoted when needed.
+    pub fn to_csv_row(&self) -> String {
+        let key = if self.key.contains([',', '"', '\n']) {
+            format!("\"{}\"", self.key.replace('"', "\"\""))
+        } else {
+            self.key.clone()
+        };
+        format!(
+            "{},{},{},{},{},{},{},{:.6}",
+            key,
+            self.steps,
+            self.input_tokens,
+            self.output_tokens,
+            self.reasoning_tokens,
+            self.cache_read_tokens,
+            self.cache_write_tokens,
+            self.cost
+        )
+    }
+}
+
+/// Renders summaries as CSV with a header line.
+pub fn usage_summaries_to_csv(rows: &[UsageSummary]) -> String {
+    let mut out = String::from(UsageSummary::CSV_HEADER);
+    out.push('\n');
+    for row in rows {
+        out.push_str(&row.to_csv_row());
+        out.push('\n');
+    }
+    out
+}
+
+const USAGE_FILTER_SQL: &str = r#"
+WHERE (?1 IS NULL OR created_at >= ?1)
+  AND (?2 IS NULL OR created_at < ?2)
+  AND (?3 IS NULL OR project_id = ?3)
+  AND (?4 IS NULL OR directory = ?4)
+"#;
+
+/// Append-only store behind `rocode stats` and the `/usage` endpoint.
+#[derive(Clone)]
+pub struct UsageLedgerRepository {
+    pool: SqlitePool,
+}
+
+impl UsageLedgerRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn append(&self, record: &UsageRecord) -> Result<(), DatabaseError> {
+        sqlx::query(
+            r#"
+            INSERT INTO usage_ledger (
+                created_at, session_id, message_id, project_id, directory, provider_id,
+                model_id, agent, input_tokens, output_tokens, reasoning_tokens,
+                cache_read_tokens, cache_write_tokens, cost
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(record.created_at)
+        .bind(&record.session_id)
+        .bind(&record.message_id)
+        .bind(&record.project_id)
+        .bind(&record.directory)
+        .bind(&record.provider_id)
+        .bind(&record.model_id)
+        .bind(&record.agent)
+        .bind(record.input_tokens as i64)
+        .bind(record.output_tokens as i64)
+        .bind(record.reasoning_tokens as i64)
+        .bind(record.cache_read_tokens as i64)
+        .bind(record.cache_write_tokens as i64)
+        .bind(record.cost)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    /// Raw ledger rows in chronological order.
+    pub async fn list(&self, filter: &UsageFilter) -> Result<Vec<UsageRecord>, DatabaseError> {
+        #[derive(FromRow)]
+        struct Row {
+            created_at: i64,
+            session_id: String,
+            message_id: Option<String>,
+            project_id: String,
+            directory: String,
+            provider_id: String,
+            model_id: String,
+            agent: Option<String>,
+            input_tokens: i64,
+            output_tokens: i64,
+            reasoning_tokens: i64,
+            cache_read_tokens: i64,
+            cache_write_tokens: i64,
+            cost: f64,
+        }
+
+        let sql = format!(
+            r#"SELECT created_at, session_id, message_id, project_id, directory, provider_id,
+                      model_id, agent, input_tokens, output_tokens, reasoning_tokens,
+                      cache_read_tokens, cache_write_tokens, cost
+               FROM usage_ledger {USAGE_FILTER_SQL}
+               ORDER BY created_at ASC, id ASC"#
+        );
+        let rows = sqlx::query_as::<_, Row>(&sql)
+            .bind(filter.since)
+            .bind(filter.until)
+            .bind(&filter.project_id)
+            .bind(&filter.directory)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| UsageRecord {
+                created_at: row.created_at,
+                session_id: row.session_id,
+                message_id: row.message_id,
+                project_id: row.project_id,
+                directory: row.directory,
+                provider_id: row.provider_id,
+                model_id: row.model_id,
+                agent: row.agent,
+                input_tokens: row.input_tokens.max(0) as u64,
+                output_tokens: row.output_tokens.max(0) as u64,
+                reasoning_tokens: row.reasoning_tokens.max(0) as u64,
+                cache_read_tokens: row.cache_read_tokens.max(0) as u64,
+                cache_write_tokens: row.cache_write_tokens.max(0) as u64,
+                cost: row.cost,
+            })
+            .collect())
+    }
+
+    /// Totals per group, most expensive first. Without `group_by` a single
+    /// row keyed `total` covers the whole filter.
+    pub async fn summarize(
+        &self,
+        filter: &UsageFilter,
+        group_by: Option<UsageGroupBy>,
+    ) -> Result<Vec<UsageSummary>, DatabaseError> {
+        let key = group_by.map_or("'total'", UsageGroupBy::key_sql);
+        let sql = format!(
+            r#"SELECT {key} AS key,
+                      COUNT(*) AS steps,
+                      COALESCE(SUM(input_tokens), 0) AS input_tokens,
+                      COALESCE(SUM(output_tokens), 0) AS output_tokens,
+                      COALESCE(SUM(reasoning_tokens), 0) AS reasoning_tokens,
+                      COALESCE(SUM(cache_read_tokens), 0) AS cache_read_tokens,
+                      COALESCE(SUM(cache_write_tokens), 0) AS cache_write_tokens,
+                      COALESCE(SUM(cost), 0.0) AS cost
+               FROM usage_ledger {USAGE_FILTER_SQL}
+               GROUP BY 1
+               ORDER BY cost DESC, key ASC"#
+        );
+        let mut rows = sqlx::query_as::<_, UsageSummary>(&sql)
+            .bind(filter.since)
+            .bind(filter.until)
+            .bind(&filter.project_id)
+            .bind(&filter.directory)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DatabaseError::QueryError(e.to_string()))?;
+
+        if group_by == Some(UsageGroupBy::Day) {
+            rows.sort_by(|a, b| a.key.cmp(&b.key));
+        }
+        Ok(rows)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1373,4 +1630,60 @@ mod tests {
         assert_eq!(loaded_msgs[0].id, "m1");
         assert_eq!(loaded_msgs[1].id, "m2");
     }
+
+    #[tokio::test]
+    async fn usage_ledger_survives_session_delete_and_groups_totals() {
+        let db = Database::in_memory().await.unwrap();
+        let session_repo = SessionRepository::new(db.pool().clone());
+        let ledger = UsageLedgerRepository::new(db.pool().clone());
+        session_repo.create(&make_session("s1")).await.unwrap();
+
+        let step = |model: &str, created_at: i64, cost: f64| UsageRecord {
+            created_at,
+            session_id: "s1".to_string(),
+            message_id: Some("m1".to_string()),
+            project_id: "proj-1".to_string(),
+            directory: "/tmp/test".to_string(),
+            provider_id: "anthropic".to_string(),
+            model_id: model.to_string(),
+            agent: Some("build".to_string()),
+            input_tokens: 100,
+            output_tokens: 10,
+            cost,
+            ..Default::default()
+        };
+        ledger.append(&step("sonnet", 1_000, 0.5)).await.unwrap();
+        ledger.append(&step("sonnet", 2_000, 0.25)).await.unwrap();
+        ledger.append(&step("haiku", 3_000, 0.125)).await.unwrap();
+        session_repo.delete("s1").await.unwrap();
+
+        let by_model = ledger
+            .summarize(&UsageFilter::default(), Some(UsageGroupBy::Model))
+            .await
+            .unwrap();
+        assert_eq!(by_model.len(), 2);
+        assert_eq!(by_model[0].key, "anthropic/sonnet");
+        assert_eq!(by_model[0].steps, 2);
+        assert_eq!(by_model[0].input_tokens, 200);
+        assert!((by_model[0].cost - 0.75).abs() < f64::EPSILON);
+
+        let ranged = UsageFilter {
+            since: Some(2_000),
+            until: Some(3_000),
+            ..Default::default()
+        };
+        let total = ledger.summarize(&ranged, None).await.unwrap();
+        assert_eq!(total.len(), 1);
+        assert_eq!(total[0].key, "total");
+        assert_eq!(total[0].steps, 1);
+        assert_eq!(ledger.list(&ranged).await.unwrap()[0].model_id, "sonnet");
+
+        let csv = usage_summaries_to_csv(&by_model);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(UsageSummary::CSV_HEADER));
+        assert_eq!(
+            lines.next(),
+            Some("anthropic/sonnet,2,200,20,0,0,0,0.750000")
+        );
+    }
 }
@@ -157,6 +157,28 @@ CREATE TABLE IF NOT EXISTS session_shares (
 );
 "#;
 
+/// Usage ledger - append-only record of every model step's token usage and
+/// cost. Deliberately has no foreign keys so totals survive session deletion.
+pub const CREATE_USAGE_LEDGER_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS usage_ledger (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_at INTEGER NOT NULL,
+    session_id TEXT NOT NULL,
+    message_id TEXT,
+    project_id TEXT NOT NULL,
+    directory TEXT NOT NULL,
+    provider_id TEXT NOT NULL,
+    model_id TEXT NOT NULL,
+    agent TEXT,
+    input_tokens INTEGER NOT NULL DEFAULT 0,
+    output_tokens INTEGER NOT NULL DEFAULT 0,
+    reasoning_tokens INTEGER NOT NULL DEFAULT 0,
+    cache_read_tokens INTEGER NOT NULL DEFAULT 0,
+    cache_write_tokens INTEGER NOT NULL DEFAULT 0,
+    cost REAL NOT NULL DEFAULT 0.0
+);
+"#;
+
 /// Create indexes for better query performance
 pub const CREATE_INDEXES: &str = r#"
 -- Session indexes
@@ -177,6 +199,10 @@ CREATE INDEX IF NOT EXISTS idx_parts_order ON parts(sort_order);
 -- Todo indexes
 CREATE INDEX IF NOT EXISTS idx_todos_session ON todos(session_id);
 CREATE INDEX IF NOT EXISTS idx_todos_status ON todos(status);
+
+-- Usage ledger indexes
+CREATE INDEX IF NOT EXISTS idx_usage_ledger_created ON usage_ledger(created_at);
+CREATE INDEX IF NOT EXISTS idx_usage_ledger_project ON usage_ledger(project_id);
 "#;
 
 /// Add finish column to messages table for existing databases.
@@ -191,6 +217,7 @@ pub const ALL_MIGRATIONS: &[&str] = &[
     CREATE_TODOS_TABLE,
     CREATE_PERMISSIONS_TABLE,
     CREATE_SESSION_SHARES_TABLE,
+    CREATE_USAGE_LEDGER_TABLE,
     CREATE_INDEXES,
     ADD_MESSAGES_FINISH_COLUMN,
 ];
@@ -0,0 +1,166 @@
+use std::io::{Cursor, Read};
+
+use super::{DocumentKind, ExtractError, ExtractedDocument};
+
+struct ArchiveEntry {
+    path: String,
+    size: u64,
+    is_dir: bool,
+}
+
+pub(super) fn list_zip(content: &[u8]) -> Result<ExtractedDocument, ExtractError> {
+    let kind = DocumentKind::Zip;
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(content)).map_err(|e| ExtractError::malformed(kind, e))?;
+    let mut entries = Vec::with_capacity(archive.len());
+    for index in 0..archive.len() {
+        let file = archive
+            .by_index_raw(index)
+            .map_err(|e| ExtractError::malformed(kind, e))?;
+        entries.push(ArchiveEntry {
+            path: file.name().to_string(),
+            size: file.size(),
+            is_dir: file.is_dir(),
+        });
+    }
+    Ok(render(entries))
+}
+
+pub(super) fn list_tar(content: &[u8], gzipped: bool) -> Result<ExtractedDocument, ExtractError> {
+    let kind = if gzipped {
+        DocumentKind::TarGz
+    } else {
+        DocumentKind::Tar
+    };
+    let reader: Box<dyn Read + '_> = if gzipped {
+        Box::new(flate2::read::GzDecoder::new(content))
+    } else {
+        Box::new(content)
+    };
+    let mut archive = tar::Archive::new(reader);
+    let mut entries = Vec::new();
+    for entry in archive
+        .entries()
+        .map_err(|e| ExtractError::malformed(kind, e))?
+    {
+        let entry = entry.map_err(|e| ExtractError::malformed(kind, e))?;
+        let header = entry.header();
+        let path = entry
+            .path()
+            .map(|p| p.to_string_lossy().to_string())
+            .map_err(|e| ExtractError::malformed(kind, e))?;
+        entries.push(ArchiveEntry {
+            path,
+            size: header.size().unwrap_or(0),
+            is_dir: header.entry_type().is_dir(),
+        });
+    }
+    Ok(render(entries))
+}
+
+/// Largest decompressed `.gz` payload returned as text.
+const MAX_GZIP_TEXT_BYTES: u64 = 16 * 1024 * 1024;
+
+pub(super) fn decompress_gzip(content: &[u8]) -> Result<ExtractedDocument, ExtractError> {
+    let kind = DocumentKind::Gzip;
+    let mut bytes = Vec::new();
+    flate2::read::GzDecoder::new(content)
+        .take(MAX_GZIP_TEXT_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| ExtractError::malformed(kind, e))?;
+    if bytes.len() as u64 > MAX_GZIP_TEXT_BYTES {
+        return Err(ExtractError::malformed(
+            kind,
+            format!("decompressed content exceeds {} bytes", MAX_GZIP_TEXT_BYTES),
+        ));
+    }
+    if bytes.contains(&0) {
+        return Err(ExtractError::malformed(
+            kind,
+            "decompressed content is binary",
+        ));
+    }
+    let summary = format!("{} bytes decompressed", bytes.len());
+    Ok(ExtractedDocument {
+        text: String::from_utf8_lossy(&bytes).into_owned(),
+        summary,
+    })
+}
+
+fn render(mut entries: Vec<ArchiveEntry>) -> ExtractedDocument {
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    let files = entries.iter().filter(|e| !e.is_dir).count();
+    let total_size: u64 = entries.iter().map(|e| e.size).sum();
+
+    let mut text = String::new();
+    for entry in &entries {
+        if entry.is_dir {
+            let path = entry.path.trim_end_matches('/');
+            text.push_str(&format!("{}/\n", path));
+        } else {
+            text.push_str(&format!("{}\t{}\n", entry.path, entry.size));
+        }
+    }
+
+    ExtractedDocument {
+        text,
+        summary: format!("{} files, {} bytes uncompressed", files, total_size),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn zip_listing_is_sorted_with_sizes() {
+        let mut buf = Cursor::new(Vec::new());
+        {
+            let mut writer = zip::ZipWriter::new(&mut buf);
+            let options = zip::write::SimpleFileOptions::default();
+            writer.add_directory("src/", options).unwrap();
+            writer.start_file("src/main.rs", options).unwrap();
+            writer.write_all(b"fn main() {}\n").unwrap();
+            writer.start_file("README.md", options).unwrap();
+            writer.write_all(b"# hi\n").unwrap();
+            writer.finish().unwrap();
+        }
+        let listing = list_zip(buf.get_ref()).unwrap();
+        assert_eq!(listing.text, "README.md\t5\nsrc/\nsrc/main.rs\t13\n");
+        assert_eq!(listing.summary, "2 files, 18 bytes uncompressed");
+    }
+
+    #[test]
+    fn tar_gz_listing_reads_headers() {
+        let mut tar_bytes = Vec::new();
+        {
+            let mut builder = tar::Builder::new(&mut tar_bytes);
+            let data = b"hello";
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, "docs/a.txt", &data[..])
+                .unwrap();
+            builder.finish().unwrap();
+        }
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        gz.write_all(&tar_bytes).unwrap();
+        let listing = list_tar(&gz.finish().unwrap(), true).unwrap();
+        assert_eq!(listing.text, "docs/a.txt\t5\n");
+    }
+
+    #[test]
+    fn plain_gzip_file_is_decompressed_to_text() {
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        gz.write_all(b"line one\nline two\n").unwrap();
+        let doc = decompress_gzip(&gz.finish().unwrap()).unwrap();
+        assert_eq!(doc.text, "line one\nline two\n");
+        assert_eq!(doc.summary, "18 bytes decompressed");
+
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        gz.write_all(&[0u8, 1, 2, 3]).unwrap();
+        assert!(decompress_gzip(&gz.finish().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,243 @@
+//! Local text extraction for documents the read tool cannot return as plain
+//! text: PDFs, office documents, spreadsheets and archives.
+
+mod archive;
+mod office;
+mod pdf;
+mod spreadsheet;
+
+use std::path::Path;
+
+pub use spreadsheet::CellRange;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    Pdf,
+    Docx,
+    Pptx,
+    OpenDocument,
+    Spreadsheet,
+    Zip,
+    Tar,
+    TarGz,
+    /// A single gzip-compressed file such as `server.log.gz`.
+    Gzip,
+}
+
+impl DocumentKind {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            return Some(Self::TarGz);
+        }
+        let ext = name.rsplit_once('.')?.1;
+        match ext {
+            "pdf" => Some(Self::Pdf),
+            "docx" | "docm" => Some(Self::Docx),
+            "pptx" | "pptm" => Some(Self::Pptx),
+            "odt" | "odp" => Some(Self::OpenDocument),
+            "xlsx" | "xlsm" | "xlsb" | "xls" | "ods" => Some(Self::Spreadsheet),
+            "zip" | "jar" | "whl" | "nupkg" => Some(Self::Zip),
+            "tar" => Some(Self::Tar),
+            "gz" => Some(Self::Gzip),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Pdf => "pdf",
+            Self::Docx | Self::OpenDocument => "document",
+            Self::Pptx => "presentation",
+            Self::Spreadsheet => "spreadsheet",
+            Self::Zip | Self::Tar | Self::TarGz => "archive",
+            Self::Gzip => "gzip file",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ExtractOptions {
+    /// Pages (PDF) or slides (presentations) to include.
+    pub pages: Option<PageSelection>,
+    /// Worksheet name; all sheets are included when unset.
+    pub sheet: Option<String>,
+    pub range: Option<CellRange>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExtractedDocument {
+    pub text: String,
+    /// Short description of what was extracted, e.g. "pages 1-3 of 12".
+    pub summary: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExtractError {
+    #[error("Invalid selection: {0}")]
+    InvalidSelection(String),
+
+    #[error("Failed to parse {kind}: {message}")]
+    Malformed { kind: &'static str, message: String },
+}
+
+impl ExtractError {
+    fn malformed(kind: DocumentKind, message: impl std::fmt::Display) -> Self {
+        Self::Malformed {
+            kind: kind.label(),
+            message: message.to_string(),
+        }
+    }
+}
+
+pub fn extract(
+    kind: DocumentKind,
+    content: &[u8],
+    options: &ExtractOptions,
+) -> Result<ExtractedDocument, ExtractError> {
+    match kind {
+        DocumentKind::Pdf => pdf::extract(content, options.pages.as_ref()),
+        DocumentKind::Docx => office::extract_docx(content),
+        DocumentKind::Pptx => office::extract_pptx(content, options.pages.as_ref()),
+        DocumentKind::OpenDocument => office::extract_opendocument(content),
+        DocumentKind::Spreadsheet => {
+            spreadsheet::extract(content, options.sheet.as_deref(), options.range.as_ref())
+        }
+        DocumentKind::Zip => archive::list_zip(content),
+        DocumentKind::Tar => archive::list_tar(content, false),
+        DocumentKind::TarGz => archive::list_tar(content, true),
+        DocumentKind::Gzip => archive::decompress_gzip(content),
+    }
+}
+
+/// A 1-indexed page selection such as `3`, `1-5` or `1-3,7,10-12`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageSelection {
+    ranges: Vec<(u32, u32)>,
+}
+
+impl PageSelection {
+    pub fn parse(input: &str) -> Result<Self, ExtractError> {
+        let mut ranges = Vec::new();
+        for part in input.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (start, end) = match part.split_once('-') {
+                Some((start, end)) => (parse_page(start, input)?, parse_page(end, input)?),
+                None => {
+                    let page = parse_page(part, input)?;
+                    (page, page)
+                }
+            };
+            if start > end {
+                return Err(ExtractError::InvalidSelection(format!(
+                    "page range '{}' is reversed",
+                    part
+                )));
+            }
+            ranges.push((start, end));
+        }
+        if ranges.is_empty() {
+            return Err(ExtractError::InvalidSelection(
+                "page selection is empty".to_string(),
+            ));
+        }
+        Ok(Self { ranges })
+    }
+
+    pub fn contains(&self, page: u32) -> bool {
+        self.ranges
+            .iter()
+            .any(|(start, end)| (*start..=*end).contains(&page))
+    }
+}
+
+fn parse_page(value: &str, input: &str) -> Result<u32, ExtractError> {
+    match value.trim().parse::<u32>() {
+        Ok(page) if page >= 1 => Ok(page),
+        _ => Err(ExtractError::InvalidSelection(format!(
+            "'{}' is not a valid page selection (expected e.g. '1-5' or '2,4')",
+            input
+        ))),
+    }
+}
+
+/// Collapses a sorted list of unit numbers into a compact "1-3, 5" label.
+fn describe_units(units: &[u32]) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    let mut iter = units.iter().copied().peekable();
+    while let Some(start) = iter.next() {
+        let mut end = start;
+        while iter.peek() == Some(&(end + 1)) {
+            end = iter.next().unwrap_or(end);
+        }
+        if start == end {
+            parts.push(start.to_string());
+        } else {
+            parts.push(format!("{}-{}", start, end));
+        }
+    }
+    parts.join(", ")
+}
+
+/// Picks the requested units out of `1..=total`, failing when the selection
+/// matches nothing so the model gets told how many units exist.
+fn select_units(
+    total: u32,
+    selection: Option<&PageSelection>,
+    unit: &str,
+) -> Result<Vec<u32>, ExtractError> {
+    let selected: Vec<u32> = (1..=total)
+        .filter(|n| selection.map(|s| s.contains(*n)).unwrap_or(true))
+        .collect();
+    if selected.is_empty() && total > 0 {
+        return Err(ExtractError::InvalidSelection(format!(
+            "no {}s match the selection (document has {} {}s)",
+            unit, total, unit
+        )));
+    }
+    Ok(selected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_selection_parses_lists_and_ranges() {
+        let selection = PageSelection::parse("1-3, 7,10-11").unwrap();
+        assert!(selection.contains(2));
+        assert!(selection.contains(7));
+        assert!(selection.contains(11));
+        assert!(!selection.contains(4));
+        assert!(!selection.contains(12));
+    }
+
+    #[test]
+    fn page_selection_rejects_garbage() {
+        assert!(PageSelection::parse("0").is_err());
+        assert!(PageSelection::parse("5-2").is_err());
+        assert!(PageSelection::parse("a-b").is_err());
+        assert!(PageSelection::parse(" , ").is_err());
+    }
+
+    #[test]
+    fn describe_units_collapses_runs() {
+        assert_eq!(describe_units(&[1, 2, 3, 5, 7, 8]), "1-3, 5, 7-8");
+    }
+
+    #[test]
+    fn document_kind_detects_compound_extensions() {
+        assert_eq!(
+            DocumentKind::from_path(Path::new("/tmp/release.tar.gz")),
+            Some(DocumentKind::TarGz)
+        );
+        assert_eq!(
+            DocumentKind::from_path(Path::new("logs/server.log.gz")),
+            Some(DocumentKind::Gzip)
+        );
+        assert_eq!(
+            DocumentKind::from_path(Path::new("Report.DOCX")),
+            Some(DocumentKind::Docx)
+        );
+        assert_eq!(DocumentKind::from_path(Path::new("main.rs")), None);
+    }
+}
@@ -0,0 +1,337 @@
+use std::io::{Cursor, Read};
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use super::PageSelection;
+use super::{describe_units, select_units, DocumentKind, ExtractError, ExtractedDocument};
+
+type ZipArchive<'a> = zip::ZipArchive<Cursor<&'a [u8]>>;
+
+pub(super) fn extract_docx(content: &[u8]) -> Result<ExtractedDocument, ExtractError> {
+    let mut archive = open_zip(content, DocumentKind::Docx)?;
+    let xml = read_entry(&mut archive, "word/document.xml", DocumentKind::Docx)?;
+    let text = ooxml_text(&xml, DocumentKind::Docx)?;
+    let summary = format!("{} paragraphs", text.lines().count());
+    Ok(ExtractedDocument { text, summary })
+}
+
+pub(super) fn extract_pptx(
+    content: &[u8],
+    slides: Option<&PageSelection>,
+) -> Result<ExtractedDocument, ExtractError> {
+    let mut archive = open_zip(content, DocumentKind::Pptx)?;
+    let slide_paths = slide_order(&mut archive);
+
+    let total = slide_paths.len() as u32;
+    let selected = select_units(total, slides, "slide")?;
+
+    let mut text = String::new();
+    for (index, slide_path) in slide_paths.iter().enumerate() {
+        let position = index as u32 + 1;
+        if !selected.contains(&position) {
+            continue;
+        }
+        let xml = read_entry(&mut archive, slide_path, DocumentKind::Pptx)?;
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&format!("--- slide {} ---\n", position));
+        text.push_str(&ooxml_text(&xml, DocumentKind::Pptx)?);
+    }
+
+    let summary = if selected.len() as u32 == total {
+        format!("{} slides", total)
+    } else {
+        format!("slides {} of {}", describe_units(&selected), total)
+    };
+    Ok(ExtractedDocument { text, summary })
+}
+
+/// Slide part names in presentation order: `<p:sldIdLst>` in
+/// `presentation.xml`, resolved through its relationships. Slide file numbers
+/// are only a fallback, since reordering slides does not rename the parts.
+fn slide_order(archive: &mut ZipArchive<'_>) -> Vec<String> {
+    let ordered = read_entry(archive, "ppt/presentation.xml", DocumentKind::Pptx)
+        .ok()
+        .zip(
+            read_entry(
+                archive,
+                "ppt/_rels/presentation.xml.rels",
+                DocumentKind::Pptx,
+            )
+            .ok(),
+        )
+        .map(|(presentation, rels)| presentation_slide_parts(&presentation, &rels))
+        .unwrap_or_default();
+    if !ordered.is_empty()
+        && ordered
+            .iter()
+            .all(|part| archive.index_for_name(part).is_some())
+    {
+        return ordered;
+    }
+
+    let mut slide_numbers: Vec<u32> = archive
+        .file_names()
+        .filter_map(|name| {
+            name.strip_prefix("ppt/slides/slide")?
+                .strip_suffix(".xml")?
+                .parse()
+                .ok()
+        })
+        .collect();
+    slide_numbers.sort_unstable();
+    slide_numbers
+        .into_iter()
+        .map(|number| format!("ppt/slides/slide{}.xml", number))
+        .collect()
+}
+
+fn presentation_slide_parts(presentation: &str, rels: &str) -> Vec<String> {
+    let mut targets = std::collections::HashMap::new();
+    let mut reader = Reader::from_str(rels);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Empty(e)) | Ok(Event::Start(e))
+                if e.local_name().as_ref() == b"Relationship" =>
+            {
+                let attr = |key: &[u8]| {
+                    e.attributes()
+                        .flatten()
+                        .find(|a| a.key.as_ref() == key)
+                        .map(|a| String::from_utf8_lossy(&a.value).into_owned())
+                };
+                if let (Some(id), Some(target)) = (attr(b"Id"), attr(b"Target")) {
+                    targets.insert(id, target);
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(_) => {}
+        }
+    }
+
+    let mut parts = Vec::new();
+    let mut reader = Reader::from_str(presentation);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Empty(e)) | Ok(Event::Start(e)) if e.local_name().as_ref() == b"sldId" => {
+                let rel_id = e
+                    .attributes()
+                    .flatten()
+                    .find(|a| a.key.prefix().is_some() && a.key.local_name().as_ref() == b"id")
+                    .map(|a| String::from_utf8_lossy(&a.value).into_owned());
+                if let Some(target) = rel_id.and_then(|id| targets.get(&id)) {
+                    parts.push(match target.strip_prefix('/') {
+                        Some(absolute) => absolute.to_string(),
+                        None => format!("ppt/{}", target),
+                    });
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            Ok(_) => {}
+        }
+    }
+    parts
+}
+
+pub(super) fn extract_opendocument(content: &[u8]) -> Result<ExtractedDocument, ExtractError> {
+    let mut archive = open_zip(content, DocumentKind::OpenDocument)?;
+    let xml = read_entry(&mut archive, "content.xml", DocumentKind::OpenDocument)?;
+    let text = odf_text(&xml)?;
+    let summary = format!("{} paragraphs", text.lines().count());
+    Ok(ExtractedDocument { text, summary })
+}
+
+fn open_zip(content: &[u8], kind: DocumentKind) -> Result<ZipArchive<'_>, ExtractError> {
+    zip::ZipArchive::new(Cursor::new(content)).map_err(|e| ExtractError::malformed(kind, e))
+}
+
+/// Largest decompressed XML part read from an office document.
+const MAX_ENTRY_BYTES: u64 = 64 * 1024 * 1024;
+
+fn read_entry(
+    archive: &mut ZipArchive<'_>,
+    name: &str,
+    kind: DocumentKind,
+) -> Result<String, ExtractError> {
+    let entry = archive
+        .by_name(name)
+        .map_err(|e| ExtractError::malformed(kind, format!("{}: {}", name, e)))?;
+    read_capped(entry, name, kind, MAX_ENTRY_BYTES)
+}
+
+/// Reads at most `limit` bytes of `reader` as text; a zip entry that inflates
+/// past it is rejected rather than buffered.
+fn read_capped(
+    reader: impl Read,
+    name: &str,
+    kind: DocumentKind,
+    limit: u64,
+) -> Result<String, ExtractError> {
+    let mut xml = String::new();
+    reader
+        .take(limit + 1)
+        .read_to_string(&mut xml)
+        .map_err(|e| ExtractError::malformed(kind, format!("{}: {}", name, e)))?;
+    if xml.len() as u64 > limit {
+        return Err(ExtractError::malformed(
+            kind,
+            format!("{}: decompressed size exceeds {} bytes", name, limit),
+        ));
+    }
+    Ok(xml)
+}
+
+/// Office Open XML keeps visible text in `<w:t>`/`<a:t>` runs grouped into
+/// `<w:p>`/`<a:p>` paragraphs; everything else is layout.
+fn ooxml_text(xml: &str, kind: DocumentKind) -> Result<String, ExtractError> {
+    let mut reader = Reader::from_str(xml);
+    let mut out = String::new();
+    let mut in_text_run = false;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                if e.local_name().as_ref() == b"t" {
+                    in_text_run = true;
+                }
+            }
+            Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                b"tab" => out.push('\t'),
+                b"br" | b"cr" => out.push('\n'),
+                _ => {}
+            },
+            Ok(Event::Text(t)) if in_text_run => {
+                let text = t.unescape().map_err(|e| ExtractError::malformed(kind, e))?;
+                out.push_str(&text);
+            }
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"t" => in_text_run = false,
+                b"p" => out.push('\n'),
+                _ => {}
+            },
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => return Err(ExtractError::malformed(kind, e)),
+        }
+    }
+    Ok(out)
+}
+
+/// OpenDocument stores text directly inside `<text:p>`/`<text:h>` with
+/// `<text:s>`, `<text:tab>` and `<text:line-break>` as inline markers.
+fn odf_text(xml: &str) -> Result<String, ExtractError> {
+    let kind = DocumentKind::OpenDocument;
+    let mut reader = Reader::from_str(xml);
+    let mut out = String::new();
+    let mut depth = 0usize;
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                if matches!(e.local_name().as_ref(), b"p" | b"h") {
+                    depth += 1;
+                }
+            }
+            Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                b"s" => out.push(' '),
+                b"tab" => out.push('\t'),
+                b"line-break" => out.push('\n'),
+                b"p" | b"h" => out.push('\n'),
+                _ => {}
+            },
+            Ok(Event::Text(t)) if depth > 0 => {
+                let text = t.unescape().map_err(|e| ExtractError::malformed(kind, e))?;
+                out.push_str(&text);
+            }
+            Ok(Event::End(e)) => {
+                if matches!(e.local_name().as_ref(), b"p" | b"h") {
+                    depth = depth.saturating_sub(1);
+                    out.push('\n');
+                }
+            }
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => return Err(ExtractError::malformed(kind, e)),
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ooxml_text_keeps_runs_tabs_and_paragraphs() {
+        let xml = r#"<w:document xmlns:w="w"><w:body>
+            <w:p><w:r><w:t>Hello</w:t></w:r><w:r><w:tab/><w:t xml:space="preserve"> &amp; world</w:t></w:r></w:p>
+            <w:p><w:r><w:t>Second</w:t></w:r></w:p>
+        </w:body></w:document>"#;
+        let text = ooxml_text(xml, DocumentKind::Docx).unwrap();
+        assert_eq!(text, "Hello\t & world\nSecond\n");
+    }
+
+    #[test]
+    fn entries_past_the_size_limit_are_rejected() {
+        let xml = "<w:t>".repeat(100);
+        assert_eq!(
+            read_capped(xml.as_bytes(), "word/document.xml", DocumentKind::Docx, 500).unwrap(),
+            xml
+        );
+        let error =
+            read_capped(xml.as_bytes(), "word/document.xml", DocumentKind::Docx, 499).unwrap_err();
+        assert!(error.to_string().contains("exceeds 499 bytes"), "{error}");
+    }
+
+    #[test]
+    fn pptx_slides_follow_presentation_order() {
+        use std::io::Write;
+
+        let mut buf = Cursor::new(Vec::new());
+        {
+            let mut writer = zip::ZipWriter::new(&mut buf);
+            let options = zip::write::SimpleFileOptions::default();
+            writer.start_file("ppt/presentation.xml", options).unwrap();
+            writer
+                .write_all(
+                    br#"<p:presentation xmlns:p="p" xmlns:r="r"><p:sldIdLst>
+                        <p:sldId id="257" r:id="rId3"/><p:sldId id="256" r:id="rId2"/>
+                    </p:sldIdLst></p:presentation>"#,
+                )
+                .unwrap();
+            writer
+                .start_file("ppt/_rels/presentation.xml.rels", options)
+                .unwrap();
+            writer
+                .write_all(
+                    br#"<Relationships>
+                        <Relationship Id="rId2" Target="slides/slide1.xml"/>
+                        <Relationship Id="rId3" Target="slides/slide2.xml"/>
+                    </Relationships>"#,
+                )
+                .unwrap();
+            for (number, body) in [(1, "First file"), (2, "Second file")] {
+                writer
+                    .start_file(format!("ppt/slides/slide{}.xml", number), options)
+                    .unwrap();
+                write!(writer, "<p:sld><a:p><a:t>{}</a:t></a:p></p:sld>", body).unwrap();
+            }
+            writer.finish().unwrap();
+        }
+
+        let doc = extract_pptx(buf.get_ref(), None).unwrap();
+        assert_eq!(
+            doc.text,
+            "--- slide 1 ---\nSecond file\n\n--- slide 2 ---\nFirst file\n"
+        );
+    }
+
+    #[test]
+    fn odf_text_reads_paragraphs_and_headings() {
+        let xml = r#"<office:document-content><office:body><office:text>
+            <text:h>Title</text:h><text:p>One<text:s/>two<text:line-break/>three</text:p>
+        </office:text></office:body></office:document-content>"#;
+        let text = odf_text(xml).unwrap();
+        assert_eq!(text, "Title\nOne two\nthree\n");
+    }
+}
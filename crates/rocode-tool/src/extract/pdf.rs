@@ -0,0 +1,38 @@
+use super::PageSelection;
+use super::{describe_units, select_units, DocumentKind, ExtractError, ExtractedDocument};
+
+pub(super) fn extract(
+    content: &[u8],
+    pages: Option<&PageSelection>,
+) -> Result<ExtractedDocument, ExtractError> {
+    let mut doc = lopdf::Document::load_mem(content)
+        .map_err(|e| ExtractError::malformed(DocumentKind::Pdf, e))?;
+    if doc.is_encrypted() {
+        // Many "encrypted" PDFs only carry an owner password; an empty user
+        // password is enough to read them.
+        doc.decrypt("")
+            .map_err(|e| ExtractError::malformed(DocumentKind::Pdf, e))?;
+    }
+
+    let page_numbers: Vec<u32> = doc.get_pages().keys().copied().collect();
+    let total = page_numbers.len() as u32;
+    let selected = select_units(total, pages, "page")?;
+
+    let mut text = String::new();
+    for page in &selected {
+        let page_text = doc.extract_text(&[*page]).unwrap_or_default();
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&format!("--- page {} ---\n", page));
+        text.push_str(page_text.trim_end());
+        text.push('\n');
+    }
+
+    let summary = if selected.len() as u32 == total {
+        format!("{} pages", total)
+    } else {
+        format!("pages {} of {}", describe_units(&selected), total)
+    };
+    Ok(ExtractedDocument { text, summary })
+}
@@ -0,0 +1,214 @@
+use std::io::{Cursor, Read};
+
+use calamine::{open_workbook_auto_from_rs, Data, Reader};
+
+use super::{DocumentKind, ExtractError, ExtractedDocument};
+
+/// An A1-style rectangle such as `B2:D40`. Bounds are 0-indexed and inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellRange {
+    pub start: (u32, u32),
+    pub end: (u32, u32),
+}
+
+impl CellRange {
+    pub fn parse(input: &str) -> Result<Self, ExtractError> {
+        let invalid = || {
+            ExtractError::InvalidSelection(format!(
+                "'{}' is not a valid cell range (expected e.g. 'A1:D20')",
+                input
+            ))
+        };
+        let (start, end) = match input.trim().split_once(':') {
+            Some((start, end)) => (parse_cell(start), parse_cell(end)),
+            None => (parse_cell(input), parse_cell(input)),
+        };
+        let (start, end) = (start.ok_or_else(invalid)?, end.ok_or_else(invalid)?);
+        Ok(Self {
+            start: (start.0.min(end.0), start.1.min(end.1)),
+            end: (start.0.max(end.0), start.1.max(end.1)),
+        })
+    }
+
+    fn contains(&self, row: u32, col: u32) -> bool {
+        (self.start.0..=self.end.0).contains(&row) && (self.start.1..=self.end.1).contains(&col)
+    }
+}
+
+/// Parses `C12` into `(row 11, column 2)`.
+fn parse_cell(cell: &str) -> Option<(u32, u32)> {
+    let cell = cell.trim().to_ascii_uppercase();
+    let split = cell.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = cell.split_at(split);
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+    let col = letters.bytes().try_fold(0u32, |acc, b| {
+        acc.checked_mul(26)?.checked_add((b - b'A' + 1) as u32)
+    })?;
+    let row: u32 = digits.parse().ok()?;
+    if row == 0 {
+        return None;
+    }
+    Some((row - 1, col - 1))
+}
+
+fn column_name(mut col: u32) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (col % 26) as u8);
+        if col < 26 {
+            break;
+        }
+        col = col / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// Largest total decompressed size of a zipped workbook (xlsx, ods).
+const MAX_WORKBOOK_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Inflates every entry of a zipped workbook into a sink, stopping once the
+/// total passes `limit`, so a zip bomb is rejected before the spreadsheet
+/// reader loads it into memory. Declared entry sizes are not trusted.
+fn check_workbook_size(content: &[u8], limit: u64) -> Result<(), ExtractError> {
+    let kind = DocumentKind::Spreadsheet;
+    let Ok(mut archive) = zip::ZipArchive::new(Cursor::new(content)) else {
+        // Not a zip (legacy .xls); its size is bounded by the file itself.
+        return Ok(());
+    };
+    let mut total = 0u64;
+    for index in 0..archive.len() {
+        let entry = archive
+            .by_index(index)
+            .map_err(|e| ExtractError::malformed(kind, e))?;
+        total += std::io::copy(&mut entry.take(limit - total + 1), &mut std::io::sink())
+            .map_err(|e| ExtractError::malformed(kind, e))?;
+        if total > limit {
+            return Err(ExtractError::malformed(
+                kind,
+                format!("decompressed workbook exceeds {} bytes", limit),
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub(super) fn extract(
+    content: &[u8],
+    sheet: Option<&str>,
+    range: Option<&CellRange>,
+) -> Result<ExtractedDocument, ExtractError> {
+    let kind = DocumentKind::Spreadsheet;
+    check_workbook_size(content, MAX_WORKBOOK_BYTES)?;
+    let mut workbook = open_workbook_auto_from_rs(Cursor::new(content))
+        .map_err(|e| ExtractError::malformed(kind, e))?;
+    let sheet_names = workbook.sheet_names();
+
+    let targets: Vec<String> = match sheet {
+        Some(name) => {
+            let found = sheet_names
+                .iter()
+                .find(|s| s.eq_ignore_ascii_case(name))
+                .cloned()
+                .ok_or_else(|| {
+                    ExtractError::InvalidSelection(format!(
+                        "sheet '{}' not found (available: {})",
+                        name,
+                        sheet_names.join(", ")
+                    ))
+                })?;
+            vec![found]
+        }
+        None => sheet_names.clone(),
+    };
+
+    let mut text = String::new();
+    for name in &targets {
+        let cells = workbook
+            .worksheet_range(name)
+            .map_err(|e| ExtractError::malformed(kind, e))?;
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&format!("--- sheet {} ---\n", name));
+        let Some((first_row, first_col)) = cells.start() else {
+            text.push_str("(empty)\n");
+            continue;
+        };
+        for (row_offset, row) in cells.rows().enumerate() {
+            let row_index = first_row + row_offset as u32;
+            let values: Vec<(u32, &Data)> = row
+                .iter()
+                .enumerate()
+                .map(|(col_offset, value)| (first_col + col_offset as u32, value))
+                .filter(|(col, _)| range.map(|r| r.contains(row_index, *col)).unwrap_or(true))
+                .collect();
+            if values.is_empty() || values.iter().all(|(_, v)| matches!(v, Data::Empty)) {
+                continue;
+            }
+            let first = values[0].0;
+            let rendered: Vec<String> = values
+                .iter()
+                .map(|(_, v)| v.to_string().replace(['\n', '\t'], " "))
+                .collect();
+            text.push_str(&format!(
+                "{}{}\t{}\n",
+                column_name(first),
+                row_index + 1,
+                rendered.join("\t")
+            ));
+        }
+    }
+
+    let summary = if targets.len() == sheet_names.len() {
+        format!("{} sheets: {}", sheet_names.len(), sheet_names.join(", "))
+    } else {
+        format!("sheet {} of {}", targets.join(", "), sheet_names.join(", "))
+    };
+    Ok(ExtractedDocument { text, summary })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cell_range_parses_a1_notation() {
+        let range = CellRange::parse("b2:AA10").unwrap();
+        assert_eq!(range.start, (1, 1));
+        assert_eq!(range.end, (9, 26));
+        assert!(range.contains(5, 3));
+        assert!(!range.contains(0, 3));
+        assert!(CellRange::parse("2B").is_err());
+        assert!(CellRange::parse("A0").is_err());
+    }
+
+    #[test]
+    fn oversized_workbooks_are_rejected() {
+        use std::io::Write;
+
+        let mut buf = Cursor::new(Vec::new());
+        {
+            let mut writer = zip::ZipWriter::new(&mut buf);
+            let options = zip::write::SimpleFileOptions::default();
+            writer.start_file("xl/sharedStrings.xml", options).unwrap();
+            writer.write_all(&[b' '; 4096]).unwrap();
+            writer.finish().unwrap();
+        }
+        let content = buf.into_inner();
+
+        assert!(check_workbook_size(&content, 4096).is_ok());
+        let error = check_workbook_size(&content, 1024).unwrap_err();
+        assert!(error.to_string().contains("exceeds 1024 bytes"), "{error}");
+    }
+
+    #[test]
+    fn column_names_round_trip() {
+        for (col, name) in [(0, "A"), (25, "Z"), (26, "AA"), (701, "ZZ"), (702, "AAA")] {
+            assert_eq!(column_name(col), name);
+            assert_eq!(parse_cell(&format!("{}1", name)), Some((0, col)));
+        }
+    }
+}
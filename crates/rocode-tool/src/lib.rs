@@ -4,6 +4,7 @@ pub mod batch;
 pub mod codesearch;
 pub mod edit;
 pub mod external_directory;
+pub mod extract;
 pub mod glob_tool;
 pub mod grep_tool;
 pub mod invalid;
@@ -15,6 +16,7 @@ pub mod plan;
 pub mod question;
 pub mod read;
 pub mod registry;
+pub mod semantic;
 pub mod skill;
 pub mod task;
 pub mod todo;
@@ -3,6 +3,7 @@ use std::path::{Path, PathBuf};
 use tokio::fs;
 use walkdir::WalkDir;
 
+use crate::extract::{self, CellRange, DocumentKind, ExtractError, ExtractOptions, PageSelection};
 use crate::path_guard::{resolve_user_path, RootPathFallbackPolicy};
 use crate::{Metadata, Tool, ToolContext, ToolError, ToolResult};
 
@@ -71,6 +72,23 @@ impl Tool for ReadTool {
                 "limit": {
                     "type": "number",
                     "description": "The maximum number of lines to read (defaults to 2000)"
+                },
+                "format": {
+                    "type": "string",
+                    "enum": ["auto", "text", "attachment"],
+                    "description": "How to return PDFs and images: 'text' extracts text locally, 'attachment' sends the raw file to the model, 'auto' (default) picks based on what the model accepts"
+                },
+                "pages": {
+                    "type": "string",
+                    "description": "Pages (PDF) or slides (presentations) to extract, e.g. '1-5' or '2,4,7-9'"
+                },
+                "sheet": {
+                    "type": "string",
+                    "description": "Spreadsheet sheet name to extract (defaults to all sheets)"
+                },
+                "range": {
+                    "type": "string",
+                    "description": "Spreadsheet cell range to extract, e.g. 'A1:F50'"
                 }
             },
             "required": ["file_path"]
@@ -111,6 +129,9 @@ impl Tool for ReadTool {
             return Err(ToolError::InvalidArguments("offset must be >= 1".into()));
         }
 
+        let format = ReadFormat::parse(args.get("format").and_then(|v| v.as_str()))?;
+        let extract_options = parse_extract_options(&args)?;
+
         let base_dir = if ctx.directory.is_empty() {
             &self.directory
         } else {
@@ -200,13 +221,55 @@ impl Tool for ReadTool {
             .map_err(|e| ToolError::ExecutionError(format!("Failed to read file: {}", e)))?;
 
         let mime = detect_mime(&path);
+        let modalities = input_modalities(&ctx);
 
-        if is_image_mime(&mime) || mime == "application/pdf" {
+        if is_image_mime(&mime) {
             ctx.do_file_time_read(path_str.clone()).await?;
             ctx.do_lsp_touch_file(path_str.clone(), false).await?;
+            if format == ReadFormat::Text {
+                return Err(ToolError::InvalidArguments(
+                    "Text cannot be extracted from images; omit 'format' to attach the image"
+                        .into(),
+                ));
+            }
+            if format == ReadFormat::Auto && !accepts_modality(modalities.as_deref(), "image") {
+                return Ok(unsupported_image_result(&path, &content, &mime, title));
+            }
             return handle_binary_file(&path, &content, &mime, title);
         }
 
+        if let Some(kind) = DocumentKind::from_path(&path) {
+            ctx.do_file_time_read(path_str.clone()).await?;
+            ctx.do_lsp_touch_file(path_str.clone(), false).await?;
+            let attach = match format {
+                ReadFormat::Attachment if kind == DocumentKind::Pdf => true,
+                ReadFormat::Attachment => {
+                    return Err(ToolError::InvalidArguments(format!(
+                        "Only images and PDFs can be read as attachments; {} files are always extracted as text",
+                        kind.label()
+                    )))
+                }
+                ReadFormat::Text => false,
+                ReadFormat::Auto => {
+                    kind == DocumentKind::Pdf && accepts_modality(modalities.as_deref(), "pdf")
+                }
+            };
+            if attach {
+                return handle_binary_file(&path, &content, &mime, title);
+            }
+            return read_extracted_document(
+                &path,
+                kind,
+                content,
+                &mime,
+                extract_options,
+                offset,
+                limit,
+                title,
+            )
+            .await;
+        }
+
         if is_binary(&content) {
             return Err(ToolError::BinaryFile(path.display().to_string()));
         }
@@ -244,6 +307,20 @@ fn detect_mime(path: &Path) -> String {
         "avif" => "image/avif",
         "heic" | "heif" => "image/heic",
         "pdf" => "application/pdf",
+        "docx" | "docm" => {
+            "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+        }
+        "pptx" | "pptm" => {
+            "application/vnd.openxmlformats-officedocument.presentationml.presentation"
+        }
+        "xlsx" | "xlsm" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "xls" => "application/vnd.ms-excel",
+        "odt" => "application/vnd.oasis.opendocument.text",
+        "odp" => "application/vnd.oasis.opendocument.presentation",
+        "ods" => "application/vnd.oasis.opendocument.spreadsheet",
+        "zip" | "jar" | "whl" | "nupkg" => "application/zip",
+        "tar" => "application/x-tar",
+        "gz" | "tgz" => "application/gzip",
         "json" => "application/json",
         "html" | "htm" => "text/html",
         "css" => "text/css",
@@ -262,6 +339,167 @@ fn is_image_mime(mime: &str) -> bool {
     mime.starts_with("image/") && mime != "image/svg+xml" && mime != "image/vnd.fastbidsheet"
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReadFormat {
+    Auto,
+    Text,
+    Attachment,
+}
+
+impl ReadFormat {
+    fn parse(value: Option<&str>) -> Result<Self, ToolError> {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            None | Some("") | Some("auto") => Ok(Self::Auto),
+            Some("text") => Ok(Self::Text),
+            Some("attachment") => Ok(Self::Attachment),
+            Some(other) => Err(ToolError::InvalidArguments(format!(
+                "Unknown format '{}'. Expected one of: auto, text, attachment",
+                other
+            ))),
+        }
+    }
+}
+
+fn parse_extract_options(args: &serde_json::Value) -> Result<ExtractOptions, ToolError> {
+    let text_arg = |key: &str| {
+        args.get(key)
+            .and_then(|v| match v {
+                serde_json::Value::String(s) => Some(s.trim().to_string()),
+                serde_json::Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+            .filter(|s| !s.is_empty())
+    };
+    let invalid = |e: ExtractError| ToolError::InvalidArguments(e.to_string());
+
+    Ok(ExtractOptions {
+        pages: text_arg("pages")
+            .map(|p| PageSelection::parse(&p))
+            .transpose()
+            .map_err(invalid)?,
+        sheet: text_arg("sheet"),
+        range: text_arg("range")
+            .map(|r| CellRange::parse(&r))
+            .transpose()
+            .map_err(invalid)?,
+    })
+}
+
+/// Input modalities of the model driving this session, when the caller knows
+/// them (see `inputModalities` in `ToolContext::extra`).
+fn input_modalities(ctx: &ToolContext) -> Option<Vec<String>> {
+    ctx.extra
+        .get("inputModalities")
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|v| v.as_str().map(str::to_string))
+                .collect()
+        })
+}
+
+/// Images are attached unless the model is known to lack vision; PDFs are
+/// only attached when the model explicitly accepts them, since local
+/// extraction is far cheaper in context.
+fn accepts_modality(modalities: Option<&[String]>, modality: &str) -> bool {
+    match modalities {
+        Some(list) => list.iter().any(|m| m == modality),
+        None => modality == "image",
+    }
+}
+
+fn unsupported_image_result(path: &Path, content: &[u8], mime: &str, title: String) -> ToolResult {
+    let msg = "Image not attached: the active model does not accept image input";
+    let output = format!(
+        "<path>{}</path>\n<type>binary</type>\n<mime>{}</mime>\n<size>{}</size>\n<total-lines>0</total-lines>\n<content>\n{}\n</content>",
+        path.display(),
+        mime,
+        content.len(),
+        msg
+    );
+    ToolResult {
+        title,
+        output,
+        metadata: {
+            let mut m = Metadata::new();
+            m.insert("preview".into(), serde_json::json!(msg));
+            m.insert("truncated".into(), serde_json::json!(false));
+            m.insert("mime".into(), serde_json::json!(mime));
+            m.insert("size".into(), serde_json::json!(content.len()));
+            m
+        },
+        truncated: false,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn read_extracted_document(
+    path: &Path,
+    kind: DocumentKind,
+    content: Vec<u8>,
+    mime: &str,
+    options: ExtractOptions,
+    offset: usize,
+    limit: usize,
+    title: String,
+) -> Result<ToolResult, ToolError> {
+    let size = content.len();
+    let document = tokio::task::spawn_blocking(move || extract::extract(kind, &content, &options))
+        .await
+        .map_err(|e| ToolError::ExecutionError(format!("Text extraction failed: {}", e)))?
+        .map_err(|e| match e {
+            ExtractError::InvalidSelection(msg) => ToolError::InvalidArguments(msg),
+            other => ToolError::ExecutionError(other.to_string()),
+        })?;
+
+    let text = if document.text.trim().is_empty() {
+        "(no text content)"
+    } else {
+        document.text.as_str()
+    };
+    let lines: Vec<&str> = text.lines().collect();
+    let has_text = lines
+        .iter()
+        .any(|l| !l.trim().is_empty() && !l.starts_with("--- "));
+    let window = window_lines(&lines, offset, limit)?;
+    let mut truncation_msg = window.truncation_message();
+    if !has_text && kind == DocumentKind::Pdf {
+        truncation_msg.push_str(
+            "\n(No extractable text found; the PDF may be scanned. Retry with format='attachment' if the model accepts PDF input.)",
+        );
+    }
+
+    let output = format!(
+        "<path>{}</path>\n<type>{}</type>\n<mime>{}</mime>\n<size>{}</size>\n<extracted>{}</extracted>\n<total-lines>{}</total-lines>\n<content>\n{}{}\n</content>",
+        path.display(),
+        kind.label(),
+        mime,
+        size,
+        document.summary,
+        window.total_lines,
+        window.lines.join("\n"),
+        truncation_msg
+    );
+
+    Ok(ToolResult {
+        title,
+        output,
+        metadata: {
+            let mut m = Metadata::new();
+            m.insert("preview".into(), serde_json::json!(window.preview()));
+            m.insert("truncated".into(), serde_json::json!(window.truncated()));
+            m.insert("filepath".into(), serde_json::json!(path.to_string_lossy()));
+            m.insert("mime".into(), serde_json::json!(mime));
+            m.insert("size".into(), serde_json::json!(size));
+            m.insert("extracted".into(), serde_json::json!(document.summary));
+            m.insert("total_lines".into(), serde_json::json!(window.total_lines));
+            m
+        },
+        truncated: window.truncated(),
+    })
+}
+
 fn handle_binary_file(
     path: &Path,
     content: &[u8],
@@ -399,68 +637,18 @@ async fn read_file_content(
     let text = String::from_utf8_lossy(content);
     let lines: Vec<&str> = text.lines().collect();
 
-    if offset > lines.len() {
-        return Err(ToolError::InvalidArguments(format!(
-            "Offset {} is out of range (file has {} lines)",
-            offset,
-            lines.len()
-        )));
-    }
-
-    let start = offset.saturating_sub(1);
-    let mut result_lines: Vec<String> = Vec::new();
-    let mut bytes = 0;
-    let mut truncated_by_bytes = false;
-
-    for i in start..std::cmp::min(lines.len(), start + limit) {
-        let line = if lines[i].len() > MAX_LINE_LENGTH {
-            format!("{}...", &lines[i][..MAX_LINE_LENGTH])
-        } else {
-            lines[i].to_string()
-        };
-
-        let size = line.len() + if result_lines.is_empty() { 0 } else { 1 };
-        if bytes + size > MAX_BYTES {
-            truncated_by_bytes = true;
-            break;
-        }
-
-        result_lines.push(format!("{}: {}", i + 1, line));
-        bytes += size;
-    }
-
-    let preview = result_lines
-        .iter()
-        .take(20)
-        .cloned()
-        .collect::<Vec<_>>()
-        .join("\n");
-    let total_lines = lines.len();
-    let last_read_line = offset + result_lines.len() - 1;
-    let has_more_lines = total_lines > last_read_line;
-    let truncated = has_more_lines || truncated_by_bytes;
-
-    let truncation_msg = if truncated_by_bytes {
-        format!(
-            "\n\n(Output truncated at {} bytes. Use 'offset' parameter to read beyond line {})",
-            MAX_BYTES, last_read_line
-        )
-    } else if has_more_lines {
-        format!(
-            "\n\n(File has more lines. Use 'offset' parameter to read beyond line {})",
-            last_read_line
-        )
-    } else {
-        format!("\n\n(End of file - total {} lines)", total_lines)
-    };
+    let window = window_lines(&lines, offset, limit)?;
+    let preview = window.preview();
+    let total_lines = window.total_lines;
+    let truncated = window.truncated();
 
     let mut output = format!(
         "<path>{}</path>\n<type>file</type>\n<size>{}</size>\n<total-lines>{}</total-lines>\n<content>\n{}{}\n</content>",
         path.display(),
         content.len(),
         total_lines,
-        result_lines.join("\n"),
-        truncation_msg
+        window.lines.join("\n"),
+        window.truncation_message()
     );
 
     let project_root_path = PathBuf::from(project_root);
@@ -499,6 +687,95 @@ async fn read_file_content(
     })
 }
 
+/// A numbered slice of lines as returned to the model, shared by plain file
+/// reads and extracted documents so `offset`/`limit` paging behaves the same.
+struct LineWindow {
+    lines: Vec<String>,
+    total_lines: usize,
+    last_read_line: usize,
+    truncated_by_bytes: bool,
+}
+
+impl LineWindow {
+    fn has_more_lines(&self) -> bool {
+        self.total_lines > self.last_read_line
+    }
+
+    fn truncated(&self) -> bool {
+        self.has_more_lines() || self.truncated_by_bytes
+    }
+
+    fn preview(&self) -> String {
+        self.lines
+            .iter()
+            .take(20)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn truncation_message(&self) -> String {
+        if self.truncated_by_bytes {
+            format!(
+                "\n\n(Output truncated at {} bytes. Use 'offset' parameter to read beyond line {})",
+                MAX_BYTES, self.last_read_line
+            )
+        } else if self.has_more_lines() {
+            format!(
+                "\n\n(File has more lines. Use 'offset' parameter to read beyond line {})",
+                self.last_read_line
+            )
+        } else {
+            format!("\n\n(End of file - total {} lines)", self.total_lines)
+        }
+    }
+}
+
+fn window_lines(lines: &[&str], offset: usize, limit: usize) -> Result<LineWindow, ToolError> {
+    if offset > lines.len() {
+        return Err(ToolError::InvalidArguments(format!(
+            "Offset {} is out of range (file has {} lines)",
+            offset,
+            lines.len()
+        )));
+    }
+
+    let start = offset.saturating_sub(1);
+    let mut result_lines: Vec<String> = Vec::new();
+    let mut bytes = 0;
+    let mut truncated_by_bytes = false;
+
+    for (i, raw) in lines
+        .iter()
+        .enumerate()
+        .take(std::cmp::min(lines.len(), start + limit))
+        .skip(start)
+    {
+        let line = if raw.len() > MAX_LINE_LENGTH {
+            format!("{}...", &raw[..raw.floor_char_boundary(MAX_LINE_LENGTH)])
+        } else {
+            raw.to_string()
+        };
+
+        let size = line.len() + if result_lines.is_empty() { 0 } else { 1 };
+        if bytes + size > MAX_BYTES {
+            truncated_by_bytes = true;
+            break;
+        }
+
+        result_lines.push(format!("{}: {}", i + 1, line));
+        bytes += size;
+    }
+
+    let last_read_line = (offset + result_lines.len()).saturating_sub(1);
+    Ok(LineWindow {
+        lines: result_lines,
+        total_lines: lines.len(),
+        last_read_line,
+        truncated_by_bytes,
+    })
+}
+
 fn is_binary(content: &[u8]) -> bool {
     if content.is_empty() {
         return false;
@@ -611,6 +888,60 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn read_extracts_archive_listing_with_paging() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("bundle.zip");
+        {
+            let file = std::fs::File::create(&archive_path).unwrap();
+            let mut writer = zip::ZipWriter::new(file);
+            let options = zip::write::SimpleFileOptions::default();
+            for name in ["a.txt", "b.txt", "c.txt"] {
+                writer.start_file(name, options).unwrap();
+                writer.write_all(name.as_bytes()).unwrap();
+            }
+            writer.finish().unwrap();
+        }
+
+        let tool = ReadTool::new();
+        let ctx = ToolContext::new(
+            "session-1".to_string(),
+            "message-1".to_string(),
+            dir.path().to_string_lossy().to_string(),
+        );
+        let result = tool
+            .execute(
+                serde_json::json!({ "file_path": "bundle.zip", "offset": 2, "limit": 1 }),
+                ctx.clone(),
+            )
+            .await
+            .expect("archive read should succeed");
+        assert!(result.output.contains("<type>archive</type>"));
+        assert!(result.output.contains("2: b.txt\t5"));
+        assert!(!result.output.contains("a.txt"));
+        assert!(result.truncated);
+
+        let err = tool
+            .execute(
+                serde_json::json!({ "file_path": "bundle.zip", "format": "attachment" }),
+                ctx,
+            )
+            .await
+            .expect_err("archives cannot be attached");
+        assert!(matches!(err, ToolError::InvalidArguments(_)));
+    }
+
+    #[test]
+    fn pdf_attachment_requires_declared_modality() {
+        assert!(accepts_modality(None, "image"));
+        assert!(!accepts_modality(None, "pdf"));
+        let modalities = vec!["text".to_string(), "pdf".to_string()];
+        assert!(accepts_modality(Some(&modalities), "pdf"));
+        assert!(!accepts_modality(Some(&modalities), "image"));
+    }
+
     #[test]
     fn binary_read_keeps_output_compact_and_moves_payload_to_metadata_attachments() {
         let path = Path::new("/tmp/sample.pdf");
@@ -549,6 +549,8 @@ pub async fn create_default_registry() -> ToolRegistry {
     registry.register(crate::grep_tool::GrepTool::new()).await;
     registry.register(crate::ls::LsTool::new()).await;
     registry.register(crate::task::TaskTool::new()).await;
+    registry.register(crate::task::TaskStatusTool).await;
+    registry.register(crate::task::TaskResultTool).await;
     registry
         .register(crate::question::QuestionTool::new())
         .await;
@@ -566,6 +568,7 @@ pub async fn create_default_registry() -> ToolRegistry {
     registry.register(crate::lsp_tool::LspTool).await;
     registry.register(crate::batch::BatchTool).await;
     registry.register(crate::codesearch::CodeSearchTool).await;
+    registry.register(crate::semantic::SemanticSearchTool).await;
     registry.register(crate::plan::PlanEnterTool).await;
     registry.register(crate::plan::PlanExitTool).await;
     registry.register(crate::invalid::InvalidTool).await;
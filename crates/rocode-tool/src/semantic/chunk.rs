@@ -0,0 +1,291 @@
+use std::path::Path;
+
+/// Segments are merged until a chunk reaches roughly this many lines.
+const TARGET_LINES: usize = 60;
+/// Segments shorter than this are always merged into their neighbour.
+const MIN_LINES: usize = 8;
+/// Hard cap; longer items are split into windows of this size.
+const MAX_LINES: usize = 120;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Chunk {
+    /// 1-indexed, inclusive.
+    pub start_line: usize,
+    pub end_line: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Syntax {
+    Braces,
+    Indent,
+    Markdown,
+    Plain,
+}
+
+fn syntax_for(path: &Path) -> Syntax {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "rs" | "c" | "h" | "cc" | "cpp" | "hpp" | "cs" | "go" | "java" | "kt" | "kts" | "scala"
+        | "swift" | "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" | "php" | "dart" | "zig"
+        | "css" | "scss" | "less" | "proto" | "sol" => Syntax::Braces,
+        "py" | "pyi" | "rb" | "ex" | "exs" | "yaml" | "yml" | "toml" | "nim" | "lua" | "sh"
+        | "bash" | "zsh" => Syntax::Indent,
+        "md" | "mdx" | "markdown" | "rst" | "adoc" => Syntax::Markdown,
+        _ => Syntax::Plain,
+    }
+}
+
+/// Splits a file into chunks that follow its top-level structure: items in
+/// brace languages, unindented blocks in indentation languages, sections in
+/// markdown, and blank-line separated paragraphs otherwise.
+pub fn chunk_source(path: &Path, content: &str) -> Vec<Chunk> {
+    let lines: Vec<&str> = content.lines().collect();
+    if lines.is_empty() {
+        return Vec::new();
+    }
+
+    let boundaries = match syntax_for(path) {
+        Syntax::Braces => brace_boundaries(&lines),
+        Syntax::Indent => indent_boundaries(&lines),
+        Syntax::Markdown => markdown_boundaries(&lines),
+        Syntax::Plain => paragraph_boundaries(&lines),
+    };
+
+    let mut segments: Vec<(usize, usize)> = Vec::new();
+    let mut start = 0;
+    for boundary in boundaries.into_iter().filter(|b| *b > 0) {
+        if boundary > start {
+            segments.push((start, boundary));
+            start = boundary;
+        }
+    }
+    segments.push((start, lines.len()));
+
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (seg_start, seg_end) in segments {
+        match merged.last_mut() {
+            Some((cur_start, cur_end)) => {
+                let current_len = *cur_end - *cur_start;
+                let segment_len = seg_end - seg_start;
+                let combined_len = seg_end - *cur_start;
+                if current_len < MIN_LINES
+                    || (segment_len < MIN_LINES && combined_len <= MAX_LINES)
+                    || combined_len <= TARGET_LINES
+                {
+                    *cur_end = seg_end;
+                } else {
+                    merged.push((seg_start, seg_end));
+                }
+            }
+            None => merged.push((seg_start, seg_end)),
+        }
+    }
+
+    let mut chunks = Vec::new();
+    for (start, end) in merged {
+        let mut window_start = start;
+        while window_start < end {
+            let window_end = (window_start + MAX_LINES).min(end);
+            if let Some(chunk) = make_chunk(&lines, window_start, window_end) {
+                chunks.push(chunk);
+            }
+            window_start = window_end;
+        }
+    }
+    chunks
+}
+
+fn make_chunk(lines: &[&str], start: usize, end: usize) -> Option<Chunk> {
+    let mut start = start;
+    let mut end = end;
+    while start < end && lines[start].trim().is_empty() {
+        start += 1;
+    }
+    while end > start && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+    if start == end {
+        return None;
+    }
+    Some(Chunk {
+        start_line: start + 1,
+        end_line: end,
+        text: lines[start..end].join("\n"),
+    })
+}
+
+/// Net brace depth change of a line, ignoring string literals and `//`
+/// comments. Good enough for chunking; it does not need to be exact.
+fn brace_delta(line: &str) -> i32 {
+    let mut delta = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == q {
+                quote = None;
+            }
+            continue;
+        }
+        match ch {
+            '"' | '`' => quote = Some(ch),
+            '/' if chars.peek() == Some(&'/') => break,
+            '{' => delta += 1,
+            '}' => delta -= 1,
+            _ => {}
+        }
+    }
+    delta
+}
+
+/// A top-level item starts at depth zero after a blank line or after the
+/// previous item closed, so doc comments and attributes stay with the item
+/// they describe.
+fn brace_boundaries(lines: &[&str]) -> Vec<usize> {
+    let mut boundaries = Vec::new();
+    let mut depth = 0i32;
+    let mut previous_closed = false;
+    let mut previous_blank = true;
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            previous_blank = true;
+            continue;
+        }
+        if depth <= 0 && (previous_blank || previous_closed) && !trimmed.starts_with('}') {
+            boundaries.push(index);
+        }
+        let delta = brace_delta(line);
+        let before = depth;
+        depth = (depth + delta).max(0);
+        previous_closed = depth == 0 && (before > 0 || trimmed.ends_with(';'));
+        previous_blank = false;
+    }
+    boundaries
+}
+
+fn indent_boundaries(lines: &[&str]) -> Vec<usize> {
+    let mut boundaries = Vec::new();
+    let mut previous_indented = false;
+    let mut previous_blank = true;
+    for (index, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            previous_blank = true;
+            continue;
+        }
+        let indented = line.starts_with(' ') || line.starts_with('\t');
+        if !indented && (previous_blank || previous_indented) {
+            boundaries.push(index);
+        }
+        previous_indented = indented;
+        previous_blank = false;
+    }
+    boundaries
+}
+
+fn markdown_boundaries(lines: &[&str]) -> Vec<usize> {
+    let mut in_fence = false;
+    let mut boundaries = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        } else if !in_fence && trimmed.starts_with('#') {
+            boundaries.push(index);
+        }
+    }
+    boundaries
+}
+
+fn paragraph_boundaries(lines: &[&str]) -> Vec<usize> {
+    let mut boundaries = Vec::new();
+    let mut previous_blank = false;
+    for (index, line) in lines.iter().enumerate() {
+        let blank = line.trim().is_empty();
+        if !blank && previous_blank {
+            boundaries.push(index);
+        }
+        previous_blank = blank;
+    }
+    boundaries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rust_item(name: &str, body_lines: usize) -> String {
+        let mut out = format!("/// Docs for {name}.\n#[inline]\nfn {name}() {{\n");
+        for i in 0..body_lines {
+            out.push_str(&format!("    let v{i} = \"{{\";\n"));
+        }
+        out.push_str("}\n\n");
+        out
+    }
+
+    #[test]
+    fn brace_chunks_keep_doc_comments_with_items() {
+        let source = format!("{}{}", rust_item("alpha", 50), rust_item("beta", 50));
+        let chunks = chunk_source(Path::new("lib.rs"), &source);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].text.starts_with("/// Docs for alpha."));
+        assert!(chunks[1].text.starts_with("/// Docs for beta."));
+        assert_eq!(chunks[1].start_line, 56);
+        assert!(chunks[1].text.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn small_items_are_merged() {
+        let source = format!(
+            "use a;\nuse b;\n\n{}{}",
+            rust_item("one", 2),
+            rust_item("two", 2)
+        );
+        let chunks = chunk_source(Path::new("lib.rs"), &source);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].start_line, 1);
+    }
+
+    #[test]
+    fn oversized_items_are_windowed() {
+        let source = rust_item("huge", 300);
+        let chunks = chunk_source(Path::new("lib.rs"), &source);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| c.end_line - c.start_line < MAX_LINES));
+    }
+
+    #[test]
+    fn markdown_splits_on_headings_outside_fences() {
+        let mut source = String::from("# Intro\n");
+        source.push_str(&"text\n".repeat(70));
+        source.push_str("```sh\n# not a heading\n```\n## Usage\n");
+        source.push_str(&"more\n".repeat(10));
+        let chunks = chunk_source(Path::new("README.md"), &source);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].text.contains("# not a heading"));
+        assert!(chunks[1].text.starts_with("## Usage"));
+    }
+
+    #[test]
+    fn python_splits_on_top_level_definitions() {
+        let mut source = String::new();
+        for name in ["a", "b"] {
+            source.push_str(&format!("@decorator\ndef {name}():\n"));
+            source.push_str(&"    pass\n".repeat(40));
+            source.push('\n');
+        }
+        let chunks = chunk_source(Path::new("mod.py"), &source);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[1].text.starts_with("@decorator\ndef b"));
+    }
+}
@@ -0,0 +1,617 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::overrides::{Override, OverrideBuilder};
+
+use rocode_provider::EmbeddingModel;
+use sha2::{Digest, Sha256};
+use tokio_util::sync::CancellationToken;
+
+use super::chunk::{chunk_source, Chunk};
+use super::store::{store_path, ChunkRecord, FileRecord, ScoredChunk, VectorStore};
+use crate::ToolError;
+
+const MAX_FILE_BYTES: u64 = 256 * 1024;
+const MAX_EMBED_CHARS: usize = 6000;
+const DEFAULT_BATCH_SIZE: usize = 64;
+
+/// Paths that are almost never useful to search semantically, in addition to
+/// whatever `.gitignore` already excludes.
+const DEFAULT_IGNORES: &[&str] = &[
+    "**/node_modules/**",
+    "**/target/**",
+    "**/dist/**",
+    "**/.git/**",
+    "*.lock",
+    "package-lock.json",
+    "pnpm-lock.yaml",
+    "*.min.js",
+    "*.min.css",
+    "*.map",
+    "*.svg",
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct RefreshStats {
+    pub files: usize,
+    pub chunks: usize,
+    pub embedded_files: usize,
+    pub embedded_chunks: usize,
+    pub removed_files: usize,
+}
+
+struct ScannedFile {
+    rel_path: String,
+    modified: i64,
+    size: u64,
+}
+
+struct PendingFile {
+    rel_path: String,
+    modified: i64,
+    size: u64,
+    hash: String,
+    chunks: Vec<Chunk>,
+}
+
+/// Result of comparing the working tree against the stored index.
+#[derive(Default)]
+struct RefreshPlan {
+    seen: HashSet<String>,
+    /// Files whose metadata changed but whose content hash did not.
+    touched: Vec<(String, i64, u64)>,
+    pending: Vec<PendingFile>,
+}
+
+/// A per-project semantic index backed by a [`VectorStore`] on disk.
+pub struct SemanticIndex {
+    root: PathBuf,
+    store_path: PathBuf,
+    ignore: Vec<String>,
+    batch_size: usize,
+}
+
+impl SemanticIndex {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        Self {
+            store_path: store_path(&root),
+            root,
+            ignore: Vec::new(),
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    pub fn with_store_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.store_path = path.into();
+        self
+    }
+
+    pub fn with_ignore(mut self, patterns: Vec<String>) -> Self {
+        self.ignore = patterns;
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Brings the store up to date with the working tree, embedding only
+    /// files that are new or whose content changed. Progress is saved even
+    /// when embedding fails part-way, so the next refresh resumes.
+    pub async fn refresh(
+        &self,
+        model: &dyn EmbeddingModel,
+        abort: &CancellationToken,
+    ) -> Result<(VectorStore, RefreshStats), ToolError> {
+        self.refresh_scoped(model, abort, None).await
+    }
+
+    /// Like [`refresh`](Self::refresh), but only re-examines `paths` (files
+    /// or directories reported by a file watcher) instead of walking the
+    /// whole tree. Every other file keeps its stored record.
+    pub async fn refresh_paths(
+        &self,
+        model: &dyn EmbeddingModel,
+        abort: &CancellationToken,
+        paths: Vec<PathBuf>,
+    ) -> Result<(VectorStore, RefreshStats), ToolError> {
+        self.refresh_scoped(model, abort, Some(paths)).await
+    }
+
+    async fn refresh_scoped(
+        &self,
+        model: &dyn EmbeddingModel,
+        abort: &CancellationToken,
+        paths: Option<Vec<PathBuf>>,
+    ) -> Result<(VectorStore, RefreshStats), ToolError> {
+        let store_path = self.store_path.clone();
+        let model_id = model.id().to_string();
+        let root = self.root.clone();
+        let ignore = self.ignore.clone();
+
+        let (mut store, plan) = tokio::task::spawn_blocking(move || {
+            let store = VectorStore::load(&store_path, &model_id);
+            let plan = match paths {
+                Some(paths) => plan_paths(&root, &ignore, &store, &paths)?,
+                None => plan_refresh(&root, &ignore, &store)?,
+            };
+            Ok::<_, ToolError>((store, plan))
+        })
+        .await
+        .map_err(|e| ToolError::ExecutionError(format!("Index scan failed: {}", e)))??;
+
+        let mut stats = RefreshStats::default();
+        let mut dirty = false;
+
+        let before = store.files.len();
+        store.files.retain(|path, _| plan.seen.contains(path));
+        stats.removed_files = before - store.files.len();
+        dirty |= stats.removed_files > 0;
+
+        for (path, modified, size) in plan.touched {
+            if let Some(record) = store.files.get_mut(&path) {
+                record.modified = modified;
+                record.size = size;
+                dirty = true;
+            }
+        }
+
+        let result = self
+            .embed_pending(model, abort, plan.pending, &mut store, &mut stats)
+            .await;
+        dirty |= stats.embedded_files > 0;
+
+        if dirty {
+            let snapshot = store.clone();
+            let path = self.store_path.clone();
+            tokio::task::spawn_blocking(move || snapshot.save(&path))
+                .await
+                .map_err(|e| ToolError::ExecutionError(e.to_string()))?
+                .map_err(|e| {
+                    ToolError::ExecutionError(format!("Failed to save semantic index: {}", e))
+                })?;
+        }
+        result?;
+
+        stats.files = store.files.len();
+        stats.chunks = store.chunk_count();
+        Ok((store, stats))
+    }
+
+    async fn embed_pending(
+        &self,
+        model: &dyn EmbeddingModel,
+        abort: &CancellationToken,
+        pending: Vec<PendingFile>,
+        store: &mut VectorStore,
+        stats: &mut RefreshStats,
+    ) -> Result<(), ToolError> {
+        let mut inputs: Vec<(usize, String)> = Vec::new();
+        let mut vectors: HashMap<usize, Vec<Vec<f32>>> = HashMap::new();
+
+        for (file_index, file) in pending.iter().enumerate() {
+            if file.chunks.is_empty() {
+                continue;
+            }
+            for chunk in &file.chunks {
+                inputs.push((file_index, embedding_input(&file.rel_path, &chunk.text)));
+            }
+        }
+
+        let mut completed: HashSet<usize> = HashSet::new();
+        for batch in inputs.chunks(self.batch_size) {
+            if abort.is_cancelled() {
+                return Err(ToolError::Cancelled);
+            }
+            let texts: Vec<String> = batch.iter().map(|(_, text)| text.clone()).collect();
+            let embedded = model.embed(&texts).await.map_err(|e| {
+                ToolError::ExecutionError(format!("Embedding request failed: {}", e))
+            })?;
+            for ((file_index, _), vector) in batch.iter().zip(embedded) {
+                vectors.entry(*file_index).or_default().push(vector);
+            }
+            stats.embedded_chunks += batch.len();
+
+            // Commit every file whose chunks are now all embedded.
+            for (file_index, file) in pending.iter().enumerate() {
+                let done = vectors.get(&file_index).map(Vec::len).unwrap_or(0);
+                if !completed.contains(&file_index) && done == file.chunks.len() {
+                    completed.insert(file_index);
+                    let file_vectors = vectors.remove(&file_index).unwrap_or_default();
+                    store
+                        .files
+                        .insert(file.rel_path.clone(), to_record(file, file_vectors));
+                    stats.embedded_files += 1;
+                }
+            }
+        }
+
+        // Files without any chunk (e.g. whitespace only) are recorded too so
+        // they are not rescanned on every refresh.
+        for file in pending.iter().filter(|f| f.chunks.is_empty()) {
+            store
+                .files
+                .insert(file.rel_path.clone(), to_record(file, Vec::new()));
+        }
+        Ok(())
+    }
+
+    pub async fn search(
+        &self,
+        model: &dyn EmbeddingModel,
+        store: &VectorStore,
+        query: &str,
+        limit: usize,
+        filter: impl Fn(&str) -> bool,
+    ) -> Result<Vec<ScoredChunk>, ToolError> {
+        let embedded = model
+            .embed(&[query.to_string()])
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Embedding request failed: {}", e)))?;
+        let query_vector = embedded.into_iter().next().ok_or_else(|| {
+            ToolError::ExecutionError("Embedding endpoint returned no vector".into())
+        })?;
+        Ok(store.search(&query_vector, limit, filter))
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+fn to_record(file: &PendingFile, vectors: Vec<Vec<f32>>) -> FileRecord {
+    FileRecord {
+        modified: file.modified,
+        size: file.size,
+        hash: file.hash.clone(),
+        chunks: file
+            .chunks
+            .iter()
+            .zip(vectors)
+            .map(|(chunk, vector)| ChunkRecord {
+                start_line: chunk.start_line,
+                end_line: chunk.end_line,
+                vector,
+            })
+            .collect(),
+    }
+}
+
+fn embedding_input(rel_path: &str, text: &str) -> String {
+    let mut input = format!("{}\n{}", rel_path, text);
+    if input.len() > MAX_EMBED_CHARS {
+        let cut = input.floor_char_boundary(MAX_EMBED_CHARS);
+        input.truncate(cut);
+    }
+    input
+}
+
+fn content_hash(content: &[u8]) -> String {
+    Sha256::digest(content)
+        .iter()
+        .take(16)
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn plan_refresh(
+    root: &Path,
+    ignore: &[String],
+    store: &VectorStore,
+) -> Result<RefreshPlan, ToolError> {
+    let mut plan = RefreshPlan::default();
+    for file in scan_files(root, root, ignore)? {
+        plan_file(&mut plan, root, store, file);
+    }
+    Ok(plan)
+}
+
+/// Plans a refresh limited to `paths`. Files outside those paths are kept
+/// as stored; a path that no longer exists (or is now ignored) drops every
+/// record at or below it.
+fn plan_paths(
+    root: &Path,
+    ignore: &[String],
+    store: &VectorStore,
+    paths: &[PathBuf],
+) -> Result<RefreshPlan, ToolError> {
+    let mut plan = RefreshPlan {
+        seen: store.files.keys().cloned().collect(),
+        ..Default::default()
+    };
+    let matcher = PathMatcher::new(root, ignore)?;
+
+    let mut rel_paths: Vec<String> = paths
+        .iter()
+        .filter_map(|path| path.strip_prefix(root).ok())
+        .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+        .filter(|rel| !rel.is_empty())
+        .collect();
+    rel_paths.sort();
+    rel_paths.dedup();
+
+    for rel_path in rel_paths {
+        let absolute = root.join(&rel_path);
+        let is_dir = absolute.is_dir();
+        plan.seen.retain(|path| {
+            path != &rel_path
+                && !path
+                    .strip_prefix(rel_path.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        });
+        if !absolute.exists() || matcher.is_ignored(&absolute, is_dir) {
+            continue;
+        }
+        let files = if is_dir {
+            scan_files(root, &absolute, ignore)?
+        } else {
+            scan_file(root, &absolute).into_iter().collect()
+        };
+        for file in files {
+            plan_file(&mut plan, root, store, file);
+        }
+    }
+    Ok(plan)
+}
+
+fn plan_file(plan: &mut RefreshPlan, root: &Path, store: &VectorStore, file: ScannedFile) {
+    plan.seen.insert(file.rel_path.clone());
+    let existing = store.files.get(&file.rel_path);
+    if let Some(record) = existing {
+        if record.modified == file.modified && record.size == file.size {
+            return;
+        }
+    }
+
+    let Ok(content) = std::fs::read(root.join(&file.rel_path)) else {
+        plan.seen.remove(&file.rel_path);
+        return;
+    };
+    if content_inspector::inspect(&content).is_binary() {
+        plan.seen.remove(&file.rel_path);
+        return;
+    }
+    let hash = content_hash(&content);
+    if existing.map(|r| r.hash == hash).unwrap_or(false) {
+        plan.touched.push((file.rel_path, file.modified, file.size));
+        return;
+    }
+
+    let text = String::from_utf8_lossy(&content);
+    let chunks = chunk_source(Path::new(&file.rel_path), &text);
+    plan.pending.push(PendingFile {
+        rel_path: file.rel_path,
+        modified: file.modified,
+        size: file.size,
+        hash,
+        chunks,
+    });
+}
+
+fn build_overrides(root: &Path, ignore: &[String]) -> Result<Override, ToolError> {
+    let mut overrides = OverrideBuilder::new(root);
+    for pattern in DEFAULT_IGNORES
+        .iter()
+        .map(|p| p.to_string())
+        .chain(ignore.iter().cloned())
+    {
+        overrides
+            .add(&format!("!{}", pattern))
+            .map_err(|e| ToolError::InvalidArguments(format!("Invalid ignore pattern: {}", e)))?;
+    }
+    overrides
+        .build()
+        .map_err(|e| ToolError::InvalidArguments(format!("Invalid ignore pattern: {}", e)))
+}
+
+/// Applies the same exclusions as the directory walk to a single path:
+/// hidden components, ignore patterns and the root `.gitignore`.
+struct PathMatcher {
+    root: PathBuf,
+    overrides: Override,
+    gitignore: Gitignore,
+}
+
+impl PathMatcher {
+    fn new(root: &Path, ignore: &[String]) -> Result<Self, ToolError> {
+        let mut gitignore = GitignoreBuilder::new(root);
+        gitignore.add(root.join(".gitignore"));
+        gitignore.add(root.join(".git").join("info").join("exclude"));
+        Ok(Self {
+            root: root.to_path_buf(),
+            overrides: build_overrides(root, ignore)?,
+            gitignore: gitignore.build().unwrap_or_else(|_| Gitignore::empty()),
+        })
+    }
+
+    fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let Ok(rel) = path.strip_prefix(&self.root) else {
+            return true;
+        };
+        rel.components()
+            .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+            || self.overrides.matched(path, is_dir).is_ignore()
+            || self
+                .gitignore
+                .matched_path_or_any_parents(path, is_dir)
+                .is_ignore()
+    }
+}
+
+fn scan_files(root: &Path, start: &Path, ignore: &[String]) -> Result<Vec<ScannedFile>, ToolError> {
+    let walker = ignore::WalkBuilder::new(start)
+        .hidden(true)
+        .git_ignore(true)
+        .git_exclude(true)
+        .require_git(false)
+        .overrides(build_overrides(root, ignore)?)
+        .build();
+
+    Ok(walker
+        .filter_map(|e| e.ok())
+        .filter(|entry| entry.file_type().map(|t| t.is_file()).unwrap_or(false))
+        .filter_map(|entry| scan_file(root, entry.path()))
+        .collect())
+}
+
+fn scan_file(root: &Path, path: &Path) -> Option<ScannedFile> {
+    let metadata = std::fs::metadata(path).ok()?;
+    if !metadata.is_file() || metadata.len() == 0 || metadata.len() > MAX_FILE_BYTES {
+        return None;
+    }
+    let rel = path.strip_prefix(root).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0);
+    Some(ScannedFile {
+        rel_path: rel.to_string_lossy().replace('\\', "/"),
+        modified,
+        size: metadata.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use rocode_provider::ProviderError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Bag-of-words embedding over a tiny fixed vocabulary.
+    struct KeywordEmbeddings {
+        calls: AtomicUsize,
+        inputs: AtomicUsize,
+    }
+
+    const VOCAB: &[&str] = &["parser", "token", "http", "server", "database", "query"];
+
+    #[async_trait]
+    impl EmbeddingModel for KeywordEmbeddings {
+        fn id(&self) -> &str {
+            "test/keywords"
+        }
+
+        async fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, ProviderError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.inputs.fetch_add(inputs.len(), Ordering::SeqCst);
+            Ok(inputs
+                .iter()
+                .map(|text| {
+                    let lower = text.to_lowercase();
+                    VOCAB
+                        .iter()
+                        .map(|word| lower.matches(word).count() as f32)
+                        .collect()
+                })
+                .collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn refresh_is_incremental_and_search_ranks_relevant_chunks() {
+        let project = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(project.path().join("src")).unwrap();
+        std::fs::write(
+            project.path().join("src/parser.rs"),
+            "fn parse() {\n    // parser reads each token\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            project.path().join("src/server.rs"),
+            "fn serve() {\n    // http server loop\n}\n",
+        )
+        .unwrap();
+        std::fs::write(project.path().join("Cargo.lock"), "ignored\n").unwrap();
+
+        let model = KeywordEmbeddings {
+            calls: AtomicUsize::new(0),
+            inputs: AtomicUsize::new(0),
+        };
+        let index =
+            SemanticIndex::new(project.path()).with_store_path(cache.path().join("index.json"));
+        let abort = CancellationToken::new();
+
+        let (store, stats) = index.refresh(&model, &abort).await.unwrap();
+        assert_eq!(stats.files, 2);
+        assert_eq!(stats.embedded_files, 2);
+
+        let hits = index
+            .search(&model, &store, "http server", 5, |_| true)
+            .await
+            .unwrap();
+        assert_eq!(hits[0].path, "src/server.rs");
+        assert_eq!((hits[0].start_line, hits[0].end_line), (1, 3));
+
+        // Nothing changed: no files are re-embedded.
+        let embedded_before = model.inputs.load(Ordering::SeqCst);
+        let (_, stats) = index.refresh(&model, &abort).await.unwrap();
+        assert_eq!(stats.embedded_files, 0);
+        assert_eq!(model.inputs.load(Ordering::SeqCst), embedded_before);
+
+        // One file changes, one is deleted.
+        std::fs::write(
+            project.path().join("src/parser.rs"),
+            "fn parse() {\n    // database query parser\n}\n",
+        )
+        .unwrap();
+        std::fs::remove_file(project.path().join("src/server.rs")).unwrap();
+        let (store, stats) = index.refresh(&model, &abort).await.unwrap();
+        assert_eq!(stats.embedded_files, 1);
+        assert_eq!(stats.removed_files, 1);
+        assert_eq!(store.files.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn refresh_paths_only_touches_reported_paths() {
+        let project = tempfile::tempdir().unwrap();
+        let cache = tempfile::tempdir().unwrap();
+        let root = project.path();
+        std::fs::create_dir_all(root.join("src/net")).unwrap();
+        std::fs::write(root.join("src/parser.rs"), "// parser token\n").unwrap();
+        std::fs::write(root.join("src/net/http.rs"), "// http server\n").unwrap();
+        std::fs::write(root.join("src/net/db.rs"), "// database query\n").unwrap();
+
+        let model = KeywordEmbeddings {
+            calls: AtomicUsize::new(0),
+            inputs: AtomicUsize::new(0),
+        };
+        let index = SemanticIndex::new(root).with_store_path(cache.path().join("index.json"));
+        let abort = CancellationToken::new();
+        index.refresh(&model, &abort).await.unwrap();
+
+        // An unreported edit is not picked up; a reported one is.
+        std::fs::write(root.join("src/parser.rs"), "// parser query\n").unwrap();
+        std::fs::write(root.join("notes.md"), "database notes\n").unwrap();
+        std::fs::write(root.join("Cargo.lock"), "ignored\n").unwrap();
+        let (store, stats) = index
+            .refresh_paths(
+                &model,
+                &abort,
+                vec![root.join("notes.md"), root.join("Cargo.lock")],
+            )
+            .await
+            .unwrap();
+        assert_eq!(stats.embedded_files, 1);
+        assert_eq!(store.files.len(), 4);
+        assert!(!store.files.contains_key("Cargo.lock"));
+
+        // Removing a directory drops everything below it.
+        std::fs::remove_dir_all(root.join("src/net")).unwrap();
+        let (store, stats) = index
+            .refresh_paths(&model, &abort, vec![root.join("src/net")])
+            .await
+            .unwrap();
+        assert_eq!(stats.removed_files, 2);
+        let mut paths: Vec<_> = store.files.keys().cloned().collect();
+        paths.sort();
+        assert_eq!(paths, vec!["notes.md", "src/parser.rs"]);
+    }
+}
@@ -0,0 +1,11 @@
+mod chunk;
+mod index;
+mod store;
+mod tool;
+mod watch;
+
+pub use chunk::{chunk_source, Chunk};
+pub use index::{RefreshStats, SemanticIndex};
+pub use store::{store_path, ScoredChunk, VectorStore};
+pub use tool::SemanticSearchTool;
+pub use watch::{IndexWatch, PendingChanges};
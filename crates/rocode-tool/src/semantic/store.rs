@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+const STORE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkRecord {
+    pub start_line: usize,
+    pub end_line: usize,
+    #[serde(with = "vector_base64")]
+    pub vector: Vec<f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileRecord {
+    /// Modification time in milliseconds since the epoch.
+    pub modified: i64,
+    pub size: u64,
+    pub hash: String,
+    pub chunks: Vec<ChunkRecord>,
+}
+
+/// On-disk vector store for one project. Vectors are kept per file so a
+/// refresh only re-embeds files whose content changed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorStore {
+    version: u32,
+    pub model: String,
+    pub files: BTreeMap<String, FileRecord>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ScoredChunk {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub score: f32,
+}
+
+impl VectorStore {
+    pub fn new(model: impl Into<String>) -> Self {
+        Self {
+            version: STORE_VERSION,
+            model: model.into(),
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Loads the store, starting fresh when it is missing, unreadable, from
+    /// an older format or built with a different embedding model.
+    pub fn load(path: &Path, model: &str) -> Self {
+        std::fs::read(path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<VectorStore>(&bytes).ok())
+            .filter(|store| store.version == STORE_VERSION && store.model == model)
+            .unwrap_or_else(|| Self::new(model))
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec(self)?)?;
+        std::fs::rename(tmp, path)
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.files.values().map(|f| f.chunks.len()).sum()
+    }
+
+    pub fn search(
+        &self,
+        query: &[f32],
+        limit: usize,
+        filter: impl Fn(&str) -> bool,
+    ) -> Vec<ScoredChunk> {
+        let query_norm = norm(query);
+        let mut scored: Vec<ScoredChunk> = self
+            .files
+            .iter()
+            .filter(|(path, _)| filter(path))
+            .flat_map(|(path, file)| {
+                file.chunks.iter().map(move |chunk| ScoredChunk {
+                    path: path.clone(),
+                    start_line: chunk.start_line,
+                    end_line: chunk.end_line,
+                    score: cosine(query, query_norm, &chunk.vector),
+                })
+            })
+            .collect();
+        scored.sort_by(|a, b| b.score.total_cmp(&a.score));
+        scored.truncate(limit);
+        scored
+    }
+}
+
+/// Location of the store for a project under the user cache directory.
+pub fn store_path(project_root: &Path) -> PathBuf {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(project_root.to_string_lossy().as_bytes());
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("opencode")
+        .join("semantic")
+        .join(hex_prefix(&digest, 16))
+        .join("index.json")
+}
+
+fn hex_prefix(bytes: &[u8], len: usize) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<String>()
+        .chars()
+        .take(len)
+        .collect()
+}
+
+fn norm(vector: &[f32]) -> f32 {
+    vector.iter().map(|v| v * v).sum::<f32>().sqrt()
+}
+
+fn cosine(query: &[f32], query_norm: f32, vector: &[f32]) -> f32 {
+    if query.len() != vector.len() || query_norm == 0.0 {
+        return 0.0;
+    }
+    let vector_norm = norm(vector);
+    if vector_norm == 0.0 {
+        return 0.0;
+    }
+    let dot: f32 = query.iter().zip(vector).map(|(a, b)| a * b).sum();
+    dot / (query_norm * vector_norm)
+}
+
+/// Vectors are stored as base64 of little-endian `f32`s, which is about a
+/// third of the size of a JSON number array.
+mod vector_base64 {
+    use super::*;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(vector: &[f32], serializer: S) -> Result<S::Ok, S::Error> {
+        let bytes: Vec<u8> = vector.iter().flat_map(|v| v.to_le_bytes()).collect();
+        serializer.serialize_str(&base64::engine::general_purpose::STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<f32>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(serde::de::Error::custom)?;
+        if bytes.len() % 4 != 0 {
+            return Err(serde::de::Error::custom(
+                "vector length is not a multiple of 4",
+            ));
+        }
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(vectors: &[[f32; 2]]) -> FileRecord {
+        FileRecord {
+            modified: 0,
+            size: 0,
+            hash: String::new(),
+            chunks: vectors
+                .iter()
+                .enumerate()
+                .map(|(i, v)| ChunkRecord {
+                    start_line: i * 10 + 1,
+                    end_line: i * 10 + 10,
+                    vector: v.to_vec(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn search_ranks_by_cosine_similarity_and_filters() {
+        let mut store = VectorStore::new("test/model");
+        store
+            .files
+            .insert("src/a.rs".into(), record(&[[1.0, 0.0], [0.6, 0.8]]));
+        store
+            .files
+            .insert("docs/b.md".into(), record(&[[0.9, 0.1]]));
+
+        let hits = store.search(&[1.0, 0.0], 2, |_| true);
+        assert_eq!(hits[0].path, "src/a.rs");
+        assert_eq!(hits[0].start_line, 1);
+        assert_eq!(hits[1].path, "docs/b.md");
+
+        let hits = store.search(&[1.0, 0.0], 10, |p| p.starts_with("src/"));
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|h| h.path == "src/a.rs"));
+    }
+
+    #[test]
+    fn store_round_trips_and_resets_on_model_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nested/index.json");
+        let mut store = VectorStore::new("a/model");
+        store.files.insert("x.rs".into(), record(&[[0.25, -1.5]]));
+        store.save(&path).unwrap();
+
+        let loaded = VectorStore::load(&path, "a/model");
+        assert_eq!(loaded.files["x.rs"].chunks[0].vector, vec![0.25, -1.5]);
+
+        let other = VectorStore::load(&path, "b/model");
+        assert!(other.files.is_empty());
+    }
+}
@@ -0,0 +1,368 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use rocode_config::{Config, EmbeddingConfig};
+use rocode_provider::{
+    default_embedding_base_url, get_env_key, is_embedding_model, is_local_embedding_provider,
+    OpenAICompatibleEmbeddings,
+};
+use serde::{Deserialize, Serialize};
+
+use super::index::SemanticIndex;
+use super::store::ScoredChunk;
+use super::watch::{IndexWatch, PendingChanges};
+use crate::{
+    with_file_lock, Metadata, PermissionRequest, Tool, ToolContext, ToolError, ToolResult,
+};
+
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 50;
+const SNIPPET_LINES: usize = 12;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SemanticSearchParams {
+    pub query: String,
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+pub struct SemanticSearchTool;
+
+#[async_trait]
+impl Tool for SemanticSearchTool {
+    fn id(&self) -> &str {
+        "semantic_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search the project by meaning rather than exact text. Use this to find code related to a concept (e.g. 'where are retries handled', 'session persistence') when you do not know the identifiers to grep for. Results are ranked code chunks with file paths and line ranges. The index is updated incrementally from file changes before each search. Requires an `embedding.model` in the config."
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Natural language description of the code to find"
+                },
+                "limit": {
+                    "type": "integer",
+                    "minimum": 1,
+                    "maximum": MAX_LIMIT,
+                    "default": DEFAULT_LIMIT,
+                    "description": "Maximum number of results to return"
+                },
+                "path": {
+                    "type": "string",
+                    "description": "Only return results under this directory, or matching this glob (e.g. 'src/**/*.rs')"
+                }
+            },
+            "required": ["query"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        args: serde_json::Value,
+        ctx: ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        let params: SemanticSearchParams = serde_json::from_value(args)
+            .map_err(|e| ToolError::InvalidArguments(format!("Invalid parameters: {}", e)))?;
+        if params.query.trim().is_empty() {
+            return Err(ToolError::InvalidArguments(
+                "query must not be empty".into(),
+            ));
+        }
+
+        let root = if ctx.worktree.is_empty() || ctx.worktree == "/" {
+            PathBuf::from(&ctx.directory)
+        } else {
+            PathBuf::from(&ctx.worktree)
+        };
+        let config = rocode_config::load_config(&ctx.directory).unwrap_or_default();
+        let embedding = config.embedding.clone().unwrap_or_default();
+        let model = resolve_embedding_model(&config, &embedding)?;
+
+        ctx.ask_permission(
+            PermissionRequest::new("semantic_search")
+                .with_pattern(&params.query)
+                .always_allow(),
+        )
+        .await?;
+
+        let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let filter = PathFilter::new(params.path.as_deref(), &root, Path::new(&ctx.directory))?;
+
+        let mut index = SemanticIndex::new(&root).with_ignore(embedding.ignore.clone());
+        if let Some(batch_size) = embedding.batch_size {
+            index = index.with_batch_size(batch_size);
+        }
+
+        let lock_key = super::store::store_path(&root)
+            .to_string_lossy()
+            .to_string();
+        let watch = IndexWatch::for_root(&root);
+        let (store, stats) = with_file_lock(&lock_key, || async {
+            let pending = watch.take();
+            let result = match pending.clone() {
+                PendingChanges::Full => index.refresh(&model, &ctx.abort).await,
+                PendingChanges::Paths(paths) => {
+                    index.refresh_paths(&model, &ctx.abort, paths).await
+                }
+            };
+            if result.is_err() {
+                watch.restore(pending);
+            }
+            result
+        })
+        .await?;
+        let hits = index
+            .search(&model, &store, &params.query, limit, |p| filter.matches(p))
+            .await?;
+
+        let mut output = if hits.is_empty() {
+            format!("No results for \"{}\".", params.query)
+        } else {
+            format!("Found {} results for \"{}\".", hits.len(), params.query)
+        };
+        output.push_str(&format!(
+            " (index: {} files, {} chunks",
+            stats.files, stats.chunks
+        ));
+        if stats.embedded_files > 0 {
+            output.push_str(&format!("; re-indexed {} files", stats.embedded_files));
+        }
+        output.push_str(")\n");
+        for hit in &hits {
+            output.push('\n');
+            output.push_str(&format_hit(&root, hit));
+        }
+
+        let mut metadata = Metadata::new();
+        metadata.insert("query".to_string(), serde_json::json!(params.query));
+        metadata.insert("model".to_string(), serde_json::json!(store.model));
+        metadata.insert("count".to_string(), serde_json::json!(hits.len()));
+        metadata.insert(
+            "indexedFiles".to_string(),
+            serde_json::json!(stats.embedded_files),
+        );
+        metadata.insert(
+            "results".to_string(),
+            serde_json::json!(hits
+                .iter()
+                .map(|h| serde_json::json!({
+                    "path": h.path,
+                    "startLine": h.start_line,
+                    "endLine": h.end_line,
+                    "score": h.score,
+                }))
+                .collect::<Vec<_>>()),
+        );
+
+        Ok(ToolResult {
+            output,
+            title: format!("Semantic search: {}", params.query),
+            metadata,
+            truncated: false,
+        })
+    }
+}
+
+/// Builds the embedding client from `embedding` config, falling back to the
+/// matching provider entry and then the provider's environment variable for
+/// credentials and endpoint.
+fn resolve_embedding_model(
+    config: &Config,
+    embedding: &EmbeddingConfig,
+) -> Result<OpenAICompatibleEmbeddings, ToolError> {
+    if embedding.disabled == Some(true) {
+        return Err(ToolError::ExecutionError(
+            "Semantic search is disabled (embedding.disabled = true)".into(),
+        ));
+    }
+    let Some(model_ref) = embedding.model.as_deref() else {
+        return Err(ToolError::ExecutionError(
+            "No embedding model configured. Set `embedding.model` in the config, e.g. \"ollama/nomic-embed-text\" or \"openai/text-embedding-3-small\".".into(),
+        ));
+    };
+    let Some((provider_id, model_id)) = model_ref.split_once('/') else {
+        return Err(ToolError::InvalidArguments(format!(
+            "embedding.model must be `provider/model`, got \"{}\"",
+            model_ref
+        )));
+    };
+
+    if !is_embedding_model(model_id) {
+        return Err(ToolError::InvalidArguments(format!(
+            "embedding.model \"{}\" is not an embedding model; pick one such as \"openai/text-embedding-3-small\" or \"ollama/nomic-embed-text\"",
+            model_ref
+        )));
+    }
+
+    let provider = config.provider.as_ref().and_then(|p| p.get(provider_id));
+    let provider_option = |key: &str| {
+        provider
+            .and_then(|p| p.options.as_ref())
+            .and_then(|o| o.get(key))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+
+    let base_url = embedding
+        .base_url
+        .clone()
+        .or_else(|| provider.and_then(|p| p.base_url.clone()))
+        .or_else(|| provider_option("baseURL"))
+        .or_else(|| default_embedding_base_url(provider_id).map(str::to_string))
+        .ok_or_else(|| {
+            ToolError::ExecutionError(format!(
+                "No embeddings endpoint known for provider \"{}\"; set `embedding.base_url`",
+                provider_id
+            ))
+        })?;
+
+    let api_key = embedding
+        .api_key
+        .clone()
+        .or_else(|| provider.and_then(|p| p.api_key.clone()))
+        .or_else(|| provider_option("apiKey"))
+        .or_else(|| get_env_key(provider_id));
+    if api_key.is_none()
+        && !is_local_embedding_provider(provider_id)
+        && embedding.base_url.is_none()
+    {
+        return Err(ToolError::ExecutionError(format!(
+            "No API key for embedding provider \"{}\"; set `embedding.api_key` or the provider's API key",
+            provider_id
+        )));
+    }
+
+    Ok(
+        OpenAICompatibleEmbeddings::new(provider_id, model_id, base_url, api_key)
+            .with_dimensions(embedding.dimensions),
+    )
+}
+
+/// Restricts results to a subdirectory or a glob, both relative to the
+/// index root.
+enum PathFilter {
+    All,
+    Prefix(String),
+    Glob(glob::Pattern),
+}
+
+impl PathFilter {
+    fn new(path: Option<&str>, root: &Path, directory: &Path) -> Result<Self, ToolError> {
+        let Some(path) = path.map(str::trim).filter(|p| !p.is_empty() && *p != ".") else {
+            return Ok(Self::All);
+        };
+        if path.contains(['*', '?', '[']) {
+            let pattern = glob::Pattern::new(path.trim_start_matches("./"))
+                .map_err(|e| ToolError::InvalidArguments(format!("Invalid glob: {}", e)))?;
+            return Ok(Self::Glob(pattern));
+        }
+
+        let absolute = if Path::new(path).is_absolute() {
+            PathBuf::from(path)
+        } else {
+            directory.join(path)
+        };
+        let relative = absolute
+            .strip_prefix(root)
+            .map_err(|_| {
+                ToolError::InvalidArguments(format!("path is outside the project: {}", path))
+            })?
+            .to_string_lossy()
+            .replace('\\', "/");
+        let relative = relative.trim_start_matches("./").trim_end_matches('/');
+        if relative.is_empty() {
+            Ok(Self::All)
+        } else {
+            Ok(Self::Prefix(relative.to_string()))
+        }
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        match self {
+            Self::All => true,
+            Self::Prefix(prefix) => {
+                path == prefix
+                    || path
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            }
+            Self::Glob(pattern) => pattern.matches(path),
+        }
+    }
+}
+
+fn format_hit(root: &Path, hit: &ScoredChunk) -> String {
+    let mut out = format!(
+        "{}:{}-{} (score {:.3})\n",
+        hit.path, hit.start_line, hit.end_line, hit.score
+    );
+    let Ok(content) = std::fs::read_to_string(root.join(&hit.path)) else {
+        return out;
+    };
+    let lines: Vec<&str> = content
+        .lines()
+        .skip(hit.start_line.saturating_sub(1))
+        .take(hit.end_line + 1 - hit.start_line)
+        .collect();
+    for (offset, line) in lines.iter().take(SNIPPET_LINES).enumerate() {
+        out.push_str(&format!("{:>6}\t{}\n", hit.start_line + offset, line));
+    }
+    if lines.len() > SNIPPET_LINES {
+        out.push_str(&format!(
+            "       ... {} more lines\n",
+            lines.len() - SNIPPET_LINES
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_filter_handles_directories_and_globs() {
+        let root = Path::new("/repo");
+        let filter = PathFilter::new(Some("src/"), root, root).unwrap();
+        assert!(filter.matches("src/lib.rs"));
+        assert!(!filter.matches("srcx/lib.rs"));
+
+        let filter = PathFilter::new(Some("lib"), root, Path::new("/repo/src")).unwrap();
+        assert!(filter.matches("src/lib/mod.rs"));
+        assert!(!filter.matches("src/main.rs"));
+
+        let filter = PathFilter::new(Some("**/*.md"), root, root).unwrap();
+        assert!(filter.matches("docs/guide.md"));
+        assert!(!filter.matches("src/lib.rs"));
+
+        assert!(PathFilter::new(Some("/elsewhere"), root, root).is_err());
+    }
+
+    #[test]
+    fn embedding_model_requires_configuration() {
+        let config = Config::default();
+        let err = resolve_embedding_model(&config, &EmbeddingConfig::default()).unwrap_err();
+        assert!(err.to_string().contains("embedding.model"));
+
+        let local = EmbeddingConfig {
+            model: Some("ollama/nomic-embed-text".into()),
+            ..Default::default()
+        };
+        assert!(resolve_embedding_model(&config, &local).is_ok());
+
+        let chat = EmbeddingConfig {
+            model: Some("ollama/llama3.1".into()),
+            ..Default::default()
+        };
+        let err = resolve_embedding_model(&config, &chat).unwrap_err();
+        assert!(err.to_string().contains("not an embedding model"));
+    }
+}
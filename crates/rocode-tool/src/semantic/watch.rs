@@ -0,0 +1,140 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use rocode_watcher::{FileWatcher, WatcherConfig};
+use tokio::sync::broadcast::error::RecvError;
+
+/// What a refresh has to look at since the previous one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PendingChanges {
+    /// The tree must be walked in full: first use in this process, the
+    /// watcher could not be started, or events were dropped.
+    Full,
+    Paths(Vec<PathBuf>),
+}
+
+#[derive(Default)]
+struct ChangeSet {
+    full: bool,
+    paths: HashSet<PathBuf>,
+}
+
+/// Collects file-watcher events for one index root so searches only
+/// re-examine paths that actually changed.
+pub struct IndexWatch {
+    changes: Arc<Mutex<ChangeSet>>,
+    watching: bool,
+    _watcher: Option<FileWatcher>,
+}
+
+impl IndexWatch {
+    /// Returns the process-wide watch for `root`, starting it on first use.
+    pub fn for_root(root: &Path) -> Arc<IndexWatch> {
+        static WATCHES: OnceLock<Mutex<HashMap<PathBuf, Arc<IndexWatch>>>> = OnceLock::new();
+        let mut watches = WATCHES
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        watches
+            .entry(root.to_path_buf())
+            .or_insert_with(|| Arc::new(IndexWatch::start(root)))
+            .clone()
+    }
+
+    fn start(root: &Path) -> Self {
+        let changes = Arc::new(Mutex::new(ChangeSet {
+            full: true,
+            ..Default::default()
+        }));
+        let watcher = tokio::runtime::Handle::try_current()
+            .ok()
+            .and_then(|handle| {
+                let watcher = FileWatcher::new(WatcherConfig::default()).ok()?;
+                if let Err(error) = watcher.watch(root) {
+                    tracing::warn!(%error, root = %root.display(), "semantic index watcher unavailable; falling back to full scans");
+                    return None;
+                }
+                let mut events = watcher.subscribe();
+                let sink = changes.clone();
+                handle.spawn(async move {
+                    loop {
+                        match events.recv().await {
+                            Ok(event) => {
+                                let mut set = sink.lock().unwrap_or_else(|e| e.into_inner());
+                                set.paths.insert(event.file);
+                            }
+                            Err(RecvError::Lagged(_)) => {
+                                sink.lock().unwrap_or_else(|e| e.into_inner()).full = true;
+                            }
+                            Err(RecvError::Closed) => break,
+                        }
+                    }
+                });
+                Some(watcher)
+            });
+        Self {
+            changes,
+            watching: watcher.is_some(),
+            _watcher: watcher,
+        }
+    }
+
+    /// Takes everything recorded since the last call. Without a running
+    /// watcher every refresh is a full one.
+    pub fn take(&self) -> PendingChanges {
+        let mut set = self.changes.lock().unwrap_or_else(|e| e.into_inner());
+        if set.full || !self.watching {
+            set.full = false;
+            set.paths.clear();
+            return PendingChanges::Full;
+        }
+        let mut paths: Vec<PathBuf> = set.paths.drain().collect();
+        paths.sort();
+        PendingChanges::Paths(paths)
+    }
+
+    /// Puts changes back after a refresh that did not complete, so the next
+    /// search retries them.
+    pub fn restore(&self, pending: PendingChanges) {
+        let mut set = self.changes.lock().unwrap_or_else(|e| e.into_inner());
+        match pending {
+            PendingChanges::Full => set.full = true,
+            PendingChanges::Paths(paths) => set.paths.extend(paths),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn first_refresh_is_full_then_only_changed_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let watch = IndexWatch::start(&root);
+        assert_eq!(watch.take(), PendingChanges::Full);
+        assert_eq!(watch.take(), PendingChanges::Paths(Vec::new()));
+
+        let file = root.join("lib.rs");
+        std::fs::write(&file, "fn main() {}\n").unwrap();
+        let mut seen = Vec::new();
+        for _ in 0..50 {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            if let PendingChanges::Paths(paths) = watch.take() {
+                seen.extend(paths);
+            }
+            if seen.contains(&file) {
+                break;
+            }
+        }
+        assert!(seen.contains(&file), "watcher did not report {:?}", file);
+
+        watch.restore(PendingChanges::Paths(vec![file.clone()]));
+        assert_eq!(watch.take(), PendingChanges::Paths(vec![file]));
+        watch.restore(PendingChanges::Full);
+        assert_eq!(watch.take(), PendingChanges::Full);
+    }
+}
@@ -0,0 +1,387 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{watch, Notify};
+use tokio_util::sync::CancellationToken;
+
+use crate::ToolError;
+
+/// Background subagents allowed to run at once unless
+/// `experimental.max_background_tasks` says otherwise.
+pub const DEFAULT_MAX_BACKGROUND_TASKS: usize = 4;
+
+/// Finished tasks are kept this long so their results can still be
+/// collected.
+const FINISHED_RETENTION_MS: i64 = 60 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackgroundTaskStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl BackgroundTaskStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn is_finished(self) -> bool {
+        matches!(self, Self::Completed | Self::Failed | Self::Cancelled)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackgroundTaskInfo {
+    /// Subsession id; doubles as the `task_id` handed to the model.
+    pub id: String,
+    pub parent_session_id: String,
+    pub subagent_type: String,
+    pub description: String,
+    pub prompt: String,
+    pub status: BackgroundTaskStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub created_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<i64>,
+}
+
+impl BackgroundTaskInfo {
+    pub fn new(
+        id: impl Into<String>,
+        parent_session_id: impl Into<String>,
+        subagent_type: impl Into<String>,
+        description: impl Into<String>,
+        prompt: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            parent_session_id: parent_session_id.into(),
+            subagent_type: subagent_type.into(),
+            description: description.into(),
+            prompt: prompt.into(),
+            status: BackgroundTaskStatus::Queued,
+            output: None,
+            error: None,
+            created_at: chrono::Utc::now().timestamp_millis(),
+            started_at: None,
+            finished_at: None,
+        }
+    }
+}
+
+struct Entry {
+    info: watch::Sender<BackgroundTaskInfo>,
+    cancel: CancellationToken,
+    /// Spawn order; `created_at` ties when tasks start in the same
+    /// millisecond.
+    seq: u64,
+}
+
+/// Tracks `task` subagents launched with `run_in_background` and runs them
+/// on a bounded pool.
+pub struct BackgroundTasks {
+    tasks: Mutex<HashMap<String, Entry>>,
+    pool: Arc<Pool>,
+    next_seq: AtomicU64,
+}
+
+impl BackgroundTasks {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            tasks: Mutex::new(HashMap::new()),
+            pool: Arc::new(Pool {
+                state: Mutex::new(PoolState { running: 0, limit }),
+                freed: Notify::new(),
+            }),
+            next_seq: AtomicU64::new(0),
+        }
+    }
+
+    pub fn set_limit(&self, limit: usize) {
+        self.pool.state.lock().unwrap().limit = limit;
+        self.pool.freed.notify_waiters();
+    }
+
+    /// Registers `info` and runs `run` once a pool slot is free. `cancel`
+    /// stops the task whether it is still queued or already running.
+    pub fn spawn<F>(
+        self: &Arc<Self>,
+        info: BackgroundTaskInfo,
+        cancel: CancellationToken,
+        run: F,
+    ) -> Result<BackgroundTaskInfo, ToolError>
+    where
+        F: Future<Output = Result<String, ToolError>> + Send + 'static,
+    {
+        let id = info.id.clone();
+        {
+            let mut tasks = self.tasks.lock().unwrap();
+            let now = chrono::Utc::now().timestamp_millis();
+            tasks.retain(|_, entry| {
+                entry
+                    .info
+                    .borrow()
+                    .finished_at
+                    .is_none_or(|at| now - at < FINISHED_RETENTION_MS)
+            });
+            if let Some(existing) = tasks.get(&id) {
+                if !existing.info.borrow().status.is_finished() {
+                    return Err(ToolError::ExecutionError(format!(
+                        "Task {} is still running. Wait for it with task_result before sending it another prompt.",
+                        id
+                    )));
+                }
+            }
+            let (sender, _) = watch::channel(info.clone());
+            tasks.insert(
+                id.clone(),
+                Entry {
+                    info: sender,
+                    cancel: cancel.clone(),
+                    seq: self.next_seq.fetch_add(1, Ordering::Relaxed),
+                },
+            );
+        }
+
+        let this = self.clone();
+        tokio::spawn(async move {
+            let slot = tokio::select! {
+                slot = this.pool.acquire() => slot,
+                _ = cancel.cancelled() => {
+                    this.finish(&id, Err(ToolError::Cancelled));
+                    return;
+                }
+            };
+            this.update(&id, |info| {
+                info.status = BackgroundTaskStatus::Running;
+                info.started_at = Some(chrono::Utc::now().timestamp_millis());
+            });
+            let result = tokio::select! {
+                result = run => result,
+                _ = cancel.cancelled() => Err(ToolError::Cancelled),
+            };
+            drop(slot);
+            this.finish(&id, result);
+        });
+
+        Ok(info)
+    }
+
+    pub fn get(&self, id: &str) -> Option<BackgroundTaskInfo> {
+        let tasks = self.tasks.lock().unwrap();
+        tasks.get(id).map(|entry| entry.info.borrow().clone())
+    }
+
+    /// Tasks launched from `parent_session_id`, oldest first.
+    pub fn list(&self, parent_session_id: &str) -> Vec<BackgroundTaskInfo> {
+        let tasks = self.tasks.lock().unwrap();
+        let mut items: Vec<(u64, BackgroundTaskInfo)> = tasks
+            .values()
+            .map(|entry| (entry.seq, entry.info.borrow().clone()))
+            .filter(|(_, info)| info.parent_session_id == parent_session_id)
+            .collect();
+        items.sort_by_key(|(seq, _)| *seq);
+        items.into_iter().map(|(_, info)| info).collect()
+    }
+
+    /// Waits up to `timeout` for the task to finish and returns its latest
+    /// state.
+    pub async fn wait(&self, id: &str, timeout: Duration) -> Option<BackgroundTaskInfo> {
+        let mut receiver = {
+            let tasks = self.tasks.lock().unwrap();
+            tasks.get(id)?.info.subscribe()
+        };
+        let _ = tokio::time::timeout(timeout, receiver.wait_for(|info| info.status.is_finished()))
+            .await;
+        let info = receiver.borrow().clone();
+        Some(info)
+    }
+
+    pub fn cancel(&self, id: &str) -> bool {
+        let tasks = self.tasks.lock().unwrap();
+        match tasks.get(id) {
+            Some(entry) if !entry.info.borrow().status.is_finished() => {
+                entry.cancel.cancel();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn update(&self, id: &str, apply: impl FnOnce(&mut BackgroundTaskInfo)) {
+        let tasks = self.tasks.lock().unwrap();
+        if let Some(entry) = tasks.get(id) {
+            entry.info.send_modify(apply);
+        }
+    }
+
+    fn finish(&self, id: &str, result: Result<String, ToolError>) {
+        self.update(id, |info| {
+            info.finished_at = Some(chrono::Utc::now().timestamp_millis());
+            match result {
+                Ok(output) => {
+                    info.status = BackgroundTaskStatus::Completed;
+                    info.output = Some(output);
+                }
+                Err(ToolError::Cancelled) => info.status = BackgroundTaskStatus::Cancelled,
+                Err(e) => {
+                    info.status = BackgroundTaskStatus::Failed;
+                    info.error = Some(e.to_string());
+                }
+            }
+        });
+    }
+}
+
+/// Applies `experimental.max_background_tasks` from a loaded config to the
+/// process-wide pool. Hosts call this when they load or reload config, so
+/// launching a task never has to read config from disk.
+pub fn configure_background_tasks(config: &rocode_config::Config) {
+    let limit = config
+        .experimental
+        .as_ref()
+        .and_then(|experimental| experimental.max_background_tasks)
+        .unwrap_or(DEFAULT_MAX_BACKGROUND_TASKS);
+    background_tasks().set_limit(limit);
+}
+
+/// Process-wide registry shared by the task tools and the server.
+pub fn background_tasks() -> Arc<BackgroundTasks> {
+    static TASKS: OnceLock<Arc<BackgroundTasks>> = OnceLock::new();
+    TASKS
+        .get_or_init(|| Arc::new(BackgroundTasks::new(DEFAULT_MAX_BACKGROUND_TASKS)))
+        .clone()
+}
+
+struct PoolState {
+    running: usize,
+    limit: usize,
+}
+
+/// Counting pool whose limit can change while tasks are waiting.
+struct Pool {
+    state: Mutex<PoolState>,
+    freed: Notify,
+}
+
+struct PoolSlot {
+    pool: Arc<Pool>,
+}
+
+impl Pool {
+    async fn acquire(self: &Arc<Self>) -> PoolSlot {
+        loop {
+            let freed = self.freed.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                if state.running < state.limit.max(1) {
+                    state.running += 1;
+                    return PoolSlot { pool: self.clone() };
+                }
+            }
+            freed.await;
+        }
+    }
+}
+
+impl Drop for PoolSlot {
+    fn drop(&mut self) {
+        self.pool.state.lock().unwrap().running -= 1;
+        self.pool.freed.notify_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(id: &str) -> BackgroundTaskInfo {
+        BackgroundTaskInfo::new(id, "parent", "explore", id, "prompt")
+    }
+
+    #[tokio::test]
+    async fn runs_at_most_limit_tasks_at_once() {
+        let tasks = Arc::new(BackgroundTasks::new(1));
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+
+        tasks
+            .spawn(info("a"), CancellationToken::new(), async move {
+                let _ = release_rx.await;
+                Ok("first".to_string())
+            })
+            .unwrap();
+        tasks
+            .spawn(info("b"), CancellationToken::new(), async {
+                Ok("second".to_string())
+            })
+            .unwrap();
+
+        let a = tasks.wait("a", Duration::from_millis(50)).await.unwrap();
+        let b = tasks.get("b").unwrap();
+        assert_eq!(a.status, BackgroundTaskStatus::Running);
+        assert_eq!(b.status, BackgroundTaskStatus::Queued);
+
+        release_tx.send(()).unwrap();
+        let b = tasks.wait("b", Duration::from_secs(5)).await.unwrap();
+        assert_eq!(b.status, BackgroundTaskStatus::Completed);
+        assert_eq!(b.output.as_deref(), Some("second"));
+        assert_eq!(
+            tasks
+                .list("parent")
+                .iter()
+                .map(|t| t.id.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert!(tasks.list("other").is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancellation_stops_running_tasks_and_rejects_duplicates() {
+        let tasks = Arc::new(BackgroundTasks::new(2));
+        let parent = CancellationToken::new();
+
+        tasks
+            .spawn(info("a"), parent.child_token(), std::future::pending())
+            .unwrap();
+        assert!(tasks
+            .spawn(info("a"), CancellationToken::new(), async {
+                Ok(String::new())
+            })
+            .is_err());
+
+        parent.cancel();
+        let a = tasks.wait("a", Duration::from_secs(5)).await.unwrap();
+        assert_eq!(a.status, BackgroundTaskStatus::Cancelled);
+        assert!(a.finished_at.is_some());
+
+        tasks
+            .spawn(info("a"), CancellationToken::new(), async {
+                Err(ToolError::ExecutionError("boom".into()))
+            })
+            .unwrap();
+        let a = tasks.wait("a", Duration::from_secs(5)).await.unwrap();
+        assert_eq!(a.status, BackgroundTaskStatus::Failed);
+        assert!(a.error.unwrap().contains("boom"));
+    }
+}
@@ -1,3 +1,6 @@
+mod background;
+mod status;
+
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
@@ -6,6 +9,12 @@ use crate::{
     ToolResult,
 };
 
+pub use background::{
+    background_tasks, configure_background_tasks, BackgroundTaskInfo, BackgroundTaskStatus,
+    BackgroundTasks, DEFAULT_MAX_BACKGROUND_TASKS,
+};
+pub use status::{TaskResultTool, TaskStatusTool};
+
 pub struct TaskTool;
 
 impl TaskTool {
@@ -52,7 +61,6 @@ struct NormalizedTaskInput {
     #[allow(dead_code)]
     command: Option<String>,
     load_skills: Option<Vec<String>>,
-    #[allow(dead_code)]
     run_in_background: bool,
 }
 
@@ -157,7 +165,7 @@ impl Tool for TaskTool {
     }
 
     fn description(&self) -> &str {
-        "Launch a specialized subagent to handle a complex task. Use this to delegate tasks that require specialized expertise or multi-step reasoning."
+        "Launch a specialized subagent to handle a complex task. Use this to delegate tasks that require specialized expertise or multi-step reasoning. Set run_in_background to launch several subagents at once: the call returns a task_id immediately, task_status reports progress and task_result collects the output."
     }
 
     fn parameters(&self) -> serde_json::Value {
@@ -191,7 +199,7 @@ impl Tool for TaskTool {
                 },
                 "run_in_background": {
                     "type": "boolean",
-                    "description": "Run the task in background (default: false)"
+                    "description": "Run the task in the background and return its task_id immediately (default: false). Collect the output later with task_result."
                 }
             },
             "required": ["subagent_type", "description", "prompt"]
@@ -248,11 +256,15 @@ impl Tool for TaskTool {
             .await?
         };
 
+        let model = parse_model_ref(preferred_model.as_deref());
+        if input.run_in_background {
+            return launch_in_background(&ctx, input, session_id, model);
+        }
+
         let title = input.description.clone();
         let result_text = ctx
             .do_prompt_subsession(session_id.clone(), input.prompt.clone())
             .await?;
-        let model = parse_model_ref(preferred_model.as_deref());
 
         let (output, has_text_output) = format_task_output(&session_id, &result_text);
 
@@ -280,6 +292,59 @@ impl Tool for TaskTool {
     }
 }
 
+/// Starts the subagent on the background pool; cancelling the parent's
+/// abort token cancels it too.
+fn launch_in_background(
+    ctx: &ToolContext,
+    input: NormalizedTaskInput,
+    session_id: String,
+    model: TaskAgentModel,
+) -> Result<ToolResult, ToolError> {
+    let tasks = background_tasks();
+
+    let info = BackgroundTaskInfo::new(
+        session_id.clone(),
+        ctx.session_id.clone(),
+        input.subagent_type.clone(),
+        input.description.clone(),
+        input.prompt.clone(),
+    );
+    let run = {
+        let ctx = ctx.clone();
+        let session_id = session_id.clone();
+        let prompt = input.prompt.clone();
+        async move { ctx.do_prompt_subsession(session_id, prompt).await }
+    };
+    tasks.spawn(info, ctx.abort.child_token(), run)?;
+    let status = tasks
+        .get(&session_id)
+        .map(|info| info.status)
+        .unwrap_or(BackgroundTaskStatus::Queued);
+
+    let mut metadata = Metadata::new();
+    metadata.insert("sessionId".into(), serde_json::json!(session_id));
+    metadata.insert("taskStatus".into(), serde_json::json!(status));
+    metadata.insert("background".into(), serde_json::json!(true));
+    metadata.insert(
+        "model".into(),
+        serde_json::json!({
+            "modelID": model.model_id,
+            "providerID": model.provider_id,
+        }),
+    );
+
+    Ok(ToolResult {
+        title: input.description,
+        output: format!(
+            "task_id: {} (running in background; use task_result to collect its output)\ntask_status: {}",
+            session_id,
+            status.as_str()
+        ),
+        metadata,
+        truncated: false,
+    })
+}
+
 fn get_disabled_tools(
     agent: Option<&TaskAgentInfo>,
     _load_skills: Option<&Vec<String>>,
@@ -288,7 +353,7 @@ fn get_disabled_tools(
 
     let has_task_permission = agent.map(|a| a.can_use_task).unwrap_or(false);
     if !has_task_permission {
-        disabled.push("task".to_string());
+        disabled.extend(["task", "task_status", "task_result"].map(String::from));
     }
 
     disabled
@@ -815,4 +880,74 @@ mod tests {
             Some(&serde_json::json!(false))
         );
     }
+
+    #[tokio::test]
+    async fn background_task_returns_immediately_and_result_is_collected_later() {
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+        let release_rx = Arc::new(Mutex::new(Some(release_rx)));
+        let ctx = ToolContext::new("session-bg".into(), "message-1".into(), ".".into())
+            .with_create_subsession(|_agent, _title, _model, _disabled_tools| async move {
+                Ok("task_explore_bg".to_string())
+            })
+            .with_prompt_subsession(move |_session_id, prompt| {
+                let release_rx = release_rx.clone();
+                async move {
+                    if let Some(rx) = release_rx.lock().await.take() {
+                        let _ = rx.await;
+                    }
+                    Ok(format!("explored: {}", prompt))
+                }
+            });
+
+        let args = serde_json::json!({
+            "description": "Explore",
+            "prompt": "map the crate",
+            "subagent_type": "explore",
+            "run_in_background": true
+        });
+        let launched = TaskTool::new().execute(args, ctx.clone()).await.unwrap();
+        assert!(launched.output.starts_with("task_id: task_explore_bg"));
+        assert_eq!(
+            launched.metadata.get("background"),
+            Some(&serde_json::json!(true))
+        );
+
+        let pending = TaskResultTool
+            .execute(
+                serde_json::json!({ "task_id": "task_explore_bg", "wait": false }),
+                ctx.clone(),
+            )
+            .await
+            .unwrap();
+        assert!(!pending.output.contains("<task_result>"));
+
+        let status = TaskStatusTool
+            .execute(serde_json::json!({}), ctx.clone())
+            .await
+            .unwrap();
+        assert!(status.output.contains("task_explore_bg"));
+        assert_eq!(status.metadata.get("running"), Some(&serde_json::json!(1)));
+
+        let other_session = ToolContext::new("other".into(), "message-1".into(), ".".into());
+        assert!(TaskResultTool
+            .execute(
+                serde_json::json!({ "task_id": "task_explore_bg" }),
+                other_session
+            )
+            .await
+            .is_err());
+
+        release_tx.send(()).unwrap();
+        let done = TaskResultTool
+            .execute(serde_json::json!({ "task_id": "task_explore_bg" }), ctx)
+            .await
+            .unwrap();
+        assert!(done
+            .output
+            .contains("<task_result>\nexplored: map the crate\n</task_result>"));
+        assert_eq!(
+            done.metadata.get("taskStatus"),
+            Some(&serde_json::json!("completed"))
+        );
+    }
 }
@@ -0,0 +1,238 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use super::background::{background_tasks, BackgroundTaskInfo, BackgroundTaskStatus};
+use super::format_task_output;
+use crate::{Metadata, Tool, ToolContext, ToolError, ToolResult};
+
+const DEFAULT_WAIT_SECS: u64 = 300;
+const MAX_WAIT_SECS: u64 = 1800;
+
+pub struct TaskStatusTool;
+
+pub struct TaskResultTool;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TaskStatusInput {
+    #[serde(default, alias = "taskId")]
+    task_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TaskResultInput {
+    #[serde(alias = "taskId")]
+    task_id: String,
+    #[serde(default = "default_wait")]
+    wait: bool,
+    #[serde(default)]
+    timeout: Option<u64>,
+}
+
+fn default_wait() -> bool {
+    true
+}
+
+/// Looks up a background task, hiding tasks that belong to other sessions.
+fn find_task(ctx: &ToolContext, task_id: &str) -> Result<BackgroundTaskInfo, ToolError> {
+    background_tasks()
+        .get(task_id)
+        .filter(|info| info.parent_session_id == ctx.session_id)
+        .ok_or_else(|| {
+            ToolError::InvalidArguments(format!(
+                "Unknown background task: {}. Use task_status to list this session's tasks.",
+                task_id
+            ))
+        })
+}
+
+fn elapsed_secs(info: &BackgroundTaskInfo) -> i64 {
+    let Some(started) = info.started_at else {
+        return 0;
+    };
+    let end = info
+        .finished_at
+        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+    (end - started).max(0) / 1000
+}
+
+fn status_line(info: &BackgroundTaskInfo) -> String {
+    let mut line = format!(
+        "{} [{}] {}: {}",
+        info.id,
+        info.status.as_str(),
+        info.subagent_type,
+        info.description
+    );
+    if info.started_at.is_some() {
+        line.push_str(&format!(" ({}s)", elapsed_secs(info)));
+    }
+    if let Some(error) = &info.error {
+        line.push_str(&format!(" - {}", error));
+    }
+    line
+}
+
+fn task_metadata(info: &BackgroundTaskInfo) -> serde_json::Value {
+    serde_json::json!({
+        "id": info.id,
+        "status": info.status,
+        "subagentType": info.subagent_type,
+        "description": info.description,
+        "elapsed": elapsed_secs(info),
+    })
+}
+
+#[async_trait]
+impl Tool for TaskStatusTool {
+    fn id(&self) -> &str {
+        "task_status"
+    }
+
+    fn description(&self) -> &str {
+        "Check on subagents started with task(run_in_background=true). Without task_id, lists every background task of this session with its status (queued, running, completed, failed, cancelled). Does not wait; use task_result to collect output."
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "task_id": {
+                    "type": "string",
+                    "description": "The task_id returned by task. Omit to list all background tasks."
+                }
+            }
+        })
+    }
+
+    async fn execute(
+        &self,
+        args: serde_json::Value,
+        ctx: ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        let input: TaskStatusInput =
+            serde_json::from_value(args).map_err(|e| ToolError::InvalidArguments(e.to_string()))?;
+
+        let tasks = match input.task_id.as_deref() {
+            Some(task_id) => vec![find_task(&ctx, task_id)?],
+            None => background_tasks().list(&ctx.session_id),
+        };
+
+        let output = if tasks.is_empty() {
+            "No background tasks in this session.".to_string()
+        } else {
+            tasks.iter().map(status_line).collect::<Vec<_>>().join("\n")
+        };
+        let running = tasks.iter().filter(|t| !t.status.is_finished()).count();
+
+        let mut metadata = Metadata::new();
+        metadata.insert("count".into(), serde_json::json!(tasks.len()));
+        metadata.insert("running".into(), serde_json::json!(running));
+        metadata.insert(
+            "tasks".into(),
+            serde_json::json!(tasks.iter().map(task_metadata).collect::<Vec<_>>()),
+        );
+
+        Ok(ToolResult {
+            title: format!("{} background tasks ({} active)", tasks.len(), running),
+            output,
+            metadata,
+            truncated: false,
+        })
+    }
+}
+
+#[async_trait]
+impl Tool for TaskResultTool {
+    fn id(&self) -> &str {
+        "task_result"
+    }
+
+    fn description(&self) -> &str {
+        "Collect the output of a subagent started with task(run_in_background=true). By default waits until the task finishes (up to timeout seconds); pass wait=false to return immediately. If the task is still running when this returns, call it again later."
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "task_id": {
+                    "type": "string",
+                    "description": "The task_id returned by task"
+                },
+                "wait": {
+                    "type": "boolean",
+                    "default": true,
+                    "description": "Wait for the task to finish"
+                },
+                "timeout": {
+                    "type": "number",
+                    "description": "Maximum seconds to wait (default 300, max 1800)"
+                }
+            },
+            "required": ["task_id"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        args: serde_json::Value,
+        ctx: ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        let input: TaskResultInput =
+            serde_json::from_value(args).map_err(|e| ToolError::InvalidArguments(e.to_string()))?;
+
+        let mut info = find_task(&ctx, &input.task_id)?;
+        if input.wait && !info.status.is_finished() {
+            let timeout = input
+                .timeout
+                .unwrap_or(DEFAULT_WAIT_SECS)
+                .min(MAX_WAIT_SECS);
+            let tasks = background_tasks();
+            info = tokio::select! {
+                waited = tasks.wait(&info.id, Duration::from_secs(timeout)) => {
+                    waited.unwrap_or(info)
+                }
+                _ = ctx.abort.cancelled() => return Err(ToolError::Cancelled),
+            };
+        }
+
+        let output = match info.status {
+            BackgroundTaskStatus::Completed => {
+                format_task_output(&info.id, info.output.as_deref().unwrap_or_default()).0
+            }
+            BackgroundTaskStatus::Failed => {
+                return Err(ToolError::ExecutionError(format!(
+                    "Background task {} failed: {}",
+                    info.id,
+                    info.error.as_deref().unwrap_or("unknown error")
+                )));
+            }
+            BackgroundTaskStatus::Cancelled => {
+                return Err(ToolError::ExecutionError(format!(
+                    "Background task {} was cancelled",
+                    info.id
+                )));
+            }
+            BackgroundTaskStatus::Queued | BackgroundTaskStatus::Running => format!(
+                "task_id: {}\ntask_status: {}\n\nThe task has not finished yet ({}s elapsed). Call task_result again later.",
+                info.id,
+                info.status.as_str(),
+                elapsed_secs(&info)
+            ),
+        };
+
+        let mut metadata = Metadata::new();
+        metadata.insert("sessionId".into(), serde_json::json!(info.id));
+        metadata.insert("taskStatus".into(), serde_json::json!(info.status));
+        metadata.insert("elapsed".into(), serde_json::json!(elapsed_secs(&info)));
+
+        Ok(ToolResult {
+            title: info.description.clone(),
+            output,
+            metadata,
+            truncated: false,
+        })
+    }
+}
@@ -0,0 +1,222 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Responses without explicit freshness information are reused for this
+/// long, which covers paging through a document with `offset`.
+const DEFAULT_FRESH_SECS: u64 = 300;
+const RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheMeta {
+    pub url: String,
+    pub content_type: String,
+    #[serde(default)]
+    pub etag: Option<String>,
+    #[serde(default)]
+    pub last_modified: Option<String>,
+    /// Seconds since the epoch.
+    pub fetched_at: u64,
+    #[serde(default)]
+    pub max_age: Option<u64>,
+    #[serde(default)]
+    pub no_cache: bool,
+}
+
+impl CacheMeta {
+    pub fn is_fresh(&self, now: u64) -> bool {
+        if self.no_cache {
+            return false;
+        }
+        let age = now.saturating_sub(self.fetched_at);
+        age < self.max_age.unwrap_or(DEFAULT_FRESH_SECS)
+    }
+
+    pub fn has_validators(&self) -> bool {
+        self.etag.is_some() || self.last_modified.is_some()
+    }
+
+    /// Applies the caching headers of a (possibly 304) response.
+    pub fn update_from_headers(&mut self, headers: &reqwest::header::HeaderMap) -> bool {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        };
+        if let Some(etag) = header("etag") {
+            self.etag = Some(etag);
+        }
+        if let Some(last_modified) = header("last-modified") {
+            self.last_modified = Some(last_modified);
+        }
+        let directives = CacheControl::parse(header("cache-control").as_deref().unwrap_or(""));
+        self.max_age = directives.max_age;
+        self.no_cache = directives.no_cache;
+        self.fetched_at = now_secs();
+        !directives.no_store
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct CacheControl {
+    pub no_store: bool,
+    pub no_cache: bool,
+    pub max_age: Option<u64>,
+}
+
+impl CacheControl {
+    pub fn parse(value: &str) -> Self {
+        let mut out = Self::default();
+        for directive in value.split(',') {
+            let directive = directive.trim().to_ascii_lowercase();
+            match directive.split_once('=') {
+                Some(("max-age", secs)) | Some(("s-maxage", secs)) => {
+                    out.max_age = secs.trim_matches('"').parse().ok().or(out.max_age);
+                }
+                _ if directive == "no-store" => out.no_store = true,
+                _ if directive == "no-cache" || directive == "must-revalidate" => {
+                    out.no_cache = true
+                }
+                _ => {}
+            }
+        }
+        out
+    }
+}
+
+pub struct CachedResponse {
+    pub meta: CacheMeta,
+    pub body: Vec<u8>,
+}
+
+/// Per-URL disk cache of fetched responses. Each entry is a metadata file
+/// plus the raw body, keyed by a hash of the URL.
+#[derive(Debug, Clone)]
+pub struct FetchCache {
+    dir: PathBuf,
+}
+
+impl FetchCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn default_dir() -> PathBuf {
+        dirs::cache_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("opencode")
+            .join("webfetch")
+    }
+
+    fn paths(&self, url: &str) -> (PathBuf, PathBuf) {
+        let key: String = Sha256::digest(url.as_bytes())
+            .iter()
+            .take(16)
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        (
+            self.dir.join(format!("{}.json", key)),
+            self.dir.join(format!("{}.body", key)),
+        )
+    }
+
+    pub async fn get(&self, url: &str) -> Option<CachedResponse> {
+        let (meta_path, body_path) = self.paths(url);
+        let meta: CacheMeta =
+            serde_json::from_slice(&tokio::fs::read(meta_path).await.ok()?).ok()?;
+        if meta.url != url {
+            return None;
+        }
+        let body = tokio::fs::read(body_path).await.ok()?;
+        Some(CachedResponse { meta, body })
+    }
+
+    pub async fn put(&self, meta: &CacheMeta, body: &[u8]) -> std::io::Result<()> {
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let (meta_path, body_path) = self.paths(&meta.url);
+        write_atomic(&body_path, body).await?;
+        write_atomic(&meta_path, &serde_json::to_vec(meta)?).await?;
+        self.prune().await;
+        Ok(())
+    }
+
+    /// Refreshes only the metadata after a `304 Not Modified`.
+    pub async fn touch(&self, meta: &CacheMeta) -> std::io::Result<()> {
+        let (meta_path, _) = self.paths(&meta.url);
+        write_atomic(&meta_path, &serde_json::to_vec(meta)?).await
+    }
+
+    async fn prune(&self) {
+        let Ok(mut entries) = tokio::fs::read_dir(&self.dir).await else {
+            return;
+        };
+        let cutoff = SystemTime::now() - RETENTION;
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let expired = entry
+                .metadata()
+                .await
+                .and_then(|m| m.modified())
+                .map(|modified| modified < cutoff)
+                .unwrap_or(false);
+            if expired {
+                let _ = tokio::fs::remove_file(entry.path()).await;
+            }
+        }
+    }
+}
+
+async fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+    tokio::fs::write(&tmp, bytes).await?;
+    tokio::fs::rename(tmp, path).await
+}
+
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cache_control() {
+        assert_eq!(
+            CacheControl::parse("public, max-age=600"),
+            CacheControl {
+                max_age: Some(600),
+                ..Default::default()
+            }
+        );
+        let cc = CacheControl::parse("no-cache, no-store");
+        assert!(cc.no_cache && cc.no_store);
+    }
+
+    #[tokio::test]
+    async fn stores_and_reloads_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = FetchCache::new(dir.path());
+        let meta = CacheMeta {
+            url: "https://example.com/a".into(),
+            content_type: "text/html".into(),
+            etag: Some("\"v1\"".into()),
+            last_modified: None,
+            fetched_at: now_secs(),
+            max_age: None,
+            no_cache: false,
+        };
+        cache.put(&meta, b"<p>hi</p>").await.unwrap();
+
+        let cached = cache.get("https://example.com/a").await.unwrap();
+        assert_eq!(cached.body, b"<p>hi</p>");
+        assert!(cached.meta.is_fresh(now_secs()));
+        assert!(!cached.meta.is_fresh(now_secs() + DEFAULT_FRESH_SECS));
+        assert!(cache.get("https://example.com/b").await.is_none());
+    }
+}
@@ -0,0 +1,86 @@
+use encoding_rs::{Encoding, UTF_8};
+
+/// Decodes a response body using, in order: the `charset` of the
+/// Content-Type header, a `<meta>` declaration for HTML, a byte order mark,
+/// UTF-8 if the bytes are valid, and finally statistical detection.
+pub fn decode(bytes: &[u8], content_type: &str) -> String {
+    let encoding = charset_from_content_type(content_type)
+        .or_else(|| {
+            if content_type.is_empty() || content_type.contains("html") {
+                sniff_meta_charset(bytes)
+            } else {
+                None
+            }
+        })
+        .or_else(|| Encoding::for_bom(bytes).map(|(encoding, _)| encoding))
+        .unwrap_or_else(|| {
+            if std::str::from_utf8(bytes).is_ok() {
+                UTF_8
+            } else {
+                let mut detector = chardetng::EncodingDetector::new();
+                detector.feed(bytes, true);
+                detector.guess(None, true)
+            }
+        });
+    let (text, _, _) = encoding.decode(bytes);
+    text.into_owned()
+}
+
+fn charset_from_content_type(content_type: &str) -> Option<&'static Encoding> {
+    content_type.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        if key.trim().eq_ignore_ascii_case("charset") {
+            Encoding::for_label(value.trim().trim_matches('"').as_bytes())
+        } else {
+            None
+        }
+    })
+}
+
+/// Looks for `<meta charset=...>` or an http-equiv Content-Type in the first
+/// kilobyte, as browsers do.
+fn sniff_meta_charset(bytes: &[u8]) -> Option<&'static Encoding> {
+    let head = &bytes[..bytes.len().min(1024)];
+    let head = String::from_utf8_lossy(head).to_ascii_lowercase();
+    let mut rest = head.as_str();
+    while let Some(pos) = rest.find("charset=") {
+        let value = rest[pos + "charset=".len()..].trim_start_matches(['"', '\'', ' ']);
+        let end = value
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.')))
+            .unwrap_or(value.len());
+        if let Some(encoding) = Encoding::for_label(&value.as_bytes()[..end]) {
+            // A meta tag can't meaningfully declare UTF-16; the bytes were
+            // readable as ASCII, so it must be UTF-8.
+            return Some(if encoding.is_single_byte() || encoding == UTF_8 {
+                encoding
+            } else if encoding.name().starts_with("UTF-16") {
+                UTF_8
+            } else {
+                encoding
+            });
+        }
+        rest = &rest[pos + "charset=".len()..];
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_using_header_meta_and_detection() {
+        let latin1 = b"<html><head><meta charset=\"iso-8859-1\"></head><body>caf\xe9</body></html>";
+        assert!(decode(latin1, "text/html").contains("café"));
+        assert!(decode(b"caf\xe9", "text/plain; charset=windows-1252").contains("café"));
+
+        let utf8 = "naïve ✓".as_bytes();
+        assert_eq!(decode(utf8, "text/plain"), "naïve ✓");
+
+        let shift_jis = b"\x82\xb1\x82\xea\x82\xcd\x93\xfa\x96\x7b\x8c\xea\x82\xcc\x83\x65\x83\x4c\x83\x58\x83\x67\x82\xc5\x82\xb7\x81\x42";
+        assert_eq!(
+            decode(shift_jis, "text/plain"),
+            "これは日本語のテキストです。"
+        );
+    }
+}
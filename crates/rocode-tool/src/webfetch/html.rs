@@ -0,0 +1,914 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+use html5ever::tendril::TendrilSink;
+use markup5ever_rcdom::{Handle, NodeData, RcDom};
+use regex::Regex;
+use reqwest::Url;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Markdown,
+    Text,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConvertedPage {
+    pub title: Option<String>,
+    pub content: String,
+    /// Whether a main-content block was found and used instead of the
+    /// whole body.
+    pub extracted: bool,
+}
+
+/// Below this much text a "main content" candidate is probably a teaser or
+/// navigation block, so the whole body is converted instead.
+const MIN_CONTENT_CHARS: usize = 250;
+
+const SKIP_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "canvas", "iframe", "object", "embed",
+    "head", "button", "input", "select", "textarea", "option", "link", "meta",
+];
+
+const BOILERPLATE_TAGS: &[&str] = &["nav", "aside", "footer", "form", "dialog", "menu"];
+
+const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "body",
+    "center",
+    "details",
+    "dialog",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "html",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "summary",
+    "table",
+    "ul",
+];
+
+fn unlikely_pattern() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"\b(comments?|share|sharing|social|related|advert|ads|promo|cookie|newsletter|subscribe|sidebar|breadcrumbs?|popup|modal|banner|sponsor(ed)?|footer|masthead|menu|nav|navbar|widget)\b",
+        )
+        .unwrap()
+    })
+}
+
+fn likely_pattern() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"\b(article|body|content|entry|main|page|post|text|blog|story|markdown|prose)\b",
+        )
+        .unwrap()
+    })
+}
+
+/// Converts an HTML document to Markdown or plain text. With
+/// `extract_main`, boilerplate (navigation, sidebars, footers) is dropped and
+/// only the highest-scoring content block and its related siblings are kept.
+pub fn convert_html(
+    html: &str,
+    base: Option<&Url>,
+    style: Style,
+    extract_main: bool,
+) -> ConvertedPage {
+    let dom = html5ever::parse_document(RcDom::default(), Default::default()).one(html);
+    let title = find_title(&dom.document);
+    let base = find_base(&dom.document, base);
+    let writer = Writer {
+        base: base.as_ref(),
+        style,
+        extract: extract_main,
+    };
+
+    let body = find_first(&dom.document, "body").unwrap_or_else(|| dom.document.clone());
+    let main = if extract_main {
+        main_content(&body)
+    } else {
+        None
+    };
+    let extracted = main.is_some();
+    let roots = main.unwrap_or_else(|| vec![body]);
+    let mut content = writer.blocks(&roots);
+
+    if let Some(title) = &title {
+        let starts_with_heading = content.starts_with("# ") || content.starts_with(title.as_str());
+        if !starts_with_heading {
+            content = match style {
+                Style::Markdown => format!("# {}\n\n{}", title, content),
+                Style::Text => format!("{}\n\n{}", title, content),
+            };
+        }
+    }
+
+    ConvertedPage {
+        title,
+        content: collapse_blank_lines(&content),
+        extracted,
+    }
+}
+
+fn tag_name(node: &Handle) -> Option<&str> {
+    match &node.data {
+        NodeData::Element { name, .. } => Some(&name.local),
+        _ => None,
+    }
+}
+
+fn attr(node: &Handle, key: &str) -> Option<String> {
+    match &node.data {
+        NodeData::Element { attrs, .. } => attrs
+            .borrow()
+            .iter()
+            .find(|a| a.name.local.as_ref() == key)
+            .map(|a| a.value.to_string()),
+        _ => None,
+    }
+}
+
+fn class_and_id(node: &Handle) -> String {
+    let mut out = attr(node, "class").unwrap_or_default();
+    if let Some(id) = attr(node, "id") {
+        out.push(' ');
+        out.push_str(&id);
+    }
+    if let Some(role) = attr(node, "role") {
+        out.push(' ');
+        out.push_str(&role);
+    }
+    out.to_lowercase()
+}
+
+fn is_hidden(node: &Handle) -> bool {
+    attr(node, "hidden").is_some()
+        || attr(node, "aria-hidden").as_deref() == Some("true")
+        || attr(node, "style")
+            .map(|s| s.replace(' ', "").to_lowercase().contains("display:none"))
+            .unwrap_or(false)
+}
+
+fn find_first(node: &Handle, tag: &str) -> Option<Handle> {
+    if tag_name(node) == Some(tag) {
+        return Some(node.clone());
+    }
+    node.children
+        .borrow()
+        .iter()
+        .find_map(|child| find_first(child, tag))
+}
+
+fn find_title(document: &Handle) -> Option<String> {
+    let head = find_first(document, "head")?;
+    let title = find_first(&head, "title").map(|t| normalize_space(&text_content(&t)));
+    title.filter(|t| !t.is_empty()).or_else(|| {
+        head.children.borrow().iter().find_map(|child| {
+            if tag_name(child) == Some("meta")
+                && attr(child, "property").as_deref() == Some("og:title")
+            {
+                attr(child, "content").map(|c| normalize_space(&c))
+            } else {
+                None
+            }
+        })
+    })
+}
+
+fn find_base(document: &Handle, base: Option<&Url>) -> Option<Url> {
+    let href = find_first(document, "base").and_then(|b| attr(&b, "href"));
+    match (base, href) {
+        (Some(base), Some(href)) => base.join(&href).ok().or_else(|| Some(base.clone())),
+        (Some(base), None) => Some(base.clone()),
+        (None, Some(href)) => Url::parse(&href).ok(),
+        (None, None) => None,
+    }
+}
+
+fn text_content(node: &Handle) -> String {
+    let mut out = String::new();
+    collect_text(node, &mut out);
+    out
+}
+
+fn collect_text(node: &Handle, out: &mut String) {
+    match &node.data {
+        NodeData::Text { contents } => out.push_str(&contents.borrow()),
+        NodeData::Element { .. } => {
+            if tag_name(node).is_some_and(|t| SKIP_TAGS.contains(&t)) {
+                return;
+            }
+            for child in node.children.borrow().iter() {
+                collect_text(child, out);
+            }
+        }
+        _ => {
+            for child in node.children.borrow().iter() {
+                collect_text(child, out);
+            }
+        }
+    }
+}
+
+fn normalize_space(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn text_len(node: &Handle) -> usize {
+    normalize_space(&text_content(node)).chars().count()
+}
+
+fn link_density(node: &Handle) -> f64 {
+    let total = text_len(node);
+    if total == 0 {
+        return 0.0;
+    }
+    let mut links = 0;
+    count_link_text(node, &mut links);
+    links as f64 / total as f64
+}
+
+fn count_link_text(node: &Handle, links: &mut usize) {
+    if tag_name(node) == Some("a") {
+        *links += text_len(node);
+        return;
+    }
+    for child in node.children.borrow().iter() {
+        count_link_text(child, links);
+    }
+}
+
+fn node_key(node: &Handle) -> usize {
+    Rc::as_ptr(node) as usize
+}
+
+fn class_weight(node: &Handle) -> f64 {
+    let names = class_and_id(node);
+    let mut weight = 0.0;
+    if unlikely_pattern().is_match(&names) {
+        weight -= 25.0;
+    }
+    if likely_pattern().is_match(&names) {
+        weight += 25.0;
+    }
+    weight
+}
+
+fn initial_score(node: &Handle) -> f64 {
+    let tag_bonus = match tag_name(node) {
+        Some("article") | Some("main") => 25.0,
+        Some("div") | Some("section") => 5.0,
+        Some("pre") | Some("td") | Some("blockquote") => 3.0,
+        Some("form") | Some("ol") | Some("ul") | Some("dl") => -3.0,
+        Some("h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th") => -5.0,
+        _ => 0.0,
+    };
+    tag_bonus + class_weight(node)
+}
+
+fn has_block_child(node: &Handle) -> bool {
+    node.children
+        .borrow()
+        .iter()
+        .any(|c| tag_name(c).is_some_and(|t| BLOCK_TAGS.contains(&t)))
+}
+
+/// Readability-style scoring: every paragraph-like node awards points to its
+/// parent and, diminishing, to further ancestors. The best-scoring ancestor
+/// (penalised by link density) is the main content.
+fn main_content(body: &Handle) -> Option<Vec<Handle>> {
+    let mut scores: HashMap<usize, (Handle, f64)> = HashMap::new();
+    let mut ancestors: Vec<Handle> = Vec::new();
+    score_paragraphs(body, &mut ancestors, &mut scores);
+
+    let (best, best_score) = scores
+        .values()
+        .map(|(node, score)| (node.clone(), score * (1.0 - link_density(node))))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    if text_len(&best) < MIN_CONTENT_CHARS {
+        return None;
+    }
+
+    // Content is sometimes split across sibling blocks; keep siblings that
+    // score reasonably well next to the winner.
+    let threshold = (best_score * 0.2).max(10.0);
+    let parent = find_parent(body, &best);
+    let Some(parent) = parent else {
+        return Some(vec![best]);
+    };
+    let siblings = parent
+        .children
+        .borrow()
+        .iter()
+        .filter(|sibling| {
+            Rc::ptr_eq(sibling, &best)
+                || scores
+                    .get(&node_key(sibling))
+                    .map(|(node, score)| score * (1.0 - link_density(node)) >= threshold)
+                    .unwrap_or(false)
+        })
+        .cloned()
+        .collect();
+    Some(siblings)
+}
+
+fn score_paragraphs(
+    node: &Handle,
+    ancestors: &mut Vec<Handle>,
+    scores: &mut HashMap<usize, (Handle, f64)>,
+) {
+    let Some(tag) = tag_name(node) else {
+        return;
+    };
+    if SKIP_TAGS.contains(&tag) || BOILERPLATE_TAGS.contains(&tag) || is_hidden(node) {
+        return;
+    }
+
+    let paragraph_like = matches!(tag, "p" | "pre" | "td" | "blockquote")
+        || (matches!(tag, "div" | "section") && !has_block_child(node));
+    if paragraph_like {
+        let text = normalize_space(&text_content(node));
+        let len = text.chars().count();
+        if len >= 25 {
+            let points = 1.0 + text.matches(',').count() as f64 + (len as f64 / 100.0).min(3.0);
+            for (depth, ancestor) in ancestors.iter().rev().take(3).enumerate() {
+                let entry = scores
+                    .entry(node_key(ancestor))
+                    .or_insert_with(|| (ancestor.clone(), initial_score(ancestor)));
+                entry.1 += points / (depth as f64 + 1.0);
+            }
+        }
+    }
+
+    ancestors.push(node.clone());
+    for child in node.children.borrow().iter() {
+        score_paragraphs(child, ancestors, scores);
+    }
+    ancestors.pop();
+}
+
+fn find_parent(root: &Handle, target: &Handle) -> Option<Handle> {
+    for child in root.children.borrow().iter() {
+        if Rc::ptr_eq(child, target) {
+            return Some(root.clone());
+        }
+        if let Some(parent) = find_parent(child, target) {
+            return Some(parent);
+        }
+    }
+    None
+}
+
+fn collapse_blank_lines(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut blank_run = 0;
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.is_empty() {
+            blank_run += 1;
+            if blank_run > 1 {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.trim().to_string()
+}
+
+struct Writer<'a> {
+    base: Option<&'a Url>,
+    style: Style,
+    extract: bool,
+}
+
+impl Writer<'_> {
+    fn markdown(&self) -> bool {
+        self.style == Style::Markdown
+    }
+
+    fn skip(&self, node: &Handle) -> bool {
+        let Some(tag) = tag_name(node) else {
+            return false;
+        };
+        if SKIP_TAGS.contains(&tag) || is_hidden(node) {
+            return true;
+        }
+        if !self.extract || matches!(tag, "html" | "body" | "article" | "main" | "table") {
+            return false;
+        }
+        if BOILERPLATE_TAGS.contains(&tag) {
+            return true;
+        }
+        let names = class_and_id(node);
+        !names.is_empty()
+            && unlikely_pattern().is_match(&names)
+            && !likely_pattern().is_match(&names)
+    }
+
+    fn resolve(&self, href: &str) -> String {
+        let href = href.trim();
+        match self.base {
+            Some(base) => base
+                .join(href)
+                .map(|u| u.to_string())
+                .unwrap_or_else(|_| href.to_string()),
+            None => href.to_string(),
+        }
+    }
+
+    /// Renders a run of sibling nodes, grouping inline content into
+    /// paragraphs and separating blocks with blank lines.
+    fn blocks(&self, nodes: &[Handle]) -> String {
+        let mut blocks: Vec<String> = Vec::new();
+        let mut inline = String::new();
+        for node in nodes {
+            if self.skip(node) {
+                continue;
+            }
+            match tag_name(node) {
+                Some(tag) if BLOCK_TAGS.contains(&tag) => {
+                    push_paragraph(&mut blocks, &mut inline);
+                    let block = self.block(node, tag);
+                    if !block.trim().is_empty() {
+                        blocks.push(block);
+                    }
+                }
+                _ => self.inline(node, &mut inline),
+            }
+        }
+        push_paragraph(&mut blocks, &mut inline);
+        blocks.join("\n\n")
+    }
+
+    fn children_blocks(&self, node: &Handle) -> String {
+        let children: Vec<Handle> = node.children.borrow().clone();
+        self.blocks(&children)
+    }
+
+    fn inline_text(&self, node: &Handle) -> String {
+        let mut out = String::new();
+        for child in node.children.borrow().iter() {
+            self.inline(child, &mut out);
+        }
+        finish_inline(&out)
+    }
+
+    fn block(&self, node: &Handle, tag: &str) -> String {
+        match tag {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let text = self.inline_text(node).replace('\n', " ");
+                if self.markdown() {
+                    let level = tag[1..].parse::<usize>().unwrap_or(1);
+                    format!("{} {}", "#".repeat(level), text)
+                } else {
+                    text
+                }
+            }
+            "p" => self.inline_text(node),
+            "pre" => self.code_block(node),
+            "hr" => {
+                if self.markdown() {
+                    "---".to_string()
+                } else {
+                    String::new()
+                }
+            }
+            "blockquote" => {
+                let inner = self.children_blocks(node);
+                if self.markdown() {
+                    inner
+                        .lines()
+                        .map(|l| {
+                            if l.is_empty() {
+                                ">".to_string()
+                            } else {
+                                format!("> {}", l)
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                } else {
+                    inner
+                }
+            }
+            "ul" | "ol" => self.list(node, tag == "ol"),
+            "table" => self.table(node),
+            "dt" => {
+                let text = self.inline_text(node);
+                if self.markdown() && !text.is_empty() {
+                    format!("**{}**", text)
+                } else {
+                    text
+                }
+            }
+            _ => self.children_blocks(node),
+        }
+    }
+
+    fn code_block(&self, node: &Handle) -> String {
+        let code = node
+            .children
+            .borrow()
+            .iter()
+            .find(|c| tag_name(c) == Some("code"))
+            .cloned();
+        let text = text_content(node);
+        let text = text.strip_prefix('\n').unwrap_or(&text).trim_end();
+        if !self.markdown() {
+            return text.to_string();
+        }
+
+        let language = [Some(node.clone()), code]
+            .iter()
+            .flatten()
+            .filter_map(|n| attr(n, "class"))
+            .flat_map(|class| {
+                class
+                    .split_whitespace()
+                    .filter_map(|c| {
+                        c.strip_prefix("language-")
+                            .or_else(|| c.strip_prefix("lang-"))
+                            .or_else(|| c.strip_prefix("highlight-source-"))
+                            .map(str::to_string)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .next()
+            .unwrap_or_default();
+        let mut fence = "```".to_string();
+        while text.contains(&fence) {
+            fence.push('`');
+        }
+        format!("{}{}\n{}\n{}", fence, language, text, fence)
+    }
+
+    fn list(&self, node: &Handle, ordered: bool) -> String {
+        let mut number = attr(node, "start")
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(1);
+        let mut items = Vec::new();
+        for child in node.children.borrow().iter() {
+            if self.skip(child) {
+                continue;
+            }
+            let body = match tag_name(child) {
+                Some("li") => self.children_blocks(child),
+                Some("ul") | Some("ol") => {
+                    // Nested list directly inside a list (invalid but common).
+                    let nested = self.list(child, tag_name(child) == Some("ol"));
+                    items.push(indent(&nested, "  "));
+                    continue;
+                }
+                _ => continue,
+            };
+            if body.trim().is_empty() {
+                continue;
+            }
+            let marker = if ordered {
+                let marker = format!("{}. ", number);
+                number += 1;
+                marker
+            } else {
+                "- ".to_string()
+            };
+            let padding = " ".repeat(marker.len());
+            let mut lines = body.lines();
+            let mut item = format!("{}{}", marker, lines.next().unwrap_or_default());
+            for line in lines {
+                item.push('\n');
+                if !line.is_empty() {
+                    item.push_str(&padding);
+                    item.push_str(line);
+                }
+            }
+            items.push(item);
+        }
+        items.join("\n")
+    }
+
+    fn table(&self, node: &Handle) -> String {
+        let mut rows: Vec<(Vec<Handle>, bool)> = Vec::new();
+        collect_rows(node, &mut rows, false);
+        let width = rows
+            .iter()
+            .map(|(cells, _)| {
+                cells
+                    .iter()
+                    .map(|c| {
+                        attr(c, "colspan")
+                            .and_then(|s| s.parse().ok())
+                            .unwrap_or(1usize)
+                    })
+                    .sum::<usize>()
+            })
+            .max()
+            .unwrap_or(0);
+
+        // Single-column tables are layout, not data.
+        if width <= 1 {
+            let cells: Vec<Handle> = rows.into_iter().flat_map(|(cells, _)| cells).collect();
+            return cells
+                .iter()
+                .map(|cell| self.children_blocks(cell))
+                .filter(|b| !b.trim().is_empty())
+                .collect::<Vec<_>>()
+                .join("\n\n");
+        }
+
+        let rendered: Vec<Vec<String>> = rows
+            .iter()
+            .map(|(cells, _)| {
+                let mut out = Vec::new();
+                for cell in cells {
+                    let text = self.inline_text(cell).replace('\n', " ");
+                    out.push(if self.markdown() {
+                        text.replace('|', "\\|")
+                    } else {
+                        text
+                    });
+                    let span = attr(cell, "colspan")
+                        .and_then(|s| s.parse().ok())
+                        .unwrap_or(1usize);
+                    out.extend(std::iter::repeat_n(String::new(), span.saturating_sub(1)));
+                }
+                out.resize(width, String::new());
+                out
+            })
+            .collect();
+
+        if !self.markdown() {
+            return rendered
+                .iter()
+                .map(|row| row.join("\t").trim_end().to_string())
+                .collect::<Vec<_>>()
+                .join("\n");
+        }
+
+        let mut lines = Vec::new();
+        for (index, row) in rendered.iter().enumerate() {
+            lines.push(format!("| {} |", row.join(" | ")));
+            if index == 0 {
+                lines.push(format!("|{}", " --- |".repeat(width)));
+            }
+        }
+        lines.join("\n")
+    }
+
+    fn inline(&self, node: &Handle, out: &mut String) {
+        match &node.data {
+            NodeData::Text { contents } => {
+                let text = contents.borrow();
+                let mut last_space = out.ends_with(' ') || out.ends_with('\n') || out.is_empty();
+                for ch in text.chars() {
+                    if ch.is_whitespace() {
+                        if !last_space {
+                            out.push(' ');
+                            last_space = true;
+                        }
+                    } else {
+                        out.push(ch);
+                        last_space = false;
+                    }
+                }
+            }
+            NodeData::Element { .. } => {
+                if self.skip(node) {
+                    return;
+                }
+                let tag = tag_name(node).unwrap_or_default();
+                match tag {
+                    "br" => out.push('\n'),
+                    "a" => {
+                        let text = self.inline_text(node);
+                        let href = attr(node, "href").unwrap_or_default();
+                        let linkable = self.markdown()
+                            && !href.is_empty()
+                            && !href.starts_with('#')
+                            && !href.starts_with("javascript:");
+                        if text.is_empty() {
+                            return;
+                        }
+                        if linkable {
+                            out.push_str(&format!("[{}]({})", text, self.resolve(&href)));
+                        } else {
+                            out.push_str(&text);
+                        }
+                    }
+                    "img" => {
+                        let alt = normalize_space(&attr(node, "alt").unwrap_or_default());
+                        let src = attr(node, "src").unwrap_or_default();
+                        if self.markdown() && !src.is_empty() && !src.starts_with("data:") {
+                            out.push_str(&format!("![{}]({})", alt, self.resolve(&src)));
+                        } else if !alt.is_empty() {
+                            out.push_str(&alt);
+                        }
+                    }
+                    "strong" | "b" => self.emphasis(node, "**", out),
+                    "em" | "i" => self.emphasis(node, "*", out),
+                    "del" | "s" | "strike" => self.emphasis(node, "~~", out),
+                    "code" | "kbd" | "samp" | "tt" => {
+                        let text = normalize_space(&text_content(node));
+                        if text.is_empty() {
+                            return;
+                        }
+                        if self.markdown() {
+                            let fence = if text.contains('`') { "``" } else { "`" };
+                            out.push_str(&format!("{}{}{}", fence, text, fence));
+                        } else {
+                            out.push_str(&text);
+                        }
+                    }
+                    _ => {
+                        let block = BLOCK_TAGS.contains(&tag);
+                        if block && !out.ends_with(' ') && !out.is_empty() {
+                            out.push(' ');
+                        }
+                        for child in node.children.borrow().iter() {
+                            self.inline(child, out);
+                        }
+                        if block && !out.ends_with(' ') {
+                            out.push(' ');
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn emphasis(&self, node: &Handle, marker: &str, out: &mut String) {
+        let text = self.inline_text(node);
+        if text.is_empty() {
+            return;
+        }
+        if self.markdown() {
+            out.push_str(&format!("{}{}{}", marker, text, marker));
+        } else {
+            out.push_str(&text);
+        }
+    }
+}
+
+fn finish_inline(text: &str) -> String {
+    text.split('\n')
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+fn push_paragraph(blocks: &mut Vec<String>, inline: &mut String) {
+    let text = finish_inline(inline);
+    if !text.is_empty() {
+        blocks.push(text);
+    }
+    inline.clear();
+}
+
+fn indent(text: &str, prefix: &str) -> String {
+    text.lines()
+        .map(|l| {
+            if l.is_empty() {
+                String::new()
+            } else {
+                format!("{}{}", prefix, l)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn collect_rows(node: &Handle, rows: &mut Vec<(Vec<Handle>, bool)>, in_head: bool) {
+    for child in node.children.borrow().iter() {
+        match tag_name(child) {
+            Some("tr") => {
+                let cells: Vec<Handle> = child
+                    .children
+                    .borrow()
+                    .iter()
+                    .filter(|c| matches!(tag_name(c), Some("td") | Some("th")))
+                    .cloned()
+                    .collect();
+                if !cells.is_empty() {
+                    rows.push((cells, in_head));
+                }
+            }
+            Some("thead") => collect_rows(child, rows, true),
+            Some("tbody") | Some("tfoot") => collect_rows(child, rows, in_head),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn markdown(html: &str) -> String {
+        let base = Url::parse("https://example.com/docs/page.html").unwrap();
+        convert_html(html, Some(&base), Style::Markdown, false).content
+    }
+
+    #[test]
+    fn converts_inline_markup_and_resolves_links() {
+        let md = markdown(
+            r#"<p>Read the <a href="../guide/">guide</a>, <strong>now</strong> or <em>later</em>.<br>Use <code>cargo build</code>.</p>
+               <p><img src="/logo.png" alt="Logo"></p>"#,
+        );
+        assert_eq!(
+            md,
+            "Read the [guide](https://example.com/guide/), **now** or *later*.\nUse `cargo build`.\n\n![Logo](https://example.com/logo.png)"
+        );
+    }
+
+    #[test]
+    fn converts_code_blocks_lists_and_headings() {
+        let md = markdown(
+            "<h2>Install</h2><pre><code class=\"language-rust\">fn main() {\n    println!(\"hi\");\n}</code></pre>\
+             <ol><li>First</li><li><p>Second</p><ul><li>nested</li></ul></li></ol>\
+             <blockquote><p>Quoted</p></blockquote>",
+        );
+        assert_eq!(
+            md,
+            "## Install\n\n```rust\nfn main() {\n    println!(\"hi\");\n}\n```\n\n1. First\n2. Second\n\n   - nested\n\n> Quoted"
+        );
+    }
+
+    #[test]
+    fn converts_tables() {
+        let md = markdown(
+            "<table><thead><tr><th>Name</th><th>Value</th></tr></thead>\
+             <tbody><tr><td>a|b</td><td><code>1</code></td></tr><tr><td colspan=\"2\">span</td></tr></tbody></table>",
+        );
+        assert_eq!(
+            md,
+            "| Name | Value |\n| --- | --- |\n| a\\|b | `1` |\n| span |  |"
+        );
+    }
+
+    #[test]
+    fn extracts_main_content_and_drops_boilerplate() {
+        let paragraph =
+            "This paragraph has real content, with commas, and enough words to count as prose. ";
+        let html = format!(
+            r#"<html><head><title>Post title</title></head><body>
+            <nav><a href="/">Home</a> <a href="/about">About</a></nav>
+            <div class="sidebar"><p>{p}</p></div>
+            <div class="post-content"><h1>Post title</h1><p>{p}{p}</p><p>{p}</p>
+              <div class="share-buttons"><a href="/t">Tweet this article now please</a></div>
+            </div>
+            <footer>Copyright</footer></body></html>"#,
+            p = paragraph
+        );
+        let page = convert_html(&html, None, Style::Markdown, true);
+        assert!(page.extracted);
+        assert_eq!(page.title.as_deref(), Some("Post title"));
+        assert!(page.content.starts_with("# Post title\n\nThis paragraph"));
+        assert!(!page.content.contains("Home"));
+        assert!(!page.content.contains("Tweet"));
+        assert!(!page.content.contains("Copyright"));
+        assert_eq!(page.content.matches("This paragraph").count(), 3);
+    }
+
+    #[test]
+    fn text_style_drops_markup() {
+        let page = convert_html(
+            "<h1>T</h1><p>A <a href='/x'>link</a> and <b>bold</b></p><script>x()</script>",
+            None,
+            Style::Text,
+            false,
+        );
+        assert_eq!(page.content, "T\n\nA link and bold");
+    }
+}
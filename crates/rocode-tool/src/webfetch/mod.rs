@@ -0,0 +1,722 @@
+mod cache;
+mod charset;
+mod html;
+mod robots;
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::{Tool, ToolContext, ToolError, ToolResult};
+
+pub use cache::{CacheControl, CacheMeta, FetchCache};
+pub use charset::decode as decode_body;
+pub use html::{convert_html, ConvertedPage, Style};
+pub use robots::{Robots, ROBOTS_AGENT};
+
+const MAX_RESPONSE_SIZE: usize = 5 * 1024 * 1024;
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+const MAX_TIMEOUT_SECS: u64 = 120;
+const ROBOTS_TIMEOUT_SECS: u64 = 10;
+const ROBOTS_TTL: Duration = Duration::from_secs(60 * 60);
+/// Converted pages longer than this are returned in pages; the caller asks
+/// for the next one with `offset`.
+const PAGE_BYTES: usize = 40 * 1024;
+
+pub struct WebFetchTool {
+    client: Client,
+    cache: FetchCache,
+}
+
+impl WebFetchTool {
+    pub fn new() -> Self {
+        Self {
+            client: Client::builder()
+                .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36")
+                .timeout(std::time::Duration::from_secs(MAX_TIMEOUT_SECS))
+                .build()
+                .unwrap(),
+            cache: FetchCache::new(FetchCache::default_dir()),
+        }
+    }
+
+    pub fn with_cache_dir(mut self, dir: impl Into<std::path::PathBuf>) -> Self {
+        self.cache = FetchCache::new(dir);
+        self
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct WebFetchInput {
+    url: String,
+    #[serde(default = "default_format")]
+    format: String,
+    #[serde(default)]
+    timeout: Option<u64>,
+    #[serde(default)]
+    offset: Option<usize>,
+    #[serde(default = "default_extract")]
+    extract: bool,
+}
+
+fn default_format() -> String {
+    "markdown".to_string()
+}
+
+fn default_extract() -> bool {
+    true
+}
+
+struct Fetched {
+    content_type: String,
+    body: Vec<u8>,
+    /// `miss`, `hit` or `revalidated`.
+    cache: &'static str,
+}
+
+#[async_trait]
+impl Tool for WebFetchTool {
+    fn id(&self) -> &str {
+        "webfetch"
+    }
+
+    fn description(&self) -> &str {
+        "Fetch content from a URL. Returns the content in the specified format (text, markdown, or html). Defaults to markdown, converted from the page's main content with navigation and other boilerplate removed. Long pages are returned in parts; pass the offset given at the end of the output to read the next part. Responses are cached and revalidated, and robots.txt is respected."
+    }
+
+    fn parameters(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "url": {
+                    "type": "string",
+                    "description": "The URL to fetch content from"
+                },
+                "format": {
+                    "type": "string",
+                    "enum": ["text", "markdown", "html"],
+                    "default": "markdown",
+                    "description": "The format to return the content in (text, markdown, or html). Defaults to markdown."
+                },
+                "timeout": {
+                    "type": "number",
+                    "description": "Optional timeout in seconds (max 120)"
+                },
+                "offset": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Position in the converted content to start from, for reading long pages in parts"
+                },
+                "extract": {
+                    "type": "boolean",
+                    "default": true,
+                    "description": "Keep only the main content of HTML pages. Set to false to convert the whole page."
+                }
+            },
+            "required": ["url"]
+        })
+    }
+
+    async fn execute(
+        &self,
+        args: serde_json::Value,
+        ctx: ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        let input: WebFetchInput =
+            serde_json::from_value(args).map_err(|e| ToolError::InvalidArguments(e.to_string()))?;
+
+        let url = input.url.clone();
+
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(ToolError::InvalidArguments(
+                "URL must start with http:// or https://".to_string(),
+            ));
+        }
+        let parsed_url = Url::parse(&url)
+            .map_err(|e| ToolError::InvalidArguments(format!("Invalid URL: {}", e)))?;
+
+        ctx.ask_permission(
+            crate::PermissionRequest::new("webfetch")
+                .with_pattern(&url)
+                .always_allow(),
+        )
+        .await?;
+
+        let timeout_secs = input
+            .timeout
+            .unwrap_or(DEFAULT_TIMEOUT_SECS)
+            .min(MAX_TIMEOUT_SECS);
+
+        let accept_header = match input.format.as_str() {
+            "markdown" => "text/markdown;q=1.0, text/x-markdown;q=0.9, text/plain;q=0.8, text/html;q=0.7, */*;q=0.1",
+            "text" => "text/plain;q=1.0, text/markdown;q=0.9, text/html;q=0.8, */*;q=0.1",
+            "html" => "text/html;q=1.0, application/xhtml+xml;q=0.9, text/plain;q=0.8, text/markdown;q=0.7, */*;q=0.1",
+            _ => "*/*",
+        };
+
+        let fetched = tokio::select! {
+            result = async {
+                if !self.robots_allows(&parsed_url, timeout_secs).await {
+                    return Err(ToolError::ExecutionError(format!(
+                        "Fetching {} is disallowed by {}/robots.txt",
+                        url,
+                        parsed_url.origin().ascii_serialization()
+                    )));
+                }
+                self.fetch(&parsed_url, accept_header, timeout_secs).await
+            } => result,
+            _ = tokio::time::sleep(std::time::Duration::from_secs(timeout_secs)) => {
+                return Err(ToolError::Timeout(format!("Request timed out after {} seconds", timeout_secs)));
+            }
+            _ = ctx.abort.cancelled() => {
+                return Err(ToolError::Cancelled);
+            }
+        };
+
+        let Fetched {
+            content_type,
+            body: bytes,
+            cache,
+        } = fetched?;
+
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_lowercase();
+        let title = format!("{} ({})", url, content_type);
+
+        let is_image = mime.starts_with("image/")
+            && mime != "image/svg+xml"
+            && mime != "image/vnd.fastbidsheet";
+
+        if is_image {
+            let base64_content =
+                base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &bytes);
+            let data_url = format!("data:{};base64,{}", mime, base64_content);
+            let output = format!(
+                "Image fetched successfully.\n\n<attachment type=\"image\" mimeType=\"{}\" url=\"{}\" size=\"{}\" data=\"{}\" />",
+                mime, url, bytes.len(), data_url
+            );
+            let mut metadata = std::collections::HashMap::new();
+            metadata.insert("url".to_string(), serde_json::json!(url));
+            metadata.insert("mimeType".to_string(), serde_json::json!(mime));
+            metadata.insert("size".to_string(), serde_json::json!(bytes.len()));
+            metadata.insert("cache".to_string(), serde_json::json!(cache));
+            metadata.insert("data".to_string(), serde_json::json!(data_url));
+            metadata.insert(
+                "attachment".to_string(),
+                serde_json::json!({
+                    "type": "image",
+                    "mimeType": mime,
+                    "url": url,
+                    "size": bytes.len(),
+                    "data": data_url
+                }),
+            );
+            return Ok(ToolResult {
+                title,
+                output,
+                metadata,
+                truncated: false,
+            });
+        }
+
+        let content = charset::decode(&bytes, &content_type);
+        let is_html = mime == "text/html" || mime == "application/xhtml+xml";
+
+        let (content, extracted) = match input.format.as_str() {
+            "markdown" | "text" if is_html => {
+                let style = if input.format == "text" {
+                    Style::Text
+                } else {
+                    Style::Markdown
+                };
+                let page = convert_html(&content, Some(&parsed_url), style, input.extract);
+                (page.content, page.extracted)
+            }
+            _ => (content, false),
+        };
+
+        let offset = input.offset.unwrap_or(0);
+        let (output, next_offset) = paginate(&content, offset)?;
+
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert("url".to_string(), serde_json::json!(url));
+        metadata.insert("format".to_string(), serde_json::json!(input.format));
+        metadata.insert("mimeType".to_string(), serde_json::json!(mime));
+        metadata.insert("size".to_string(), serde_json::json!(content.len()));
+        metadata.insert("cache".to_string(), serde_json::json!(cache));
+        metadata.insert("extracted".to_string(), serde_json::json!(extracted));
+        metadata.insert("offset".to_string(), serde_json::json!(offset));
+        if let Some(next) = next_offset {
+            metadata.insert("nextOffset".to_string(), serde_json::json!(next));
+        }
+
+        Ok(ToolResult {
+            title,
+            output,
+            metadata,
+            truncated: next_offset.is_some(),
+        })
+    }
+}
+
+impl WebFetchTool {
+    /// Serves fresh cache entries directly, revalidates stale ones with
+    /// `If-None-Match`/`If-Modified-Since`, and stores new responses unless
+    /// they are marked `no-store`.
+    async fn fetch(
+        &self,
+        url: &Url,
+        accept_header: &str,
+        timeout_secs: u64,
+    ) -> Result<Fetched, ToolError> {
+        let cached = self.cache.get(url.as_str()).await;
+        if let Some(entry) = &cached {
+            if entry.meta.is_fresh(cache::now_secs()) {
+                return Ok(Fetched {
+                    content_type: entry.meta.content_type.clone(),
+                    body: entry.body.clone(),
+                    cache: "hit",
+                });
+            }
+        }
+
+        let validators = cached
+            .as_ref()
+            .map(|entry| &entry.meta)
+            .filter(|meta| meta.has_validators());
+        let response = self
+            .fetch_with_retry(url.as_str(), accept_header, timeout_secs, validators)
+            .await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(mut entry) = cached {
+                if entry.meta.update_from_headers(response.headers()) {
+                    if let Err(e) = self.cache.touch(&entry.meta).await {
+                        tracing::debug!(url = %url, "failed to update webfetch cache: {}", e);
+                    }
+                }
+                return Ok(Fetched {
+                    content_type: entry.meta.content_type,
+                    body: entry.body,
+                    cache: "revalidated",
+                });
+            }
+            return Err(ToolError::ExecutionError(
+                "Server returned 304 Not Modified for an uncached URL".to_string(),
+            ));
+        }
+
+        let content_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+            .to_string();
+
+        let content_length = response
+            .headers()
+            .get("content-length")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<usize>().ok());
+
+        if let Some(len) = content_length {
+            if len > MAX_RESPONSE_SIZE {
+                return Err(ToolError::ExecutionError(
+                    "Response too large (exceeds 5MB limit)".to_string(),
+                ));
+            }
+        }
+
+        let mut meta = CacheMeta {
+            url: url.to_string(),
+            content_type: content_type.clone(),
+            etag: None,
+            last_modified: None,
+            fetched_at: 0,
+            max_age: None,
+            no_cache: false,
+        };
+        let storable = meta.update_from_headers(response.headers());
+        let body = read_limited(response).await?;
+
+        if storable {
+            if let Err(e) = self.cache.put(&meta, &body).await {
+                tracing::debug!(url = %url, "failed to write webfetch cache: {}", e);
+            }
+        }
+
+        Ok(Fetched {
+            content_type,
+            body,
+            cache: "miss",
+        })
+    }
+
+    async fn fetch_with_retry(
+        &self,
+        url: &str,
+        accept_header: &str,
+        timeout_secs: u64,
+        validators: Option<&CacheMeta>,
+    ) -> Result<reqwest::Response, ToolError> {
+        let request = |user_agent: Option<&str>| {
+            let mut builder = self
+                .client
+                .get(url)
+                .header("Accept", accept_header)
+                .header("Accept-Language", "en-US,en;q=0.9")
+                .timeout(Duration::from_secs(timeout_secs));
+            if let Some(user_agent) = user_agent {
+                builder = builder.header("User-Agent", user_agent);
+            }
+            if let Some(meta) = validators {
+                if let Some(etag) = &meta.etag {
+                    builder = builder.header("If-None-Match", etag);
+                }
+                if let Some(last_modified) = &meta.last_modified {
+                    builder = builder.header("If-Modified-Since", last_modified);
+                }
+            }
+            builder
+        };
+
+        let mut response = request(None)
+            .send()
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to fetch URL: {}", e)))?;
+
+        if response.status() == 403 {
+            let cf_mitigated = response
+                .headers()
+                .get("cf-mitigated")
+                .and_then(|v| v.to_str().ok());
+
+            if cf_mitigated == Some("challenge") {
+                response = request(Some(ROBOTS_AGENT)).send().await.map_err(|e| {
+                    ToolError::ExecutionError(format!("Failed to fetch URL: {}", e))
+                })?;
+            }
+        }
+
+        if !response.status().is_success() && response.status() != StatusCode::NOT_MODIFIED {
+            return Err(ToolError::ExecutionError(format!(
+                "Request failed with status code: {}",
+                response.status()
+            )));
+        }
+
+        Ok(response)
+    }
+
+    /// Checks the origin's robots.txt, cached per origin for an hour. A
+    /// missing or unreachable robots.txt places no restrictions.
+    async fn robots_allows(&self, url: &Url, timeout_secs: u64) -> bool {
+        let origin = url.origin().ascii_serialization();
+        let mut path = url.path().to_string();
+        if let Some(query) = url.query() {
+            path.push('?');
+            path.push_str(query);
+        }
+
+        let cached = robots_memo()
+            .lock()
+            .unwrap()
+            .get(&origin)
+            .filter(|(at, _)| at.elapsed() < ROBOTS_TTL)
+            .map(|(_, robots)| robots.clone());
+        let robots = match cached {
+            Some(robots) => robots,
+            None => {
+                let response = self
+                    .client
+                    .get(format!("{}/robots.txt", origin))
+                    .timeout(Duration::from_secs(timeout_secs.min(ROBOTS_TIMEOUT_SECS)))
+                    .send()
+                    .await;
+                let robots = match response {
+                    Ok(response) if response.status().is_success() => response
+                        .text()
+                        .await
+                        .map(|text| Robots::parse(&text))
+                        .unwrap_or_default(),
+                    _ => Robots::allow_all(),
+                };
+                let robots = Arc::new(robots);
+                robots_memo()
+                    .lock()
+                    .unwrap()
+                    .insert(origin, (Instant::now(), robots.clone()));
+                robots
+            }
+        };
+        robots.is_allowed(ROBOTS_AGENT, &path)
+    }
+}
+
+type RobotsMemo = std::sync::Mutex<HashMap<String, (Instant, Arc<Robots>)>>;
+
+fn robots_memo() -> &'static RobotsMemo {
+    static MEMO: OnceLock<RobotsMemo> = OnceLock::new();
+    MEMO.get_or_init(Default::default)
+}
+
+async fn read_limited(mut response: reqwest::Response) -> Result<Vec<u8>, ToolError> {
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| ToolError::ExecutionError(format!("Failed to read response: {}", e)))?
+    {
+        if body.len() + chunk.len() > MAX_RESPONSE_SIZE {
+            return Err(ToolError::ExecutionError(
+                "Response too large (exceeds 5MB limit)".to_string(),
+            ));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// Returns the page of `content` starting at `offset`, preferring to end on
+/// a line break, and the offset of the following page if there is one.
+fn paginate(content: &str, offset: usize) -> Result<(String, Option<usize>), ToolError> {
+    if offset > content.len() {
+        return Err(ToolError::InvalidArguments(format!(
+            "offset {} is past the end of the content ({} bytes)",
+            offset,
+            content.len()
+        )));
+    }
+    let start = content.floor_char_boundary(offset);
+    if content.len() - start <= PAGE_BYTES {
+        return Ok((content[start..].to_string(), None));
+    }
+
+    let limit = content.floor_char_boundary(start + PAGE_BYTES);
+    let end = content[start..limit]
+        .rfind('\n')
+        .map(|pos| start + pos + 1)
+        .filter(|end| end - start > PAGE_BYTES / 2)
+        .unwrap_or(limit);
+    let page = format!(
+        "{}\n\n(Showing content {}-{} of {}. Call webfetch again with offset={} to continue.)",
+        content[start..end].trim_end(),
+        start,
+        end,
+        content.len(),
+        end
+    );
+    Ok((page, Some(end)))
+}
+
+impl Default for WebFetchTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    type Handler = dyn Fn(&str, &str) -> (u16, Vec<(&'static str, String)>, Vec<u8>) + Send + Sync;
+
+    /// Minimal HTTP/1.1 server; records each request head.
+    async fn fixture(handler: Arc<Handler>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let log = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    return;
+                };
+                let handler = handler.clone();
+                let log = log.clone();
+                tokio::spawn(async move {
+                    let mut head = Vec::new();
+                    let mut buf = [0u8; 1024];
+                    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                        let n = stream.read(&mut buf).await.unwrap_or(0);
+                        if n == 0 {
+                            return;
+                        }
+                        head.extend_from_slice(&buf[..n]);
+                    }
+                    let head = String::from_utf8_lossy(&head).to_lowercase();
+                    let path = head.split_whitespace().nth(1).unwrap_or("/").to_string();
+                    log.lock().unwrap().push(head.clone());
+                    let (status, headers, body) = handler(&path, &head);
+                    let mut response = format!(
+                        "HTTP/1.1 {} X\r\nContent-Length: {}\r\nConnection: close\r\n",
+                        status,
+                        body.len()
+                    );
+                    for (name, value) in headers {
+                        response.push_str(&format!("{}: {}\r\n", name, value));
+                    }
+                    response.push_str("\r\n");
+                    let _ = stream.write_all(response.as_bytes()).await;
+                    let _ = stream.write_all(&body).await;
+                });
+            }
+        });
+        (base, requests)
+    }
+
+    fn count(requests: &Arc<Mutex<Vec<String>>>, path: &str) -> usize {
+        let prefix = format!("get {} ", path);
+        requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| r.starts_with(&prefix))
+            .count()
+    }
+
+    async fn call(tool: &WebFetchTool, args: serde_json::Value) -> Result<ToolResult, ToolError> {
+        let ctx = ToolContext::new("s".into(), "m".into(), ".".into());
+        tool.execute(args, ctx).await
+    }
+
+    #[tokio::test]
+    async fn converts_html_and_revalidates_cached_copy_with_etag() {
+        let handler: Arc<Handler> = Arc::new(|path, head| {
+            match path {
+            "/page" if head.contains("if-none-match: \"v1\"") => {
+                (304, vec![("ETag", "\"v1\"".into())], Vec::new())
+            }
+            "/page" => (
+                200,
+                vec![
+                    ("Content-Type", "text/html; charset=iso-8859-1".into()),
+                    ("ETag", "\"v1\"".into()),
+                    ("Cache-Control", "no-cache".into()),
+                ],
+                b"<html><head><title>Caf\xe9</title></head><body><h1>Caf\xe9</h1><p>See <a href=\"/menu\">menu</a>.</p></body></html>".to_vec(),
+            ),
+            _ => (404, Vec::new(), Vec::new()),
+        }
+        });
+        let (base, requests) = fixture(handler).await;
+        let cache = tempfile::tempdir().unwrap();
+        let tool = WebFetchTool::new().with_cache_dir(cache.path());
+        let url = format!("{}/page", base);
+
+        let first = call(&tool, serde_json::json!({ "url": url }))
+            .await
+            .unwrap();
+        assert_eq!(
+            first.output,
+            format!("# Café\n\nSee [menu]({}/menu).", base)
+        );
+        assert_eq!(first.metadata["cache"], "miss");
+
+        let second = call(&tool, serde_json::json!({ "url": url }))
+            .await
+            .unwrap();
+        assert_eq!(second.output, first.output);
+        assert_eq!(second.metadata["cache"], "revalidated");
+        assert_eq!(count(&requests, "/page"), 2);
+        assert_eq!(count(&requests, "/robots.txt"), 1);
+    }
+
+    #[tokio::test]
+    async fn pages_long_content_from_cache_with_offset() {
+        let handler: Arc<Handler> = Arc::new(|path, _| match path {
+            "/long" => (
+                200,
+                vec![
+                    ("Content-Type", "text/plain".into()),
+                    ("Cache-Control", "max-age=60".into()),
+                ],
+                "0123456789\n".repeat(10_000).into_bytes(),
+            ),
+            _ => (404, Vec::new(), Vec::new()),
+        });
+        let (base, requests) = fixture(handler).await;
+        let cache = tempfile::tempdir().unwrap();
+        let tool = WebFetchTool::new().with_cache_dir(cache.path());
+        let url = format!("{}/long", base);
+
+        let first = call(&tool, serde_json::json!({ "url": url }))
+            .await
+            .unwrap();
+        assert!(first.truncated);
+        let next = first.metadata["nextOffset"].as_u64().unwrap();
+        assert_eq!(next % 11, 0);
+        assert!(first.output.contains(&format!("offset={}", next)));
+
+        let mut offset = next;
+        let mut pages = 1;
+        loop {
+            let page = call(&tool, serde_json::json!({ "url": url, "offset": offset }))
+                .await
+                .unwrap();
+            assert_eq!(page.metadata["cache"], "hit");
+            assert!(page.output.starts_with("0123456789\n"));
+            pages += 1;
+            match page.metadata.get("nextOffset") {
+                Some(next) => offset = next.as_u64().unwrap(),
+                None => break,
+            }
+        }
+        assert_eq!(pages, 3);
+        assert_eq!(count(&requests, "/long"), 1);
+
+        let err = call(
+            &tool,
+            serde_json::json!({ "url": url, "offset": 10_000_000 }),
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidArguments(_)));
+    }
+
+    #[tokio::test]
+    async fn respects_robots_txt() {
+        let handler: Arc<Handler> = Arc::new(|path, _| match path {
+            "/robots.txt" => (
+                200,
+                Vec::new(),
+                b"User-agent: *\nDisallow: /private\n".to_vec(),
+            ),
+            _ => (
+                200,
+                vec![("Content-Type", "text/plain".into())],
+                b"ok".to_vec(),
+            ),
+        });
+        let (base, requests) = fixture(handler).await;
+        let cache = tempfile::tempdir().unwrap();
+        let tool = WebFetchTool::new().with_cache_dir(cache.path());
+
+        let err = call(
+            &tool,
+            serde_json::json!({ "url": format!("{}/private/x", base) }),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("robots.txt"));
+        assert_eq!(count(&requests, "/private/x"), 0);
+
+        let ok = call(
+            &tool,
+            serde_json::json!({ "url": format!("{}/public", base) }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(ok.output, "ok");
+    }
+}
@@ -0,0 +1,142 @@
+/// Product token matched against `User-agent` lines.
+pub const ROBOTS_AGENT: &str = "rocode";
+
+#[derive(Debug, Default)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<(bool, String)>,
+}
+
+/// Parsed robots.txt following RFC 9309: rules from every group naming the
+/// agent apply (falling back to `*`), the longest matching rule wins and
+/// `Allow` wins ties.
+#[derive(Debug, Default)]
+pub struct Robots {
+    groups: Vec<Group>,
+}
+
+impl Robots {
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let mut groups: Vec<Group> = Vec::new();
+        let mut current: Option<Group> = None;
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "user-agent" => {
+                    let starts_new = current.as_ref().is_none_or(|g| !g.rules.is_empty());
+                    if starts_new {
+                        groups.extend(current.take());
+                        current = Some(Group::default());
+                    }
+                    if let Some(group) = current.as_mut() {
+                        group.agents.push(value.to_ascii_lowercase());
+                    }
+                }
+                rule @ ("allow" | "disallow") => {
+                    if let Some(group) = current.as_mut() {
+                        if !value.is_empty() {
+                            group.rules.push((rule == "allow", value.to_string()));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        groups.extend(current);
+        Self { groups }
+    }
+
+    /// `path` is the URL path plus query string.
+    pub fn is_allowed(&self, agent: &str, path: &str) -> bool {
+        let agent = agent.to_ascii_lowercase();
+        let named: Vec<&Group> = self
+            .groups
+            .iter()
+            .filter(|g| g.agents.iter().any(|a| a == &agent))
+            .collect();
+        let groups = if named.is_empty() {
+            self.groups
+                .iter()
+                .filter(|g| g.agents.iter().any(|a| a == "*"))
+                .collect()
+        } else {
+            named
+        };
+
+        let mut best: Option<(usize, bool)> = None;
+        for (allow, pattern) in groups.iter().flat_map(|g| g.rules.iter()) {
+            if !pattern_matches(pattern, path) {
+                continue;
+            }
+            let len = pattern.len();
+            best = match best {
+                Some((best_len, best_allow))
+                    if best_len > len || (best_len == len && best_allow) =>
+                {
+                    Some((best_len, best_allow))
+                }
+                _ => Some((len, *allow)),
+            };
+        }
+        best.map(|(_, allow)| allow).unwrap_or(true)
+    }
+}
+
+/// Prefix match with `*` wildcards and an optional trailing `$` anchor.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let Some(rest) = path.strip_prefix(parts[0]) else {
+        return false;
+    };
+    let mut rest = rest;
+    for (index, part) in parts.iter().enumerate().skip(1) {
+        let is_last = index == parts.len() - 1;
+        if is_last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longest_rule_wins_and_named_agent_overrides_wildcard() {
+        let robots = Robots::parse(
+            "User-agent: *\nDisallow: /private\nAllow: /private/public\nDisallow: /*.pdf$\n\n\
+             User-agent: rocode\nUser-agent: other\nDisallow: /rocode-only # comment\n",
+        );
+        assert!(robots.is_allowed("somebot", "/docs"));
+        assert!(!robots.is_allowed("somebot", "/private/x"));
+        assert!(robots.is_allowed("somebot", "/private/public/x"));
+        assert!(!robots.is_allowed("somebot", "/files/a.pdf"));
+        assert!(robots.is_allowed("somebot", "/files/a.pdf?x=1"));
+
+        assert!(robots.is_allowed(ROBOTS_AGENT, "/private/x"));
+        assert!(!robots.is_allowed(ROBOTS_AGENT, "/rocode-only/page"));
+    }
+
+    #[test]
+    fn empty_disallow_allows_everything() {
+        let robots = Robots::parse("User-agent: *\nDisallow:\n");
+        assert!(robots.is_allowed("x", "/anything"));
+    }
+}
@@ -0,0 +1,427 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use reqwest::Client;
+use rocode_config::WebSearchConfig;
+use serde::Serialize;
+use tokio::sync::Mutex;
+
+use super::{brave, exa, http, mcp, searxng, tavily};
+use crate::{ToolContext, ToolError};
+
+#[derive(Debug, Clone)]
+pub struct SearchRequest {
+    pub query: String,
+    pub num_results: usize,
+    pub livecrawl: Option<String>,
+    pub search_type: Option<String>,
+    pub context_max_characters: Option<usize>,
+}
+
+/// A search hit normalized across backends.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SearchResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+}
+
+#[async_trait]
+pub trait WebSearchBackend: Send + Sync {
+    fn id(&self) -> &str;
+
+    /// Requests per minute allowed when the config does not set one.
+    fn default_rate_limit(&self) -> Option<u32> {
+        None
+    }
+
+    async fn search(
+        &self,
+        request: &SearchRequest,
+        ctx: &ToolContext,
+    ) -> Result<Vec<SearchResult>, ToolError>;
+}
+
+/// Builds the backend selected by `websearch.backend`, defaulting to Exa.
+pub fn backend_from_config(
+    config: &WebSearchConfig,
+    client: Client,
+) -> Result<Box<dyn WebSearchBackend>, ToolError> {
+    let backend = config
+        .backend
+        .as_deref()
+        .map(|b| b.trim().to_ascii_lowercase())
+        .unwrap_or_else(|| "exa".to_string());
+    let api_key = |env_vars: &[&str]| {
+        config
+            .api_key
+            .clone()
+            .filter(|k| !k.trim().is_empty())
+            .or_else(|| env_vars.iter().find_map(|var| std::env::var(var).ok()))
+    };
+
+    Ok(match backend.as_str() {
+        "exa" => Box::new(exa::ExaBackend::new(client, config.base_url.clone())),
+        "searxng" => {
+            let base_url = config.base_url.clone().ok_or_else(|| {
+                ToolError::ExecutionError(
+                    "websearch.base_url must point at a SearXNG instance".to_string(),
+                )
+            })?;
+            Box::new(searxng::SearxngBackend::new(
+                client,
+                base_url,
+                api_key(&["SEARXNG_API_KEY"]),
+            ))
+        }
+        "brave" => {
+            let key = api_key(&["BRAVE_API_KEY", "BRAVE_SEARCH_API_KEY"]).ok_or_else(|| {
+                ToolError::ExecutionError(
+                    "Brave search needs websearch.api_key or BRAVE_API_KEY".to_string(),
+                )
+            })?;
+            Box::new(brave::BraveBackend::new(client, config.base_url.clone(), key))
+        }
+        "tavily" => {
+            let key = api_key(&["TAVILY_API_KEY"]).ok_or_else(|| {
+                ToolError::ExecutionError(
+                    "Tavily search needs websearch.api_key or TAVILY_API_KEY".to_string(),
+                )
+            })?;
+            Box::new(tavily::TavilyBackend::new(
+                client,
+                config.base_url.clone(),
+                key,
+            ))
+        }
+        "http" => Box::new(http::HttpTemplateBackend::from_config(
+            client,
+            config,
+            api_key(&[]),
+        )?),
+        "mcp" => Box::new(mcp::McpBackend::from_config(config)?),
+        other => {
+            return Err(ToolError::ExecutionError(format!(
+                "Unknown websearch backend '{}'. Expected one of: exa, searxng, brave, tavily, http, mcp",
+                other
+            )))
+        }
+    })
+}
+
+pub(super) fn check_status(status: reqwest::StatusCode, body: &str) -> Result<(), ToolError> {
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(ToolError::ExecutionError(format!(
+            "Search error ({}): {}",
+            status, body
+        )))
+    }
+}
+
+pub(super) fn request_error(e: reqwest::Error) -> ToolError {
+    if e.is_timeout() {
+        ToolError::ExecutionError("Search request timed out".to_string())
+    } else {
+        ToolError::ExecutionError(format!("Search request failed: {}", e))
+    }
+}
+
+pub(super) fn parse_json(body: &str) -> Result<serde_json::Value, ToolError> {
+    serde_json::from_str(body)
+        .map_err(|e| ToolError::ExecutionError(format!("Invalid search response: {}", e)))
+}
+
+/// Looks up a dotted path such as `web.results` or `data.0.items`.
+pub(super) fn json_path<'a>(
+    value: &'a serde_json::Value,
+    path: &str,
+) -> Option<&'a serde_json::Value> {
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .try_fold(value, |current, segment| match current {
+            serde_json::Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => current.get(segment),
+        })
+}
+
+/// The result array of a response whose shape is not known up front.
+pub(super) fn result_items(value: &serde_json::Value) -> Option<&Vec<serde_json::Value>> {
+    match value {
+        serde_json::Value::Array(items) => Some(items),
+        other => ["results", "web.results", "data", "items"]
+            .iter()
+            .find_map(|path| json_path(other, path)?.as_array()),
+    }
+}
+
+fn string_field(item: &serde_json::Value, keys: &[&str]) -> Option<String> {
+    keys.iter().find_map(|key| match json_path(item, key)? {
+        serde_json::Value::String(s) if !s.trim().is_empty() => Some(s.trim().to_string()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    })
+}
+
+/// Field names tried, in order, for each normalized attribute.
+pub(super) struct FieldNames<'a> {
+    pub title: &'a [&'a str],
+    pub url: &'a [&'a str],
+    pub snippet: &'a [&'a str],
+    pub date: &'a [&'a str],
+}
+
+pub(super) const COMMON_FIELDS: FieldNames<'static> = FieldNames {
+    title: &["title", "name"],
+    url: &["url", "link", "href"],
+    snippet: &["snippet", "content", "description", "text", "summary"],
+    date: &["date", "publishedDate", "published_date", "page_age", "age"],
+};
+
+pub(super) fn normalize_items(
+    items: &[serde_json::Value],
+    fields: &FieldNames<'_>,
+) -> Vec<SearchResult> {
+    items
+        .iter()
+        .filter_map(|item| {
+            let url = string_field(item, fields.url)?;
+            Some(SearchResult {
+                title: string_field(item, fields.title).unwrap_or_else(|| url.clone()),
+                snippet: string_field(item, fields.snippet)
+                    .map(|s| strip_tags(&s))
+                    .unwrap_or_default(),
+                date: string_field(item, fields.date),
+                url,
+            })
+        })
+        .collect()
+}
+
+/// Backends that return free text (Exa, most MCP search tools) are parsed
+/// from JSON when possible, then from `Title:`/`URL:` blocks. Anything else
+/// is kept as a single untitled result so no content is lost.
+pub(super) fn normalize_text(text: &str) -> Vec<SearchResult> {
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(text) {
+        if let Some(items) = result_items(&value) {
+            let results = normalize_items(items, &COMMON_FIELDS);
+            if !results.is_empty() {
+                return results;
+            }
+        }
+    }
+
+    let results = parse_labelled_blocks(text);
+    if !results.is_empty() {
+        return results;
+    }
+
+    let text = text.trim();
+    if text.is_empty() {
+        Vec::new()
+    } else {
+        vec![SearchResult {
+            snippet: text.to_string(),
+            ..Default::default()
+        }]
+    }
+}
+
+fn parse_labelled_blocks(text: &str) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+    let mut current: Option<SearchResult> = None;
+    let mut in_text = false;
+    for line in text.lines() {
+        if let Some(title) = line.strip_prefix("Title:") {
+            results.extend(current.take());
+            current = Some(SearchResult {
+                title: title.trim().to_string(),
+                ..Default::default()
+            });
+            in_text = false;
+            continue;
+        }
+        let Some(result) = current.as_mut() else {
+            continue;
+        };
+        if let Some(url) = line.strip_prefix("URL:") {
+            result.url = url.trim().to_string();
+        } else if let Some(date) = line
+            .strip_prefix("Published Date:")
+            .or_else(|| line.strip_prefix("Published:"))
+            .or_else(|| line.strip_prefix("Date:"))
+        {
+            let date = date.trim();
+            if !date.is_empty() && date != "N/A" {
+                result.date = Some(date.to_string());
+            }
+        } else if let Some(body) = line
+            .strip_prefix("Text:")
+            .or_else(|| line.strip_prefix("Snippet:"))
+            .or_else(|| line.strip_prefix("Content:"))
+        {
+            result.snippet = body.trim().to_string();
+            in_text = true;
+        } else if in_text {
+            result.snippet.push('\n');
+            result.snippet.push_str(line);
+        }
+    }
+    results.extend(current);
+    for result in &mut results {
+        result.snippet = result.snippet.trim().to_string();
+    }
+    results.retain(|r| !r.url.is_empty());
+    results
+}
+
+/// Some APIs highlight matches with `<strong>` and similar inline tags.
+fn strip_tags(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for ch in text.chars() {
+        match ch {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => out.push(ch),
+            _ => {}
+        }
+    }
+    out.replace("&amp;", "&")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+}
+
+/// Spaces requests to one backend so they never exceed `per_minute`.
+pub struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(per_minute: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(60) / per_minute.max(1),
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Waits for the next free slot, or returns `Cancelled` if the call is
+    /// aborted first. The slot is only reserved once the wait is over, so a
+    /// cancelled caller never holds back the ones queued behind it.
+    pub async fn acquire(&self, ctx: &ToolContext) -> Result<(), ToolError> {
+        loop {
+            let wait_until = {
+                let mut next = self.next.lock().await;
+                let now = Instant::now();
+                if *next <= now {
+                    *next = now + self.interval;
+                    return Ok(());
+                }
+                *next
+            };
+            tokio::select! {
+                _ = tokio::time::sleep_until(wait_until.into()) => {}
+                _ = ctx.abort.cancelled() => return Err(ToolError::Cancelled),
+            }
+        }
+    }
+}
+
+static RATE_LIMITERS: OnceLock<std::sync::Mutex<HashMap<String, Arc<RateLimiter>>>> =
+    OnceLock::new();
+
+/// Limiters are shared process-wide per backend and rate so concurrent
+/// sessions draw from the same budget.
+pub fn rate_limiter(key: &str, per_minute: u32) -> Arc<RateLimiter> {
+    let limiters = RATE_LIMITERS.get_or_init(Default::default);
+    let mut guard = limiters.lock().unwrap();
+    guard
+        .entry(format!("{}@{}", key, per_minute))
+        .or_insert_with(|| Arc::new(RateLimiter::new(per_minute)))
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_path_walks_objects_and_arrays() {
+        let value = serde_json::json!({ "data": [{ "items": [1, 2] }] });
+        assert_eq!(
+            json_path(&value, "data.0.items"),
+            Some(&serde_json::json!([1, 2]))
+        );
+        assert_eq!(json_path(&value, "data.1"), None);
+    }
+
+    #[test]
+    fn normalize_text_parses_labelled_blocks() {
+        let text = "Title: Rust 1.80\nURL: https://blog.rust-lang.org/1.80\nPublished Date: 2024-07-25\nText: LazyCell and LazyLock\nare stable.\n\nTitle: No url\nText: dropped\n";
+        let results = normalize_text(text);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "Rust 1.80");
+        assert_eq!(results[0].date.as_deref(), Some("2024-07-25"));
+        assert_eq!(results[0].snippet, "LazyCell and LazyLock\nare stable.");
+    }
+
+    #[test]
+    fn normalize_text_reads_json_and_keeps_unstructured_text() {
+        let json = r#"{"results":[{"name":"A","link":"https://a","description":"<b>bold</b> &amp; plain"}]}"#;
+        let results = normalize_text(json);
+        assert_eq!(results[0].title, "A");
+        assert_eq!(results[0].url, "https://a");
+        assert_eq!(results[0].snippet, "bold & plain");
+
+        let results = normalize_text("just an answer");
+        assert_eq!(results[0].snippet, "just an answer");
+        assert!(results[0].url.is_empty());
+    }
+
+    #[tokio::test]
+    async fn rate_limiter_spaces_requests() {
+        let limiter = RateLimiter::new(1200); // one slot every 50ms
+        let ctx = ToolContext::new("s".into(), "m".into(), ".".into());
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire(&ctx).await.unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn cancelled_acquire_does_not_consume_a_slot() {
+        let limiter = RateLimiter::new(60); // one slot per second
+        let ctx = ToolContext::new("s".into(), "m".into(), ".".into());
+        limiter.acquire(&ctx).await.unwrap();
+
+        let cancelled = ToolContext::new("s".into(), "m".into(), ".".into());
+        cancelled.abort.cancel();
+        for _ in 0..5 {
+            assert!(matches!(
+                limiter.acquire(&cancelled).await,
+                Err(ToolError::Cancelled)
+            ));
+        }
+        let next = *limiter.next.lock().await;
+        assert!(next <= Instant::now() + Duration::from_secs(1));
+    }
+
+    #[test]
+    fn unknown_backend_is_rejected() {
+        let config = WebSearchConfig {
+            backend: Some("bing".into()),
+            ..Default::default()
+        };
+        let err = backend_from_config(&config, Client::new()).err().unwrap();
+        assert!(err.to_string().contains("Unknown websearch backend"));
+    }
+}
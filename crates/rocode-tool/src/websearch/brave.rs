@@ -0,0 +1,92 @@
+use async_trait::async_trait;
+use reqwest::Client;
+
+use super::backend::{
+    check_status, normalize_items, parse_json, request_error, SearchRequest, SearchResult,
+    WebSearchBackend, COMMON_FIELDS,
+};
+use crate::{ToolContext, ToolError};
+
+const API_BASE_URL: &str = "https://api.search.brave.com/res/v1";
+const MAX_COUNT: usize = 20;
+
+pub struct BraveBackend {
+    client: Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl BraveBackend {
+    pub fn new(client: Client, base_url: Option<String>, api_key: String) -> Self {
+        Self {
+            client,
+            base_url: base_url
+                .unwrap_or_else(|| API_BASE_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl WebSearchBackend for BraveBackend {
+    fn id(&self) -> &str {
+        "brave"
+    }
+
+    /// The free plan allows one query per second.
+    fn default_rate_limit(&self) -> Option<u32> {
+        Some(60)
+    }
+
+    async fn search(
+        &self,
+        request: &SearchRequest,
+        _ctx: &ToolContext,
+    ) -> Result<Vec<SearchResult>, ToolError> {
+        let count = request.num_results.clamp(1, MAX_COUNT).to_string();
+        let response = self
+            .client
+            .get(format!("{}/web/search", self.base_url))
+            .query(&[("q", request.query.as_str()), ("count", count.as_str())])
+            .header("Accept", "application/json")
+            .header("X-Subscription-Token", &self.api_key)
+            .send()
+            .await
+            .map_err(request_error)?;
+        let status = response.status();
+        let body = response.text().await.map_err(request_error)?;
+        check_status(status, &body)?;
+
+        Ok(parse_results(&parse_json(&body)?))
+    }
+}
+
+fn parse_results(value: &serde_json::Value) -> Vec<SearchResult> {
+    value
+        .pointer("/web/results")
+        .and_then(|r| r.as_array())
+        .map(|items| normalize_items(items, &COMMON_FIELDS))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_brave_web_results() {
+        let value = serde_json::json!({
+            "web": { "results": [{
+                "title": "Serde",
+                "url": "https://serde.rs",
+                "description": "A <strong>serialization</strong> framework",
+                "page_age": "2024-01-02T00:00:00"
+            }]}
+        });
+        let results = parse_results(&value);
+        assert_eq!(results[0].snippet, "A serialization framework");
+        assert_eq!(results[0].date.as_deref(), Some("2024-01-02T00:00:00"));
+    }
+}
@@ -0,0 +1,137 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use super::backend::{
+    check_status, normalize_text, request_error, SearchRequest, SearchResult, WebSearchBackend,
+};
+use crate::{ToolContext, ToolError};
+
+const API_BASE_URL: &str = "https://mcp.exa.ai";
+
+/// Exa's hosted MCP endpoint; needs no API key.
+pub struct ExaBackend {
+    client: Client,
+    base_url: String,
+}
+
+impl ExaBackend {
+    pub fn new(client: Client, base_url: Option<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url
+                .unwrap_or_else(|| API_BASE_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct McpSearchRequest {
+    jsonrpc: String,
+    id: u32,
+    method: String,
+    params: McpSearchParams,
+}
+
+#[derive(Debug, Serialize)]
+struct McpSearchParams {
+    name: String,
+    arguments: McpSearchArguments,
+}
+
+#[derive(Debug, Serialize)]
+struct McpSearchArguments {
+    query: String,
+    #[serde(rename = "type")]
+    search_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "numResults")]
+    num_results: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    livecrawl: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "contextMaxCharacters")]
+    context_max_characters: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct McpSearchResponse {
+    result: McpSearchResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct McpSearchResult {
+    content: Vec<McpContent>,
+}
+
+#[derive(Debug, Deserialize)]
+struct McpContent {
+    #[serde(rename = "type")]
+    _content_type: String,
+    text: String,
+}
+
+#[async_trait]
+impl WebSearchBackend for ExaBackend {
+    fn id(&self) -> &str {
+        "exa"
+    }
+
+    async fn search(
+        &self,
+        request: &SearchRequest,
+        _ctx: &ToolContext,
+    ) -> Result<Vec<SearchResult>, ToolError> {
+        let search_request = McpSearchRequest {
+            jsonrpc: "2.0".to_string(),
+            id: 1,
+            method: "tools/call".to_string(),
+            params: McpSearchParams {
+                name: "web_search_exa".to_string(),
+                arguments: McpSearchArguments {
+                    query: request.query.clone(),
+                    search_type: request.search_type.clone().or(Some("auto".to_string())),
+                    num_results: Some(request.num_results),
+                    livecrawl: request.livecrawl.clone().or(Some("fallback".to_string())),
+                    context_max_characters: request.context_max_characters,
+                },
+            },
+        };
+
+        let response = self
+            .client
+            .post(format!("{}/mcp", self.base_url))
+            .header("Accept", "application/json, text/event-stream")
+            .header("Content-Type", "application/json")
+            .json(&search_request)
+            .send()
+            .await
+            .map_err(request_error)?;
+
+        let status = response.status();
+        let response_text = response
+            .text()
+            .await
+            .map_err(|e| ToolError::ExecutionError(format!("Failed to read response: {}", e)))?;
+        check_status(status, &response_text)?;
+
+        Ok(parse_sse_response(&response_text)
+            .map(|text| normalize_text(&text))
+            .unwrap_or_default())
+    }
+}
+
+fn parse_sse_response(text: &str) -> Option<String> {
+    for line in text.lines() {
+        if let Some(data) = line.strip_prefix("data: ") {
+            if let Ok(response) = serde_json::from_str::<McpSearchResponse>(data) {
+                if !response.result.content.is_empty() {
+                    return Some(response.result.content[0].text.clone());
+                }
+            }
+        }
+    }
+    None
+}
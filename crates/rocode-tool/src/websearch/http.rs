@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use reqwest::Client;
+use rocode_config::WebSearchConfig;
+
+use super::backend::{
+    check_status, json_path, normalize_items, parse_json, request_error, result_items, FieldNames,
+    SearchRequest, SearchResult, WebSearchBackend, COMMON_FIELDS,
+};
+use crate::{ToolContext, ToolError};
+
+/// Any JSON-over-HTTP search API described entirely by config: a URL
+/// template, optional headers and body, and where to find the results.
+pub struct HttpTemplateBackend {
+    client: Client,
+    url: String,
+    method: reqwest::Method,
+    headers: HashMap<String, String>,
+    body: Option<serde_json::Value>,
+    results_path: Option<String>,
+    fields: HashMap<String, String>,
+    api_key: Option<String>,
+    timeout: Option<Duration>,
+}
+
+impl HttpTemplateBackend {
+    pub fn from_config(
+        client: Client,
+        config: &WebSearchConfig,
+        api_key: Option<String>,
+    ) -> Result<Self, ToolError> {
+        let url = config.base_url.clone().ok_or_else(|| {
+            ToolError::ExecutionError(
+                "websearch.base_url must be set to a URL template for the http backend".to_string(),
+            )
+        })?;
+        let method = match config.method.as_deref().map(str::to_ascii_uppercase) {
+            None => reqwest::Method::GET,
+            Some(m) if m == "GET" => reqwest::Method::GET,
+            Some(m) if m == "POST" => reqwest::Method::POST,
+            Some(m) => {
+                return Err(ToolError::ExecutionError(format!(
+                    "websearch.method must be GET or POST, got '{}'",
+                    m
+                )))
+            }
+        };
+        Ok(Self {
+            client,
+            url,
+            method,
+            headers: config.headers.clone(),
+            body: config.body.clone(),
+            results_path: config.results_path.clone(),
+            fields: config.fields.clone(),
+            api_key,
+            timeout: config.timeout.map(Duration::from_millis),
+        })
+    }
+
+    fn substitute(&self, template: &str, request: &SearchRequest, encode: bool) -> String {
+        let query = if encode {
+            urlencoding::encode(&request.query).into_owned()
+        } else {
+            request.query.clone()
+        };
+        template
+            .replace("{query}", &query)
+            .replace("{limit}", &request.num_results.to_string())
+            .replace("{apiKey}", self.api_key.as_deref().unwrap_or_default())
+    }
+
+    fn render_body(&self, value: &serde_json::Value, request: &SearchRequest) -> serde_json::Value {
+        match value {
+            serde_json::Value::String(s) if s == "{limit}" => {
+                serde_json::json!(request.num_results)
+            }
+            serde_json::Value::String(s) => {
+                serde_json::Value::String(self.substitute(s, request, false))
+            }
+            serde_json::Value::Array(items) => items
+                .iter()
+                .map(|item| self.render_body(item, request))
+                .collect(),
+            serde_json::Value::Object(map) => map
+                .iter()
+                .map(|(k, v)| (k.clone(), self.render_body(v, request)))
+                .collect(),
+            other => other.clone(),
+        }
+    }
+
+    fn parse_results(&self, value: &serde_json::Value) -> Vec<SearchResult> {
+        let items = match &self.results_path {
+            Some(path) => json_path(value, path).and_then(|v| v.as_array()),
+            None => result_items(value),
+        };
+        let Some(items) = items else {
+            return Vec::new();
+        };
+
+        let names = |key: &str, defaults: &[&'static str]| -> Vec<String> {
+            self.fields
+                .get(key)
+                .cloned()
+                .into_iter()
+                .chain(defaults.iter().map(|d| d.to_string()))
+                .collect()
+        };
+        let title = names("title", COMMON_FIELDS.title);
+        let url = names("url", COMMON_FIELDS.url);
+        let snippet = names("snippet", COMMON_FIELDS.snippet);
+        let date = names("date", COMMON_FIELDS.date);
+        let title: Vec<&str> = title.iter().map(String::as_str).collect();
+        let url: Vec<&str> = url.iter().map(String::as_str).collect();
+        let snippet: Vec<&str> = snippet.iter().map(String::as_str).collect();
+        let date: Vec<&str> = date.iter().map(String::as_str).collect();
+        normalize_items(
+            items,
+            &FieldNames {
+                title: &title,
+                url: &url,
+                snippet: &snippet,
+                date: &date,
+            },
+        )
+    }
+}
+
+#[async_trait]
+impl WebSearchBackend for HttpTemplateBackend {
+    fn id(&self) -> &str {
+        "http"
+    }
+
+    async fn search(
+        &self,
+        request: &SearchRequest,
+        _ctx: &ToolContext,
+    ) -> Result<Vec<SearchResult>, ToolError> {
+        let mut builder = self
+            .client
+            .request(
+                self.method.clone(),
+                self.substitute(&self.url, request, true),
+            )
+            .header("Accept", "application/json");
+        for (name, value) in &self.headers {
+            builder = builder.header(name, self.substitute(value, request, false));
+        }
+        if let Some(body) = &self.body {
+            builder = builder.json(&self.render_body(body, request));
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        let response = builder.send().await.map_err(request_error)?;
+        let status = response.status();
+        let body = response.text().await.map_err(request_error)?;
+        check_status(status, &body)?;
+
+        let mut results = self.parse_results(&parse_json(&body)?);
+        results.truncate(request.num_results);
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> SearchRequest {
+        SearchRequest {
+            query: "rust & wasm".into(),
+            num_results: 3,
+            livecrawl: None,
+            search_type: None,
+            context_max_characters: None,
+        }
+    }
+
+    fn backend(config: WebSearchConfig) -> HttpTemplateBackend {
+        HttpTemplateBackend::from_config(Client::new(), &config, Some("k".into())).unwrap()
+    }
+
+    #[test]
+    fn templates_encode_query_in_url_only() {
+        let backend = backend(WebSearchConfig {
+            base_url: Some("https://s.example/api?q={query}&n={limit}&key={apiKey}".into()),
+            body: Some(serde_json::json!({ "q": "{query}", "size": "{limit}" })),
+            ..Default::default()
+        });
+        let request = request();
+        assert_eq!(
+            backend.substitute(&backend.url, &request, true),
+            "https://s.example/api?q=rust%20%26%20wasm&n=3&key=k"
+        );
+        assert_eq!(
+            backend.render_body(backend.body.as_ref().unwrap(), &request),
+            serde_json::json!({ "q": "rust & wasm", "size": 3 })
+        );
+    }
+
+    #[test]
+    fn results_path_and_field_map_are_applied() {
+        let backend = backend(WebSearchConfig {
+            base_url: Some("https://s.example/?q={query}".into()),
+            results_path: Some("hits.hits".into()),
+            fields: HashMap::from([
+                ("title".to_string(), "_source.heading".to_string()),
+                ("url".to_string(), "_source.href".to_string()),
+                ("snippet".to_string(), "_source.summary".to_string()),
+            ]),
+            ..Default::default()
+        });
+        let value = serde_json::json!({ "hits": { "hits": [
+            { "_source": { "heading": "Intranet", "href": "https://wiki/x", "summary": "docs" } }
+        ]}});
+        let results = backend.parse_results(&value);
+        assert_eq!(results[0].title, "Intranet");
+        assert_eq!(results[0].url, "https://wiki/x");
+        assert_eq!(results[0].snippet, "docs");
+    }
+}
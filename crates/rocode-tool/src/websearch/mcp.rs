@@ -0,0 +1,153 @@
+use async_trait::async_trait;
+use rocode_config::WebSearchConfig;
+
+use super::backend::{normalize_text, SearchRequest, SearchResult, WebSearchBackend};
+use crate::{PermissionRequest, ToolContext, ToolError};
+
+/// Routes queries through a search tool exposed by a configured MCP server.
+/// MCP tools are registered as `<server>_<tool>`, so the call goes through
+/// the session's tool registry like any other tool, after the same
+/// permission check a direct call to that tool would get.
+pub struct McpBackend {
+    tool_id: String,
+    query_param: String,
+    limit_param: Option<String>,
+}
+
+impl McpBackend {
+    pub fn from_config(config: &WebSearchConfig) -> Result<Self, ToolError> {
+        let (Some(server), Some(tool)) = (config.server.as_deref(), config.tool.as_deref()) else {
+            return Err(ToolError::ExecutionError(
+                "The mcp websearch backend needs websearch.server and websearch.tool".to_string(),
+            ));
+        };
+        Ok(Self {
+            tool_id: format!("{}_{}", server, tool),
+            query_param: config
+                .query_param
+                .clone()
+                .unwrap_or_else(|| "query".to_string()),
+            limit_param: config.limit_param.clone(),
+        })
+    }
+}
+
+#[async_trait]
+impl WebSearchBackend for McpBackend {
+    fn id(&self) -> &str {
+        "mcp"
+    }
+
+    async fn search(
+        &self,
+        request: &SearchRequest,
+        ctx: &ToolContext,
+    ) -> Result<Vec<SearchResult>, ToolError> {
+        let registry = ctx.registry.as_ref().ok_or_else(|| {
+            ToolError::ExecutionError(
+                "Tool registry not available for the mcp websearch backend".to_string(),
+            )
+        })?;
+        if registry.get(&self.tool_id).await.is_none() {
+            return Err(ToolError::ExecutionError(format!(
+                "MCP search tool '{}' is not available; check that the server is connected",
+                self.tool_id
+            )));
+        }
+
+        ctx.ask_permission(
+            PermissionRequest::new(self.tool_id.clone())
+                .with_pattern("*")
+                .with_metadata("query", serde_json::json!(request.query)),
+        )
+        .await?;
+
+        let mut args = serde_json::Map::new();
+        args.insert(self.query_param.clone(), serde_json::json!(request.query));
+        if let Some(limit_param) = &self.limit_param {
+            args.insert(limit_param.clone(), serde_json::json!(request.num_results));
+        }
+        let result = registry
+            .execute(&self.tool_id, serde_json::Value::Object(args), ctx.clone())
+            .await?;
+
+        let mut results = normalize_text(&result.output);
+        results.truncate(request.num_results);
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Tool, ToolRegistry, ToolResult};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    struct FakeSearch(Arc<AtomicBool>);
+
+    #[async_trait]
+    impl Tool for FakeSearch {
+        fn id(&self) -> &str {
+            "search_web"
+        }
+
+        fn description(&self) -> &str {
+            "fake"
+        }
+
+        fn parameters(&self) -> serde_json::Value {
+            serde_json::json!({ "type": "object" })
+        }
+
+        async fn execute(
+            &self,
+            _args: serde_json::Value,
+            _ctx: ToolContext,
+        ) -> Result<ToolResult, ToolError> {
+            self.0.store(true, Ordering::SeqCst);
+            Ok(ToolResult::simple(
+                "search",
+                "Title: A\nURL: https://a\nText: b\n",
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn mcp_backend_asks_for_the_mcp_tool_permission() {
+        let called = Arc::new(AtomicBool::new(false));
+        let registry = Arc::new(ToolRegistry::new());
+        registry.register(FakeSearch(called.clone())).await;
+        let backend = McpBackend::from_config(&WebSearchConfig {
+            server: Some("search".into()),
+            tool: Some("web".into()),
+            ..Default::default()
+        })
+        .unwrap();
+        let request = SearchRequest {
+            query: "rust".into(),
+            num_results: 5,
+            livecrawl: None,
+            search_type: None,
+            context_max_characters: None,
+        };
+
+        let asked = Arc::new(Mutex::new(Vec::new()));
+        let seen = asked.clone();
+        let ctx = ToolContext::new("s".into(), "m".into(), ".".into())
+            .with_registry(registry.clone())
+            .with_ask(move |req| {
+                seen.lock().unwrap().push(req.permission);
+                async { Err(ToolError::PermissionDenied("denied".into())) }
+            });
+        let err = backend.search(&request, &ctx).await.unwrap_err();
+        assert!(matches!(err, ToolError::PermissionDenied(_)));
+        assert_eq!(*asked.lock().unwrap(), vec!["search_web".to_string()]);
+        assert!(!called.load(Ordering::SeqCst));
+
+        let ctx = ToolContext::new("s".into(), "m".into(), ".".into()).with_registry(registry);
+        let results = backend.search(&request, &ctx).await.unwrap();
+        assert_eq!(results[0].url, "https://a");
+        assert!(called.load(Ordering::SeqCst));
+    }
+}
@@ -1,10 +1,27 @@
+mod backend;
+mod brave;
+mod exa;
+mod http;
+mod mcp;
+mod searxng;
+mod tavily;
+
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
-use crate::{Tool, ToolContext, ToolError, ToolResult};
+use crate::{Metadata, Tool, ToolContext, ToolError, ToolResult};
+
+pub use backend::{
+    backend_from_config, rate_limiter, RateLimiter, SearchRequest, SearchResult, WebSearchBackend,
+};
+pub use brave::BraveBackend;
+pub use exa::ExaBackend;
+pub use http::HttpTemplateBackend;
+pub use mcp::McpBackend;
+pub use searxng::SearxngBackend;
+pub use tavily::TavilyBackend;
 
-const API_BASE_URL: &str = "https://mcp.exa.ai";
 const DEFAULT_NUM_RESULTS: usize = 8;
 
 pub struct WebSearchTool {
@@ -39,53 +56,7 @@ fn default_num_results() -> usize {
     DEFAULT_NUM_RESULTS
 }
 
-#[derive(Debug, Serialize)]
-struct McpSearchRequest {
-    jsonrpc: String,
-    id: u32,
-    method: String,
-    params: McpSearchParams,
-}
-
-#[derive(Debug, Serialize)]
-struct McpSearchParams {
-    name: String,
-    arguments: McpSearchArguments,
-}
-
-#[derive(Debug, Serialize)]
-struct McpSearchArguments {
-    query: String,
-    #[serde(rename = "type")]
-    search_type: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "numResults")]
-    num_results: Option<usize>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    livecrawl: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    #[serde(rename = "contextMaxCharacters")]
-    context_max_characters: Option<usize>,
-}
-
-#[derive(Debug, Deserialize)]
-struct McpSearchResponse {
-    result: McpSearchResult,
-}
-
-#[derive(Debug, Deserialize)]
-struct McpSearchResult {
-    content: Vec<McpContent>,
-}
-
-#[derive(Debug, Deserialize)]
-struct McpContent {
-    #[serde(rename = "type")]
-    _content_type: String,
-    text: String,
-}
-
-static DESCRIPTION: &str = r#"Search the web for real-time information using Exa AI search engine.
+static DESCRIPTION: &str = r#"Search the web for real-time information.
 
 This tool provides access to current information from across the web. Use it when you need:
 - Current events or news
@@ -94,7 +65,7 @@ This tool provides access to current information from across the web. Use it whe
 - Recent research or publications
 - Any information that may have changed since the knowledge cutoff date
 
-The search returns relevant web pages with their content, optimized for LLM context."#;
+The search returns relevant web pages with their title, URL, date and a content snippet."#;
 
 #[async_trait]
 impl Tool for WebSearchTool {
@@ -128,7 +99,7 @@ impl Tool for WebSearchTool {
                     "type": "string",
                     "enum": ["fallback", "preferred"],
                     "default": "fallback",
-                    "description": "Live crawl mode - 'fallback': use live crawling as backup if cached content unavailable, 'preferred': prioritize live crawling"
+                    "description": "Live crawl mode (Exa only) - 'fallback': use live crawling as backup if cached content unavailable, 'preferred': prioritize live crawling"
                 },
                 "type": {
                     "type": "string",
@@ -139,12 +110,12 @@ impl Tool for WebSearchTool {
                 "contextMaxCharacters": {
                     "type": "integer",
                     "default": 10000,
-                    "description": "Maximum characters for context string optimized for LLMs"
+                    "description": "Maximum characters for context string optimized for LLMs (Exa only)"
                 },
                 "context_max_characters": {
                     "type": "integer",
                     "default": 10000,
-                    "description": "Maximum characters for context string optimized for LLMs (snake_case alias)"
+                    "description": "Maximum characters for context string optimized for LLMs (Exa only, snake_case alias)"
                 }
             },
             "required": ["query"]
@@ -171,76 +142,85 @@ impl Tool for WebSearchTool {
         )
         .await?;
 
-        let search_request = McpSearchRequest {
-            jsonrpc: "2.0".to_string(),
-            id: 1,
-            method: "tools/call".to_string(),
-            params: McpSearchParams {
-                name: "web_search_exa".to_string(),
-                arguments: McpSearchArguments {
-                    query: input.query.clone(),
-                    search_type: input.search_type.or(Some("auto".to_string())),
-                    num_results: Some(input.num_results),
-                    livecrawl: input.livecrawl.or(Some("fallback".to_string())),
-                    context_max_characters: input.context_max_characters,
-                },
-            },
+        let config = rocode_config::load_config(&ctx.directory)
+            .ok()
+            .and_then(|c| c.websearch)
+            .unwrap_or_default();
+        let client = match config.timeout {
+            Some(ms) => Client::builder()
+                .timeout(std::time::Duration::from_millis(ms))
+                .build()
+                .map_err(|e| {
+                    ToolError::ExecutionError(format!("Failed to create HTTP client: {}", e))
+                })?,
+            None => self.client.clone(),
         };
-
-        let response = self
-            .client
-            .post(format!("{}/mcp", API_BASE_URL))
-            .header("Accept", "application/json, text/event-stream")
-            .header("Content-Type", "application/json")
-            .json(&search_request)
-            .timeout(std::time::Duration::from_secs(25))
-            .send()
-            .await
-            .map_err(|e| {
-                if e.is_timeout() {
-                    ToolError::ExecutionError("Search request timed out".to_string())
-                } else {
-                    ToolError::ExecutionError(format!("Search request failed: {}", e))
-                }
-            })?;
-
-        let status = response.status();
-        let response_text = response
-            .text()
-            .await
-            .map_err(|e| ToolError::ExecutionError(format!("Failed to read response: {}", e)))?;
-
-        if !status.is_success() {
-            return Err(ToolError::ExecutionError(format!(
-                "Search error ({}): {}",
-                status, response_text
-            )));
+        let backend = backend_from_config(&config, client)?;
+
+        if let Some(per_minute) = config.rate_limit.or_else(|| backend.default_rate_limit()) {
+            let key = format!(
+                "{}:{}",
+                backend.id(),
+                config.base_url.as_deref().unwrap_or_default()
+            );
+            rate_limiter(&key, per_minute).acquire(&ctx).await?;
         }
 
-        let output = parse_sse_response(&response_text).unwrap_or_else(|| {
+        let request = SearchRequest {
+            query: input.query.clone(),
+            num_results: input.num_results,
+            livecrawl: input.livecrawl,
+            search_type: input.search_type,
+            context_max_characters: input.context_max_characters,
+        };
+        let results = tokio::select! {
+            results = backend.search(&request, &ctx) => results?,
+            _ = ctx.abort.cancelled() => return Err(ToolError::Cancelled),
+        };
+
+        let output = if results.is_empty() {
             "No search results found. Please try a different query.".to_string()
-        });
+        } else {
+            format_results(&results)
+        };
+
+        let mut metadata = Metadata::new();
+        metadata.insert("backend".to_string(), serde_json::json!(backend.id()));
+        metadata.insert("count".to_string(), serde_json::json!(results.len()));
+        metadata.insert("results".to_string(), serde_json::json!(results));
 
         Ok(ToolResult {
             title: format!("Web search: {}", input.query),
             output,
-            metadata: std::collections::HashMap::new(),
+            metadata,
             truncated: false,
         })
     }
 }
 
-fn parse_sse_response(text: &str) -> Option<String> {
-    for line in text.lines() {
-        if let Some(data) = line.strip_prefix("data: ") {
-            if let Ok(response) = serde_json::from_str::<McpSearchResponse>(data) {
-                if !response.result.content.is_empty() {
-                    return Some(response.result.content[0].text.clone());
-                }
-            }
+fn format_results(results: &[SearchResult]) -> String {
+    let mut out = String::new();
+    for (index, result) in results.iter().enumerate() {
+        if index > 0 {
+            out.push('\n');
+        }
+        if result.title.is_empty() && result.url.is_empty() {
+            out.push_str(&result.snippet);
+            out.push('\n');
+            continue;
+        }
+        out.push_str(&format!("{}. {}\n", index + 1, result.title));
+        out.push_str(&format!("   URL: {}\n", result.url));
+        if let Some(date) = &result.date {
+            out.push_str(&format!("   Published: {}\n", date));
+        }
+        for line in result.snippet.lines() {
+            out.push_str("   ");
+            out.push_str(line);
+            out.push('\n');
         }
     }
-    None
+    out
 }
 
 impl Default for WebSearchTool {
@@ -248,3 +228,28 @@ impl Default for WebSearchTool {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_results_lists_normalized_fields() {
+        let results = vec![
+            SearchResult {
+                title: "Tokio".into(),
+                url: "https://tokio.rs".into(),
+                snippet: "An async runtime\nfor Rust".into(),
+                date: Some("2024-05-01".into()),
+            },
+            SearchResult {
+                snippet: "Free-form answer".into(),
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            format_results(&results),
+            "1. Tokio\n   URL: https://tokio.rs\n   Published: 2024-05-01\n   An async runtime\n   for Rust\n\nFree-form answer\n"
+        );
+    }
+}
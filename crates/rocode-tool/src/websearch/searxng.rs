@@ -0,0 +1,82 @@
+use async_trait::async_trait;
+use reqwest::Client;
+
+use super::backend::{
+    check_status, normalize_items, parse_json, request_error, SearchRequest, SearchResult,
+    WebSearchBackend, COMMON_FIELDS,
+};
+use crate::{ToolContext, ToolError};
+
+/// A self-hosted SearXNG instance with the JSON output format enabled.
+pub struct SearxngBackend {
+    client: Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl SearxngBackend {
+    pub fn new(client: Client, base_url: String, api_key: Option<String>) -> Self {
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl WebSearchBackend for SearxngBackend {
+    fn id(&self) -> &str {
+        "searxng"
+    }
+
+    async fn search(
+        &self,
+        request: &SearchRequest,
+        _ctx: &ToolContext,
+    ) -> Result<Vec<SearchResult>, ToolError> {
+        let mut builder = self
+            .client
+            .get(format!("{}/search", self.base_url))
+            .query(&[("q", request.query.as_str()), ("format", "json")])
+            .header("Accept", "application/json");
+        if let Some(key) = &self.api_key {
+            builder = builder.header("Authorization", format!("Bearer {}", key));
+        }
+        let response = builder.send().await.map_err(request_error)?;
+        let status = response.status();
+        let body = response.text().await.map_err(request_error)?;
+        check_status(status, &body)?;
+
+        let mut results = parse_results(&parse_json(&body)?);
+        results.truncate(request.num_results);
+        Ok(results)
+    }
+}
+
+fn parse_results(value: &serde_json::Value) -> Vec<SearchResult> {
+    value
+        .get("results")
+        .and_then(|r| r.as_array())
+        .map(|items| normalize_items(items, &COMMON_FIELDS))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_searxng_results() {
+        let value = serde_json::json!({
+            "results": [
+                { "title": "Tokio", "url": "https://tokio.rs", "content": "Async runtime", "publishedDate": null },
+                { "title": "No url" }
+            ]
+        });
+        let results = parse_results(&value);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].snippet, "Async runtime");
+        assert_eq!(results[0].date, None);
+    }
+}
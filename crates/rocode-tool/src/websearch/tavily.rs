@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use reqwest::Client;
+
+use super::backend::{
+    check_status, normalize_items, parse_json, request_error, SearchRequest, SearchResult,
+    WebSearchBackend, COMMON_FIELDS,
+};
+use crate::{ToolContext, ToolError};
+
+const API_BASE_URL: &str = "https://api.tavily.com";
+
+pub struct TavilyBackend {
+    client: Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl TavilyBackend {
+    pub fn new(client: Client, base_url: Option<String>, api_key: String) -> Self {
+        Self {
+            client,
+            base_url: base_url
+                .unwrap_or_else(|| API_BASE_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            api_key,
+        }
+    }
+}
+
+#[async_trait]
+impl WebSearchBackend for TavilyBackend {
+    fn id(&self) -> &str {
+        "tavily"
+    }
+
+    async fn search(
+        &self,
+        request: &SearchRequest,
+        _ctx: &ToolContext,
+    ) -> Result<Vec<SearchResult>, ToolError> {
+        let depth = match request.search_type.as_deref() {
+            Some("deep") => "advanced",
+            _ => "basic",
+        };
+        let response = self
+            .client
+            .post(format!("{}/search", self.base_url))
+            .bearer_auth(&self.api_key)
+            .json(&serde_json::json!({
+                "query": request.query,
+                "max_results": request.num_results,
+                "search_depth": depth,
+            }))
+            .send()
+            .await
+            .map_err(request_error)?;
+        let status = response.status();
+        let body = response.text().await.map_err(request_error)?;
+        check_status(status, &body)?;
+
+        Ok(parse_results(&parse_json(&body)?))
+    }
+}
+
+fn parse_results(value: &serde_json::Value) -> Vec<SearchResult> {
+    value
+        .get("results")
+        .and_then(|r| r.as_array())
+        .map(|items| normalize_items(items, &COMMON_FIELDS))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tavily_results() {
+        let value = serde_json::json!({
+            "answer": null,
+            "results": [{
+                "title": "Axum",
+                "url": "https://docs.rs/axum",
+                "content": "Web framework",
+                "score": 0.9,
+                "published_date": "2024-03-01"
+            }]
+        });
+        let results = parse_results(&value);
+        assert_eq!(results[0].title, "Axum");
+        assert_eq!(results[0].date.as_deref(), Some("2024-03-01"));
+    }
+}
@@ -37,6 +37,26 @@ pub struct SessionRevertInfo {
     pub diff: Option<String>,
 }
 
+/// Rate governor state for one provider/model, from `/provider/queue`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderQueueInfo {
+    pub key: String,
+    #[serde(default)]
+    pub in_flight: u32,
+    #[serde(default)]
+    pub queued: usize,
+    #[serde(default)]
+    pub oldest_wait_ms: u64,
+    #[serde(default)]
+    pub last_wait_ms: u64,
+    #[serde(default)]
+    pub avg_wait_ms: u64,
+    #[serde(default)]
+    pub max_wait_ms: u64,
+    #[serde(default)]
+    pub cooldown_ms: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionStatusInfo {
     pub status: String,
@@ -60,6 +80,76 @@ pub struct QuestionInfo {
     pub options: Option<Vec<Vec<String>>>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileStatusInfo {
+    pub path: String,
+    /// `modified`, `added`, `deleted`, `renamed`, `untracked`, ...
+    pub status: String,
+    pub staged: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewHunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub header: String,
+    pub lines: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewFile {
+    pub path: String,
+    /// `added`, `deleted` or `modified`.
+    pub status: String,
+    #[serde(default)]
+    pub binary: bool,
+    #[serde(default)]
+    pub additions: u64,
+    #[serde(default)]
+    pub deletions: u64,
+    #[serde(default)]
+    pub hunks: Vec<ReviewHunk>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReviewRejection {
+    pub path: String,
+    pub hunks: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigDiagnostic {
+    /// `error` or `warning`.
+    pub severity: String,
+    pub kind: String,
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    #[serde(default)]
+    pub key: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackgroundTaskInfo {
+    pub id: String,
+    pub subagent_type: String,
+    pub description: String,
+    pub prompt: String,
+    pub status: String,
+    #[serde(default)]
+    pub output: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub started_at: Option<i64>,
+    #[serde(default)]
+    pub finished_at: Option<i64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MessagePart {
     pub id: String,
@@ -546,6 +636,108 @@ impl ApiClient {
         Ok(response.json::<serde_json::Value>()?)
     }
 
+    pub fn list_background_tasks(
+        &self,
+        session_id: &str,
+    ) -> anyhow::Result<Vec<BackgroundTaskInfo>> {
+        let url = format!("{}/session/{}/task", self.base_url, session_id);
+        let response = self.client.get(&url).send()?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().unwrap_or_default();
+            anyhow::bail!("Failed to list background tasks: {} - {}", status, text);
+        }
+
+        Ok(response.json::<Vec<BackgroundTaskInfo>>()?)
+    }
+
+    pub fn cancel_background_task(&self, session_id: &str, task_id: &str) -> anyhow::Result<bool> {
+        let url = format!(
+            "{}/session/{}/task/{}/cancel",
+            self.base_url, session_id, task_id
+        );
+        let response = self.client.post(&url).send()?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().unwrap_or_default();
+            anyhow::bail!("Failed to cancel background task: {} - {}", status, text);
+        }
+
+        Ok(response.json::<bool>()?)
+    }
+
+    /// The effective config of `directory`, or of the server's working
+    /// directory when `None`.
+    pub fn get_config(&self, directory: Option<&str>) -> anyhow::Result<serde_json::Value> {
+        let url = format!("{}/config", self.base_url);
+        let mut request = self.client.get(&url);
+        if let Some(directory) = directory {
+            request = request.query(&[("directory", directory)]);
+        }
+        let response = request.send()?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().unwrap_or_default();
+            anyhow::bail!("Failed to get config: {} - {}", status, text);
+        }
+
+        Ok(response.json::<serde_json::Value>()?)
+    }
+
+    /// Merges `patch` into the project (`scope = "project"`, in `directory`)
+    /// or global config file and returns the effective config. Top-level
+    /// keys listed in `replace` are overwritten rather than merged.
+    pub fn patch_config(
+        &self,
+        patch: &serde_json::Value,
+        scope: &str,
+        directory: Option<&str>,
+        replace: &[String],
+    ) -> anyhow::Result<serde_json::Value> {
+        let url = format!("{}/config", self.base_url);
+        let mut params = vec![("scope", scope.to_string())];
+        if let Some(directory) = directory {
+            params.push(("directory", directory.to_string()));
+        }
+        if !replace.is_empty() {
+            params.push(("replace", replace.join(",")));
+        }
+        let response = self.client.patch(&url).query(&params).json(patch).send()?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().unwrap_or_default();
+            anyhow::bail!("Failed to update config: {} - {}", status, text);
+        }
+
+        Ok(response.json::<serde_json::Value>()?)
+    }
+
+    /// Problems found in the config files for `directory`, or for the
+    /// server's working directory when `None`.
+    pub fn validate_config(
+        &self,
+        directory: Option<&str>,
+    ) -> anyhow::Result<Vec<ConfigDiagnostic>> {
+        let url = format!("{}/config/validate", self.base_url);
+        let mut request = self.client.get(&url);
+        if let Some(directory) = directory {
+            request = request.query(&[("directory", directory)]);
+        }
+        let response = request.send()?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().unwrap_or_default();
+            anyhow::bail!("Failed to validate config: {} - {}", status, text);
+        }
+
+        Ok(response.json::<Vec<ConfigDiagnostic>>()?)
+    }
+
     pub fn get_config_providers(&self) -> anyhow::Result<ProviderListResponse> {
         let url = format!("{}/config/providers", self.base_url);
 
@@ -781,6 +973,29 @@ impl ApiClient {
         Ok(status.formatters)
     }
 
+    pub fn get_provider_queues(&self) -> anyhow::Result<Vec<ProviderQueueInfo>> {
+        let url = format!("{}/provider/queue", self.base_url);
+        let response = self.client.get(&url).send()?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().unwrap_or_default();
+            anyhow::bail!("Failed to get provider queues: {} - {}", status, text);
+        }
+        Ok(response.json::<Vec<ProviderQueueInfo>>()?)
+    }
+
+    pub fn get_file_status(&self) -> anyhow::Result<Vec<FileStatusInfo>> {
+        let url = format!("{}/file/status", self.base_url);
+        let response = self.client.get(&url).send()?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().unwrap_or_default();
+            anyhow::bail!("Failed to get file status: {} - {}", status, text);
+        }
+        let files: Vec<FileStatusInfo> = response.json()?;
+        Ok(files)
+    }
+
     pub fn share_session(&self, session_id: &str) -> anyhow::Result<ShareResponse> {
         let url = format!("{}/session/{}/share", self.base_url, session_id);
         let response = self.client.post(&url).send()?;
@@ -833,6 +1048,34 @@ impl ApiClient {
         Ok(response.json::<CompactResponse>()?)
     }
 
+    pub fn get_session_review(&self, session_id: &str) -> anyhow::Result<Vec<ReviewFile>> {
+        let url = format!("{}/session/{}/review", self.base_url, session_id);
+        let response = self.client.get(&url).send()?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().unwrap_or_default();
+            anyhow::bail!("Failed to load session changes: {} - {}", status, text);
+        }
+        Ok(response.json::<Vec<ReviewFile>>()?)
+    }
+
+    /// Reverts the given hunks on disk; returns the changes still pending.
+    pub fn reject_review_hunks(
+        &self,
+        session_id: &str,
+        files: Vec<ReviewRejection>,
+    ) -> anyhow::Result<Vec<ReviewFile>> {
+        let url = format!("{}/session/{}/review/reject", self.base_url, session_id);
+        let body = serde_json::json!({ "files": files });
+        let response = self.client.post(&url).json(&body).send()?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().unwrap_or_default();
+            anyhow::bail!("Failed to reject changes: {} - {}", status, text);
+        }
+        Ok(response.json::<Vec<ReviewFile>>()?)
+    }
+
     pub fn revert_session(
         &self,
         session_id: &str,
@@ -10,6 +10,7 @@ use std::time::{Duration, Instant};
 
 use chrono::{TimeZone, Utc};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use rocode_config::{KeybindsConfig, TuiConfig};
 
 use crate::api::{
     ApiClient, McpStatusInfo, MessageInfo, QuestionInfo, SessionInfo, SessionRevertInfo,
@@ -18,22 +19,27 @@ use crate::app::state::AppState;
 use crate::app::terminal;
 use crate::command::CommandAction;
 use crate::components::{
-    exit_logo_lines, Agent, AgentSelectDialog, AlertDialog, CommandPalette, ForkDialog, ForkEntry,
-    HelpDialog, HomeView, McpDialog, McpItem, Model, ModelSelectDialog, PermissionAction,
-    PermissionPrompt, Prompt, PromptStashDialog, ProviderDialog, QuestionOption, QuestionPrompt,
-    QuestionRequest, QuestionType, SessionDeleteState, SessionExportDialog, SessionItem,
-    SessionListDialog, SessionRenameDialog, SessionView, SkillListDialog, SlashCommandPopup,
-    StashItem, StatusDialog, StatusLine, SubagentDialog, TagDialog, TaskKind, ThemeListDialog,
-    ThemeOption, TimelineDialog, TimelineEntry, Toast, ToastVariant,
+    collect_session_images, exit_logo_lines, image_label, switcher_entries, Agent,
+    AgentSelectDialog, AlertDialog, CommandPalette, FileTree, FileTreeAction, ForkDialog,
+    ForkEntry, HelpDialog, HomeView, ImageViewerDialog, McpDialog, McpItem, Model,
+    ModelSelectDialog, PermissionAction, PermissionPrompt, Prompt, PromptStashDialog,
+    ProviderDialog, QuestionOption, QuestionPrompt, QuestionRequest, QuestionType, ReviewAction,
+    ReviewView, SessionDeleteState, SessionExportDialog, SessionItem, SessionListDialog,
+    SessionRenameDialog, SessionSplit, SessionSwitcherDialog, SessionView, SettingsAction,
+    SettingsView, SkillListDialog, SlashCommandPopup, StashItem, StatusDialog, StatusLine,
+    SubagentDialog, SubagentInfo, SubagentMessage, TagDialog, TaskKind, ThemeListDialog,
+    ThemeOption, TimelineDialog, TimelineEntry, Toast, ToastVariant, MAX_SPLIT_PANES,
 };
 use crate::context::keybind::LeaderKeyState;
 use crate::context::{
-    AppContext, McpConnectionStatus, McpServerStatus, Message, MessagePart as ContextMessagePart,
-    MessageRole, RevertInfo, Session, SessionStatus, TokenUsage,
+    AppContext, KeybindRegistry, McpConnectionStatus, McpServerStatus, Message,
+    MessagePart as ContextMessagePart, MessageRole, RevertInfo, Session, SessionStatus, TokenUsage,
 };
 use crate::event::{CustomEvent, Event, StateChange};
 use crate::router::Route;
-use crate::ui::{line_from_cells, strip_session_gutter, truncate, Clipboard, Selection};
+use crate::ui::{
+    highlight_cell, line_from_cells, strip_session_gutter, truncate, Clipboard, Selection,
+};
 
 // TS parity: renderer targetFps is 60, ~16ms frame budget.
 const TICK_RATE_MS: u64 = 16;
@@ -42,6 +48,7 @@ const SESSION_SYNC_DEBOUNCE_MS: u64 = 180;
 const SESSION_FULL_SYNC_INTERVAL_SECS: u64 = 10;
 const QUESTION_SYNC_FALLBACK_SECS: u64 = 5;
 const PERF_LOG_INTERVAL_SECS: u64 = 10;
+const MULTI_CLICK_MS: u64 = 400;
 const ANSI_RESET: &str = "\x1b[0m";
 const ANSI_DIM: &str = "\x1b[90m";
 const ANSI_BOLD: &str = "\x1b[1m";
@@ -55,10 +62,13 @@ pub struct App {
     prompt: Prompt,
     selection: Selection,
     session_view: Option<SessionView>,
+    session_split: SessionSplit,
     active_session_id: Option<String>,
     command_palette: CommandPalette,
     slash_popup: SlashCommandPopup,
     leader_state: LeaderKeyState,
+    /// Set while dispatching the key pressed right after the leader.
+    after_leader: bool,
     model_select: ModelSelectDialog,
     agent_select: AgentSelectDialog,
     alert_dialog: AlertDialog,
@@ -72,9 +82,16 @@ pub struct App {
     status_dialog: StatusDialog,
     mcp_dialog: McpDialog,
     timeline_dialog: TimelineDialog,
+    image_viewer: ImageViewerDialog,
     fork_dialog: ForkDialog,
+    session_switcher: SessionSwitcherDialog,
     provider_dialog: ProviderDialog,
     subagent_dialog: SubagentDialog,
+    settings_view: SettingsView,
+    /// Route to return to when the settings screen is closed.
+    settings_return: Option<Route>,
+    review_view: ReviewView,
+    file_tree: FileTree,
     tag_dialog: TagDialog,
     permission_prompt: PermissionPrompt,
     question_prompt: QuestionPrompt,
@@ -89,14 +106,17 @@ pub struct App {
     pending_question_queue: VecDeque<String>,
     pending_questions: HashMap<String, QuestionInfo>,
     pending_initial_submit: bool,
-    pending_session_sync: Option<String>,
-    pending_session_sync_due_at: Option<Instant>,
+    /// Debounced incremental syncs for the focused session and split panes.
+    pending_session_syncs: HashMap<String, Instant>,
     last_session_sync: Instant,
     last_full_session_sync: Instant,
     last_question_sync: Instant,
     last_aux_sync: Instant,
     last_process_refresh: Instant,
     last_perf_log: Instant,
+    /// Time, position and count of the last left click, for double and
+    /// triple click selection.
+    last_click: Option<(Instant, u16, u16, u8)>,
     perf: PerfCounters,
     perf_log_info: bool,
     event_caused_change: bool,
@@ -230,10 +250,12 @@ impl App {
             prompt,
             selection: Selection::new(),
             session_view: None,
+            session_split: SessionSplit::new(),
             active_session_id: None,
             command_palette: CommandPalette::new(),
             slash_popup: SlashCommandPopup::new(),
             leader_state: LeaderKeyState::new(),
+            after_leader: false,
             model_select: ModelSelectDialog::new(),
             agent_select: AgentSelectDialog::new(),
             alert_dialog: AlertDialog::info(""),
@@ -247,9 +269,15 @@ impl App {
             status_dialog: StatusDialog::new(),
             mcp_dialog: McpDialog::new(),
             timeline_dialog: TimelineDialog::new(),
+            image_viewer: ImageViewerDialog::new(),
             fork_dialog: ForkDialog::new(),
+            session_switcher: SessionSwitcherDialog::new(),
             provider_dialog: ProviderDialog::new(),
             subagent_dialog: SubagentDialog::new(),
+            settings_view: SettingsView::new(),
+            settings_return: None,
+            review_view: ReviewView::new(),
+            file_tree: FileTree::new(),
             tag_dialog: TagDialog::new(),
             permission_prompt: PermissionPrompt::new(),
             question_prompt: QuestionPrompt::new(),
@@ -263,14 +291,14 @@ impl App {
             pending_question_queue: VecDeque::new(),
             pending_questions: HashMap::new(),
             pending_initial_submit,
-            pending_session_sync: None,
-            pending_session_sync_due_at: None,
+            pending_session_syncs: HashMap::new(),
             last_session_sync: Instant::now(),
             last_full_session_sync: Instant::now(),
             last_question_sync: Instant::now(),
             last_aux_sync: Instant::now(),
             last_process_refresh: Instant::now(),
             last_perf_log: Instant::now(),
+            last_click: None,
             perf: PerfCounters::default(),
             perf_log_info: env_var_enabled("ROCODE_PERF_LOG"),
             event_caused_change: true,
@@ -281,6 +309,7 @@ impl App {
         let _ = app.refresh_skill_list_dialog();
         app.refresh_session_list_dialog();
         app.refresh_theme_list_dialog();
+        app.refresh_tui_config();
         let _ = app.refresh_lsp_status();
         let _ = app.refresh_mcp_dialog();
         let _ = app.sync_question_requests();
@@ -296,6 +325,7 @@ impl App {
     }
 
     pub fn run(&mut self) -> anyhow::Result<()> {
+        self.report_config_diagnostics();
         self.draw()?;
 
         while self.state != AppState::Exiting {
@@ -445,6 +475,16 @@ impl App {
                     return Ok(());
                 }
 
+                if matches!(self.context.current_route(), Route::Settings) {
+                    self.handle_settings_key(*key);
+                    return Ok(());
+                }
+
+                if matches!(self.context.current_route(), Route::Review { .. }) {
+                    self.handle_review_key(*key);
+                    return Ok(());
+                }
+
                 // Leader key handling
                 if self.leader_state.active {
                     if self.leader_state.check_timeout() {
@@ -457,26 +497,60 @@ impl App {
                             KeyCode::Char('a') => Some(CommandAction::SwitchAgent),
                             KeyCode::Char('t') => Some(CommandAction::SwitchTheme),
                             KeyCode::Char('b') => Some(CommandAction::ToggleSidebar),
+                            KeyCode::Char('f') => Some(CommandAction::ToggleFileTree),
+                            KeyCode::Char('w') => Some(CommandAction::QuickSwitchSession),
                             KeyCode::Char('s') => Some(CommandAction::ViewStatus),
                             KeyCode::Char('q') => Some(CommandAction::Exit),
                             KeyCode::Char('u') => Some(CommandAction::Undo),
                             KeyCode::Char('r') => Some(CommandAction::Redo),
+                            KeyCode::Char('y') => Some(CommandAction::CopySelectionMarkdown),
                             _ => None,
                         };
                         self.leader_state.reset();
                         if let Some(action) = action {
                             self.execute_command_action(action)?;
+                        } else if self
+                            .context
+                            .keybind
+                            .read()
+                            .has_leader_binding(key.code, key.modifiers)
+                        {
+                            // `<leader>` bindings from the config.
+                            self.after_leader = true;
+                            let handled = self.handle_bound_key(*key);
+                            self.after_leader = false;
+                            handled?;
                         }
                         return Ok(());
                     }
                 }
 
-                // Ctrl+X starts leader key sequence
-                if key.code == KeyCode::Char('x') && key.modifiers == KeyModifiers::CONTROL {
-                    self.leader_state.start(KeyCode::Char('x'));
+                if self.matches_keybind("leader", *key) {
+                    self.leader_state.start(key.code);
                     return Ok(());
                 }
 
+                if self.file_tree.is_focused()
+                    && matches!(
+                        self.context.current_route(),
+                        Route::Home | Route::Session { .. }
+                    )
+                {
+                    let theme = self.context.theme.read().clone();
+                    match self.file_tree.handle_key(*key, &theme) {
+                        FileTreeAction::Ignored => {}
+                        FileTreeAction::None => return Ok(()),
+                        FileTreeAction::Insert(reference) => {
+                            self.insert_prompt_reference(&reference);
+                            return Ok(());
+                        }
+                        FileTreeAction::Refresh => {
+                            self.refresh_file_tree();
+                            return Ok(());
+                        }
+                    }
+                }
+
                 // Ctrl+Shift+C (crossterm reports uppercase 'C' with SHIFT modifier)
                 if (key.code == KeyCode::Char('C') || key.code == KeyCode::Char('c'))
                     && key.modifiers.contains(KeyModifiers::CONTROL)
@@ -488,7 +562,7 @@ impl App {
 
                 if key.code == KeyCode::Char('c') && key.modifiers == KeyModifiers::CONTROL {
                     // If there's an active selection, copy it instead of exiting (TS parity)
-                    if self.selection.is_active() {
+                    if self.has_selection() {
                         self.copy_selection();
                         return Ok(());
                     }
@@ -503,8 +577,8 @@ impl App {
                             return Ok(());
                         }
                     }
-                    if self.selection.is_active() {
-                        self.selection.clear();
+                    if self.has_selection() {
+                        self.clear_selection();
                         return Ok(());
                     }
                 }
@@ -539,6 +613,19 @@ impl App {
                     }
                 }
 
+                // Vim editing claims keys ahead of the single-letter shortcuts below.
+                if matches!(
+                    self.context.current_route(),
+                    Route::Home | Route::Session { .. }
+                ) {
+                    if let Some(submit) = self.prompt.handle_vim_key(*key) {
+                        if submit {
+                            self.submit_prompt()?;
+                        }
+                        return Ok(());
+                    }
+                }
+
                 // 'p' toggles process panel focus when sidebar is visible
                 if key.code == KeyCode::Char('p') && key.modifiers.is_empty() {
                     let sidebar_visible = *self.context.show_sidebar.read();
@@ -556,118 +643,7 @@ impl App {
                     return Ok(());
                 }
 
-                if self.matches_keybind("session_interrupt", *key) {
-                    if self.prompt.is_shell_mode() {
-                        self.prompt.exit_shell_mode();
-                        self.prompt.clear_interrupt_confirmation();
-                        return Ok(());
-                    }
-                    if let Route::Session { session_id } = self.context.current_route() {
-                        let status = {
-                            let session_ctx = self.context.session.read();
-                            session_ctx.status(&session_id).clone()
-                        };
-                        if !matches!(status, SessionStatus::Idle) {
-                            if !self.prompt.register_interrupt_keypress() {
-                                return Ok(());
-                            }
-                            if let Some(client) = self.context.get_api_client() {
-                                let _ = client.abort_session(&session_id);
-                            }
-                            self.prompt.clear_interrupt_confirmation();
-                            self.set_session_status(&session_id, SessionStatus::Idle);
-                            self.sync_prompt_spinner_state();
-                            return Ok(());
-                        }
-                    }
-                    self.prompt.clear_interrupt_confirmation();
-                    return Ok(());
-                }
-
-                if self.matches_keybind("input_paste", *key) {
-                    self.paste_clipboard_to_prompt();
-                    return Ok(());
-                }
-                if self.matches_keybind("input_copy", *key) {
-                    self.copy_prompt_to_clipboard();
-                    return Ok(());
-                }
-                if self.matches_keybind("input_cut", *key) {
-                    self.cut_prompt_to_clipboard();
-                    return Ok(());
-                }
-                if self.matches_keybind("history_previous", *key) {
-                    self.prompt.history_previous_entry();
-                    return Ok(());
-                }
-                if self.matches_keybind("history_next", *key) {
-                    self.prompt.history_next_entry();
-                    return Ok(());
-                }
-                if self.matches_keybind("page_up", *key) {
-                    if let Route::Session { .. } = self.context.current_route() {
-                        if let Some(ref mut sv) = self.session_view {
-                            sv.scroll_page_up();
-                            return Ok(());
-                        }
-                    }
-                }
-                if self.matches_keybind("page_down", *key) {
-                    if let Route::Session { .. } = self.context.current_route() {
-                        if let Some(ref mut sv) = self.session_view {
-                            sv.scroll_page_down();
-                            return Ok(());
-                        }
-                    }
-                }
-
-                if self.matches_keybind("command_palette", *key) {
-                    self.sync_command_palette_labels();
-                    self.command_palette.open();
-                    return Ok(());
-                }
-                if self.matches_keybind("model_cycle", *key) {
-                    self.refresh_model_dialog();
-                    self.model_select.open();
-                    return Ok(());
-                }
-                if self.matches_keybind("agent_cycle", *key) {
-                    self.cycle_agent(1);
-                    return Ok(());
-                }
-                if self.matches_keybind("agent_cycle_reverse", *key) {
-                    self.cycle_agent(-1);
-                    return Ok(());
-                }
-                if self.matches_keybind("variant_cycle", *key) {
-                    self.cycle_model_variant();
-                    return Ok(());
-                }
-                if self.matches_keybind("sidebar_toggle", *key) {
-                    self.context.toggle_sidebar();
-                    return Ok(());
-                }
-                if self.matches_keybind("display_thinking", *key) {
-                    self.context.toggle_thinking();
-                    return Ok(());
-                }
-                if self.matches_keybind("tool_details", *key) {
-                    self.context.toggle_tool_details();
-                    return Ok(());
-                }
-                if self.matches_keybind("input_clear", *key) {
-                    self.prompt.clear();
-                    return Ok(());
-                }
-                if self.matches_keybind("input_newline", *key) {
-                    let route = self.context.current_route();
-                    if matches!(route, Route::Home | Route::Session { .. }) {
-                        self.prompt.insert_text("\n");
-                        return Ok(());
-                    }
-                }
-                if self.matches_keybind("help_toggle", *key) {
-                    self.help_dialog.open();
+                if self.handle_bound_key(*key)? {
                     return Ok(());
                 }
 
@@ -703,6 +679,7 @@ impl App {
             }
             Event::Resize(_, _) => {
                 self.terminal.autoresize()?;
+                self.context.graphics.write().invalidate();
             }
             Event::Mouse(mouse_event) => {
                 use crossterm::event::{MouseButton, MouseEventKind};
@@ -713,7 +690,7 @@ impl App {
 
                         if button == MouseButton::Right {
                             // Right-click copies selection (if any) then clears it
-                            if self.selection.is_active() {
+                            if self.has_selection() {
                                 self.copy_selection();
                             }
                             return Ok(());
@@ -748,7 +725,17 @@ impl App {
                         }
 
                         if button == MouseButton::Left {
+                            let clicks = self.register_click(col, row);
+                            self.clear_selection();
                             if let Route::Session { .. } = self.context.current_route() {
+                                let pane = self
+                                    .session_split
+                                    .pane_at(col, row)
+                                    .map(|view| view.session_id().to_string());
+                                if let Some(session_id) = pane {
+                                    self.focus_session(&session_id);
+                                    return Ok(());
+                                }
                                 if let Some(ref mut sv) = self.session_view {
                                     if sv.handle_sidebar_click(col, row) {
                                         return Ok(());
@@ -756,20 +743,41 @@ impl App {
                                     if sv.is_point_in_sidebar(col, row) {
                                         return Ok(());
                                     }
+                                    if let Some(url) = sv.image_at(col, row) {
+                                        self.open_image_viewer(Some(&url));
+                                        return Ok(());
+                                    }
+                                    if let Some(code) = sv.code_block_at(col, row) {
+                                        self.copy_to_clipboard(&code, "Copied code block");
+                                        return Ok(());
+                                    }
                                     if sv.handle_click(col, row) {
                                         return Ok(());
                                     }
+                                    let selected = match clicks {
+                                        2 => sv.select_word_at(col, row),
+                                        3 => sv.select_line_at(col, row),
+                                        _ => sv.begin_selection(col, row),
+                                    };
+                                    if selected {
+                                        return Ok(());
+                                    }
                                 }
                             }
-                            // Clear previous selection and start a new one
-                            self.selection.start(row, col);
+                            // Start a new screen selection
+                            self.selection.start(usize::from(row), col);
                         }
                     }
                     MouseEventKind::ScrollUp => {
                         if self.handle_dialog_mouse(mouse_event)? {
                             return Ok(());
                         }
-                        if let Some(ref mut sv) = self.session_view {
+                        if let Some(pane) = self
+                            .session_split
+                            .pane_at(mouse_event.column, mouse_event.row)
+                        {
+                            pane.scroll_up_mouse();
+                        } else if let Some(ref mut sv) = self.session_view {
                             if !sv.scroll_sidebar_up_at(mouse_event.column, mouse_event.row) {
                                 sv.scroll_up_mouse();
                             }
@@ -779,7 +787,12 @@ impl App {
                         if self.handle_dialog_mouse(mouse_event)? {
                             return Ok(());
                         }
-                        if let Some(ref mut sv) = self.session_view {
+                        if let Some(pane) = self
+                            .session_split
+                            .pane_at(mouse_event.column, mouse_event.row)
+                        {
+                            pane.scroll_down_mouse();
+                        } else if let Some(ref mut sv) = self.session_view {
                             if !sv.scroll_sidebar_down_at(mouse_event.column, mouse_event.row) {
                                 sv.scroll_down_mouse();
                             }
@@ -788,7 +801,10 @@ impl App {
                     MouseEventKind::Drag(_) => {
                         let col = mouse_event.column;
                         let row = mouse_event.row;
-                        self.selection.update(row, col);
+                        match self.session_view.as_mut() {
+                            Some(sv) if sv.is_selecting() => sv.update_selection(col, row),
+                            _ => self.selection.update(usize::from(row), col),
+                        }
                     }
                     MouseEventKind::Moved => {
                         if self.handle_dialog_mouse(mouse_event)? {
@@ -798,6 +814,9 @@ impl App {
                     }
                     MouseEventKind::Up(_) => {
                         self.selection.finalize();
+                        if let Some(sv) = self.session_view.as_mut() {
+                            sv.finish_selection();
+                        }
                     }
                     _ => {}
                 }
@@ -864,6 +883,17 @@ impl App {
                     }
                     self.event_caused_change = true;
                 }
+                CustomEvent::FileTreeScanned {
+                    root,
+                    files,
+                    status,
+                } => {
+                    self.file_tree.load(root, files.clone());
+                    if let Some(status) = status {
+                        self.file_tree.set_git_status(status);
+                    }
+                    self.event_caused_change = true;
+                }
                 CustomEvent::PromptDispatchSessionFinished {
                     session_id,
                     optimistic_message_id,
@@ -883,13 +913,15 @@ impl App {
                 CustomEvent::StateChanged(StateChange::SessionUpdated(session_id)) => {
                     self.perf.session_updated_events =
                         self.perf.session_updated_events.saturating_add(1);
-                    if let Route::Session { session_id: active } = self.context.current_route() {
-                        if active == *session_id {
-                            self.pending_session_sync = Some(session_id.to_string());
-                            self.pending_session_sync_due_at = Some(
-                                Instant::now() + Duration::from_millis(SESSION_SYNC_DEBOUNCE_MS),
-                            );
-                        }
+                    let is_focused = matches!(
+                        self.context.current_route(),
+                        Route::Session { session_id: active } if active == *session_id
+                    );
+                    if is_focused || self.session_split.contains(session_id) {
+                        self.pending_session_syncs.insert(
+                            session_id.to_string(),
+                            Instant::now() + Duration::from_millis(SESSION_SYNC_DEBOUNCE_MS),
+                        );
                     }
                     self.sync_prompt_spinner_state();
                 }
@@ -918,6 +950,10 @@ impl App {
                     );
                     self.sync_prompt_spinner_state();
                 }
+                CustomEvent::StateChanged(StateChange::ConfigUpdated) => {
+                    self.refresh_tui_config();
+                    self.event_caused_change = true;
+                }
                 CustomEvent::StateChanged(StateChange::QuestionCreated { session_id, .. })
                 | CustomEvent::StateChanged(StateChange::QuestionResolved { session_id, .. }) => {
                     let should_sync = match self.context.current_route() {
@@ -938,6 +974,9 @@ impl App {
                 tick_changed |= self.toast.tick(TICK_RATE_MS);
                 tick_changed |= self.prompt.tick_spinner(TICK_RATE_MS);
                 tick_changed |= self.sync_prompt_spinner_state();
+                if let Some(sv) = self.session_view.as_mut() {
+                    tick_changed |= sv.auto_scroll_selection();
+                }
 
                 if self.pending_initial_submit && !self.prompt.get_input().trim().is_empty() {
                     self.pending_initial_submit = false;
@@ -945,32 +984,33 @@ impl App {
                     tick_changed = true;
                 }
 
-                let route = self.context.current_route();
-                if let Route::Session { session_id } = &route {
-                    let should_sync_pending =
-                        self.pending_session_sync.as_deref() == Some(session_id.as_str())
-                            && self
-                                .pending_session_sync_due_at
-                                .map(|due| Instant::now() >= due)
-                                .unwrap_or(false);
-                    if should_sync_pending {
-                        let sync_result = self
-                            .sync_session_from_server_with_mode(
-                                session_id,
-                                SessionSyncMode::Incremental,
+                let now = Instant::now();
+                let due_syncs = self
+                    .pending_session_syncs
+                    .iter()
+                    .filter(|(_, due)| now >= **due)
+                    .map(|(session_id, _)| session_id.clone())
+                    .collect::<Vec<_>>();
+                for session_id in due_syncs {
+                    self.pending_session_syncs.remove(&session_id);
+                    let sync_result = self
+                        .sync_session_from_server_with_mode(
+                            &session_id,
+                            SessionSyncMode::Incremental,
+                        )
+                        .or_else(|_| {
+                            self.sync_session_from_server_with_mode(
+                                &session_id,
+                                SessionSyncMode::Full,
                             )
-                            .or_else(|_| {
-                                self.sync_session_from_server_with_mode(
-                                    session_id,
-                                    SessionSyncMode::Full,
-                                )
-                            });
-                        if sync_result.is_ok() {
-                            tick_changed = true;
-                        }
-                        self.pending_session_sync = None;
-                        self.pending_session_sync_due_at = None;
+                        });
+                    if sync_result.is_ok() {
+                        tick_changed = true;
                     }
+                }
+
+                let route = self.context.current_route();
+                if let Route::Session { session_id } = &route {
                     if self.last_full_session_sync.elapsed()
                         >= Duration::from_secs(SESSION_FULL_SYNC_INTERVAL_SECS)
                     {
@@ -992,6 +1032,10 @@ impl App {
                     let _ = self.refresh_skill_list_dialog();
                     let _ = self.refresh_lsp_status();
                     let _ = self.refresh_mcp_dialog();
+                    if self.subagent_dialog.is_open() {
+                        let tasks = self.background_task_subagents();
+                        self.subagent_dialog.update(tasks);
+                    }
                     self.last_aux_sync = Instant::now();
                     tick_changed = true;
                 }
@@ -1031,7 +1075,9 @@ impl App {
             || self.theme_list_dialog.is_open()
             || self.mcp_dialog.is_open()
             || self.timeline_dialog.is_open()
+            || self.image_viewer.is_open()
             || self.fork_dialog.is_open()
+            || self.session_switcher.is_open()
             || self.provider_dialog.is_open()
             || self.subagent_dialog.is_open()
             || self.tag_dialog.is_open()
@@ -1100,10 +1146,18 @@ impl App {
             self.timeline_dialog.close();
             return true;
         }
+        if self.image_viewer.is_open() {
+            self.image_viewer.close();
+            return true;
+        }
         if self.fork_dialog.is_open() {
             self.fork_dialog.close();
             return true;
         }
+        if self.session_switcher.is_open() {
+            self.session_switcher.close();
+            return true;
+        }
         if self.provider_dialog.is_open() {
             self.provider_dialog.close();
             return true;
@@ -1206,6 +1260,14 @@ impl App {
             }
             return;
         }
+        if self.image_viewer.is_open() {
+            if up {
+                self.image_viewer.previous();
+            } else {
+                self.image_viewer.next();
+            }
+            return;
+        }
         if self.fork_dialog.is_open() {
             if up {
                 self.fork_dialog.move_up();
@@ -1214,6 +1276,14 @@ impl App {
             }
             return;
         }
+        if self.session_switcher.is_open() {
+            if up {
+                self.session_switcher.move_up();
+            } else {
+                self.session_switcher.move_down();
+            }
+            return;
+        }
         if self.provider_dialog.is_open() {
             if up {
                 self.provider_dialog.move_up();
@@ -1633,6 +1703,7 @@ impl App {
                                         ));
                                         self.alert_dialog.open();
                                     } else {
+                                        self.session_split.forget(&session_id);
                                         if self.active_session_id.as_deref()
                                             == Some(session_id.as_str())
                                         {
@@ -1793,6 +1864,40 @@ impl App {
             return Ok(true);
         }
 
+        if self.image_viewer.is_open() {
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.image_viewer.close(),
+                KeyCode::Left | KeyCode::Up | KeyCode::Char('h') | KeyCode::Char('k') => {
+                    self.image_viewer.previous()
+                }
+                KeyCode::Right | KeyCode::Down | KeyCode::Char('l') | KeyCode::Char('j') => {
+                    self.image_viewer.next()
+                }
+                _ => {}
+            }
+            return Ok(true);
+        }
+
+        if self.session_switcher.is_open() {
+            let split_mode = self.session_switcher.is_split_mode();
+            match key.code {
+                KeyCode::Esc => self.session_switcher.close(),
+                KeyCode::Up => self.session_switcher.move_up(),
+                KeyCode::Down => self.session_switcher.move_down(),
+                KeyCode::Enter => self.confirm_session_switcher(split_mode),
+                KeyCode::Char('s') if !split_mode => self.confirm_session_switcher(true),
+                KeyCode::Char(c @ '1'..='9')
+                    if self
+                        .session_switcher
+                        .select_index(c as usize - '1' as usize) =>
+                {
+                    self.confirm_session_switcher(split_mode);
+                }
+                _ => {}
+            }
+            return Ok(true);
+        }
+
         if self.fork_dialog.is_open() {
             match key.code {
                 KeyCode::Esc => self.fork_dialog.close(),
@@ -1877,6 +1982,9 @@ impl App {
                 KeyCode::Esc => self.subagent_dialog.close(),
                 KeyCode::Up => self.subagent_dialog.scroll_up(),
                 KeyCode::Down => self.subagent_dialog.scroll_down(50),
+                KeyCode::Left => self.subagent_dialog.previous(),
+                KeyCode::Right | KeyCode::Tab => self.subagent_dialog.next(),
+                KeyCode::Char('c') => self.cancel_selected_background_task(),
                 _ => {}
             }
             return Ok(true);
@@ -1907,6 +2015,7 @@ impl App {
             CommandAction::HistoryPrevious => self.prompt.history_previous_entry(),
             CommandAction::HistoryNext => self.prompt.history_next_entry(),
             CommandAction::ToggleSidebar => self.context.toggle_sidebar(),
+            CommandAction::ToggleFileTree => self.toggle_file_tree(),
             CommandAction::ToggleHeader => self.context.toggle_header(),
             CommandAction::ToggleScrollbar => self.context.toggle_scrollbar(),
             CommandAction::SwitchSession => {
@@ -1969,7 +2078,9 @@ impl App {
             CommandAction::NewSession => {
                 self.context.navigate(Route::Home);
                 self.active_session_id = None;
-                self.session_view = None;
+                if let Some(view) = self.session_view.take() {
+                    self.session_split.park(view);
+                }
             }
             CommandAction::ShowHelp => {
                 self.help_dialog.open();
@@ -2019,6 +2130,30 @@ impl App {
             CommandAction::Redo => {
                 self.handle_redo();
             }
+            CommandAction::ReviewChanges => {
+                self.open_review();
+            }
+            CommandAction::OpenImageViewer => {
+                self.open_image_viewer(None);
+            }
+            CommandAction::QuickSwitchSession => {
+                self.open_session_switcher(false);
+            }
+            CommandAction::SplitSession => {
+                if !matches!(self.context.current_route(), Route::Session { .. }) {
+                    self.alert_dialog
+                        .set_message("Open a session before splitting the view.");
+                    self.alert_dialog.open();
+                } else {
+                    self.open_session_switcher(true);
+                }
+            }
+            CommandAction::CloseSplit => {
+                self.session_split.close_all();
+            }
+            CommandAction::ToggleSplitDirection => {
+                self.session_split.toggle_direction();
+            }
             CommandAction::ListSessions | CommandAction::OpenSessionList => {
                 self.refresh_session_list_dialog();
                 self.session_list_dialog
@@ -2027,12 +2162,38 @@ impl App {
             CommandAction::CopySession => {
                 self.handle_copy_session();
             }
+            CommandAction::CopySelectionMarkdown => {
+                self.copy_selection_markdown();
+            }
             CommandAction::OpenStash => {
                 self.open_prompt_stash_dialog();
             }
             CommandAction::OpenSkills => {
                 self.open_skill_list_dialog();
             }
+            CommandAction::OpenBackgroundTasks => {
+                let tasks = self.background_task_subagents();
+                self.subagent_dialog.open_list(tasks);
+            }
+            CommandAction::OpenSettings => {
+                if let Some(client) = self.context.get_api_client() {
+                    let directory = self.project_directory(&client);
+                    match client.get_config(directory.as_deref()) {
+                        Ok(config) => {
+                            self.settings_view.set_config(config);
+                            self.settings_view.set_directory(directory);
+                        }
+                        Err(err) => {
+                            self.alert_dialog
+                                .set_message(&format!("Failed to load config:\n{}", err));
+                            self.alert_dialog.open();
+                            return Ok(());
+                        }
+                    }
+                }
+                self.settings_return = Some(self.context.current_route());
+                self.context.navigate(Route::Settings);
+            }
             CommandAction::OpenThemeList => {
                 self.refresh_theme_list_dialog();
                 let current_theme = self.context.current_theme_name();
@@ -2115,31 +2276,116 @@ impl App {
         self.prompt.clear();
     }
 
-    /// Copy the current screen selection to clipboard and show a toast.
+    fn has_selection(&self) -> bool {
+        self.selection.is_active()
+            || self
+                .session_view
+                .as_ref()
+                .is_some_and(|sv| sv.has_selection())
+    }
+
+    fn clear_selection(&mut self) {
+        self.selection.clear();
+        if let Some(sv) = self.session_view.as_mut() {
+            sv.clear_selection();
+        }
+    }
+
+    /// Copy the current transcript or screen selection to clipboard and show
+    /// a toast.
     fn copy_selection(&mut self) {
-        if !self.selection.is_active() {
-            return;
+        let text = match self.session_view.as_ref() {
+            Some(sv) if sv.has_selection() => sv.selected_text(),
+            _ if self.selection.is_active() => {
+                let lines = &self.screen_lines;
+                let text = self
+                    .selection
+                    .get_selected_text(|row| lines.get(row).cloned());
+                if matches!(self.context.current_route(), Route::Session { .. }) {
+                    strip_session_gutter(&text)
+                } else {
+                    text
+                }
+            }
+            _ => return,
+        };
+        if !text.is_empty() {
+            self.copy_to_clipboard(&text, "Copied to clipboard");
         }
-        let lines = self.screen_lines.clone();
-        let mut text = self
-            .selection
-            .get_selected_text(|row| lines.get(row as usize).cloned());
-        if matches!(self.context.current_route(), Route::Session { .. }) {
-            text = strip_session_gutter(&text);
+        self.clear_selection();
+    }
+
+    /// Copy the raw markdown of the selected messages, or of the last
+    /// assistant message when nothing is selected.
+    fn copy_selection_markdown(&mut self) {
+        let Some(session_id) = self.current_session_id() else {
+            return;
+        };
+        let ids = self
+            .session_view
+            .as_ref()
+            .map(|sv| sv.selected_message_ids())
+            .unwrap_or_default();
+        let markdown = {
+            let session_ctx = self.context.session.read();
+            let messages = session_ctx
+                .messages
+                .get(&session_id)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            if ids.is_empty() {
+                messages
+                    .iter()
+                    .rev()
+                    .find(|m| matches!(m.role, MessageRole::Assistant))
+                    .map(message_markdown)
+                    .unwrap_or_default()
+            } else {
+                messages
+                    .iter()
+                    .filter(|m| ids.contains(&m.id))
+                    .map(message_markdown)
+                    .filter(|text| !text.trim().is_empty())
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            }
+        };
+        if markdown.trim().is_empty() {
+            self.toast
+                .show(ToastVariant::Warning, "Nothing to copy", 2000);
+            return;
         }
-        if !text.is_empty() {
-            match Clipboard::write_text(&text) {
-                Ok(()) => {
-                    self.toast
-                        .show(ToastVariant::Info, "Copied to clipboard", 2000);
-                }
-                Err(err) => {
-                    self.toast
-                        .show(ToastVariant::Error, &format!("Copy failed: {}", err), 3000);
-                }
+        self.copy_to_clipboard(&markdown, "Copied markdown to clipboard");
+        self.clear_selection();
+    }
+
+    fn copy_to_clipboard(&mut self, text: &str, success: &str) {
+        match Clipboard::write_text(text) {
+            Ok(()) => {
+                self.toast.show(ToastVariant::Info, success, 2000);
+            }
+            Err(err) => {
+                self.toast
+                    .show(ToastVariant::Error, &format!("Copy failed: {}", err), 3000);
             }
         }
-        self.selection.clear();
+    }
+
+    /// Counts consecutive clicks on the same cell (1 to 3).
+    fn register_click(&mut self, col: u16, row: u16) -> u8 {
+        let now = Instant::now();
+        let count = match self.last_click {
+            Some((at, last_col, last_row, count))
+                if last_col == col
+                    && last_row == row
+                    && now.duration_since(at) <= Duration::from_millis(MULTI_CLICK_MS) =>
+            {
+                count % 3 + 1
+            }
+            _ => 1,
+        };
+        self.last_click = Some((now, col, row, count));
+        count
     }
 
     fn current_session_id(&self) -> Option<String> {
@@ -2204,6 +2450,233 @@ impl App {
         self.prompt_stash_dialog.open();
     }
 
+    fn background_task_subagents(&self) -> Vec<SubagentInfo> {
+        let (Some(session_id), Some(client)) =
+            (self.current_session_id(), self.context.get_api_client())
+        else {
+            return Vec::new();
+        };
+        let tasks = match client.list_background_tasks(&session_id) {
+            Ok(tasks) => tasks,
+            Err(err) => {
+                tracing::debug!(%err, "failed to list background tasks");
+                return Vec::new();
+            }
+        };
+        tasks
+            .into_iter()
+            .map(|task| {
+                let mut messages = vec![SubagentMessage {
+                    role: "user".to_string(),
+                    content: task.prompt,
+                }];
+                if let Some(output) = task.output.or(task.error) {
+                    messages.push(SubagentMessage {
+                        role: "assistant".to_string(),
+                        content: output,
+                    });
+                }
+                SubagentInfo {
+                    id: task.id,
+                    name: task.description,
+                    category: task.subagent_type,
+                    status: task.status,
+                    messages,
+                }
+            })
+            .collect()
+    }
+
+    fn cancel_selected_background_task(&mut self) {
+        let (Some(session_id), Some(client), Some(task_id)) = (
+            self.current_session_id(),
+            self.context.get_api_client(),
+            self.subagent_dialog.selected_id().map(str::to_string),
+        ) else {
+            return;
+        };
+        if let Err(err) = client.cancel_background_task(&session_id, &task_id) {
+            self.alert_dialog
+                .set_message(&format!("Failed to cancel task:\n{}", err));
+            self.alert_dialog.open();
+            return;
+        }
+        let tasks = self.background_task_subagents();
+        self.subagent_dialog.update(tasks);
+    }
+
+    /// Warns about config problems the server's lenient loader ignored.
+    fn report_config_diagnostics(&mut self) {
+        let Some(client) = self.context.get_api_client() else {
+            return;
+        };
+        let directory = self.project_directory(&client);
+        let Ok(diagnostics) = client.validate_config(directory.as_deref()) else {
+            return;
+        };
+        let Some(first) = diagnostics.first() else {
+            return;
+        };
+        let errors = diagnostics.iter().filter(|d| d.severity == "error").count();
+        let variant = if errors > 0 {
+            ToastVariant::Error
+        } else {
+            ToastVariant::Warning
+        };
+        let more = match diagnostics.len() {
+            1 => String::new(),
+            n => format!(" (+{} more, run `rocode config validate`)", n - 1),
+        };
+        self.toast.show(
+            variant,
+            &format!(
+                "Config: {}:{}: {}{}",
+                first.file, first.line, first.message, more
+            ),
+            8000,
+        );
+    }
+
+    /// Directory of the active session's project, falling back to the
+    /// directory the TUI was started in.
+    fn project_directory(&self, client: &ApiClient) -> Option<String> {
+        self.current_session_id()
+            .and_then(|id| client.get_session(&id).ok())
+            .map(|session| session.directory)
+            .or_else(|| Some(self.context.directory.read().clone()))
+            .filter(|directory| !directory.trim().is_empty())
+    }
+
+    fn refresh_tui_config(&mut self) {
+        let Some(client) = self.context.get_api_client() else {
+            return;
+        };
+        match client.get_config(None) {
+            Ok(config) => self.apply_tui_config(&config),
+            Err(err) => tracing::debug!(%err, "failed to load config for the tui"),
+        }
+    }
+
+    /// Applies the parts of the config the TUI reads itself: vim editing
+    /// and keybind overrides on top of the default bindings.
+    fn apply_tui_config(&mut self, config: &serde_json::Value) {
+        let section = |key: &str| config.get(key).cloned().unwrap_or_default();
+        let tui: TuiConfig = serde_json::from_value(section("tui")).unwrap_or_default();
+        let keybinds: KeybindsConfig =
+            serde_json::from_value(section("keybinds")).unwrap_or_default();
+        let mut registry = KeybindRegistry::new();
+        registry.apply_config(&keybinds);
+        let conflicts = registry.conflicts();
+        *self.context.keybind.write() = registry;
+        if let Some(conflict) = conflicts.first() {
+            for conflict in &conflicts {
+                tracing::warn!(%conflict, "keybind conflict");
+            }
+            self.toast.show(
+                ToastVariant::Warning,
+                &format!("Keybind conflict: {}", conflict),
+                5000,
+            );
+        }
+        self.prompt.set_vim_enabled(tui.vim.unwrap_or(false));
+    }
+
+    fn handle_settings_key(&mut self, key: KeyEvent) {
+        match self.settings_view.handle_key(key) {
+            SettingsAction::None => {}
+            SettingsAction::Close => {
+                let route = self.settings_return.take().unwrap_or(Route::Home);
+                self.context.navigate(route);
+            }
+            SettingsAction::Save {
+                patch,
+                scope,
+                replace,
+            } => {
+                let Some(client) = self.context.get_api_client() else {
+                    self.settings_view
+                        .save_failed("Not connected to the server".to_string());
+                    return;
+                };
+                let directory = self.settings_view.directory().map(str::to_string);
+                let saved =
+                    client.patch_config(&patch, scope.as_str(), directory.as_deref(), &replace);
+                match saved {
+                    Ok(config) => {
+                        self.apply_tui_config(&config);
+                        self.settings_view.save_succeeded(config);
+                    }
+                    Err(err) => self.settings_view.save_failed(err.to_string()),
+                }
+            }
+        }
+    }
+
+    /// Shows and focuses the file tree, focuses it if shown but unfocused,
+    /// and hides it otherwise.
+    fn toggle_file_tree(&mut self) {
+        if self.file_tree.is_focused() {
+            self.file_tree.hide();
+            return;
+        }
+        if self.file_tree.is_visible() {
+            self.file_tree.focus();
+        } else {
+            self.file_tree.show();
+        }
+        self.refresh_file_tree();
+    }
+
+    fn refresh_file_tree(&mut self) {
+        let root = self.context.directory_path();
+        self.file_tree.begin_refresh();
+        let client = self.context.get_api_client();
+        let event_tx = self.event_tx.clone();
+        thread::spawn(move || {
+            let files = FileTree::scan(&root);
+            let status = client.and_then(|client| match client.get_file_status() {
+                Ok(files) => Some(files),
+                Err(err) => {
+                    tracing::debug!(%err, "failed to load file status");
+                    None
+                }
+            });
+            let _ = event_tx.send(Event::Custom(CustomEvent::FileTreeScanned {
+                root,
+                files,
+                status,
+            }));
+        });
+    }
+
+    fn sync_file_tree_touched(&mut self) {
+        let Some(session_id) = self.active_session_id.clone() else {
+            self.file_tree.set_touched(std::iter::empty());
+            return;
+        };
+        let session_ctx = self.context.session.read();
+        let entries = session_ctx.session_diff.get(&session_id);
+        self.file_tree.set_touched(
+            entries
+                .into_iter()
+                .flatten()
+                .map(|entry| entry.file.as_str()),
+        );
+    }
+
+    fn insert_prompt_reference(&mut self, reference: &str) {
+        let needs_space = self
+            .prompt
+            .get_input()
+            .chars()
+            .last()
+            .is_some_and(|c| !c.is_whitespace());
+        if needs_space {
+            self.prompt.insert_text(" ");
+        }
+        self.prompt.insert_text(&format!("{} ", reference));
+    }
+
     fn open_skill_list_dialog(&mut self) {
         if let Err(err) = self.refresh_skill_list_dialog() {
             self.alert_dialog
@@ -2288,6 +2761,154 @@ impl App {
         }
     }
 
+    fn open_session_switcher(&mut self, split_mode: bool) {
+        let Some(client) = self.context.get_api_client() else {
+            return;
+        };
+        let sessions = match client.list_sessions() {
+            Ok(sessions) => sessions,
+            Err(err) => {
+                self.alert_dialog
+                    .set_message(&format!("Failed to list sessions:\n{}", err));
+                self.alert_dialog.open();
+                return;
+            }
+        };
+        let status_map = client.get_session_status().unwrap_or_default();
+        let items = sessions
+            .into_iter()
+            .map(|session| SessionItem {
+                is_busy: status_map.get(&session.id).map(|s| s.busy).unwrap_or(false),
+                id: session.id,
+                title: session.title,
+                directory: session.directory,
+                parent_id: session.parent_id,
+                updated_at: session.time.updated,
+            })
+            .collect::<Vec<_>>();
+        let focused = match self.context.current_route() {
+            Route::Session { session_id } => Some(session_id),
+            _ => None,
+        };
+        let mut entries = switcher_entries(
+            focused.as_deref(),
+            &self.session_split.pane_session_ids(),
+            items,
+        );
+        if split_mode {
+            entries.retain(|entry| entry.relation != "focused" && !entry.in_split);
+        }
+        self.session_switcher.open(entries, split_mode);
+    }
+
+    fn confirm_session_switcher(&mut self, split: bool) {
+        let Some(entry) = self.session_switcher.selected_entry().cloned() else {
+            return;
+        };
+        self.session_switcher.close();
+        if split {
+            self.toggle_split_pane(&entry.session_id);
+        } else {
+            self.focus_session(&entry.session_id);
+        }
+    }
+
+    fn focus_session(&mut self, session_id: &str) {
+        if self.active_session_id.as_deref() == Some(session_id) {
+            return;
+        }
+        self.context.navigate(Route::Session {
+            session_id: session_id.to_string(),
+        });
+        self.ensure_session_view(session_id);
+        let _ = self.sync_session_from_server(session_id);
+    }
+
+    /// Opens `session_id` as a watch pane next to the focused session, or
+    /// closes its pane when it is already shown.
+    fn toggle_split_pane(&mut self, session_id: &str) {
+        if self.session_split.close(session_id) {
+            return;
+        }
+        if self.active_session_id.as_deref() == Some(session_id) {
+            return;
+        }
+        let view = self
+            .session_split
+            .take_for_focus(session_id, None)
+            .unwrap_or_else(|| SessionView::new(self.context.clone(), session_id.to_string()));
+        if !self.session_split.open(view) {
+            self.toast.show(
+                ToastVariant::Warning,
+                &format!("At most {} split panes", MAX_SPLIT_PANES),
+                3000,
+            );
+            return;
+        }
+        let _ = self.sync_session_from_server(session_id);
+    }
+
+    fn open_review(&mut self) {
+        let Some(session_id) = self.current_session_id() else {
+            self.alert_dialog
+                .set_message("No active session to review.");
+            self.alert_dialog.open();
+            return;
+        };
+        let Some(client) = self.context.get_api_client() else {
+            return;
+        };
+        match client.get_session_review(&session_id) {
+            Ok(files) => {
+                self.review_view.set_files(files);
+                self.context.navigate(Route::Review { session_id });
+            }
+            Err(err) => {
+                self.alert_dialog
+                    .set_message(&format!("Failed to load session changes:\n{}", err));
+                self.alert_dialog.open();
+            }
+        }
+    }
+
+    fn handle_review_key(&mut self, key: KeyEvent) {
+        match self.review_view.handle_key(key) {
+            ReviewAction::None => {}
+            ReviewAction::Close => {
+                let route = match self.context.current_route() {
+                    Route::Review { session_id } => Route::Session { session_id },
+                    _ => Route::Home,
+                };
+                self.context.navigate(route);
+            }
+            ReviewAction::Apply(rejections) => {
+                let Route::Review { session_id } = self.context.current_route() else {
+                    return;
+                };
+                let Some(client) = self.context.get_api_client() else {
+                    self.review_view.set_message("Not connected to the server");
+                    return;
+                };
+                let count: usize = rejections.iter().map(|r| r.hunks.len().max(1)).sum();
+                match client.reject_review_hunks(&session_id, rejections) {
+                    Ok(files) => {
+                        self.review_view.set_files(files);
+                        self.toast.show(
+                            ToastVariant::Info,
+                            &format!(
+                                "Reverted {} change{}; the agent will be told on the next prompt",
+                                count,
+                                if count == 1 { "" } else { "s" }
+                            ),
+                            4000,
+                        );
+                    }
+                    Err(err) => self.review_view.set_message(err.to_string()),
+                }
+            }
+        }
+    }
+
     fn handle_undo(&mut self) {
         let Some(session_id) = self.current_session_id() else {
             self.alert_dialog.set_message("No active session for undo.");
@@ -2413,6 +3034,27 @@ impl App {
         self.timeline_dialog.open(entries);
     }
 
+    fn open_image_viewer(&mut self, selected: Option<&str>) {
+        let images = self
+            .current_session_id()
+            .and_then(|session_id| {
+                let session_ctx = self.context.session.read();
+                session_ctx
+                    .messages
+                    .get(&session_id)
+                    .map(|messages| collect_session_images(messages))
+            })
+            .unwrap_or_default();
+        if images.is_empty() {
+            self.alert_dialog
+                .set_message("There are no images in this session.");
+            self.alert_dialog.open();
+            return;
+        }
+        let directory = self.context.directory_path();
+        self.image_viewer.open(images, selected, directory);
+    }
+
     fn handle_fork_session(&mut self) {
         let Some(session_id) = self.current_session_id() else {
             self.alert_dialog.set_message("No active session to fork.");
@@ -3046,10 +3688,16 @@ impl App {
             .get_api_client()
             .and_then(|client| client.get_formatters().ok())
             .unwrap_or_default();
+        let provider_queues = self
+            .context
+            .get_api_client()
+            .and_then(|client| client.get_provider_queues().ok())
+            .unwrap_or_default();
         let route_label = match self.context.current_route() {
             Route::Home => "home".to_string(),
             Route::Session { session_id } => format!("session ({})", session_id),
             Route::Settings => "settings".to_string(),
+            Route::Review { session_id } => format!("review ({})", session_id),
             Route::Help => "help".to_string(),
         };
         let session_ctx = self.context.session.read();
@@ -3140,6 +3788,40 @@ impl App {
                 lines.push(StatusLine::success(format!("- {}", formatter)));
             }
         }
+
+        lines.push(StatusLine::muted(""));
+        lines.push(StatusLine::title(format!(
+            "Provider Queues ({})",
+            provider_queues.len()
+        )));
+        if provider_queues.is_empty() {
+            lines.push(StatusLine::muted("- No provider requests yet"));
+        } else {
+            for queue in provider_queues {
+                let mut text = format!(
+                    "- {}: {} in flight, {} queued, wait avg {} / max {}",
+                    queue.key,
+                    queue.in_flight,
+                    queue.queued,
+                    format_wait_ms(queue.avg_wait_ms),
+                    format_wait_ms(queue.max_wait_ms)
+                );
+                if queue.queued > 0 {
+                    text.push_str(&format!(
+                        ", oldest {}",
+                        format_wait_ms(queue.oldest_wait_ms)
+                    ));
+                }
+                if queue.cooldown_ms > 0 {
+                    text.push_str(&format!(", paused {}", format_wait_ms(queue.cooldown_ms)));
+                    lines.push(StatusLine::warning(text));
+                } else if queue.queued > 0 {
+                    lines.push(StatusLine::warning(text));
+                } else {
+                    lines.push(StatusLine::normal(text));
+                }
+            }
+        }
         self.status_dialog.set_status_lines(lines);
     }
 
@@ -3496,11 +4178,13 @@ impl App {
             return;
         }
 
+        let previous = self.session_view.take();
         self.active_session_id = Some(session_id.to_string());
-        self.session_view = Some(SessionView::new(
-            self.context.clone(),
-            session_id.to_string(),
-        ));
+        self.session_view = Some(
+            self.session_split
+                .take_for_focus(session_id, previous)
+                .unwrap_or_else(|| SessionView::new(self.context.clone(), session_id.to_string())),
+        );
     }
 
     fn cache_session_from_api(&self, session: &SessionInfo) {
@@ -3846,11 +4530,133 @@ impl App {
         }
     }
 
+    /// Runs the action bound to `key` through the keybind registry.
+    /// Returns whether the key was handled.
+    fn handle_bound_key(&mut self, key: KeyEvent) -> anyhow::Result<bool> {
+        if self.matches_keybind("session_interrupt", key) {
+            if self.prompt.is_shell_mode() {
+                self.prompt.exit_shell_mode();
+                self.prompt.clear_interrupt_confirmation();
+                return Ok(true);
+            }
+            if let Route::Session { session_id } = self.context.current_route() {
+                let status = {
+                    let session_ctx = self.context.session.read();
+                    session_ctx.status(&session_id).clone()
+                };
+                if !matches!(status, SessionStatus::Idle) {
+                    if !self.prompt.register_interrupt_keypress() {
+                        return Ok(true);
+                    }
+                    if let Some(client) = self.context.get_api_client() {
+                        let _ = client.abort_session(&session_id);
+                    }
+                    self.prompt.clear_interrupt_confirmation();
+                    self.set_session_status(&session_id, SessionStatus::Idle);
+                    self.sync_prompt_spinner_state();
+                    return Ok(true);
+                }
+            }
+            self.prompt.clear_interrupt_confirmation();
+            return Ok(true);
+        }
+
+        if self.matches_keybind("input_paste", key) {
+            self.paste_clipboard_to_prompt();
+            return Ok(true);
+        }
+        if self.matches_keybind("input_copy", key) {
+            self.copy_prompt_to_clipboard();
+            return Ok(true);
+        }
+        if self.matches_keybind("input_cut", key) {
+            self.cut_prompt_to_clipboard();
+            return Ok(true);
+        }
+        if self.matches_keybind("history_previous", key) {
+            self.prompt.history_previous_entry();
+            return Ok(true);
+        }
+        if self.matches_keybind("history_next", key) {
+            self.prompt.history_next_entry();
+            return Ok(true);
+        }
+        if self.matches_keybind("page_up", key) {
+            if let Route::Session { .. } = self.context.current_route() {
+                if let Some(ref mut sv) = self.session_view {
+                    sv.scroll_page_up();
+                    return Ok(true);
+                }
+            }
+        }
+        if self.matches_keybind("page_down", key) {
+            if let Route::Session { .. } = self.context.current_route() {
+                if let Some(ref mut sv) = self.session_view {
+                    sv.scroll_page_down();
+                    return Ok(true);
+                }
+            }
+        }
+
+        if self.matches_keybind("command_palette", key) {
+            self.sync_command_palette_labels();
+            self.command_palette.open();
+            return Ok(true);
+        }
+        if self.matches_keybind("model_cycle", key) {
+            self.refresh_model_dialog();
+            self.model_select.open();
+            return Ok(true);
+        }
+        if self.matches_keybind("agent_cycle", key) {
+            self.cycle_agent(1);
+            return Ok(true);
+        }
+        if self.matches_keybind("agent_cycle_reverse", key) {
+            self.cycle_agent(-1);
+            return Ok(true);
+        }
+        if self.matches_keybind("variant_cycle", key) {
+            self.cycle_model_variant();
+            return Ok(true);
+        }
+        if self.matches_keybind("sidebar_toggle", key) {
+            self.context.toggle_sidebar();
+            return Ok(true);
+        }
+        if self.matches_keybind("display_thinking", key) {
+            self.context.toggle_thinking();
+            return Ok(true);
+        }
+        if self.matches_keybind("tool_details", key) {
+            self.context.toggle_tool_details();
+            return Ok(true);
+        }
+        if self.matches_keybind("input_clear", key) {
+            self.prompt.clear();
+            return Ok(true);
+        }
+        if self.matches_keybind("input_newline", key) {
+            let route = self.context.current_route();
+            if matches!(route, Route::Home | Route::Session { .. }) {
+                self.prompt.insert_text("\n");
+                return Ok(true);
+            }
+        }
+        if self.matches_keybind("help_toggle", key) {
+            self.help_dialog.open();
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
     fn matches_keybind(&self, keybind_name: &str, key: KeyEvent) -> bool {
-        self.context
-            .keybind
-            .read()
-            .match_key(keybind_name, key.code, key.modifiers)
+        let keybind = self.context.keybind.read();
+        if self.after_leader {
+            keybind.match_leader_key(keybind_name, key.code, key.modifiers)
+        } else {
+            keybind.match_key(keybind_name, key.code, key.modifiers)
+        }
     }
 
     fn sync_command_palette_labels(&mut self) {
@@ -3882,7 +4688,14 @@ impl App {
             self.ensure_session_view(session_id);
         } else {
             self.active_session_id = None;
-            self.session_view = None;
+            if let Some(view) = self.session_view.take() {
+                self.session_split.park(view);
+            }
+            self.session_split.clear_pane_areas();
+        }
+
+        if self.file_tree.is_visible() {
+            self.sync_file_tree_touched();
         }
 
         let context = self.context.clone();
@@ -3892,6 +4705,7 @@ impl App {
             || self.permission_prompt.is_open
             || self.question_prompt.is_open;
         let session_view = self.session_view.as_mut();
+        let session_split = &mut self.session_split;
         let theme = self.context.theme.read().clone();
         let command_palette = &self.command_palette;
         let model_select = &self.model_select;
@@ -3907,9 +4721,14 @@ impl App {
         let prompt_stash_dialog = &self.prompt_stash_dialog;
         let skill_list_dialog = &self.skill_list_dialog;
         let timeline_dialog = &self.timeline_dialog;
+        let image_viewer = &self.image_viewer;
         let fork_dialog = &self.fork_dialog;
+        let session_switcher = &self.session_switcher;
         let provider_dialog = &self.provider_dialog;
         let subagent_dialog = &self.subagent_dialog;
+        let settings_view = &self.settings_view;
+        let review_view = &self.review_view;
+        let file_tree = &mut self.file_tree;
         let tag_dialog = &self.tag_dialog;
         let permission_prompt = &self.permission_prompt;
         let question_prompt = &self.question_prompt;
@@ -3919,33 +4738,60 @@ impl App {
         let capture_screen_lines = selection.is_active() || selection.is_selecting();
 
         let mut captured_lines: Vec<String> = Vec::new();
+        self.context.graphics.write().begin_frame();
 
-        self.terminal.draw(|frame| {
+        let completed = self.terminal.draw(|frame| {
             let area = frame.size();
             if area.width < 10 || area.height < 10 {
                 return;
             }
 
-            match route_for_draw {
-                Route::Home => {
+            let main_area = match route_for_draw {
+                Route::Home | Route::Session { .. } => file_tree.render(frame, area, &theme),
+                _ => Some(area),
+            };
+            match (route_for_draw, main_area) {
+                (_, None) => {}
+                (Route::Home, Some(main_area)) => {
                     let home = HomeView::new(context.clone());
-                    home.render_with_prompt(frame, area, prompt);
+                    home.render_with_prompt(frame, main_area, prompt);
                 }
-                Route::Session { .. } => {
+                (Route::Session { .. }, Some(main_area)) => {
                     if let Some(view) = session_view {
-                        view.render(frame, area, prompt);
+                        if session_split.is_active() {
+                            let areas = session_split.layout(main_area);
+                            view.render(frame, areas[0], prompt);
+                            session_split.render_panes(frame, &areas[1..], &theme, |session_id| {
+                                let session_ctx = context.session.read();
+                                let title = session_ctx
+                                    .sessions
+                                    .get(session_id)
+                                    .map(|session| session.title.clone())
+                                    .unwrap_or_else(|| session_id.to_string());
+                                (title, session_ctx.status(session_id).clone())
+                            });
+                        } else {
+                            view.render(frame, main_area, prompt);
+                        }
                     } else {
                         let home = HomeView::new(context.clone());
-                        home.render_with_prompt(frame, area, prompt);
+                        home.render_with_prompt(frame, main_area, prompt);
                     }
                 }
-                _ => {
+                (Route::Settings, Some(main_area)) => {
+                    settings_view.render(frame, main_area, &theme)
+                }
+                (Route::Review { .. }, Some(main_area)) => {
+                    review_view.render(frame, main_area, &theme)
+                }
+                (_, Some(main_area)) => {
                     let home = HomeView::new(context.clone());
-                    home.render_with_prompt(frame, area, prompt);
+                    home.render_with_prompt(frame, main_area, prompt);
                 }
             }
 
             if show_modal_overlay {
+                context.graphics.write().clear_placements();
                 let modal_backdrop = ratatui::widgets::Block::default()
                     .style(ratatui::style::Style::default().bg(theme.background_menu));
                 frame.render_widget(modal_backdrop, area);
@@ -3966,7 +4812,9 @@ impl App {
             prompt_stash_dialog.render(frame, area, &theme);
             skill_list_dialog.render(frame, area, &theme);
             timeline_dialog.render(frame, area, &theme);
+            image_viewer.render(frame, area, &theme, &mut context.graphics.write());
             fork_dialog.render(frame, area, &theme);
+            session_switcher.render(frame, area, &theme);
             provider_dialog.render(frame, area, &theme);
             subagent_dialog.render(frame, area, &theme);
             tag_dialog.render(frame, area, &theme);
@@ -3987,6 +4835,7 @@ impl App {
                     height: toast_height.min(area.height.saturating_sub(2)),
                 };
                 toast.render(frame, toast_area, &theme);
+                context.graphics.write().hide_in(toast_area);
             }
 
             let buf = frame.buffer_mut();
@@ -4002,42 +4851,32 @@ impl App {
                 }
             }
 
-            // Render selection highlight — invert colors on non-empty cells,
-            // matching standard terminal selection behavior (like opentui).
+            // Render selection highlight — invert colors on non-empty cells.
             if selection.is_active() {
-                use ratatui::style::Color;
                 for y in area.y..area.y + area.height {
                     for x in area.x..area.x + area.width {
-                        if !selection.is_selected(y, x) {
-                            continue;
-                        }
-                        let cell = buf.get(x, y);
-                        let sym = cell.symbol();
-                        // Only highlight cells with visible text content
-                        if sym.is_empty() || sym.chars().all(|c| c == ' ') {
-                            continue;
+                        if selection.is_selected(usize::from(y), x) {
+                            highlight_cell(buf.get_mut(x, y));
                         }
-                        let cell = buf.get_mut(x, y);
-                        // Resolve Reset to concrete terminal defaults before swapping.
-                        // Reset fg = terminal default foreground (typically white/light).
-                        // Reset bg = terminal default background (typically black/dark).
-                        let fg = if cell.fg == Color::Reset {
-                            Color::White
-                        } else {
-                            cell.fg
-                        };
-                        let bg = if cell.bg == Color::Reset {
-                            Color::Black
-                        } else {
-                            cell.bg
-                        };
-                        cell.fg = bg;
-                        cell.bg = fg;
                     }
                 }
             }
         })?;
 
+        // iTerm2 and sixel images live in the cell grid, so cells an image
+        // no longer covers are repainted from the frame before new images go
+        // on top.
+        let stale_cells: Vec<(u16, u16, ratatui::buffer::Cell)> = self
+            .context
+            .graphics
+            .read()
+            .stale_areas()
+            .into_iter()
+            .flat_map(|stale| stale.intersection(completed.area).positions())
+            .map(|pos| (pos.x, pos.y, completed.buffer.get(pos.x, pos.y).clone()))
+            .collect();
+        self.flush_graphics(&stale_cells)?;
+
         if capture_screen_lines {
             self.screen_lines = captured_lines;
             self.perf.screen_snapshots = self.perf.screen_snapshots.saturating_add(1);
@@ -4045,6 +4884,23 @@ impl App {
         Ok(())
     }
 
+    fn flush_graphics(
+        &mut self,
+        stale_cells: &[(u16, u16, ratatui::buffer::Cell)],
+    ) -> anyhow::Result<()> {
+        use ratatui::backend::Backend;
+        use std::io::Write;
+
+        let backend = self.terminal.backend_mut();
+        if !stale_cells.is_empty() {
+            backend.write_all(b"\x1b7")?;
+            backend.draw(stale_cells.iter().map(|(x, y, cell)| (*x, *y, cell)))?;
+            backend.write_all(b"\x1b8")?;
+        }
+        self.context.graphics.write().flush(backend)?;
+        Ok(())
+    }
+
     fn maybe_log_perf_snapshot(&mut self) {
         if self.last_perf_log.elapsed() < Duration::from_secs(PERF_LOG_INTERVAL_SECS) {
             return;
@@ -4172,6 +5028,12 @@ fn map_api_message_part(
     }
 
     if let Some(file) = &part.file {
+        if file.mime.starts_with("image/") {
+            return Some(ContextMessagePart::Image {
+                url: file.url.clone(),
+                filename: Some(file.filename.clone()),
+            });
+        }
         return Some(ContextMessagePart::File {
             path: file.filename.clone(),
             mime: file.mime.clone(),
@@ -4192,18 +5054,42 @@ fn map_api_message_part(
     }
 
     if let Some(tool_result) = &part.tool_result {
+        // Attachments ride along in the metadata, where tools put them.
+        let mut metadata = tool_result.metadata.clone();
+        if let Some(attachments) = tool_result.attachments.as_ref().filter(|a| !a.is_empty()) {
+            metadata.get_or_insert_with(HashMap::new).insert(
+                "attachments".to_string(),
+                serde_json::Value::Array(attachments.clone()),
+            );
+        }
         return Some(ContextMessagePart::ToolResult {
             id: tool_result.tool_call_id.clone(),
             result: tool_result.content.clone(),
             is_error: tool_result.is_error,
             title: tool_result.title.clone(),
-            metadata: tool_result.metadata.clone(),
+            metadata,
         });
     }
 
     None
 }
 
+/// The markdown a message was written in, as opposed to its rendering.
+fn message_markdown(message: &Message) -> String {
+    if message.parts.is_empty() {
+        return message.content.clone();
+    }
+    message
+        .parts
+        .iter()
+        .filter_map(|part| match part {
+            ContextMessagePart::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
 fn message_part_text(part: &ContextMessagePart) -> String {
     match part {
         ContextMessagePart::Text { text } => text.clone(),
@@ -4220,7 +5106,9 @@ fn message_part_text(part: &ContextMessagePart) -> String {
             format!("[tool-result] {}", result)
         }
         ContextMessagePart::File { path, .. } => format!("[file] {}", path),
-        ContextMessagePart::Image { url } => format!("[image] {}", url),
+        ContextMessagePart::Image { url, filename } => {
+            format!("[image] {}", image_label(url, filename.as_deref()))
+        }
     }
 }
 
@@ -4541,6 +5429,11 @@ fn forward_server_event(data_lines: &[String], event_tx: &Sender<Event>) {
                 },
             )));
         }
+        Some("config.updated") => {
+            let _ = event_tx.send(Event::Custom(CustomEvent::StateChanged(
+                StateChange::ConfigUpdated,
+            )));
+        }
         Some("question.replied") | Some("question.rejected") => {
             let Some(session_id) = session_id else {
                 return;
@@ -4570,6 +5463,14 @@ fn format_theme_option_label(theme_id: &str) -> String {
     theme_id.to_string()
 }
 
+fn format_wait_ms(ms: u64) -> String {
+    if ms < 1000 {
+        format!("{}ms", ms)
+    } else {
+        format!("{:.1}s", ms as f64 / 1000.0)
+    }
+}
+
 fn split_theme_variant(theme_id: &str) -> Option<(&str, &str)> {
     let (base, variant) = theme_id
         .rsplit_once('@')
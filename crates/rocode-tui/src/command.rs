@@ -53,7 +53,13 @@ pub enum CommandAction {
     Timeline,
     Undo,
     Redo,
+    ReviewChanges,
+    QuickSwitchSession,
+    SplitSession,
+    CloseSplit,
+    ToggleSplitDirection,
     CopySession,
+    CopySelectionMarkdown,
     ExportSession,
     // Model/Agent
     SwitchModel,
@@ -76,6 +82,7 @@ pub enum CommandAction {
     ToggleHeader,
     ToggleScrollbar,
     ToggleSidebar,
+    ToggleFileTree,
     ToggleMcp,
     ToggleTips,
     ToggleCommandPalette,
@@ -88,6 +95,9 @@ pub enum CommandAction {
     OpenThemeList,
     OpenStash,
     OpenSkills,
+    OpenBackgroundTasks,
+    OpenSettings,
+    OpenImageViewer,
     // Prompt
     SubmitPrompt,
     ClearPrompt,
@@ -243,6 +253,72 @@ impl CommandRegistry {
             action: CommandAction::Redo,
         });
 
+        self.register(SlashCommand {
+            name: "/review".to_string(),
+            aliases: vec!["/changes".to_string()],
+            title: "Review Changes".to_string(),
+            description: "Accept or reject the session's edits hunk by hunk".to_string(),
+            category: CommandCategory::Session,
+            keybind: None,
+            suggested: false,
+            action: CommandAction::ReviewChanges,
+        });
+
+        self.register(SlashCommand {
+            name: "/switch".to_string(),
+            aliases: vec![],
+            title: "Quick Switch".to_string(),
+            description: "Jump between open, child and recent sessions".to_string(),
+            category: CommandCategory::Session,
+            keybind: None,
+            suggested: false,
+            action: CommandAction::QuickSwitchSession,
+        });
+
+        self.register(SlashCommand {
+            name: "/split".to_string(),
+            aliases: vec![],
+            title: "Split View".to_string(),
+            description: "Watch another session next to this one".to_string(),
+            category: CommandCategory::Session,
+            keybind: None,
+            suggested: false,
+            action: CommandAction::SplitSession,
+        });
+
+        self.register(SlashCommand {
+            name: "/unsplit".to_string(),
+            aliases: vec![],
+            title: "Close Split".to_string(),
+            description: "Close all split panes".to_string(),
+            category: CommandCategory::Session,
+            keybind: None,
+            suggested: false,
+            action: CommandAction::CloseSplit,
+        });
+
+        self.register(SlashCommand {
+            name: "/split-direction".to_string(),
+            aliases: vec![],
+            title: "Toggle Split Direction".to_string(),
+            description: "Arrange split panes side by side or stacked".to_string(),
+            category: CommandCategory::Display,
+            keybind: None,
+            suggested: false,
+            action: CommandAction::ToggleSplitDirection,
+        });
+
+        self.register(SlashCommand {
+            name: "/images".to_string(),
+            aliases: vec![],
+            title: "View Images".to_string(),
+            description: "Browse the images in this session".to_string(),
+            category: CommandCategory::Navigation,
+            keybind: None,
+            suggested: false,
+            action: CommandAction::OpenImageViewer,
+        });
+
         self.register(SlashCommand {
             name: "/copy".to_string(),
             aliases: vec![],
@@ -254,6 +330,17 @@ impl CommandRegistry {
             action: CommandAction::CopySession,
         });
 
+        self.register(SlashCommand {
+            name: "/copy-markdown".to_string(),
+            aliases: vec![],
+            title: "Copy as Markdown".to_string(),
+            description: "Copy the selected messages (or last reply) as raw markdown".to_string(),
+            category: CommandCategory::Session,
+            keybind: None,
+            suggested: false,
+            action: CommandAction::CopySelectionMarkdown,
+        });
+
         self.register(SlashCommand {
             name: "/export".to_string(),
             aliases: vec![],
@@ -331,6 +418,17 @@ impl CommandRegistry {
             action: CommandAction::ShowStatus,
         });
 
+        self.register(SlashCommand {
+            name: "/settings".to_string(),
+            aliases: vec!["/config".to_string()],
+            title: "Settings".to_string(),
+            description: "Edit project or global configuration".to_string(),
+            category: CommandCategory::System,
+            keybind: None,
+            suggested: false,
+            action: CommandAction::OpenSettings,
+        });
+
         self.register(SlashCommand {
             name: "/help".to_string(),
             aliases: vec!["/commands".to_string()],
@@ -431,6 +529,17 @@ impl CommandRegistry {
             action: CommandAction::ToggleSidebar,
         });
 
+        self.register(SlashCommand {
+            name: "/files".to_string(),
+            aliases: vec!["/tree".to_string()],
+            title: "Toggle File Tree".to_string(),
+            description: "Browse project files and insert @file references".to_string(),
+            category: CommandCategory::Display,
+            keybind: None,
+            suggested: false,
+            action: CommandAction::ToggleFileTree,
+        });
+
         self.register(SlashCommand {
             name: "/header".to_string(),
             aliases: vec![],
@@ -485,6 +594,17 @@ impl CommandRegistry {
             suggested: false,
             action: CommandAction::OpenSkills,
         });
+
+        self.register(SlashCommand {
+            name: "/tasks".to_string(),
+            aliases: vec!["/subagents".to_string()],
+            title: "Background Tasks".to_string(),
+            description: "Show subagents running in the background".to_string(),
+            category: CommandCategory::Navigation,
+            keybind: None,
+            suggested: false,
+            action: CommandAction::OpenBackgroundTasks,
+        });
     }
 
     pub fn get(&self, name: &str) -> Option<&SlashCommand> {
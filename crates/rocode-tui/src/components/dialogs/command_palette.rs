@@ -95,6 +95,12 @@ impl CommandPalette {
                 keybind: Some("ctrl+s".to_string()),
                 category: "View".to_string(),
             },
+            Command {
+                action: CommandAction::ToggleFileTree,
+                title: "Toggle file tree".to_string(),
+                keybind: Some("ctrl+x f".to_string()),
+                category: "View".to_string(),
+            },
             Command {
                 action: CommandAction::ToggleHeader,
                 title: "Hide header".to_string(),
@@ -149,6 +155,48 @@ impl CommandPalette {
                 keybind: None,
                 category: "Session".to_string(),
             },
+            Command {
+                action: CommandAction::QuickSwitchSession,
+                title: "Quick switch session".to_string(),
+                keybind: Some("ctrl+x w".to_string()),
+                category: "Session".to_string(),
+            },
+            Command {
+                action: CommandAction::SplitSession,
+                title: "Open session in split".to_string(),
+                keybind: None,
+                category: "Session".to_string(),
+            },
+            Command {
+                action: CommandAction::CloseSplit,
+                title: "Close split panes".to_string(),
+                keybind: None,
+                category: "Session".to_string(),
+            },
+            Command {
+                action: CommandAction::ToggleSplitDirection,
+                title: "Toggle split direction".to_string(),
+                keybind: None,
+                category: "View".to_string(),
+            },
+            Command {
+                action: CommandAction::OpenImageViewer,
+                title: "View session images".to_string(),
+                keybind: None,
+                category: "View".to_string(),
+            },
+            Command {
+                action: CommandAction::ReviewChanges,
+                title: "Review session changes".to_string(),
+                keybind: None,
+                category: "Session".to_string(),
+            },
+            Command {
+                action: CommandAction::CopySelectionMarkdown,
+                title: "Copy selection as markdown".to_string(),
+                keybind: Some("ctrl+x y".to_string()),
+                category: "Session".to_string(),
+            },
             Command {
                 action: CommandAction::ExportSession,
                 title: "Export current session".to_string(),
@@ -170,7 +218,7 @@ impl CommandPalette {
             Command {
                 action: CommandAction::CycleVariant,
                 title: "Cycle model variant".to_string(),
-                keybind: Some("ctrl+v".to_string()),
+                keybind: Some("ctrl+t".to_string()),
                 category: "Session".to_string(),
             },
             Command {
@@ -65,6 +65,10 @@ impl HelpDialog {
             Line::from("  Ctrl+X  Open command list"),
             Line::from("  Ctrl+P  Open command palette"),
             Line::from("  Ctrl+H  Open help"),
+            Line::from("  Ctrl+X F  Toggle file tree"),
+            Line::from("  Ctrl+X W  Quick switch / split sessions"),
+            Line::from("  Drag / double / triple click  Select text, Ctrl+C copies"),
+            Line::from("  Ctrl+X Y  Copy selection as markdown"),
             Line::from("  Ctrl+C/q Exit TUI"),
             Line::from(""),
             Line::from(Span::styled(
@@ -89,6 +93,7 @@ impl HelpDialog {
             Line::from("  Ctrl+M  Model list"),
             Line::from("  Ctrl+V  Cycle model variant"),
             Line::from("  Use /agents to open full agent list"),
+            Line::from("  Use /images to view session images, or click one"),
             Line::from("  Ctrl+S  Toggle sidebar"),
             Line::from("  Use command palette for session/theme/status/MCP dialogs"),
             Line::from("  Use command palette -> Toggle appearance to switch dark/light"),
@@ -0,0 +1,164 @@
+use std::path::PathBuf;
+
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::components::{image_label, SessionImage};
+use crate::theme::Theme;
+use crate::ui::{Graphics, GraphicsProtocol, ImageAccess};
+
+/// Full-screen viewer for the images in the current session.
+pub struct ImageViewerDialog {
+    images: Vec<SessionImage>,
+    /// Directory images not attached by the user must live under.
+    directory: PathBuf,
+    index: usize,
+    open: bool,
+}
+
+impl ImageViewerDialog {
+    pub fn new() -> Self {
+        Self {
+            images: Vec::new(),
+            directory: PathBuf::new(),
+            index: 0,
+            open: false,
+        }
+    }
+
+    /// Opens on `selected` when given, otherwise on the latest image.
+    pub fn open(&mut self, images: Vec<SessionImage>, selected: Option<&str>, directory: PathBuf) {
+        self.index = selected
+            .and_then(|url| images.iter().position(|image| image.url == url))
+            .unwrap_or(images.len().saturating_sub(1));
+        self.images = images;
+        self.directory = directory;
+        self.open = true;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn previous(&mut self) {
+        self.index = self.index.saturating_sub(1);
+    }
+
+    pub fn next(&mut self) {
+        if self.index + 1 < self.images.len() {
+            self.index += 1;
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme, graphics: &mut Graphics) {
+        if !self.open {
+            return;
+        }
+        let dialog_area = super::centered_rect(area.width, area.height, area);
+        frame.render_widget(Clear, dialog_area);
+
+        let current = self.images.get(self.index);
+        let title = match current {
+            Some(image) => format!(
+                " {} ({}/{}) ",
+                image_label(&image.url, image.filename.as_deref()),
+                self.index + 1,
+                self.images.len()
+            ),
+            None => " Images ".to_string(),
+        };
+        let block = Block::default()
+            .title(Span::styled(
+                title,
+                Style::default()
+                    .fg(theme.primary)
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .title_bottom(Line::from(Span::styled(
+                " ←/→ browse · esc close ",
+                Style::default().fg(theme.text_muted),
+            )))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.background_panel));
+        let inner = super::dialog_inner(block.inner(dialog_area));
+        frame.render_widget(block, dialog_area);
+
+        let Some(current) = current else {
+            let empty = Paragraph::new("No images in this session")
+                .style(Style::default().fg(theme.text_muted));
+            frame.render_widget(empty, inner);
+            return;
+        };
+        let access = if current.attachment {
+            ImageAccess::Attachment
+        } else {
+            ImageAccess::Within(&self.directory)
+        };
+        let Some((id, image)) = graphics.load(&current.url, access) else {
+            let missing = Paragraph::new("This image could not be loaded.")
+                .style(Style::default().fg(theme.error));
+            frame.render_widget(missing, inner);
+            return;
+        };
+
+        let info = Line::from(Span::styled(
+            image.describe(),
+            Style::default().fg(theme.text_muted),
+        ));
+        let picture_area = Rect {
+            y: inner.y.saturating_add(1),
+            height: inner.height.saturating_sub(1),
+            ..inner
+        };
+        frame.render_widget(Paragraph::new(info), inner);
+        if picture_area.height == 0 {
+            return;
+        }
+
+        if graphics.can_render(&image) {
+            let (cols, rows) = graphics.fit(&image, picture_area.width, picture_area.height);
+            graphics.place(
+                id,
+                Rect {
+                    x: picture_area.x + picture_area.width.saturating_sub(cols) / 2,
+                    y: picture_area.y + picture_area.height.saturating_sub(rows) / 2,
+                    width: cols,
+                    height: rows,
+                },
+            );
+            return;
+        }
+
+        let reason = match graphics.protocol() {
+            GraphicsProtocol::None => {
+                "This terminal has no inline graphics support. Set OPENCODE_TUI_IMAGES \
+                 to kitty, iterm or sixel to force a protocol."
+                    .to_string()
+            }
+            protocol => format!(
+                "The {} protocol cannot display this image format here.",
+                protocol.label()
+            ),
+        };
+        let fallback = Paragraph::new(reason)
+            .style(Style::default().fg(theme.text_muted))
+            .wrap(Wrap { trim: true });
+        frame.render_widget(fallback, picture_area);
+    }
+}
+
+impl Default for ImageViewerDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
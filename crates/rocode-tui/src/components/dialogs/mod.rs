@@ -6,6 +6,7 @@ mod command_palette;
 mod confirm;
 mod fork;
 mod help;
+mod image_viewer;
 mod mcp;
 mod model_select;
 mod prompt_stash;
@@ -13,6 +14,7 @@ mod provider;
 mod session_export;
 mod session_list;
 mod session_rename;
+mod session_switcher;
 mod skill_list;
 mod status;
 mod subagent;
@@ -71,6 +73,7 @@ pub use command_palette::CommandPalette;
 pub use confirm::ConfirmDialog;
 pub use fork::{ForkDialog, ForkEntry};
 pub use help::HelpDialog;
+pub use image_viewer::ImageViewerDialog;
 pub use mcp::{McpDialog, McpItem};
 pub use model_select::{Model, ModelSelectDialog};
 pub use prompt_stash::{PromptStashDialog, StashItem};
@@ -78,6 +81,7 @@ pub use provider::{Provider, ProviderDialog, ProviderStatus, SubmitResult};
 pub use session_export::SessionExportDialog;
 pub use session_list::{DeleteState as SessionDeleteState, SessionItem, SessionListDialog};
 pub use session_rename::SessionRenameDialog;
+pub use session_switcher::{switcher_entries, SessionSwitcherDialog, SwitcherEntry};
 pub use skill_list::SkillListDialog;
 pub use status::{StatusDialog, StatusLine};
 pub use subagent::{SubagentDialog, SubagentInfo, SubagentMessage};
@@ -0,0 +1,300 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use super::session_list::SessionItem;
+use crate::theme::Theme;
+
+const MAX_RECENT_SESSIONS: usize = 10;
+
+#[derive(Clone, Debug)]
+pub struct SwitcherEntry {
+    pub session_id: String,
+    pub title: String,
+    /// How the session relates to the focused one: `focused`, `parent`,
+    /// `child`, `split` or `recent`.
+    pub relation: &'static str,
+    pub running: bool,
+    pub in_split: bool,
+}
+
+/// Orders sessions for the switcher: the focused session, its parent, its
+/// children (e.g. `task` subagents), split panes, then recent root sessions.
+pub fn switcher_entries(
+    focused: Option<&str>,
+    split: &[String],
+    mut sessions: Vec<SessionItem>,
+) -> Vec<SwitcherEntry> {
+    sessions.sort_by_key(|session| std::cmp::Reverse(session.updated_at));
+    let parent = focused
+        .and_then(|id| sessions.iter().find(|session| session.id == id))
+        .and_then(|session| session.parent_id.clone());
+    let relation = |session: &SessionItem| {
+        if Some(session.id.as_str()) == focused {
+            Some("focused")
+        } else if parent.as_deref() == Some(session.id.as_str()) {
+            Some("parent")
+        } else if focused.is_some() && session.parent_id.as_deref() == focused {
+            Some("child")
+        } else if split.contains(&session.id) {
+            Some("split")
+        } else {
+            None
+        }
+    };
+    let entry = |session: &SessionItem, relation: &'static str| SwitcherEntry {
+        session_id: session.id.clone(),
+        title: session.title.clone(),
+        relation,
+        running: session.is_busy,
+        in_split: split.contains(&session.id),
+    };
+
+    let mut entries = Vec::new();
+    for rank in ["focused", "parent", "child", "split"] {
+        entries.extend(
+            sessions
+                .iter()
+                .filter(|session| relation(session) == Some(rank))
+                .map(|session| entry(session, rank)),
+        );
+    }
+    entries.extend(
+        sessions
+            .iter()
+            .filter(|session| relation(session).is_none() && session.parent_id.is_none())
+            .take(MAX_RECENT_SESSIONS)
+            .map(|session| entry(session, "recent")),
+    );
+    entries
+}
+
+/// Quick switcher over the open, related and recent sessions.
+pub struct SessionSwitcherDialog {
+    entries: Vec<SwitcherEntry>,
+    state: ListState,
+    /// Enter adds the selection as a split pane instead of focusing it.
+    split_mode: bool,
+    open: bool,
+}
+
+impl SessionSwitcherDialog {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            state: ListState::default(),
+            split_mode: false,
+            open: false,
+        }
+    }
+
+    pub fn open(&mut self, entries: Vec<SwitcherEntry>, split_mode: bool) {
+        self.entries = entries;
+        self.split_mode = split_mode;
+        // Preselect the first entry that is not the focused session.
+        let first = self
+            .entries
+            .iter()
+            .position(|entry| entry.relation != "focused")
+            .or(if self.entries.is_empty() {
+                None
+            } else {
+                Some(0)
+            });
+        self.state.select(first);
+        self.open = true;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn is_split_mode(&self) -> bool {
+        self.split_mode
+    }
+
+    pub fn move_up(&mut self) {
+        if let Some(i) = self.state.selected() {
+            if i > 0 {
+                self.state.select(Some(i - 1));
+            }
+        }
+    }
+
+    pub fn move_down(&mut self) {
+        if let Some(i) = self.state.selected() {
+            if i < self.entries.len().saturating_sub(1) {
+                self.state.select(Some(i + 1));
+            }
+        }
+    }
+
+    pub fn select_index(&mut self, index: usize) -> bool {
+        if index < self.entries.len() {
+            self.state.select(Some(index));
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn selected_entry(&self) -> Option<&SwitcherEntry> {
+        self.state.selected().and_then(|i| self.entries.get(i))
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        if !self.open {
+            return;
+        }
+        let dialog_width = 70u16.min(area.width.saturating_sub(4));
+        let dialog_height = (self.entries.len() as u16 + 5).clamp(7, 20);
+        let dialog_area = super::centered_rect(dialog_width, dialog_height, area);
+        frame.render_widget(Clear, dialog_area);
+        let title = if self.split_mode {
+            " Open in Split "
+        } else {
+            " Switch Session "
+        };
+        let hint = if self.split_mode {
+            "Enter open as pane · 1-9 jump · Esc close"
+        } else {
+            "Enter focus · s toggle split pane · 1-9 jump · Esc close"
+        };
+        let block = Block::default()
+            .title(Span::styled(
+                title,
+                Style::default()
+                    .fg(theme.primary)
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.background_panel));
+        let inner = super::dialog_inner(block.inner(dialog_area));
+        frame.render_widget(block, dialog_area);
+
+        frame.render_widget(
+            Paragraph::new(hint).style(Style::default().fg(theme.text_muted)),
+            Rect {
+                x: inner.x,
+                y: inner.y,
+                width: inner.width,
+                height: 1,
+            },
+        );
+        let list_area = Rect {
+            x: inner.x,
+            y: inner.y + 2,
+            width: inner.width,
+            height: inner.height.saturating_sub(2),
+        };
+        if self.entries.is_empty() {
+            frame.render_widget(
+                Paragraph::new("No sessions").style(Style::default().fg(theme.text_muted)),
+                list_area,
+            );
+            return;
+        }
+
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let index = if i < 9 {
+                    format!("{} ", i + 1)
+                } else {
+                    "  ".to_string()
+                };
+                let mut spans = vec![
+                    Span::styled(index, Style::default().fg(theme.text_muted)),
+                    Span::styled(
+                        if entry.running { "● " } else { "  " },
+                        Style::default().fg(theme.success),
+                    ),
+                    Span::styled(entry.title.clone(), Style::default().fg(theme.text)),
+                    Span::styled(
+                        format!("  {}", entry.relation),
+                        Style::default().fg(theme.text_muted),
+                    ),
+                ];
+                if entry.in_split && entry.relation != "split" {
+                    spans.push(Span::styled(
+                        "  split",
+                        Style::default().fg(theme.text_muted),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items).highlight_style(
+            Style::default()
+                .bg(theme.background_element)
+                .add_modifier(Modifier::BOLD),
+        );
+        frame.render_stateful_widget(list, list_area, &mut self.state.clone());
+    }
+}
+
+impl Default for SessionSwitcherDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str, parent: Option<&str>, updated_at: i64) -> SessionItem {
+        SessionItem {
+            id: id.to_string(),
+            title: id.to_string(),
+            directory: String::new(),
+            parent_id: parent.map(str::to_string),
+            updated_at,
+            is_busy: false,
+        }
+    }
+
+    #[test]
+    fn entries_put_related_sessions_first() {
+        let entries = switcher_entries(
+            Some("main"),
+            &["other".to_string()],
+            vec![
+                session("recent", None, 50),
+                session("main", Some("root"), 10),
+                session("root", None, 5),
+                session("task-a", Some("main"), 20),
+                session("task-b", Some("main"), 30),
+                session("other", None, 1),
+                session("nested", Some("recent"), 40),
+            ],
+        );
+        let order: Vec<(&str, &str)> = entries
+            .iter()
+            .map(|entry| (entry.session_id.as_str(), entry.relation))
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                ("main", "focused"),
+                ("root", "parent"),
+                ("task-b", "child"),
+                ("task-a", "child"),
+                ("other", "split"),
+                ("recent", "recent"),
+            ]
+        );
+        assert!(entries[4].in_split);
+    }
+}
@@ -14,6 +14,9 @@ pub struct SubagentInfo {
     pub id: String,
     pub name: String,
     pub category: String,
+    /// Background task status (`queued`, `running`, `completed`, ...);
+    /// empty for foreground subagents.
+    pub status: String,
     pub messages: Vec<SubagentMessage>,
 }
 
@@ -24,7 +27,8 @@ pub struct SubagentMessage {
 }
 
 pub struct SubagentDialog {
-    pub subagent: Option<SubagentInfo>,
+    pub subagents: Vec<SubagentInfo>,
+    pub selected: usize,
     pub open: bool,
     pub scroll_offset: u16,
 }
@@ -32,21 +36,55 @@ pub struct SubagentDialog {
 impl SubagentDialog {
     pub fn new() -> Self {
         Self {
-            subagent: None,
+            subagents: Vec::new(),
+            selected: 0,
             open: false,
             scroll_offset: 0,
         }
     }
 
     pub fn open(&mut self, subagent: SubagentInfo) {
-        self.subagent = Some(subagent);
+        self.open_list(vec![subagent]);
+    }
+
+    pub fn open_list(&mut self, subagents: Vec<SubagentInfo>) {
+        self.subagents = subagents;
+        self.selected = 0;
         self.open = true;
         self.scroll_offset = 0;
     }
 
+    /// Replaces the subagents while open, keeping the selected one.
+    pub fn update(&mut self, subagents: Vec<SubagentInfo>) {
+        let selected_id = self.selected_id().map(str::to_string);
+        self.subagents = subagents;
+        self.selected = selected_id
+            .and_then(|id| self.subagents.iter().position(|s| s.id == id))
+            .unwrap_or(0);
+    }
+
+    pub fn selected_id(&self) -> Option<&str> {
+        self.subagents.get(self.selected).map(|s| s.id.as_str())
+    }
+
+    pub fn next(&mut self) {
+        if !self.subagents.is_empty() {
+            self.selected = (self.selected + 1) % self.subagents.len();
+            self.scroll_offset = 0;
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if !self.subagents.is_empty() {
+            self.selected = (self.selected + self.subagents.len() - 1) % self.subagents.len();
+            self.scroll_offset = 0;
+        }
+    }
+
     pub fn close(&mut self) {
         self.open = false;
-        self.subagent = None;
+        self.subagents.clear();
+        self.selected = 0;
     }
 
     pub fn is_open(&self) -> bool {
@@ -70,22 +108,58 @@ impl SubagentDialog {
             return;
         }
 
-        let subagent = match &self.subagent {
-            Some(s) => s,
-            None => return,
-        };
-
         let height = area.height.saturating_sub(4).min(20);
         let width = area.width.saturating_sub(4).min(80);
         let popup_area = super::centered_rect(width, height, area);
 
+        let block = Block::default()
+            .title(" Subagent ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.background_panel));
+        let content_area = super::dialog_inner(block.inner(popup_area));
+        frame.render_widget(block, popup_area);
+
+        let Some(subagent) = self.subagents.get(self.selected) else {
+            let empty = Paragraph::new(Line::from(Span::styled(
+                "No background tasks in this session",
+                Style::default().fg(theme.text_muted),
+            )))
+            .style(Style::default().bg(theme.background_panel));
+            frame.render_widget(empty, content_area);
+            return;
+        };
+
         let title = format!(" Subagent: {} [{}] ", subagent.name, subagent.category);
 
         let mut lines = Vec::new();
-        lines.push(Line::from(Span::styled(
-            &title,
+        let mut header = vec![Span::styled(
+            title,
             Style::default().fg(theme.primary).bold(),
-        )));
+        )];
+        if !subagent.status.is_empty() {
+            let status_color = match subagent.status.as_str() {
+                "completed" => theme.success,
+                "failed" | "cancelled" => theme.error,
+                "running" => theme.warning,
+                _ => theme.text_muted,
+            };
+            header.push(Span::styled(
+                subagent.status.clone(),
+                Style::default().fg(status_color),
+            ));
+        }
+        lines.push(Line::from(header));
+        if self.subagents.len() > 1 {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "{}/{}  ←/→ switch  c cancel",
+                    self.selected + 1,
+                    self.subagents.len()
+                ),
+                Style::default().fg(theme.text_muted),
+            )));
+        }
         lines.push(Line::from(""));
 
         for msg in &subagent.messages {
@@ -105,14 +179,6 @@ impl SubagentDialog {
             lines.push(Line::from(""));
         }
 
-        let block = Block::default()
-            .title(" Subagent ")
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(theme.border))
-            .style(Style::default().bg(theme.background_panel));
-        let content_area = super::dialog_inner(block.inner(popup_area));
-        frame.render_widget(block, popup_area);
-
         let paragraph = Paragraph::new(lines)
             .style(Style::default().bg(theme.background_panel))
             .scroll((self.scroll_offset, 0));
@@ -7,6 +7,7 @@ use ratatui::{
 };
 
 use crate::theme::Theme;
+use crate::ui::{pad_right, truncate};
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum DiffMode {
@@ -190,6 +191,83 @@ impl DiffView {
         lines
     }
 
+    /// Side-by-side rendering as single lines of `width` columns, pairing
+    /// removed lines with the added lines that replace them.
+    pub fn to_split_lines(&self, theme: &Theme, width: u16) -> Vec<Line<'static>> {
+        let half = (width as usize).saturating_sub(1) / 2;
+        let cell = |line: Option<&DiffLine>, old: bool| -> Vec<Span<'static>> {
+            let Some(line) = line else {
+                return vec![Span::raw(" ".repeat(half))];
+            };
+            let number = if old {
+                line.old_line_num
+            } else {
+                line.new_line_num
+            };
+            let style = match line.line_type {
+                DiffLineType::Added => Style::default()
+                    .fg(theme.diff_added)
+                    .bg(theme.diff_added_bg),
+                DiffLineType::Removed => Style::default()
+                    .fg(theme.diff_removed)
+                    .bg(theme.diff_removed_bg),
+                _ => Style::default().fg(theme.text),
+            };
+            let text_width = half.saturating_sub(5);
+            vec![
+                Span::styled(
+                    number.map_or("     ".to_string(), |n| format!("{:4} ", n)),
+                    Style::default().fg(theme.text_muted),
+                ),
+                Span::styled(
+                    pad_right(&truncate(&line.content, text_width), text_width),
+                    style,
+                ),
+            ]
+        };
+        let row = |old: Option<&DiffLine>, new: Option<&DiffLine>| {
+            let mut spans = cell(old, true);
+            spans.push(Span::styled("│", Style::default().fg(theme.border)));
+            spans.extend(cell(new, false));
+            Line::from(spans)
+        };
+
+        let mut lines = Vec::new();
+        let mut index = 0;
+        while index < self.lines.len() {
+            let line = &self.lines[index];
+            match line.line_type {
+                DiffLineType::HunkHeader => {
+                    lines.push(Line::from(Span::styled(
+                        line.content.clone(),
+                        Style::default().fg(theme.primary),
+                    )));
+                    index += 1;
+                }
+                DiffLineType::Context => {
+                    lines.push(row(Some(line), Some(line)));
+                    index += 1;
+                }
+                DiffLineType::Removed | DiffLineType::Added => {
+                    let removed: Vec<&DiffLine> = self.lines[index..]
+                        .iter()
+                        .take_while(|l| l.line_type == DiffLineType::Removed)
+                        .collect();
+                    index += removed.len();
+                    let added: Vec<&DiffLine> = self.lines[index..]
+                        .iter()
+                        .take_while(|l| l.line_type == DiffLineType::Added)
+                        .collect();
+                    index += added.len();
+                    for i in 0..removed.len().max(added.len()) {
+                        lines.push(row(removed.get(i).copied(), added.get(i).copied()));
+                    }
+                }
+            }
+        }
+        lines
+    }
+
     pub fn scroll_up(&mut self) {
         if self.scroll_offset > 0 {
             self.scroll_offset -= 1;
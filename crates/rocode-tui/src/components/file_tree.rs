@@ -0,0 +1,767 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+use rocode_grep::{FileSearchOptions, Ripgrep};
+
+use super::markdown::{CodeBlock, CodeTheme};
+use crate::api::FileStatusInfo;
+use crate::theme::Theme;
+
+const PANE_MAX_WIDTH: u16 = 36;
+const MAX_TREE_FILES: usize = 20_000;
+const PREVIEW_MAX_BYTES: u64 = 512 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FileTreeAction {
+    /// Key not handled; let the app process it.
+    Ignored,
+    None,
+    /// Insert text into the prompt.
+    Insert(String),
+    /// Re-scan the tree in the background.
+    Refresh,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct TreeEntry {
+    name: String,
+    /// Path relative to the root, `/` separated.
+    path: String,
+    is_dir: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct TreeRow {
+    entry: TreeEntry,
+    depth: usize,
+}
+
+struct FilePreview {
+    path: String,
+    lines: Vec<Vec<Span<'static>>>,
+    cursor: usize,
+    /// First line of a range selection started with `v`.
+    anchor: Option<usize>,
+    scroll: usize,
+    page: usize,
+}
+
+impl FilePreview {
+    /// 1-based inclusive line range covered by the selection.
+    fn selected_range(&self) -> (usize, usize) {
+        let anchor = self.anchor.unwrap_or(self.cursor);
+        (anchor.min(self.cursor) + 1, anchor.max(self.cursor) + 1)
+    }
+
+    fn reference(&self) -> String {
+        if self.anchor.is_none() {
+            return format!("@{}", self.path);
+        }
+        match self.selected_range() {
+            (start, end) if start == end => format!("@{}#{}", self.path, start),
+            (start, end) => format!("@{}#{}-{}", self.path, start, end),
+        }
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let last = self.lines.len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+    }
+}
+
+/// Toggleable project file browser shown to the left of the session.
+pub struct FileTree {
+    visible: bool,
+    focused: bool,
+    root: PathBuf,
+    children: BTreeMap<String, Vec<TreeEntry>>,
+    expanded: HashSet<String>,
+    /// Git status keyed by relative path.
+    status: HashMap<String, String>,
+    /// Directories containing at least one changed file.
+    dirty_dirs: HashSet<String>,
+    touched: HashSet<String>,
+    rows: Vec<TreeRow>,
+    selected: usize,
+    scroll: usize,
+    page: usize,
+    preview: Option<FilePreview>,
+    truncated: bool,
+    /// A background scan has been requested and not delivered yet.
+    loading: bool,
+}
+
+impl FileTree {
+    pub fn new() -> Self {
+        Self {
+            visible: false,
+            focused: false,
+            root: PathBuf::from("."),
+            children: BTreeMap::new(),
+            expanded: HashSet::new(),
+            status: HashMap::new(),
+            dirty_dirs: HashSet::new(),
+            touched: HashSet::new(),
+            rows: Vec::new(),
+            selected: 0,
+            scroll: 0,
+            page: 1,
+            preview: None,
+            truncated: false,
+            loading: false,
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.visible && self.focused
+    }
+
+    pub fn show(&mut self) {
+        self.visible = true;
+        self.focused = true;
+    }
+
+    pub fn hide(&mut self) {
+        self.visible = false;
+        self.focused = false;
+        self.preview = None;
+    }
+
+    pub fn focus(&mut self) {
+        self.focused = true;
+    }
+
+    /// Lists the files under `root` as sorted relative paths, skipping
+    /// gitignored ones. Walks the disk, so call it off the UI thread.
+    pub fn scan(root: &Path) -> Vec<String> {
+        let options = FileSearchOptions {
+            respect_ignore: true,
+            ..Default::default()
+        };
+        let mut files: Vec<String> = Ripgrep::files(root, options)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|path| relative_path(root, &path))
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Marks the tree as waiting for a scan of `root`.
+    pub fn begin_refresh(&mut self) {
+        self.loading = true;
+    }
+
+    /// Applies the result of [`FileTree::scan`], keeping expanded
+    /// directories when the root is unchanged.
+    pub fn load(&mut self, root: &Path, mut files: Vec<String>) {
+        self.loading = false;
+        if self.root != root {
+            self.expanded.clear();
+            self.selected = 0;
+            self.root = root.to_path_buf();
+        }
+        self.truncated = files.len() > MAX_TREE_FILES;
+        files.truncate(MAX_TREE_FILES);
+        self.set_files(files);
+    }
+
+    fn set_files(&mut self, files: Vec<String>) {
+        let selected_path = self.selected_path();
+        self.children = build_children(&files);
+        self.rebuild_rows();
+        if let Some(path) = selected_path {
+            if let Some(index) = self.rows.iter().position(|row| row.entry.path == path) {
+                self.selected = index;
+            }
+        }
+        self.selected = self.selected.min(self.rows.len().saturating_sub(1));
+    }
+
+    pub fn set_git_status(&mut self, files: &[FileStatusInfo]) {
+        self.status = files
+            .iter()
+            .map(|file| {
+                (
+                    file.path.trim_end_matches('/').to_string(),
+                    file.status.clone(),
+                )
+            })
+            .collect();
+        self.dirty_dirs = self
+            .status
+            .keys()
+            .flat_map(|path| ancestors(path))
+            .collect();
+    }
+
+    /// Files changed in the current session, as absolute or root-relative paths.
+    pub fn set_touched<'a>(&mut self, files: impl IntoIterator<Item = &'a str>) {
+        self.touched = files
+            .into_iter()
+            .map(|file| {
+                relative_path(&self.root, Path::new(file))
+                    .unwrap_or_else(|| file.trim_start_matches("./").to_string())
+            })
+            .collect();
+    }
+
+    fn selected_path(&self) -> Option<String> {
+        self.rows
+            .get(self.selected)
+            .map(|row| row.entry.path.clone())
+    }
+
+    fn rebuild_rows(&mut self) {
+        let mut rows = Vec::new();
+        push_rows(&self.children, &self.expanded, "", 0, &mut rows);
+        self.rows = rows;
+    }
+
+    fn toggle_dir(&mut self, path: &str) {
+        if !self.expanded.remove(path) {
+            self.expanded.insert(path.to_string());
+        }
+        self.rebuild_rows();
+    }
+
+    fn open_preview(&mut self, path: &str, theme: &Theme) {
+        let full = self.root.join(path);
+        let content = match std::fs::metadata(&full) {
+            Ok(meta) if meta.len() > PREVIEW_MAX_BYTES => {
+                Err(format!("File too large to preview ({} bytes)", meta.len()))
+            }
+            _ => std::fs::read(&full).map_err(|e| e.to_string()),
+        };
+        let lines = match content {
+            Ok(bytes) if bytes.iter().take(8192).any(|b| *b == 0) => {
+                vec![muted_line("Binary file", theme)]
+            }
+            Ok(bytes) => {
+                let text = String::from_utf8_lossy(&bytes).replace('\t', "    ");
+                let language = Path::new(path)
+                    .extension()
+                    .and_then(|ext| ext.to_str())
+                    .map(str::to_string);
+                let mut lines =
+                    CodeBlock::new(language, text).to_lines(&CodeTheme::from_app_theme(theme));
+                if lines.is_empty() {
+                    lines.push(Vec::new());
+                }
+                lines
+            }
+            Err(error) => vec![muted_line(&error, theme)],
+        };
+        self.preview = Some(FilePreview {
+            path: path.to_string(),
+            lines,
+            cursor: 0,
+            anchor: None,
+            scroll: 0,
+            page: 1,
+        });
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent, theme: &Theme) -> FileTreeAction {
+        if key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return FileTreeAction::Ignored;
+        }
+        if self.preview.is_some() {
+            return self.handle_preview_key(key);
+        }
+
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::PageUp => self.move_selection(-(self.page as isize)),
+            KeyCode::PageDown => self.move_selection(self.page as isize),
+            KeyCode::Home | KeyCode::Char('g') => self.selected = 0,
+            KeyCode::End | KeyCode::Char('G') => {
+                self.selected = self.rows.len().saturating_sub(1);
+            }
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => {
+                let Some(row) = self.rows.get(self.selected).cloned() else {
+                    return FileTreeAction::None;
+                };
+                if row.entry.is_dir {
+                    if key.code == KeyCode::Right && self.expanded.contains(&row.entry.path) {
+                        self.move_selection(1);
+                    } else {
+                        self.toggle_dir(&row.entry.path);
+                    }
+                } else {
+                    self.open_preview(&row.entry.path, theme);
+                }
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                let Some(row) = self.rows.get(self.selected).cloned() else {
+                    return FileTreeAction::None;
+                };
+                if row.entry.is_dir && self.expanded.contains(&row.entry.path) {
+                    self.toggle_dir(&row.entry.path);
+                } else if let Some((parent, _)) = row.entry.path.rsplit_once('/') {
+                    if let Some(index) = self.rows.iter().position(|r| r.entry.path == parent) {
+                        self.selected = index;
+                    }
+                }
+            }
+            KeyCode::Char('@') | KeyCode::Char('i') => {
+                if let Some(path) = self.selected_path() {
+                    return FileTreeAction::Insert(format!("@{}", path));
+                }
+            }
+            KeyCode::Char('r') => return FileTreeAction::Refresh,
+            KeyCode::Esc | KeyCode::Tab => self.focused = false,
+            _ => {}
+        }
+        FileTreeAction::None
+    }
+
+    fn handle_preview_key(&mut self, key: KeyEvent) -> FileTreeAction {
+        let Some(preview) = self.preview.as_mut() else {
+            return FileTreeAction::None;
+        };
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => preview.move_cursor(-1),
+            KeyCode::Down | KeyCode::Char('j') => preview.move_cursor(1),
+            KeyCode::PageUp => preview.move_cursor(-(preview.page as isize)),
+            KeyCode::PageDown => preview.move_cursor(preview.page as isize),
+            KeyCode::Home | KeyCode::Char('g') => preview.cursor = 0,
+            KeyCode::End | KeyCode::Char('G') => {
+                preview.cursor = preview.lines.len().saturating_sub(1);
+            }
+            KeyCode::Char('v') | KeyCode::Char(' ') => {
+                preview.anchor = match preview.anchor {
+                    Some(_) => None,
+                    None => Some(preview.cursor),
+                };
+            }
+            KeyCode::Enter | KeyCode::Char('i') | KeyCode::Char('@') => {
+                let reference = preview.reference();
+                self.preview = None;
+                self.focused = false;
+                return FileTreeAction::Insert(reference);
+            }
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Left | KeyCode::Char('h') => {
+                self.preview = None;
+            }
+            _ => {}
+        }
+        FileTreeAction::None
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let last = self.rows.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Renders the tree pane (and preview, if open) and returns the area left
+    /// for the main view, or `None` when the preview covers it.
+    pub fn render(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) -> Option<Rect> {
+        if !self.visible {
+            return Some(area);
+        }
+        let width = PANE_MAX_WIDTH.min(area.width / 3).max(12);
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(width), Constraint::Min(0)])
+            .split(area);
+        self.render_tree(frame, chunks[0], theme);
+        if self.preview.is_some() {
+            self.render_preview(frame, chunks[1], theme);
+            return None;
+        }
+        Some(chunks[1])
+    }
+
+    fn render_tree(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let title_style = if self.focused {
+            Style::default()
+                .fg(theme.primary)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text_muted)
+        };
+        let block = Block::default()
+            .title(Span::styled(" Files ", title_style))
+            .borders(Borders::RIGHT)
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.background_panel));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        if inner.height == 0 {
+            return;
+        }
+
+        let hint_height = u16::from(self.focused && inner.height > 4);
+        let list_height = inner.height.saturating_sub(hint_height) as usize;
+        self.page = list_height.max(1);
+        self.scroll = scroll_to_fit(self.scroll, self.selected, self.page);
+
+        let mut lines: Vec<Line> = self
+            .rows
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(list_height)
+            .map(|(index, row)| self.row_line(row, index == self.selected, inner.width, theme))
+            .collect();
+        if self.rows.is_empty() {
+            let empty = if self.loading {
+                "Loading…"
+            } else {
+                "No files"
+            };
+            lines.push(muted_line(empty, theme).into());
+        } else if self.truncated && lines.len() < list_height {
+            lines.push(muted_line("… listing truncated", theme).into());
+        }
+        frame.render_widget(
+            Paragraph::new(lines),
+            Rect {
+                height: list_height as u16,
+                ..inner
+            },
+        );
+
+        if hint_height > 0 {
+            frame.render_widget(
+                Paragraph::new(Line::from(Span::styled(
+                    "⏎ open  i insert  r refresh  esc back",
+                    Style::default().fg(theme.text_muted),
+                ))),
+                Rect {
+                    y: inner.y + inner.height - 1,
+                    height: 1,
+                    ..inner
+                },
+            );
+        }
+    }
+
+    fn row_line(&self, row: &TreeRow, selected: bool, width: u16, theme: &Theme) -> Line<'static> {
+        let entry = &row.entry;
+        let status = self.status.get(&entry.path).map(String::as_str);
+        let marker = if entry.is_dir {
+            if self.expanded.contains(&entry.path) {
+                "▾ "
+            } else {
+                "▸ "
+            }
+        } else {
+            "  "
+        };
+        let mut name_style = match status {
+            Some(status) => Style::default().fg(status_color(status, theme)),
+            None if entry.is_dir && self.dirty_dirs.contains(&entry.path) => {
+                Style::default().fg(theme.warning)
+            }
+            None if entry.is_dir => Style::default().fg(theme.text),
+            None => Style::default().fg(theme.text_muted),
+        };
+        if selected {
+            name_style = if self.focused {
+                name_style.bg(theme.primary).fg(theme.background)
+            } else {
+                name_style.add_modifier(Modifier::BOLD)
+            };
+        }
+
+        let indent = "  ".repeat(row.depth);
+        let touched = self.touched.contains(&entry.path);
+        // Marker plus the ` ●` and ` M` decorations, two columns each.
+        let decoration_width = 2 + 2 * (usize::from(touched) + usize::from(status.is_some()));
+        let budget = (width as usize).saturating_sub(indent.len() + decoration_width);
+        let name: String = if entry.name.chars().count() > budget {
+            let mut name: String = entry.name.chars().take(budget.saturating_sub(1)).collect();
+            name.push('…');
+            name
+        } else {
+            entry.name.clone()
+        };
+
+        let mut spans = vec![
+            Span::raw(indent),
+            Span::styled(marker, Style::default().fg(theme.text_muted)),
+            Span::styled(name, name_style),
+        ];
+        if touched {
+            spans.push(Span::styled(" ●", Style::default().fg(theme.primary)));
+        }
+        if let Some(status) = status {
+            spans.push(Span::styled(
+                format!(" {}", status_letter(status)),
+                Style::default().fg(status_color(status, theme)),
+            ));
+        }
+        Line::from(spans)
+    }
+
+    fn render_preview(&mut self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let Some(preview) = self.preview.as_mut() else {
+            return;
+        };
+        let block = Block::default()
+            .title(Span::styled(
+                format!(" {} ", preview.path),
+                Style::default()
+                    .fg(theme.primary)
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .title_bottom(Span::styled(
+                " v select range  ⏎ insert reference  esc close ",
+                Style::default().fg(theme.text_muted),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.background));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        preview.page = (inner.height as usize).max(1);
+        preview.scroll = scroll_to_fit(preview.scroll, preview.cursor, preview.page);
+        let (range_start, range_end) = preview.selected_range();
+        let number_width = preview.lines.len().to_string().len();
+
+        let lines: Vec<Line> = preview
+            .lines
+            .iter()
+            .enumerate()
+            .skip(preview.scroll)
+            .take(preview.page)
+            .map(|(index, spans)| {
+                let line_no = index + 1;
+                let in_range =
+                    preview.anchor.is_some() && (range_start..=range_end).contains(&line_no);
+                let gutter_style = if index == preview.cursor {
+                    Style::default()
+                        .fg(theme.primary)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.text_muted)
+                };
+                let mut line_spans = vec![Span::styled(
+                    format!("{:>width$} ", line_no, width = number_width),
+                    gutter_style,
+                )];
+                line_spans.extend(spans.iter().cloned());
+                let mut line = Line::from(line_spans);
+                if in_range {
+                    line = line.style(Style::default().bg(theme.background_element));
+                } else if index == preview.cursor {
+                    line = line.style(Style::default().bg(theme.background_panel));
+                }
+                line
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines), inner);
+    }
+}
+
+impl Default for FileTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let value = relative.to_string_lossy().replace('\\', "/");
+    (!value.is_empty()).then_some(value)
+}
+
+/// `a/b/c.rs` -> `["a", "a/b"]`.
+fn ancestors(path: &str) -> impl Iterator<Item = String> + '_ {
+    path.match_indices('/')
+        .map(|(index, _)| path[..index].to_string())
+}
+
+/// Groups sorted relative file paths by parent directory, directories first.
+fn build_children(files: &[String]) -> BTreeMap<String, Vec<TreeEntry>> {
+    let mut children: BTreeMap<String, Vec<TreeEntry>> = BTreeMap::new();
+    let mut seen_dirs = HashSet::new();
+    for file in files {
+        let mut parent = String::new();
+        for dir in ancestors(file) {
+            if seen_dirs.insert(dir.clone()) {
+                children.entry(parent.clone()).or_default().push(TreeEntry {
+                    name: dir[dir.rfind('/').map_or(0, |i| i + 1)..].to_string(),
+                    path: dir.clone(),
+                    is_dir: true,
+                });
+            }
+            parent = dir;
+        }
+        children.entry(parent).or_default().push(TreeEntry {
+            name: file[file.rfind('/').map_or(0, |i| i + 1)..].to_string(),
+            path: file.clone(),
+            is_dir: false,
+        });
+    }
+    for entries in children.values_mut() {
+        entries.sort_by(|a, b| b.is_dir.cmp(&a.is_dir).then_with(|| a.name.cmp(&b.name)));
+    }
+    children
+}
+
+fn push_rows(
+    children: &BTreeMap<String, Vec<TreeEntry>>,
+    expanded: &HashSet<String>,
+    parent: &str,
+    depth: usize,
+    rows: &mut Vec<TreeRow>,
+) {
+    let Some(entries) = children.get(parent) else {
+        return;
+    };
+    for entry in entries {
+        rows.push(TreeRow {
+            entry: entry.clone(),
+            depth,
+        });
+        if entry.is_dir && expanded.contains(&entry.path) {
+            push_rows(children, expanded, &entry.path, depth + 1, rows);
+        }
+    }
+}
+
+fn scroll_to_fit(scroll: usize, cursor: usize, page: usize) -> usize {
+    if cursor < scroll {
+        cursor
+    } else if cursor >= scroll + page {
+        cursor + 1 - page
+    } else {
+        scroll
+    }
+}
+
+fn status_letter(status: &str) -> char {
+    match status {
+        "modified" => 'M',
+        "added" => 'A',
+        "deleted" => 'D',
+        "renamed" => 'R',
+        "copied" => 'C',
+        "unmerged" => 'U',
+        "untracked" => '?',
+        _ => '•',
+    }
+}
+
+fn status_color(status: &str, theme: &Theme) -> Color {
+    match status {
+        "added" | "untracked" => theme.success,
+        "deleted" | "unmerged" => theme.error,
+        _ => theme.warning,
+    }
+}
+
+fn muted_line(text: &str, theme: &Theme) -> Vec<Span<'static>> {
+    vec![Span::styled(
+        text.to_string(),
+        Style::default().fg(theme.text_muted),
+    )]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn tree(files: &[&str]) -> FileTree {
+        let mut tree = FileTree::new();
+        tree.show();
+        tree.set_files(files.iter().map(|f| f.to_string()).collect());
+        tree
+    }
+
+    fn row_paths(tree: &FileTree) -> Vec<&str> {
+        tree.rows
+            .iter()
+            .map(|row| row.entry.path.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn lists_directories_first_and_expands_on_enter() {
+        let theme = Theme::default();
+        let mut tree = tree(&["README.md", "src/lib.rs", "src/ui/view.rs"]);
+        assert_eq!(row_paths(&tree), vec!["src", "README.md"]);
+
+        tree.handle_key(key(KeyCode::Enter), &theme);
+        assert_eq!(
+            row_paths(&tree),
+            vec!["src", "src/ui", "src/lib.rs", "README.md"]
+        );
+
+        tree.handle_key(key(KeyCode::Down), &theme);
+        tree.handle_key(key(KeyCode::Left), &theme);
+        assert_eq!(tree.selected, 0);
+        tree.handle_key(key(KeyCode::Left), &theme);
+        assert_eq!(row_paths(&tree), vec!["src", "README.md"]);
+    }
+
+    #[test]
+    fn inserts_file_references_and_line_ranges() {
+        let theme = Theme::default();
+        let mut tree = tree(&["src/lib.rs"]);
+        tree.handle_key(key(KeyCode::Enter), &theme);
+        tree.handle_key(key(KeyCode::Down), &theme);
+        assert_eq!(
+            tree.handle_key(key(KeyCode::Char('i')), &theme),
+            FileTreeAction::Insert("@src/lib.rs".to_string())
+        );
+
+        tree.preview = Some(FilePreview {
+            path: "src/lib.rs".to_string(),
+            lines: vec![Vec::new(); 10],
+            cursor: 2,
+            anchor: None,
+            scroll: 0,
+            page: 5,
+        });
+        tree.handle_key(key(KeyCode::Char('v')), &theme);
+        tree.handle_key(key(KeyCode::Down), &theme);
+        tree.handle_key(key(KeyCode::Down), &theme);
+        assert_eq!(
+            tree.handle_key(key(KeyCode::Enter), &theme),
+            FileTreeAction::Insert("@src/lib.rs#3-5".to_string())
+        );
+        assert!(tree.preview.is_none());
+        assert!(!tree.is_focused());
+    }
+
+    #[test]
+    fn marks_directories_containing_changes() {
+        let mut tree = tree(&["src/a/b.rs"]);
+        tree.set_git_status(&[FileStatusInfo {
+            path: "src/a/b.rs".to_string(),
+            status: "modified".to_string(),
+            staged: false,
+        }]);
+        assert!(tree.dirty_dirs.contains("src"));
+        assert!(tree.dirty_dirs.contains("src/a"));
+        assert!(!tree.dirty_dirs.contains("src/a/b.rs"));
+
+        tree.set_touched(["./src/a/b.rs"]);
+        assert!(tree.touched.contains("src/a/b.rs"));
+    }
+}
@@ -5,6 +5,9 @@ use ratatui::{
 
 use super::syntax;
 
+/// Clickable label on the header of every rendered code block.
+pub const COPY_CODE_LABEL: &str = "[copy]";
+
 #[derive(Clone, Debug)]
 pub struct CodeBlock {
     pub language: Option<String>,
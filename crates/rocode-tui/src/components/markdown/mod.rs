@@ -3,6 +3,6 @@ mod parser;
 mod renderer;
 mod syntax;
 
-pub use code_block::CodeBlock;
+pub use code_block::{CodeBlock, CodeTheme, COPY_CODE_LABEL};
 pub use parser::MarkdownBlock;
-pub use renderer::MarkdownRenderer;
+pub use renderer::{code_block_contents, MarkdownRenderer};
@@ -8,7 +8,7 @@ use ratatui::{
 };
 use unicode_width::UnicodeWidthStr;
 
-use super::code_block::{CodeBlock, CodeTheme};
+use super::code_block::{CodeBlock, CodeTheme, COPY_CODE_LABEL};
 use crate::theme::Theme;
 
 pub struct MarkdownRenderer {
@@ -143,13 +143,7 @@ impl MarkdownRenderer {
     }
 
     pub fn to_lines(&self, text: &str) -> Vec<Line<'static>> {
-        let mut options = Options::empty();
-        options.insert(Options::ENABLE_STRIKETHROUGH);
-        options.insert(Options::ENABLE_TABLES);
-        options.insert(Options::ENABLE_TASKLISTS);
-        options.insert(Options::ENABLE_FOOTNOTES);
-
-        let parser = Parser::new_ext(text, options);
+        let parser = Parser::new_ext(text, markdown_options());
 
         let mut lines: Vec<Line<'static>> = Vec::new();
         let mut current: Vec<Span<'static>> = Vec::new();
@@ -492,6 +486,8 @@ impl MarkdownRenderer {
                 "─".repeat(20),
                 Style::default().fg(self.theme.markdown_horizontal_rule),
             ),
+            Span::raw(" "),
+            Span::styled(COPY_CODE_LABEL, Style::default().fg(self.theme.text_muted)),
         ]));
 
         for code_spans in code_block.to_lines(&self.code_theme) {
@@ -510,6 +506,34 @@ impl MarkdownRenderer {
     }
 }
 
+/// Raw contents of every code block in `text`, in the order they render.
+pub fn code_block_contents(text: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current: Option<String> = None;
+    for event in Parser::new_ext(text, markdown_options()) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => current = Some(String::new()),
+            Event::End(TagEnd::CodeBlock) => blocks.extend(current.take()),
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(code) = current.as_mut() {
+                    code.push_str(&text);
+                }
+            }
+            _ => {}
+        }
+    }
+    blocks
+}
+
+fn markdown_options() -> Options {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_TASKLISTS);
+    options.insert(Options::ENABLE_FOOTNOTES);
+    options
+}
+
 fn table_border_line(
     left: char,
     middle: char,
@@ -1,6 +1,7 @@
 mod dialog;
 mod dialogs;
 mod diff;
+mod file_tree;
 mod home;
 mod logo;
 mod markdown;
@@ -10,11 +11,15 @@ mod permission;
 mod prompt;
 mod question;
 mod revert_card;
+mod review;
 pub mod semantic_highlight;
 mod session;
+mod session_image;
 mod session_message;
+mod session_split;
 mod session_text;
 mod session_tool;
+mod settings;
 mod sidebar;
 mod slash_command;
 mod spinner;
@@ -23,17 +28,20 @@ mod toast;
 mod todo_item;
 mod tool_call;
 mod tool_views;
+mod vim;
 
 pub use dialog::Dialog;
 pub use dialogs::{
-    Agent, AgentSelectDialog, AlertDialog, CommandPalette, ConfirmDialog, ForkDialog, ForkEntry,
-    HelpDialog, McpDialog, McpItem, Model, ModelSelectDialog, PromptStashDialog, Provider,
-    ProviderDialog, ProviderStatus, SessionDeleteState, SessionExportDialog, SessionItem,
-    SessionListDialog, SessionRenameDialog, SkillListDialog, StashItem, StatusDialog, StatusLine,
-    SubagentDialog, SubagentInfo, SubagentMessage, SubmitResult, Tag, TagDialog, ThemeListDialog,
+    switcher_entries, Agent, AgentSelectDialog, AlertDialog, CommandPalette, ConfirmDialog,
+    ForkDialog, ForkEntry, HelpDialog, ImageViewerDialog, McpDialog, McpItem, Model,
+    ModelSelectDialog, PromptStashDialog, Provider, ProviderDialog, ProviderStatus,
+    SessionDeleteState, SessionExportDialog, SessionItem, SessionListDialog, SessionRenameDialog,
+    SessionSwitcherDialog, SkillListDialog, StashItem, StatusDialog, StatusLine, SubagentDialog,
+    SubagentInfo, SubagentMessage, SubmitResult, SwitcherEntry, Tag, TagDialog, ThemeListDialog,
     ThemeOption, TimelineDialog, TimelineEntry,
 };
 pub use diff::{DiffLine, DiffLineType, DiffMode, DiffView};
+pub use file_tree::{FileTree, FileTreeAction};
 pub use home::HomeView;
 pub use logo::{exit_logo_lines, Logo};
 pub use markdown::{CodeBlock, MarkdownBlock, MarkdownRenderer};
@@ -41,7 +49,11 @@ pub use message::{MessagePart, MessageRole, MessageView};
 pub use permission::{PermissionAction, PermissionPrompt, PermissionRequest, PermissionType};
 pub use prompt::{Prompt, PromptStashEntry};
 pub use question::{QuestionOption, QuestionPrompt, QuestionRequest, QuestionType};
+pub use review::{HunkDecision, ReviewAction, ReviewView};
 pub use session::SessionView;
+pub use session_image::{collect_session_images, image_label, SessionImage};
+pub use session_split::{SessionSplit, SplitDirection, MAX_SPLIT_PANES};
+pub use settings::{SettingsAction, SettingsScope, SettingsView};
 pub use sidebar::Sidebar;
 pub use slash_command::SlashCommandPopup;
 pub use spinner::{KnightRiderSpinner, Spinner, SpinnerMode, TaskKind};
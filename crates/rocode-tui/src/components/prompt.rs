@@ -1,8 +1,8 @@
 use ratatui::prelude::Stylize;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
-    text::{Line, Span},
+    style::{Color, Modifier, Style},
+    text::{Line, Span, Text},
     widgets::{Block, Borders, Padding, Paragraph, Wrap},
     Frame,
 };
@@ -14,11 +14,12 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use unicode_width::UnicodeWidthChar;
 
-use crate::context::{AppContext, MessageRole, SessionStatus};
+use crate::context::{AppContext, Message, MessageRole, SessionStatus};
 use crate::file_index::FileIndex;
 use crate::theme::Theme;
 
 use super::spinner::{KnightRiderSpinner, SpinnerMode, TaskKind};
+use super::vim::{Vim, VimMode, VimOutcome};
 
 const MAX_HISTORY_ENTRIES: usize = 200;
 const MAX_STASH_ENTRIES: usize = 50;
@@ -90,6 +91,7 @@ pub struct Prompt {
     file_index: FileIndex,
     spinner: KnightRiderSpinner,
     mode: PromptMode,
+    vim: Option<Vim>,
     interrupt_press_count: u8,
     last_interrupt_time: Option<Instant>,
 }
@@ -179,6 +181,7 @@ impl Prompt {
             file_index: FileIndex::default(),
             spinner,
             mode: PromptMode::Normal,
+            vim: None,
             interrupt_press_count: 0,
             last_interrupt_time: None,
         };
@@ -254,50 +257,61 @@ impl Prompt {
             horizontal_bottom: " ",
         };
 
+        // The vim mode indicator takes the place of the bottom padding row.
+        let vim_mode = self.vim_mode();
+        let mut block = Block::default()
+            .borders(Borders::LEFT)
+            .border_set(border_set)
+            .border_style(Style::default().fg(active_color))
+            .padding(Padding::new(
+                PROMPT_BLOCK_PAD_LEFT,
+                PROMPT_BLOCK_PAD_RIGHT,
+                PROMPT_BLOCK_PAD_TOP,
+                PROMPT_BLOCK_PAD_BOTTOM.saturating_sub(u16::from(vim_mode.is_some())),
+            ))
+            .style(Style::default().bg(theme.background_element));
+        if let Some(mode) = vim_mode {
+            let color = match mode {
+                VimMode::Insert => theme.text_muted,
+                VimMode::Normal => active_color,
+                VimMode::Visual | VimMode::VisualLine => theme.warning,
+            };
+            block = block.title_bottom(Line::from(Span::styled(
+                format!(" {} ", mode.label()),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            )));
+        }
+        let text_style = Style::default().fg(if self.focused {
+            theme.text
+        } else {
+            theme.text_muted
+        });
+
+        let selection = self
+            .vim
+            .as_ref()
+            .and_then(|vim| vim.selection(&self.input, self.cursor_position));
         let paragraph = if self.input.is_empty() {
             Paragraph::new(Line::from(Span::styled(
                 placeholder,
                 Style::default().fg(theme.text_muted),
             )))
-            .block(
-                Block::default()
-                    .borders(Borders::LEFT)
-                    .border_set(border_set)
-                    .border_style(Style::default().fg(active_color))
-                    .padding(Padding::new(
-                        PROMPT_BLOCK_PAD_LEFT,
-                        PROMPT_BLOCK_PAD_RIGHT,
-                        PROMPT_BLOCK_PAD_TOP,
-                        PROMPT_BLOCK_PAD_BOTTOM,
-                    ))
-                    .style(Style::default().bg(theme.background_element)),
-            )
-            .style(Style::default().fg(if self.focused {
-                theme.text
-            } else {
-                theme.text_muted
-            }))
+            .block(block)
+            .style(text_style)
+        } else if let Some((start, end)) = selection {
+            let selected = Style::default()
+                .bg(theme.background_panel)
+                .fg(theme.text)
+                .add_modifier(Modifier::REVERSED);
+            Paragraph::new(selection_text(&self.input, start, end, selected))
+                .block(block)
+                .wrap(Wrap { trim: false })
+                .style(text_style)
         } else {
             Paragraph::new(self.input.clone())
-                .block(
-                    Block::default()
-                        .borders(Borders::LEFT)
-                        .border_set(border_set)
-                        .border_style(Style::default().fg(active_color))
-                        .padding(Padding::new(
-                            PROMPT_BLOCK_PAD_LEFT,
-                            PROMPT_BLOCK_PAD_RIGHT,
-                            PROMPT_BLOCK_PAD_TOP,
-                            PROMPT_BLOCK_PAD_BOTTOM,
-                        ))
-                        .style(Style::default().bg(theme.background_element)),
-                )
+                .block(block)
                 .wrap(Wrap { trim: false })
-                .style(Style::default().fg(if self.focused {
-                    theme.text
-                } else {
-                    theme.text_muted
-                }))
+                .style(text_style)
         };
 
         frame.render_widget(paragraph, chunks[0]);
@@ -550,6 +564,9 @@ impl Prompt {
         self.suggestions.clear();
         self.suggestion_index = None;
         self.mode = PromptMode::Normal;
+        if let Some(vim) = self.vim.as_mut() {
+            vim.reset();
+        }
         self.reset_interrupt_confirmation();
         input
     }
@@ -562,6 +579,9 @@ impl Prompt {
         self.suggestions.clear();
         self.suggestion_index = None;
         self.mode = PromptMode::Normal;
+        if let Some(vim) = self.vim.as_mut() {
+            vim.reset();
+        }
         self.reset_interrupt_confirmation();
     }
 
@@ -594,6 +614,58 @@ impl Prompt {
         self.mode = PromptMode::Normal;
     }
 
+    /// Turns modal vim editing on or off; a running vim session is kept.
+    pub fn set_vim_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            self.vim = None;
+        } else if self.vim.is_none() {
+            self.vim = Some(Vim::new());
+        }
+    }
+
+    pub fn vim_mode(&self) -> Option<VimMode> {
+        self.vim.as_ref().map(Vim::mode)
+    }
+
+    /// Routes a key through the vim engine. Returns `None` when the key
+    /// should go through the regular handling (insert mode, or keys vim
+    /// does not use), otherwise whether the prompt should be submitted.
+    pub fn handle_vim_key(&mut self, key: crossterm::event::KeyEvent) -> Option<bool> {
+        let vim = self.vim.as_mut()?;
+        if vim.mode() == VimMode::Insert {
+            let leave_insert =
+                self.context
+                    .keybind
+                    .read()
+                    .match_key("vim_normal_mode", key.code, key.modifiers);
+            if !leave_insert {
+                return None;
+            }
+            vim.enter_normal(&self.input, &mut self.cursor_position);
+            self.suggestions.clear();
+            self.suggestion_index = None;
+            return Some(false);
+        }
+
+        let before = self.input.clone();
+        let outcome = vim.handle_key(key, &mut self.input, &mut self.cursor_position);
+        match outcome {
+            VimOutcome::Ignored => return None,
+            VimOutcome::Submit => return Some(!self.input.is_empty()),
+            VimOutcome::HistoryPrevious => self.history_previous(),
+            VimOutcome::HistoryNext => self.history_next(),
+            VimOutcome::Handled if self.input != before => self.reset_history_cursor(),
+            VimOutcome::Handled => {}
+        }
+        if let Some(vim) = self.vim.as_ref() {
+            self.cursor_position = vim.normalize_cursor(&self.input, self.cursor_position);
+        }
+        if self.input != before {
+            self.recompute_suggestions();
+        }
+        Some(false)
+    }
+
     pub fn register_interrupt_keypress(&mut self) -> bool {
         if self.interrupt_confirmation_active() {
             self.reset_interrupt_confirmation();
@@ -1090,6 +1162,13 @@ impl Prompt {
                     Span::styled("thinking", Style::default().fg(theme.text_muted)),
                     Span::raw("  "),
                 ];
+                if let Some(note) = self.current_session_failover() {
+                    spans.push(Span::styled(
+                        truncate_for_status(&note, 72),
+                        Style::default().fg(theme.warning),
+                    ));
+                    spans.push(Span::raw("  "));
+                }
                 let queued = self.current_session_queue_count();
                 if queued > 0 {
                     spans.push(Span::styled(
@@ -1148,6 +1227,20 @@ impl Prompt {
         Some((last_assistant.tokens.input, last_assistant.tokens.output))
     }
 
+    fn current_session_failover(&self) -> Option<String> {
+        let session_id = match self.context.current_route() {
+            crate::router::Route::Session { session_id } => session_id,
+            _ => return None,
+        };
+        let session_ctx = self.context.session.read();
+        let messages = session_ctx.messages.get(&session_id)?;
+        messages
+            .iter()
+            .rev()
+            .find(|message| matches!(message.role, MessageRole::Assistant))
+            .and_then(failover_note)
+    }
+
     fn interrupt_confirmation_active(&self) -> bool {
         if self.interrupt_press_count == 0 {
             return false;
@@ -1201,6 +1294,46 @@ impl Prompt {
     }
 }
 
+/// The prompt input with the visual-mode selection `start..end` styled.
+fn selection_text(input: &str, start: usize, end: usize, selected: Style) -> Text<'static> {
+    let mut offset = 0;
+    let lines = input
+        .split('\n')
+        .map(|line| {
+            let line_start = offset;
+            let line_end = offset + line.len();
+            offset = line_end + 1;
+            let from = start.clamp(line_start, line_end) - line_start;
+            let to = end.clamp(line_start, line_end) - line_start;
+            let mut spans = vec![Span::raw(line[..from].to_string())];
+            if to > from {
+                spans.push(Span::styled(line[from..to].to_string(), selected));
+            } else if start <= line_end && end > line_end {
+                // A selected line break shows as a highlighted blank.
+                spans.push(Span::styled(" ", selected));
+            }
+            spans.push(Span::raw(line[to..].to_string()));
+            Line::from(spans)
+        })
+        .collect::<Vec<_>>();
+    Text::from(lines)
+}
+
+/// "failover a → b (reason)" when the server moved this message off the
+/// agent's model.
+fn failover_note(message: &Message) -> Option<String> {
+    let metadata = message.metadata.as_ref()?;
+    let from = metadata.get("failover_from")?.as_str()?;
+    let to = message.model.as_deref().unwrap_or("fallback");
+    let reason = metadata
+        .get("failover_reason")
+        .and_then(|reason| reason.as_str());
+    Some(match reason {
+        Some(reason) => format!("failover {} → {} ({})", from, to, reason),
+        None => format!("failover {} → {}", from, to),
+    })
+}
+
 fn truncate_for_status(input: &str, max_chars: usize) -> String {
     if input.chars().count() <= max_chars {
         return input.to_string();
@@ -1404,6 +1537,42 @@ mod tests {
         result
     }
 
+    #[test]
+    fn failover_note_names_both_models() {
+        let mut message = Message {
+            id: "msg_1".to_string(),
+            role: MessageRole::Assistant,
+            content: String::new(),
+            created_at: chrono::Utc::now(),
+            agent: None,
+            model: Some("amazon-bedrock/claude-sonnet".to_string()),
+            mode: None,
+            finish: None,
+            error: None,
+            completed_at: None,
+            cost: 0.0,
+            tokens: Default::default(),
+            metadata: None,
+            parts: Vec::new(),
+        };
+        assert_eq!(failover_note(&message), None);
+
+        message.metadata = Some(HashMap::from([
+            (
+                "failover_from".to_string(),
+                serde_json::json!("anthropic/claude-sonnet"),
+            ),
+            (
+                "failover_reason".to_string(),
+                serde_json::json!("overloaded"),
+            ),
+        ]));
+        assert_eq!(
+            failover_note(&message).as_deref(),
+            Some("failover anthropic/claude-sonnet → amazon-bedrock/claude-sonnet (overloaded)")
+        );
+    }
+
     #[test]
     fn tab_autocomplete_uses_first_candidate() {
         with_isolated_prompt(|mut prompt| {
@@ -0,0 +1,405 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+    Frame,
+};
+
+use super::diff::{DiffMode, DiffView};
+use crate::api::{ReviewFile, ReviewRejection};
+use crate::theme::Theme;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HunkDecision {
+    Pending,
+    Accept,
+    Reject,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReviewAction {
+    None,
+    Close,
+    /// Revert these hunks on disk. Accepted hunks need no server round-trip.
+    Apply(Vec<ReviewRejection>),
+}
+
+/// Full-screen review of the files changed in a session, with a per-hunk
+/// accept/reject decision. Binary files are decided as a whole.
+pub struct ReviewView {
+    files: Vec<ReviewFile>,
+    decisions: Vec<Vec<HunkDecision>>,
+    file: usize,
+    hunk: usize,
+    mode: DiffMode,
+    message: Option<String>,
+}
+
+impl ReviewView {
+    pub fn new() -> Self {
+        Self {
+            files: Vec::new(),
+            decisions: Vec::new(),
+            file: 0,
+            hunk: 0,
+            mode: DiffMode::Unified,
+            message: None,
+        }
+    }
+
+    pub fn set_files(&mut self, files: Vec<ReviewFile>) {
+        self.decisions = files
+            .iter()
+            .map(|file| vec![HunkDecision::Pending; file.hunks.len().max(1)])
+            .collect();
+        self.files = files;
+        self.file = self.file.min(self.files.len().saturating_sub(1));
+        self.hunk = 0;
+        self.message = None;
+    }
+
+    pub fn set_message(&mut self, message: impl Into<String>) {
+        self.message = Some(message.into());
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> ReviewAction {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return ReviewAction::Close,
+            KeyCode::Down | KeyCode::Char('j') => self.move_hunk(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_hunk(-1),
+            KeyCode::Tab | KeyCode::Right | KeyCode::Char('l') => self.move_file(1),
+            KeyCode::BackTab | KeyCode::Left | KeyCode::Char('h') => self.move_file(-1),
+            KeyCode::Char('a') => self.decide(HunkDecision::Accept, false),
+            KeyCode::Char('x') | KeyCode::Char('r') => self.decide(HunkDecision::Reject, false),
+            KeyCode::Char('A') => self.decide(HunkDecision::Accept, true),
+            KeyCode::Char('X') | KeyCode::Char('R') => self.decide(HunkDecision::Reject, true),
+            KeyCode::Char('s') => {
+                self.mode = match self.mode {
+                    DiffMode::Unified => DiffMode::Split,
+                    DiffMode::Split => DiffMode::Unified,
+                };
+            }
+            KeyCode::Enter | KeyCode::Char('w') => {
+                let rejections = self.rejections();
+                if rejections.is_empty() {
+                    self.message = Some("No hunks marked for rejection".to_string());
+                    return ReviewAction::None;
+                }
+                return ReviewAction::Apply(rejections);
+            }
+            _ => {}
+        }
+        ReviewAction::None
+    }
+
+    /// Hunks marked for rejection, grouped by file.
+    pub fn rejections(&self) -> Vec<ReviewRejection> {
+        self.files
+            .iter()
+            .zip(&self.decisions)
+            .filter_map(|(file, decisions)| {
+                let hunks: Vec<usize> = decisions
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, decision)| **decision == HunkDecision::Reject)
+                    .map(|(index, _)| index)
+                    .collect();
+                if hunks.is_empty() {
+                    return None;
+                }
+                Some(ReviewRejection {
+                    path: file.path.clone(),
+                    hunks: if file.binary { Vec::new() } else { hunks },
+                })
+            })
+            .collect()
+    }
+
+    fn move_hunk(&mut self, delta: isize) {
+        let Some(decisions) = self.decisions.get(self.file) else {
+            return;
+        };
+        let next = self.hunk as isize + delta;
+        if next < 0 {
+            if self.file > 0 {
+                self.file -= 1;
+                self.hunk = self.decisions[self.file].len() - 1;
+            }
+        } else if next as usize >= decisions.len() {
+            if self.file + 1 < self.files.len() {
+                self.file += 1;
+                self.hunk = 0;
+            }
+        } else {
+            self.hunk = next as usize;
+        }
+    }
+
+    fn move_file(&mut self, delta: isize) {
+        if self.files.is_empty() {
+            return;
+        }
+        let len = self.files.len() as isize;
+        self.file = (self.file as isize + delta).rem_euclid(len) as usize;
+        self.hunk = 0;
+    }
+
+    fn decide(&mut self, decision: HunkDecision, whole_file: bool) {
+        let Some(decisions) = self.decisions.get_mut(self.file) else {
+            return;
+        };
+        if whole_file {
+            decisions.iter_mut().for_each(|d| *d = decision);
+            return;
+        }
+        if let Some(current) = decisions.get_mut(self.hunk) {
+            *current = decision;
+        }
+        self.move_hunk(1);
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let block = Block::default()
+            .title(Span::styled(
+                " Review changes ",
+                Style::default()
+                    .fg(theme.primary)
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.background));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Min(3),
+                Constraint::Length(1),
+            ])
+            .split(inner);
+
+        frame.render_widget(
+            Paragraph::new(Line::from(Span::styled(
+                " j/k hunk  Tab file  a accept  x reject  A/X whole file  s split  Enter apply  Esc back",
+                Style::default().fg(theme.text_muted),
+            ))),
+            rows[0],
+        );
+
+        if self.files.is_empty() {
+            frame.render_widget(
+                Paragraph::new(Span::styled(
+                    " No changes in this session",
+                    Style::default().fg(theme.text_muted),
+                )),
+                rows[1],
+            );
+            return;
+        }
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(32), Constraint::Min(20)])
+            .split(rows[1]);
+
+        let file_lines: Vec<Line> = self
+            .files
+            .iter()
+            .zip(&self.decisions)
+            .enumerate()
+            .map(|(i, (file, decisions))| {
+                let marker = match file.status.as_str() {
+                    "added" => "A",
+                    "deleted" => "D",
+                    _ => "M",
+                };
+                let rejected = decisions
+                    .iter()
+                    .filter(|d| **d == HunkDecision::Reject)
+                    .count();
+                let style = if i == self.file {
+                    Style::default().fg(theme.background).bg(theme.primary)
+                } else {
+                    Style::default().fg(theme.text)
+                };
+                let mut spans = vec![Span::styled(format!(" {} {}", marker, file.path), style)];
+                if rejected > 0 {
+                    spans.push(Span::styled(
+                        format!(" ✗{}", rejected),
+                        Style::default().fg(theme.error),
+                    ));
+                }
+                Line::from(spans)
+            })
+            .collect();
+        frame.render_widget(
+            Paragraph::new(file_lines).block(
+                Block::default()
+                    .borders(Borders::RIGHT)
+                    .border_style(Style::default().fg(theme.border)),
+            ),
+            columns[0],
+        );
+
+        let (lines, selected_line) = self.hunk_lines(theme, columns[1].width);
+        let height = columns[1].height as usize;
+        let scroll = selected_line.saturating_sub(height / 3);
+        frame.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), columns[1]);
+
+        if let Some(message) = &self.message {
+            frame.render_widget(
+                Paragraph::new(Span::styled(
+                    format!(" {}", message),
+                    Style::default().fg(theme.warning),
+                )),
+                rows[2],
+            );
+        }
+    }
+
+    /// Lines for the selected file, and the index of the selected hunk's
+    /// first line.
+    fn hunk_lines(&self, theme: &Theme, width: u16) -> (Vec<Line<'static>>, usize) {
+        let file = &self.files[self.file];
+        let decisions = &self.decisions[self.file];
+        let status_line = |index: usize| {
+            let (label, color) = match decisions[index] {
+                HunkDecision::Pending => ("pending", theme.text_muted),
+                HunkDecision::Accept => ("accepted", theme.success),
+                HunkDecision::Reject => ("rejected", theme.error),
+            };
+            let pointer = if index == self.hunk { "▶" } else { " " };
+            Line::from(vec![
+                Span::styled(format!("{} ", pointer), Style::default().fg(theme.primary)),
+                Span::styled(
+                    format!("[{}]", label),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+            ])
+        };
+
+        if file.binary {
+            return (
+                vec![
+                    status_line(0),
+                    Line::from(Span::styled(
+                        "  Binary file; rejecting restores it from the snapshot",
+                        Style::default().fg(theme.text_muted),
+                    )),
+                ],
+                0,
+            );
+        }
+
+        let mut lines = Vec::new();
+        let mut selected_line = 0;
+        for (index, hunk) in file.hunks.iter().enumerate() {
+            if index == self.hunk {
+                selected_line = lines.len();
+            }
+            lines.push(status_line(index));
+            let content = std::iter::once(hunk.header.as_str())
+                .chain(
+                    hunk.lines
+                        .iter()
+                        .map(String::as_str)
+                        .filter(|line| !line.starts_with('\\')),
+                )
+                .collect::<Vec<_>>()
+                .join("\n");
+            let view = DiffView::new().with_content(&content);
+            match self.mode {
+                DiffMode::Unified => lines.extend(view.to_lines(theme)),
+                DiffMode::Split => lines.extend(view.to_split_lines(theme, width)),
+            }
+            lines.push(Line::default());
+        }
+        (lines, selected_line)
+    }
+}
+
+impl Default for ReviewView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::ReviewHunk;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn file(path: &str, hunks: usize, binary: bool) -> ReviewFile {
+        ReviewFile {
+            path: path.to_string(),
+            status: "modified".to_string(),
+            binary,
+            additions: 1,
+            deletions: 1,
+            hunks: (0..hunks)
+                .map(|i| ReviewHunk {
+                    old_start: i * 10 + 1,
+                    old_lines: 1,
+                    new_start: i * 10 + 1,
+                    new_lines: 1,
+                    header: format!("@@ -{0},1 +{0},1 @@", i * 10 + 1),
+                    lines: vec!["-old".to_string(), "+new".to_string()],
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn rejections_collect_rejected_hunks_per_file() {
+        let mut view = ReviewView::new();
+        view.set_files(vec![
+            file("a.rs", 2, false),
+            file("b.rs", 2, false),
+            file("logo.png", 0, true),
+        ]);
+
+        assert_eq!(view.handle_key(key(KeyCode::Char('a'))), ReviewAction::None);
+        view.handle_key(key(KeyCode::Char('x')));
+        // Moved on to b.rs; reject all of it, then the binary file.
+        view.handle_key(key(KeyCode::Char('X')));
+        view.handle_key(key(KeyCode::Tab));
+        view.handle_key(key(KeyCode::Char('x')));
+
+        assert_eq!(
+            view.handle_key(key(KeyCode::Enter)),
+            ReviewAction::Apply(vec![
+                ReviewRejection {
+                    path: "a.rs".to_string(),
+                    hunks: vec![1],
+                },
+                ReviewRejection {
+                    path: "b.rs".to_string(),
+                    hunks: vec![0, 1],
+                },
+                ReviewRejection {
+                    path: "logo.png".to_string(),
+                    hunks: Vec::new(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn apply_without_rejections_does_nothing() {
+        let mut view = ReviewView::new();
+        view.set_files(vec![file("a.rs", 1, false)]);
+        view.handle_key(key(KeyCode::Char('a')));
+        assert_eq!(view.handle_key(key(KeyCode::Enter)), ReviewAction::None);
+        assert_eq!(view.handle_key(key(KeyCode::Esc)), ReviewAction::Close);
+    }
+}
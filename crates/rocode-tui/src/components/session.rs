@@ -3,6 +3,7 @@ use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use unicode_width::UnicodeWidthChar;
 use unicode_width::UnicodeWidthStr;
 
@@ -14,10 +15,15 @@ use ratatui::{
     Frame,
 };
 
+use super::markdown::{code_block_contents, COPY_CODE_LABEL};
 use super::message_palette;
+use super::session_image::{
+    render_image_part, tool_result_images, ImageBlock, ImagePart, InlineImage,
+};
 use super::sidebar::SidebarState;
 use crate::components::{Prompt, Sidebar};
 use crate::context::{AppContext, Message, MessagePart, MessageRole, SidebarMode};
+use crate::ui::{highlight_cell, strip_session_gutter_line, word_bounds, ImageAccess, Selection};
 
 const SIDEBAR_WIDTH: u16 = 42;
 const HEADER_NARROW_THRESHOLD: u16 = 80;
@@ -27,12 +33,37 @@ const MESSAGE_BLOCK_RIGHT_PADDING: usize = 1;
 const SIDEBAR_CLOSE_BUTTON_WIDTH: u16 = 3;
 const SIDEBAR_OPEN_BUTTON_WIDTH: u16 = 3;
 const SEMANTIC_HIGHLIGHT_MAX_CHARS: usize = 8_000;
+const SELECTION_AUTO_SCROLL_MS: u64 = 60;
 
 struct ThinkingToggleHit {
     line_index: usize,
     reasoning_id: String,
 }
 
+struct ImageHit {
+    first_line: usize,
+    image_line: usize,
+    url: String,
+    inline: Option<InlineImage>,
+}
+
+struct CodeCopyHit {
+    line_index: usize,
+    start_col: u16,
+    end_col: u16,
+    code: String,
+}
+
+impl ImageHit {
+    fn end_line(&self) -> usize {
+        self.image_line
+            + self
+                .inline
+                .as_ref()
+                .map_or(0, |inline| usize::from(inline.rows))
+    }
+}
+
 pub struct SessionView {
     context: Arc<AppContext>,
     session_id: String,
@@ -41,6 +72,16 @@ pub struct SessionView {
     messages_viewport_height: usize,
     expanded_reasoning: HashSet<String>,
     thinking_toggle_hits: Vec<ThinkingToggleHit>,
+    image_hits: Vec<ImageHit>,
+    code_copy_hits: Vec<CodeCopyHit>,
+    /// Plain text of every rendered transcript line, for selection copy.
+    line_texts: Vec<String>,
+    /// Selection over transcript lines, so it survives scrolling.
+    selection: Selection,
+    /// Last drag position, used to keep scrolling while the pointer rests
+    /// above or below the transcript.
+    drag_position: Option<(u16, u16)>,
+    last_auto_scroll: Instant,
     last_messages_area: Option<Rect>,
     line_to_message: Vec<Option<String>>,
     message_first_lines: HashMap<String, usize>,
@@ -59,6 +100,12 @@ impl SessionView {
             messages_viewport_height: 0,
             expanded_reasoning: HashSet::new(),
             thinking_toggle_hits: Vec::new(),
+            image_hits: Vec::new(),
+            code_copy_hits: Vec::new(),
+            line_texts: Vec::new(),
+            selection: Selection::new(),
+            drag_position: None,
+            last_auto_scroll: Instant::now(),
             last_messages_area: None,
             line_to_message: Vec::new(),
             message_first_lines: HashMap::new(),
@@ -105,6 +152,7 @@ impl SessionView {
         };
 
         let overlay_tint = tint_sidebar_overlay(theme.background_menu, theme.primary);
+        self.context.graphics.write().hide_in(sidebar_area);
 
         // Render a subtle underlay just for the sidebar area.
         let underlay = Block::default().style(Style::default().bg(overlay_tint));
@@ -501,6 +549,9 @@ impl SessionView {
             self.messages_viewport_height = 0;
             self.line_to_message.clear();
             self.message_first_lines.clear();
+            self.image_hits.clear();
+            self.code_copy_hits.clear();
+            self.line_texts.clear();
             return;
         }
 
@@ -530,6 +581,8 @@ impl SessionView {
         let show_tool_details = *self.context.show_tool_details.read();
         let semantic_hl = *self.context.semantic_highlight.read();
         let fallback_model = self.context.current_model.read().clone();
+        let directory = self.context.directory_path();
+        let mut graphics = self.context.graphics.write();
 
         let messages = session_ctx
             .messages
@@ -546,6 +599,8 @@ impl SessionView {
         self.last_messages_area = Some(messages_area);
         self.thinking_toggle_hits.clear();
         let mut visible_reasoning_ids = HashSet::new();
+        let mut image_hits = Vec::new();
+        let mut code_copy_hits = Vec::new();
 
         let mut lines = Vec::new();
         let mut line_to_message: Vec<Option<String>> = Vec::new();
@@ -603,12 +658,49 @@ impl SessionView {
                         show_timestamps,
                         msg.agent.as_deref(),
                     );
+                    let first_line = lines.len();
                     append_message_lines(
                         &mut lines,
                         &mut line_to_message,
                         &msg.id,
                         paint_block_lines(user_lines, message_bg, message_border, content_width),
                     );
+                    let codes = msg
+                        .parts
+                        .iter()
+                        .filter_map(|part| match part {
+                            MessagePart::Text { text } => Some(code_block_contents(text)),
+                            _ => None,
+                        })
+                        .flatten()
+                        .collect();
+                    collect_code_copy_hits(&lines, first_line, codes, &mut code_copy_hits);
+                    for part in &msg.parts {
+                        let MessagePart::Image { url, filename } = part else {
+                            continue;
+                        };
+                        let block = render_image_part(
+                            &mut graphics,
+                            ImagePart {
+                                url,
+                                filename: filename.as_deref(),
+                                access: ImageAccess::Attachment,
+                            },
+                            Span::raw("┃ "),
+                            Span::raw("┃ "),
+                            &theme,
+                            content_width,
+                        );
+                        image_hits.push(append_image_block(
+                            &mut lines,
+                            &mut line_to_message,
+                            &msg.id,
+                            block,
+                            message_bg,
+                            message_border,
+                            content_width,
+                        ));
+                    }
                 }
                 MessageRole::Assistant => {
                     let message_bg = theme.background;
@@ -646,6 +738,7 @@ impl SessionView {
                             text_lines =
                                 super::semantic_highlight::highlight_lines(text_lines, &theme);
                         }
+                        let first_line = lines.len();
                         append_message_lines(
                             &mut lines,
                             &mut line_to_message,
@@ -657,6 +750,12 @@ impl SessionView {
                                 content_width,
                             ),
                         );
+                        collect_code_copy_hits(
+                            &lines,
+                            first_line,
+                            code_block_contents(&msg.content),
+                            &mut code_copy_hits,
+                        );
                     } else {
                         let mut prev_was_text = false;
                         let mut prev_was_tool = false;
@@ -687,6 +786,7 @@ impl SessionView {
                                             text_lines, &theme,
                                         );
                                     }
+                                    let first_line = lines.len();
                                     append_message_lines(
                                         &mut lines,
                                         &mut line_to_message,
@@ -698,6 +798,12 @@ impl SessionView {
                                             content_width,
                                         ),
                                     );
+                                    collect_code_copy_hits(
+                                        &lines,
+                                        first_line,
+                                        code_block_contents(text),
+                                        &mut code_copy_hits,
+                                    );
                                     prev_was_text = true;
                                     prev_was_tool = false;
                                 }
@@ -816,6 +922,36 @@ impl SessionView {
                                         &msg.id,
                                         painted_tool,
                                     );
+                                    let attachments = tool_results
+                                        .get(id)
+                                        .map(|info| tool_result_images(info.metadata.as_ref()))
+                                        .unwrap_or_default();
+                                    for image in attachments {
+                                        let block = render_image_part(
+                                            &mut graphics,
+                                            ImagePart {
+                                                url: &image.url,
+                                                filename: image.filename.as_deref(),
+                                                access: ImageAccess::Within(&directory),
+                                            },
+                                            Span::styled(
+                                                "▸ ",
+                                                Style::default().fg(assistant_marker),
+                                            ),
+                                            Span::raw("  "),
+                                            &theme,
+                                            content_width,
+                                        );
+                                        image_hits.push(append_image_block(
+                                            &mut lines,
+                                            &mut line_to_message,
+                                            &msg.id,
+                                            block,
+                                            message_bg,
+                                            message_border,
+                                            content_width,
+                                        ));
+                                    }
                                     prev_was_text = false;
                                     prev_was_tool = true;
                                 }
@@ -842,26 +978,28 @@ impl SessionView {
                                         ),
                                     );
                                 }
-                                MessagePart::Image { url } => {
-                                    let image_line = Line::from(vec![
+                                MessagePart::Image { url, filename } => {
+                                    let block = render_image_part(
+                                        &mut graphics,
+                                        ImagePart {
+                                            url,
+                                            filename: filename.as_deref(),
+                                            access: ImageAccess::Within(&directory),
+                                        },
                                         Span::styled("▸ ", Style::default().fg(assistant_marker)),
-                                        Span::styled("[image] ", Style::default().fg(theme.info)),
-                                        Span::styled(
-                                            url.clone(),
-                                            Style::default().fg(theme.text_muted),
-                                        ),
-                                    ]);
-                                    append_message_lines(
+                                        Span::raw("  "),
+                                        &theme,
+                                        content_width,
+                                    );
+                                    image_hits.push(append_image_block(
                                         &mut lines,
                                         &mut line_to_message,
                                         &msg.id,
-                                        paint_block_lines(
-                                            vec![image_line],
-                                            message_bg,
-                                            message_border,
-                                            content_width,
-                                        ),
-                                    );
+                                        block,
+                                        message_bg,
+                                        message_border,
+                                        content_width,
+                                    ));
                                 }
                             }
                         }
@@ -942,12 +1080,48 @@ impl SessionView {
             self.scroll_offset = max_scroll;
         }
 
+        // Only images that fit entirely in the viewport are drawn; the rest
+        // keep their caption and blank rows.
+        let viewport_end = self.scroll_offset + self.messages_viewport_height;
+        for hit in &image_hits {
+            let Some(inline) = hit.inline.as_ref() else {
+                continue;
+            };
+            if hit.image_line < self.scroll_offset || hit.end_line() > viewport_end {
+                continue;
+            }
+            graphics.place(
+                inline.id,
+                Rect {
+                    x: messages_area.x + inline.x_offset,
+                    y: messages_area.y + (hit.image_line - self.scroll_offset) as u16,
+                    width: inline.cols,
+                    height: inline.rows,
+                },
+            );
+        }
+        drop(graphics);
+        self.image_hits = image_hits;
+        self.code_copy_hits = code_copy_hits;
+        self.line_texts = lines.iter().map(line_plain_text).collect();
+
         // No outer Block border — each message line's paint_block_line already
         // includes its own gutter character and fills the full content_width,
         // so the colored background spans the entire area width.
         let paragraph = Paragraph::new(lines).scroll((self.scroll_offset as u16, 0));
 
         frame.render_widget(paragraph, messages_area);
+        if self.selection.is_active() {
+            let buf = frame.buffer_mut();
+            for y in messages_area.y..messages_area.y + messages_area.height {
+                let line_index = self.scroll_offset + usize::from(y - messages_area.y);
+                for x in messages_area.x..messages_area.x + messages_area.width {
+                    if self.selection.is_selected(line_index, x - messages_area.x) {
+                        highlight_cell(buf.get_mut(x, y));
+                    }
+                }
+            }
+        }
         if let Some(scroll_area) = scrollbar_area {
             let mut scrollbar_state = ScrollbarState::new(self.rendered_line_count)
                 .position(self.scroll_offset)
@@ -963,6 +1137,18 @@ impl SessionView {
         }
     }
 
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Renders only the message list, without prompt or sidebar; used for the
+    /// read-only panes of a split layout.
+    pub fn render_watch(&mut self, frame: &mut Frame, area: Rect) {
+        self.sidebar_close_button_area = None;
+        self.sidebar_open_button_area = None;
+        self.render_messages(frame, area);
+    }
+
     pub fn handle_click(&mut self, col: u16, row: u16) -> bool {
         let Some(area) = self.last_messages_area else {
             return false;
@@ -993,6 +1179,172 @@ impl SessionView {
         true
     }
 
+    /// Starts a transcript selection when the point is over the messages.
+    pub fn begin_selection(&mut self, col: u16, row: u16) -> bool {
+        let Some((line, col)) = self.transcript_position(col, row) else {
+            return false;
+        };
+        self.selection.start(line, col);
+        self.drag_position = Some((col, row));
+        true
+    }
+
+    pub fn update_selection(&mut self, col: u16, row: u16) {
+        if !self.selection.is_selecting() {
+            return;
+        }
+        self.drag_position = Some((col, row));
+        self.extend_selection_to(col, row);
+    }
+
+    /// Keeps scrolling while a drag rests above or below the transcript.
+    /// Returns true when the view moved.
+    pub fn auto_scroll_selection(&mut self) -> bool {
+        let (Some((col, row)), Some(area)) = (self.drag_position, self.last_messages_area) else {
+            return false;
+        };
+        if !self.selection.is_selecting() || (row > area.y && row < area.bottom().saturating_sub(1))
+        {
+            return false;
+        }
+        if self.last_auto_scroll.elapsed() < Duration::from_millis(SELECTION_AUTO_SCROLL_MS) {
+            return false;
+        }
+        self.last_auto_scroll = Instant::now();
+        let before = self.scroll_offset;
+        self.extend_selection_to(col, row);
+        self.scroll_offset != before
+    }
+
+    pub fn finish_selection(&mut self) {
+        self.selection.finalize();
+        self.drag_position = None;
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selection.clear();
+        self.drag_position = None;
+    }
+
+    pub fn has_selection(&self) -> bool {
+        self.selection.is_active()
+    }
+
+    pub fn is_selecting(&self) -> bool {
+        self.selection.is_selecting()
+    }
+
+    /// Selects the word under the point (double click).
+    pub fn select_word_at(&mut self, col: u16, row: u16) -> bool {
+        let Some((line, col)) = self.transcript_position(col, row) else {
+            return false;
+        };
+        let Some((start, end)) = self
+            .line_texts
+            .get(line)
+            .and_then(|text| word_bounds(text, col))
+        else {
+            return false;
+        };
+        self.selection.select((line, start), (line, end));
+        true
+    }
+
+    /// Selects the whole line under the point (triple click).
+    pub fn select_line_at(&mut self, col: u16, row: u16) -> bool {
+        let Some((line, _)) = self.transcript_position(col, row) else {
+            return false;
+        };
+        let width = self
+            .last_messages_area
+            .map_or(0, |area| area.width.saturating_sub(1));
+        self.selection.select((line, 0), (line, width));
+        true
+    }
+
+    /// The selection as rendered text, without gutters or message markers.
+    pub fn selected_text(&self) -> String {
+        self.selection
+            .get_selected_text(|row| self.line_texts.get(row).cloned())
+            .lines()
+            .map(|line| {
+                let line = strip_session_gutter_line(line);
+                match line.strip_prefix("▸ ") {
+                    Some(rest) => rest.to_string(),
+                    None => line,
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Ids of the messages the selection touches, in transcript order.
+    pub fn selected_message_ids(&self) -> Vec<String> {
+        let Some((first, last)) = self.selection.rows() else {
+            return Vec::new();
+        };
+        let mut ids: Vec<String> = Vec::new();
+        for id in self
+            .line_to_message
+            .iter()
+            .skip(first)
+            .take(last.saturating_sub(first) + 1)
+            .flatten()
+        {
+            if ids.last() != Some(id) {
+                ids.push(id.clone());
+            }
+        }
+        ids
+    }
+
+    /// Code of the block whose copy label is under the point.
+    pub fn code_block_at(&self, col: u16, row: u16) -> Option<String> {
+        let (line, col) = self.transcript_position(col, row)?;
+        self.code_copy_hits
+            .iter()
+            .find(|hit| hit.line_index == line && (hit.start_col..hit.end_col).contains(&col))
+            .map(|hit| hit.code.clone())
+    }
+
+    fn extend_selection_to(&mut self, col: u16, row: u16) {
+        let Some(area) = self.last_messages_area else {
+            return;
+        };
+        if row <= area.y {
+            self.scroll_up_by(MOUSE_SCROLL_LINES);
+        } else if row >= area.bottom().saturating_sub(1) {
+            self.scroll_down_by(MOUSE_SCROLL_LINES);
+        }
+        let row = row.clamp(area.y, area.bottom().saturating_sub(1));
+        let col = col.clamp(area.x, area.right().saturating_sub(1));
+        let line = (self.scroll_offset + usize::from(row - area.y))
+            .min(self.rendered_line_count.saturating_sub(1));
+        self.selection.update(line, col - area.x);
+    }
+
+    fn transcript_position(&self, col: u16, row: u16) -> Option<(usize, u16)> {
+        let area = self.last_messages_area?;
+        if col < area.x || col >= area.right() || row < area.y || row >= area.bottom() {
+            return None;
+        }
+        let line = self.scroll_offset + usize::from(row - area.y);
+        (line < self.rendered_line_count).then_some((line, col - area.x))
+    }
+
+    /// URL of the image under the given screen cell, caption included.
+    pub fn image_at(&self, col: u16, row: u16) -> Option<String> {
+        let area = self.last_messages_area?;
+        if col < area.x || col >= area.x.saturating_add(area.width) || row < area.y {
+            return None;
+        }
+        let line_index = self.scroll_offset + usize::from(row - area.y);
+        self.image_hits
+            .iter()
+            .find(|hit| (hit.first_line..hit.end_line().max(hit.image_line)).contains(&line_index))
+            .map(|hit| hit.url.clone())
+    }
+
     pub fn handle_sidebar_click(&mut self, col: u16, row: u16) -> bool {
         if point_in_optional_rect(self.sidebar_open_button_area, col, row) {
             *self.context.show_sidebar.write() = true;
@@ -1125,6 +1477,73 @@ fn append_message_lines(
     lines.extend(new_lines);
 }
 
+/// Appends an image block (caption and the rows its picture covers) as its
+/// own padded block and returns where the picture goes.
+fn append_image_block(
+    lines: &mut Vec<Line<'static>>,
+    line_to_message: &mut Vec<Option<String>>,
+    message_id: &str,
+    block: ImageBlock,
+    background: Color,
+    border_color: Color,
+    width: usize,
+) -> ImageHit {
+    let first_line = lines.len();
+    let rows = block.rows.len();
+    let mut block_lines = vec![block.caption];
+    block_lines.extend(block.rows);
+    append_message_lines(
+        lines,
+        line_to_message,
+        message_id,
+        paint_block_lines(block_lines, background, border_color, width),
+    );
+    ImageHit {
+        first_line,
+        // The picture rows sit right above the bottom padding line.
+        image_line: lines.len().saturating_sub(rows + 1),
+        url: block.url,
+        inline: block.inline,
+    }
+}
+
+/// Records the copy label of each code block rendered in `lines[first_line..]`,
+/// pairing labels with `codes` in order.
+fn collect_code_copy_hits(
+    lines: &[Line<'static>],
+    first_line: usize,
+    codes: Vec<String>,
+    hits: &mut Vec<CodeCopyHit>,
+) {
+    let mut codes = codes.into_iter();
+    for (offset, line) in lines.iter().enumerate().skip(first_line) {
+        let text = line_plain_text(line);
+        if !text.contains('╭') {
+            continue;
+        }
+        let Some(at) = text.rfind(COPY_CODE_LABEL) else {
+            continue;
+        };
+        let Some(code) = codes.next() else {
+            return;
+        };
+        let start_col = UnicodeWidthStr::width(&text[..at]) as u16;
+        hits.push(CodeCopyHit {
+            line_index: offset,
+            start_col,
+            end_col: start_col + COPY_CODE_LABEL.len() as u16,
+            code,
+        });
+    }
+}
+
+fn line_plain_text(line: &Line<'_>) -> String {
+    line.spans
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect()
+}
+
 fn append_non_message_lines(
     lines: &mut Vec<Line<'static>>,
     line_to_message: &mut Vec<Option<String>>,
@@ -1433,6 +1852,30 @@ fn assistant_footer(
         ));
     }
 
+    let tokens = &message.tokens;
+    if tokens.cache_read + tokens.cache_write > 0 {
+        spans.push(Span::styled(" · ", Style::default().fg(theme.text_muted)));
+        spans.push(Span::styled(
+            format!(
+                "cache {} read / {} write",
+                format_number(tokens.cache_read),
+                format_number(tokens.cache_write)
+            ),
+            Style::default().fg(theme.text_muted),
+        ));
+    }
+    if let Some(reason) = message
+        .metadata
+        .as_ref()
+        .and_then(|metadata| metadata.get("cache_bust"))
+        .and_then(|value| value.as_str())
+    {
+        spans.push(Span::styled(
+            format!(" · cache miss: {}", reason),
+            Style::default().fg(theme.warning),
+        ));
+    }
+
     if is_interrupted {
         spans.push(Span::styled(
             " · interrupted",
@@ -1533,8 +1976,11 @@ fn format_number(value: u64) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{collect_assistant_tool_results, is_tool_result_carrier};
+    use super::{collect_assistant_tool_results, collect_code_copy_hits, is_tool_result_carrier};
+    use crate::components::markdown::code_block_contents;
+    use crate::components::MarkdownRenderer;
     use crate::context::{Message, MessagePart, MessageRole, TokenUsage};
+    use crate::theme::Theme;
     use chrono::Utc;
 
     fn message(id: &str, role: MessageRole, parts: Vec<MessagePart>) -> Message {
@@ -1622,4 +2068,17 @@ mod tests {
         assert!(first_results.contains_key("call-1"));
         assert!(!first_results.contains_key("call-2"));
     }
+
+    #[test]
+    fn code_copy_labels_pair_with_block_contents() {
+        let text = "intro\n\n```rust\nfn a() {}\n```\n\nmiddle\n\n```\nls -la\n```\n";
+        let lines = MarkdownRenderer::new(Theme::dark()).to_lines(text);
+        let mut hits = Vec::new();
+        collect_code_copy_hits(&lines, 0, code_block_contents(text), &mut hits);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].code, "fn a() {}\n");
+        assert_eq!(hits[1].code, "ls -la\n");
+        assert!(hits[0].line_index < hits[1].line_index);
+        assert!(hits[0].end_col > hits[0].start_col);
+    }
 }
@@ -0,0 +1,214 @@
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+use serde_json::Value;
+use std::collections::HashMap;
+use unicode_width::UnicodeWidthStr;
+
+use crate::context::{Message, MessagePart, MessageRole};
+use crate::theme::Theme;
+use crate::ui::{Graphics, ImageAccess};
+
+/// Tallest an inline image may get, in rows.
+const INLINE_IMAGE_MAX_ROWS: u16 = 12;
+/// Columns kept free to the right of an inline image.
+const INLINE_IMAGE_RIGHT_MARGIN: usize = 2;
+
+/// An image attachment laid out inside a message: a caption, then blank rows
+/// the terminal draws the picture over once the frame is on screen.
+pub struct ImageBlock {
+    pub url: String,
+    pub caption: Line<'static>,
+    pub rows: Vec<Line<'static>>,
+    pub inline: Option<InlineImage>,
+}
+
+/// Position of the picture relative to the first blank row of its block.
+pub struct InlineImage {
+    pub id: u32,
+    pub x_offset: u16,
+    pub cols: u16,
+    pub rows: u16,
+}
+
+/// An image part of a message, before it is loaded.
+pub struct ImagePart<'a> {
+    pub url: &'a str,
+    pub filename: Option<&'a str>,
+    pub access: ImageAccess<'a>,
+}
+
+pub fn render_image_part(
+    graphics: &mut Graphics,
+    part: ImagePart,
+    marker: Span<'static>,
+    continuation: Span<'static>,
+    theme: &Theme,
+    content_width: usize,
+) -> ImageBlock {
+    let ImagePart {
+        url,
+        filename,
+        access,
+    } = part;
+    let loaded = graphics.load(url, access);
+    let detail = match &loaded {
+        Some((_, image)) => image.describe(),
+        None => "unavailable".to_string(),
+    };
+    let caption = Line::from(vec![
+        marker,
+        Span::styled(
+            "[image] ",
+            Style::default().fg(theme.info).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(image_label(url, filename), Style::default().fg(theme.text)),
+        Span::styled(
+            format!(" ({})", detail),
+            Style::default().fg(theme.text_muted),
+        ),
+    ]);
+
+    let x_offset = UnicodeWidthStr::width(continuation.content.as_ref());
+    let max_cols = content_width.saturating_sub(x_offset + INLINE_IMAGE_RIGHT_MARGIN);
+    let inline = loaded.and_then(|(id, image)| {
+        if max_cols == 0 || !graphics.can_render(&image) {
+            return None;
+        }
+        let max_cols = u16::try_from(max_cols).unwrap_or(u16::MAX);
+        let (cols, rows) = graphics.fit(&image, max_cols, INLINE_IMAGE_MAX_ROWS);
+        Some(InlineImage {
+            id,
+            x_offset: x_offset as u16,
+            cols,
+            rows,
+        })
+    });
+    let rows = inline
+        .as_ref()
+        .map(|inline| {
+            (0..inline.rows)
+                .map(|_| Line::from(vec![continuation.clone()]))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    ImageBlock {
+        url: url.to_string(),
+        caption,
+        rows,
+        inline,
+    }
+}
+
+/// Name shown for an image: its filename, else the last path segment of a
+/// file URL. Data URLs are never printed.
+pub fn image_label(url: &str, filename: Option<&str>) -> String {
+    if let Some(name) = filename.map(str::trim).filter(|name| !name.is_empty()) {
+        return name.to_string();
+    }
+    if url.starts_with("data:") {
+        return "pasted image".to_string();
+    }
+    url.rsplit(['/', '\\'])
+        .find(|segment| !segment.is_empty())
+        .unwrap_or(url)
+        .to_string()
+}
+
+/// An image found in a session, in display order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SessionImage {
+    pub url: String,
+    pub filename: Option<String>,
+    /// Attached by the user rather than produced by the agent or a tool.
+    pub attachment: bool,
+}
+
+/// Image attachments a tool returned (e.g. `read` on a PNG), carried under
+/// `attachments` in the result metadata.
+pub fn tool_result_images(metadata: Option<&HashMap<String, Value>>) -> Vec<SessionImage> {
+    let Some(attachments) = metadata
+        .and_then(|metadata| metadata.get("attachments"))
+        .and_then(Value::as_array)
+    else {
+        return Vec::new();
+    };
+    attachments
+        .iter()
+        .filter(|attachment| {
+            attachment
+                .get("mime")
+                .and_then(Value::as_str)
+                .is_some_and(|mime| mime.starts_with("image/"))
+        })
+        .filter_map(|attachment| {
+            Some(SessionImage {
+                url: attachment.get("url")?.as_str()?.to_string(),
+                filename: attachment
+                    .get("filename")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                attachment: false,
+            })
+        })
+        .collect()
+}
+
+pub fn collect_session_images(messages: &[Message]) -> Vec<SessionImage> {
+    let mut images = Vec::new();
+    for message in messages {
+        for part in &message.parts {
+            match part {
+                MessagePart::Image { url, filename } => images.push(SessionImage {
+                    url: url.clone(),
+                    filename: filename.clone(),
+                    attachment: message.role == MessageRole::User,
+                }),
+                MessagePart::ToolResult { metadata, .. } => {
+                    images.extend(tool_result_images(metadata.as_ref()))
+                }
+                _ => {}
+            }
+        }
+    }
+    images
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_never_print_data_urls() {
+        assert_eq!(
+            image_label("data:image/png;base64,AAAA", None),
+            "pasted image"
+        );
+        assert_eq!(
+            image_label("data:image/png;base64,AAAA", Some("shot.png")),
+            "shot.png"
+        );
+        assert_eq!(image_label("file:///tmp/a/b.png", None), "b.png");
+    }
+
+    #[test]
+    fn finds_image_attachments_in_tool_metadata() {
+        let metadata: HashMap<String, Value> = serde_json::from_value(serde_json::json!({
+            "attachments": [
+                {"type": "file", "mime": "application/pdf", "url": "data:application/pdf;base64,AA"},
+                {"type": "file", "mime": "image/png", "url": "data:image/png;base64,AA", "filename": "a.png"}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            tool_result_images(Some(&metadata)),
+            vec![SessionImage {
+                url: "data:image/png;base64,AA".to_string(),
+                filename: Some("a.png".to_string()),
+                attachment: false,
+            }]
+        );
+    }
+}
@@ -103,13 +103,8 @@ pub fn render_user_message(
                         Span::styled(path.clone(), Style::default().fg(theme.text)),
                     ]));
                 }
-                MessagePart::Image { url } => {
-                    lines.push(Line::from(vec![
-                        Span::styled(border_char, border_style),
-                        Span::styled("[image] ", Style::default().fg(theme.info)),
-                        Span::styled(url.clone(), Style::default().fg(theme.text_muted)),
-                    ]));
-                }
+                // The caption and picture are laid out by the session view.
+                MessagePart::Image { .. } => {}
                 _ => {}
             }
         }
@@ -0,0 +1,328 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::Span,
+    widgets::{Block, Borders},
+    Frame,
+};
+
+use super::session::SessionView;
+use crate::context::SessionStatus;
+use crate::theme::Theme;
+
+/// Watch panes shown next to the focused session.
+pub const MAX_SPLIT_PANES: usize = 3;
+/// Views kept after leaving a session so switching back restores scroll.
+const MAX_PARKED_VIEWS: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplitDirection {
+    /// Panes side by side.
+    Horizontal,
+    /// Panes stacked top to bottom.
+    Vertical,
+}
+
+/// Extra sessions rendered alongside the focused one. The focused session
+/// keeps the prompt; the others are read-only watch panes, each with its own
+/// `SessionView` and therefore its own scroll state.
+pub struct SessionSplit {
+    direction: SplitDirection,
+    panes: Vec<SessionView>,
+    parked: Vec<SessionView>,
+    pane_areas: Vec<Rect>,
+}
+
+impl SessionSplit {
+    pub fn new() -> Self {
+        Self {
+            direction: SplitDirection::Horizontal,
+            panes: Vec::new(),
+            parked: Vec::new(),
+            pane_areas: Vec::new(),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.panes.is_empty()
+    }
+
+    pub fn direction(&self) -> SplitDirection {
+        self.direction
+    }
+
+    pub fn toggle_direction(&mut self) {
+        self.direction = match self.direction {
+            SplitDirection::Horizontal => SplitDirection::Vertical,
+            SplitDirection::Vertical => SplitDirection::Horizontal,
+        };
+    }
+
+    pub fn contains(&self, session_id: &str) -> bool {
+        self.panes
+            .iter()
+            .any(|view| view.session_id() == session_id)
+    }
+
+    pub fn pane_session_ids(&self) -> Vec<String> {
+        self.panes
+            .iter()
+            .map(|view| view.session_id().to_string())
+            .collect()
+    }
+
+    /// Adds a watch pane; returns false when the split is already full.
+    pub fn open(&mut self, view: SessionView) -> bool {
+        if self.contains(view.session_id()) {
+            return true;
+        }
+        if self.panes.len() >= MAX_SPLIT_PANES {
+            return false;
+        }
+        self.panes.push(view);
+        true
+    }
+
+    /// Removes a watch pane, parking its view.
+    pub fn close(&mut self, session_id: &str) -> bool {
+        let Some(index) = self.pane_index(session_id) else {
+            return false;
+        };
+        let view = self.panes.remove(index);
+        self.park(view);
+        true
+    }
+
+    pub fn close_all(&mut self) {
+        for view in std::mem::take(&mut self.panes) {
+            self.park(view);
+        }
+        self.pane_areas.clear();
+    }
+
+    /// Hands over the view for `session_id` from the panes or the parked
+    /// views. When it was a pane, `previous` takes its slot so focusing a
+    /// pane swaps it with the focused session.
+    pub fn take_for_focus(
+        &mut self,
+        session_id: &str,
+        previous: Option<SessionView>,
+    ) -> Option<SessionView> {
+        if let Some(index) = self.pane_index(session_id) {
+            return Some(match previous {
+                Some(previous) => std::mem::replace(&mut self.panes[index], previous),
+                None => self.panes.remove(index),
+            });
+        }
+        if let Some(previous) = previous {
+            self.park(previous);
+        }
+        let index = self
+            .parked
+            .iter()
+            .position(|view| view.session_id() == session_id)?;
+        Some(self.parked.remove(index))
+    }
+
+    pub fn park(&mut self, view: SessionView) {
+        self.parked
+            .retain(|parked| parked.session_id() != view.session_id());
+        self.parked.push(view);
+        if self.parked.len() > MAX_PARKED_VIEWS {
+            self.parked.remove(0);
+        }
+    }
+
+    /// Drops every view of a session that no longer exists.
+    pub fn forget(&mut self, session_id: &str) {
+        self.panes.retain(|view| view.session_id() != session_id);
+        self.parked.retain(|view| view.session_id() != session_id);
+    }
+
+    /// Splits `area` into the focused session's area followed by one area
+    /// per pane.
+    pub fn layout(&self, area: Rect) -> Vec<Rect> {
+        let count = self.panes.len() as u32 + 1;
+        let direction = match self.direction {
+            SplitDirection::Horizontal => Direction::Horizontal,
+            SplitDirection::Vertical => Direction::Vertical,
+        };
+        Layout::default()
+            .direction(direction)
+            .constraints(vec![Constraint::Ratio(1, count); count as usize])
+            .split(area)
+            .to_vec()
+    }
+
+    pub fn render_panes(
+        &mut self,
+        frame: &mut Frame,
+        areas: &[Rect],
+        theme: &Theme,
+        status: impl Fn(&str) -> (String, SessionStatus),
+    ) {
+        self.pane_areas = areas.to_vec();
+        for (view, area) in self.panes.iter_mut().zip(areas) {
+            let (title, status) = status(view.session_id());
+            let marker = match status {
+                SessionStatus::Idle => "",
+                SessionStatus::Running => " ●",
+                SessionStatus::Retrying { .. } => " ↻",
+            };
+            let block = Block::default()
+                .title(Span::styled(
+                    format!(" {}{} ", title, marker),
+                    Style::default()
+                        .fg(theme.text_muted)
+                        .add_modifier(Modifier::BOLD),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border))
+                .style(Style::default().bg(theme.background));
+            let inner = block.inner(*area);
+            frame.render_widget(block, *area);
+            view.render_watch(frame, inner);
+        }
+    }
+
+    /// Forgets pane positions while the split is not on screen.
+    pub fn clear_pane_areas(&mut self) {
+        self.pane_areas.clear();
+    }
+
+    /// The pane under the given screen position, as of the last render.
+    pub fn pane_at(&mut self, col: u16, row: u16) -> Option<&mut SessionView> {
+        let index = self.pane_areas.iter().position(|area| {
+            col >= area.x
+                && col < area.x.saturating_add(area.width)
+                && row >= area.y
+                && row < area.y.saturating_add(area.height)
+        })?;
+        self.panes.get_mut(index)
+    }
+
+    fn pane_index(&self, session_id: &str) -> Option<usize> {
+        self.panes
+            .iter()
+            .position(|view| view.session_id() == session_id)
+    }
+}
+
+impl Default for SessionSplit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::AppContext;
+    use std::sync::Arc;
+
+    fn view(context: &Arc<AppContext>, session_id: &str) -> SessionView {
+        SessionView::new(context.clone(), session_id.to_string())
+    }
+
+    fn parked_ids(split: &SessionSplit) -> Vec<&str> {
+        split.parked.iter().map(|view| view.session_id()).collect()
+    }
+
+    #[test]
+    fn layout_gives_the_focused_session_and_each_pane_an_equal_share() {
+        let context = Arc::new(AppContext::new());
+        let mut split = SessionSplit::new();
+        let area = Rect::new(0, 0, 120, 40);
+        assert_eq!(split.layout(area), vec![area]);
+
+        assert!(split.open(view(&context, "a")));
+        assert!(split.open(view(&context, "b")));
+        assert_eq!(
+            split.layout(area),
+            vec![
+                Rect::new(0, 0, 40, 40),
+                Rect::new(40, 0, 40, 40),
+                Rect::new(80, 0, 40, 40),
+            ]
+        );
+
+        split.toggle_direction();
+        let areas = split.layout(Rect::new(0, 0, 120, 30));
+        assert_eq!(
+            areas,
+            vec![
+                Rect::new(0, 0, 120, 10),
+                Rect::new(0, 10, 120, 10),
+                Rect::new(0, 20, 120, 10),
+            ]
+        );
+    }
+
+    #[test]
+    fn open_refuses_more_than_the_pane_limit() {
+        let context = Arc::new(AppContext::new());
+        let mut split = SessionSplit::new();
+        for index in 0..MAX_SPLIT_PANES {
+            assert!(split.open(view(&context, &format!("s{}", index))));
+        }
+        assert!(split.open(view(&context, "s0")), "reopening is a no-op");
+        assert!(!split.open(view(&context, "extra")));
+        assert_eq!(split.pane_session_ids().len(), MAX_SPLIT_PANES);
+    }
+
+    #[test]
+    fn focusing_a_pane_swaps_it_with_the_focused_session() {
+        let context = Arc::new(AppContext::new());
+        let mut split = SessionSplit::new();
+        split.open(view(&context, "a"));
+        split.open(view(&context, "b"));
+
+        let focused = split
+            .take_for_focus("a", Some(view(&context, "main")))
+            .unwrap();
+        assert_eq!(focused.session_id(), "a");
+        assert_eq!(split.pane_session_ids(), vec!["main", "b"]);
+        assert!(parked_ids(&split).is_empty());
+
+        let focused = split.take_for_focus("b", None).unwrap();
+        assert_eq!(focused.session_id(), "b");
+        assert_eq!(split.pane_session_ids(), vec!["main"]);
+    }
+
+    #[test]
+    fn leaving_a_session_parks_its_view_for_later() {
+        let context = Arc::new(AppContext::new());
+        let mut split = SessionSplit::new();
+
+        assert!(split
+            .take_for_focus("b", Some(view(&context, "a")))
+            .is_none());
+        assert_eq!(parked_ids(&split), vec!["a"]);
+
+        let restored = split.take_for_focus("a", Some(view(&context, "b")));
+        assert_eq!(restored.unwrap().session_id(), "a");
+        assert_eq!(parked_ids(&split), vec!["b"]);
+
+        split.open(view(&context, "c"));
+        assert!(split.close("c"));
+        assert!(!split.is_active());
+        assert_eq!(parked_ids(&split), vec!["b", "c"]);
+
+        split.forget("b");
+        assert_eq!(parked_ids(&split), vec!["c"]);
+    }
+
+    #[test]
+    fn parking_keeps_only_the_most_recent_views() {
+        let context = Arc::new(AppContext::new());
+        let mut split = SessionSplit::new();
+        for index in 0..=MAX_PARKED_VIEWS {
+            split.park(view(&context, &format!("s{}", index)));
+        }
+        split.park(view(&context, "s1"));
+        let parked = parked_ids(&split);
+        assert_eq!(parked.len(), MAX_PARKED_VIEWS);
+        assert!(!parked.contains(&"s0"));
+        assert_eq!(parked.last(), Some(&"s1"));
+    }
+}
@@ -52,9 +52,9 @@ pub fn tool_glyph(name: &str) -> &'static str {
         "glob" | "grep" | "search" | "ripgrep" => "✱",
         "list" | "ls" | "listDir" | "list_dir" => "→",
         "webfetch" | "web_fetch" | "fetch" => "%",
-        "codesearch" | "code_search" => "◇",
+        "codesearch" | "code_search" | "semantic_search" => "◇",
         "websearch" | "web_search" => "◈",
-        "task" | "subagent" => "#",
+        "task" | "subagent" | "task_status" | "task_result" => "#",
         "apply_patch" | "applyPatch" => "%",
         "batch" => "⫘",
         "question" => "?",
@@ -1615,7 +1615,7 @@ fn tool_argument_preview(normalized_name: &str, arguments: &str) -> Option<Strin
 
     if matches!(
         normalized_name,
-        "codesearch" | "code_search" | "websearch" | "web_search"
+        "codesearch" | "code_search" | "semantic_search" | "websearch" | "web_search"
     ) {
         if let Some(query) = parsed
             .as_ref()
@@ -1625,6 +1625,15 @@ fn tool_argument_preview(normalized_name: &str, arguments: &str) -> Option<Strin
         }
     }
 
+    if matches!(normalized_name, "task_status" | "task_result") {
+        if let Some(task_id) = parsed
+            .as_ref()
+            .and_then(|value| extract_string_key(value, &["task_id", "taskId"]))
+        {
+            return Some(task_id);
+        }
+    }
+
     if normalized_name == "task" {
         let summary = parse_task_argument_summary(arguments);
         let kind = summary
@@ -0,0 +1,751 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+use serde_json::Value;
+
+use crate::theme::Theme;
+
+const VALUE_PREVIEW_CHARS: usize = 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettingKind {
+    Text,
+    /// `provider/model`.
+    Model,
+    Bool,
+    Number,
+    /// A whole object edited as JSON (agents, MCP servers, ...).
+    Json,
+}
+
+#[derive(Clone, Debug)]
+pub struct SettingField {
+    /// Dotted path into the config, e.g. `compaction.auto`.
+    pub path: &'static str,
+    pub label: &'static str,
+    pub kind: SettingKind,
+    pub help: &'static str,
+    /// Value the server uses when the key is not set.
+    pub default: Option<Value>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SettingSection {
+    pub title: &'static str,
+    pub fields: Vec<SettingField>,
+}
+
+fn field(
+    path: &'static str,
+    label: &'static str,
+    kind: SettingKind,
+    help: &'static str,
+) -> SettingField {
+    SettingField {
+        path,
+        label,
+        kind,
+        help,
+        default: None,
+    }
+}
+
+fn toggle(
+    path: &'static str,
+    label: &'static str,
+    default: bool,
+    help: &'static str,
+) -> SettingField {
+    SettingField {
+        default: Some(Value::Bool(default)),
+        ..field(path, label, SettingKind::Bool, help)
+    }
+}
+
+pub fn setting_sections() -> Vec<SettingSection> {
+    use SettingKind::*;
+    vec![
+        SettingSection {
+            title: "Model",
+            fields: vec![
+                field("model", "Model", Model, "Default model as provider/model"),
+                field(
+                    "small_model",
+                    "Small model",
+                    Model,
+                    "Model for titles and summaries, as provider/model",
+                ),
+                field(
+                    "default_agent",
+                    "Default agent",
+                    Text,
+                    "Agent selected for new sessions",
+                ),
+            ],
+        },
+        SettingSection {
+            title: "Agents",
+            fields: vec![field(
+                "agent",
+                "Agents",
+                Json,
+                "Agent definitions keyed by name, e.g. {\"build\": {\"model\": \"...\"}}",
+            )],
+        },
+        SettingSection {
+            title: "Permissions",
+            fields: vec![field(
+                "permission",
+                "Permissions",
+                Json,
+                "Per-tool permission rules, e.g. {\"bash\": \"ask\"}",
+            )],
+        },
+        SettingSection {
+            title: "Compaction",
+            fields: vec![
+                toggle(
+                    "compaction.auto",
+                    "Auto compact",
+                    true,
+                    "Summarize the session when the context is full",
+                ),
+                toggle(
+                    "compaction.prune",
+                    "Prune tool output",
+                    true,
+                    "Drop old tool output before compacting",
+                ),
+                field(
+                    "compaction.reserved",
+                    "Reserved tokens",
+                    Number,
+                    "Tokens kept free for the response",
+                ),
+            ],
+        },
+        SettingSection {
+            title: "Keybinds",
+            fields: vec![
+                field("keybinds.leader", "Leader key", Text, "e.g. ctrl+x"),
+                field(
+                    "keybinds.vim_normal_mode",
+                    "Vim normal mode",
+                    Text,
+                    "Leaves insert mode in vim editing, default ctrl+g",
+                ),
+                field(
+                    "keybinds",
+                    "All keybinds",
+                    Json,
+                    "Keybind overrides keyed by action name",
+                ),
+            ],
+        },
+        SettingSection {
+            title: "Interface",
+            fields: vec![toggle(
+                "tui.vim",
+                "Vim editing",
+                false,
+                "Modal vim-style editing in the prompt",
+            )],
+        },
+        SettingSection {
+            title: "Theme",
+            fields: vec![field("theme", "Theme", Text, "Theme name, see /themes")],
+        },
+        SettingSection {
+            title: "MCP",
+            fields: vec![field(
+                "mcp",
+                "MCP servers",
+                Json,
+                "MCP servers keyed by name",
+            )],
+        },
+        SettingSection {
+            title: "LSP",
+            fields: vec![field(
+                "lsp",
+                "Language servers",
+                Json,
+                "Language server overrides keyed by name",
+            )],
+        },
+        SettingSection {
+            title: "Formatter",
+            fields: vec![field(
+                "formatter",
+                "Formatters",
+                Json,
+                "Formatter overrides keyed by name",
+            )],
+        },
+    ]
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettingsScope {
+    Project,
+    Global,
+}
+
+impl SettingsScope {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Project => "project",
+            Self::Global => "global",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Focus {
+    Sections,
+    Fields,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SettingsAction {
+    None,
+    Close,
+    Save {
+        patch: Value,
+        scope: SettingsScope,
+        /// Top-level keys the patch replaces instead of merging into.
+        replace: Vec<String>,
+    },
+}
+
+struct Editor {
+    input: String,
+    error: Option<String>,
+}
+
+pub struct SettingsView {
+    sections: Vec<SettingSection>,
+    section: usize,
+    field: usize,
+    focus: Focus,
+    scope: SettingsScope,
+    /// Project directory that `project` scope writes to.
+    directory: Option<String>,
+    config: Value,
+    editor: Option<Editor>,
+    status: Option<(String, bool)>,
+}
+
+impl SettingsView {
+    pub fn new() -> Self {
+        Self {
+            sections: setting_sections(),
+            section: 0,
+            field: 0,
+            focus: Focus::Sections,
+            scope: SettingsScope::Project,
+            directory: None,
+            config: Value::Object(Default::default()),
+            editor: None,
+            status: None,
+        }
+    }
+
+    pub fn set_config(&mut self, config: Value) {
+        self.config = config;
+    }
+
+    pub fn set_directory(&mut self, directory: Option<String>) {
+        self.directory = directory;
+    }
+
+    pub fn directory(&self) -> Option<&str> {
+        self.directory.as_deref()
+    }
+
+    pub fn save_succeeded(&mut self, config: Value) {
+        self.config = config;
+        self.editor = None;
+        self.status = Some((format!("Saved to {} config", self.scope.as_str()), false));
+    }
+
+    pub fn save_failed(&mut self, error: String) {
+        match &mut self.editor {
+            Some(editor) => editor.error = Some(error),
+            None => self.status = Some((error, true)),
+        }
+    }
+
+    pub fn is_editing(&self) -> bool {
+        self.editor.is_some()
+    }
+
+    fn current_field(&self) -> Option<&SettingField> {
+        self.sections.get(self.section)?.fields.get(self.field)
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> SettingsAction {
+        if let Some(editor) = &mut self.editor {
+            match key.code {
+                KeyCode::Esc => self.editor = None,
+                KeyCode::Backspace => {
+                    editor.input.pop();
+                    editor.error = None;
+                }
+                KeyCode::Enter => return self.submit_editor(),
+                KeyCode::Char(c)
+                    if !key.modifiers.contains(KeyModifiers::CONTROL)
+                        && !key.modifiers.contains(KeyModifiers::ALT) =>
+                {
+                    editor.input.push(c);
+                    editor.error = None;
+                }
+                _ => {}
+            }
+            return SettingsAction::None;
+        }
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return SettingsAction::Close,
+            KeyCode::Char('s') => {
+                self.scope = match self.scope {
+                    SettingsScope::Project => SettingsScope::Global,
+                    SettingsScope::Global => SettingsScope::Project,
+                };
+                self.status = None;
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.move_by(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.move_by(1),
+            KeyCode::Left | KeyCode::Char('h') => self.focus = Focus::Sections,
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Tab => {
+                self.focus = Focus::Fields;
+                self.field = self.field.min(self.field_count().saturating_sub(1));
+            }
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                if self.focus == Focus::Sections {
+                    self.focus = Focus::Fields;
+                    self.field = 0;
+                } else {
+                    return self.activate_field();
+                }
+            }
+            _ => {}
+        }
+        SettingsAction::None
+    }
+
+    fn field_count(&self) -> usize {
+        self.sections
+            .get(self.section)
+            .map(|s| s.fields.len())
+            .unwrap_or(0)
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        let (index, len) = match self.focus {
+            Focus::Sections => (&mut self.section, self.sections.len()),
+            Focus::Fields => {
+                let len = self.field_count();
+                (&mut self.field, len)
+            }
+        };
+        if len == 0 {
+            return;
+        }
+        *index = (*index as isize + delta).rem_euclid(len as isize) as usize;
+        if self.focus == Focus::Sections {
+            self.field = 0;
+        }
+        self.status = None;
+    }
+
+    fn activate_field(&mut self) -> SettingsAction {
+        let Some(field) = self.current_field().cloned() else {
+            return SettingsAction::None;
+        };
+        let current = lookup(&self.config, field.path);
+        if field.kind == SettingKind::Bool {
+            let enabled = !current
+                .or(field.default.as_ref())
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            return SettingsAction::Save {
+                patch: patch_for(field.path, Value::Bool(enabled)),
+                scope: self.scope,
+                replace: Vec::new(),
+            };
+        }
+        let input = match current {
+            Some(Value::String(s)) => s.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(other) => other.to_string(),
+        };
+        self.editor = Some(Editor { input, error: None });
+        SettingsAction::None
+    }
+
+    fn submit_editor(&mut self) -> SettingsAction {
+        let (Some(field), Some(editor)) = (self.current_field().cloned(), self.editor.as_mut())
+        else {
+            return SettingsAction::None;
+        };
+        match parse_input(field.kind, &editor.input) {
+            Ok(value) => SettingsAction::Save {
+                patch: patch_for(field.path, value),
+                scope: self.scope,
+                // A JSON object is the whole new value: entries deleted in
+                // the editor must not survive a merge.
+                replace: if field.kind == SettingKind::Json && !field.path.contains('.') {
+                    vec![field.path.to_string()]
+                } else {
+                    Vec::new()
+                },
+            },
+            Err(error) => {
+                editor.error = Some(error);
+                SettingsAction::None
+            }
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let block = Block::default()
+            .title(Span::styled(
+                " Settings ",
+                Style::default()
+                    .fg(theme.primary)
+                    .add_modifier(Modifier::BOLD),
+            ))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border))
+            .style(Style::default().bg(theme.background));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(3),
+                Constraint::Length(4),
+            ])
+            .split(inner);
+
+        let scope_style = |scope: SettingsScope| {
+            if scope == self.scope {
+                Style::default()
+                    .fg(theme.primary)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(theme.text_muted)
+            }
+        };
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                Span::styled(" Save to: ", Style::default().fg(theme.text_muted)),
+                Span::styled(
+                    match &self.directory {
+                        Some(directory) => format!("project ({})", directory),
+                        None => "project".to_string(),
+                    },
+                    scope_style(SettingsScope::Project),
+                ),
+                Span::raw(" / "),
+                Span::styled("global", scope_style(SettingsScope::Global)),
+                Span::styled(
+                    "   ↑↓ move  ←→ section/field  Enter edit  s scope  Esc back",
+                    Style::default().fg(theme.text_muted),
+                ),
+            ])),
+            rows[0],
+        );
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(18), Constraint::Min(20)])
+            .split(rows[2]);
+
+        let section_lines: Vec<Line> = self
+            .sections
+            .iter()
+            .enumerate()
+            .map(|(i, section)| {
+                let selected = i == self.section;
+                let style = if selected && self.focus == Focus::Sections {
+                    Style::default()
+                        .fg(theme.background)
+                        .bg(theme.primary)
+                        .add_modifier(Modifier::BOLD)
+                } else if selected {
+                    Style::default().fg(theme.primary)
+                } else {
+                    Style::default().fg(theme.text)
+                };
+                Line::from(Span::styled(format!(" {} ", section.title), style))
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(section_lines), columns[0]);
+
+        let mut field_lines = Vec::new();
+        if let Some(section) = self.sections.get(self.section) {
+            for (i, field) in section.fields.iter().enumerate() {
+                let selected = i == self.field && self.focus == Focus::Fields;
+                let label_style = if selected {
+                    Style::default()
+                        .fg(theme.background)
+                        .bg(theme.primary)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.text)
+                };
+                let value = display_value(field, lookup(&self.config, field.path));
+                field_lines.push(Line::from(vec![
+                    Span::styled(format!(" {:<18}", field.label), label_style),
+                    Span::raw(" "),
+                    Span::styled(value, Style::default().fg(theme.text_muted)),
+                ]));
+            }
+        }
+        frame.render_widget(Paragraph::new(field_lines), columns[1]);
+
+        let mut footer = Vec::new();
+        if let Some(field) = self.current_field().filter(|_| self.focus == Focus::Fields) {
+            footer.push(Line::from(Span::styled(
+                field.help,
+                Style::default().fg(theme.text_muted),
+            )));
+        }
+        if let Some(editor) = &self.editor {
+            footer.push(Line::from(vec![
+                Span::styled("> ", Style::default().fg(theme.primary)),
+                Span::styled(editor.input.as_str(), Style::default().fg(theme.text)),
+                Span::styled("▏", Style::default().fg(theme.primary)),
+            ]));
+            if let Some(error) = &editor.error {
+                footer.push(Line::from(Span::styled(
+                    error.as_str(),
+                    Style::default().fg(theme.error),
+                )));
+            }
+        } else if let Some((message, is_error)) = &self.status {
+            let color = if *is_error {
+                theme.error
+            } else {
+                theme.success
+            };
+            footer.push(Line::from(Span::styled(
+                message.as_str(),
+                Style::default().fg(color),
+            )));
+        }
+        frame.render_widget(Paragraph::new(footer).wrap(Wrap { trim: false }), rows[3]);
+    }
+}
+
+impl Default for SettingsView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn lookup<'a>(config: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .try_fold(config, |value, key| value.as_object()?.get(key))
+}
+
+/// Builds the nested object that sets `path` to `value`.
+fn patch_for(path: &str, value: Value) -> Value {
+    path.rsplit('.').fold(value, |inner, key| {
+        let mut object = serde_json::Map::new();
+        object.insert(key.to_string(), inner);
+        Value::Object(object)
+    })
+}
+
+fn parse_input(kind: SettingKind, input: &str) -> Result<Value, String> {
+    let input = input.trim();
+    match kind {
+        SettingKind::Text => {
+            if input.is_empty() {
+                return Err("Value must not be empty".to_string());
+            }
+            Ok(Value::String(input.to_string()))
+        }
+        SettingKind::Model => match input.split_once('/') {
+            Some((provider, model)) if !provider.is_empty() && !model.is_empty() => {
+                Ok(Value::String(input.to_string()))
+            }
+            _ => Err("Expected provider/model, e.g. anthropic/claude-sonnet-4".to_string()),
+        },
+        SettingKind::Number => input
+            .parse::<u64>()
+            .map(Value::from)
+            .map_err(|_| "Expected a non-negative whole number".to_string()),
+        SettingKind::Json => match serde_json::from_str::<Value>(input) {
+            Ok(value @ Value::Object(_)) => Ok(value),
+            Ok(_) => Err("Expected a JSON object".to_string()),
+            Err(e) => Err(format!("Invalid JSON: {}", e)),
+        },
+        SettingKind::Bool => match input {
+            "true" | "on" | "yes" => Ok(Value::Bool(true)),
+            "false" | "off" | "no" => Ok(Value::Bool(false)),
+            _ => Err("Expected true or false".to_string()),
+        },
+    }
+}
+
+fn display_value(field: &SettingField, value: Option<&Value>) -> String {
+    let text = match (field.kind, value) {
+        (SettingKind::Bool, None | Some(Value::Null)) => match &field.default {
+            Some(Value::Bool(true)) => return "on (default)".to_string(),
+            Some(Value::Bool(false)) => return "off (default)".to_string(),
+            _ => return "(not set)".to_string(),
+        },
+        (_, None | Some(Value::Null)) => return "(not set)".to_string(),
+        (SettingKind::Bool, Some(Value::Bool(true))) => "on".to_string(),
+        (SettingKind::Bool, Some(Value::Bool(false))) => "off".to_string(),
+        (_, Some(Value::String(s))) => s.clone(),
+        (_, Some(other)) => other.to_string(),
+    };
+    if text.chars().count() > VALUE_PREVIEW_CHARS {
+        let truncated: String = text.chars().take(VALUE_PREVIEW_CHARS - 1).collect();
+        format!("{}…", truncated)
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn type_text(view: &mut SettingsView, text: &str) {
+        for c in text.chars() {
+            view.handle_key(key(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn patch_nests_dotted_paths() {
+        assert_eq!(
+            patch_for("compaction.reserved", Value::from(2048)),
+            serde_json::json!({ "compaction": { "reserved": 2048 } })
+        );
+        assert_eq!(
+            patch_for("model", Value::from("a/b")),
+            serde_json::json!({ "model": "a/b" })
+        );
+    }
+
+    #[test]
+    fn inputs_are_validated_by_kind() {
+        assert!(parse_input(SettingKind::Model, "claude").is_err());
+        assert_eq!(
+            parse_input(SettingKind::Model, " openai/gpt-4o ").unwrap(),
+            Value::from("openai/gpt-4o")
+        );
+        assert!(parse_input(SettingKind::Number, "-1").is_err());
+        assert!(parse_input(SettingKind::Json, "[1]").is_err());
+        assert!(parse_input(SettingKind::Json, "{\"a\":").is_err());
+        assert!(parse_input(SettingKind::Text, "  ").is_err());
+    }
+
+    #[test]
+    fn editing_a_field_produces_a_scoped_patch() {
+        let mut view = SettingsView::new();
+        view.set_config(
+            serde_json::json!({ "model": "anthropic/claude", "compaction": { "auto": true } }),
+        );
+
+        // Model section, first field: replace the model.
+        view.handle_key(key(KeyCode::Enter));
+        view.handle_key(key(KeyCode::Enter));
+        assert!(view.is_editing());
+        for _ in 0.."anthropic/claude".len() {
+            view.handle_key(key(KeyCode::Backspace));
+        }
+        type_text(&mut view, "bad");
+        assert_eq!(view.handle_key(key(KeyCode::Enter)), SettingsAction::None);
+        assert!(view.editor.as_ref().unwrap().error.is_some());
+        type_text(&mut view, "/model");
+        assert_eq!(
+            view.handle_key(key(KeyCode::Enter)),
+            SettingsAction::Save {
+                patch: serde_json::json!({ "model": "bad/model" }),
+                scope: SettingsScope::Project,
+                replace: Vec::new(),
+            }
+        );
+
+        // Toggling a bool saves immediately; `s` switches to global scope.
+        view.save_succeeded(serde_json::json!({ "compaction": { "auto": true } }));
+        view.handle_key(key(KeyCode::Char('s')));
+        view.handle_key(key(KeyCode::Left));
+        for _ in 0..3 {
+            view.handle_key(key(KeyCode::Down));
+        }
+        view.handle_key(key(KeyCode::Enter));
+        assert_eq!(
+            view.handle_key(key(KeyCode::Enter)),
+            SettingsAction::Save {
+                patch: serde_json::json!({ "compaction": { "auto": false } }),
+                scope: SettingsScope::Global,
+                replace: Vec::new(),
+            }
+        );
+        assert_eq!(view.handle_key(key(KeyCode::Esc)), SettingsAction::Close);
+    }
+
+    #[test]
+    fn unset_toggles_start_from_the_effective_default() {
+        let mut view = SettingsView::new();
+        view.set_config(serde_json::json!({}));
+        // Compaction section, "Auto compact" defaults to on.
+        for _ in 0..3 {
+            view.handle_key(key(KeyCode::Down));
+        }
+        view.handle_key(key(KeyCode::Enter));
+        assert_eq!(
+            view.handle_key(key(KeyCode::Enter)),
+            SettingsAction::Save {
+                patch: serde_json::json!({ "compaction": { "auto": false } }),
+                scope: SettingsScope::Project,
+                replace: Vec::new(),
+            }
+        );
+        let auto = &view.sections[3].fields[0];
+        assert_eq!(display_value(auto, None), "on (default)");
+    }
+
+    #[test]
+    fn json_fields_replace_the_stored_object() {
+        let mut view = SettingsView::new();
+        view.set_config(serde_json::json!({ "agent": { "old": {}, "build": {} } }));
+        view.handle_key(key(KeyCode::Down));
+        view.handle_key(key(KeyCode::Enter));
+        view.handle_key(key(KeyCode::Enter));
+        let input = view.editor.as_ref().unwrap().input.clone();
+        for _ in 0..input.chars().count() {
+            view.handle_key(key(KeyCode::Backspace));
+        }
+        type_text(&mut view, "{\"build\":{}}");
+        assert_eq!(
+            view.handle_key(key(KeyCode::Enter)),
+            SettingsAction::Save {
+                patch: serde_json::json!({ "agent": { "build": {} } }),
+                scope: SettingsScope::Project,
+                replace: vec!["agent".to_string()],
+            }
+        );
+    }
+}
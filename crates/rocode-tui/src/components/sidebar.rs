@@ -297,6 +297,15 @@ impl Sidebar {
                     + m.tokens.cache_write
             })
             .sum::<u64>();
+        let (cache_read, cache_prompt) = messages
+            .iter()
+            .filter(|m| matches!(m.role, MessageRole::Assistant))
+            .fold((0u64, 0u64), |(read, prompt), m| {
+                (
+                    read + m.tokens.cache_read,
+                    prompt + m.tokens.input + m.tokens.cache_read + m.tokens.cache_write,
+                )
+            });
         let model_context_limit = {
             let providers = self.context.providers.read();
             let current_model = self.context.current_model.read();
@@ -325,33 +334,45 @@ impl Sidebar {
                 })
                 .unwrap_or(0)
         };
+        let mut context_lines = vec![
+            {
+                let mut spans = vec![
+                    Span::styled("Tokens ", Style::default().fg(theme.text_muted)),
+                    Span::styled(format_number(total_tokens), Style::default().fg(theme.text)),
+                ];
+                if model_context_limit > 0 && total_tokens > 0 {
+                    let used_pct =
+                        ((total_tokens as f64 / model_context_limit as f64) * 100.0).round() as u64;
+                    spans.push(Span::styled(
+                        format!("  {}%", used_pct),
+                        Style::default().fg(theme.text_muted),
+                    ));
+                }
+                Line::from(spans)
+            },
+            Line::from(vec![
+                Span::styled("Cost   ", Style::default().fg(theme.text_muted)),
+                Span::styled(
+                    format!("${:.2}", total_cost),
+                    Style::default().fg(theme.text),
+                ),
+            ]),
+        ];
+        if cache_prompt > 0 {
+            let hit_pct = ((cache_read as f64 / cache_prompt as f64) * 100.0).round() as u64;
+            context_lines.push(Line::from(vec![
+                Span::styled("Cache  ", Style::default().fg(theme.text_muted)),
+                Span::styled(format!("{}% hit", hit_pct), Style::default().fg(theme.text)),
+                Span::styled(
+                    format!("  {} read", format_number(cache_read)),
+                    Style::default().fg(theme.text_muted),
+                ),
+            ]));
+        }
         sections.push(SidebarSection {
             key: "context",
             title: "Context",
-            lines: vec![
-                {
-                    let mut spans = vec![
-                        Span::styled("Tokens ", Style::default().fg(theme.text_muted)),
-                        Span::styled(format_number(total_tokens), Style::default().fg(theme.text)),
-                    ];
-                    if model_context_limit > 0 && total_tokens > 0 {
-                        let used_pct = ((total_tokens as f64 / model_context_limit as f64) * 100.0)
-                            .round() as u64;
-                        spans.push(Span::styled(
-                            format!("  {}%", used_pct),
-                            Style::default().fg(theme.text_muted),
-                        ));
-                    }
-                    Line::from(spans)
-                },
-                Line::from(vec![
-                    Span::styled("Cost   ", Style::default().fg(theme.text_muted)),
-                    Span::styled(
-                        format!("${:.2}", total_cost),
-                        Style::default().fg(theme.text),
-                    ),
-                ]),
-            ],
+            lines: context_lines,
             summary: None,
             collapsible: false,
         });
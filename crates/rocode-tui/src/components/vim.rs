@@ -0,0 +1,1319 @@
+use std::collections::HashMap;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::ui::Clipboard;
+
+const MAX_UNDO_STEPS: usize = 100;
+const MOTION_KEYS: &str = "hljkwbeWBE0^$G;,";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VimMode {
+    Normal,
+    Insert,
+    Visual,
+    VisualLine,
+}
+
+impl VimMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            VimMode::Normal => "NORMAL",
+            VimMode::Insert => "INSERT",
+            VimMode::Visual => "VISUAL",
+            VimMode::VisualLine => "V-LINE",
+        }
+    }
+}
+
+/// What the prompt should do after a key went through the vim engine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VimOutcome {
+    /// The key was consumed.
+    Handled,
+    /// Not a vim key in the current mode; handle it as usual.
+    Ignored,
+    Submit,
+    HistoryPrevious,
+    HistoryNext,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operator {
+    Delete,
+    Change,
+    Yank,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Find {
+    Forward,
+    Backward,
+    TillForward,
+    TillBackward,
+}
+
+impl Find {
+    fn from_key(c: char) -> Option<Self> {
+        match c {
+            'f' => Some(Find::Forward),
+            'F' => Some(Find::Backward),
+            't' => Some(Find::TillForward),
+            'T' => Some(Find::TillBackward),
+            _ => None,
+        }
+    }
+
+    fn reversed(self) -> Self {
+        match self {
+            Find::Forward => Find::Backward,
+            Find::Backward => Find::Forward,
+            Find::TillForward => Find::TillBackward,
+            Find::TillBackward => Find::TillForward,
+        }
+    }
+}
+
+/// A key that needs a second key to complete.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Pending {
+    None,
+    Register,
+    G,
+    Find(Find),
+    Replace,
+    Object { inner: bool },
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Register {
+    text: String,
+    linewise: bool,
+}
+
+/// Target of a motion. Inclusive motions cover the character under the
+/// target (`e`, `f`); linewise motions cover whole lines (`j`, `gg`).
+#[derive(Clone, Copy, Debug)]
+struct Motion {
+    pos: usize,
+    inclusive: bool,
+    linewise: bool,
+}
+
+impl Motion {
+    fn charwise(pos: usize, inclusive: bool) -> Self {
+        Self {
+            pos,
+            inclusive,
+            linewise: false,
+        }
+    }
+}
+
+/// Modal editing state for the prompt. The prompt owns the text and cursor
+/// (a byte offset); in insert mode it edits them itself and the engine only
+/// takes over again once the normal-mode key is pressed.
+pub struct Vim {
+    mode: VimMode,
+    pending: Pending,
+    operator: Option<Operator>,
+    count: Option<usize>,
+    operator_count: Option<usize>,
+    register: Option<char>,
+    registers: HashMap<char, Register>,
+    undo: Vec<(String, usize)>,
+    redo: Vec<(String, usize)>,
+    anchor: usize,
+    last_find: Option<(Find, char)>,
+}
+
+impl Vim {
+    pub fn new() -> Self {
+        Self {
+            mode: VimMode::Insert,
+            pending: Pending::None,
+            operator: None,
+            count: None,
+            operator_count: None,
+            register: None,
+            registers: HashMap::new(),
+            undo: vec![(String::new(), 0)],
+            redo: Vec::new(),
+            anchor: 0,
+            last_find: None,
+        }
+    }
+
+    pub fn mode(&self) -> VimMode {
+        self.mode
+    }
+
+    /// Starts over for a fresh prompt. Registers survive.
+    pub fn reset(&mut self) {
+        self.clear_pending();
+        self.mode = VimMode::Insert;
+        self.undo = vec![(String::new(), 0)];
+        self.redo.clear();
+    }
+
+    pub fn enter_normal(&mut self, text: &str, cursor: &mut usize) {
+        self.clear_pending();
+        if self.mode == VimMode::Insert && *cursor > line_start(text, *cursor) {
+            *cursor = prev_char(text, *cursor);
+        }
+        self.mode = VimMode::Normal;
+        *cursor = clamp_normal(text, *cursor);
+    }
+
+    /// Keeps the cursor on a character outside insert mode.
+    pub fn normalize_cursor(&self, text: &str, cursor: usize) -> usize {
+        match self.mode {
+            VimMode::Insert => cursor.min(text.len()),
+            _ => clamp_normal(text, cursor),
+        }
+    }
+
+    /// Byte range of the visual selection.
+    pub fn selection(&self, text: &str, cursor: usize) -> Option<(usize, usize)> {
+        self.selection_range(text, cursor)
+            .map(|(start, end, _)| (start, end))
+    }
+
+    pub fn handle_key(
+        &mut self,
+        key: KeyEvent,
+        text: &mut String,
+        cursor: &mut usize,
+    ) -> VimOutcome {
+        match self.mode {
+            VimMode::Insert => VimOutcome::Ignored,
+            VimMode::Normal => self.normal_key(key, text, cursor),
+            VimMode::Visual | VimMode::VisualLine => self.visual_key(key, text, cursor),
+        }
+    }
+
+    fn normal_key(&mut self, key: KeyEvent, text: &mut String, cursor: &mut usize) -> VimOutcome {
+        match key.code {
+            KeyCode::Esc if self.is_idle() => return VimOutcome::Ignored,
+            KeyCode::Esc => {
+                self.clear_pending();
+                return VimOutcome::Handled;
+            }
+            KeyCode::Enter => {
+                self.clear_pending();
+                return VimOutcome::Submit;
+            }
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.clear_pending();
+                self.redo(text, cursor);
+                return VimOutcome::Handled;
+            }
+            _ => {}
+        }
+        if let Some(outcome) = self.pending_key(key, text, cursor) {
+            return outcome;
+        }
+        let Some(c) = key_char(key) else {
+            return VimOutcome::Ignored;
+        };
+        if self.push_count(c) {
+            return VimOutcome::Handled;
+        }
+        match self.operator {
+            Some(op) => self.operator_key(op, c, text, cursor),
+            None => return self.command_key(c, text, cursor),
+        }
+        VimOutcome::Handled
+    }
+
+    fn visual_key(&mut self, key: KeyEvent, text: &mut String, cursor: &mut usize) -> VimOutcome {
+        match key.code {
+            KeyCode::Esc => {
+                self.mode = VimMode::Normal;
+                self.clear_pending();
+                *cursor = clamp_normal(text, *cursor);
+                return VimOutcome::Handled;
+            }
+            KeyCode::Enter => return VimOutcome::Handled,
+            _ => {}
+        }
+        if let Some(outcome) = self.pending_key(key, text, cursor) {
+            return outcome;
+        }
+        let Some(c) = key_char(key) else {
+            return VimOutcome::Ignored;
+        };
+        if self.push_count(c) {
+            return VimOutcome::Handled;
+        }
+        match c {
+            'v' | 'V' => {
+                let mode = if c == 'v' {
+                    VimMode::Visual
+                } else {
+                    VimMode::VisualLine
+                };
+                self.mode = if self.mode == mode {
+                    VimMode::Normal
+                } else {
+                    mode
+                };
+                self.clear_pending();
+            }
+            'o' => std::mem::swap(&mut self.anchor, cursor),
+            'd' | 'x' | 'X' | 'D' => self.operate_selection(Operator::Delete, text, cursor),
+            'c' | 's' => self.operate_selection(Operator::Change, text, cursor),
+            'y' => self.operate_selection(Operator::Yank, text, cursor),
+            'p' | 'P' => self.replace_selection(text, cursor),
+            '~' => {
+                if let Some((start, end, _)) = self.selection_range(text, *cursor) {
+                    self.mode = VimMode::Normal;
+                    self.toggle_case(start, end, text);
+                    *cursor = clamp_normal(text, start);
+                }
+                self.clear_pending();
+            }
+            'i' | 'a' => self.pending = Pending::Object { inner: c == 'i' },
+            _ => {
+                if !self.start_pending(c) {
+                    match self.motion(c, text, *cursor) {
+                        Some(motion) => self.apply_motion(motion, text, cursor),
+                        None => self.clear_pending(),
+                    }
+                }
+            }
+        }
+        VimOutcome::Handled
+    }
+
+    /// Completes a two-key command such as `f,`, `ra`, `"a` or `iw`.
+    fn pending_key(
+        &mut self,
+        key: KeyEvent,
+        text: &mut String,
+        cursor: &mut usize,
+    ) -> Option<VimOutcome> {
+        let pending = std::mem::replace(&mut self.pending, Pending::None);
+        if pending == Pending::None {
+            return None;
+        }
+        let KeyCode::Char(c) = key.code else {
+            self.clear_pending();
+            return Some(VimOutcome::Handled);
+        };
+        match pending {
+            Pending::None => {}
+            Pending::Register => self.register = Some(c),
+            Pending::G if c == 'g' => {
+                let explicit = self.count.is_some() || self.operator_count.is_some();
+                let count = self.take_count();
+                let line = if explicit {
+                    nth_line_start(text, count - 1)
+                } else {
+                    0
+                };
+                let motion = Motion {
+                    pos: first_non_blank(text, line),
+                    inclusive: false,
+                    linewise: true,
+                };
+                self.apply_motion(motion, text, cursor);
+            }
+            Pending::G => self.clear_pending(),
+            Pending::Find(kind) => {
+                self.last_find = Some((kind, c));
+                let count = self.take_count();
+                match find_in_line(text, *cursor, kind, c, count) {
+                    Some(motion) => self.apply_motion(motion, text, cursor),
+                    None => self.clear_pending(),
+                }
+            }
+            Pending::Replace => self.replace_chars(c, text, cursor),
+            Pending::Object { inner } => {
+                let object = text_object(text, *cursor, inner, c);
+                match (object, self.operator.take()) {
+                    (Some((start, end)), _) if self.mode != VimMode::Normal && end > start => {
+                        self.anchor = start;
+                        *cursor = prev_char(text, end);
+                    }
+                    (Some((start, end)), Some(op)) if self.mode == VimMode::Normal => {
+                        self.operate(op, start, end, false, text, cursor);
+                    }
+                    _ => {}
+                }
+                self.clear_pending();
+            }
+        }
+        Some(VimOutcome::Handled)
+    }
+
+    fn command_key(&mut self, c: char, text: &mut String, cursor: &mut usize) -> VimOutcome {
+        match c {
+            'd' => self.begin_operator(Operator::Delete),
+            'c' => self.begin_operator(Operator::Change),
+            'y' => self.begin_operator(Operator::Yank),
+            'x' | 'X' | 'D' | 'C' | 's' | 'S' | 'Y' => {
+                let (op, motion) = match c {
+                    'x' => (Operator::Delete, 'l'),
+                    'X' => (Operator::Delete, 'h'),
+                    'D' => (Operator::Delete, '$'),
+                    'C' => (Operator::Change, '$'),
+                    's' => (Operator::Change, 'l'),
+                    'S' => (Operator::Change, 'c'),
+                    _ => (Operator::Yank, 'y'),
+                };
+                self.begin_operator(op);
+                self.operator_key(op, motion, text, cursor);
+            }
+            'i' => self.insert_at(*cursor, text, cursor),
+            'a' => {
+                let pos = next_char(text, *cursor).min(line_end(text, *cursor));
+                self.insert_at(pos, text, cursor);
+            }
+            'I' => self.insert_at(first_non_blank(text, *cursor), text, cursor),
+            'A' => self.insert_at(line_end(text, *cursor), text, cursor),
+            'o' | 'O' => {
+                self.snapshot(text, *cursor);
+                let at = if c == 'o' {
+                    line_end(text, *cursor)
+                } else {
+                    line_start(text, *cursor)
+                };
+                text.insert(at, '\n');
+                *cursor = if c == 'o' { at + 1 } else { at };
+                self.mode = VimMode::Insert;
+                self.clear_pending();
+            }
+            'p' | 'P' => self.paste(c == 'P', text, cursor),
+            'J' => self.join_lines(text, cursor),
+            '~' => {
+                let count = self.take_count();
+                let end = advance_in_line(text, *cursor, count);
+                self.toggle_case(*cursor, end, text);
+                *cursor = clamp_normal(text, end);
+                self.clear_pending();
+            }
+            'u' => {
+                self.clear_pending();
+                self.undo(text, cursor);
+            }
+            'v' | 'V' => {
+                self.clear_pending();
+                self.anchor = *cursor;
+                self.mode = if c == 'v' {
+                    VimMode::Visual
+                } else {
+                    VimMode::VisualLine
+                };
+            }
+            'k' if self.is_idle() && line_start(text, *cursor) == 0 => {
+                return VimOutcome::HistoryPrevious;
+            }
+            'j' if self.is_idle() && line_end(text, *cursor) == text.len() => {
+                return VimOutcome::HistoryNext;
+            }
+            _ => {
+                if !self.start_pending(c) {
+                    match self.motion(c, text, *cursor) {
+                        Some(motion) => self.apply_motion(motion, text, cursor),
+                        None => self.clear_pending(),
+                    }
+                }
+            }
+        }
+        VimOutcome::Handled
+    }
+
+    fn operator_key(&mut self, op: Operator, c: char, text: &mut String, cursor: &mut usize) {
+        let doubled = matches!(
+            (op, c),
+            (Operator::Delete, 'd') | (Operator::Change, 'c') | (Operator::Yank, 'y')
+        );
+        if doubled {
+            let count = self.take_count();
+            let last = line_offset(text, *cursor, count as isize - 1);
+            let motion = Motion {
+                pos: last,
+                inclusive: false,
+                linewise: true,
+            };
+            self.apply_motion(motion, text, cursor);
+            return;
+        }
+        if c == 'i' || c == 'a' {
+            self.pending = Pending::Object { inner: c == 'i' };
+            return;
+        }
+        if self.start_pending(c) {
+            return;
+        }
+        // `cw` behaves like `ce` when the cursor is on a word.
+        let on_word = text[*cursor..]
+            .chars()
+            .next()
+            .is_some_and(|ch| !ch.is_whitespace());
+        let c = match c {
+            'w' if op == Operator::Change && on_word => 'e',
+            'W' if op == Operator::Change && on_word => 'E',
+            c => c,
+        };
+        let Some(mut motion) = self.motion(c, text, *cursor) else {
+            self.clear_pending();
+            return;
+        };
+        // `dw` on the last word of a line stops at the line end.
+        let end = line_end(text, *cursor);
+        if matches!(c, 'w' | 'W') && motion.pos > end && end > *cursor {
+            motion.pos = end;
+        }
+        self.apply_motion(motion, text, cursor);
+    }
+
+    fn start_pending(&mut self, c: char) -> bool {
+        self.pending = match c {
+            '"' => Pending::Register,
+            'g' => Pending::G,
+            'r' if self.mode == VimMode::Normal && self.operator.is_none() => Pending::Replace,
+            c => match Find::from_key(c) {
+                Some(kind) => Pending::Find(kind),
+                None => return false,
+            },
+        };
+        true
+    }
+
+    fn motion(&mut self, c: char, text: &str, cursor: usize) -> Option<Motion> {
+        if !MOTION_KEYS.contains(c) {
+            return None;
+        }
+        let explicit = self.count.is_some() || self.operator_count.is_some();
+        let count = self.take_count();
+        let big = c.is_ascii_uppercase();
+        let motion = match c {
+            'h' => {
+                let start = line_start(text, cursor);
+                let mut pos = cursor;
+                for _ in 0..count {
+                    if pos <= start {
+                        break;
+                    }
+                    pos = prev_char(text, pos);
+                }
+                Motion::charwise(pos, false)
+            }
+            'l' => Motion::charwise(advance_in_line(text, cursor, count), false),
+            'j' | 'k' => {
+                let delta = if c == 'j' {
+                    count as isize
+                } else {
+                    -(count as isize)
+                };
+                Motion {
+                    pos: line_offset(text, cursor, delta),
+                    inclusive: false,
+                    linewise: true,
+                }
+            }
+            'w' | 'W' => {
+                let pos = (0..count).fold(cursor, |pos, _| word_forward(text, pos, big));
+                Motion::charwise(pos, false)
+            }
+            'b' | 'B' => {
+                let pos = (0..count).fold(cursor, |pos, _| word_backward(text, pos, big));
+                Motion::charwise(pos, false)
+            }
+            'e' | 'E' => {
+                let pos = (0..count).fold(cursor, |pos, _| word_end(text, pos, big));
+                Motion::charwise(pos, true)
+            }
+            '0' => Motion::charwise(line_start(text, cursor), false),
+            '^' => Motion::charwise(first_non_blank(text, cursor), false),
+            '$' => {
+                let line = line_offset(text, cursor, count as isize - 1);
+                Motion::charwise(line_end(text, line), false)
+            }
+            'G' => {
+                let line = if explicit {
+                    nth_line_start(text, count - 1)
+                } else {
+                    line_start(text, text.len())
+                };
+                Motion {
+                    pos: first_non_blank(text, line),
+                    inclusive: false,
+                    linewise: true,
+                }
+            }
+            _ => {
+                let (kind, target) = self.last_find?;
+                let kind = if c == ',' { kind.reversed() } else { kind };
+                find_in_line(text, cursor, kind, target, count)?
+            }
+        };
+        Some(motion)
+    }
+
+    fn apply_motion(&mut self, motion: Motion, text: &mut String, cursor: &mut usize) {
+        match self.mode {
+            VimMode::Visual | VimMode::VisualLine => {
+                *cursor = clamp_normal(text, motion.pos);
+            }
+            _ => match self.operator.take() {
+                Some(op) => {
+                    let (a, b) = if motion.pos < *cursor {
+                        (motion.pos, *cursor)
+                    } else {
+                        (*cursor, motion.pos)
+                    };
+                    let (start, end) = if motion.linewise {
+                        (line_start(text, a), line_end(text, b))
+                    } else if motion.inclusive {
+                        (a, next_char(text, b))
+                    } else {
+                        (a, b)
+                    };
+                    self.operate(op, start, end, motion.linewise, text, cursor);
+                }
+                None => *cursor = clamp_normal(text, motion.pos),
+            },
+        }
+        self.clear_pending();
+    }
+
+    /// Runs an operator over `start..end`. Linewise ranges exclude the final
+    /// newline; deleting them also removes the line break.
+    fn operate(
+        &mut self,
+        op: Operator,
+        start: usize,
+        end: usize,
+        linewise: bool,
+        text: &mut String,
+        cursor: &mut usize,
+    ) {
+        if start == end && !linewise && op != Operator::Change {
+            self.clear_pending();
+            return;
+        }
+        let removed = text[start..end].to_string();
+        match op {
+            Operator::Yank => {
+                self.write_register(removed, linewise);
+                *cursor = clamp_normal(text, start);
+            }
+            Operator::Delete => {
+                self.snapshot(text, *cursor);
+                self.write_register(removed, linewise);
+                let (start, end) = if !linewise {
+                    (start, end)
+                } else if end < text.len() {
+                    (start, end + 1)
+                } else {
+                    (start.saturating_sub(1), end)
+                };
+                text.replace_range(start..end, "");
+                *cursor = if linewise {
+                    first_non_blank(text, start.min(text.len()))
+                } else {
+                    clamp_normal(text, start)
+                };
+            }
+            Operator::Change => {
+                self.snapshot(text, *cursor);
+                self.write_register(removed, linewise);
+                text.replace_range(start..end, "");
+                *cursor = start;
+                self.mode = VimMode::Insert;
+            }
+        }
+        self.clear_pending();
+    }
+
+    fn operate_selection(&mut self, op: Operator, text: &mut String, cursor: &mut usize) {
+        let Some((start, end, linewise)) = self.selection_range(text, *cursor) else {
+            return;
+        };
+        self.mode = VimMode::Normal;
+        self.operate(op, start, end, linewise, text, cursor);
+    }
+
+    fn replace_selection(&mut self, text: &mut String, cursor: &mut usize) {
+        let Some((start, end, linewise)) = self.selection_range(text, *cursor) else {
+            return;
+        };
+        self.mode = VimMode::Normal;
+        let Some(register) = self.read_register() else {
+            self.clear_pending();
+            return;
+        };
+        self.snapshot(text, *cursor);
+        let removed = text[start..end].to_string();
+        text.replace_range(start..end, &register.text);
+        self.write_register(removed, linewise);
+        *cursor = clamp_normal(
+            text,
+            prev_char(text, start + register.text.len()).max(start),
+        );
+        self.clear_pending();
+    }
+
+    fn selection_range(&self, text: &str, cursor: usize) -> Option<(usize, usize, bool)> {
+        let (a, b) = if self.anchor < cursor {
+            (self.anchor, cursor)
+        } else {
+            (cursor, self.anchor)
+        };
+        let b = b.min(text.len());
+        match self.mode {
+            VimMode::Visual => Some((a.min(b), next_char(text, b), false)),
+            VimMode::VisualLine => Some((line_start(text, a), line_end(text, b), true)),
+            _ => None,
+        }
+    }
+
+    fn paste(&mut self, before: bool, text: &mut String, cursor: &mut usize) {
+        let count = self.take_count();
+        let register = self.read_register();
+        self.clear_pending();
+        let Some(register) = register.filter(|register| !register.text.is_empty()) else {
+            return;
+        };
+        self.snapshot(text, *cursor);
+        if register.linewise {
+            let block = vec![register.text.as_str(); count].join("\n");
+            if before {
+                let at = line_start(text, *cursor);
+                text.insert_str(at, &format!("{block}\n"));
+                *cursor = first_non_blank(text, at);
+            } else {
+                let at = line_end(text, *cursor);
+                text.insert_str(at, &format!("\n{block}"));
+                *cursor = first_non_blank(text, at + 1);
+            }
+        } else {
+            let block = register.text.repeat(count);
+            let at = if before || *cursor >= line_end(text, *cursor) {
+                *cursor
+            } else {
+                next_char(text, *cursor)
+            };
+            text.insert_str(at, &block);
+            *cursor = prev_char(text, at + block.len());
+        }
+    }
+
+    fn replace_chars(&mut self, c: char, text: &mut String, cursor: &mut usize) {
+        let count = self.take_count();
+        self.clear_pending();
+        let end = advance_in_line(text, *cursor, count);
+        if text[*cursor..end].chars().count() < count {
+            return;
+        }
+        self.snapshot(text, *cursor);
+        let replacement = c.to_string().repeat(count);
+        text.replace_range(*cursor..end, &replacement);
+        *cursor = prev_char(text, *cursor + replacement.len());
+    }
+
+    fn join_lines(&mut self, text: &mut String, cursor: &mut usize) {
+        let joins = self.take_count().max(2) - 1;
+        self.clear_pending();
+        if line_end(text, *cursor) == text.len() {
+            return;
+        }
+        self.snapshot(text, *cursor);
+        for _ in 0..joins {
+            let end = line_end(text, *cursor);
+            if end == text.len() {
+                break;
+            }
+            let next = end + 1;
+            let content = first_non_blank(text, next);
+            let separator = if content == line_end(text, next) {
+                ""
+            } else {
+                " "
+            };
+            text.replace_range(end..content, separator);
+            *cursor = end;
+        }
+    }
+
+    fn toggle_case(&mut self, start: usize, end: usize, text: &mut String) {
+        if start >= end {
+            return;
+        }
+        self.snapshot(text, start);
+        let toggled: String = text[start..end]
+            .chars()
+            .flat_map(|c| {
+                if c.is_uppercase() {
+                    c.to_lowercase().collect::<Vec<_>>()
+                } else {
+                    c.to_uppercase().collect::<Vec<_>>()
+                }
+            })
+            .collect();
+        text.replace_range(start..end, &toggled);
+    }
+
+    fn insert_at(&mut self, pos: usize, text: &str, cursor: &mut usize) {
+        self.snapshot(text, *cursor);
+        self.clear_pending();
+        *cursor = pos;
+        self.mode = VimMode::Insert;
+    }
+
+    fn write_register(&mut self, text: String, linewise: bool) {
+        let name = self.register.take().unwrap_or('"');
+        let mut register = Register { text, linewise };
+        match name {
+            '_' => return,
+            '+' | '*' => {
+                let mut clip = register.text.clone();
+                if linewise {
+                    clip.push('\n');
+                }
+                let _ = Clipboard::write_text(&clip);
+            }
+            'a'..='z' => {
+                self.registers.insert(name, register.clone());
+            }
+            'A'..='Z' => {
+                let entry = self.registers.entry(name.to_ascii_lowercase()).or_default();
+                if linewise || entry.linewise {
+                    if !entry.text.is_empty() {
+                        entry.text.push('\n');
+                    }
+                    entry.linewise = true;
+                }
+                entry.text.push_str(&register.text);
+                register = entry.clone();
+            }
+            _ => {}
+        }
+        self.registers.insert('"', register);
+    }
+
+    fn read_register(&mut self) -> Option<Register> {
+        match self.register.take().unwrap_or('"') {
+            '+' | '*' => {
+                let text = Clipboard::read_text().ok()?;
+                Some(match text.strip_suffix('\n') {
+                    Some(line) => Register {
+                        text: line.to_string(),
+                        linewise: true,
+                    },
+                    None => Register {
+                        text,
+                        linewise: false,
+                    },
+                })
+            }
+            name => self.registers.get(&name.to_ascii_lowercase()).cloned(),
+        }
+    }
+
+    fn snapshot(&mut self, text: &str, cursor: usize) {
+        if self.undo.last().map(|(saved, _)| saved.as_str()) != Some(text) {
+            self.undo.push((text.to_string(), cursor));
+            if self.undo.len() > MAX_UNDO_STEPS {
+                self.undo.remove(0);
+            }
+        }
+        self.redo.clear();
+    }
+
+    fn undo(&mut self, text: &mut String, cursor: &mut usize) {
+        while let Some((saved, pos)) = self.undo.pop() {
+            if saved == *text {
+                continue;
+            }
+            self.redo.push((std::mem::replace(text, saved), *cursor));
+            *cursor = clamp_normal(text, pos);
+            return;
+        }
+    }
+
+    fn redo(&mut self, text: &mut String, cursor: &mut usize) {
+        let Some((saved, pos)) = self.redo.pop() else {
+            return;
+        };
+        self.undo.push((std::mem::replace(text, saved), *cursor));
+        *cursor = clamp_normal(text, pos);
+    }
+
+    fn begin_operator(&mut self, op: Operator) {
+        self.operator_count = self.count.take();
+        self.operator = Some(op);
+    }
+
+    fn push_count(&mut self, c: char) -> bool {
+        let Some(digit) = c.to_digit(10) else {
+            return false;
+        };
+        if digit == 0 && self.count.is_none() {
+            return false;
+        }
+        let count = self.count.unwrap_or(0) * 10 + digit as usize;
+        self.count = Some(count.min(9999));
+        true
+    }
+
+    fn take_count(&mut self) -> usize {
+        self.operator_count.take().unwrap_or(1) * self.count.take().unwrap_or(1)
+    }
+
+    fn is_idle(&self) -> bool {
+        self.pending == Pending::None
+            && self.operator.is_none()
+            && self.count.is_none()
+            && self.register.is_none()
+    }
+
+    fn clear_pending(&mut self) {
+        self.pending = Pending::None;
+        self.operator = None;
+        self.count = None;
+        self.operator_count = None;
+        self.register = None;
+    }
+}
+
+impl Default for Vim {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn key_char(key: KeyEvent) -> Option<char> {
+    match key.code {
+        KeyCode::Char(c)
+            if !key
+                .modifiers
+                .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+        {
+            Some(c)
+        }
+        KeyCode::Left | KeyCode::Backspace => Some('h'),
+        KeyCode::Right => Some('l'),
+        KeyCode::Up => Some('k'),
+        KeyCode::Down => Some('j'),
+        KeyCode::Home => Some('0'),
+        KeyCode::End => Some('$'),
+        _ => None,
+    }
+}
+
+fn char_class(c: char, big: bool) -> u8 {
+    if c.is_whitespace() {
+        0
+    } else if big || c.is_alphanumeric() || c == '_' {
+        1
+    } else {
+        2
+    }
+}
+
+fn prev_char(text: &str, pos: usize) -> usize {
+    text[..pos.min(text.len())]
+        .char_indices()
+        .next_back()
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+fn next_char(text: &str, pos: usize) -> usize {
+    text[pos.min(text.len())..]
+        .chars()
+        .next()
+        .map(|c| pos + c.len_utf8())
+        .unwrap_or(text.len())
+}
+
+fn line_start(text: &str, pos: usize) -> usize {
+    text[..pos.min(text.len())]
+        .rfind('\n')
+        .map(|i| i + 1)
+        .unwrap_or(0)
+}
+
+fn line_end(text: &str, pos: usize) -> usize {
+    let pos = pos.min(text.len());
+    text[pos..]
+        .find('\n')
+        .map(|i| pos + i)
+        .unwrap_or(text.len())
+}
+
+fn first_non_blank(text: &str, pos: usize) -> usize {
+    let start = line_start(text, pos);
+    let end = line_end(text, pos);
+    text[start..end]
+        .char_indices()
+        .find(|(_, c)| !c.is_whitespace())
+        .map(|(i, _)| start + i)
+        .unwrap_or(end)
+}
+
+/// Outside insert mode the cursor sits on a character, never past the end
+/// of a non-empty line.
+fn clamp_normal(text: &str, pos: usize) -> usize {
+    let pos = pos.min(text.len());
+    let end = line_end(text, pos);
+    if pos >= end && end > line_start(text, pos) {
+        prev_char(text, end)
+    } else {
+        pos
+    }
+}
+
+/// Moves `count` characters right without leaving the line.
+fn advance_in_line(text: &str, pos: usize, count: usize) -> usize {
+    let end = line_end(text, pos);
+    text[pos..end]
+        .char_indices()
+        .nth(count)
+        .map(|(i, _)| pos + i)
+        .unwrap_or(end)
+}
+
+fn nth_line_start(text: &str, n: usize) -> usize {
+    if n == 0 {
+        return 0;
+    }
+    text.match_indices('\n')
+        .nth(n - 1)
+        .map(|(i, _)| i + 1)
+        .unwrap_or_else(|| line_start(text, text.len()))
+}
+
+/// Same column, `delta` lines down (or up when negative), stopping at the
+/// first or last line.
+fn line_offset(text: &str, pos: usize, delta: isize) -> usize {
+    let start = line_start(text, pos);
+    let column = text[start..pos].chars().count();
+    let mut line = start;
+    for _ in 0..delta.unsigned_abs() {
+        if delta > 0 {
+            let end = line_end(text, line);
+            if end == text.len() {
+                break;
+            }
+            line = end + 1;
+        } else {
+            if line == 0 {
+                break;
+            }
+            line = line_start(text, line - 1);
+        }
+    }
+    advance_in_line(text, line, column)
+}
+
+fn word_forward(text: &str, pos: usize, big: bool) -> usize {
+    let mut chars = text[pos..].char_indices().peekable();
+    let Some(&(_, first)) = chars.peek() else {
+        return text.len();
+    };
+    let class = char_class(first, big);
+    if class != 0 {
+        while chars
+            .peek()
+            .is_some_and(|&(_, c)| char_class(c, big) == class)
+        {
+            chars.next();
+        }
+    }
+    chars
+        .find(|(_, c)| !c.is_whitespace())
+        .map(|(i, _)| pos + i)
+        .unwrap_or(text.len())
+}
+
+fn word_end(text: &str, pos: usize, big: bool) -> usize {
+    let chars: Vec<(usize, char)> = text[pos..].char_indices().skip(1).collect();
+    let mut i = 0;
+    while i < chars.len() && chars[i].1.is_whitespace() {
+        i += 1;
+    }
+    if i == chars.len() {
+        return prev_char(text, text.len()).max(pos);
+    }
+    let class = char_class(chars[i].1, big);
+    while i + 1 < chars.len() && char_class(chars[i + 1].1, big) == class {
+        i += 1;
+    }
+    pos + chars[i].0
+}
+
+fn word_backward(text: &str, pos: usize, big: bool) -> usize {
+    let chars: Vec<(usize, char)> = text[..pos].char_indices().collect();
+    let mut i = chars.len();
+    while i > 0 && chars[i - 1].1.is_whitespace() {
+        i -= 1;
+    }
+    if i == 0 {
+        return 0;
+    }
+    let class = char_class(chars[i - 1].1, big);
+    while i > 0 && char_class(chars[i - 1].1, big) == class {
+        i -= 1;
+    }
+    chars[i].0
+}
+
+fn find_in_line(
+    text: &str,
+    cursor: usize,
+    kind: Find,
+    target: char,
+    count: usize,
+) -> Option<Motion> {
+    match kind {
+        Find::Forward | Find::TillForward => {
+            let end = line_end(text, cursor);
+            let (offset, _) = text[cursor..end]
+                .char_indices()
+                .skip(1)
+                .filter(|(_, c)| *c == target)
+                .nth(count - 1)?;
+            let pos = cursor + offset;
+            let pos = if kind == Find::TillForward {
+                prev_char(text, pos)
+            } else {
+                pos
+            };
+            Some(Motion::charwise(pos, true))
+        }
+        Find::Backward | Find::TillBackward => {
+            let start = line_start(text, cursor);
+            let (offset, _) = text[start..cursor]
+                .char_indices()
+                .rev()
+                .filter(|(_, c)| *c == target)
+                .nth(count - 1)?;
+            let pos = start + offset;
+            let pos = if kind == Find::TillBackward {
+                next_char(text, pos)
+            } else {
+                pos
+            };
+            Some(Motion::charwise(pos, false))
+        }
+    }
+}
+
+/// Byte range of a text object (`iw`, `a"`, `i(` ...) around the cursor.
+fn text_object(text: &str, cursor: usize, inner: bool, c: char) -> Option<(usize, usize)> {
+    match c {
+        'w' | 'W' => word_object(text, cursor, inner, c == 'W'),
+        '"' | '\'' | '`' => quote_object(text, cursor, inner, c),
+        '(' | ')' | 'b' => pair_object(text, cursor, inner, '(', ')'),
+        '[' | ']' => pair_object(text, cursor, inner, '[', ']'),
+        '{' | '}' | 'B' => pair_object(text, cursor, inner, '{', '}'),
+        '<' | '>' => pair_object(text, cursor, inner, '<', '>'),
+        _ => None,
+    }
+}
+
+fn word_object(text: &str, cursor: usize, inner: bool, big: bool) -> Option<(usize, usize)> {
+    let current = text[cursor..].chars().next().filter(|c| *c != '\n')?;
+    let class = char_class(current, big);
+    let same = |c: char| c != '\n' && char_class(c, big) == class;
+    let mut start = cursor;
+    for (i, c) in text[..cursor].char_indices().rev() {
+        if !same(c) {
+            break;
+        }
+        start = i;
+    }
+    let mut end = line_end(text, cursor);
+    if let Some((i, _)) = text[cursor..].char_indices().find(|&(_, c)| !same(c)) {
+        end = end.min(cursor + i);
+    }
+    if !inner && class != 0 {
+        let blank = |c: char| c == ' ' || c == '\t';
+        let trailing = text[end..]
+            .char_indices()
+            .find(|&(_, c)| !blank(c))
+            .map(|(i, _)| end + i)
+            .unwrap_or(text.len());
+        if trailing > end {
+            end = trailing;
+        } else {
+            start = text[..start]
+                .char_indices()
+                .rev()
+                .take_while(|&(_, c)| blank(c))
+                .last()
+                .map(|(i, _)| i)
+                .unwrap_or(start);
+        }
+    }
+    Some((start, end))
+}
+
+fn quote_object(text: &str, cursor: usize, inner: bool, quote: char) -> Option<(usize, usize)> {
+    let start = line_start(text, cursor);
+    let end = line_end(text, cursor);
+    let quotes: Vec<usize> = text[start..end]
+        .char_indices()
+        .filter(|&(_, c)| c == quote)
+        .map(|(i, _)| start + i)
+        .collect();
+    let pair = quotes.chunks_exact(2).find(|pair| pair[1] >= cursor)?;
+    let width = quote.len_utf8();
+    Some(if inner {
+        (pair[0] + width, pair[1])
+    } else {
+        (pair[0], pair[1] + width)
+    })
+}
+
+fn pair_object(
+    text: &str,
+    cursor: usize,
+    inner: bool,
+    open: char,
+    close: char,
+) -> Option<(usize, usize)> {
+    let mut depth = 0usize;
+    let mut start = None;
+    for (i, c) in text[..next_char(text, cursor)].char_indices().rev() {
+        if c == close && i != cursor {
+            depth += 1;
+        } else if c == open {
+            if depth == 0 {
+                start = Some(i);
+                break;
+            }
+            depth -= 1;
+        }
+    }
+    let start = start?;
+    let body = start + open.len_utf8();
+    let mut depth = 0usize;
+    let mut end = None;
+    for (i, c) in text[body..].char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            if depth == 0 {
+                end = Some(body + i);
+                break;
+            }
+            depth -= 1;
+        }
+    }
+    let end = end?;
+    Some(if inner {
+        (body, end)
+    } else {
+        (start, end + close.len_utf8())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feeds keys the way the prompt would: insert-mode keys edit the text
+    /// directly and `\x1b` leaves insert mode.
+    fn run(initial: &str, cursor: usize, keys: &str) -> (String, usize, Vim) {
+        let mut vim = Vim::new();
+        let mut text = initial.to_string();
+        let mut cursor = cursor;
+        vim.enter_normal(&text, &mut cursor);
+        for c in keys.chars() {
+            let key = match c {
+                '\x1b' => KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE),
+                '\x12' => KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL),
+                c => KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE),
+            };
+            if vim.mode() == VimMode::Insert {
+                if c == '\x1b' {
+                    vim.enter_normal(&text, &mut cursor);
+                } else {
+                    text.insert(cursor, c);
+                    cursor += c.len_utf8();
+                }
+                continue;
+            }
+            vim.handle_key(key, &mut text, &mut cursor);
+        }
+        (text, cursor, vim)
+    }
+
+    fn edit(initial: &str, keys: &str) -> String {
+        run(initial, 0, keys).0
+    }
+
+    #[test]
+    fn operators_with_motions_and_counts() {
+        assert_eq!(edit("hello world foo", "dw"), "world foo");
+        assert_eq!(edit("hello world foo", "2dw"), "foo");
+        assert_eq!(edit("hello world foo", "d2w"), "foo");
+        assert_eq!(edit("hello world", "wD"), "hello ");
+        assert_eq!(edit("hello world", "3x"), "lo world");
+        assert_eq!(edit("a, b, c", "dt,"), ", b, c");
+        assert_eq!(edit("a, b, c", "f,;x"), "a, b c");
+        assert_eq!(edit("one\ntwo\nthree", "jdd"), "one\nthree");
+        assert_eq!(edit("one\ntwo\nthree", "Gdd"), "one\ntwo");
+        assert_eq!(edit("one\ntwo\nthree", "dj"), "three");
+        assert_eq!(edit("last word\nnext", "wdw"), "last \nnext");
+    }
+
+    #[test]
+    fn change_enters_insert_mode() {
+        assert_eq!(edit("hello world", "cwbye\x1b"), "bye world");
+        assert_eq!(
+            edit("say \"hi there\" now", "fhci\"yo\x1b"),
+            "say \"yo\" now"
+        );
+        assert_eq!(edit("call(a, (b))", "2fadi("), "call()");
+        assert_eq!(edit("foo bar baz", "wdaw"), "foo baz");
+        assert_eq!(edit("one\ntwo", "jccnew\x1b"), "one\nnew");
+        let (_, _, vim) = run("x", 0, "cc");
+        assert_eq!(vim.mode(), VimMode::Insert);
+    }
+
+    #[test]
+    fn yank_paste_and_registers() {
+        assert_eq!(edit("one\ntwo", "yyp"), "one\none\ntwo");
+        assert_eq!(edit("one\ntwo", "jyykP"), "two\none\ntwo");
+        assert_eq!(edit("foo bar", "yiw$p"), "foo barfoo");
+        assert_eq!(edit("foo bar", "\"ayiwwdiw\"ap"), "foo foo");
+        assert_eq!(edit("abc", "xp"), "bac");
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        let (text, _, _) = run("hello world", 0, "dwu");
+        assert_eq!(text, "hello world");
+        let (text, _, _) = run("hello world", 0, "dwu\x12");
+        assert_eq!(text, "world");
+        let (text, _, _) = run("abc", 0, "xxuu");
+        assert_eq!(text, "abc");
+    }
+
+    #[test]
+    fn visual_selections() {
+        assert_eq!(edit("hello world", "vex"), " world");
+        assert_eq!(edit("one\ntwo\nthree", "Vjd"), "three");
+        assert_eq!(edit("hello world", "wviwy0vep"), "world world");
+        assert_eq!(edit("abc", "vl~"), "ABc");
+        let (text, cursor, vim) = run("hello world", 0, "wvb");
+        assert_eq!(vim.selection(&text, cursor), Some((0, 7)));
+    }
+
+    #[test]
+    fn cursor_stays_on_a_character() {
+        let (text, cursor, _) = run("abc", 3, "");
+        assert_eq!(cursor, 2);
+        let (_, cursor, _) = run(&text, 0, "$");
+        assert_eq!(cursor, 2);
+        let (_, cursor, _) = run("héllo", 0, "ll");
+        assert_eq!(cursor, 3);
+    }
+}
@@ -10,6 +10,7 @@ use crate::context::{KeybindRegistry, SessionContext};
 use crate::event::EventBus;
 use crate::router::Router;
 use crate::theme::Theme;
+use crate::ui::Graphics;
 use rocode_core::process_registry::ProcessInfo;
 
 #[derive(Clone)]
@@ -119,6 +120,7 @@ pub struct AppContext {
     pub semantic_highlight: RwLock<bool>,
     pub has_connected_provider: RwLock<bool>,
     pub processes: RwLock<Vec<ProcessInfo>>,
+    pub graphics: RwLock<Graphics>,
     ui_kv: RwLock<UiKv>,
     pub api_client: RwLock<Option<Arc<ApiClient>>>,
 }
@@ -160,6 +162,7 @@ impl AppContext {
             semantic_highlight: RwLock::new(ui_kv.get_bool("semantic_highlight", false)),
             has_connected_provider: RwLock::new(false),
             processes: RwLock::new(Vec::new()),
+            graphics: RwLock::new(Graphics::new()),
             ui_kv: RwLock::new(ui_kv),
             api_client: RwLock::new(None),
         }
@@ -169,6 +172,10 @@ impl AppContext {
         self.router.write().navigate(route);
     }
 
+    pub fn go_back(&self) -> bool {
+        self.router.write().go_back()
+    }
+
     pub fn current_route(&self) -> crate::router::Route {
         self.router.read().current().clone()
     }
@@ -335,6 +342,16 @@ impl AppContext {
     pub fn get_api_client(&self) -> Option<Arc<ApiClient>> {
         self.api_client.read().clone()
     }
+
+    /// The project directory, or the process working directory when unset.
+    pub fn directory_path(&self) -> PathBuf {
+        let directory = self.directory.read().clone();
+        if directory.trim().is_empty() {
+            std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."))
+        } else {
+            PathBuf::from(directory)
+        }
+    }
 }
 
 impl Default for AppContext {
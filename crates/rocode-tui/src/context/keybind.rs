@@ -52,17 +52,24 @@ impl Default for LeaderKeyState {
 pub struct Keybind {
     pub code: KeyCode,
     pub modifiers: KeyModifiers,
+    /// Pressed after the leader key rather than on its own.
+    pub leader: bool,
 }
 
 impl Keybind {
     pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
-        Self { code, modifiers }
+        Self {
+            code,
+            modifiers,
+            leader: false,
+        }
     }
 
     pub fn key(code: KeyCode) -> Self {
         Self {
             code,
             modifiers: KeyModifiers::empty(),
+            leader: false,
         }
     }
 
@@ -70,6 +77,7 @@ impl Keybind {
         Self {
             code,
             modifiers: KeyModifiers::CONTROL,
+            leader: false,
         }
     }
 
@@ -77,6 +85,7 @@ impl Keybind {
         Self {
             code,
             modifiers: KeyModifiers::ALT,
+            leader: false,
         }
     }
 
@@ -84,6 +93,7 @@ impl Keybind {
         Self {
             code,
             modifiers: KeyModifiers::SHIFT,
+            leader: false,
         }
     }
 
@@ -91,12 +101,116 @@ impl Keybind {
         Self {
             code,
             modifiers: KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+            leader: false,
+        }
+    }
+
+    /// The same key, pressed after the leader key.
+    pub fn after_leader(self) -> Self {
+        Self {
+            leader: true,
+            ..self
         }
     }
+
+    /// Parses a config binding such as `ctrl+x,alt+enter` or `<leader>q`
+    /// into its comma-separated alternatives. Returns `None` if any
+    /// alternative is invalid.
+    pub fn parse_all(spec: &str) -> Option<Vec<Self>> {
+        let alternatives: Vec<Self> = spec
+            .split(',')
+            .filter(|part| !part.trim().is_empty())
+            .map(Self::parse)
+            .collect::<Option<_>>()?;
+        (!alternatives.is_empty()).then_some(alternatives)
+    }
+
+    /// Parses a single binding such as `ctrl+x`, `esc` or `<leader>n`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim().to_ascii_lowercase();
+        if let Some(rest) = spec.strip_prefix("<leader>") {
+            let keybind = Self::parse(rest)?;
+            return (!keybind.leader).then(|| keybind.after_leader());
+        }
+        if spec.is_empty() || spec.contains('<') {
+            return None;
+        }
+        let mut modifiers = KeyModifiers::empty();
+        let mut parts: Vec<&str> = spec.split('+').collect();
+        let key = parts.pop()?;
+        for part in parts {
+            match part {
+                "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
+                "alt" | "meta" | "option" => modifiers |= KeyModifiers::ALT,
+                "shift" => modifiers |= KeyModifiers::SHIFT,
+                _ => return None,
+            }
+        }
+        let code = match key {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "del" | "delete" => KeyCode::Delete,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pgup" | "pageup" => KeyCode::PageUp,
+            "pgdn" | "pagedown" => KeyCode::PageDown,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "space" => KeyCode::Char(' '),
+            _ if key.len() > 1 && key.starts_with('f') => KeyCode::F(key[1..].parse().ok()?),
+            _ => {
+                let mut chars = key.chars();
+                let c = chars.next()?;
+                if chars.next().is_some() {
+                    return None;
+                }
+                KeyCode::Char(c)
+            }
+        };
+        Some(Self {
+            code,
+            modifiers,
+            leader: false,
+        })
+    }
+
+    fn matches(&self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        if self.code == code && self.modifiers == modifiers {
+            return true;
+        }
+
+        // Terminal implementations can report Shift+Tab as either:
+        // - KeyCode::BackTab with no modifiers
+        // - KeyCode::Tab with SHIFT modifier
+        if self.code == KeyCode::BackTab {
+            match (code, modifiers) {
+                (KeyCode::BackTab, mods) if mods.is_empty() || mods == KeyModifiers::SHIFT => {
+                    return true;
+                }
+                (KeyCode::Tab, mods) if mods.contains(KeyModifiers::SHIFT) => {
+                    return true;
+                }
+                _ => {}
+            }
+        }
+
+        false
+    }
 }
 
 impl std::fmt::Display for Keybind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.leader {
+            write!(f, "<leader> ")?;
+        }
         let mut parts = Vec::new();
 
         if self.modifiers.contains(KeyModifiers::CONTROL) {
@@ -134,8 +248,53 @@ impl std::fmt::Display for Keybind {
     }
 }
 
+/// Bindings the app dispatches on a plain key press, in the order it
+/// checks them. Two of these on the same key leave the later one
+/// unreachable.
+const DISPATCHED_BINDINGS: &[&str] = &[
+    "vim_normal_mode",
+    "leader",
+    "session_interrupt",
+    "input_paste",
+    "input_copy",
+    "input_cut",
+    "history_previous",
+    "history_next",
+    "page_up",
+    "page_down",
+    "command_palette",
+    "model_cycle",
+    "agent_cycle",
+    "agent_cycle_reverse",
+    "variant_cycle",
+    "sidebar_toggle",
+    "display_thinking",
+    "tool_details",
+    "input_clear",
+    "input_newline",
+    "help_toggle",
+];
+
+/// Two dispatched bindings sharing a key; `shadowed` never fires.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeybindConflict {
+    pub keybind: Keybind,
+    pub winner: String,
+    pub shadowed: String,
+}
+
+impl std::fmt::Display for KeybindConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} is bound to both {} and {}; {} never fires",
+            self.keybind, self.winner, self.shadowed, self.shadowed
+        )
+    }
+}
+
 pub struct KeybindRegistry {
-    bindings: HashMap<String, Keybind>,
+    bindings: HashMap<String, Vec<Keybind>>,
 }
 
 impl Default for KeybindRegistry {
@@ -154,6 +313,7 @@ impl KeybindRegistry {
     }
 
     fn register_defaults(&mut self) {
+        self.register("leader", Keybind::ctrl(KeyCode::Char('x')));
         self.register("app_exit", Keybind::ctrl(KeyCode::Char('c')));
         self.register("app_exit_alt", Keybind::key(KeyCode::Esc));
 
@@ -173,7 +333,7 @@ impl KeybindRegistry {
         self.register("agent_cycle", Keybind::key(KeyCode::Tab));
         self.register("agent_cycle_reverse", Keybind::key(KeyCode::BackTab));
         self.register("model_cycle", Keybind::ctrl(KeyCode::Char('m')));
-        self.register("variant_cycle", Keybind::ctrl(KeyCode::Char('v')));
+        self.register("variant_cycle", Keybind::ctrl(KeyCode::Char('t')));
 
         self.register("session_child_cycle", Keybind::ctrl(KeyCode::Char('j')));
         self.register(
@@ -199,51 +359,220 @@ impl KeybindRegistry {
         self.register("page_down", Keybind::key(KeyCode::PageDown));
         self.register("home", Keybind::key(KeyCode::Home));
         self.register("end", Keybind::key(KeyCode::End));
+
+        // Esc already interrupts the session, so vim needs its own key.
+        self.register("vim_normal_mode", Keybind::ctrl(KeyCode::Char('g')));
+    }
+
+    /// Applies the `keybinds` section of the config on top of the defaults.
+    /// Bindings that cannot be parsed keep their default; `none` removes one.
+    pub fn apply_config(&mut self, config: &rocode_config::KeybindsConfig) {
+        let Ok(serde_json::Value::Object(entries)) = serde_json::to_value(config) else {
+            return;
+        };
+        for (name, value) in entries {
+            let Some(spec) = value.as_str() else {
+                continue;
+            };
+            if spec.trim().eq_ignore_ascii_case("none") {
+                self.bindings.remove(&name);
+                continue;
+            }
+            let Some(alternatives) = Keybind::parse_all(spec) else {
+                continue;
+            };
+            if name == "leader" && alternatives.iter().any(|kb| kb.leader) {
+                continue;
+            }
+            self.bindings.insert(name, alternatives);
+        }
     }
 
     pub fn register(&mut self, name: &str, keybind: Keybind) {
-        self.bindings.insert(name.to_string(), keybind);
+        self.bindings.insert(name.to_string(), vec![keybind]);
     }
 
+    /// The first alternative bound to `name`.
     pub fn get(&self, name: &str) -> Option<&Keybind> {
-        self.bindings.get(name)
+        self.bindings.get(name)?.first()
     }
 
+    /// Whether the key matches one of `name`'s alternatives that are
+    /// pressed on their own.
     pub fn match_key(&self, name: &str, code: KeyCode, modifiers: KeyModifiers) -> bool {
-        let Some(kb) = self.bindings.get(name) else {
-            return false;
-        };
+        self.matches(name, false, code, modifiers)
+    }
 
-        if kb.code == code && kb.modifiers == modifiers {
-            return true;
+    /// Whether the key, pressed right after the leader, matches one of
+    /// `name`'s `<leader>` alternatives.
+    pub fn match_leader_key(&self, name: &str, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        self.matches(name, true, code, modifiers)
+    }
+
+    /// Whether any binding uses the key as a `<leader>` sequence.
+    pub fn has_leader_binding(&self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        self.bindings
+            .values()
+            .flatten()
+            .any(|kb| kb.leader && kb.matches(code, modifiers))
+    }
+
+    fn matches(&self, name: &str, leader: bool, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        self.bindings.get(name).is_some_and(|alternatives| {
+            alternatives
+                .iter()
+                .any(|kb| kb.leader == leader && kb.matches(code, modifiers))
+        })
+    }
+
+    pub fn print(&self, name: &str) -> String {
+        let Some(kb) = self.get(name) else {
+            return "?".to_string();
+        };
+        match self.get("leader") {
+            Some(leader) if kb.leader => {
+                format!(
+                    "{} {}",
+                    leader,
+                    Keybind {
+                        leader: false,
+                        ..kb.clone()
+                    }
+                )
+            }
+            _ => kb.to_string(),
         }
+    }
 
-        // Terminal implementations can report Shift+Tab as either:
-        // - KeyCode::BackTab with no modifiers
-        // - KeyCode::Tab with SHIFT modifier
-        if kb.code == KeyCode::BackTab {
-            match (code, modifiers) {
-                (KeyCode::BackTab, mods) if mods.is_empty() || mods == KeyModifiers::SHIFT => {
-                    return true;
-                }
-                (KeyCode::Tab, mods) if mods.contains(KeyModifiers::SHIFT) => {
-                    return true;
+    pub fn all(&self) -> &HashMap<String, Vec<Keybind>> {
+        &self.bindings
+    }
+
+    /// Dispatched bindings that share a key with an earlier one.
+    pub fn conflicts(&self) -> Vec<KeybindConflict> {
+        let mut seen: Vec<(&Keybind, &str)> = Vec::new();
+        let mut conflicts = Vec::new();
+        for name in DISPATCHED_BINDINGS {
+            let Some(alternatives) = self.bindings.get(*name) else {
+                continue;
+            };
+            for keybind in alternatives {
+                match seen.iter().find(|(other, _)| *other == keybind) {
+                    Some((_, winner)) if winner != name => conflicts.push(KeybindConflict {
+                        keybind: keybind.clone(),
+                        winner: winner.to_string(),
+                        shadowed: name.to_string(),
+                    }),
+                    Some(_) => {}
+                    None => seen.push((keybind, name)),
                 }
-                _ => {}
             }
         }
+        conflicts
+    }
+}
 
-        false
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_config_bindings() {
+        assert_eq!(
+            Keybind::parse("ctrl+x"),
+            Some(Keybind::ctrl(KeyCode::Char('x')))
+        );
+        assert_eq!(
+            Keybind::parse_all("Alt+Enter, ctrl+j"),
+            Some(vec![
+                Keybind::alt(KeyCode::Enter),
+                Keybind::ctrl(KeyCode::Char('j'))
+            ])
+        );
+        assert_eq!(Keybind::parse_all("ctrl+j,hyper+x"), None);
+        assert_eq!(
+            Keybind::parse("shift+tab"),
+            Some(Keybind::key(KeyCode::BackTab))
+        );
+        assert_eq!(Keybind::parse("f2"), Some(Keybind::key(KeyCode::F(2))));
+        assert_eq!(
+            Keybind::parse("<leader>q"),
+            Some(Keybind::key(KeyCode::Char('q')).after_leader())
+        );
+        assert_eq!(
+            Keybind::parse("<leader> ctrl+n"),
+            Some(Keybind::ctrl(KeyCode::Char('n')).after_leader())
+        );
+        assert_eq!(Keybind::parse("<leader><leader>q"), None);
+        assert_eq!(Keybind::parse("hyper+x"), None);
     }
 
-    pub fn print(&self, name: &str) -> String {
-        self.bindings
-            .get(name)
-            .map(|kb| kb.to_string())
-            .unwrap_or_else(|| "?".to_string())
+    #[test]
+    fn config_overrides_defaults() {
+        let mut registry = KeybindRegistry::new();
+        let config = rocode_config::KeybindsConfig {
+            vim_normal_mode: Some("ctrl+c".to_string()),
+            editor_open: Some("none".to_string()),
+            ..Default::default()
+        };
+        registry.apply_config(&config);
+        assert!(registry.match_key("vim_normal_mode", KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert!(registry.get("editor_open").is_none());
     }
 
-    pub fn all(&self) -> &HashMap<String, Keybind> {
-        &self.bindings
+    #[test]
+    fn every_alternative_and_leader_sequences_match() {
+        let mut registry = KeybindRegistry::new();
+        let config = rocode_config::KeybindsConfig {
+            leader: Some("ctrl+a".to_string()),
+            sidebar_toggle: Some("ctrl+b,<leader>b".to_string()),
+            ..Default::default()
+        };
+        registry.apply_config(&config);
+
+        assert!(registry.match_key("leader", KeyCode::Char('a'), KeyModifiers::CONTROL));
+        assert!(registry.match_key("sidebar_toggle", KeyCode::Char('b'), KeyModifiers::CONTROL));
+        assert!(!registry.match_key("sidebar_toggle", KeyCode::Char('b'), KeyModifiers::NONE));
+        assert!(registry.match_leader_key(
+            "sidebar_toggle",
+            KeyCode::Char('b'),
+            KeyModifiers::NONE
+        ));
+        assert!(registry.has_leader_binding(KeyCode::Char('b'), KeyModifiers::NONE));
+        assert_eq!(registry.print("sidebar_toggle"), "ctrl+b");
+
+        registry.apply_config(&rocode_config::KeybindsConfig {
+            sidebar_toggle: Some("<leader>b".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(registry.print("sidebar_toggle"), "ctrl+a b");
+    }
+
+    #[test]
+    fn defaults_have_no_conflicts_and_overrides_are_reported() {
+        let mut registry = KeybindRegistry::new();
+        assert_eq!(registry.conflicts(), Vec::new());
+        assert!(!registry.match_key("vim_normal_mode", KeyCode::Esc, KeyModifiers::NONE));
+
+        registry.apply_config(&rocode_config::KeybindsConfig {
+            vim_normal_mode: Some("esc".to_string()),
+            agent_cycle: Some("tab,ctrl+p".to_string()),
+            ..Default::default()
+        });
+        let conflicts: Vec<(String, String)> = registry
+            .conflicts()
+            .into_iter()
+            .map(|conflict| (conflict.winner, conflict.shadowed))
+            .collect();
+        assert_eq!(
+            conflicts,
+            vec![
+                (
+                    "vim_normal_mode".to_string(),
+                    "session_interrupt".to_string()
+                ),
+                ("command_palette".to_string(), "agent_cycle".to_string()),
+            ]
+        );
     }
 }
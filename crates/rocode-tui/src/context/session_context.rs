@@ -66,6 +66,8 @@ pub enum MessagePart {
     },
     Image {
         url: String,
+        #[serde(default)]
+        filename: Option<String>,
     },
     ToolCall {
         id: String,
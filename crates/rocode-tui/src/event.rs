@@ -37,6 +37,11 @@ pub enum CustomEvent {
         optimistic_message_id: String,
         error: Option<String>,
     },
+    FileTreeScanned {
+        root: std::path::PathBuf,
+        files: Vec<String>,
+        status: Option<Vec<crate::api::FileStatusInfo>>,
+    },
     StateChanged(StateChange),
 }
 
@@ -64,6 +69,7 @@ pub enum StateChange {
     TodoUpdated,
     DiffUpdated,
     ProcessesUpdated,
+    ConfigUpdated,
     QuestionCreated {
         session_id: String,
         request_id: String,
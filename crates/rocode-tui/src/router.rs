@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 pub enum Route {
     Home,
     Session { session_id: String },
+    Review { session_id: String },
     Settings,
     Help,
 }
@@ -19,6 +20,7 @@ impl std::fmt::Display for Route {
         match self {
             Route::Home => write!(f, "Home"),
             Route::Session { session_id } => write!(f, "Session: {}", session_id),
+            Route::Review { session_id } => write!(f, "Review: {}", session_id),
             Route::Settings => write!(f, "Settings"),
             Route::Help => write!(f, "Help"),
         }
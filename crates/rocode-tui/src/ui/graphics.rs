@@ -0,0 +1,806 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use ratatui::layout::Rect;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Images larger than this are not loaded for display.
+const MAX_IMAGE_BYTES: usize = 20 * 1024 * 1024;
+/// Decoded images larger than this (in pixels) are not converted to sixel.
+const MAX_DECODED_PIXELS: u64 = 4096 * 4096;
+const KITTY_CHUNK_SIZE: usize = 4096;
+/// Used when the terminal does not report its pixel size.
+const DEFAULT_CELL_SIZE: (u16, u16) = (8, 16);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphicsProtocol {
+    Kitty,
+    Iterm2,
+    Sixel,
+    None,
+}
+
+impl GraphicsProtocol {
+    /// Picks a protocol from the environment. `OPENCODE_TUI_IMAGES` forces
+    /// one (`kitty`, `iterm`, `sixel`) or turns images off (`off`).
+    pub fn detect() -> Self {
+        if let Ok(value) = std::env::var("OPENCODE_TUI_IMAGES") {
+            match value.trim().to_ascii_lowercase().as_str() {
+                "kitty" => return Self::Kitty,
+                "iterm" | "iterm2" => return Self::Iterm2,
+                "sixel" => return Self::Sixel,
+                "off" | "none" | "0" | "false" => return Self::None,
+                _ => {}
+            }
+        }
+        // Multiplexers need passthrough wrapping that we do not do.
+        if std::env::var_os("TMUX").is_some() || std::env::var_os("STY").is_some() {
+            return Self::None;
+        }
+        let term = std::env::var("TERM")
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let program = std::env::var("TERM_PROGRAM")
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if term.contains("kitty")
+            || std::env::var_os("KITTY_WINDOW_ID").is_some()
+            || program == "ghostty"
+            || std::env::var_os("KONSOLE_VERSION").is_some()
+        {
+            return Self::Kitty;
+        }
+        if matches!(program.as_str(), "iterm.app" | "wezterm" | "mintty") {
+            return Self::Iterm2;
+        }
+        if term.contains("foot") || term.contains("mlterm") || term.contains("sixel") {
+            return Self::Sixel;
+        }
+        Self::None
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Kitty => "kitty",
+            Self::Iterm2 => "iTerm2",
+            Self::Sixel => "sixel",
+            Self::None => "none",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Webp,
+    Other,
+}
+
+impl ImageFormat {
+    pub fn sniff(bytes: &[u8]) -> Self {
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Self::Png
+        } else if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+            Self::Jpeg
+        } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            Self::Gif
+        } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+            Self::Webp
+        } else {
+            Self::Other
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Png => "PNG",
+            Self::Jpeg => "JPEG",
+            Self::Gif => "GIF",
+            Self::Webp => "WebP",
+            Self::Other => "image",
+        }
+    }
+}
+
+/// Which local files an image URL may be read from.
+#[derive(Clone, Copy, Debug)]
+pub enum ImageAccess<'a> {
+    /// Attached by the user, who picked the file themselves.
+    Attachment,
+    /// Named by the agent or a tool: only files under this directory.
+    Within(&'a Path),
+}
+
+impl ImageAccess<'_> {
+    fn permits(self, path: &Path) -> bool {
+        match self {
+            Self::Attachment => true,
+            Self::Within(root) => match (path.canonicalize(), root.canonicalize()) {
+                (Ok(path), Ok(root)) => path.starts_with(root),
+                _ => false,
+            },
+        }
+    }
+}
+
+/// An image attachment loaded for display.
+pub struct ImageData {
+    pub format: ImageFormat,
+    pub bytes: Vec<u8>,
+    /// Pixel size, `(0, 0)` when the header could not be read.
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ImageData {
+    pub fn from_bytes(bytes: Vec<u8>) -> Self {
+        let format = ImageFormat::sniff(&bytes);
+        let (width, height) = image_dimensions(&bytes).unwrap_or((0, 0));
+        Self {
+            format,
+            bytes,
+            width,
+            height,
+        }
+    }
+
+    /// Loads `data:` URLs, and `file://` URLs and plain paths that `access`
+    /// permits.
+    pub fn from_url(url: &str, access: ImageAccess) -> Option<Self> {
+        let bytes = if let Some(rest) = url.strip_prefix("data:") {
+            let (header, payload) = rest.split_once(',')?;
+            if !header.ends_with(";base64") {
+                return None;
+            }
+            STANDARD.decode(payload.trim()).ok()?
+        } else {
+            let path = url.strip_prefix("file://").unwrap_or(url);
+            if path.contains("://") || !access.permits(Path::new(path)) {
+                return None;
+            }
+            let metadata = std::fs::metadata(path).ok()?;
+            if metadata.len() > MAX_IMAGE_BYTES as u64 {
+                return None;
+            }
+            std::fs::read(path).ok()?
+        };
+        if bytes.is_empty() || bytes.len() > MAX_IMAGE_BYTES {
+            return None;
+        }
+        Some(Self::from_bytes(bytes))
+    }
+
+    /// Short text used where the image itself cannot be shown.
+    pub fn describe(&self) -> String {
+        let size = format_size(self.bytes.len());
+        if self.width > 0 && self.height > 0 {
+            format!(
+                "{} {}×{}, {}",
+                self.format.label(),
+                self.width,
+                self.height,
+                size
+            )
+        } else {
+            format!("{}, {}", self.format.label(), size)
+        }
+    }
+}
+
+/// Where an image is drawn this frame, in screen cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ImagePlacement {
+    pub id: u32,
+    pub area: Rect,
+}
+
+/// Terminal graphics state shared by everything that draws images. Views
+/// record placements while rendering a frame; once the frame is on screen
+/// the app calls `flush` to emit the escape sequences for them.
+pub struct Graphics {
+    protocol: GraphicsProtocol,
+    cell_size: (u16, u16),
+    images: HashMap<u32, Option<Arc<ImageData>>>,
+    /// Encoded iTerm2/sixel payloads keyed by image id and cell size.
+    encoded: HashMap<(u32, u16, u16), Option<Arc<String>>>,
+    transmitted: HashSet<u32>,
+    placements: Vec<ImagePlacement>,
+    shown: Vec<ImagePlacement>,
+}
+
+impl Graphics {
+    pub fn new() -> Self {
+        Self::with_protocol(GraphicsProtocol::detect())
+    }
+
+    pub fn with_protocol(protocol: GraphicsProtocol) -> Self {
+        Self {
+            protocol,
+            cell_size: query_cell_size(),
+            images: HashMap::new(),
+            encoded: HashMap::new(),
+            transmitted: HashSet::new(),
+            placements: Vec::new(),
+            shown: Vec::new(),
+        }
+    }
+
+    pub fn protocol(&self) -> GraphicsProtocol {
+        self.protocol
+    }
+
+    /// Loads the image behind `url` once and returns it with its id.
+    /// Local files are checked against `access` on every call, so a file
+    /// cached for one message is not shown for another that may not read it.
+    pub fn load(&mut self, url: &str, access: ImageAccess) -> Option<(u32, Arc<ImageData>)> {
+        if !url.starts_with("data:") {
+            let path = url.strip_prefix("file://").unwrap_or(url);
+            if !access.permits(Path::new(path)) {
+                return None;
+            }
+        }
+        let id = image_id(url);
+        let image = self
+            .images
+            .entry(id)
+            .or_insert_with(|| ImageData::from_url(url, access).map(Arc::new))
+            .clone()?;
+        Some((id, image))
+    }
+
+    /// Whether `image` can be drawn with the detected protocol.
+    pub fn can_render(&self, image: &ImageData) -> bool {
+        if image.width == 0 || image.height == 0 {
+            return false;
+        }
+        match self.protocol {
+            GraphicsProtocol::Kitty | GraphicsProtocol::Sixel => image.format == ImageFormat::Png,
+            GraphicsProtocol::Iterm2 => matches!(
+                image.format,
+                ImageFormat::Png | ImageFormat::Jpeg | ImageFormat::Gif
+            ),
+            GraphicsProtocol::None => false,
+        }
+    }
+
+    /// Cells taken by `image` scaled down (never up) to fit the given box.
+    pub fn fit(&self, image: &ImageData, max_cols: u16, max_rows: u16) -> (u16, u16) {
+        let (cell_width, cell_height) = self.cell_size;
+        let max_width = f64::from(max_cols.max(1)) * f64::from(cell_width);
+        let max_height = f64::from(max_rows.max(1)) * f64::from(cell_height);
+        let width = f64::from(image.width.max(1));
+        let height = f64::from(image.height.max(1));
+        let scale = (max_width / width).min(max_height / height).min(1.0);
+        let cols = (width * scale / f64::from(cell_width)).ceil() as u16;
+        let rows = (height * scale / f64::from(cell_height)).ceil() as u16;
+        (
+            cols.clamp(1, max_cols.max(1)),
+            rows.clamp(1, max_rows.max(1)),
+        )
+    }
+
+    pub fn begin_frame(&mut self) {
+        self.placements.clear();
+    }
+
+    pub fn place(&mut self, id: u32, area: Rect) {
+        if self.protocol != GraphicsProtocol::None && area.width > 0 && area.height > 0 {
+            self.placements.push(ImagePlacement { id, area });
+        }
+    }
+
+    /// Drops everything placed so far, e.g. when a dialog covers the view.
+    pub fn clear_placements(&mut self) {
+        self.placements.clear();
+    }
+
+    /// Drops placements that overlap `area` so it stays readable.
+    pub fn hide_in(&mut self, area: Rect) {
+        self.placements
+            .retain(|placement| !placement.area.intersects(area));
+    }
+
+    /// Forgets what is on screen and re-reads the cell size, e.g. after a
+    /// resize, so the next flush draws everything again.
+    pub fn invalidate(&mut self) {
+        // Kitty placements survive a resize and are deleted by id.
+        if self.protocol != GraphicsProtocol::Kitty {
+            self.shown.clear();
+        }
+        self.cell_size = query_cell_size();
+        self.encoded.clear();
+    }
+
+    /// Screen areas that still show an image that is gone this frame. Kitty
+    /// images are removed by id; other protocols draw into the cell grid, so
+    /// these cells have to be repainted from the frame buffer.
+    pub fn stale_areas(&self) -> Vec<Rect> {
+        if self.protocol == GraphicsProtocol::Kitty || self.shown == self.placements {
+            return Vec::new();
+        }
+        self.shown
+            .iter()
+            .filter(|shown| !self.placements.contains(shown))
+            .map(|shown| shown.area)
+            .collect()
+    }
+
+    /// Emits the escape sequences for this frame's placements.
+    pub fn flush(&mut self, out: &mut impl Write) -> io::Result<()> {
+        if self.protocol == GraphicsProtocol::None || self.shown == self.placements {
+            return Ok(());
+        }
+        let mut sequence = String::from("\x1b7");
+        if self.protocol == GraphicsProtocol::Kitty {
+            for shown in &self.shown {
+                sequence.push_str(&format!("\x1b_Ga=d,d=i,i={},q=2\x1b\\", shown.id));
+            }
+        }
+        let placements = self.placements.clone();
+        for placement in &placements {
+            // Images already in the cell grid stay where they are.
+            if self.protocol != GraphicsProtocol::Kitty && self.shown.contains(placement) {
+                continue;
+            }
+            let Some(payload) = self.payload(placement) else {
+                continue;
+            };
+            sequence.push_str(&format!(
+                "\x1b[{};{}H",
+                placement.area.y + 1,
+                placement.area.x + 1
+            ));
+            sequence.push_str(&payload);
+        }
+        sequence.push_str("\x1b8");
+        out.write_all(sequence.as_bytes())?;
+        out.flush()?;
+        self.shown = placements;
+        Ok(())
+    }
+
+    fn payload(&mut self, placement: &ImagePlacement) -> Option<Arc<String>> {
+        let image = self.images.get(&placement.id)?.clone()?;
+        let (cols, rows) = (placement.area.width, placement.area.height);
+        if self.protocol == GraphicsProtocol::Kitty {
+            let mut payload = String::new();
+            if self.transmitted.insert(placement.id) {
+                payload.push_str(&kitty_transmit(placement.id, &image.bytes));
+            }
+            payload.push_str(&format!(
+                "\x1b_Ga=p,i={},c={},r={},C=1,q=2\x1b\\",
+                placement.id, cols, rows
+            ));
+            return Some(Arc::new(payload));
+        }
+        let protocol = self.protocol;
+        let cell_size = self.cell_size;
+        self.encoded
+            .entry((placement.id, cols, rows))
+            .or_insert_with(|| {
+                let encoded = match protocol {
+                    GraphicsProtocol::Iterm2 => Some(iterm2_sequence(&image.bytes, cols, rows)),
+                    GraphicsProtocol::Sixel => {
+                        let pixels = decode_png(&image.bytes)?;
+                        let width = u32::from(cols) * u32::from(cell_size.0);
+                        let height = u32::from(rows) * u32::from(cell_size.1);
+                        let (width, height) =
+                            fit_pixels(pixels.width, pixels.height, width, height);
+                        Some(sixel_sequence(&pixels.resize(width, height)))
+                    }
+                    _ => None,
+                };
+                encoded.map(Arc::new)
+            })
+            .clone()
+    }
+}
+
+impl Default for Graphics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn image_id(url: &str) -> u32 {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    // Kitty reserves id 0.
+    (hasher.finish() as u32).max(1)
+}
+
+fn query_cell_size() -> (u16, u16) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => (
+            (size.width / size.columns).max(1),
+            (size.height / size.rows).max(1),
+        ),
+        _ => DEFAULT_CELL_SIZE,
+    }
+}
+
+fn format_size(bytes: usize) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{} KB", bytes / 1024)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+/// Reads the pixel size from a PNG, GIF or JPEG header.
+pub fn image_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let be32 = |at: usize| -> Option<u32> {
+        Some(u32::from_be_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+    };
+    match ImageFormat::sniff(bytes) {
+        ImageFormat::Png => Some((be32(16)?, be32(20)?)),
+        ImageFormat::Gif => {
+            let header = bytes.get(6..10)?;
+            Some((
+                u32::from(u16::from_le_bytes([header[0], header[1]])),
+                u32::from(u16::from_le_bytes([header[2], header[3]])),
+            ))
+        }
+        ImageFormat::Jpeg => jpeg_dimensions(bytes),
+        ImageFormat::Webp | ImageFormat::Other => None,
+    }
+}
+
+fn jpeg_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let mut at = 2;
+    while at + 9 < bytes.len() {
+        if bytes[at] != 0xff {
+            return None;
+        }
+        let marker = bytes[at + 1];
+        let length = usize::from(u16::from_be_bytes([bytes[at + 2], bytes[at + 3]]));
+        // SOF0..SOF15, minus DHT (C4), JPG (C8) and DAC (CC).
+        if (0xc0..=0xcf).contains(&marker) && !matches!(marker, 0xc4 | 0xc8 | 0xcc) {
+            let height = u16::from_be_bytes([bytes[at + 5], bytes[at + 6]]);
+            let width = u16::from_be_bytes([bytes[at + 7], bytes[at + 8]]);
+            return Some((u32::from(width), u32::from(height)));
+        }
+        at += 2 + length;
+    }
+    None
+}
+
+fn fit_pixels(width: u32, height: u32, max_width: u32, max_height: u32) -> (u32, u32) {
+    let scale = (f64::from(max_width) / f64::from(width.max(1)))
+        .min(f64::from(max_height) / f64::from(height.max(1)))
+        .min(1.0);
+    (
+        ((f64::from(width) * scale) as u32).max(1),
+        ((f64::from(height) * scale) as u32).max(1),
+    )
+}
+
+fn kitty_transmit(id: u32, bytes: &[u8]) -> String {
+    let encoded = STANDARD.encode(bytes);
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(KITTY_CHUNK_SIZE).collect();
+    let mut out = String::new();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(index + 1 < chunks.len());
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if index == 0 {
+            out.push_str(&format!(
+                "\x1b_Ga=t,f=100,i={},q=2,m={};{}\x1b\\",
+                id, more, chunk
+            ));
+        } else {
+            out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    out
+}
+
+fn iterm2_sequence(bytes: &[u8], cols: u16, rows: u16) -> String {
+    format!(
+        "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+        bytes.len(),
+        cols,
+        rows,
+        STANDARD.encode(bytes)
+    )
+}
+
+/// 8-bit RGBA pixels.
+struct Pixels {
+    width: u32,
+    height: u32,
+    data: Vec<u8>,
+}
+
+impl Pixels {
+    /// Nearest-neighbour resize.
+    fn resize(&self, width: u32, height: u32) -> Pixels {
+        let mut data = Vec::with_capacity((width * height * 4) as usize);
+        for y in 0..height {
+            let source_y = (u64::from(y) * u64::from(self.height) / u64::from(height)) as u32;
+            for x in 0..width {
+                let source_x = (u64::from(x) * u64::from(self.width) / u64::from(width)) as u32;
+                let at = ((source_y * self.width + source_x) * 4) as usize;
+                data.extend_from_slice(&self.data[at..at + 4]);
+            }
+        }
+        Pixels {
+            width,
+            height,
+            data,
+        }
+    }
+}
+
+/// Decodes non-interlaced 8-bit PNGs (gray, RGB, palette, gray+alpha, RGBA).
+fn decode_png(bytes: &[u8]) -> Option<Pixels> {
+    if ImageFormat::sniff(bytes) != ImageFormat::Png {
+        return None;
+    }
+    let mut decoder = png::Decoder::new(bytes);
+    // Palette, low bit depths and tRNS become plain 8-bit channels.
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    decoder.set_limits(png::Limits {
+        bytes: (MAX_DECODED_PIXELS * 4) as usize,
+    });
+    let mut reader = decoder.read_info().ok()?;
+    let (width, height) = reader.info().size();
+    if u64::from(width) * u64::from(height) > MAX_DECODED_PIXELS {
+        return None;
+    }
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let frame = reader.next_frame(&mut buffer).ok()?;
+    let raw = &buffer[..frame.buffer_size()];
+    if frame.bit_depth != png::BitDepth::Eight {
+        return None;
+    }
+
+    let mut data = Vec::with_capacity(width as usize * height as usize * 4);
+    match frame.color_type {
+        png::ColorType::Grayscale => {
+            for &gray in raw {
+                data.extend_from_slice(&[gray, gray, gray, 255]);
+            }
+        }
+        png::ColorType::GrayscaleAlpha => {
+            for pixel in raw.chunks_exact(2) {
+                data.extend_from_slice(&[pixel[0], pixel[0], pixel[0], pixel[1]]);
+            }
+        }
+        png::ColorType::Rgb => {
+            for pixel in raw.chunks_exact(3) {
+                data.extend_from_slice(&[pixel[0], pixel[1], pixel[2], 255]);
+            }
+        }
+        png::ColorType::Rgba => data.extend_from_slice(raw),
+        png::ColorType::Indexed => return None,
+    }
+    Some(Pixels {
+        width,
+        height,
+        data,
+    })
+}
+
+/// Encodes pixels as sixel using a fixed 6×6×6 colour cube; transparent
+/// pixels are left unpainted.
+fn sixel_sequence(pixels: &Pixels) -> String {
+    const LEVELS: [u8; 6] = [0, 51, 102, 153, 204, 255];
+    let quantize = |value: u8| (usize::from(value) + 25) / 51;
+    let indices: Vec<Option<usize>> = pixels
+        .data
+        .chunks_exact(4)
+        .map(|p| (p[3] >= 128).then(|| quantize(p[0]) * 36 + quantize(p[1]) * 6 + quantize(p[2])))
+        .collect();
+
+    let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", pixels.width, pixels.height);
+    let mut used = [false; 216];
+    for index in indices.iter().flatten() {
+        used[*index] = true;
+    }
+    for (index, _) in used.iter().enumerate().filter(|(_, used)| **used) {
+        let percent = |level: usize| u32::from(LEVELS[level]) * 100 / 255;
+        out.push_str(&format!(
+            "#{};2;{};{};{}",
+            index,
+            percent(index / 36),
+            percent(index / 6 % 6),
+            percent(index % 6)
+        ));
+    }
+
+    let width = pixels.width as usize;
+    let height = pixels.height as usize;
+    for band in (0..height).step_by(6) {
+        let mut colors: Vec<usize> = (band..(band + 6).min(height))
+            .flat_map(|y| {
+                indices[y * width..(y + 1) * width]
+                    .iter()
+                    .flatten()
+                    .copied()
+            })
+            .collect();
+        colors.sort_unstable();
+        colors.dedup();
+        for color in colors {
+            out.push_str(&format!("#{}", color));
+            let mut run: Option<(u8, usize)> = None;
+            for x in 0..width {
+                let mut bits = 0u8;
+                for dy in 0..6 {
+                    let y = band + dy;
+                    if y < height && indices[y * width + x] == Some(color) {
+                        bits |= 1 << dy;
+                    }
+                }
+                run = match run {
+                    Some((current, count)) if current == bits => Some((current, count + 1)),
+                    Some((current, count)) => {
+                        push_sixel_run(&mut out, current, count);
+                        Some((bits, 1))
+                    }
+                    None => Some((bits, 1)),
+                };
+            }
+            if let Some((current, count)) = run {
+                push_sixel_run(&mut out, current, count);
+            }
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+fn push_sixel_run(out: &mut String, bits: u8, count: usize) {
+    let symbol = char::from(b'?' + bits);
+    if count > 3 {
+        out.push_str(&format!("!{}{}", count, symbol));
+    } else {
+        out.extend(std::iter::repeat_n(symbol, count));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 2×1 RGBA PNG: one red pixel, one transparent pixel.
+    fn tiny_png() -> Vec<u8> {
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, 2, 1);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_filter(png::FilterType::Sub);
+        encoder.set_adaptive_filter(png::AdaptiveFilterType::NonAdaptive);
+        let mut writer = encoder.write_header().unwrap();
+        writer
+            .write_image_data(&[255, 0, 0, 255, 255, 0, 0, 0])
+            .unwrap();
+        writer.finish().unwrap();
+        png
+    }
+
+    #[test]
+    fn loads_data_urls_and_reads_dimensions() {
+        let url = format!("data:image/png;base64,{}", STANDARD.encode(tiny_png()));
+        let image = ImageData::from_url(&url, ImageAccess::Attachment).expect("image");
+        assert_eq!(image.format, ImageFormat::Png);
+        assert_eq!((image.width, image.height), (2, 1));
+        assert!(image.describe().starts_with("PNG 2×1"));
+        assert!(
+            ImageData::from_url("https://example.com/a.png", ImageAccess::Attachment).is_none()
+        );
+    }
+
+    #[test]
+    fn tool_images_are_limited_to_the_session_directory() {
+        let session = tempfile::tempdir().unwrap();
+        let outside = tempfile::tempdir().unwrap();
+        let inside_path = session.path().join("shot.png");
+        let outside_path = outside.path().join("secret.png");
+        std::fs::write(&inside_path, tiny_png()).unwrap();
+        std::fs::write(&outside_path, tiny_png()).unwrap();
+        let escaping = session.path().join("..").join(
+            outside
+                .path()
+                .strip_prefix(session.path().parent().unwrap())
+                .unwrap()
+                .join("secret.png"),
+        );
+
+        let within = ImageAccess::Within(session.path());
+        let url = |path: &Path| format!("file://{}", path.display());
+        assert!(ImageData::from_url(&url(&inside_path), within).is_some());
+        assert!(ImageData::from_url(&url(&outside_path), within).is_none());
+        assert!(ImageData::from_url(&escaping.to_string_lossy(), within).is_none());
+        assert!(ImageData::from_url(&url(&outside_path), ImageAccess::Attachment).is_some());
+
+        let mut graphics = Graphics::with_protocol(GraphicsProtocol::Kitty);
+        assert!(graphics
+            .load(&url(&outside_path), ImageAccess::Attachment)
+            .is_some());
+        assert!(graphics.load(&url(&outside_path), within).is_none());
+    }
+
+    #[test]
+    fn decodes_filtered_png_rows() {
+        let pixels = decode_png(&tiny_png()).expect("decoded");
+        // Sub filter: the second pixel is stored relative to the first.
+        assert_eq!(pixels.data, vec![255, 0, 0, 255, 255, 0, 0, 0]);
+    }
+
+    #[test]
+    fn rejects_corrupt_and_truncated_pngs() {
+        let mut corrupt = tiny_png();
+        let last = corrupt.len() - 13;
+        corrupt[last] ^= 0xff;
+        assert!(decode_png(&corrupt).is_none());
+
+        let png = tiny_png();
+        assert!(decode_png(&png[..png.len() - 20]).is_none());
+    }
+
+    #[test]
+    fn fits_images_without_upscaling() {
+        let graphics = Graphics::with_protocol(GraphicsProtocol::Kitty);
+        let image = ImageData {
+            format: ImageFormat::Png,
+            bytes: Vec::new(),
+            width: 1600,
+            height: 800,
+        };
+        let (cols, rows) = graphics.fit(&image, 40, 20);
+        assert_eq!(cols, 40);
+        assert!(rows < 20);
+        let small = ImageData {
+            width: 8,
+            height: 16,
+            ..image
+        };
+        assert_eq!(graphics.fit(&small, 40, 20), (1, 1));
+    }
+
+    #[test]
+    fn kitty_flush_replaces_previous_placements() {
+        let mut graphics = Graphics::with_protocol(GraphicsProtocol::Kitty);
+        let url = format!("data:image/png;base64,{}", STANDARD.encode(tiny_png()));
+        let (id, _) = graphics.load(&url, ImageAccess::Attachment).expect("image");
+        graphics.begin_frame();
+        graphics.place(id, Rect::new(2, 3, 4, 2));
+        let mut out = Vec::new();
+        graphics.flush(&mut out).unwrap();
+        let first = String::from_utf8(out).unwrap();
+        assert!(first.contains("a=t,f=100"));
+        assert!(first.contains("\x1b[4;3H"));
+
+        // Same frame again: nothing to emit.
+        let mut out = Vec::new();
+        graphics.flush(&mut out).unwrap();
+        assert!(out.is_empty());
+
+        graphics.begin_frame();
+        graphics.place(id, Rect::new(2, 1, 4, 2));
+        let mut out = Vec::new();
+        graphics.flush(&mut out).unwrap();
+        let moved = String::from_utf8(out).unwrap();
+        assert!(moved.contains(&format!("a=d,d=i,i={}", id)));
+        assert!(!moved.contains("a=t"));
+    }
+
+    #[test]
+    fn sixel_encodes_opaque_pixels_only() {
+        let pixels = decode_png(&tiny_png()).expect("decoded");
+        let sequence = sixel_sequence(&pixels);
+        assert!(sequence.starts_with("\x1bP0;1;0q\"1;1;2;1"));
+        assert!(sequence.contains("#180;2;100;0;0"));
+        assert!(sequence.ends_with("-\x1b\\"));
+    }
+}
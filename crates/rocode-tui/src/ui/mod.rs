@@ -1,11 +1,13 @@
 mod border;
 mod clipboard;
+mod graphics;
 mod layout;
 mod selection;
 mod text;
 
 pub use border::{BorderChars, BorderStyle};
 pub use clipboard::{Clipboard, ClipboardContent};
+pub use graphics::{Graphics, GraphicsProtocol, ImageAccess, ImageData};
 pub use layout::*;
-pub use selection::Selection;
+pub use selection::{highlight_cell, word_bounds, Selection};
 pub use text::*;
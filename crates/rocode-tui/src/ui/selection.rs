@@ -1,7 +1,10 @@
+use ratatui::buffer::Cell;
+use ratatui::style::Color;
 use unicode_width::UnicodeWidthChar;
 
-/// Terminal text selection — tracks a rectangular region in screen coordinates
-/// and provides hit-testing + text extraction.
+/// Terminal text selection — tracks a region in (row, column) coordinates and
+/// provides hit-testing + text extraction. Rows are screen rows for the
+/// app-wide selection and transcript line indices inside the session view.
 ///
 /// Selection follows standard terminal behavior:
 /// - First row: from start column to end of line
@@ -11,9 +14,9 @@ use unicode_width::UnicodeWidthChar;
 
 pub struct Selection {
     /// Anchor point (where mouse-down happened).
-    anchor: Option<(u16, u16)>,
+    anchor: Option<(usize, u16)>,
     /// Current drag endpoint.
-    cursor: Option<(u16, u16)>,
+    cursor: Option<(usize, u16)>,
     /// True while the mouse button is held down.
     dragging: bool,
 }
@@ -28,19 +31,26 @@ impl Selection {
     }
 
     /// Begin a new selection at (row, col).
-    pub fn start(&mut self, row: u16, col: u16) {
+    pub fn start(&mut self, row: usize, col: u16) {
         self.anchor = Some((row, col));
         self.cursor = Some((row, col));
         self.dragging = true;
     }
 
     /// Update the drag endpoint.
-    pub fn update(&mut self, row: u16, col: u16) {
+    pub fn update(&mut self, row: usize, col: u16) {
         if self.dragging {
             self.cursor = Some((row, col));
         }
     }
 
+    /// Select a fixed range at once (word or line selection), inclusive.
+    pub fn select(&mut self, start: (usize, u16), end: (usize, u16)) {
+        self.anchor = Some(start);
+        self.cursor = Some(end);
+        self.dragging = false;
+    }
+
     /// Mouse button released — keep the selection visible but stop tracking.
     pub fn finalize(&mut self) {
         self.dragging = false;
@@ -63,8 +73,13 @@ impl Selection {
         self.dragging
     }
 
+    /// First and last selected rows.
+    pub fn rows(&self) -> Option<(usize, usize)> {
+        self.range().map(|((r0, _), (r1, _))| (r0, r1))
+    }
+
     /// Returns the normalized range: (top-left, bottom-right) in reading order.
-    fn range(&self) -> Option<((u16, u16), (u16, u16))> {
+    fn range(&self) -> Option<((usize, u16), (usize, u16))> {
         match (self.anchor, self.cursor) {
             (Some(a), Some(b)) => {
                 if a.0 < b.0 || (a.0 == b.0 && a.1 <= b.1) {
@@ -78,7 +93,7 @@ impl Selection {
     }
 
     /// Test whether a specific cell is inside the selection.
-    pub fn is_selected(&self, row: u16, col: u16) -> bool {
+    pub fn is_selected(&self, row: usize, col: u16) -> bool {
         let ((r0, c0), (r1, c1)) = match self.range() {
             Some(r) => r,
             None => return false,
@@ -111,7 +126,7 @@ impl Selection {
     /// content for a given row number.
     pub fn get_selected_text<F>(&self, get_line: F) -> String
     where
-        F: Fn(u16) -> Option<String>,
+        F: Fn(usize) -> Option<String>,
     {
         let ((r0, c0), (r1, c1)) = match self.range() {
             Some(r) => r,
@@ -150,6 +165,60 @@ impl Selection {
     }
 }
 
+/// Inclusive column range of the word under `col`, if there is one. Words are
+/// runs of alphanumerics plus `_`, `-`, `.`, `/` and `:` so paths and URLs
+/// select whole.
+pub fn word_bounds(line: &str, col: u16) -> Option<(u16, u16)> {
+    let is_word = |ch: char| ch.is_alphanumeric() || matches!(ch, '_' | '-' | '.' | '/' | ':');
+    let mut cells: Vec<(usize, char)> = Vec::new();
+    let mut column = 0usize;
+    for ch in line.chars() {
+        cells.push((column, ch));
+        column += UnicodeWidthChar::width(ch).unwrap_or(0).max(1);
+    }
+    let target = usize::from(col);
+    let index = cells.iter().rposition(|(start, _)| *start <= target)?;
+    let (start_col, ch) = cells[index];
+    let width = UnicodeWidthChar::width(ch).unwrap_or(0).max(1);
+    if target >= start_col + width || !is_word(ch) {
+        return None;
+    }
+    let first = cells[..index]
+        .iter()
+        .rposition(|(_, ch)| !is_word(*ch))
+        .map_or(0, |i| i + 1);
+    let last = cells[index..]
+        .iter()
+        .position(|(_, ch)| !is_word(*ch))
+        .map_or(cells.len() - 1, |i| index + i - 1);
+    let end_col = cells[last].0 + UnicodeWidthChar::width(cells[last].1).unwrap_or(0).max(1) - 1;
+    Some((cells[first].0 as u16, end_col as u16))
+}
+
+/// Selection highlight: swap colors on a cell with visible content, matching
+/// standard terminal selection behavior (like opentui).
+pub fn highlight_cell(cell: &mut Cell) {
+    let symbol = cell.symbol();
+    if symbol.is_empty() || symbol.chars().all(|c| c == ' ') {
+        return;
+    }
+    // Resolve Reset to concrete terminal defaults before swapping.
+    // Reset fg = terminal default foreground (typically white/light).
+    // Reset bg = terminal default background (typically black/dark).
+    let fg = if cell.fg == Color::Reset {
+        Color::White
+    } else {
+        cell.fg
+    };
+    let bg = if cell.bg == Color::Reset {
+        Color::Black
+    } else {
+        cell.bg
+    };
+    cell.fg = bg;
+    cell.bg = fg;
+}
+
 fn slice_by_columns(line: &str, start_col: usize, end_col_exclusive: usize) -> String {
     if start_col >= end_col_exclusive {
         return String::new();
@@ -230,7 +299,7 @@ impl Default for Selection {
 
 #[cfg(test)]
 mod tests {
-    use super::Selection;
+    use super::{word_bounds, Selection};
 
     #[test]
     fn utf8_glyph_boundary_selection_is_safe() {
@@ -270,4 +339,24 @@ mod tests {
 
         assert_eq!(selected, "你");
     }
+
+    #[test]
+    fn word_bounds_cover_paths_and_skip_spaces() {
+        let line = "  open src/ui/mod.rs now";
+        assert_eq!(word_bounds(line, 9), Some((7, 19)));
+        assert_eq!(word_bounds(line, 2), Some((2, 5)));
+        assert_eq!(word_bounds(line, 6), None);
+        assert_eq!(word_bounds(line, 60), None);
+    }
+
+    #[test]
+    fn fixed_range_selection_spans_rows() {
+        let lines = ["first line", "second line"];
+        let mut selection = Selection::new();
+        selection.select((0, 6), (1, 5));
+        assert!(!selection.is_selecting());
+        assert_eq!(selection.rows(), Some((0, 1)));
+        let selected = selection.get_selected_text(|row| lines.get(row).map(|l| l.to_string()));
+        assert_eq!(selected, "line\nsecond");
+    }
 }